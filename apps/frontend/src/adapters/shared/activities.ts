@@ -8,8 +8,17 @@ import type {
   ActivitySearchResponse,
   ActivityUpdate,
   ActivityImport,
+  ActivityUpsert,
+  DuplicateCandidate,
+  DuplicatePairDecision,
+  DuplicateResolution,
   ImportActivitiesResult,
   ImportMappingData,
+  ImportRun,
+  ImportRunReview,
+  ReviewMode,
+  StagedActivity,
+  StagedActivityDecision,
 } from "@/lib/types";
 
 import { invoke, logger } from "./platform";
@@ -139,16 +148,20 @@ export const deleteActivity = async (activityId: string): Promise<Activity> => {
  * Import activities into the system.
  * Extracts accountId from the first activity for the backend call.
  * Returns ImportActivitiesResult with activities, import_run_id, and summary.
+ * With a review mode other than NEVER, activities may be staged on the run for review.
  */
 export const importActivities = async ({
   activities,
+  reviewMode,
 }: {
   activities: ActivityImport[];
+  reviewMode?: ReviewMode;
 }): Promise<ImportActivitiesResult> => {
   try {
     return await invoke<ImportActivitiesResult>("import_activities", {
       accountId: activities[0].accountId,
       activities,
+      reviewMode,
     });
   } catch (err) {
     logger.error(`Error importing activities: ${err}`);
@@ -221,3 +234,95 @@ export const checkExistingDuplicates = async (
     throw err;
   }
 };
+
+/**
+ * List likely duplicate activity pairs that have not been reviewed yet.
+ */
+export const getDuplicateCandidates = async (accountId?: string): Promise<DuplicateCandidate[]> => {
+  try {
+    return await invoke<DuplicateCandidate[]>("get_duplicate_candidates", { accountId });
+  } catch (err) {
+    logger.error("Error fetching duplicate candidates.");
+    throw err;
+  }
+};
+
+/**
+ * Merge a duplicate pair or keep both; the decision is remembered.
+ */
+export const resolveDuplicateCandidate = async (
+  resolution: DuplicateResolution,
+): Promise<DuplicatePairDecision> => {
+  try {
+    return await invoke<DuplicatePairDecision>("resolve_duplicate_candidate", { resolution });
+  } catch (err) {
+    logger.error("Error resolving duplicate candidate.");
+    throw err;
+  }
+};
+
+/**
+ * Staged activities of an import run with the holdings and cash impact of applying them.
+ */
+export const getImportRunReview = async (importRunId: string): Promise<ImportRunReview> => {
+  try {
+    return await invoke<ImportRunReview>("get_import_run_review", { importRunId });
+  } catch (err) {
+    logger.error("Error fetching import run review.");
+    throw err;
+  }
+};
+
+/**
+ * Write the accepted and pending rows of a staged run to the ledger.
+ */
+export const applyImportRun = async (importRunId: string): Promise<ImportRun> => {
+  try {
+    return await invoke<ImportRun>("apply_import_run", { importRunId });
+  } catch (err) {
+    logger.error("Error applying import run.");
+    throw err;
+  }
+};
+
+/**
+ * Reject every remaining row of a staged run; rejected rows are not staged again.
+ */
+export const discardImportRun = async (importRunId: string): Promise<ImportRun> => {
+  try {
+    return await invoke<ImportRun>("discard_import_run", { importRunId });
+  } catch (err) {
+    logger.error("Error discarding import run.");
+    throw err;
+  }
+};
+
+/**
+ * Edit a staged activity before the run is applied.
+ */
+export const updateStagedActivity = async (
+  stagedId: string,
+  activity: ActivityUpsert,
+): Promise<StagedActivity> => {
+  try {
+    return await invoke<StagedActivity>("update_staged_activity", { stagedId, activity });
+  } catch (err) {
+    logger.error("Error updating staged activity.");
+    throw err;
+  }
+};
+
+/**
+ * Accept or reject a single staged activity.
+ */
+export const setStagedActivityDecision = async (
+  stagedId: string,
+  decision: StagedActivityDecision,
+): Promise<StagedActivity> => {
+  try {
+    return await invoke<StagedActivity>("set_staged_activity_decision", { stagedId, decision });
+  } catch (err) {
+    logger.error("Error updating staged activity decision.");
+    throw err;
+  }
+};
@@ -3,6 +3,8 @@ import type {
   AiProvidersResponse,
   UpdateProviderSettingsRequest,
   SetDefaultProviderRequest,
+  SetUsageBudgetRequest,
+  SaveCustomProviderRequest,
   ListModelsResponse,
 } from "@/lib/types";
 
@@ -32,6 +34,29 @@ export const setDefaultAiProvider = async (request: SetDefaultProviderRequest):
   return invoke<void>("set_default_ai_provider", { request });
 };
 
+/**
+ * Set or clear the monthly AI spend budget.
+ * Pass `budget: null` to remove the limit.
+ */
+export const setAiUsageBudget = async (request: SetUsageBudgetRequest): Promise<void> => {
+  return invoke<void>("set_ai_usage_budget", { request });
+};
+
+/**
+ * Add or update a user-defined OpenAI-compatible provider.
+ * Returns the provider ID.
+ */
+export const saveCustomAiProvider = async (request: SaveCustomProviderRequest): Promise<string> => {
+  return invoke<string>("save_custom_ai_provider", { request });
+};
+
+/**
+ * Remove a user-defined provider together with its settings and API key.
+ */
+export const deleteCustomAiProvider = async (providerId: string): Promise<void> => {
+  return invoke<void>("delete_custom_ai_provider", { providerId });
+};
+
 /**
  * List available models from a provider.
  * Fetches models from the provider's API using backend-stored secrets.
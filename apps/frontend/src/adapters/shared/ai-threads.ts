@@ -5,6 +5,7 @@ import type {
   ChatMessage,
   ChatThread,
 } from "@/features/ai-assistant/types";
+import type { UsageReport } from "@/lib/types";
 import type { UpdateThreadRequest, UpdateToolResultRequest } from "../types";
 
 import { invoke } from "./platform";
@@ -79,3 +80,14 @@ export const getAiThreadTags = async (threadId: string): Promise<string[]> => {
 export const updateToolResult = async (request: UpdateToolResultRequest): Promise<void> => {
   return invoke<void>("update_tool_result", { request });
 };
+
+/**
+ * Get AI token usage and estimated cost for a date range.
+ * Defaults to month-to-date when no dates are given.
+ *
+ * @param from - Inclusive start date (YYYY-MM-DD)
+ * @param to - Inclusive end date (YYYY-MM-DD)
+ */
+export const getAiUsageReport = async (from?: string, to?: string): Promise<UsageReport> => {
+  return invoke<UsageReport>("get_ai_usage_report", { from, to });
+};
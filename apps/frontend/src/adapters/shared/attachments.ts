@@ -0,0 +1,15 @@
+// Attachment Commands
+import type { Attachment, AttachmentEntityType } from "@/lib/types";
+
+import { invoke } from "./platform";
+
+export const listAttachments = async (
+  entityType: AttachmentEntityType,
+  entityId: string,
+): Promise<Attachment[]> => {
+  return invoke<Attachment[]>("list_attachments", { entityType, entityId });
+};
+
+export const deleteAttachment = async (attachmentId: string): Promise<void> => {
+  return invoke<void>("delete_attachment", { attachmentId });
+};
@@ -0,0 +1,23 @@
+// Change History Commands
+import type { ChangeEntityType, ChangeLogEntry, RollbackResult } from "@/lib/types";
+
+import { invoke } from "./platform";
+
+export const getEntityHistory = async (
+  entityType: ChangeEntityType,
+  entityId: string,
+): Promise<ChangeLogEntry[]> => {
+  return invoke<ChangeLogEntry[]>("get_entity_history", { entityType, entityId });
+};
+
+export const getImportRunChanges = async (importRunId: string): Promise<ChangeLogEntry[]> => {
+  return invoke<ChangeLogEntry[]>("get_import_run_changes", { importRunId });
+};
+
+export const rollbackChange = async (changeId: string): Promise<RollbackResult> => {
+  return invoke<RollbackResult>("rollback_change", { changeId });
+};
+
+export const rollbackImportRun = async (importRunId: string): Promise<RollbackResult> => {
+  return invoke<RollbackResult>("rollback_import_run", { importRunId });
+};
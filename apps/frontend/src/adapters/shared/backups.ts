@@ -0,0 +1,39 @@
+// Scheduled Backup Commands
+import type {
+  BackupArchiveInfo,
+  BackupRunResult,
+  BackupSchedule,
+  BackupScheduleUpdate,
+  RestorePreview,
+} from "@/lib/types";
+
+import { invoke } from "./platform";
+
+export const getBackupSchedule = async (): Promise<BackupSchedule> => {
+  return invoke<BackupSchedule>("get_backup_schedule");
+};
+
+export const updateBackupSchedule = async (
+  update: BackupScheduleUpdate,
+): Promise<BackupSchedule> => {
+  return invoke<BackupSchedule>("update_backup_schedule", { update });
+};
+
+export const listBackups = async (): Promise<BackupArchiveInfo[]> => {
+  return invoke<BackupArchiveInfo[]>("list_backups");
+};
+
+export const runBackup = async (): Promise<BackupRunResult> => {
+  return invoke<BackupRunResult>("run_backup");
+};
+
+export const previewBackupRestore = async (
+  archivePath: string,
+  passphrase?: string,
+): Promise<RestorePreview> => {
+  return invoke<RestorePreview>("preview_backup_restore", { archivePath, passphrase });
+};
+
+export const restoreBackup = async (archivePath: string, passphrase?: string): Promise<void> => {
+  return invoke<void>("restore_backup", { archivePath, passphrase });
+};
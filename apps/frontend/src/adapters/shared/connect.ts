@@ -30,7 +30,11 @@ import type {
   BackendSyncReconcileReadyResult,
   BackendSyncSnapshotUploadResult,
   BackendSyncStateResult,
+  DeviceSyncRelayInfo,
   ImportRunsRequest,
+  LocalConnectorInfo,
+  RepairSyncRequest,
+  SyncSchedule,
 } from "../types";
 
 import { invoke } from "./platform";
@@ -83,6 +87,50 @@ export async function getImportRuns(request?: ImportRunsRequest): Promise<Import
   });
 }
 
+// ============================================================================
+// Local Connector Commands (broker APIs called directly, no Connect session)
+// ============================================================================
+
+export async function listLocalConnectors(): Promise<LocalConnectorInfo[]> {
+  return invoke<LocalConnectorInfo[]>("list_local_connectors");
+}
+
+export async function configureLocalConnector(
+  connectorId: string,
+  credential: string,
+): Promise<void> {
+  return invoke<void>("configure_local_connector", { connectorId, credential });
+}
+
+export async function removeLocalConnector(connectorId: string): Promise<void> {
+  return invoke<void>("remove_local_connector", { connectorId });
+}
+
+export async function syncLocalConnector(connectorId: string): Promise<void> {
+  return invoke<void>("sync_local_connector", { connectorId });
+}
+
+// ============================================================================
+// Sync Schedules & Repair
+// ============================================================================
+
+export async function getSyncSchedules(): Promise<SyncSchedule[]> {
+  return invoke<SyncSchedule[]>("get_sync_schedules");
+}
+
+export async function updateSyncSchedule(schedule: SyncSchedule): Promise<SyncSchedule> {
+  return invoke<SyncSchedule>("update_sync_schedule", { sourceId: schedule.sourceId, schedule });
+}
+
+/** Re-fetches activities from `fromDate`; progress arrives via the broker sync events. */
+export async function repairBrokerSync(request: RepairSyncRequest): Promise<void> {
+  return invoke<void>("repair_broker_sync", {
+    sourceId: request.sourceId,
+    fromDate: request.fromDate,
+    accountId: request.accountId,
+  });
+}
+
 // ============================================================================
 // Device Sync Commands (DeviceEnrollService)
 // ============================================================================
@@ -103,6 +151,20 @@ export const reinitializeDeviceSync = async (): Promise<BackendEnableSyncResult>
   return invoke<BackendEnableSyncResult>("reinitialize_device_sync");
 };
 
+export const getDeviceSyncRelay = async (): Promise<DeviceSyncRelayInfo | null> => {
+  return invoke<DeviceSyncRelayInfo | null>("get_device_sync_relay");
+};
+
+/** Desktop only; the server reads its relay from the environment. Applies after restart. */
+export const setDeviceSyncRelay = async (baseUrl: string, token: string): Promise<void> => {
+  return invoke<void>("set_device_sync_relay", { baseUrl, token });
+};
+
+/** Desktop only. Applies after restart. */
+export const clearDeviceSyncRelay = async (): Promise<void> => {
+  return invoke<void>("clear_device_sync_relay");
+};
+
 export const getSyncEngineStatus = async (): Promise<BackendSyncEngineStatusResult> => {
   return invoke<BackendSyncEngineStatusResult>("device_sync_engine_status");
 };
@@ -1,5 +1,12 @@
 // Health Center Commands
-import type { FixAction, HealthConfig, HealthStatus } from "@/lib/types";
+import type {
+  BrokerStatement,
+  FixAction,
+  HealthConfig,
+  HealthStatus,
+  HoldingsDriftCheck,
+  ReconciliationResult,
+} from "@/lib/types";
 import { invoke } from "./platform";
 
 /**
@@ -57,3 +64,46 @@ export const getHealthConfig = async (): Promise<HealthConfig> => {
 export const updateHealthConfig = async (config: HealthConfig): Promise<void> => {
   return invoke<void>("update_health_config", { config });
 };
+
+// Broker Reconciliation Commands
+
+/**
+ * Reconcile broker-reported positions and cash balances against computed holdings.
+ */
+export const reconcileBrokerStatement = async (
+  statement: BrokerStatement,
+): Promise<ReconciliationResult> => {
+  return invoke<ReconciliationResult>("reconcile_broker_statement", { statement });
+};
+
+/**
+ * Reconcile a broker statement CSV export against computed holdings.
+ */
+export const reconcileBrokerStatementCsv = async (
+  accountId: string,
+  asOfDate: string,
+  content: string,
+): Promise<ReconciliationResult> => {
+  return invoke<ReconciliationResult>("reconcile_broker_statement_csv", {
+    accountId,
+    asOfDate,
+    content,
+  });
+};
+
+/**
+ * Reconcile a synced account against the holdings currently reported by the broker.
+ */
+export const reconcileBrokerAccount = async (accountId: string): Promise<ReconciliationResult> => {
+  return invoke<ReconciliationResult>("reconcile_broker_account", { accountId });
+};
+
+/**
+ * List post-sync holdings drift checks, newest first.
+ */
+export const getHoldingsDriftHistory = async (
+  accountId?: string,
+  limit?: number,
+): Promise<HoldingsDriftCheck[]> => {
+  return invoke<HoldingsDriftCheck[]>("get_holdings_drift_history", { accountId, limit });
+};
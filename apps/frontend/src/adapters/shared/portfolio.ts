@@ -11,6 +11,8 @@ import type {
   ImportHoldingsCsvResult,
   CheckHoldingsImportResult,
   SnapshotInfo,
+  ScenarioRequest,
+  ScenarioResult,
 } from "@/lib/types";
 
 import { invoke, logger } from "./platform";
@@ -153,6 +155,13 @@ export const getHoldingsByAllocation = async (
   });
 };
 
+/**
+ * Simulates hypothetical activities against current holdings without recording them.
+ */
+export const simulateScenario = async (request: ScenarioRequest): Promise<ScenarioResult> => {
+  return invoke<ScenarioResult>("simulate_scenario", { request });
+};
+
 /**
  * Input for a single holding when saving manual holdings
  *
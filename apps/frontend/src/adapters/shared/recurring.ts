@@ -0,0 +1,42 @@
+// Recurring Activity Commands
+import type {
+  NewRecurringActivityTemplate,
+  RecurringActivityTemplate,
+  RecurringGenerationResult,
+  RecurringOccurrence,
+} from "@/lib/types";
+
+import { invoke } from "./platform";
+
+export const getRecurringTemplates = async (): Promise<RecurringActivityTemplate[]> => {
+  return invoke<RecurringActivityTemplate[]>("get_recurring_templates");
+};
+
+export const createRecurringTemplate = async (
+  template: NewRecurringActivityTemplate,
+): Promise<RecurringActivityTemplate> => {
+  return invoke<RecurringActivityTemplate>("create_recurring_template", { template });
+};
+
+export const updateRecurringTemplate = async (
+  template: RecurringActivityTemplate,
+): Promise<RecurringActivityTemplate> => {
+  return invoke<RecurringActivityTemplate>("update_recurring_template", { template });
+};
+
+export const deleteRecurringTemplate = async (id: string): Promise<void> => {
+  return invoke<void>("delete_recurring_template", { id });
+};
+
+export const previewRecurringOccurrences = async (
+  id: string,
+  through?: string,
+): Promise<RecurringOccurrence[]> => {
+  return invoke<RecurringOccurrence[]>("preview_recurring_occurrences", { id, through });
+};
+
+export const generateRecurringActivities = async (
+  asOf?: string,
+): Promise<RecurringGenerationResult> => {
+  return invoke<RecurringGenerationResult>("generate_recurring_activities", { asOf });
+};
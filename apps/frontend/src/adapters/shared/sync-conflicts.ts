@@ -0,0 +1,14 @@
+// Sync Conflict Commands
+import type { ResolveSyncConflictRequest, SyncFieldConflict } from "@/lib/types";
+
+import { invoke } from "./platform";
+
+export const listSyncConflicts = async (includeResolved = false): Promise<SyncFieldConflict[]> => {
+  return invoke<SyncFieldConflict[]>("list_sync_conflicts", { includeResolved });
+};
+
+export const resolveSyncConflict = async (
+  request: ResolveSyncConflictRequest,
+): Promise<SyncFieldConflict> => {
+  return invoke<SyncFieldConflict>("resolve_sync_conflict", { request });
+};
@@ -0,0 +1,32 @@
+// Tauri-specific attachment commands
+import type { Attachment, AttachmentContent, NewAttachment } from "@/lib/types";
+import { invoke, logger } from "./core";
+
+/**
+ * Attach a file to an activity, asset or account.
+ * Tauri implementation: reads file as ArrayBuffer and invokes upload_attachment command.
+ */
+export const uploadAttachment = async (
+  file: File,
+  attachment: NewAttachment,
+): Promise<Attachment> => {
+  try {
+    const buffer = await file.arrayBuffer();
+    const content = Array.from(new Uint8Array(buffer));
+    return await invoke<Attachment>("upload_attachment", {
+      attachment: { ...attachment, fileName: file.name, contentType: file.type || null },
+      content,
+    });
+  } catch (err) {
+    logger.error("Error uploading attachment:", err);
+    throw err;
+  }
+};
+
+export const getAttachmentContent = async (attachmentId: string): Promise<AttachmentContent> => {
+  // Desktop returns a tuple [attachment, data[]], transform internally to object
+  const [attachment, data] = await invoke<[Attachment, number[]]>("get_attachment_content", {
+    attachmentId,
+  });
+  return { attachment, data: new Uint8Array(data) };
+};
@@ -1,4 +1,8 @@
-import type { FolderSyncCommandResult, FolderSyncState } from "@/lib/types";
+import type {
+  FolderSyncCommandResult,
+  FolderSyncRemoteCredentials,
+  FolderSyncState,
+} from "@/lib/types";
 
 import { invoke } from "./core";
 
@@ -6,14 +10,48 @@ export const getFolderSyncState = async (): Promise<FolderSyncState> => {
   return invoke<FolderSyncState>("get_folder_sync_state");
 };
 
+/**
+ * `sharedFolderPath` is a local path, a `webdav+https://` URL or an
+ * `s3+https://endpoint/bucket/prefix` URL; remote folders take credentials.
+ */
 export const initializeFolderSync = async (
   sharedFolderPath: string,
+  passphrase: string,
+  remoteCredentials?: FolderSyncRemoteCredentials,
 ): Promise<FolderSyncCommandResult> => {
-  return invoke<FolderSyncCommandResult>("initialize_folder_sync", { sharedFolderPath });
+  return invoke<FolderSyncCommandResult>("initialize_folder_sync", {
+    sharedFolderPath,
+    passphrase,
+    remoteCredentials: remoteCredentials ?? null,
+  });
 };
 
-export const joinFolderSync = async (sharedFolderPath: string): Promise<FolderSyncCommandResult> => {
-  return invoke<FolderSyncCommandResult>("join_folder_sync", { sharedFolderPath });
+/** `passphrase` may be omitted for folders created before encryption. */
+export const joinFolderSync = async (
+  sharedFolderPath: string,
+  passphrase?: string,
+  remoteCredentials?: FolderSyncRemoteCredentials,
+): Promise<FolderSyncCommandResult> => {
+  return invoke<FolderSyncCommandResult>("join_folder_sync", {
+    sharedFolderPath,
+    passphrase: passphrase || null,
+    remoteCredentials: remoteCredentials ?? null,
+  });
+};
+
+export const unlockFolderSync = async (passphrase: string): Promise<FolderSyncCommandResult> => {
+  return invoke<FolderSyncCommandResult>("unlock_folder_sync", { passphrase });
+};
+
+/** Re-encrypts the whole shared folder. `currentPassphrase` is null for unencrypted folders. */
+export const changeFolderSyncPassphrase = async (
+  currentPassphrase: string | null,
+  newPassphrase: string,
+): Promise<FolderSyncCommandResult> => {
+  return invoke<FolderSyncCommandResult>("change_folder_sync_passphrase", {
+    currentPassphrase,
+    newPassphrase,
+  });
 };
 
 export const retryFolderSyncNow = async (): Promise<FolderSyncCommandResult> => {
@@ -45,6 +45,11 @@ export type {
   BackendSyncCycleResult,
   BackendSyncBackgroundEngineResult,
   BackendSyncSnapshotUploadResult,
+  DeviceSyncRelayInfo,
+  LocalConnectorInfo,
+  SyncSchedule,
+  SyncScheduleTrigger,
+  RepairSyncRequest,
   EphemeralKeyPair,
 } from "../types";
 
@@ -91,6 +96,22 @@ export * from "../shared/alternative-assets";
 // Contribution Limits Commands
 export * from "../shared/contribution-limits";
 
+// Recurring Activity Commands
+export * from "../shared/recurring";
+
+// Change History Commands
+export * from "../shared/audit";
+
+// Scheduled Backup Commands
+export * from "../shared/backups";
+
+// Sync Conflict Commands
+export * from "../shared/sync-conflicts";
+
+// Attachment Commands
+export * from "../shared/attachments";
+export { uploadAttachment, getAttachmentContent } from "./attachments";
+
 // Exchange Rates Commands
 export * from "../shared/exchange-rates";
 
@@ -131,6 +152,8 @@ export {
   getFolderSyncState,
   initializeFolderSync,
   joinFolderSync,
+  unlockFolderSync,
+  changeFolderSyncPassphrase,
   retryFolderSyncNow,
   disableFolderSync,
 } from "./folder-sync";
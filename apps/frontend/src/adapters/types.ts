@@ -45,6 +45,7 @@ import type {
   FunctionPermission,
   Permission,
 } from "@wealthfolio/addon-sdk";
+import type { ReviewMode } from "@/lib/types";
 
 // Tauri-specific types with camelCase serialization to match Rust
 export interface AddonFile extends Omit<BaseAddonFile, "is_main"> {
@@ -114,6 +115,49 @@ export interface ImportRunsRequest {
   offset?: number;
 }
 
+/**
+ * Local broker connector and whether credentials are stored for it.
+ */
+export interface LocalConnectorInfo {
+  id: string;
+  name: string;
+  provider: string;
+  configured: boolean;
+}
+
+/**
+ * When a scheduled broker sync fires. Cron expressions use five fields in local time.
+ */
+export type SyncScheduleTrigger =
+  | { type: "interval"; minutes: number }
+  | { type: "cron"; expression: string };
+
+/**
+ * Sync schedule for one connection source ("connect" or a local connector id).
+ */
+export interface SyncSchedule {
+  sourceId: string;
+  enabled: boolean;
+  trigger: SyncScheduleTrigger;
+  /** Local "HH:MM" window with no scheduled syncs; may wrap past midnight. */
+  quietHours?: { start: string; end: string } | null;
+  /** Limit the first sync of new accounts to this many days of history. */
+  backfillDays?: number | null;
+  /** Stage synced activities for review before they reach the ledger. */
+  reviewMode?: ReviewMode;
+}
+
+/**
+ * Request to re-fetch activities from a date (recorded as a REPAIR import run).
+ */
+export interface RepairSyncRequest {
+  /** Defaults to "connect". */
+  sourceId?: string;
+  /** YYYY-MM-DD */
+  fromDate: string;
+  accountId?: string;
+}
+
 /**
  * Request for updating thread title or pinned status.
  */
@@ -196,6 +240,15 @@ export interface BackendSyncStateResult {
   trustedDevices: TrustedDeviceSummary[];
 }
 
+/**
+ * Self-hosted sync relay from get_device_sync_relay.
+ */
+export interface DeviceSyncRelayInfo {
+  baseUrl: string;
+  /** Configured through environment variables and not editable in-app. */
+  fromEnv: boolean;
+}
+
 /**
  * Result from enable_device_sync command.
  */
@@ -0,0 +1,51 @@
+// Web-specific attachment commands
+import { getAuthToken } from "@/lib/auth-token";
+import type { Attachment, AttachmentContent, NewAttachment } from "@/lib/types";
+import { API_PREFIX, invoke, logger } from "./core";
+
+/**
+ * Attach a file to an activity, asset or account.
+ * Web implementation: POSTs multipart form data to /api/v1/attachments.
+ */
+export const uploadAttachment = async (
+  file: File,
+  attachment: NewAttachment,
+): Promise<Attachment> => {
+  try {
+    const formData = new FormData();
+    formData.append("file", file);
+    formData.append("metadata", JSON.stringify(attachment));
+
+    const headers: HeadersInit = {};
+    const token = getAuthToken();
+    if (token) {
+      headers.Authorization = `Bearer ${token}`;
+    }
+
+    const response = await fetch(`${API_PREFIX}/attachments`, {
+      method: "POST",
+      headers,
+      body: formData,
+    });
+
+    if (!response.ok) {
+      let message = response.statusText;
+      try {
+        const payload = (await response.json()) as { message?: string };
+        message = payload.message ?? message;
+      } catch {
+        // Non-JSON error body
+      }
+      throw new Error(`Failed to upload attachment: ${message}`);
+    }
+
+    return (await response.json()) as Attachment;
+  } catch (err) {
+    logger.error("Error uploading attachment:", err);
+    throw err;
+  }
+};
+
+export const getAttachmentContent = async (attachmentId: string): Promise<AttachmentContent> => {
+  return invoke<AttachmentContent>("get_attachment_content", { attachmentId });
+};
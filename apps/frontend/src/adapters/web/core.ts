@@ -2,6 +2,7 @@
 // This module exports invoke, logger, and platform constants for shared modules
 
 import { getAuthToken, notifyUnauthorized } from "@/lib/auth-token";
+import type { Attachment } from "@/lib/types";
 import type { Logger } from "../types";
 
 /** True when running in the desktop (Tauri) environment */
@@ -29,12 +30,19 @@ export const COMMANDS: CommandMap = {
   backup_database: { method: "POST", path: "/utilities/database/backup" },
   backup_database_to_path: { method: "POST", path: "/utilities/database/backup-to-path" },
   restore_database: { method: "POST", path: "/utilities/database/restore" },
+  get_backup_schedule: { method: "GET", path: "/utilities/backups/schedule" },
+  update_backup_schedule: { method: "PUT", path: "/utilities/backups/schedule" },
+  list_backups: { method: "GET", path: "/utilities/backups" },
+  run_backup: { method: "POST", path: "/utilities/backups/run" },
+  preview_backup_restore: { method: "POST", path: "/utilities/backups/preview" },
+  restore_backup: { method: "POST", path: "/utilities/backups/restore" },
   get_holdings: { method: "GET", path: "/holdings" },
   get_holding: { method: "GET", path: "/holdings/item" },
   get_asset_holdings: { method: "GET", path: "/holdings/by-asset" },
   get_historical_valuations: { method: "GET", path: "/valuations/history" },
   get_latest_valuations: { method: "GET", path: "/valuations/latest" },
   get_portfolio_allocations: { method: "GET", path: "/allocations" },
+  simulate_scenario: { method: "POST", path: "/scenarios/simulate" },
   // Snapshot management
   get_snapshots: { method: "GET", path: "/snapshots" },
   get_snapshot_by_date: { method: "GET", path: "/snapshots/holdings" },
@@ -72,6 +80,13 @@ export const COMMANDS: CommandMap = {
   import_activities: { method: "POST", path: "/activities/import" },
   get_account_import_mapping: { method: "GET", path: "/activities/import/mapping" },
   save_account_import_mapping: { method: "POST", path: "/activities/import/mapping" },
+  get_duplicate_candidates: { method: "GET", path: "/activities/duplicates" },
+  resolve_duplicate_candidate: { method: "POST", path: "/activities/duplicates/resolve" },
+  get_import_run_review: { method: "GET", path: "/activities/import-runs" },
+  apply_import_run: { method: "POST", path: "/activities/import-runs" },
+  discard_import_run: { method: "POST", path: "/activities/import-runs" },
+  update_staged_activity: { method: "PUT", path: "/activities/staged" },
+  set_staged_activity_decision: { method: "POST", path: "/activities/staged" },
   // Market data providers
   get_exchanges: { method: "GET", path: "/exchanges" },
   get_market_data_providers: { method: "GET", path: "/providers" },
@@ -83,6 +98,24 @@ export const COMMANDS: CommandMap = {
   update_contribution_limit: { method: "PUT", path: "/limits" },
   delete_contribution_limit: { method: "DELETE", path: "/limits" },
   calculate_deposits_for_contribution_limit: { method: "GET", path: "/limits" },
+  // Recurring activities
+  get_recurring_templates: { method: "GET", path: "/recurring-activities" },
+  create_recurring_template: { method: "POST", path: "/recurring-activities" },
+  update_recurring_template: { method: "PUT", path: "/recurring-activities" },
+  delete_recurring_template: { method: "DELETE", path: "/recurring-activities" },
+  preview_recurring_occurrences: { method: "GET", path: "/recurring-activities" },
+  generate_recurring_activities: { method: "POST", path: "/recurring-activities/generate" },
+  // Change history
+  get_entity_history: { method: "GET", path: "/history" },
+  get_import_run_changes: { method: "GET", path: "/import-runs" },
+  rollback_change: { method: "POST", path: "/change-log" },
+  rollback_import_run: { method: "POST", path: "/import-runs" },
+  // Sync conflicts
+  list_sync_conflicts: { method: "GET", path: "/sync/conflicts" },
+  resolve_sync_conflict: { method: "POST", path: "/sync/conflicts/resolve" },
+  list_attachments: { method: "GET", path: "/attachments" },
+  get_attachment_content: { method: "GET", path: "/attachments" },
+  delete_attachment: { method: "DELETE", path: "/attachments" },
   // Asset profile
   get_assets: { method: "GET", path: "/assets" },
   delete_asset: { method: "DELETE", path: "/assets" },
@@ -131,6 +164,18 @@ export const COMMANDS: CommandMap = {
   execute_health_fix: { method: "POST", path: "/health/fix" },
   get_health_config: { method: "GET", path: "/health/config" },
   update_health_config: { method: "PUT", path: "/health/config" },
+  // Broker Reconciliation
+  reconcile_broker_statement: { method: "POST", path: "/reconciliation/statement" },
+  reconcile_broker_statement_csv: { method: "POST", path: "/reconciliation/csv" },
+  reconcile_broker_account: { method: "POST", path: "/connect/reconcile" },
+  get_holdings_drift_history: { method: "GET", path: "/reconciliation/drift-history" },
+  list_local_connectors: { method: "GET", path: "/connect/local-connectors" },
+  configure_local_connector: { method: "PUT", path: "/connect/local-connectors" },
+  remove_local_connector: { method: "DELETE", path: "/connect/local-connectors" },
+  sync_local_connector: { method: "POST", path: "/connect/local-connectors" },
+  get_sync_schedules: { method: "GET", path: "/connect/sync-schedules" },
+  update_sync_schedule: { method: "PUT", path: "/connect/sync-schedules" },
+  repair_broker_sync: { method: "POST", path: "/connect/sync/repair" },
   // Addons
   list_installed_addons: { method: "GET", path: "/addons/installed" },
   install_addon_zip: { method: "POST", path: "/addons/install-zip" },
@@ -211,6 +256,7 @@ export const COMMANDS: CommandMap = {
   enable_device_sync: { method: "POST", path: "/connect/device/enable" },
   clear_device_sync_data: { method: "DELETE", path: "/connect/device/sync-data" },
   reinitialize_device_sync: { method: "POST", path: "/connect/device/reinitialize" },
+  get_device_sync_relay: { method: "GET", path: "/connect/device/relay" },
   device_sync_engine_status: { method: "GET", path: "/connect/device/engine-status" },
   device_sync_pairing_source_status: {
     method: "GET",
@@ -252,6 +298,9 @@ export const COMMANDS: CommandMap = {
   get_ai_providers: { method: "GET", path: "/ai/providers" },
   update_ai_provider_settings: { method: "PUT", path: "/ai/providers/settings" },
   set_default_ai_provider: { method: "POST", path: "/ai/providers/default" },
+  set_ai_usage_budget: { method: "PUT", path: "/ai/providers/budget" },
+  save_custom_ai_provider: { method: "POST", path: "/ai/providers/custom" },
+  delete_custom_ai_provider: { method: "DELETE", path: "/ai/providers/custom" },
   list_ai_models: { method: "GET", path: "/ai/providers" },
   // AI Threads
   list_ai_threads: { method: "GET", path: "/ai/threads" },
@@ -263,6 +312,7 @@ export const COMMANDS: CommandMap = {
   remove_ai_thread_tag: { method: "DELETE", path: "/ai/threads" },
   get_ai_thread_tags: { method: "GET", path: "/ai/threads" },
   update_tool_result: { method: "PATCH", path: "/ai/tool-result" },
+  get_ai_usage_report: { method: "GET", path: "/ai/usage" },
   // Alternative Assets
   create_alternative_asset: { method: "POST", path: "/alternative-assets" },
   update_alternative_asset_valuation: { method: "PUT", path: "/alternative-assets" },
@@ -348,6 +398,17 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
       body = JSON.stringify({ backupFilePath });
       break;
     }
+    case "update_backup_schedule": {
+      const { update } = payload as { update: Record<string, unknown> };
+      body = JSON.stringify(update);
+      break;
+    }
+    case "preview_backup_restore":
+    case "restore_backup": {
+      const { archivePath, passphrase } = payload as { archivePath: string; passphrase?: string };
+      body = JSON.stringify({ archivePath, passphrase });
+      break;
+    }
     case "update_settings": {
       const data = payload as { settingsUpdate: Record<string, unknown> };
       body = JSON.stringify(data.settingsUpdate);
@@ -398,6 +459,11 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
       url += `?${params.toString()}`;
       break;
     }
+    case "simulate_scenario": {
+      const { request } = payload as { request: unknown };
+      body = JSON.stringify(request);
+      break;
+    }
     // Snapshot management
     case "get_snapshots": {
       const { accountId, dateFrom, dateTo } = payload as {
@@ -573,6 +639,47 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
       url += `?${params.toString()}`;
       break;
     }
+    case "get_duplicate_candidates": {
+      const { accountId } = (payload ?? {}) as { accountId?: string };
+      if (accountId) {
+        const params = new URLSearchParams();
+        params.set("accountId", accountId);
+        url += `?${params.toString()}`;
+      }
+      break;
+    }
+    case "resolve_duplicate_candidate": {
+      const { resolution } = payload as { resolution: Record<string, unknown> };
+      body = JSON.stringify(resolution);
+      break;
+    }
+    case "get_import_run_review": {
+      const { importRunId } = payload as { importRunId: string };
+      url += `/${encodeURIComponent(importRunId)}/review`;
+      break;
+    }
+    case "apply_import_run":
+    case "discard_import_run": {
+      const { importRunId } = payload as { importRunId: string };
+      const action = command === "apply_import_run" ? "apply" : "discard";
+      url += `/${encodeURIComponent(importRunId)}/${action}`;
+      break;
+    }
+    case "update_staged_activity": {
+      const { stagedId, activity } = payload as {
+        stagedId: string;
+        activity: Record<string, unknown>;
+      };
+      url += `/${encodeURIComponent(stagedId)}`;
+      body = JSON.stringify(activity);
+      break;
+    }
+    case "set_staged_activity_decision": {
+      const { stagedId, decision } = payload as { stagedId: string; decision: string };
+      url += `/${encodeURIComponent(stagedId)}/decision`;
+      body = JSON.stringify({ decision });
+      break;
+    }
     case "save_account_import_mapping": {
       const { mapping } = payload as { mapping: Record<string, unknown> };
       body = JSON.stringify({ mapping });
@@ -608,6 +715,85 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
       url += `/${encodeURIComponent(limitId)}/deposits`;
       break;
     }
+    case "create_recurring_template": {
+      const { template } = payload as { template: Record<string, unknown> };
+      body = JSON.stringify(template);
+      break;
+    }
+    case "update_recurring_template": {
+      const { template } = payload as { template: { id: string } & Record<string, unknown> };
+      url += `/${encodeURIComponent(template.id)}`;
+      body = JSON.stringify(template);
+      break;
+    }
+    case "delete_recurring_template": {
+      const { id } = payload as { id: string };
+      url += `/${encodeURIComponent(id)}`;
+      break;
+    }
+    case "preview_recurring_occurrences": {
+      const { id, through } = payload as { id: string; through?: string };
+      url += `/${encodeURIComponent(id)}/occurrences`;
+      if (through) url += `?through=${encodeURIComponent(through)}`;
+      break;
+    }
+    case "generate_recurring_activities": {
+      const { asOf } = (payload ?? {}) as { asOf?: string };
+      body = JSON.stringify({ asOf });
+      break;
+    }
+    case "get_entity_history": {
+      const { entityType, entityId } = payload as { entityType: string; entityId: string };
+      url += `/${encodeURIComponent(entityType)}/${encodeURIComponent(entityId)}`;
+      break;
+    }
+    case "get_import_run_changes": {
+      const { importRunId } = payload as { importRunId: string };
+      url += `/${encodeURIComponent(importRunId)}/changes`;
+      break;
+    }
+    case "list_sync_conflicts": {
+      const { includeResolved } = (payload ?? {}) as { includeResolved?: boolean };
+      if (includeResolved) {
+        const params = new URLSearchParams();
+        params.set("includeResolved", "true");
+        url += `?${params.toString()}`;
+      }
+      break;
+    }
+    case "resolve_sync_conflict": {
+      const { request } = payload as { request: Record<string, unknown> };
+      body = JSON.stringify(request);
+      break;
+    }
+    case "rollback_change": {
+      const { changeId } = payload as { changeId: string };
+      url += `/${encodeURIComponent(changeId)}/rollback`;
+      break;
+    }
+    case "rollback_import_run": {
+      const { importRunId } = payload as { importRunId: string };
+      url += `/${encodeURIComponent(importRunId)}/rollback`;
+      break;
+    }
+    case "list_attachments": {
+      const { entityType, entityId } = payload as { entityType: string; entityId: string };
+      const params = new URLSearchParams();
+      params.set("entityType", entityType);
+      params.set("entityId", entityId);
+      url += `?${params.toString()}`;
+      break;
+    }
+    case "get_attachment_content": {
+      const { attachmentId } = payload as { attachmentId: string };
+      url += `/${encodeURIComponent(attachmentId)}/content`;
+      break;
+    }
+    case "delete_attachment": {
+      const { attachmentId } = payload as { attachmentId: string };
+      url += `/${encodeURIComponent(attachmentId)}`;
+      break;
+    }
     case "get_asset_profile": {
       const { assetId } = payload as { assetId: string };
       const params = new URLSearchParams();
@@ -817,6 +1003,60 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
       body = JSON.stringify(config);
       break;
     }
+    case "reconcile_broker_statement": {
+      const { statement } = payload as { statement: Record<string, unknown> };
+      body = JSON.stringify(statement);
+      break;
+    }
+    case "reconcile_broker_statement_csv": {
+      const { accountId, asOfDate, content } = payload as {
+        accountId: string;
+        asOfDate: string;
+        content: string;
+      };
+      body = JSON.stringify({ accountId, asOfDate, content });
+      break;
+    }
+    case "reconcile_broker_account": {
+      const { accountId } = payload as { accountId: string };
+      url += `/${encodeURIComponent(accountId)}`;
+      break;
+    }
+    case "get_holdings_drift_history": {
+      const { accountId, limit } = (payload ?? {}) as { accountId?: string; limit?: number };
+      const params = new URLSearchParams();
+      if (accountId) params.set("accountId", accountId);
+      if (limit !== undefined) params.set("limit", String(limit));
+      const query = params.toString();
+      if (query) url += `?${query}`;
+      break;
+    }
+    case "configure_local_connector": {
+      const { connectorId, credential } = payload as { connectorId: string; credential: string };
+      url += `/${encodeURIComponent(connectorId)}`;
+      body = JSON.stringify({ credential });
+      break;
+    }
+    case "remove_local_connector": {
+      const { connectorId } = payload as { connectorId: string };
+      url += `/${encodeURIComponent(connectorId)}`;
+      break;
+    }
+    case "sync_local_connector": {
+      const { connectorId } = payload as { connectorId: string };
+      url += `/${encodeURIComponent(connectorId)}/sync`;
+      break;
+    }
+    case "update_sync_schedule": {
+      const { sourceId, schedule } = payload as { sourceId: string; schedule: unknown };
+      url += `/${encodeURIComponent(sourceId)}`;
+      body = JSON.stringify(schedule);
+      break;
+    }
+    case "repair_broker_sync": {
+      body = JSON.stringify(payload);
+      break;
+    }
     // Addons
     case "install_addon_zip": {
       const { zipData, enableAfterInstall } = payload as {
@@ -1064,6 +1304,7 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
     case "enable_device_sync":
     case "clear_device_sync_data":
     case "reinitialize_device_sync":
+    case "get_device_sync_relay":
       break;
     case "get_import_runs":
     case "get_data_import_runs": {
@@ -1154,6 +1395,21 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
       body = JSON.stringify(request);
       break;
     }
+    case "set_ai_usage_budget": {
+      const { request } = payload as { request: Record<string, unknown> };
+      body = JSON.stringify(request);
+      break;
+    }
+    case "save_custom_ai_provider": {
+      const { request } = payload as { request: Record<string, unknown> };
+      body = JSON.stringify(request);
+      break;
+    }
+    case "delete_custom_ai_provider": {
+      const { providerId } = payload as { providerId: string };
+      url += `/${encodeURIComponent(providerId)}`;
+      break;
+    }
     case "list_ai_models": {
       const { providerId } = payload as { providerId: string };
       url += `/${encodeURIComponent(providerId)}/models`;
@@ -1184,6 +1440,15 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
       url += `/${encodeURIComponent(threadId)}/messages`;
       break;
     }
+    case "get_ai_usage_report": {
+      const { from, to } = (payload ?? {}) as { from?: string; to?: string };
+      const params = new URLSearchParams();
+      if (from) params.set("from", from);
+      if (to) params.set("to", to);
+      const qs = params.toString();
+      if (qs) url += `?${qs}`;
+      break;
+    }
     case "update_tool_result": {
       const { request } = payload as {
         request: { threadId: string; toolCallId: string; resultPatch: unknown };
@@ -1257,6 +1522,7 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
     "get_broker_ingest_states",
     "get_import_runs",
     "get_data_import_runs",
+    "reconcile_broker_account",
     "get_synced_accounts",
     "get_platforms",
     "sync_broker_data",
@@ -1296,6 +1562,13 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
       data: fromBase64(parsed.dataB64),
     } as T;
   }
+  if (command === "get_attachment_content") {
+    const parsed = (await res.json()) as { attachment: Attachment; dataB64: string };
+    return {
+      attachment: parsed.attachment,
+      data: fromBase64(parsed.dataB64),
+    } as T;
+  }
   if (command === "backup_database_to_path") {
     const parsed = (await res.json()) as { path: string };
     return parsed.path as T;
@@ -2,6 +2,7 @@ import type { FolderSyncCommandResult, FolderSyncState } from "@/lib/types";
 
 const unsupportedState: FolderSyncState = {
   config: null,
+  isEncrypted: false,
   status: {
     syncState: "unsupported",
     lastCheckedAt: null,
@@ -28,6 +29,12 @@ export const initializeFolderSync = (): Promise<FolderSyncCommandResult> =>
 
 export const joinFolderSync = (): Promise<FolderSyncCommandResult> => Promise.resolve(unsupportedResult);
 
+export const unlockFolderSync = (): Promise<FolderSyncCommandResult> =>
+  Promise.resolve(unsupportedResult);
+
+export const changeFolderSyncPassphrase = (): Promise<FolderSyncCommandResult> =>
+  Promise.resolve(unsupportedResult);
+
 export const retryFolderSyncNow = (): Promise<FolderSyncCommandResult> =>
   Promise.resolve(unsupportedResult);
 
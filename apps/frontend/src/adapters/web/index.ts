@@ -42,6 +42,11 @@ export type {
   BackendSyncCycleResult,
   BackendSyncBackgroundEngineResult,
   BackendSyncSnapshotUploadResult,
+  DeviceSyncRelayInfo,
+  LocalConnectorInfo,
+  SyncSchedule,
+  SyncScheduleTrigger,
+  RepairSyncRequest,
   EphemeralKeyPair,
   Logger,
 } from "../types";
@@ -91,6 +96,13 @@ export {
   getAccountImportMapping,
   saveAccountImportMapping,
   checkExistingDuplicates,
+  getDuplicateCandidates,
+  resolveDuplicateCandidate,
+  getImportRunReview,
+  applyImportRun,
+  discardImportRun,
+  updateStagedActivity,
+  setStagedActivityDecision,
 } from "../shared/activities";
 export { parseCsv } from "./activities";
 
@@ -142,6 +154,7 @@ export {
   getAssetHoldings,
   getPortfolioAllocations,
   getHoldingsByAllocation,
+  simulateScenario,
   saveManualHoldings,
   importHoldingsCsv,
   checkHoldingsImport,
@@ -183,6 +196,41 @@ export {
   calculateDepositsForLimit,
 } from "../shared/contribution-limits";
 
+// Recurring Activity Commands
+export {
+  getRecurringTemplates,
+  createRecurringTemplate,
+  updateRecurringTemplate,
+  deleteRecurringTemplate,
+  previewRecurringOccurrences,
+  generateRecurringActivities,
+} from "../shared/recurring";
+
+// Change History Commands
+export {
+  getEntityHistory,
+  getImportRunChanges,
+  rollbackChange,
+  rollbackImportRun,
+} from "../shared/audit";
+
+// Scheduled Backup Commands
+export {
+  getBackupSchedule,
+  updateBackupSchedule,
+  listBackups,
+  runBackup,
+  previewBackupRestore,
+  restoreBackup,
+} from "../shared/backups";
+
+// Sync Conflict Commands
+export { listSyncConflicts, resolveSyncConflict } from "../shared/sync-conflicts";
+
+// Attachment Commands
+export { listAttachments, deleteAttachment } from "../shared/attachments";
+export { uploadAttachment, getAttachmentContent } from "./attachments";
+
 // Exchange Rates Commands
 export {
   getExchangeRates,
@@ -217,10 +265,18 @@ export {
   getUserInfo,
   getBrokerSyncStates,
   getImportRuns,
+  listLocalConnectors,
+  configureLocalConnector,
+  removeLocalConnector,
+  syncLocalConnector,
+  getSyncSchedules,
+  updateSyncSchedule,
+  repairBrokerSync,
   getDeviceSyncState,
   enableDeviceSync,
   clearDeviceSyncData,
   reinitializeDeviceSync,
+  getDeviceSyncRelay,
   getSyncEngineStatus,
   getPairingSourceStatus,
   deviceSyncBootstrapOverwriteCheck,
@@ -261,6 +317,9 @@ export {
   getAiProviders,
   updateAiProviderSettings,
   setDefaultAiProvider,
+  setAiUsageBudget,
+  saveCustomAiProvider,
+  deleteCustomAiProvider,
   listAiModels,
 } from "../shared/ai-providers";
 
@@ -268,6 +327,8 @@ export {
   getFolderSyncState,
   initializeFolderSync,
   joinFolderSync,
+  unlockFolderSync,
+  changeFolderSyncPassphrase,
   retryFolderSyncNow,
   disableFolderSync,
 } from "./folder-sync";
@@ -283,6 +344,7 @@ export {
   removeAiThreadTag,
   getAiThreadTags,
   updateToolResult,
+  getAiUsageReport,
 } from "../shared/ai-threads";
 
 // Health Center Commands
@@ -295,6 +357,10 @@ export {
   executeHealthFix,
   getHealthConfig,
   updateHealthConfig,
+  reconcileBrokerStatement,
+  reconcileBrokerStatementCsv,
+  reconcileBrokerAccount,
+  getHoldingsDriftHistory,
 } from "../shared/health";
 
 // ============================================================================
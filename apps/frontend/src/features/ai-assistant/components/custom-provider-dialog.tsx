@@ -0,0 +1,180 @@
+import { useEffect, useState } from "react";
+import { Button } from "@wealthfolio/ui/components/ui/button";
+import { Checkbox } from "@wealthfolio/ui/components/ui/checkbox";
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from "@wealthfolio/ui/components/ui/dialog";
+import { Icons } from "@wealthfolio/ui/components/ui/icons";
+import { Input } from "@wealthfolio/ui/components/ui/input";
+import { Label } from "@wealthfolio/ui/components/ui/label";
+import type { CustomProviderConfig, SaveCustomProviderRequest } from "../types";
+
+type CapabilityKey = "tools" | "thinking" | "vision";
+
+const CAPABILITY_OPTIONS: { key: CapabilityKey; label: string; description: string }[] = [
+  { key: "tools", label: "Tools", description: "Model can call portfolio tools" },
+  { key: "thinking", label: "Thinking", description: "Model streams its reasoning" },
+  { key: "vision", label: "Vision", description: "Model accepts image attachments" },
+];
+
+interface CustomProviderDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+  /** Provider being edited; omitted when adding a new one. */
+  provider?: CustomProviderConfig;
+  onSave: (request: SaveCustomProviderRequest) => void;
+  isSaving?: boolean;
+}
+
+/**
+ * Dialog to add or edit a user-defined OpenAI-compatible provider,
+ * such as LM Studio, vLLM, llama.cpp server or LocalAI.
+ */
+export function CustomProviderDialog({
+  open,
+  onOpenChange,
+  provider,
+  onSave,
+  isSaving = false,
+}: CustomProviderDialogProps) {
+  const [name, setName] = useState("");
+  const [baseUrl, setBaseUrl] = useState("");
+  const [defaultModel, setDefaultModel] = useState("");
+  const [contextWindow, setContextWindow] = useState("");
+  const [capabilities, setCapabilities] = useState<Record<CapabilityKey, boolean>>({
+    tools: true,
+    thinking: false,
+    vision: false,
+  });
+
+  // Reset the form each time the dialog opens
+  useEffect(() => {
+    if (!open) return;
+    setName(provider?.name ?? "");
+    setBaseUrl(provider?.baseUrl ?? "");
+    setDefaultModel(provider?.defaultModel ?? "");
+    setContextWindow(provider?.contextWindow ? String(provider.contextWindow) : "");
+    setCapabilities({
+      tools: provider?.capabilities.tools ?? true,
+      thinking: provider?.capabilities.thinking ?? false,
+      vision: provider?.capabilities.vision ?? false,
+    });
+  }, [open, provider]);
+
+  const trimmedUrl = baseUrl.trim();
+  const isValidUrl = /^https?:\/\/\S+$/i.test(trimmedUrl);
+  const parsedContextWindow = contextWindow.trim() ? Number(contextWindow) : undefined;
+  const isValidContextWindow =
+    parsedContextWindow === undefined ||
+    (Number.isInteger(parsedContextWindow) && parsedContextWindow > 0);
+  const canSave = name.trim() !== "" && isValidUrl && isValidContextWindow && !isSaving;
+
+  const handleSave = () => {
+    if (!canSave) return;
+    onSave({
+      id: provider?.id,
+      name: name.trim(),
+      baseUrl: trimmedUrl,
+      capabilities: { ...capabilities, streaming: true },
+      defaultModel: defaultModel.trim() || undefined,
+      contextWindow: parsedContextWindow,
+    });
+  };
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="sm:max-w-md">
+        <DialogHeader>
+          <DialogTitle>{provider ? "Edit provider" : "Add OpenAI-compatible provider"}</DialogTitle>
+          <DialogDescription>
+            Connect a local inference server or any endpoint that speaks the OpenAI chat
+            completions API.
+          </DialogDescription>
+        </DialogHeader>
+        <div className="space-y-4">
+          <div className="space-y-2">
+            <Label htmlFor="custom-provider-name">Name</Label>
+            <Input
+              id="custom-provider-name"
+              value={name}
+              onChange={(e) => setName(e.target.value)}
+              placeholder="LM Studio"
+            />
+          </div>
+          <div className="space-y-2">
+            <Label htmlFor="custom-provider-url">Base URL</Label>
+            <Input
+              id="custom-provider-url"
+              type="url"
+              value={baseUrl}
+              onChange={(e) => setBaseUrl(e.target.value)}
+              placeholder="http://localhost:1234/v1"
+              className="font-mono text-sm"
+            />
+            <p className="text-muted-foreground text-xs">
+              Include the version path. Models are discovered from {"<base URL>/models"}.
+            </p>
+          </div>
+          <div className="grid grid-cols-2 gap-3">
+            <div className="space-y-2">
+              <Label htmlFor="custom-provider-model">Default model</Label>
+              <Input
+                id="custom-provider-model"
+                value={defaultModel}
+                onChange={(e) => setDefaultModel(e.target.value)}
+                placeholder="Optional"
+              />
+            </div>
+            <div className="space-y-2">
+              <Label htmlFor="custom-provider-context">Context window</Label>
+              <Input
+                id="custom-provider-context"
+                inputMode="numeric"
+                value={contextWindow}
+                onChange={(e) => setContextWindow(e.target.value)}
+                placeholder="Optional"
+              />
+            </div>
+          </div>
+          <div className="space-y-2">
+            <Label>Capabilities</Label>
+            {CAPABILITY_OPTIONS.map((option) => (
+              <label
+                key={option.key}
+                htmlFor={`custom-provider-${option.key}`}
+                className="flex cursor-pointer items-start gap-2.5"
+              >
+                <Checkbox
+                  id={`custom-provider-${option.key}`}
+                  checked={capabilities[option.key]}
+                  onCheckedChange={(checked) =>
+                    setCapabilities((prev) => ({ ...prev, [option.key]: checked === true }))
+                  }
+                  className="mt-0.5"
+                />
+                <div>
+                  <div className="text-sm font-medium">{option.label}</div>
+                  <div className="text-muted-foreground text-xs">{option.description}</div>
+                </div>
+              </label>
+            ))}
+          </div>
+        </div>
+        <DialogFooter>
+          <Button variant="outline" onClick={() => onOpenChange(false)}>
+            Cancel
+          </Button>
+          <Button onClick={handleSave} disabled={!canSave}>
+            {isSaving && <Icons.Spinner className="mr-2 h-4 w-4 animate-spin" />}
+            {provider ? "Save" : "Add provider"}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -7,6 +7,7 @@ export { Reasoning, ReasoningGroup } from "./reasoning";
 export { ToolFallback } from "./tool-fallback";
 export { TooltipIconButton } from "./tooltip-icon-button";
 export { ProviderSettingsCard } from "./provider-settings-card";
+export { CustomProviderDialog } from "./custom-provider-dialog";
 export { ProviderIcon } from "./provider-icons";
 export { ProviderPicker } from "./provider-picker";
 export { ModelPicker } from "./model-picker";
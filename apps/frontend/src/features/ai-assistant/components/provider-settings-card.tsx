@@ -22,7 +22,13 @@ import {
   CommandList,
 } from "@wealthfolio/ui/components/ui/command";
 import { cn } from "@/lib/utils";
-import type { MergedProvider, MergedModel, FetchedModel, ModelCapabilityOverrides } from "../types";
+import type {
+  AmountRedaction,
+  MergedProvider,
+  MergedModel,
+  FetchedModel,
+  ModelCapabilityOverrides,
+} from "../types";
 import { ProviderIcon } from "./provider-icons";
 
 interface ProviderSettingsCardProps {
@@ -38,6 +44,11 @@ interface ProviderSettingsCardProps {
   onSetFavoriteModels?: (modelIds: string[]) => void;
   onSetCapabilityOverride?: (modelId: string, overrides: ModelCapabilityOverrides | null) => void;
   onToolsAllowlistChange?: (tools: string[] | null) => void;
+  onRedactionChange?: (redaction: MergedProvider["redaction"]) => void;
+  /** Edit the endpoint of a user-defined provider. */
+  onEditCustomProvider?: () => void;
+  /** Remove a user-defined provider. */
+  onDeleteCustomProvider?: () => void;
   isLast?: boolean;
   // Model fetching props (controlled by parent via React Query)
   modelComboboxOpen?: boolean;
@@ -70,8 +81,37 @@ const DATA_ACCESS_OPTIONS = [
     description: "Portfolio allocation breakdown",
   },
   { toolId: "get_valuation_history", label: "History", description: "Portfolio value over time" },
+  {
+    toolId: "get_net_worth",
+    label: "Net Worth",
+    description: "Net worth including property and liabilities",
+  },
+  {
+    toolId: "get_alternative_assets",
+    label: "Other Assets",
+    description: "Property, vehicles, collectibles, and loans",
+  },
+  {
+    toolId: "get_health_issues",
+    label: "Data Health",
+    description: "Stale prices and data issues",
+  },
+  { toolId: "get_exchange_rate", label: "Exchange Rates", description: "Latest and past FX rates" },
+  { toolId: "get_quote_history", label: "Prices", description: "Historical prices of your assets" },
+  {
+    toolId: "simulate_scenario",
+    label: "What-if",
+    description: "Simulate trades without recording them",
+  },
 ];
 
+const AMOUNT_REDACTION_OPTIONS: { value: AmountRedaction; label: string; description: string }[] =
+  [
+    { value: "exact", label: "Exact", description: "Amounts are sent as-is" },
+    { value: "scaled", label: "Scaled", description: "Multiplied by a hidden factor" },
+    { value: "bucketed", label: "Ranges", description: "Replaced by ranges like 10k-20k" },
+  ];
+
 export function ProviderSettingsCard({
   provider,
   onToggleEnabled,
@@ -84,6 +124,9 @@ export function ProviderSettingsCard({
   onSetFavoriteModels,
   onSetCapabilityOverride,
   onToolsAllowlistChange,
+  onRedactionChange,
+  onEditCustomProvider,
+  onDeleteCustomProvider,
   isLast = false,
   // Model fetching props
   modelComboboxOpen: controlledComboboxOpen,
@@ -115,6 +158,9 @@ export function ProviderSettingsCard({
   const isFetchingModels = externalIsFetchingModels ?? false;
   const fetchError = externalFetchModelsError ?? null;
 
+  // User-defined OpenAI-compatible endpoints; the API key is optional
+  const isCustom = provider.type === "custom";
+
   // Check if provider supports custom base URL
   const supportsCustomUrl = provider.connectionFields?.some(
     (field) => field.key === "baseUrl" || field.key === "customUrl",
@@ -325,13 +371,13 @@ export function ProviderSettingsCard({
         <CollapsibleContent>
           <div className="border-t px-4 py-5">
             <div className="space-y-5">
-              {/* API Key Section (only for API providers) */}
-              {provider.type === "api" && (
+              {/* API Key Section (API providers and user-defined endpoints) */}
+              {(provider.type === "api" || isCustom) && (
                 <div className="bg-muted/40 rounded-lg p-4">
                   <div className="space-y-3">
                     <div className="flex items-center justify-between">
                       <Label htmlFor={`apikey-${provider.id}`} className="text-sm font-medium">
-                        API Key
+                        {isCustom ? "API Key (optional)" : "API Key"}
                       </Label>
                       {provider.documentationUrl && (
                         <ExternalLink
@@ -356,7 +402,9 @@ export function ProviderSettingsCard({
                                 : ""
                           }
                           onChange={(e) => setApiKeyValue(e.target.value)}
-                          placeholder={provider.hasApiKey ? "" : "Enter API key"}
+                          placeholder={
+                            provider.hasApiKey ? "" : isCustom ? "Optional" : "Enter API key"
+                          }
                           className="bg-background pr-9 font-mono text-sm"
                           readOnly={!hasLoadedKey && provider.hasApiKey}
                         />
@@ -702,6 +750,42 @@ export function ProviderSettingsCard({
                 </div>
               )}
 
+              {/* Endpoint Section (user-defined providers) */}
+              {isCustom && (
+                <div className="bg-muted/40 rounded-lg p-4">
+                  <div className="space-y-2">
+                    <Label className="text-sm font-medium">Endpoint</Label>
+                    <div className="flex items-center gap-2">
+                      <code className="bg-background text-muted-foreground flex-1 truncate rounded-md border px-3 py-2 text-sm">
+                        {provider.customUrl}
+                      </code>
+                      {onEditCustomProvider && (
+                        <Button
+                          variant="outline"
+                          size="icon"
+                          className="shrink-0"
+                          onClick={onEditCustomProvider}
+                          aria-label="Edit provider"
+                        >
+                          <Icons.Pencil className="h-4 w-4" />
+                        </Button>
+                      )}
+                      {onDeleteCustomProvider && (
+                        <Button
+                          variant="outline"
+                          size="icon"
+                          className="text-destructive hover:text-destructive shrink-0"
+                          onClick={onDeleteCustomProvider}
+                          aria-label="Remove provider"
+                        >
+                          <Icons.Trash className="h-4 w-4" />
+                        </Button>
+                      )}
+                    </div>
+                  </div>
+                </div>
+              )}
+
               {/* Data Access Section */}
               {onToolsAllowlistChange && (
                 <div className="space-y-3">
@@ -748,6 +832,54 @@ export function ProviderSettingsCard({
                   </div>
                 </div>
               )}
+
+              {/* Privacy Section */}
+              {onRedactionChange && (
+                <div className="space-y-3">
+                  <div className="flex items-center justify-between gap-4">
+                    <div>
+                      <Label className="text-sm font-medium">Privacy Redaction</Label>
+                      <p className="text-muted-foreground text-xs">
+                        Replace account and asset names with placeholders and remove notes before
+                        data is sent. Names are restored in the answer you see.
+                      </p>
+                    </div>
+                    <Switch
+                      checked={provider.redaction.enabled}
+                      onCheckedChange={(enabled) =>
+                        onRedactionChange({ ...provider.redaction, enabled })
+                      }
+                    />
+                  </div>
+                  {provider.redaction.enabled && (
+                    <div className="grid grid-cols-1 gap-2 sm:grid-cols-3">
+                      {AMOUNT_REDACTION_OPTIONS.map((option) => {
+                        const isSelected = provider.redaction.amounts === option.value;
+                        return (
+                          <button
+                            key={option.value}
+                            type="button"
+                            onClick={() =>
+                              onRedactionChange({ ...provider.redaction, amounts: option.value })
+                            }
+                            className={cn(
+                              "rounded-lg border p-3 text-left transition-all",
+                              isSelected
+                                ? "border-primary/30 bg-primary/5"
+                                : "bg-muted/40 hover:bg-muted/60 border-transparent",
+                            )}
+                          >
+                            <span className="text-sm font-medium">{option.label}</span>
+                            <p className="text-muted-foreground mt-0.5 text-xs leading-tight">
+                              {option.description}
+                            </p>
+                          </button>
+                        );
+                      })}
+                    </div>
+                  )}
+                </div>
+              )}
             </div>
           </div>
         </CollapsibleContent>
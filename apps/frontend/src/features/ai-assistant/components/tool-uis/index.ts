@@ -14,7 +14,7 @@ import { ImportCsvToolUI } from "./import-csv-tool-ui";
 import { IncomeToolUI } from "./income-tool-ui";
 import { PerformanceToolUI } from "./performance-tool-ui";
 import { RecordActivityToolUI } from "./record-activity-tool-ui";
-import { RecordActivitiesToolUI } from "./record-activities-tool-ui";
+import { ExtractStatementToolUI, RecordActivitiesToolUI } from "./record-activities-tool-ui";
 import { ValuationToolUI } from "./valuation-tool-ui";
 
 /**
@@ -22,6 +22,7 @@ import { ValuationToolUI } from "./valuation-tool-ui";
  * Used by MessagePrimitive.Parts in thread.tsx.
  */
 export const toolUIs = {
+  extract_statement: ExtractStatementToolUI,
   get_accounts: AccountsToolUI,
   get_asset_allocation: AllocationToolUI,
   get_goals: GoalsToolUI,
@@ -42,6 +43,7 @@ export {
   AccountsToolUI,
   ActivitiesToolUI,
   AllocationToolUI,
+  ExtractStatementToolUI,
   GoalsToolUI,
   HoldingsToolUI,
   ImportCsvToolUI,
@@ -23,6 +23,8 @@ import { Icons } from "@wealthfolio/ui/components/ui/icons";
 import { useMemo, useState } from "react";
 import { useRuntimeContext } from "../../hooks/use-runtime-context";
 import type {
+  ExtractStatementArgs,
+  ExtractStatementOutput,
   RecordActivitiesArgs,
   RecordActivitiesOutput,
   RecordActivitiesSubmissionStatus,
@@ -31,6 +33,7 @@ import {
   buildRecordActivitiesCreatePayload,
   mapRecordActivitiesSubmission,
   normalizeRecordActivitiesResult,
+  normalizeStatementSources,
 } from "./record-activities-tool-utils";
 import {
   createActivityAmountFormatter,
@@ -45,7 +48,10 @@ import {
 type RecordActivitiesToolUIContentProps = ToolCallMessagePartProps<
   RecordActivitiesArgs,
   RecordActivitiesOutput
->;
+> & {
+  /** Set for extract_statement results, which list their source attachments. */
+  fromStatement?: boolean;
+};
 
 interface RowStatusBadge {
   label: string;
@@ -127,6 +133,7 @@ function RecordActivitiesToolUIContent({
   result,
   status,
   toolCallId,
+  fromStatement = false,
 }: RecordActivitiesToolUIContentProps) {
   const { settings } = useSettingsContext();
   const baseCurrency = settings?.baseCurrency ?? "USD";
@@ -137,6 +144,7 @@ function RecordActivitiesToolUIContent({
     () => normalizeRecordActivitiesResult(result, baseCurrency),
     [baseCurrency, result],
   );
+  const statementSources = useMemo(() => normalizeStatementSources(result), [result]);
   const amountFormatter = useMemo(() => createActivityAmountFormatter(), []);
   const quantityFormatter = useMemo(() => createActivityQuantityFormatter(), []);
 
@@ -177,7 +185,11 @@ function RecordActivitiesToolUIContent({
     return (
       <Card className="border-destructive/30 bg-destructive/5">
         <CardContent className="py-4">
-          <p className="text-destructive text-sm font-medium">Failed to prepare activity drafts</p>
+          <p className="text-destructive text-sm font-medium">
+            {fromStatement
+              ? "Failed to extract activities from the attachment"
+              : "Failed to prepare activity drafts"}
+          </p>
         </CardContent>
       </Card>
     );
@@ -263,8 +275,14 @@ function RecordActivitiesToolUIContent({
       <CardHeader className="pb-2">
         <div className="flex flex-wrap items-start justify-between gap-2">
           <div>
-            <CardTitle className="text-sm font-medium">Batch Activity Preview</CardTitle>
-            <p className="text-muted-foreground mt-1 text-xs">Review rows, then confirm once.</p>
+            <CardTitle className="text-sm font-medium">
+              {fromStatement ? "Statement Extraction Preview" : "Batch Activity Preview"}
+            </CardTitle>
+            <p className="text-muted-foreground mt-1 text-xs">
+              {statementSources.sources.length > 0 &&
+                `From ${statementSources.sources.join(", ")}. `}
+              Review rows, then confirm once.
+            </p>
           </div>
           <Badge variant="outline" className="text-xs">
             {validRows} ready
@@ -367,6 +385,17 @@ function RecordActivitiesToolUIContent({
                               {row.errors[0]}
                             </p>
                           )}
+                        {!statusEntry?.error &&
+                          !row.errors[0] &&
+                          row.warnings?.[0] &&
+                          rowStatusBadge.label !== "Submitted" && (
+                            <p
+                              className="max-w-[180px] truncate text-[10px] text-amber-600 dark:text-amber-400"
+                              title={row.warnings.join("\n")}
+                            >
+                              {row.warnings[0]}
+                            </p>
+                          )}
                       </div>
                     </TableCell>
                   </TableRow>
@@ -376,6 +405,14 @@ function RecordActivitiesToolUIContent({
           </Table>
         </div>
 
+        {statementSources.skipped.length > 0 && (
+          <div className="text-muted-foreground mx-6 space-y-1 text-xs">
+            {statementSources.skipped.map((entry) => (
+              <p key={entry}>Skipped {entry}</p>
+            ))}
+          </div>
+        )}
+
         {submitError && (
           <div className="border-destructive/50 bg-destructive/10 text-destructive mx-6 flex items-center gap-2 rounded-md border px-3 py-2 text-xs">
             <Icons.AlertCircle className="h-4 w-4 shrink-0" />
@@ -415,3 +452,18 @@ export const RecordActivitiesToolUI = makeAssistantToolUI<
     return <RecordActivitiesToolUIContent {...props} />;
   },
 });
+
+export const ExtractStatementToolUI = makeAssistantToolUI<
+  ExtractStatementArgs,
+  ExtractStatementOutput
+>({
+  toolName: "extract_statement",
+  render: (props) => {
+    return (
+      <RecordActivitiesToolUIContent
+        {...(props as unknown as RecordActivitiesToolUIContentProps)}
+        fromStatement
+      />
+    );
+  },
+});
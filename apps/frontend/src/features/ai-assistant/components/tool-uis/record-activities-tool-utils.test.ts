@@ -5,6 +5,7 @@ import {
   hasValidRecordActivityRows,
   mapRecordActivitiesSubmission,
   normalizeRecordActivitiesResult,
+  normalizeStatementSources,
 } from "./record-activities-tool-utils";
 
 describe("normalizeRecordActivitiesResult", () => {
@@ -48,6 +49,40 @@ describe("normalizeRecordActivitiesResult", () => {
     expect(normalized?.validation.validRows).toBe(1);
     expect(normalized?.availableAccounts[0].id).toBe("acc-1");
   });
+
+  it("keeps extraction warnings and per-field confidence", () => {
+    const normalized = normalizeRecordActivitiesResult(
+      {
+        drafts: [
+          {
+            rowIndex: 0,
+            draft: { activityType: "BUY", activityDate: "2026-03-02", currency: "USD" },
+            validation: { isValid: true, missingFields: [], errors: [] },
+            warnings: ["Low confidence: unitPrice"],
+            confidence: { symbol: 0.98, unitPrice: "0.55", note: "n/a" },
+          },
+        ],
+        validation: { totalRows: 1, validRows: 1, errorRows: 0 },
+        availableAccounts: [],
+        sources: ["contract-note.png"],
+      },
+      "USD",
+    );
+
+    expect(normalized?.drafts[0].warnings).toEqual(["Low confidence: unitPrice"]);
+    expect(normalized?.drafts[0].confidence).toEqual({ symbol: 0.98, unitPrice: 0.55 });
+  });
+});
+
+describe("normalizeStatementSources", () => {
+  it("reads sources and skipped attachments from wrapped results", () => {
+    expect(
+      normalizeStatementSources(
+        JSON.stringify({ data: { sources: ["a.png"], skipped: ["b.pdf: no text"] } }),
+      ),
+    ).toEqual({ sources: ["a.png"], skipped: ["b.pdf: no text"] });
+    expect(normalizeStatementSources(null)).toEqual({ sources: [], skipped: [] });
+  });
 });
 
 describe("buildRecordActivitiesCreatePayload", () => {
@@ -104,6 +139,7 @@ describe("buildRecordActivitiesCreatePayload", () => {
     expect(creates[0].activityType).toBe("BUY");
     expect(creates[0].symbol?.symbol).toBe("AAPL");
     expect(creates[0].symbol?.exchangeMic).toBe("XNAS");
+    expect(creates[0].sourceSystem).toBe("AI_ASSISTANT");
     expect(rowIndexByTempId.get("record-activities-0")).toBe(0);
     expect(rowIndexByTempId.get("record-activities-1")).toBeUndefined();
   });
@@ -1,3 +1,4 @@
+import { AI_ASSISTANT_SOURCE_SYSTEM } from "@/lib/constants";
 import type { ActivityBulkMutationResult, ActivityCreate } from "@/lib/types";
 import type {
   RecordActivitiesDraft,
@@ -92,6 +93,16 @@ function normalizeDraft(raw: UnknownObject, fallbackCurrency: string): RecordAct
   };
 }
 
+function normalizeConfidence(raw: UnknownObject | undefined): Record<string, number> | undefined {
+  if (!raw) return undefined;
+  const confidence: Record<string, number> = {};
+  for (const [field, value] of Object.entries(raw)) {
+    const score = toNumber(value);
+    if (score !== undefined) confidence[field] = score;
+  }
+  return Object.keys(confidence).length > 0 ? confidence : undefined;
+}
+
 function normalizeRow(
   raw: UnknownObject,
   fallbackCurrency: string,
@@ -148,6 +159,8 @@ function normalizeRow(
     errors: Array.isArray(raw.errors) ? (raw.errors as string[]) : [],
     resolvedAsset,
     availableSubtypes,
+    warnings: Array.isArray(raw.warnings) ? (raw.warnings as string[]) : [],
+    confidence: normalizeConfidence(pickUnknownObject(raw.confidence)),
   };
 }
 
@@ -229,6 +242,33 @@ export function normalizeRecordActivitiesResult(
   };
 }
 
+/**
+ * Reads the attachment names from an extract_statement result.
+ */
+export function normalizeStatementSources(result: unknown): {
+  sources: string[];
+  skipped: string[];
+} {
+  let candidate: unknown = result;
+  if (typeof candidate === "string") {
+    try {
+      candidate = JSON.parse(candidate);
+    } catch {
+      candidate = undefined;
+    }
+  }
+  let object = pickUnknownObject(candidate);
+  if (object && pickUnknownObject(object.data)) {
+    object = pickUnknownObject(object.data);
+  }
+  const strings = (values: unknown[]) =>
+    values.filter((value): value is string => typeof value === "string");
+  return {
+    sources: object ? strings(pickArray(object, "sources", "sources")) : [],
+    skipped: object ? strings(pickArray(object, "skipped", "skipped")) : [],
+  };
+}
+
 export function hasValidRecordActivityRows(rows: RecordActivitiesDraftRow[]): boolean {
   return rows.some((row) => row.validation.isValid);
 }
@@ -264,6 +304,7 @@ export function buildRecordActivitiesCreatePayload(rows: RecordActivitiesDraftRo
       fee: row.draft.fee,
       currency: row.draft.currency,
       comment: row.draft.notes ?? undefined,
+      sourceSystem: AI_ASSISTANT_SOURCE_SYSTEM,
     });
   }
 
@@ -36,6 +36,7 @@ import { useSettingsContext } from "@/lib/settings-provider";
 import { createActivity, updateToolResult } from "@/adapters";
 import {
   ActivityType,
+  AI_ASSISTANT_SOURCE_SYSTEM,
   ACTIVITY_TYPE_DISPLAY_NAMES,
   SUBTYPES_BY_ACTIVITY_TYPE,
   SUBTYPE_DISPLAY_NAMES,
@@ -681,6 +682,7 @@ function DraftForm({
         comment: formValues.notes || undefined,
         subtype:
           formValues.subtype && formValues.subtype !== "__none__" ? formValues.subtype : undefined,
+        sourceSystem: AI_ASSISTANT_SOURCE_SYSTEM,
       };
 
       // Create the activity
@@ -3,6 +3,8 @@ export {
   useAiProviders,
   useUpdateAiProviderSettings,
   useSetDefaultAiProvider,
+  useSaveCustomAiProvider,
+  useDeleteCustomAiProvider,
   useAiProviderApiKey,
   useListAiModels,
 } from "./use-ai-providers";
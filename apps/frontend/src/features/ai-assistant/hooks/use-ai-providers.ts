@@ -3,13 +3,22 @@ import {
   getAiProviders,
   updateAiProviderSettings,
   setDefaultAiProvider,
+  setAiUsageBudget,
+  saveCustomAiProvider,
+  deleteCustomAiProvider,
+  getAiUsageReport,
   listAiModels,
   logger,
   setSecret,
   getSecret,
   deleteSecret,
 } from "@/adapters";
-import type { UpdateProviderSettingsRequest, SetDefaultProviderRequest } from "@/lib/types";
+import type {
+  UpdateProviderSettingsRequest,
+  SetDefaultProviderRequest,
+  SetUsageBudgetRequest,
+  SaveCustomProviderRequest,
+} from "@/lib/types";
 import { QueryKeys } from "@/lib/query-keys";
 import { toast } from "@wealthfolio/ui/components/ui/use-toast";
 
@@ -53,6 +62,84 @@ export function useSetDefaultAiProvider() {
   });
 }
 
+/**
+ * Hook to add or update a user-defined OpenAI-compatible provider.
+ */
+export function useSaveCustomAiProvider() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (request: SaveCustomProviderRequest) => saveCustomAiProvider(request),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: AI_PROVIDERS_KEY });
+    },
+    onError: (error) => {
+      logger.error(`Failed to save custom AI provider: ${error}`);
+      toast({
+        title: "Failed to save provider",
+        description: error instanceof Error ? error.message : String(error),
+        variant: "destructive",
+      });
+    },
+  });
+}
+
+/**
+ * Hook to remove a user-defined provider.
+ */
+export function useDeleteCustomAiProvider() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (providerId: string) => deleteCustomAiProvider(providerId),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: AI_PROVIDERS_KEY });
+    },
+    onError: (error) => {
+      logger.error(`Failed to delete custom AI provider: ${error}`);
+      toast({
+        title: "Failed to delete provider",
+        description: error instanceof Error ? error.message : String(error),
+        variant: "destructive",
+      });
+    },
+  });
+}
+
+/**
+ * Hook to fetch AI token usage and estimated cost.
+ * Defaults to month-to-date when no range is given.
+ */
+export function useAiUsageReport(from?: string, to?: string) {
+  return useQuery({
+    queryKey: QueryKeys.aiUsage(from, to),
+    queryFn: () => getAiUsageReport(from, to),
+  });
+}
+
+/**
+ * Hook to set or clear the monthly AI spend budget.
+ */
+export function useSetAiUsageBudget() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (request: SetUsageBudgetRequest) => setAiUsageBudget(request),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: AI_PROVIDERS_KEY });
+      queryClient.invalidateQueries({ queryKey: [QueryKeys.AI_USAGE] });
+    },
+    onError: (error) => {
+      logger.error(`Failed to save AI usage budget: ${error}`);
+      toast({
+        title: "Failed to save budget",
+        description: error instanceof Error ? error.message : String(error),
+        variant: "destructive",
+      });
+    },
+  });
+}
+
 /**
  * Hook to manage API keys for AI providers.
  * Uses the ai_{providerId} key format in the secret store.
@@ -96,7 +96,7 @@ export function useChatModel(): ChatModelState {
     }
 
     // Fall back to first ready provider.
-    const firstReadyProvider = enabledProviders.find((p) => p.type === "local" || p.hasApiKey);
+    const firstReadyProvider = enabledProviders.find((p) => p.type !== "api" || p.hasApiKey);
     const firstProvider = firstReadyProvider ?? enabledProviders[0];
     if (firstProvider) {
       return {
@@ -24,6 +24,7 @@ import { generateId } from "@/lib/id";
 import { AI_THREADS_KEY } from "./use-threads";
 import { deleteAiThread, getAiThreadMessages, updateAiThread } from "@/adapters";
 import { useHapticFeedback } from "@/hooks";
+import { toast } from "@wealthfolio/ui/components/ui/use-toast";
 
 function deriveInitialThreadTitle(firstUserMessage: string): string {
   const normalized = firstUserMessage.replace(/\s+/g, " ").trim();
@@ -702,6 +703,12 @@ export function useChatRuntime(config?: ChatModelConfig) {
               break;
             }
 
+            case "usageBudgetWarning":
+              toast.warning("AI budget nearly used", {
+                description: `Estimated spend this month is $${event.spentUsd.toFixed(2)} of your $${event.limitUsd.toFixed(2)} limit.`,
+              });
+              break;
+
             case "textDelta":
               // Trigger haptic pattern when streaming starts (first text delta)
               if (!hapticTriggeredRef.current) {
@@ -775,6 +782,7 @@ export function useChatRuntime(config?: ChatModelConfig) {
               // Just invalidate threads to refresh from DB (updates title if generated)
               setTimeout(() => {
                 queryClient.invalidateQueries({ queryKey: AI_THREADS_KEY });
+                queryClient.invalidateQueries({ queryKey: [QueryKeys.AI_USAGE] });
               }, 1000);
               break;
 
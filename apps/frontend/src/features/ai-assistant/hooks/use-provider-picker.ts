@@ -75,7 +75,7 @@ export function useProviderPicker(): UseProviderPickerResult {
     }
 
     // Fall back to first ready provider (has key or local), otherwise first enabled provider
-    const firstReady = activeProviders.find((p) => p.type === "local" || p.hasApiKey);
+    const firstReady = activeProviders.find((p) => p.type !== "api" || p.hasApiKey);
     return firstReady?.id ?? activeProviders[0]?.id;
   }, [activeProviders, settings?.defaultProvider, storedSelection]);
 
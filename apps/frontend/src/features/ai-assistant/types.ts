@@ -12,6 +12,10 @@ export type {
   ModelCapabilityOverrideUpdate,
   FetchedModel,
   ListModelsResponse,
+  RedactionSettings,
+  AmountRedaction,
+  CustomProviderConfig,
+  SaveCustomProviderRequest,
 } from "@/lib/types";
 
 // ============================================================================
@@ -96,6 +100,18 @@ export interface ChatMessage {
   /** Structured content with parts array */
   content: ChatMessageContent;
   createdAt: string;
+  /** Token usage reported by the provider (assistant messages only) */
+  usage?: MessageUsage;
+}
+
+/**
+ * Token usage persisted on an assistant message.
+ */
+export interface MessageUsage {
+  providerId: string;
+  modelId: string;
+  promptTokens: number;
+  completionTokens: number;
 }
 
 export interface ToolCall {
@@ -294,6 +310,17 @@ interface ThreadTitleUpdatedEvent extends AiStreamEventBase {
   title: string;
 }
 
+/**
+ * Usage budget warning - month-to-date spend is near or over the monthly limit.
+ */
+interface UsageBudgetWarningEvent extends AiStreamEventBase {
+  type: "usageBudgetWarning";
+  /** Estimated spend this month (USD) */
+  spentUsd: number;
+  /** Configured monthly limit (USD) */
+  limitUsd: number;
+}
+
 /**
  * Token usage statistics.
  */
@@ -315,7 +342,8 @@ export type AiStreamEvent =
   | ToolResultEvent
   | ErrorEvent
   | DoneEvent
-  | ThreadTitleUpdatedEvent;
+  | ThreadTitleUpdatedEvent
+  | UsageBudgetWarningEvent;
 
 // ============================================================================
 // UI State Types
@@ -516,6 +544,10 @@ export interface RecordActivitiesDraftRow {
   errors: string[];
   resolvedAsset?: RecordActivitiesResolvedAsset;
   availableSubtypes: RecordActivitiesSubtypeOption[];
+  /** Non-blocking review notes (e.g. low extraction confidence). */
+  warnings?: string[];
+  /** Per-field confidence (0-1) for drafts extracted from documents. */
+  confidence?: Record<string, number>;
 }
 
 export interface RecordActivitiesValidationSummary {
@@ -542,6 +574,24 @@ export interface RecordActivitiesOutput {
   submittedAt?: string;
 }
 
+// ============================================================================
+// Extract Statement Tool Types
+// ============================================================================
+
+export interface ExtractStatementArgs {
+  attachment?: string;
+  account?: string;
+}
+
+/**
+ * Output from the extract_statement tool: a record_activities batch plus the
+ * attachments it was extracted from.
+ */
+export interface ExtractStatementOutput extends RecordActivitiesOutput {
+  sources?: string[];
+  skipped?: string[];
+}
+
 // ============================================================================
 // Import CSV Tool Types (uses same format as manual import)
 // ============================================================================
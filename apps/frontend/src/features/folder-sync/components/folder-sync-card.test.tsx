@@ -6,6 +6,8 @@ const {
   useFolderSyncMock,
   initializeMock,
   joinMock,
+  unlockMock,
+  changePassphraseMock,
   retryNowMock,
   disableMock,
   refreshMock,
@@ -14,6 +16,8 @@ const {
   useFolderSyncMock: vi.fn(),
   initializeMock: vi.fn(),
   joinMock: vi.fn(),
+  unlockMock: vi.fn(),
+  changePassphraseMock: vi.fn(),
   retryNowMock: vi.fn(),
   disableMock: vi.fn(),
   refreshMock: vi.fn(),
@@ -30,6 +34,8 @@ describe("folder sync card", () => {
   beforeEach(() => {
     initializeMock.mockResolvedValue({ status: "initialized" });
     joinMock.mockResolvedValue({ status: "joined" });
+    unlockMock.mockResolvedValue({ status: "unlocked" });
+    changePassphraseMock.mockResolvedValue({ status: "passphrase_changed" });
     retryNowMock.mockResolvedValue({ status: "ok" });
     disableMock.mockResolvedValue({ status: "disabled" });
     refreshMock.mockResolvedValue(undefined);
@@ -95,9 +101,12 @@ describe("folder sync card", () => {
           createdAt: "2026-03-07T18:08:00Z",
         },
       ],
+      isEncrypted: true,
       lastError: null,
       initialize: initializeMock,
       join: joinMock,
+      unlock: unlockMock,
+      changePassphrase: changePassphraseMock,
       retryNow: retryNowMock,
       disable: disableMock,
       refresh: refreshMock,
@@ -135,6 +144,8 @@ describe("folder sync card", () => {
       lastError: null,
       initialize: initializeMock,
       join: joinMock,
+      unlock: unlockMock,
+      changePassphrase: changePassphraseMock,
       retryNow: retryNowMock,
       disable: disableMock,
       refresh: refreshMock,
@@ -144,13 +155,56 @@ describe("folder sync card", () => {
     const user = userEvent.setup();
     render(<FolderSyncCard />);
 
+    expect(screen.getByRole("button", { name: "Initialize Sync" })).toBeDisabled();
+
+    await user.type(screen.getByLabelText("Passphrase"), "correct horse battery");
     await user.click(screen.getByRole("button", { name: "Initialize Sync" }));
     await user.click(screen.getByRole("button", { name: "Join Existing Sync" }));
 
-    expect(initializeMock).toHaveBeenCalledTimes(1);
+    expect(initializeMock).toHaveBeenCalledWith("correct horse battery", undefined, undefined);
     expect(joinMock).toHaveBeenCalledTimes(1);
   });
 
+  it("passes a remote folder and its credentials to setup", async () => {
+    const base = useFolderSyncMock();
+    useFolderSyncMock.mockReturnValue({ ...base, config: null });
+
+    const user = userEvent.setup();
+    render(<FolderSyncCard />);
+
+    await user.type(screen.getByLabelText("Passphrase"), "correct horse battery");
+    await user.type(
+      screen.getByLabelText("Remote folder (optional)"),
+      "webdav+https://cloud.example.com/dav/Panorama",
+    );
+    await user.type(screen.getByLabelText("Username or access key"), "me");
+    await user.type(screen.getByLabelText("Password or secret key"), "app-password");
+    await user.click(screen.getByRole("button", { name: "Initialize Sync" }));
+
+    expect(initializeMock).toHaveBeenCalledWith(
+      "correct horse battery",
+      "webdav+https://cloud.example.com/dav/Panorama",
+      { username: "me", secret: "app-password" },
+    );
+  });
+
+  it("asks for the passphrase when the folder key changed", async () => {
+    const base = useFolderSyncMock();
+    useFolderSyncMock.mockReturnValue({
+      ...base,
+      status: { ...base.status, syncState: "passphrase_required" },
+    });
+
+    const user = userEvent.setup();
+    render(<FolderSyncCard />);
+
+    expect(screen.getByText("Passphrase required")).toBeInTheDocument();
+    await user.type(screen.getByLabelText("Passphrase"), "another horse battery");
+    await user.click(screen.getByRole("button", { name: "Unlock" }));
+
+    expect(unlockMock).toHaveBeenCalledWith("another horse battery");
+  });
+
   it("renders status summary, timestamps, and history", () => {
     render(<FolderSyncCard />);
 
@@ -163,6 +217,7 @@ describe("folder sync card", () => {
     expect(screen.getByText("Status")).toBeInTheDocument();
     expect(screen.getByText("Recent Activity")).toBeInTheDocument();
     expect(screen.getByText("Up to date")).toBeInTheDocument();
+    expect(screen.getByText("Encrypted")).toBeInTheDocument();
     expect(screen.getByText("/tmp/PanoramaSync")).toBeInTheDocument();
     expect(screen.getByText("Last successful sync")).toBeInTheDocument();
     expect(screen.getByText("2026-03-07T18:10:00Z")).toBeInTheDocument();
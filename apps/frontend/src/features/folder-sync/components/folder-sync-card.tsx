@@ -10,10 +10,13 @@ import {
   CardHeader,
   CardTitle,
 } from "@wealthfolio/ui/components/ui/card";
+import { Input } from "@wealthfolio/ui/components/ui/input";
+import { Label } from "@wealthfolio/ui/components/ui/label";
 
 import { useFolderSync } from "../hooks/use-folder-sync";
 
 const folderSyncGuideUrl = "https://panorama.gallantguo.com/docs/guides/sync";
+const minPassphraseLength = 8;
 
 const statusLabels: Record<string, string> = {
   idle: "Idle",
@@ -23,6 +26,7 @@ const statusLabels: Record<string, string> = {
   up_to_date: "Up to date",
   needs_attention: "Needs attention",
   folder_unavailable: "Folder unavailable",
+  passphrase_required: "Passphrase required",
   unsupported: "Unsupported",
 };
 
@@ -35,6 +39,7 @@ const statusVariants: Record<string, "destructive" | "outline" | "secondary" | "
     up_to_date: "success",
     needs_attention: "warning",
     folder_unavailable: "destructive",
+    passphrase_required: "warning",
     unsupported: "outline",
   };
 
@@ -72,10 +77,28 @@ function TimestampRow({ label, value }: { label: string; value: string | null |
 }
 
 export function FolderSyncCard() {
-  const { isLoading, config, status, history, lastError, initialize, join, retryNow, disable } =
-    useFolderSync();
+  const {
+    isLoading,
+    config,
+    isEncrypted,
+    status,
+    history,
+    lastError,
+    initialize,
+    join,
+    unlock,
+    changePassphrase,
+    retryNow,
+    disable,
+  } = useFolderSync();
   const [pendingAction, setPendingAction] = useState<string | null>(null);
   const [actionError, setActionError] = useState<string | null>(null);
+  const [passphrase, setPassphrase] = useState("");
+  const [newPassphrase, setNewPassphrase] = useState("");
+  const [showPassphraseForm, setShowPassphraseForm] = useState(false);
+  const [remoteUrl, setRemoteUrl] = useState("");
+  const [remoteUsername, setRemoteUsername] = useState("");
+  const [remoteSecret, setRemoteSecret] = useState("");
 
   const runAction = async (actionName: string, action: () => Promise<unknown>) => {
     setPendingAction(actionName);
@@ -83,6 +106,9 @@ export function FolderSyncCard() {
 
     try {
       await action();
+      setPassphrase("");
+      setNewPassphrase("");
+      setShowPassphraseForm(false);
     } catch (error) {
       setActionError(error instanceof Error ? error.message : "Folder sync action failed");
     } finally {
@@ -92,8 +118,14 @@ export function FolderSyncCard() {
 
   const isBusy = isLoading || pendingAction !== null;
   const syncState = status?.syncState ?? (isLoading ? "checking" : "idle");
+  const needsPassphrase = syncState === "passphrase_required";
+  const isPassphraseValid = passphrase.length >= minPassphraseLength;
+  const isNewPassphraseValid = newPassphrase.length >= minPassphraseLength;
   const visibleHistory = history.slice(0, 5);
   const errorMessage = actionError ?? lastError;
+  const remoteFolder = remoteUrl.trim() || undefined;
+  const remoteCredentials =
+    remoteFolder && remoteUsername ? { username: remoteUsername, secret: remoteSecret } : undefined;
 
   return (
     <div className="space-y-6">
@@ -112,7 +144,7 @@ export function FolderSyncCard() {
           </a>
         </div>
         <p className="text-muted-foreground text-sm">
-          Keep shared Panorama data aligned across devices through one Syncthing folder.
+          Keep shared Panorama data aligned across devices through one shared folder.
         </p>
       </div>
 
@@ -127,7 +159,7 @@ export function FolderSyncCard() {
           <CardHeader>
             <CardTitle className="text-lg">Setup</CardTitle>
             <CardDescription>
-              Choose or review the shared Syncthing folder used by this device.
+              Choose a synced local folder, a WebDAV share or an S3 bucket for this device.
             </CardDescription>
           </CardHeader>
           <CardContent className="space-y-5">
@@ -136,40 +168,194 @@ export function FolderSyncCard() {
               <p className="bg-muted rounded-md px-3 py-2 font-mono text-xs">
                 {config?.sharedFolderPath ?? "Not configured"}
               </p>
+              {config && (
+                <Badge variant={isEncrypted ? "success" : "outline"}>
+                  {isEncrypted ? "Encrypted" : "Not encrypted"}
+                </Badge>
+              )}
             </div>
 
             {!config ? (
-              <div className="flex flex-wrap items-center gap-2">
-                <Button
-                  disabled={isBusy}
-                  onClick={() => runAction("initialize", () => initialize())}
-                >
-                  Initialize Sync
-                </Button>
-                <Button
-                  variant="outline"
-                  disabled={isBusy}
-                  onClick={() => runAction("join", () => join())}
-                >
-                  Join Existing Sync
-                </Button>
+              <div className="space-y-3">
+                <div className="space-y-2">
+                  <Label htmlFor="folder-sync-passphrase">Passphrase</Label>
+                  <Input
+                    id="folder-sync-passphrase"
+                    type="password"
+                    autoComplete="new-password"
+                    value={passphrase}
+                    disabled={isBusy}
+                    onChange={(event) => setPassphrase(event.target.value)}
+                  />
+                  <p className="text-muted-foreground text-xs">
+                    Encrypts everything written to the shared folder. Use the same passphrase on
+                    every device; it cannot be recovered.
+                  </p>
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="folder-sync-remote-url">Remote folder (optional)</Label>
+                  <Input
+                    id="folder-sync-remote-url"
+                    placeholder="webdav+https://cloud.example.com/remote.php/dav/files/me/Panorama"
+                    value={remoteUrl}
+                    disabled={isBusy}
+                    onChange={(event) => setRemoteUrl(event.target.value)}
+                  />
+                  <p className="text-muted-foreground text-xs">
+                    Leave empty to pick a local folder. Use a <code>webdav+https://</code> URL for
+                    Nextcloud or <code>s3+https://endpoint/bucket/prefix</code> for S3-compatible
+                    storage.
+                  </p>
+                </div>
+                {remoteFolder && (
+                  <div className="grid gap-3 sm:grid-cols-2">
+                    <div className="space-y-2">
+                      <Label htmlFor="folder-sync-remote-username">Username or access key</Label>
+                      <Input
+                        id="folder-sync-remote-username"
+                        autoComplete="username"
+                        value={remoteUsername}
+                        disabled={isBusy}
+                        onChange={(event) => setRemoteUsername(event.target.value)}
+                      />
+                    </div>
+                    <div className="space-y-2">
+                      <Label htmlFor="folder-sync-remote-secret">Password or secret key</Label>
+                      <Input
+                        id="folder-sync-remote-secret"
+                        type="password"
+                        autoComplete="current-password"
+                        value={remoteSecret}
+                        disabled={isBusy}
+                        onChange={(event) => setRemoteSecret(event.target.value)}
+                      />
+                    </div>
+                  </div>
+                )}
+                <div className="flex flex-wrap items-center gap-2">
+                  <Button
+                    disabled={isBusy || !isPassphraseValid}
+                    onClick={() =>
+                      runAction("initialize", () =>
+                        initialize(passphrase, remoteFolder, remoteCredentials),
+                      )
+                    }
+                  >
+                    Initialize Sync
+                  </Button>
+                  <Button
+                    variant="outline"
+                    disabled={isBusy}
+                    onClick={() =>
+                      runAction("join", () =>
+                        join(passphrase || undefined, remoteFolder, remoteCredentials),
+                      )
+                    }
+                  >
+                    Join Existing Sync
+                  </Button>
+                </div>
+              </div>
+            ) : needsPassphrase ? (
+              <div className="space-y-3">
+                <div className="space-y-2">
+                  <Label htmlFor="folder-sync-unlock-passphrase">Passphrase</Label>
+                  <Input
+                    id="folder-sync-unlock-passphrase"
+                    type="password"
+                    autoComplete="current-password"
+                    value={passphrase}
+                    disabled={isBusy}
+                    onChange={(event) => setPassphrase(event.target.value)}
+                  />
+                  <p className="text-muted-foreground text-xs">
+                    The shared folder passphrase was set or changed on another device.
+                  </p>
+                </div>
+                <div className="flex flex-wrap items-center gap-2">
+                  <Button
+                    disabled={isBusy || passphrase.length === 0}
+                    onClick={() => runAction("unlock", () => unlock(passphrase))}
+                  >
+                    Unlock
+                  </Button>
+                  <Button
+                    variant="outline"
+                    disabled={isBusy}
+                    onClick={() => runAction("disable", disable)}
+                  >
+                    Disable Sync
+                  </Button>
+                </div>
               </div>
             ) : (
-              <div className="flex flex-wrap items-center gap-2">
-                <Button
-                  variant="outline"
-                  disabled={isBusy}
-                  onClick={() => runAction("retry", retryNow)}
-                >
-                  Check now
-                </Button>
-                <Button
-                  variant="outline"
-                  disabled={isBusy}
-                  onClick={() => runAction("disable", disable)}
-                >
-                  Disable Sync
-                </Button>
+              <div className="space-y-3">
+                <div className="flex flex-wrap items-center gap-2">
+                  <Button
+                    variant="outline"
+                    disabled={isBusy}
+                    onClick={() => runAction("retry", retryNow)}
+                  >
+                    Check now
+                  </Button>
+                  <Button
+                    variant="outline"
+                    disabled={isBusy}
+                    onClick={() => setShowPassphraseForm((visible) => !visible)}
+                  >
+                    {isEncrypted ? "Change passphrase" : "Encrypt folder"}
+                  </Button>
+                  <Button
+                    variant="outline"
+                    disabled={isBusy}
+                    onClick={() => runAction("disable", disable)}
+                  >
+                    Disable Sync
+                  </Button>
+                </div>
+
+                {showPassphraseForm && (
+                  <div className="space-y-3 rounded-md border p-3">
+                    {isEncrypted && (
+                      <div className="space-y-2">
+                        <Label htmlFor="folder-sync-current-passphrase">Current passphrase</Label>
+                        <Input
+                          id="folder-sync-current-passphrase"
+                          type="password"
+                          autoComplete="current-password"
+                          value={passphrase}
+                          disabled={isBusy}
+                          onChange={(event) => setPassphrase(event.target.value)}
+                        />
+                      </div>
+                    )}
+                    <div className="space-y-2">
+                      <Label htmlFor="folder-sync-new-passphrase">New passphrase</Label>
+                      <Input
+                        id="folder-sync-new-passphrase"
+                        type="password"
+                        autoComplete="new-password"
+                        value={newPassphrase}
+                        disabled={isBusy}
+                        onChange={(event) => setNewPassphrase(event.target.value)}
+                      />
+                      <p className="text-muted-foreground text-xs">
+                        Re-encrypts the shared folder. Other devices will ask for the new
+                        passphrase before syncing again.
+                      </p>
+                    </div>
+                    <Button
+                      disabled={isBusy || !isNewPassphraseValid || (isEncrypted && !passphrase)}
+                      onClick={() =>
+                        runAction("change-passphrase", () =>
+                          changePassphrase(isEncrypted ? passphrase : null, newPassphrase),
+                        )
+                      }
+                    >
+                      Save passphrase
+                    </Button>
+                  </div>
+                )}
               </div>
             )}
           </CardContent>
@@ -0,0 +1,74 @@
+import userEvent from "@testing-library/user-event";
+import { render, screen } from "@testing-library/react";
+import { beforeEach, describe, expect, it, vi } from "vitest";
+
+const { useSyncConflictsMock, mutateMock } = vi.hoisted(() => ({
+  useSyncConflictsMock: vi.fn(),
+  mutateMock: vi.fn(),
+}));
+
+vi.mock("../hooks/use-sync-conflicts", () => ({
+  useSyncConflicts: useSyncConflictsMock,
+}));
+
+import { SyncConflictsCard } from "./sync-conflicts-card";
+
+const conflict = {
+  id: "conflict-1",
+  entity: "activity",
+  entityId: "act-1",
+  field: "notes",
+  localValue: "desktop note",
+  localUpdatedAt: "2026-03-12T10:00:00Z",
+  remoteValue: "phone note",
+  remoteUpdatedAt: "2026-03-12T11:00:00Z",
+  remoteEventId: "evt-1",
+  resolution: null,
+  resolvedValue: null,
+  createdAt: "2026-03-12T11:00:01Z",
+  resolvedAt: null,
+};
+
+describe("sync conflicts card", () => {
+  beforeEach(() => {
+    mutateMock.mockReset();
+    useSyncConflictsMock.mockReturnValue({
+      conflicts: [conflict],
+      isLoading: false,
+      resolve: { mutate: mutateMock, isPending: false },
+    });
+  });
+
+  it("renders nothing without open conflicts", () => {
+    useSyncConflictsMock.mockReturnValue({
+      conflicts: [],
+      isLoading: false,
+      resolve: { mutate: mutateMock, isPending: false },
+    });
+    const { container } = render(<SyncConflictsCard />);
+    expect(container).toBeEmptyDOMElement();
+  });
+
+  it("resolves with the other device or a manual value", async () => {
+    const user = userEvent.setup();
+    render(<SyncConflictsCard />);
+
+    expect(screen.getByText("desktop note")).toBeInTheDocument();
+    expect(screen.getByText("phone note")).toBeInTheDocument();
+
+    await user.click(screen.getByRole("button", { name: "Use other device" }));
+    expect(mutateMock).toHaveBeenCalledWith({
+      conflictId: "conflict-1",
+      resolution: "remote",
+      value: undefined,
+    });
+
+    await user.type(screen.getByLabelText("Manual value for notes"), "merged note");
+    await user.click(screen.getByRole("button", { name: "Save" }));
+    expect(mutateMock).toHaveBeenLastCalledWith({
+      conflictId: "conflict-1",
+      resolution: "manual",
+      value: "merged note",
+    });
+  });
+});
@@ -0,0 +1,124 @@
+import { useState } from "react";
+
+import { Badge } from "@wealthfolio/ui/components/ui/badge";
+import { Button } from "@wealthfolio/ui/components/ui/button";
+import {
+  Card,
+  CardContent,
+  CardDescription,
+  CardHeader,
+  CardTitle,
+} from "@wealthfolio/ui/components/ui/card";
+import { Input } from "@wealthfolio/ui/components/ui/input";
+
+import type { SyncFieldConflict } from "@/lib/types";
+import { useSyncConflicts } from "../hooks/use-sync-conflicts";
+
+const formatValue = (value: unknown): string => {
+  if (value === null || value === undefined) {
+    return "(empty)";
+  }
+  return typeof value === "string" ? value : JSON.stringify(value);
+};
+
+// Manual values are typed as text; numbers and booleans keep their JSON type.
+const parseManualValue = (input: string): unknown => {
+  try {
+    const parsed: unknown = JSON.parse(input);
+    return typeof parsed === "object" && parsed !== null ? input : parsed;
+  } catch {
+    return input;
+  }
+};
+
+function ConflictRow({
+  conflict,
+  isPending,
+  onResolve,
+}: {
+  conflict: SyncFieldConflict;
+  isPending: boolean;
+  onResolve: (resolution: "local" | "remote" | "manual", value?: unknown) => void;
+}) {
+  const [manualValue, setManualValue] = useState("");
+
+  return (
+    <div className="space-y-3 rounded-md border p-3">
+      <div className="flex flex-wrap items-center gap-2 text-sm">
+        <Badge variant="outline">{conflict.entity}</Badge>
+        <span className="font-medium">{conflict.field}</span>
+        <span className="text-muted-foreground truncate text-xs">{conflict.entityId}</span>
+      </div>
+      <div className="grid gap-2 text-sm sm:grid-cols-2">
+        <div>
+          <p className="text-muted-foreground text-xs">This device</p>
+          <p className="break-words">{formatValue(conflict.localValue)}</p>
+        </div>
+        <div>
+          <p className="text-muted-foreground text-xs">Other device</p>
+          <p className="break-words">{formatValue(conflict.remoteValue)}</p>
+        </div>
+      </div>
+      <div className="flex flex-wrap items-center gap-2">
+        <Button size="sm" variant="outline" disabled={isPending} onClick={() => onResolve("local")}>
+          Keep this device
+        </Button>
+        <Button
+          size="sm"
+          variant="outline"
+          disabled={isPending}
+          onClick={() => onResolve("remote")}
+        >
+          Use other device
+        </Button>
+        <Input
+          aria-label={`Manual value for ${conflict.field}`}
+          className="h-8 max-w-48"
+          placeholder="Other value"
+          value={manualValue}
+          onChange={(event) => setManualValue(event.target.value)}
+        />
+        <Button
+          size="sm"
+          disabled={isPending || manualValue.trim() === ""}
+          onClick={() => onResolve("manual", parseManualValue(manualValue))}
+        >
+          Save
+        </Button>
+      </div>
+    </div>
+  );
+}
+
+/** Lists fields edited on two devices and lets the user pick the value to keep. */
+export function SyncConflictsCard() {
+  const { conflicts, resolve } = useSyncConflicts();
+
+  if (conflicts.length === 0) {
+    return null;
+  }
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle>Sync conflicts</CardTitle>
+        <CardDescription>
+          These fields were changed on this device and on another device. The value on this
+          device is kept until you choose.
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-3">
+        {conflicts.map((conflict) => (
+          <ConflictRow
+            key={conflict.id}
+            conflict={conflict}
+            isPending={resolve.isPending}
+            onResolve={(resolution, value) =>
+              resolve.mutate({ conflictId: conflict.id, resolution, value })
+            }
+          />
+        ))}
+      </CardContent>
+    </Card>
+  );
+}
@@ -8,6 +8,8 @@ const {
   getFolderSyncStateMock,
   initializeFolderSyncMock,
   joinFolderSyncMock,
+  unlockFolderSyncMock,
+  changeFolderSyncPassphraseMock,
   retryFolderSyncNowMock,
   disableFolderSyncMock,
   openFolderDialogMock,
@@ -16,6 +18,8 @@ const {
   getFolderSyncStateMock: vi.fn(),
   initializeFolderSyncMock: vi.fn(),
   joinFolderSyncMock: vi.fn(),
+  unlockFolderSyncMock: vi.fn(),
+  changeFolderSyncPassphraseMock: vi.fn(),
   retryFolderSyncNowMock: vi.fn(),
   disableFolderSyncMock: vi.fn(),
   openFolderDialogMock: vi.fn(),
@@ -26,6 +30,8 @@ vi.mock("@/adapters", () => ({
   getFolderSyncState: getFolderSyncStateMock,
   initializeFolderSync: initializeFolderSyncMock,
   joinFolderSync: joinFolderSyncMock,
+  unlockFolderSync: unlockFolderSyncMock,
+  changeFolderSyncPassphrase: changeFolderSyncPassphraseMock,
   retryFolderSyncNow: retryFolderSyncNowMock,
   disableFolderSync: disableFolderSyncMock,
   openFolderDialog: openFolderDialogMock,
@@ -61,6 +67,7 @@ describe("useFolderSync", () => {
         createdAt: "2026-03-07T18:00:00Z",
         updatedAt: "2026-03-07T18:00:00Z",
       },
+      isEncrypted: true,
       status: {
         syncState: "up_to_date",
         lastCheckedAt: "2026-03-07T18:10:00Z",
@@ -84,6 +91,8 @@ describe("useFolderSync", () => {
     });
     initializeFolderSyncMock.mockResolvedValue({ status: "initialized" });
     joinFolderSyncMock.mockResolvedValue({ status: "joined" });
+    unlockFolderSyncMock.mockResolvedValue({ status: "unlocked" });
+    changeFolderSyncPassphraseMock.mockResolvedValue({ status: "passphrase_changed" });
     retryFolderSyncNowMock.mockResolvedValue({ status: "ok" });
     disableFolderSyncMock.mockResolvedValue({ status: "disabled" });
     openFolderDialogMock.mockResolvedValue("/tmp/PanoramaSync");
@@ -118,6 +127,29 @@ describe("useFolderSync", () => {
     expect(retryFolderSyncNowMock).toHaveBeenCalledTimes(1);
   });
 
+  it("passes passphrases through to the adapters", async () => {
+    const { Wrapper } = createWrapper();
+    const { result } = renderHook(() => useFolderSync(), { wrapper: Wrapper });
+
+    await waitFor(() => expect(result.current.isLoading).toBe(false));
+    expect(result.current.isEncrypted).toBe(true);
+
+    await result.current.initialize("correct horse battery");
+    await result.current.unlock("correct horse battery");
+    await result.current.changePassphrase("correct horse battery", "another horse battery");
+
+    expect(initializeFolderSyncMock).toHaveBeenCalledWith(
+      "/tmp/PanoramaSync",
+      "correct horse battery",
+      undefined,
+    );
+    expect(unlockFolderSyncMock).toHaveBeenCalledWith("correct horse battery");
+    expect(changeFolderSyncPassphraseMock).toHaveBeenCalledWith(
+      "correct horse battery",
+      "another horse battery",
+    );
+  });
+
   it("invalidates other app queries after applying folder sync changes", async () => {
     const { Wrapper, queryClient } = createWrapper();
     queryClient.setQueryData([QueryKeys.ACCOUNTS], [{ id: "account-1" }]);
@@ -133,6 +165,7 @@ describe("useFolderSync", () => {
   it("surfaces attention states from backend status", async () => {
     getFolderSyncStateMock.mockResolvedValueOnce({
       config: null,
+      isEncrypted: false,
       status: {
         syncState: "folder_unavailable",
         lastCheckedAt: null,
@@ -1,16 +1,22 @@
 import { useQuery, useQueryClient } from "@tanstack/react-query";
 
 import {
+  changeFolderSyncPassphrase,
   disableFolderSync,
   getFolderSyncState,
   initializeFolderSync,
   joinFolderSync,
   openFolderDialog,
   retryFolderSyncNow,
+  unlockFolderSync,
 } from "@/adapters";
 import { useAuth } from "@/context/auth-context";
 import { QueryKeys } from "@/lib/query-keys";
-import type { FolderSyncCommandResult, FolderSyncState } from "@/lib/types";
+import type {
+  FolderSyncCommandResult,
+  FolderSyncRemoteCredentials,
+  FolderSyncState,
+} from "@/lib/types";
 
 export function useFolderSync() {
   const queryClient = useQueryClient();
@@ -40,20 +46,39 @@ export function useFolderSync() {
     return openFolderDialog();
   };
 
-  const initialize = async (sharedFolderPath?: string): Promise<FolderSyncCommandResult | null> => {
+  const initialize = async (
+    passphrase: string,
+    sharedFolderPath?: string,
+    remoteCredentials?: FolderSyncRemoteCredentials,
+  ): Promise<FolderSyncCommandResult | null> => {
     const folderPath = sharedFolderPath ?? (await selectSharedFolder());
     if (!folderPath) {
       return null;
     }
-    return withRefresh(() => initializeFolderSync(folderPath));
+    return withRefresh(() => initializeFolderSync(folderPath, passphrase, remoteCredentials));
   };
 
-  const join = async (sharedFolderPath?: string): Promise<FolderSyncCommandResult | null> => {
+  const join = async (
+    passphrase?: string,
+    sharedFolderPath?: string,
+    remoteCredentials?: FolderSyncRemoteCredentials,
+  ): Promise<FolderSyncCommandResult | null> => {
     const folderPath = sharedFolderPath ?? (await selectSharedFolder());
     if (!folderPath) {
       return null;
     }
-    return withRefresh(() => joinFolderSync(folderPath));
+    return withRefresh(() => joinFolderSync(folderPath, passphrase, remoteCredentials));
+  };
+
+  const unlock = async (passphrase: string): Promise<FolderSyncCommandResult> => {
+    return withRefresh(() => unlockFolderSync(passphrase));
+  };
+
+  const changePassphrase = async (
+    currentPassphrase: string | null,
+    newPassphrase: string,
+  ): Promise<FolderSyncCommandResult> => {
+    return withRefresh(() => changeFolderSyncPassphrase(currentPassphrase, newPassphrase));
   };
 
   const retryNow = async (): Promise<FolderSyncCommandResult> => {
@@ -71,6 +96,7 @@ export function useFolderSync() {
     isFetching: query.isFetching,
     state: query.data ?? null,
     config: query.data?.config ?? null,
+    isEncrypted: query.data?.isEncrypted ?? false,
     status: query.data?.status ?? null,
     history: query.data?.history ?? [],
     lastError: query.data?.status.lastError ?? null,
@@ -80,6 +106,8 @@ export function useFolderSync() {
     selectSharedFolder,
     initialize,
     join,
+    unlock,
+    changePassphrase,
     retryNow,
     disable,
   };
@@ -0,0 +1,42 @@
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import { toast } from "@wealthfolio/ui/components/ui/use-toast";
+
+import { listSyncConflicts, resolveSyncConflict } from "@/adapters";
+import { QueryKeys } from "@/lib/query-keys";
+import type { ResolveSyncConflictRequest, SyncFieldConflict } from "@/lib/types";
+
+/** Open field conflicts from device sync and the action that settles them. */
+export function useSyncConflicts() {
+  const queryClient = useQueryClient();
+
+  const query = useQuery<SyncFieldConflict[], Error>({
+    queryKey: [QueryKeys.SYNC_CONFLICTS],
+    queryFn: () => listSyncConflicts(),
+  });
+
+  const resolve = useMutation({
+    mutationFn: (request: ResolveSyncConflictRequest) => resolveSyncConflict(request),
+    onSuccess: () => {
+      // The chosen value may belong to any entity, so refresh everything.
+      queryClient.invalidateQueries();
+      toast({
+        title: "Conflict resolved",
+        variant: "success",
+        duration: 1500,
+      });
+    },
+    onError: (error) => {
+      toast({
+        title: "Failed to resolve conflict",
+        description: error instanceof Error ? error.message : String(error),
+        variant: "destructive",
+      });
+    },
+  });
+
+  return {
+    conflicts: query.data ?? [],
+    isLoading: query.isLoading,
+    resolve,
+  };
+}
@@ -5,6 +5,9 @@ export const WEALTHFOLIO_CONNECT_PORTAL_URL = "https://connect.wealthfolio.app";
 
 export const PORTFOLIO_ACCOUNT_ID = "TOTAL";
 
+// Source system for activities confirmed from AI assistant drafts
+export const AI_ASSISTANT_SOURCE_SYSTEM = "AI_ASSISTANT";
+
 export const HoldingType = {
   CASH: "cash",
   SECURITY: "security",
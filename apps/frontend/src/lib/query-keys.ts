@@ -24,6 +24,7 @@ export const QueryKeys = {
   // Settings related keys
   SETTINGS: "settings",
   FOLDER_SYNC: "folderSync",
+  SYNC_CONFLICTS: "syncConflicts",
   EXCHANGE_RATES: "exchangeRates",
 
   // New keys for exchange rates
@@ -67,6 +68,8 @@ export const QueryKeys = {
   AI_THREAD_MESSAGES: "aiThreadMessages",
   aiThread: (threadId: string) => [QueryKeys.AI_THREAD, threadId],
   aiThreadMessages: (threadId: string) => [QueryKeys.AI_THREAD_MESSAGES, threadId],
+  AI_USAGE: "aiUsage",
+  aiUsage: (from?: string, to?: string) => [QueryKeys.AI_USAGE, from, to],
 
   transactions: "transactions",
   latestValuations: "latest-valuations",
@@ -236,6 +236,8 @@ export interface ActivityCreate {
   activityDate: string | Date;
   /** Optional grouping key (links paired transfer legs). */
   sourceGroupId?: string;
+  /** Origin of the activity (e.g. AI_ASSISTANT), recorded in the change history. */
+  sourceSystem?: string;
   symbol?: SymbolInput;
   quantity?: string | number | null;
   unitPrice?: string | number | null;
@@ -436,12 +438,204 @@ export interface ImportActivitiesSummary {
   duplicates: number;
   /** Number of new assets created during import */
   assetsCreated: number;
+  /** Number of activities staged for review instead of imported */
+  staged?: number;
   /** Whether the import was successful (no validation errors) */
   success: boolean;
 }
 
 export type ValidationResult = { status: "success" } | { status: "error"; errors: string[] };
 
+export type DuplicateDecision = "MERGE" | "KEEP_BOTH";
+
+/**
+ * Pair of activities that look like the same real-world event
+ */
+export interface DuplicateCandidate {
+  /** Order-independent key identifying the pair */
+  pairKey: string;
+  activity: Activity;
+  otherActivity: Activity;
+  /** Similarity score between 0 and 1 */
+  score: string;
+  /** How the two activities differ */
+  reasons: string[];
+  /** Activity suggested to keep when merging */
+  suggestedKeepId: string;
+}
+
+export interface DuplicateResolution {
+  activityId: string;
+  otherActivityId: string;
+  decision: DuplicateDecision;
+  /** Activity to keep on merge (defaults to the suggested one) */
+  keepActivityId?: string;
+}
+
+export interface DuplicatePairDecision {
+  pairKey: string;
+  activityId: string;
+  otherActivityId: string;
+  decision: DuplicateDecision;
+  decidedAt: string;
+}
+
+// Recurring activity templates
+
+export type BusinessDayAdjustment = "NONE" | "FOLLOWING" | "PRECEDING" | "MODIFIED_FOLLOWING";
+
+export interface NewRecurringActivityTemplate {
+  id?: string;
+  accountId: string;
+  name: string;
+  activityType: string;
+  subtype?: string | null;
+  assetId?: string | null;
+  quantity?: string | null;
+  unitPrice?: string | null;
+  /** Fixed amount per occurrence */
+  amount?: string | null;
+  /** Formula evaluated per occurrence, e.g. `500 * 1.03 ^ years` */
+  amountFormula?: string | null;
+  fee?: string | null;
+  currency: string;
+  notes?: string | null;
+  /** RRULE-style schedule, e.g. `FREQ=MONTHLY;BYMONTHDAY=25` */
+  rrule: string;
+  startDate: string;
+  endDate?: string | null;
+  businessDayAdjustment?: BusinessDayAdjustment;
+  /** Status of generated activities (POSTED or PENDING) */
+  generateStatus?: ActivityStatus;
+}
+
+export interface RecurringActivityTemplate extends NewRecurringActivityTemplate {
+  id: string;
+  businessDayAdjustment: BusinessDayAdjustment;
+  generateStatus: ActivityStatus;
+  isActive: boolean;
+  /** Latest nominal occurrence date already generated */
+  lastGeneratedDate?: string | null;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface RecurringOccurrence {
+  index: number;
+  nominalDate: string;
+  activityDate: string;
+  amount?: string | null;
+}
+
+export interface RecurringGenerationResult {
+  generated: number;
+  skippedExisting: number;
+  activityIds: string[];
+  errors: string[];
+}
+
+export type ChangeEntityType = "ACTIVITY" | "ASSET";
+
+export type ChangeOperation = "CREATE" | "UPDATE" | "DELETE";
+
+export type ChangeSource =
+  | "MANUAL"
+  | "IMPORT"
+  | "BROKER_SYNC"
+  | "AI_TOOL"
+  | "RECURRING"
+  | "ROLLBACK";
+
+export interface ChangeLogEntry {
+  id: string;
+  entityType: ChangeEntityType;
+  entityId: string;
+  operation: ChangeOperation;
+  source: ChangeSource;
+  /** Import run id for imports and broker sync, undone change id for rollbacks */
+  sourceRef?: string | null;
+  accountId?: string | null;
+  before?: Record<string, unknown> | null;
+  after?: Record<string, unknown> | null;
+  createdAt: string;
+}
+
+export interface RollbackResult {
+  reverted: number;
+  skipped: number;
+  warnings: string[];
+}
+
+export interface BackupRetentionPolicy {
+  daily: number;
+  weekly: number;
+  monthly: number;
+}
+
+export interface BackupSchedule {
+  enabled: boolean;
+  intervalHours: number;
+  targetDir?: string | null;
+  retention: BackupRetentionPolicy;
+  /** Present once a backup passphrase has been configured */
+  kdf?: Record<string, unknown> | null;
+}
+
+export interface BackupScheduleUpdate {
+  enabled: boolean;
+  intervalHours: number;
+  targetDir?: string | null;
+  retention: BackupRetentionPolicy;
+  /** Required the first time backups are enabled; replaces the key when given again */
+  passphrase?: string;
+}
+
+export interface BackupArchiveInfo {
+  path: string;
+  fileName: string;
+  createdAt: string;
+  sizeBytes: number;
+}
+
+export interface BackupRunResult {
+  archive: BackupArchiveInfo;
+  pruned: string[];
+}
+
+export interface RestorePreview {
+  createdAt: string;
+  integrityOk: boolean;
+  tableCounts: Record<string, number>;
+  firstActivityDate?: string | null;
+  lastActivityDate?: string | null;
+}
+
+export type AttachmentEntityType = "ACTIVITY" | "ASSET" | "ACCOUNT";
+
+export interface Attachment {
+  id: string;
+  entityType: AttachmentEntityType;
+  entityId: string;
+  fileName: string;
+  contentType?: string | null;
+  sizeBytes: number;
+  /** SHA-256 of the file contents */
+  contentHash: string;
+  notes?: string | null;
+  createdAt: string;
+}
+
+export interface NewAttachment {
+  entityType: AttachmentEntityType;
+  entityId: string;
+  notes?: string | null;
+}
+
+export interface AttachmentContent {
+  attachment: Attachment;
+  data: Uint8Array;
+}
+
 // Holding types based on Rust HoldingView model
 
 export interface Instrument {
@@ -679,10 +873,44 @@ export interface FolderSyncHistoryEntry {
 
 export interface FolderSyncState {
   config: FolderSyncConfig | null;
+  /** Whether the shared folder is encrypted with a passphrase. */
+  isEncrypted: boolean;
   status: FolderSyncStatus;
   history: FolderSyncHistoryEntry[];
 }
 
+/** Basic-auth user and password for WebDAV, or access key id and secret for S3. */
+export interface FolderSyncRemoteCredentials {
+  username: string;
+  secret: string;
+}
+
+export type SyncConflictResolution = "local" | "remote" | "manual" | "superseded";
+
+/** A field edited on this device and on another device from the same version. */
+export interface SyncFieldConflict {
+  id: string;
+  entity: string;
+  entityId: string;
+  field: string;
+  localValue: unknown;
+  localUpdatedAt: string;
+  remoteValue: unknown;
+  remoteUpdatedAt: string;
+  remoteEventId: string;
+  resolution: SyncConflictResolution | null;
+  resolvedValue: unknown;
+  createdAt: string;
+  resolvedAt: string | null;
+}
+
+export interface ResolveSyncConflictRequest {
+  conflictId: string;
+  resolution: Exclude<SyncConflictResolution, "superseded">;
+  /** Required for manual resolutions. */
+  value?: unknown;
+}
+
 export interface FolderSyncCommandResult {
   status: string;
   message: string;
@@ -964,6 +1192,81 @@ export interface ImportRun {
   updatedAt: string;
 }
 
+export type StagedActivityStatus = "PENDING" | "ACCEPTED" | "REJECTED" | "APPLIED";
+export type StagedActivityDecision = "ACCEPT" | "REJECT";
+
+/**
+ * Activity payload as written to the ledger by sync and staged imports
+ */
+export interface ActivityUpsert {
+  id: string;
+  accountId: string;
+  assetId?: string | null;
+  activityType: string;
+  subtype?: string | null;
+  activityDate: string;
+  quantity?: string | null;
+  unitPrice?: string | null;
+  currency: string;
+  fee?: string | null;
+  amount?: string | null;
+  status?: ActivityStatus | null;
+  notes?: string | null;
+  fxRate?: string | null;
+  metadata?: string | null;
+  needsReview?: boolean | null;
+  sourceSystem?: string | null;
+  sourceRecordId?: string | null;
+  sourceGroupId?: string | null;
+  idempotencyKey?: string | null;
+  importRunId?: string | null;
+}
+
+/**
+ * Activity held on an import run until the run is applied or discarded
+ */
+export interface StagedActivity {
+  id: string;
+  importRunId: string;
+  accountId: string;
+  idempotencyKey: string;
+  activity: ActivityUpsert;
+  status: StagedActivityStatus;
+  /** Whether the user changed the payload during review */
+  edited: boolean;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface PositionImpact {
+  assetId: string;
+  currency: string;
+  quantityBefore: string;
+  quantityAfter: string;
+  costBasisBefore: string;
+  costBasisAfter: string;
+}
+
+/**
+ * Holdings and cash changes the included staged rows would cause
+ */
+export interface ImportImpact {
+  accountId: string;
+  currency: string;
+  /** Cash balances by currency, only for currencies that change */
+  cashBefore: Record<string, string>;
+  cashAfter: Record<string, string>;
+  positions: PositionImpact[];
+  warnings: { activityId: string; accountId: string; date: string; message: string }[];
+}
+
+export interface ImportRunReview {
+  run: ImportRun;
+  activities: StagedActivity[];
+  /** Missing once the run has been applied or discarded */
+  impact?: ImportImpact | null;
+}
+
 // ============================================================================
 // Sync State Types
 // ============================================================================
@@ -1455,6 +1758,65 @@ export interface PortfolioAllocations {
   totalValue: number;
 }
 
+/**
+ * Hypothetical activity applied on top of current holdings in a what-if scenario.
+ */
+export interface ScenarioActivity {
+  activityType: string;
+  assetId?: string | null;
+  quantity?: number | null;
+  unitPrice?: number | null; // Defaults to the latest quote for trades
+  amount?: number | null;
+  fee?: number | null;
+  currency?: string | null;
+}
+
+export interface ScenarioRequest {
+  accountId?: string | null; // Defaults to the total portfolio
+  activities: ScenarioActivity[];
+}
+
+export interface ConcentrationMetrics {
+  positionCount: number;
+  largestPosition?: string | null;
+  largestPositionPct: number; // 0-100 of invested (non-cash) value
+  top5Pct: number;
+  hhi: number; // Herfindahl-Hirschman index (0-10000)
+  effectivePositions: number;
+}
+
+export interface ScenarioState {
+  totalValue: number;
+  cashBalances: Record<string, number>;
+  cashValue: number;
+  allocations: PortfolioAllocations;
+  concentration: ConcentrationMetrics;
+}
+
+export interface RealizedGainEstimate {
+  assetId: string;
+  symbol?: string | null;
+  quantity: number;
+  proceeds: number;
+  costBasis: number;
+  gain: number;
+  currency: string; // Position currency
+  gainBase?: number | null;
+}
+
+/**
+ * Result of a what-if simulation. Nothing is persisted.
+ */
+export interface ScenarioResult {
+  accountId: string;
+  baseCurrency: string;
+  before: ScenarioState;
+  after: ScenarioState;
+  realizedGains: RealizedGainEstimate[];
+  totalRealizedGain: number;
+  warnings: string[];
+}
+
 export interface MigrationResult {
   sectorsMigrated: number;
   countriesMigrated: number;
@@ -1535,6 +1897,17 @@ export interface CapabilityInfo {
   icon: string;
 }
 
+/** How absolute amounts in tool output are presented to the provider. */
+export type AmountRedaction = "exact" | "scaled" | "bucketed";
+
+/** Privacy redaction of tool output sent to an LLM provider. */
+export interface RedactionSettings {
+  enabled: boolean;
+  amounts: AmountRedaction;
+  /** Drop notes, descriptions and raw CSV values. */
+  stripFreeText: boolean;
+}
+
 /**
  * A provider in the merged view returned to the UI.
  * Combines catalog data with user settings and computed fields.
@@ -1564,6 +1937,8 @@ export interface MergedProvider {
   modelCapabilityOverrides: Record<string, ModelCapabilityOverrides>;
   /** Allowlist of tool IDs that this provider can use. null = all tools enabled. */
   toolsAllowlist?: string[] | null;
+  /** Effective privacy redaction applied to tool output sent to this provider. */
+  redaction: RedactionSettings;
 
   // Computed
   hasApiKey: boolean;
@@ -1579,6 +1954,38 @@ export interface AiProvidersResponse {
   providers: MergedProvider[];
   capabilities: Record<string, CapabilityInfo>;
   defaultProvider?: string;
+  /** Monthly spending limit, if configured. */
+  usageBudget?: UsageBudget;
+  /** Stored configuration of user-defined providers. */
+  customProviders?: CustomProviderConfig[];
+}
+
+/**
+ * A user-defined OpenAI-compatible provider (LM Studio, vLLM, llama.cpp server, ...).
+ */
+export interface CustomProviderConfig {
+  id: string;
+  name: string;
+  /** API base including the version path, e.g. `http://localhost:1234/v1`. */
+  baseUrl: string;
+  /** Capabilities assumed for every model served by this endpoint. */
+  capabilities: ModelCapabilities;
+  defaultModel?: string;
+  /** Context window in tokens, when known. */
+  contextWindow?: number;
+}
+
+/**
+ * Request to add or update a user-defined provider.
+ * Omit `id` to add a new provider.
+ */
+export interface SaveCustomProviderRequest {
+  id?: string;
+  name: string;
+  baseUrl: string;
+  capabilities: ModelCapabilities;
+  defaultModel?: string;
+  contextWindow?: number;
 }
 
 /**
@@ -1607,6 +2014,8 @@ export interface UpdateProviderSettingsRequest {
   favoriteModels?: string[];
   /** Update tools allowlist. null = all tools enabled, [] = no tools, [...] = only specified tools. */
   toolsAllowlist?: string[] | null;
+  /** Update privacy redaction. null = default for the provider type. */
+  redaction?: RedactionSettings | null;
 }
 
 /**
@@ -1616,6 +2025,90 @@ export interface SetDefaultProviderRequest {
   providerId?: string;
 }
 
+// ============================================================================
+// AI Usage Types
+// ============================================================================
+
+/** What happens once the monthly budget is spent. */
+export type BudgetAction = "warn" | "block";
+
+/** Monthly AI spending limit across all providers. */
+export interface UsageBudget {
+  monthlyLimitUsd: number;
+  action: BudgetAction;
+}
+
+/**
+ * Request to set the monthly usage budget. null removes the limit.
+ */
+export interface SetUsageBudgetRequest {
+  budget: UsageBudget | null;
+}
+
+export type BudgetLevel = "ok" | "warning" | "exceeded";
+
+/** Month-to-date spend against the configured budget. */
+export interface BudgetStatus {
+  monthlyLimitUsd: number;
+  action: BudgetAction;
+  monthToDateUsd: number;
+  remainingUsd: number;
+  level: BudgetLevel;
+}
+
+/** Model list price in USD per million tokens. */
+export interface ModelPricing {
+  inputPerMillion: number;
+  outputPerMillion: number;
+}
+
+/** Aggregated token counts and estimated cost. */
+export interface UsageTotals {
+  messageCount: number;
+  promptTokens: number;
+  completionTokens: number;
+  totalTokens: number;
+  estimatedCostUsd: number;
+  /** Messages from models without a known price, excluded from the estimate. */
+  unpricedMessageCount: number;
+}
+
+export interface DailyUsage extends UsageTotals {
+  /** UTC day (YYYY-MM-DD). */
+  date: string;
+}
+
+export interface ProviderUsage extends UsageTotals {
+  providerId: string;
+}
+
+export interface ModelUsage extends UsageTotals {
+  providerId: string;
+  modelId: string;
+  pricing?: ModelPricing;
+}
+
+export interface ThreadUsage extends UsageTotals {
+  threadId: string;
+  /** Missing if the thread has been deleted. */
+  title?: string;
+}
+
+/**
+ * Token usage and estimated cost over an inclusive date range.
+ * Breakdowns are sorted by estimated cost, descending.
+ */
+export interface UsageReport {
+  from: string;
+  to: string;
+  totals: UsageTotals;
+  byDay: DailyUsage[];
+  byProvider: ProviderUsage[];
+  byModel: ModelUsage[];
+  byThread: ThreadUsage[];
+  budget?: BudgetStatus;
+}
+
 /**
  * Model info returned from provider API.
  */
@@ -1649,7 +2142,10 @@ export type HealthCategory =
   | "FX_INTEGRITY"
   | "CLASSIFICATION"
   | "DATA_CONSISTENCY"
-  | "ACCOUNT_CONFIGURATION";
+  | "ACCOUNT_CONFIGURATION"
+  | "RECONCILIATION"
+  | "SYNC_CONFLICTS"
+  | "HOLDINGS_DRIFT";
 
 /**
  * Navigation action for health issue resolution.
@@ -1720,6 +2216,106 @@ export interface HealthConfig {
   enabled: boolean;
 }
 
+// ============================================================================
+// Broker Reconciliation Types
+// ============================================================================
+
+export type StatementSource = "FILE" | "BROKER_SYNC" | "MANUAL";
+
+/**
+ * A position as reported by the broker.
+ */
+export interface BrokerPosition {
+  /** Local asset ID, when already known */
+  assetId?: string;
+  symbol: string;
+  quantity: string;
+  /** Total cost basis in `currency` */
+  costBasis?: string;
+  currency: string;
+}
+
+/**
+ * Positions and cash balances reported by a broker for one account on one date.
+ */
+export interface BrokerStatement {
+  accountId: string;
+  /** Statement date (YYYY-MM-DD) */
+  asOfDate: string;
+  source?: StatementSource;
+  positions: BrokerPosition[];
+  /** Currency code -> cash amount */
+  cashBalances: Record<string, string>;
+}
+
+export type DiscrepancyKind =
+  | "QUANTITY_MISMATCH"
+  | "COST_BASIS_MISMATCH"
+  | "MISSING_LOCALLY"
+  | "MISSING_AT_BROKER"
+  | "CASH_DELTA";
+
+export type SuggestedFixKind = "SPLIT" | "ADJUSTMENT" | "TRANSFER" | "FEE" | "CREDIT";
+
+export interface SuggestedFix {
+  kind: SuggestedFixKind;
+  description: string;
+  /** Draft activity that would close the gap */
+  activity: ActivityCreate;
+}
+
+export interface Discrepancy {
+  kind: DiscrepancyKind;
+  assetId?: string;
+  /** Ticker, or currency code for cash deltas */
+  symbol: string;
+  currency: string;
+  brokerQuantity?: string;
+  localQuantity?: string;
+  brokerCostBasis?: string;
+  localCostBasis?: string;
+  /** broker - local */
+  delta: string;
+  suggestedFix?: SuggestedFix;
+}
+
+export interface ReconciliationReport {
+  accountId: string;
+  accountName: string;
+  asOfDate: string;
+  snapshotDate?: string;
+  source: StatementSource;
+  positionsCompared: number;
+  discrepancies: Discrepancy[];
+}
+
+/**
+ * Reconciliation report plus the health issues published for it.
+ */
+export interface ReconciliationResult {
+  report: ReconciliationReport;
+  issues: HealthIssue[];
+}
+
+export type HoldingsDriftStatus = "PENDING" | "CHECKED" | "SUPERSEDED" | "FAILED";
+
+/**
+ * Broker holdings recorded during a sync, compared with computed holdings
+ * once snapshots are recalculated.
+ */
+export interface HoldingsDriftCheck {
+  id: string;
+  accountId: string;
+  asOfDate: string;
+  status: HoldingsDriftStatus;
+  statement: BrokerStatement;
+  report?: ReconciliationReport;
+  discrepancyCount: number;
+  error?: string;
+  createdAt: string;
+  checkedAt?: string;
+}
+
 // ============================================================================
 // Snapshot Info Types
 // ============================================================================
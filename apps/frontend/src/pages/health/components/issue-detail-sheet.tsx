@@ -56,6 +56,21 @@ const CATEGORY_LABELS: Record<HealthCategory, { label: string; description: stri
     description:
       "Some accounts need configuration before data can be synced. Set tracking mode to start importing data.",
   },
+  RECONCILIATION: {
+    label: "Broker Reconciliation",
+    description:
+      "Computed holdings differ from what the broker reports. A missed corporate action, fee or transfer is the usual cause.",
+  },
+  SYNC_CONFLICTS: {
+    label: "Sync Conflicts",
+    description:
+      "The same fields were edited on two devices before they synced. Pick the value to keep; the choice syncs to your other devices.",
+  },
+  HOLDINGS_DRIFT: {
+    label: "Holdings Drift",
+    description:
+      "After the last broker sync, holdings computed from synced transactions no longer match what the broker reports. Missing or duplicated transactions are the usual cause.",
+  },
 };
 
 export function IssueDetailSheet({
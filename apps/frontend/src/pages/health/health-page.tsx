@@ -57,6 +57,9 @@ const CATEGORY_CONFIG: Record<HealthCategory, { label: string; icon: keyof typeo
   CLASSIFICATION: { label: "Categories", icon: "Tag" },
   DATA_CONSISTENCY: { label: "Data", icon: "Database" },
   ACCOUNT_CONFIGURATION: { label: "Accounts", icon: "Settings" },
+  RECONCILIATION: { label: "Broker", icon: "ListChecks" },
+  SYNC_CONFLICTS: { label: "Sync", icon: "CloudSync" },
+  HOLDINGS_DRIFT: { label: "Drift", icon: "ArrowLeftRight" },
 };
 
 function SeverityDot({ severity }: { severity: HealthSeverity }) {
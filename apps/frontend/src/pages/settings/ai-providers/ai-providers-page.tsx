@@ -3,17 +3,34 @@ import { Separator } from "@wealthfolio/ui/components/ui/separator";
 import { Skeleton } from "@wealthfolio/ui/components/ui/skeleton";
 import { Icons } from "@wealthfolio/ui/components/ui/icons";
 import { Button } from "@wealthfolio/ui/components/ui/button";
+import {
+  AlertDialog,
+  AlertDialogCancel,
+  AlertDialogContent,
+  AlertDialogDescription,
+  AlertDialogFooter,
+  AlertDialogHeader,
+  AlertDialogTitle,
+} from "@wealthfolio/ui/components/ui/alert-dialog";
 
 import { SettingsHeader } from "../settings-header";
 import {
+  CustomProviderDialog,
   ProviderSettingsCard,
   useAiProviders,
   useUpdateAiProviderSettings,
   useSetDefaultAiProvider,
   useAiProviderApiKey,
   useListAiModels,
+  useSaveCustomAiProvider,
+  useDeleteCustomAiProvider,
 } from "@/features/ai-assistant";
-import type { ModelCapabilityOverrides } from "@/lib/types";
+import type {
+  CustomProviderConfig,
+  ModelCapabilityOverrides,
+  RedactionSettings,
+  SaveCustomProviderRequest,
+} from "@/lib/types";
 
 /**
  * AI Providers settings page - configure AI provider API keys and preferences.
@@ -22,8 +39,16 @@ export default function AiProvidersPage() {
   const { data, isLoading, error, refetch } = useAiProviders();
   const { mutate: updateSettings } = useUpdateAiProviderSettings();
   const { mutate: setDefault } = useSetDefaultAiProvider();
+  const { mutate: saveCustomProvider, isPending: isSavingCustomProvider } =
+    useSaveCustomAiProvider();
+  const { mutate: deleteCustomProvider } = useDeleteCustomAiProvider();
+
+  const [customDialogOpen, setCustomDialogOpen] = useState(false);
+  const [editingCustomProvider, setEditingCustomProvider] = useState<CustomProviderConfig>();
+  const [deletingCustomProvider, setDeletingCustomProvider] = useState<CustomProviderConfig>();
 
   const providers = useMemo(() => data?.providers ?? [], [data?.providers]);
+  const customProviders = useMemo(() => data?.customProviders ?? [], [data?.customProviders]);
 
   const handleCustomUrlChange = (providerId: string, customUrl: string) => {
     updateSettings({ providerId, customUrl });
@@ -55,6 +80,28 @@ export default function AiProvidersPage() {
     updateSettings({ providerId, toolsAllowlist: tools });
   };
 
+  const handleRedactionChange = (providerId: string, redaction: RedactionSettings) => {
+    updateSettings({ providerId, redaction });
+  };
+
+  const handleOpenCustomDialog = (provider?: CustomProviderConfig) => {
+    setEditingCustomProvider(provider);
+    setCustomDialogOpen(true);
+  };
+
+  const handleSaveCustomProvider = (request: SaveCustomProviderRequest) => {
+    saveCustomProvider(request, {
+      onSuccess: () => setCustomDialogOpen(false),
+    });
+  };
+
+  const handleConfirmDeleteCustomProvider = () => {
+    if (deletingCustomProvider) {
+      deleteCustomProvider(deletingCustomProvider.id);
+    }
+    setDeletingCustomProvider(undefined);
+  };
+
   if (isLoading) {
     return (
       <div className="text-foreground space-y-6">
@@ -114,10 +161,22 @@ export default function AiProvidersPage() {
 
   return (
     <div className="text-foreground space-y-6">
-      <SettingsHeader
-        heading="AI Providers"
-        text="Configure AI providers for portfolio insights."
-      />
+      <SettingsHeader heading="AI Providers" text="Configure AI providers for portfolio insights.">
+        <>
+          <Button
+            size="icon"
+            className="sm:hidden"
+            onClick={() => handleOpenCustomDialog()}
+            aria-label="Add provider"
+          >
+            <Icons.Plus className="h-4 w-4" />
+          </Button>
+          <Button className="hidden sm:inline-flex" onClick={() => handleOpenCustomDialog()}>
+            <Icons.Plus className="mr-2 h-4 w-4" />
+            Add provider
+          </Button>
+        </>
+      </SettingsHeader>
       <Separator />
       <div>
         {sortedProviders.length === 0 ? (
@@ -144,11 +203,45 @@ export default function AiProvidersPage() {
                   handleSetCapabilityOverride(provider.id, modelId, overrides)
                 }
                 onToolsAllowlistChange={(tools) => handleToolsAllowlistChange(provider.id, tools)}
+                onRedactionChange={(redaction) => handleRedactionChange(provider.id, redaction)}
+                customProvider={customProviders.find((custom) => custom.id === provider.id)}
+                onEditCustomProvider={handleOpenCustomDialog}
+                onDeleteCustomProvider={setDeletingCustomProvider}
               />
             ))}
           </div>
         )}
       </div>
+
+      <CustomProviderDialog
+        open={customDialogOpen}
+        onOpenChange={setCustomDialogOpen}
+        provider={editingCustomProvider}
+        onSave={handleSaveCustomProvider}
+        isSaving={isSavingCustomProvider}
+      />
+
+      <AlertDialog
+        open={!!deletingCustomProvider}
+        onOpenChange={(open) => !open && setDeletingCustomProvider(undefined)}
+      >
+        <AlertDialogContent>
+          <AlertDialogHeader>
+            <AlertDialogTitle>Remove provider?</AlertDialogTitle>
+            <AlertDialogDescription>
+              This removes &ldquo;{deletingCustomProvider?.name}&rdquo; together with its settings
+              and stored API key. Existing conversations are kept.
+            </AlertDialogDescription>
+          </AlertDialogHeader>
+          <AlertDialogFooter>
+            <AlertDialogCancel>Cancel</AlertDialogCancel>
+            <Button variant="destructive" onClick={handleConfirmDeleteCustomProvider}>
+              <Icons.Trash className="mr-2 h-4 w-4" />
+              Remove
+            </Button>
+          </AlertDialogFooter>
+        </AlertDialogContent>
+      </AlertDialog>
     </div>
   );
 }
@@ -166,6 +259,10 @@ function ProviderSettingsCardWrapper({
   onSetFavoriteModels,
   onSetCapabilityOverride,
   onToolsAllowlistChange,
+  onRedactionChange,
+  customProvider,
+  onEditCustomProvider,
+  onDeleteCustomProvider,
 }: {
   provider: Parameters<typeof ProviderSettingsCard>[0]["provider"];
   isLast: boolean;
@@ -176,6 +273,11 @@ function ProviderSettingsCardWrapper({
   onSetFavoriteModels: (modelIds: string[]) => void;
   onSetCapabilityOverride: (modelId: string, overrides: ModelCapabilityOverrides | null) => void;
   onToolsAllowlistChange: (tools: string[] | null) => void;
+  onRedactionChange: (redaction: RedactionSettings) => void;
+  /** Stored configuration when this is a user-defined provider. */
+  customProvider?: CustomProviderConfig;
+  onEditCustomProvider: (provider: CustomProviderConfig) => void;
+  onDeleteCustomProvider: (provider: CustomProviderConfig) => void;
 }) {
   const { setApiKey, deleteApiKey, revealApiKey } = useAiProviderApiKey(provider.id);
   const [modelComboboxOpen, setModelComboboxOpen] = useState(false);
@@ -218,6 +320,9 @@ function ProviderSettingsCardWrapper({
       onSetFavoriteModels={onSetFavoriteModels}
       onSetCapabilityOverride={onSetCapabilityOverride}
       onToolsAllowlistChange={onToolsAllowlistChange}
+      onRedactionChange={onRedactionChange}
+      onEditCustomProvider={customProvider && (() => onEditCustomProvider(customProvider))}
+      onDeleteCustomProvider={customProvider && (() => onDeleteCustomProvider(customProvider))}
       modelComboboxOpen={modelComboboxOpen}
       onModelComboboxOpenChange={setModelComboboxOpen}
       fetchedModels={fetchedModels}
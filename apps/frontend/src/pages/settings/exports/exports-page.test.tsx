@@ -11,6 +11,10 @@ vi.mock("@/features/folder-sync/components/folder-sync-card", () => ({
   FolderSyncCard: FolderSyncCardMock,
 }));
 
+vi.mock("@/features/folder-sync/components/sync-conflicts-card", () => ({
+  SyncConflictsCard: () => null,
+}));
+
 vi.mock("./backup-restore-form", () => ({
   BackupRestoreForm: () => <div>Backup Restore Form</div>,
 }));
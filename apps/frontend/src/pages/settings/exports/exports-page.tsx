@@ -1,6 +1,7 @@
 import { Separator } from "@wealthfolio/ui/components/ui/separator";
 import { Tabs, TabsContent, TabsList, TabsTrigger } from "@wealthfolio/ui/components/ui/tabs";
 import { FolderSyncCard } from "@/features/folder-sync/components/folder-sync-card";
+import { SyncConflictsCard } from "@/features/folder-sync/components/sync-conflicts-card";
 import { SettingsHeader } from "../settings-header";
 import { BackupRestoreForm } from "./backup-restore-form";
 import { ExportForm } from "./exports-form";
@@ -14,6 +15,8 @@ const ExportSettingsPage = () => {
       />
       <Separator />
 
+      <SyncConflictsCard />
+
       <Tabs defaultValue="backup" className="w-full">
         <TabsList className="grid w-full grid-cols-3">
           <TabsTrigger value="backup">Backup & Restore</TabsTrigger>
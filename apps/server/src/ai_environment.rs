@@ -8,9 +8,12 @@ use std::sync::{Arc, RwLock};
 use wealthfolio_ai::{AiEnvironment, ChatRepositoryTrait};
 use wealthfolio_core::{
     accounts::AccountServiceTrait, activities::ActivityServiceTrait,
-    allocation::AllocationServiceTrait, goals::GoalServiceTrait, holdings::HoldingsServiceTrait,
-    income::IncomeServiceTrait, performance::PerformanceServiceTrait, quotes::QuoteServiceTrait,
-    secrets::SecretStore, settings::SettingsServiceTrait, valuation::ValuationServiceTrait,
+    allocation::AllocationServiceTrait, assets::AlternativeAssetServiceTrait, fx::FxServiceTrait,
+    goals::GoalServiceTrait, health::HealthServiceTrait, holdings::HoldingsServiceTrait,
+    income::IncomeServiceTrait, net_worth::NetWorthServiceTrait,
+    performance::PerformanceServiceTrait, quotes::QuoteServiceTrait,
+    scenario::ScenarioServiceTrait, secrets::SecretStore, settings::SettingsServiceTrait,
+    valuation::ValuationServiceTrait,
 };
 
 /// Server-side implementation of AiEnvironment.
@@ -31,6 +34,11 @@ pub struct ServerAiEnvironment {
     allocation_service: Arc<dyn AllocationServiceTrait + Send + Sync>,
     performance_service: Arc<dyn PerformanceServiceTrait + Send + Sync>,
     income_service: Arc<dyn IncomeServiceTrait + Send + Sync>,
+    net_worth_service: Arc<dyn NetWorthServiceTrait + Send + Sync>,
+    health_service: Arc<dyn HealthServiceTrait + Send + Sync>,
+    fx_service: Arc<dyn FxServiceTrait + Send + Sync>,
+    alternative_asset_service: Arc<dyn AlternativeAssetServiceTrait + Send + Sync>,
+    scenario_service: Arc<dyn ScenarioServiceTrait + Send + Sync>,
 }
 
 impl ServerAiEnvironment {
@@ -50,6 +58,11 @@ impl ServerAiEnvironment {
         allocation_service: Arc<dyn AllocationServiceTrait + Send + Sync>,
         performance_service: Arc<dyn PerformanceServiceTrait + Send + Sync>,
         income_service: Arc<dyn IncomeServiceTrait + Send + Sync>,
+        net_worth_service: Arc<dyn NetWorthServiceTrait + Send + Sync>,
+        health_service: Arc<dyn HealthServiceTrait + Send + Sync>,
+        fx_service: Arc<dyn FxServiceTrait + Send + Sync>,
+        alternative_asset_service: Arc<dyn AlternativeAssetServiceTrait + Send + Sync>,
+        scenario_service: Arc<dyn ScenarioServiceTrait + Send + Sync>,
     ) -> Self {
         Self {
             base_currency,
@@ -65,6 +78,11 @@ impl ServerAiEnvironment {
             allocation_service,
             performance_service,
             income_service,
+            net_worth_service,
+            health_service,
+            fx_service,
+            alternative_asset_service,
+            scenario_service,
         }
     }
 }
@@ -121,4 +139,24 @@ impl AiEnvironment for ServerAiEnvironment {
     fn income_service(&self) -> Arc<dyn IncomeServiceTrait> {
         self.income_service.clone()
     }
+
+    fn net_worth_service(&self) -> Arc<dyn NetWorthServiceTrait> {
+        self.net_worth_service.clone()
+    }
+
+    fn health_service(&self) -> Arc<dyn HealthServiceTrait> {
+        self.health_service.clone()
+    }
+
+    fn fx_service(&self) -> Arc<dyn FxServiceTrait> {
+        self.fx_service.clone()
+    }
+
+    fn alternative_asset_service(&self) -> Arc<dyn AlternativeAssetServiceTrait> {
+        self.alternative_asset_service.clone()
+    }
+
+    fn scenario_service(&self) -> Arc<dyn ScenarioServiceTrait> {
+        self.scenario_service.clone()
+    }
 }
@@ -23,10 +23,12 @@ mod ai_chat;
 mod ai_providers;
 mod alternative_assets;
 mod assets;
+mod attachments;
+mod audit;
 #[cfg(any(feature = "connect-sync", feature = "device-sync"))]
 pub mod connect;
 #[cfg(feature = "device-sync")]
-mod device_sync;
+pub(crate) mod device_sync;
 #[cfg(feature = "device-sync")]
 pub(crate) mod device_sync_engine;
 mod exchange_rates;
@@ -35,14 +37,19 @@ mod health;
 mod holdings;
 mod limits;
 mod market_data;
+mod mcp;
 mod net_worth;
 mod performance;
 mod portfolio;
+pub(crate) mod reconciliation;
+mod recurring;
 mod secrets;
 mod settings;
 pub mod shared;
+mod sync_conflicts;
 #[cfg(feature = "device-sync")]
 mod sync_crypto;
+pub mod sync_relay;
 mod taxonomies;
 
 #[utoipa::path(get, path = "/api/v1/healthz", responses((status = 200, description = "Health")))]
@@ -100,7 +107,13 @@ pub fn app_router(state: Arc<AppState>, config: &Config) -> Router {
         .merge(alternative_assets::router())
         .merge(ai_providers::router())
         .merge(ai_chat::router())
-        .merge(health::router());
+        .merge(mcp::router())
+        .merge(health::router())
+        .merge(reconciliation::router())
+        .merge(recurring::router())
+        .merge(audit::router())
+        .merge(sync_conflicts::router())
+        .merge(attachments::router());
 
     #[cfg(feature = "device-sync")]
     {
@@ -123,13 +136,19 @@ pub fn app_router(state: Arc<AppState>, config: &Config) -> Router {
         protected_api
     };
 
-    let api = Router::new()
+    let mut api = Router::new()
         .route("/healthz", get(healthz))
         .route("/readyz", get(readyz))
         .route("/auth/status", get(auth::auth_status))
         .route("/auth/login", axum::routing::post(auth::login))
-        .merge(protected_api)
-        .with_state(state.clone());
+        .merge(protected_api);
+
+    // The relay authenticates devices with its own tokens, so it stays outside the JWT layer.
+    if state.sync_relay.is_some() {
+        api = api.merge(sync_relay::router());
+    }
+
+    let api = api.with_state(state.clone());
 
     Router::new()
         .nest("/api/v1", api)
@@ -4,13 +4,15 @@ use std::sync::Arc;
 use crate::{error::ApiResult, main_lib::AppState};
 use axum::{
     extract::{Multipart, Path, Query, State},
-    routing::{delete, get, post},
+    routing::{delete, get, post, put},
     Json, Router,
 };
 use wealthfolio_core::activities::{
     Activity, ActivityBulkMutationRequest, ActivityBulkMutationResult, ActivityImport,
-    ActivitySearchResponse, ActivityUpdate, ImportActivitiesResult, ImportMappingData, NewActivity,
-    ParseConfig, ParsedCsvResult,
+    ActivitySearchResponse, ActivityUpdate, ActivityUpsert, DuplicateCandidate,
+    DuplicatePairDecision, DuplicateResolution, ImportActivitiesResult, ImportMappingData,
+    ImportRun, ImportRunReview, NewActivity, ParseConfig, ParsedCsvResult, ReviewMode,
+    StagedActivity, StagedActivityDecision,
 };
 
 use super::shared::parse_date_optional;
@@ -151,6 +153,8 @@ struct ImportBody {
     #[serde(rename = "accountId")]
     account_id: String,
     activities: Vec<ActivityImport>,
+    #[serde(rename = "reviewMode", default)]
+    review_mode: ReviewMode,
 }
 
 async fn import_activities(
@@ -159,7 +163,7 @@ async fn import_activities(
 ) -> ApiResult<Json<ImportActivitiesResult>> {
     let result = state
         .activity_service
-        .import_activities(body.account_id, body.activities)
+        .import_activities(body.account_id, body.activities, body.review_mode)
         .await?;
     // Domain events handle asset enrichment and portfolio recalculation
     Ok(Json(result))
@@ -216,6 +220,84 @@ async fn check_existing_duplicates(
     Ok(Json(CheckDuplicatesResponse { duplicates }))
 }
 
+#[derive(serde::Deserialize)]
+struct DuplicateCandidatesQuery {
+    #[serde(rename = "accountId")]
+    account_id: Option<String>,
+}
+
+async fn get_duplicate_candidates(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<DuplicateCandidatesQuery>,
+) -> ApiResult<Json<Vec<DuplicateCandidate>>> {
+    let candidates = state
+        .activity_duplicate_service
+        .list_candidates(q.account_id.as_deref())?;
+    Ok(Json(candidates))
+}
+
+async fn resolve_duplicate_candidate(
+    State(state): State<Arc<AppState>>,
+    Json(resolution): Json<DuplicateResolution>,
+) -> ApiResult<Json<DuplicatePairDecision>> {
+    let decision = state.activity_duplicate_service.resolve(resolution).await?;
+    Ok(Json(decision))
+}
+
+async fn get_import_run_review(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<ImportRunReview>> {
+    let review = state.activity_staging_service.get_review(&id)?;
+    Ok(Json(review))
+}
+
+async fn apply_import_run(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<ImportRun>> {
+    // Domain events handle asset enrichment and portfolio recalculation
+    let run = state.activity_staging_service.apply_run(&id).await?;
+    Ok(Json(run))
+}
+
+async fn discard_import_run(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<ImportRun>> {
+    let run = state.activity_staging_service.discard_run(&id).await?;
+    Ok(Json(run))
+}
+
+async fn update_staged_activity(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(activity): Json<ActivityUpsert>,
+) -> ApiResult<Json<StagedActivity>> {
+    let row = state
+        .activity_staging_service
+        .update_staged_activity(&id, activity)
+        .await?;
+    Ok(Json(row))
+}
+
+#[derive(serde::Deserialize)]
+struct StagedDecisionBody {
+    decision: StagedActivityDecision,
+}
+
+async fn set_staged_activity_decision(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(body): Json<StagedDecisionBody>,
+) -> ApiResult<Json<StagedActivity>> {
+    let row = state
+        .activity_staging_service
+        .set_decision(&id, body.decision)
+        .await?;
+    Ok(Json(row))
+}
+
 async fn parse_csv_endpoint(
     State(_state): State<Arc<AppState>>,
     mut multipart: Multipart,
@@ -279,4 +361,23 @@ pub fn router() -> Router<Arc<AppState>> {
             "/activities/import/check-duplicates",
             post(check_existing_duplicates),
         )
+        .route("/activities/duplicates", get(get_duplicate_candidates))
+        .route(
+            "/activities/duplicates/resolve",
+            post(resolve_duplicate_candidate),
+        )
+        .route(
+            "/activities/import-runs/{id}/review",
+            get(get_import_run_review),
+        )
+        .route("/activities/import-runs/{id}/apply", post(apply_import_run))
+        .route(
+            "/activities/import-runs/{id}/discard",
+            post(discard_import_run),
+        )
+        .route("/activities/staged/{id}", put(update_staged_activity))
+        .route(
+            "/activities/staged/{id}/decision",
+            post(set_staged_activity_decision),
+        )
 }
@@ -13,13 +13,14 @@ use axum::{
     routing::{delete, get, post},
     Json, Router,
 };
+use chrono::NaiveDate;
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 
 use crate::main_lib::AppState;
 use wealthfolio_ai::{
     AiError, AiStreamEvent, ChatMessage, ChatThread, ListThreadsRequest, SendMessageRequest,
-    ThreadPage,
+    ThreadPage, UsageReport,
 };
 
 // ============================================================================
@@ -73,7 +74,8 @@ pub struct UpdateToolResultRequest {
 /// Streams AI assistant responses as NDJSON (one JSON object per line).
 /// Each line is a complete `AiStreamEvent` JSON object.
 ///
-/// Event types: `system`, `textDelta`, `reasoningDelta`, `toolCall`, `toolResult`, `error`, `done`
+/// Event types: `system`, `textDelta`, `reasoningDelta`, `toolCall`, `toolResult`,
+/// `threadTitleUpdated`, `usageBudgetWarning`, `error`, `done`
 ///
 /// The stream always starts with a `system` event and ends with a `done` event.
 async fn stream_chat(
@@ -287,6 +289,32 @@ async fn update_tool_result(
     Ok(Json(message))
 }
 
+// ============================================================================
+// Usage
+// ============================================================================
+
+/// Query parameters for the usage report (inclusive UTC days).
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+/// GET /api/v1/ai/usage
+///
+/// Token usage and estimated cost by day, provider, model and thread.
+/// Defaults to the current month to date.
+async fn get_usage_report(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<UsageQuery>,
+) -> Result<Json<UsageReport>, AiChatError> {
+    let report = state
+        .ai_chat_service
+        .get_usage_report(query.from, query.to)
+        .map_err(AiChatError::Ai)?;
+    Ok(Json(report))
+}
+
 // ============================================================================
 // Error Handling
 // ============================================================================
@@ -311,6 +339,7 @@ impl IntoResponse for AiChatError {
                     AiError::ToolExecutionFailed(_) => StatusCode::INTERNAL_SERVER_ERROR,
                     AiError::ThreadNotFound(_) => StatusCode::NOT_FOUND,
                     AiError::InvalidCursor(_) => StatusCode::BAD_REQUEST,
+                    AiError::BudgetExceeded(_) => StatusCode::TOO_MANY_REQUESTS,
                     AiError::Core(_) => StatusCode::INTERNAL_SERVER_ERROR,
                     AiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
                 };
@@ -351,4 +380,6 @@ pub fn router() -> Router<Arc<AppState>> {
         // Tag management
         .route("/ai/threads/{id}/tags", get(get_tags).post(add_tag))
         .route("/ai/threads/{id}/tags/{tag}", delete(remove_tag))
+        // Usage accounting
+        .route("/ai/usage", get(get_usage_report))
 }
@@ -3,12 +3,12 @@ use std::sync::Arc;
 use crate::{error::ApiResult, main_lib::AppState};
 use axum::{
     extract::{Path, State},
-    routing::{get, post, put},
+    routing::{delete, get, post, put},
     Json, Router,
 };
 use wealthfolio_ai::{
-    AiProvidersResponse, ListModelsResponse, SetDefaultProviderRequest,
-    UpdateProviderSettingsRequest,
+    AiProvidersResponse, ListModelsResponse, SaveCustomProviderRequest, SetDefaultProviderRequest,
+    SetUsageBudgetRequest, UpdateProviderSettingsRequest,
 };
 
 async fn get_ai_providers(
@@ -40,6 +40,37 @@ async fn set_default_provider(
     Ok(Json(()))
 }
 
+async fn set_usage_budget(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<SetUsageBudgetRequest>,
+) -> ApiResult<Json<()>> {
+    state.ai_provider_service.set_usage_budget(request).await?;
+    Ok(Json(()))
+}
+
+/// Add or update a user-defined OpenAI-compatible provider. Returns its ID.
+async fn save_custom_provider(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<SaveCustomProviderRequest>,
+) -> ApiResult<Json<String>> {
+    let provider_id = state
+        .ai_provider_service
+        .save_custom_provider(request)
+        .await?;
+    Ok(Json(provider_id))
+}
+
+async fn delete_custom_provider(
+    State(state): State<Arc<AppState>>,
+    Path(provider_id): Path<String>,
+) -> ApiResult<Json<()>> {
+    state
+        .ai_provider_service
+        .delete_custom_provider(&provider_id)
+        .await?;
+    Ok(Json(()))
+}
+
 /// List available models from a provider.
 /// Fetches models from the provider's API using backend-stored secrets.
 /// Frontend never needs to send API keys - they are retrieved internally.
@@ -56,5 +87,11 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/ai/providers", get(get_ai_providers))
         .route("/ai/providers/settings", put(update_provider_settings))
         .route("/ai/providers/default", post(set_default_provider))
+        .route("/ai/providers/budget", put(set_usage_budget))
+        .route("/ai/providers/custom", post(save_custom_provider))
+        .route(
+            "/ai/providers/custom/{provider_id}",
+            delete(delete_custom_provider),
+        )
         .route("/ai/providers/{provider_id}/models", get(list_models))
 }
@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use crate::{
+    error::{ApiError, ApiResult},
+    main_lib::AppState,
+};
+use axum::{
+    extract::{DefaultBodyLimit, Multipart, Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get},
+    Json, Router,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use wealthfolio_core::attachments::{
+    Attachment, AttachmentEntityType, NewAttachment, ATTACHMENT_MAX_SIZE_BYTES,
+};
+
+fn parse_entity_type(value: &str) -> Result<AttachmentEntityType, ApiError> {
+    AttachmentEntityType::parse(&value.to_uppercase())
+        .ok_or_else(|| ApiError::BadRequest(format!("Unknown entity type: {}", value)))
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListAttachmentsQuery {
+    entity_type: String,
+    entity_id: String,
+}
+
+async fn list_attachments(
+    Query(query): Query<ListAttachmentsQuery>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<Vec<Attachment>>> {
+    let entity_type = parse_entity_type(&query.entity_type)?;
+    let attachments = state
+        .attachment_service
+        .list_attachments(entity_type, &query.entity_id)?;
+    Ok(Json(attachments))
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UploadMetadata {
+    entity_type: String,
+    entity_id: String,
+    notes: Option<String>,
+}
+
+/// Multipart upload with a `file` part and a `metadata` JSON part.
+async fn upload_attachment(
+    State(state): State<Arc<AppState>>,
+    mut multipart: Multipart,
+) -> ApiResult<Json<Attachment>> {
+    let mut file: Option<(String, Option<String>, Vec<u8>)> = None;
+    let mut metadata: Option<UploadMetadata> = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to read multipart field: {}", e)))?
+    {
+        match field.name().unwrap_or("") {
+            "file" => {
+                let file_name = field.file_name().unwrap_or("attachment").to_string();
+                let content_type = field.content_type().map(|c| c.to_string());
+                let data = field.bytes().await.map_err(|e| {
+                    ApiError::BadRequest(format!("Failed to read file content: {}", e))
+                })?;
+                file = Some((file_name, content_type, data.to_vec()));
+            }
+            "metadata" => {
+                let bytes = field
+                    .bytes()
+                    .await
+                    .map_err(|e| ApiError::BadRequest(format!("Failed to read metadata: {}", e)))?;
+                metadata =
+                    Some(serde_json::from_slice(&bytes).map_err(|e| {
+                        ApiError::BadRequest(format!("Invalid metadata JSON: {}", e))
+                    })?);
+            }
+            _ => {}
+        }
+    }
+
+    let (file_name, content_type, data) =
+        file.ok_or_else(|| ApiError::BadRequest("Missing file in multipart request".to_string()))?;
+    let metadata = metadata
+        .ok_or_else(|| ApiError::BadRequest("Missing metadata in multipart request".to_string()))?;
+
+    let attachment = state
+        .attachment_service
+        .add_attachment(
+            NewAttachment {
+                entity_type: parse_entity_type(&metadata.entity_type)?,
+                entity_id: metadata.entity_id,
+                file_name,
+                content_type,
+                notes: metadata.notes,
+            },
+            data,
+        )
+        .await?;
+    Ok(Json(attachment))
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AttachmentContentResponse {
+    attachment: Attachment,
+    data_b64: String,
+}
+
+async fn get_attachment_content(
+    Path(id): Path<String>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<AttachmentContentResponse>> {
+    let service = state.attachment_service.clone();
+    let (attachment, data) =
+        tokio::task::spawn_blocking(move || service.get_attachment_content(&id))
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read attachment: {}", e))??;
+    Ok(Json(AttachmentContentResponse {
+        attachment,
+        data_b64: BASE64.encode(&data),
+    }))
+}
+
+async fn delete_attachment(
+    Path(id): Path<String>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<StatusCode> {
+    state.attachment_service.delete_attachment(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/attachments",
+            get(list_attachments)
+                .post(upload_attachment)
+                // Leave room for multipart framing on top of the file itself
+                .layer(DefaultBodyLimit::max(ATTACHMENT_MAX_SIZE_BYTES + 64 * 1024)),
+        )
+        .route("/attachments/{id}", delete(delete_attachment))
+        .route("/attachments/{id}/content", get(get_attachment_content))
+}
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use crate::{
+    error::{ApiError, ApiResult},
+    main_lib::AppState,
+};
+use axum::{
+    extract::{Path, State},
+    routing::{get, post},
+    Json, Router,
+};
+use wealthfolio_core::audit::{ChangeEntityType, ChangeLogEntry, RollbackResult};
+
+async fn get_entity_history(
+    Path((entity_type, entity_id)): Path<(String, String)>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<Vec<ChangeLogEntry>>> {
+    let entity_type = ChangeEntityType::parse(&entity_type.to_uppercase())
+        .ok_or_else(|| ApiError::BadRequest(format!("Unknown entity type: {}", entity_type)))?;
+    let history = state
+        .change_log_service
+        .get_entity_history(entity_type, &entity_id)?;
+    Ok(Json(history))
+}
+
+async fn get_import_run_changes(
+    Path(import_run_id): Path<String>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<Vec<ChangeLogEntry>>> {
+    let changes = state
+        .change_log_service
+        .get_import_run_changes(&import_run_id)?;
+    Ok(Json(changes))
+}
+
+async fn rollback_change(
+    Path(id): Path<String>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<RollbackResult>> {
+    // Rollbacks emit ActivitiesChanged, which triggers recalculation
+    let result = state.change_log_service.rollback_change(&id).await?;
+    Ok(Json(result))
+}
+
+async fn rollback_import_run(
+    Path(import_run_id): Path<String>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<RollbackResult>> {
+    let result = state
+        .change_log_service
+        .rollback_import_run(&import_run_id)
+        .await?;
+    Ok(Json(result))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/history/{entity_type}/{entity_id}",
+            get(get_entity_history),
+        )
+        .route("/change-log/{id}/rollback", post(rollback_change))
+        .route("/import-runs/{id}/changes", get(get_import_run_changes))
+        .route("/import-runs/{id}/rollback", post(rollback_import_run))
+}
@@ -7,8 +7,8 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use axum::{
-    extract::{Query, State},
-    routing::{delete, get, post},
+    extract::{Path, Query, State},
+    routing::{delete, get, post, put},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
@@ -26,10 +26,12 @@ use wealthfolio_connect::{
         BrokerApiClient, PlansResponse, SyncAccountsResponse, SyncActivitiesResponse,
         SyncConnectionsResponse, UserInfo,
     },
-    fetch_subscription_plans_public, ConnectApiClient, SyncConfig, SyncOrchestrator,
-    SyncProgressPayload, SyncProgressReporter, SyncResult,
+    fetch_subscription_plans_public, ConnectApiClient, LocalConnectorInfo, LocalConnectorKind,
+    SyncConfig, SyncOrchestrator, SyncProgressPayload, SyncProgressReporter, SyncResult,
+    SyncSchedule, CONNECT_SYNC_SOURCE,
 };
-use wealthfolio_core::accounts::TrackingMode;
+use wealthfolio_core::accounts::{AccountServiceTrait, TrackingMode};
+use wealthfolio_core::portfolio::reconciliation::ReconciliationResult;
 use wealthfolio_device_sync::{EnableSyncResult, SyncState, SyncStateResult};
 
 // Storage keys (without prefix - the SecretStore adds "wealthfolio_" prefix)
@@ -622,7 +624,10 @@ async fn sync_broker_data(State(state): State<Arc<AppState>>) -> StatusCode {
 
     // Spawn background task to perform the sync
     tokio::spawn(async move {
-        match perform_broker_sync(&state).await {
+        let config = state
+            .sync_schedule_service
+            .manual_sync_config(CONNECT_SYNC_SOURCE);
+        match perform_broker_sync(&state, config).await {
             Ok(_result) => {
                 info!("[Connect] Broker sync completed successfully");
                 // Events are emitted by the orchestrator via EventBusProgressReporter
@@ -650,7 +655,10 @@ pub async fn has_broker_sync(state: &AppState) -> Result<bool, String> {
 /// Core broker sync logic - syncs connections, accounts, and activities from cloud to local DB.
 /// Uses the centralized SyncOrchestrator for full pagination support.
 /// Also used by the background scheduler for periodic syncs.
-pub async fn perform_broker_sync(state: &AppState) -> Result<SyncResult, String> {
+pub async fn perform_broker_sync(
+    state: &AppState,
+    config: SyncConfig,
+) -> Result<SyncResult, String> {
     ensure_connect_sync_enabled().map_err(|e| e.to_string())?;
     // Create API client
     let client = create_connect_client(state)
@@ -659,11 +667,7 @@ pub async fn perform_broker_sync(state: &AppState) -> Result<SyncResult, String>
 
     // Create progress reporter and orchestrator
     let reporter = Arc::new(EventBusProgressReporter::new(state.event_bus.clone()));
-    let orchestrator = SyncOrchestrator::new(
-        state.connect_sync_service.clone(),
-        reporter,
-        SyncConfig::default(),
-    );
+    let orchestrator = SyncOrchestrator::new(state.connect_sync_service.clone(), reporter, config);
 
     // Run the sync via the centralized orchestrator
     // Note: Asset enrichment is handled automatically via domain events (AssetsCreated)
@@ -927,6 +931,252 @@ async fn list_broker_accounts(
     Ok(Json(accounts))
 }
 
+/// Reconcile a synced account against the holdings currently reported by the broker.
+async fn reconcile_broker_account(
+    State(state): State<Arc<AppState>>,
+    Path(account_id): Path<String>,
+) -> ApiResult<Json<ReconciliationResult>> {
+    ensure_connect_sync_enabled()?;
+
+    let account = state.account_service.get_account(&account_id)?;
+    let provider_account_id = account.provider_account_id.clone().ok_or_else(|| {
+        ApiError::BadRequest(format!("Account {} is not linked to a broker", account_id))
+    })?;
+
+    info!(
+        "[Connect] Fetching holdings for reconciliation of '{}'...",
+        account.name
+    );
+    let client = create_connect_client(&state).await?;
+    let holdings = client
+        .get_account_holdings(&provider_account_id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let statement = wealthfolio_connect::broker::mapping::holdings_to_statement(
+        &account.id,
+        &account.currency,
+        chrono::Local::now().date_naive(),
+        holdings.balances.as_deref().unwrap_or_default(),
+        holdings.positions.as_deref().unwrap_or_default(),
+    );
+    let report = state.reconciliation_service.reconcile(statement)?;
+    info!(
+        "[Connect] Reconciliation of '{}' found {} discrepancies",
+        account.name,
+        report.discrepancies.len()
+    );
+
+    Ok(Json(
+        super::reconciliation::publish_reconciliation(&state, report).await,
+    ))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Local Connectors (broker APIs called directly, no Connect session)
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigureLocalConnectorRequest {
+    credential: String,
+}
+
+fn parse_local_connector(connector_id: &str) -> ApiResult<LocalConnectorKind> {
+    LocalConnectorKind::from_id(connector_id)
+        .ok_or_else(|| ApiError::BadRequest(format!("Unknown local connector: {}", connector_id)))
+}
+
+async fn list_local_connectors(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<Vec<LocalConnectorInfo>>> {
+    Ok(Json(wealthfolio_connect::list_local_connectors(
+        state.secret_store.as_ref(),
+    )?))
+}
+
+async fn configure_local_connector(
+    State(state): State<Arc<AppState>>,
+    Path(connector_id): Path<String>,
+    Json(body): Json<ConfigureLocalConnectorRequest>,
+) -> ApiResult<StatusCode> {
+    let kind = parse_local_connector(&connector_id)?;
+    wealthfolio_connect::configure_local_connector(
+        state.secret_store.as_ref(),
+        kind,
+        &body.credential,
+    )
+    .await
+    .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    info!(
+        "[Connect] Stored credentials for local connector '{}'",
+        kind.id()
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn remove_local_connector(
+    State(state): State<Arc<AppState>>,
+    Path(connector_id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let kind = parse_local_connector(&connector_id)?;
+    wealthfolio_connect::remove_local_connector(state.secret_store.as_ref(), kind)?;
+    info!(
+        "[Connect] Removed credentials for local connector '{}'",
+        kind.id()
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Start a local connector sync (non-blocking, emits the same SSE events as Connect sync).
+async fn sync_local_connector(
+    State(state): State<Arc<AppState>>,
+    Path(connector_id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let kind = parse_local_connector(&connector_id)?;
+    let client = wealthfolio_connect::create_local_connector(state.secret_store.as_ref(), kind)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    info!(
+        "[Connect] Starting local connector sync for '{}'...",
+        kind.id()
+    );
+    tokio::spawn(async move {
+        let config = state.sync_schedule_service.manual_sync_config(kind.id());
+        match perform_local_connector_sync(&state, client.as_ref(), config).await {
+            Ok(_result) => info!("[Connect] Local connector '{}' sync completed", kind.id()),
+            Err(err) => error!(
+                "[Connect] Local connector '{}' sync failed: {}",
+                kind.id(),
+                err
+            ),
+        }
+    });
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Run the shared orchestrator against a local connector client.
+pub async fn perform_local_connector_sync(
+    state: &AppState,
+    client: &dyn BrokerApiClient,
+    config: SyncConfig,
+) -> Result<SyncResult, String> {
+    let reporter = Arc::new(EventBusProgressReporter::new(state.event_bus.clone()));
+    let orchestrator = SyncOrchestrator::new(state.connect_sync_service.clone(), reporter, config);
+    orchestrator.sync_all(client).await
+}
+
+/// Run a sync for a schedule source ([`CONNECT_SYNC_SOURCE`] or a local connector ID).
+///
+/// Returns `Ok(None)` when the source is not set up: no Connect session, or
+/// no stored connector credentials.
+pub async fn perform_source_sync(
+    state: &AppState,
+    source_id: &str,
+    config: SyncConfig,
+) -> Result<Option<SyncResult>, String> {
+    if source_id == CONNECT_SYNC_SOURCE {
+        let has_token = state
+            .secret_store
+            .get_secret(CLOUD_REFRESH_TOKEN_KEY)
+            .map(|t| t.is_some())
+            .unwrap_or(false);
+        if !has_token || !crate::features::connect_sync_enabled() {
+            return Ok(None);
+        }
+        return perform_broker_sync(state, config).await.map(Some);
+    }
+
+    let kind = LocalConnectorKind::from_id(source_id)
+        .ok_or_else(|| format!("Unknown sync source: {}", source_id))?;
+    let client =
+        match wealthfolio_connect::create_local_connector(state.secret_store.as_ref(), kind) {
+            Ok(client) => client,
+            Err(wealthfolio_core::Error::MissingConfigKey(_)) => return Ok(None),
+            Err(e) => return Err(e.to_string()),
+        };
+    perform_local_connector_sync(state, client.as_ref(), config)
+        .await
+        .map(Some)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Sync Schedules & Repair
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RepairSyncRequest {
+    /// Defaults to Wealthfolio Connect
+    source_id: Option<String>,
+    from_date: chrono::NaiveDate,
+    /// Limit the repair to one local account
+    account_id: Option<String>,
+}
+
+async fn get_sync_schedules(State(state): State<Arc<AppState>>) -> Json<Vec<SyncSchedule>> {
+    Json(state.sync_schedule_service.list_schedules())
+}
+
+async fn update_sync_schedule(
+    State(state): State<Arc<AppState>>,
+    Path(source_id): Path<String>,
+    Json(mut schedule): Json<SyncSchedule>,
+) -> ApiResult<Json<SyncSchedule>> {
+    schedule.source_id = source_id;
+    let saved = state
+        .sync_schedule_service
+        .update_schedule(schedule)
+        .await?;
+    info!(
+        "[Connect] Updated sync schedule for '{}' (enabled: {})",
+        saved.source_id, saved.enabled
+    );
+    Ok(Json(saved))
+}
+
+/// Re-fetch activities from a date and upsert them (non-blocking, emits SSE events).
+/// Recorded as a REPAIR import run.
+async fn repair_broker_sync(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<RepairSyncRequest>,
+) -> ApiResult<StatusCode> {
+    if body.from_date > chrono::Utc::now().date_naive() {
+        return Err(ApiError::BadRequest(
+            "Repair start date cannot be in the future".to_string(),
+        ));
+    }
+    let source_id = body
+        .source_id
+        .unwrap_or_else(|| CONNECT_SYNC_SOURCE.to_string());
+    // Rejects unknown sources before spawning
+    state.sync_schedule_service.get_schedule(&source_id)?;
+
+    let config = SyncConfig {
+        repair_from: Some(body.from_date),
+        account_ids: body.account_id.map(|id| vec![id]),
+        ..state.sync_schedule_service.manual_sync_config(&source_id)
+    };
+
+    info!(
+        "[Connect] Starting repair sync for '{}' from {}...",
+        source_id, body.from_date
+    );
+    tokio::spawn(async move {
+        match perform_source_sync(&state, &source_id, config).await {
+            Ok(Some(_result)) => info!("[Connect] Repair sync for '{}' completed", source_id),
+            Ok(None) => info!(
+                "[Connect] Repair sync for '{}' skipped: source not configured",
+                source_id
+            ),
+            Err(err) => error!("[Connect] Repair sync for '{}' failed: {}", source_id, err),
+        }
+    });
+
+    Ok(StatusCode::ACCEPTED)
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Local Data Queries (from local database, not cloud)
 // ─────────────────────────────────────────────────────────────────────────────
@@ -1031,7 +1281,7 @@ async fn get_device_sync_state(
     ensure_device_sync_enabled()?;
     info!("[Connect] Getting device sync state...");
     // Ensure store has a fresh access token for DeviceEnrollService (reads from store directly).
-    mint_access_token(&state).await?;
+    super::device_sync::get_access_token(&state).await?;
 
     let result = state
         .device_enroll_service
@@ -1048,7 +1298,7 @@ async fn enable_device_sync(
 ) -> ApiResult<Json<EnableSyncResult>> {
     ensure_device_sync_enabled()?;
     info!("[Connect] Enabling device sync...");
-    mint_access_token(&state).await?;
+    super::device_sync::get_access_token(&state).await?;
 
     let result = state
         .device_enroll_service
@@ -1089,13 +1339,32 @@ async fn clear_device_sync_data(State(state): State<Arc<AppState>>) -> ApiResult
     Ok(Json(()))
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeviceSyncRelayInfo {
+    base_url: String,
+    from_env: bool,
+}
+
+/// Get the self-hosted sync relay this server syncs through, if any.
+/// The server reads it from `WF_DEVICE_SYNC_RELAY_URL`/`WF_DEVICE_SYNC_RELAY_TOKEN`.
+async fn get_device_sync_relay() -> ApiResult<Json<Option<DeviceSyncRelayInfo>>> {
+    ensure_device_sync_enabled()?;
+    Ok(Json(crate::features::device_sync_relay().map(|relay| {
+        DeviceSyncRelayInfo {
+            base_url: relay.base_url,
+            from_env: true,
+        }
+    })))
+}
+
 /// Reinitialize device sync - resets server data and enables sync in one operation
 async fn reinitialize_device_sync(
     State(state): State<Arc<AppState>>,
 ) -> ApiResult<Json<EnableSyncResult>> {
     ensure_device_sync_enabled()?;
     info!("[Connect] Reinitializing device sync...");
-    mint_access_token(&state).await?;
+    super::device_sync::get_access_token(&state).await?;
 
     let result = state
         .device_enroll_service
@@ -1312,6 +1581,27 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/connect/platforms", get(get_platforms))
         .route("/connect/sync-states", get(get_broker_sync_states))
         .route("/connect/import-runs", get(get_import_runs))
+        // Scheduled sync & repair
+        .route("/connect/sync-schedules", get(get_sync_schedules))
+        .route(
+            "/connect/sync-schedules/{source_id}",
+            put(update_sync_schedule),
+        )
+        .route("/connect/sync/repair", post(repair_broker_sync))
+        .route(
+            "/connect/reconcile/{account_id}",
+            post(reconcile_broker_account),
+        )
+        // Local connectors
+        .route("/connect/local-connectors", get(list_local_connectors))
+        .route(
+            "/connect/local-connectors/{connector_id}",
+            put(configure_local_connector).delete(remove_local_connector),
+        )
+        .route(
+            "/connect/local-connectors/{connector_id}/sync",
+            post(sync_local_connector),
+        )
         // User & Subscription
         .route("/connect/plans", get(get_subscription_plans))
         .route("/connect/plans/public", get(get_subscription_plans_public))
@@ -1320,6 +1610,7 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/connect/device/sync-state", get(get_device_sync_state))
         .route("/connect/device/enable", post(enable_device_sync))
         .route("/connect/device/sync-data", delete(clear_device_sync_data))
+        .route("/connect/device/relay", get(get_device_sync_relay))
         .route(
             "/connect/device/reinitialize",
             post(reinitialize_device_sync),
@@ -19,10 +19,10 @@ use crate::main_lib::AppState;
 use wealthfolio_device_sync::{
     ClaimPairingRequest, ClaimPairingResponse, CommitInitializeKeysRequest,
     CommitInitializeKeysResponse, CommitRotateKeysRequest, CommitRotateKeysResponse,
-    CompletePairingRequest, ConfirmPairingRequest, ConfirmPairingResponse,
-    CreatePairingRequest, CreatePairingResponse, Device, DeviceSyncClient, EnrollDeviceResponse,
-    GetPairingResponse, InitializeKeysResult, PairingMessagesResponse, RegisterDeviceRequest,
-    ResetTeamSyncResponse, RotateKeysResponse, SuccessResponse, SyncIdentity, UpdateDeviceRequest,
+    CompletePairingRequest, ConfirmPairingRequest, ConfirmPairingResponse, CreatePairingRequest,
+    CreatePairingResponse, Device, DeviceSyncClient, EnrollDeviceResponse, GetPairingResponse,
+    InitializeKeysResult, PairingMessagesResponse, RegisterDeviceRequest, ResetTeamSyncResponse,
+    RotateKeysResponse, SuccessResponse, SyncIdentity, SyncRelayConfig, UpdateDeviceRequest,
 };
 
 // Storage keys (without prefix - the SecretStore adds "wealthfolio_" prefix)
@@ -30,11 +30,15 @@ const DEVICE_ID_KEY: &str = "sync_device_id";
 const SYNC_IDENTITY_KEY: &str = "sync_identity";
 
 fn cloud_api_base_url() -> String {
-    crate::features::cloud_api_base_url().unwrap_or_default()
+    crate::features::device_sync_api_base_url().unwrap_or_default()
 }
 
-/// Get a fresh access token by refreshing via the stored refresh token.
-async fn get_access_token(state: &AppState) -> ApiResult<String> {
+/// Get the token for device sync calls: the relay token when syncing through a
+/// self-hosted relay, otherwise a fresh Connect access token.
+pub(crate) async fn get_access_token(state: &AppState) -> ApiResult<String> {
+    if let Some(relay) = crate::features::device_sync_relay() {
+        return Ok(relay.token);
+    }
     super::connect::mint_access_token(state).await
 }
 
@@ -82,9 +86,16 @@ fn get_device_id(state: &AppState) -> Option<String> {
     }
 }
 
-/// Create a device sync client.
-fn create_client() -> DeviceSyncClient {
-    DeviceSyncClient::new(&cloud_api_base_url())
+/// Create a device sync client. Through a relay it carries the device token
+/// issued at enrollment.
+pub(crate) fn create_client(state: &AppState) -> DeviceSyncClient {
+    let client = DeviceSyncClient::new(&cloud_api_base_url());
+    if crate::features::device_sync_relay().is_none() {
+        return client;
+    }
+    client.with_device_token(SyncRelayConfig::load_device_token(
+        state.secret_store.as_ref(),
+    ))
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -203,7 +214,7 @@ async fn register_device(
     info!("[DeviceSync] Registering device: {}", body.display_name);
 
     let token = get_access_token(&state).await?;
-    let client = create_client();
+    let client = create_client(&state);
 
     let request = RegisterDeviceRequest {
         device_nonce: body.instance_id, // Map instance_id to device_nonce
@@ -217,13 +228,12 @@ async fn register_device(
         .enroll_device(&token, request)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
+    if let Some(device_token) = result.device_token() {
+        SyncRelayConfig::save_device_token(state.secret_store.as_ref(), device_token)
+            .map_err(ApiError::Internal)?;
+    }
 
-    // Extract device_id from the discriminated union response
-    let device_id = match &result {
-        EnrollDeviceResponse::Bootstrap { device_id, .. } => device_id,
-        EnrollDeviceResponse::Pair { device_id, .. } => device_id,
-        EnrollDeviceResponse::Ready { device_id, .. } => device_id,
-    };
+    let device_id = result.device_id();
 
     // Store the device ID
     info!("[DeviceSync] Storing device ID: {}", device_id);
@@ -242,7 +252,7 @@ async fn get_device_endpoint(
 ) -> ApiResult<Json<Device>> {
     let token = get_access_token(&state).await?;
 
-    let device = create_client()
+    let device = create_client(&state)
         .get_device(&token, &device_id)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
@@ -255,7 +265,7 @@ async fn get_current_device(State(state): State<Arc<AppState>>) -> ApiResult<Jso
     let device_id = get_device_id(&state)
         .ok_or_else(|| ApiError::BadRequest("No device ID configured".to_string()))?;
 
-    let device = create_client()
+    let device = create_client(&state)
         .get_device(&token, &device_id)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
@@ -271,7 +281,7 @@ async fn list_devices(
 
     let token = get_access_token(&state).await?;
 
-    let devices = create_client()
+    let devices = create_client(&state)
         .list_devices(&token, query.scope.as_deref())
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
@@ -292,7 +302,7 @@ async fn update_device_endpoint(
 
     let token = get_access_token(&state).await?;
 
-    let result = create_client()
+    let result = create_client(&state)
         .update_device(
             &token,
             &device_id,
@@ -315,7 +325,7 @@ async fn delete_device_endpoint(
 
     let token = get_access_token(&state).await?;
 
-    let result = create_client()
+    let result = create_client(&state)
         .delete_device(&token, &device_id)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
@@ -331,7 +341,7 @@ async fn revoke_device_endpoint(
 
     let token = get_access_token(&state).await?;
 
-    let result = create_client()
+    let result = create_client(&state)
         .revoke_device(&token, &device_id)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
@@ -352,7 +362,7 @@ async fn initialize_team_keys(
     let device_id = get_device_id(&state)
         .ok_or_else(|| ApiError::BadRequest("No device ID configured".to_string()))?;
 
-    let result = create_client()
+    let result = create_client(&state)
         .initialize_team_keys(&token, &device_id)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
@@ -379,7 +389,7 @@ async fn commit_initialize_team_keys(
         recovery_envelope: body.recovery_envelope,
     };
 
-    let result = create_client()
+    let result = create_client(&state)
         .commit_initialize_team_keys(&token, request)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
@@ -396,7 +406,7 @@ async fn rotate_team_keys(
     let device_id = get_device_id(&state)
         .ok_or_else(|| ApiError::BadRequest("No device ID configured".to_string()))?;
 
-    let result = create_client()
+    let result = create_client(&state)
         .rotate_team_keys(&token, &device_id)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
@@ -414,7 +424,7 @@ async fn commit_rotate_team_keys(
     let device_id = get_device_id(&state)
         .ok_or_else(|| ApiError::BadRequest("No device ID configured".to_string()))?;
 
-    let result = create_client()
+    let result = create_client(&state)
         .commit_rotate_team_keys(&token, &device_id, request)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
@@ -430,7 +440,7 @@ async fn reset_team_sync(
 
     let token = get_access_token(&state).await?;
 
-    let result = create_client()
+    let result = create_client(&state)
         .reset_team_sync(&token, body.reason.as_deref())
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
@@ -452,7 +462,7 @@ async fn create_pairing(
     let device_id = get_device_id(&state)
         .ok_or_else(|| ApiError::BadRequest("No device ID configured".to_string()))?;
 
-    let result = create_client()
+    let result = create_client(&state)
         .create_pairing(
             &token,
             &device_id,
@@ -477,7 +487,7 @@ async fn get_pairing(
     let device_id = get_device_id(&state)
         .ok_or_else(|| ApiError::BadRequest("No device ID configured".to_string()))?;
 
-    let result = create_client()
+    let result = create_client(&state)
         .get_pairing(&token, &device_id, &pairing_id)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
@@ -495,7 +505,7 @@ async fn approve_pairing(
     let device_id = get_device_id(&state)
         .ok_or_else(|| ApiError::BadRequest("No device ID configured".to_string()))?;
 
-    let result = create_client()
+    let result = create_client(&state)
         .approve_pairing(&token, &device_id, &pairing_id)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
@@ -517,7 +527,7 @@ async fn complete_pairing(
     let device_id = get_device_id(&state)
         .ok_or_else(|| ApiError::BadRequest("No device ID configured".to_string()))?;
 
-    let result = create_client()
+    let result = create_client(&state)
         .complete_pairing(
             &token,
             &device_id,
@@ -552,7 +562,7 @@ async fn cancel_pairing(
     let device_id = get_device_id(&state)
         .ok_or_else(|| ApiError::BadRequest("No device ID configured".to_string()))?;
 
-    let result = create_client()
+    let result = create_client(&state)
         .cancel_pairing(&token, &device_id, &pairing_id)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
@@ -574,7 +584,7 @@ async fn claim_pairing(
     let device_id = get_device_id(&state)
         .ok_or_else(|| ApiError::BadRequest("No device ID configured".to_string()))?;
 
-    let result = create_client()
+    let result = create_client(&state)
         .claim_pairing(
             &token,
             &device_id,
@@ -599,7 +609,7 @@ async fn get_pairing_messages(
     let device_id = get_device_id(&state)
         .ok_or_else(|| ApiError::BadRequest("No device ID configured".to_string()))?;
 
-    let result = create_client()
+    let result = create_client(&state)
         .get_pairing_messages(&token, &device_id, &pairing_id)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
@@ -618,7 +628,7 @@ async fn confirm_pairing_endpoint(
     let device_id = get_device_id(&state)
         .ok_or_else(|| ApiError::BadRequest("No device ID configured".to_string()))?;
 
-    let result = create_client()
+    let result = create_client(&state)
         .confirm_pairing(
             &token,
             &device_id,
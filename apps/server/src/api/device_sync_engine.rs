@@ -164,10 +164,6 @@ impl From<engine::SyncReadyReconcileResult> for SyncReconcileReadyStateResult {
     }
 }
 
-fn cloud_api_base_url() -> String {
-    crate::features::cloud_api_base_url().unwrap_or_default()
-}
-
 fn ensure_device_sync_enabled() -> Result<(), String> {
     if crate::features::device_sync_enabled() {
         Ok(())
@@ -176,8 +172,8 @@ fn ensure_device_sync_enabled() -> Result<(), String> {
     }
 }
 
-fn create_client() -> DeviceSyncClient {
-    DeviceSyncClient::new(&cloud_api_base_url())
+fn create_client(state: &AppState) -> DeviceSyncClient {
+    crate::api::device_sync::create_client(state)
 }
 
 fn get_sync_identity_from_store(state: &AppState) -> Option<SyncIdentity> {
@@ -512,7 +508,7 @@ impl SyncTransport for ServerEnginePorts {
         token: &str,
         device_id: &str,
     ) -> Result<wealthfolio_device_sync::SyncCursorResponse, TransportError> {
-        create_client()
+        create_client(&self.state)
             .get_events_cursor(token, device_id)
             .await
             .map_err(transport_err_from_sync)
@@ -524,7 +520,7 @@ impl SyncTransport for ServerEnginePorts {
         device_id: &str,
         request: SyncPushRequest,
     ) -> Result<SyncPushResponse, TransportError> {
-        create_client()
+        create_client(&self.state)
             .push_events(token, device_id, request)
             .await
             .map_err(transport_err_from_sync)
@@ -537,7 +533,7 @@ impl SyncTransport for ServerEnginePorts {
         from_cursor: Option<i64>,
         limit: Option<i64>,
     ) -> Result<SyncPullResponse, TransportError> {
-        create_client()
+        create_client(&self.state)
             .pull_events(
                 token,
                 device_id,
@@ -553,7 +549,7 @@ impl SyncTransport for ServerEnginePorts {
         token: &str,
         device_id: &str,
     ) -> Result<ReconcileReadyStateResponse, TransportError> {
-        create_client()
+        create_client(&self.state)
             .get_reconcile_ready_state(token, device_id)
             .await
             .map_err(transport_err_from_sync)
@@ -569,7 +565,7 @@ impl CredentialStore for ServerEnginePorts {
     fn get_access_token(&self) -> Result<String, String> {
         tokio::task::block_in_place(|| {
             tokio::runtime::Handle::current()
-                .block_on(crate::api::connect::mint_access_token(&self.state))
+                .block_on(crate::api::device_sync::get_access_token(&self.state))
                 .map_err(|e| e.to_string())
         })
     }
@@ -645,10 +641,10 @@ pub async fn get_pairing_source_status(
         .device_id
         .clone()
         .ok_or_else(|| "No device ID configured".to_string())?;
-    let token = crate::api::connect::mint_access_token(state)
+    let token = crate::api::device_sync::get_access_token(state)
         .await
         .map_err(|e| e.to_string())?;
-    let client = create_client();
+    let client = create_client(&state);
     let sync_state = client
         .get_device(&token, &device_id)
         .await
@@ -960,7 +956,7 @@ pub async fn sync_bootstrap_snapshot_if_needed(
         .device_id
         .clone()
         .ok_or_else(|| "No device ID configured".to_string())?;
-    let token = crate::api::connect::mint_access_token(&state)
+    let token = crate::api::device_sync::get_access_token(&state)
         .await
         .map_err(|e| e.to_string())?;
     let raw_freshness_gate = get_min_snapshot_created_at_from_store(&device_id);
@@ -995,7 +991,7 @@ pub async fn sync_bootstrap_snapshot_if_needed(
     persist_device_config_from_identity(&state, &identity, "trusted").await;
 
     let sync_repo = Arc::clone(&state.app_sync_repository);
-    let reconcile_action = create_client()
+    let reconcile_action = create_client(&state)
         .get_reconcile_ready_state(&token, &device_id)
         .await
         .ok()
@@ -1030,7 +1026,7 @@ pub async fn sync_bootstrap_snapshot_if_needed(
         );
     }
 
-    let latest = match create_client()
+    let latest = match create_client(&state)
         .get_latest_snapshot_with_cursor_fallback(&token, &device_id)
         .await
     {
@@ -1049,7 +1045,7 @@ pub async fn sync_bootstrap_snapshot_if_needed(
                         cursor: Some(sync_repo.get_cursor().map_err(|e| e.to_string())?),
                     });
                 }
-                let client = create_client();
+                let client = create_client(&state);
                 match classify_missing_snapshot_disposition(&client, &token, &device_id).await {
                     MissingSnapshotDisposition::CompleteNoBootstrap { message } => {
                         sync_repo
@@ -1093,7 +1089,7 @@ pub async fn sync_bootstrap_snapshot_if_needed(
                     cursor: Some(sync_repo.get_cursor().map_err(|e| e.to_string())?),
                 });
             }
-            let client = create_client();
+            let client = create_client(&state);
             match classify_missing_snapshot_disposition(&client, &token, &device_id).await {
                 MissingSnapshotDisposition::CompleteNoBootstrap { message } => {
                     sync_repo
@@ -1138,7 +1134,7 @@ pub async fn sync_bootstrap_snapshot_if_needed(
 
     let snapshot_oplog_seq = latest.oplog_seq;
     if let Some(min_created_at) = min_snapshot_created_at.as_deref() {
-        let client = create_client();
+        let client = create_client(&state);
         if !snapshot_satisfies_freshness_gate(&client, &token, &device_id, &latest, min_created_at)
             .await?
         {
@@ -1165,7 +1161,7 @@ pub async fn sync_bootstrap_snapshot_if_needed(
         latest.covers_tables
     };
 
-    let (headers, blob) = match create_client()
+    let (headers, blob) = match create_client(&state)
         .download_snapshot(&token, &device_id, &snapshot_id)
         .await
     {
@@ -1259,11 +1255,11 @@ pub async fn generate_snapshot_now(
         .clone()
         .ok_or_else(|| "No device ID configured".to_string())?;
     let key_version = identity.key_version.unwrap_or(1).max(1);
-    let token = crate::api::connect::mint_access_token(&state)
+    let token = crate::api::device_sync::get_access_token(&state)
         .await
         .map_err(|e| e.to_string())?;
 
-    let sync_state = create_client()
+    let sync_state = create_client(&state)
         .get_device(&token, &device_id)
         .await
         .map_err(|e| e.to_string())?;
@@ -1286,7 +1282,7 @@ pub async fn generate_snapshot_now(
     }
 
     let local_cursor = state.app_sync_repository.get_cursor().ok();
-    let server_cursor = create_client()
+    let server_cursor = create_client(&state)
         .get_events_cursor(&token, &device_id)
         .await
         .map_err(|e| e.to_string())?
@@ -1295,7 +1291,7 @@ pub async fn generate_snapshot_now(
         return Err(sync_source_restore_required_error());
     }
     if let Some(cursor) = local_cursor {
-        if let Ok(Some(latest_snapshot)) = create_client()
+        if let Ok(Some(latest_snapshot)) = create_client(&state)
             .get_latest_snapshot_with_cursor_fallback(&token, &device_id)
             .await
         {
@@ -1359,7 +1355,7 @@ pub async fn generate_snapshot_now(
         base_seq,
     };
 
-    let upload_result = create_client()
+    let upload_result = create_client(&state)
         .upload_snapshot_with_cancel_flag(
             &token,
             &device_id,
@@ -1378,7 +1374,7 @@ pub async fn generate_snapshot_now(
                 ));
             }
             if is_snapshot_index_conflict(&message) {
-                let latest = create_client()
+                let latest = create_client(&state)
                     .get_latest_snapshot_with_cursor_fallback(&token, &device_id)
                     .await
                     .ok()
@@ -1446,10 +1442,10 @@ pub async fn complete_pairing_with_transfer(
     }
 
     // 3. Approve pairing (idempotent if already approved)
-    let token = crate::api::connect::mint_access_token(&state)
+    let token = crate::api::device_sync::get_access_token(&state)
         .await
         .map_err(|e| e.to_string())?;
-    let client = create_client();
+    let client = create_client(&state);
     tracing::info!("[DeviceSync] complete_pairing_with_transfer: approving pairing");
     match client
         .approve_pairing(&token, &device_id, &pairing_id)
@@ -1521,10 +1517,10 @@ pub async fn confirm_pairing_with_bootstrap(
         .device_id
         .clone()
         .ok_or_else(|| "No device ID configured".to_string())?;
-    let token = crate::api::connect::mint_access_token(&state)
+    let token = crate::api::device_sync::get_access_token(&state)
         .await
         .map_err(|e| e.to_string())?;
-    let client = create_client();
+    let client = create_client(&state);
 
     // 1. Confirm pairing via Connect API (idempotent — tolerate "already confirmed")
     tracing::info!("[DeviceSync] confirm_pairing_with_bootstrap: confirming pairing");
@@ -1669,10 +1665,10 @@ pub async fn begin_pairing_confirm(
         .device_id
         .clone()
         .ok_or_else(|| "No device ID configured".to_string())?;
-    let token = crate::api::connect::mint_access_token(&state)
+    let token = crate::api::device_sync::get_access_token(&state)
         .await
         .map_err(|e| e.to_string())?;
-    let client = create_client();
+    let client = create_client(&state);
     let runtime = &state.device_sync_runtime;
 
     // 1. Confirm pairing (idempotent)
@@ -1,11 +1,14 @@
 use std::sync::Arc;
 
+use super::reconciliation::republish_holdings_drift;
+use super::sync_conflicts::publish_sync_conflicts;
 use crate::{error::ApiResult, main_lib::AppState};
 use axum::{
     extract::State,
     routing::{get, post},
     Json, Router,
 };
+use wealthfolio_core::activities::NewActivity;
 use wealthfolio_core::health::{FixAction, HealthConfig, HealthStatus};
 
 /// Get current health status (cached or fresh check).
@@ -35,6 +38,8 @@ async fn run_health_checks_internal(
     state: &Arc<AppState>,
     base_currency: &str,
 ) -> Result<HealthStatus, anyhow::Error> {
+    publish_sync_conflicts(state).await;
+    republish_holdings_drift(state).await;
     state
         .health_service
         .run_full_checks(
@@ -124,6 +129,16 @@ async fn execute_health_fix(
         return Ok(());
     }
 
+    // Handle record_activity by creating the drafted activity (e.g., from reconciliation)
+    if action.id == "record_activity" {
+        let activity: NewActivity = serde_json::from_value(action.payload.clone())
+            .map_err(|e| anyhow::anyhow!("Invalid payload for {}: {}", action.id, e))?;
+
+        state.activity_service.create_activity(activity).await?;
+        state.health_service.resolve_published_fix(&action).await;
+        return Ok(());
+    }
+
     state.health_service.execute_fix(&action).await?;
     Ok(())
 }
@@ -12,6 +12,7 @@ use wealthfolio_core::{
     portfolio::{
         allocation::{AllocationHoldings, PortfolioAllocations},
         holdings::Holding,
+        scenario::{ScenarioRequest, ScenarioResult},
         snapshot::{
             CashBalanceInput, ManualHoldingInput, ManualSnapshotRequest, ManualSnapshotService,
             SnapshotSource,
@@ -155,6 +156,16 @@ pub async fn get_holdings_by_allocation(
     Ok(Json(result))
 }
 
+/// Simulates hypothetical activities on current holdings without persisting them.
+pub async fn simulate_scenario(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ScenarioRequest>,
+) -> ApiResult<Json<ScenarioResult>> {
+    let base = state.base_currency.read().unwrap().clone();
+    let result = state.scenario_service.simulate(request, &base).await?;
+    Ok(Json(result))
+}
+
 /// Gets snapshots for an account (all sources: CALCULATED, MANUAL_ENTRY, etc.)
 /// Optionally filtered by date range.
 pub async fn get_snapshots(
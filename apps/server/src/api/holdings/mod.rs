@@ -26,6 +26,7 @@ pub fn router() -> Router<Arc<AppState>> {
             "/allocations/holdings",
             get(handlers::get_holdings_by_allocation),
         )
+        .route("/scenarios/simulate", post(handlers::simulate_scenario))
         .route(
             "/snapshots",
             get(handlers::get_snapshots)
@@ -0,0 +1,66 @@
+//! Model Context Protocol endpoint (streamable HTTP).
+//!
+//! Clients POST one JSON-RPC message (or batch) per request with the usual
+//! bearer token and get the response back as JSON. The server has nothing to
+//! push, so it never opens an SSE stream. `?access=read-only` lets a client
+//! narrow the access configured by `WF_MCP_ACCESS`, never widen it.
+
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    error::{ApiError, ApiResult},
+    main_lib::AppState,
+};
+use wealthfolio_ai::mcp::{parse_error, McpAccess, McpServer};
+
+#[derive(Debug, Deserialize)]
+struct McpQuery {
+    access: Option<String>,
+}
+
+async fn handle_mcp(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<McpQuery>,
+    body: Bytes,
+) -> ApiResult<Response> {
+    let max_access = state.mcp_access.ok_or(ApiError::NotFound)?;
+    let access = match query.access.as_deref() {
+        Some(requested) => requested
+            .parse::<McpAccess>()
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?
+            .min(max_access),
+        None => max_access,
+    };
+
+    let message = match serde_json::from_slice::<Value>(&body) {
+        Ok(message) => message,
+        Err(e) => return Ok((StatusCode::BAD_REQUEST, Json(parse_error(e))).into_response()),
+    };
+
+    let server = McpServer::new(state.ai_chat_service.env().clone(), access);
+    Ok(match server.handle_message(message).await {
+        Some(response) => Json(response).into_response(),
+        // Notifications and responses are acknowledged without a body
+        None => StatusCode::ACCEPTED.into_response(),
+    })
+}
+
+/// No server-initiated stream is offered.
+async fn open_stream() -> StatusCode {
+    StatusCode::METHOD_NOT_ALLOWED
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/mcp", post(handle_mcp).get(open_stream))
+}
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use crate::{error::ApiResult, main_lib::AppState};
+use axum::{
+    extract::{Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use wealthfolio_core::health::HealthServiceTrait;
+use wealthfolio_core::portfolio::reconciliation::{
+    holdings_drift_health_source, BrokerStatement, HoldingsDriftCheck, ReconciliationReport,
+    ReconciliationResult, DEFAULT_DRIFT_HISTORY_LIMIT,
+};
+
+use super::shared::parse_date;
+
+/// Converts a report into health issues and publishes them to the Health Center,
+/// replacing any issues from a previous reconciliation of the same account.
+pub(crate) async fn publish_reconciliation(
+    state: &AppState,
+    report: ReconciliationReport,
+) -> ReconciliationResult {
+    let issues = state.reconciliation_service.to_health_issues(&report);
+    state
+        .health_service
+        .publish_issues(
+            &format!("reconciliation:{}", report.account_id),
+            issues.clone(),
+        )
+        .await;
+    ReconciliationResult { report, issues }
+}
+
+/// Publishes post-sync holdings drift results, replacing each account's previous drift issues.
+pub(crate) async fn publish_holdings_drift(
+    health_service: &(dyn HealthServiceTrait + Send + Sync),
+    results: &[ReconciliationResult],
+) {
+    for result in results {
+        health_service
+            .publish_issues(
+                &holdings_drift_health_source(&result.report.account_id),
+                result.issues.clone(),
+            )
+            .await;
+    }
+}
+
+/// Republishes the latest drift result per account (published issues are kept in memory).
+pub(crate) async fn republish_holdings_drift(state: &AppState) {
+    match state.holdings_drift_service.latest_results() {
+        Ok(results) => publish_holdings_drift(state.health_service.as_ref(), &results).await,
+        Err(err) => tracing::warn!("Failed to load holdings drift results: {}", err),
+    }
+}
+
+/// Reconcile broker-reported positions and cash balances.
+async fn reconcile_statement(
+    State(state): State<Arc<AppState>>,
+    Json(statement): Json<BrokerStatement>,
+) -> ApiResult<Json<ReconciliationResult>> {
+    let report = state.reconciliation_service.reconcile(statement)?;
+    Ok(Json(publish_reconciliation(&state, report).await))
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReconcileCsvRequest {
+    account_id: String,
+    /// Statement date in ISO format (YYYY-MM-DD)
+    as_of_date: String,
+    /// Raw CSV content of the broker statement
+    content: String,
+}
+
+/// Reconcile a broker statement CSV export.
+async fn reconcile_statement_csv(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<ReconcileCsvRequest>,
+) -> ApiResult<Json<ReconciliationResult>> {
+    let as_of_date = parse_date(&body.as_of_date, "asOfDate")?;
+    let report = state.reconciliation_service.reconcile_csv(
+        &body.account_id,
+        as_of_date,
+        body.content.as_bytes(),
+    )?;
+    Ok(Json(publish_reconciliation(&state, report).await))
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DriftHistoryQuery {
+    account_id: Option<String>,
+    limit: Option<i64>,
+}
+
+/// List post-sync holdings drift checks, newest first.
+async fn get_drift_history(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<DriftHistoryQuery>,
+) -> ApiResult<Json<Vec<HoldingsDriftCheck>>> {
+    let checks = state.holdings_drift_service.get_history(
+        query.account_id.as_deref(),
+        query.limit.unwrap_or(DEFAULT_DRIFT_HISTORY_LIMIT),
+    )?;
+    Ok(Json(checks))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/reconciliation/statement", post(reconcile_statement))
+        .route("/reconciliation/csv", post(reconcile_statement_csv))
+        .route("/reconciliation/drift-history", get(get_drift_history))
+}
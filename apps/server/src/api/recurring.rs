@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use crate::{error::ApiResult, main_lib::AppState};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post, put},
+    Json, Router,
+};
+use chrono::{Duration, NaiveDate, Utc};
+use wealthfolio_core::recurring::{
+    NewRecurringActivityTemplate, RecurringActivityTemplate, RecurringGenerationResult,
+    RecurringOccurrence,
+};
+
+/// Default preview horizon when no `through` date is given.
+const DEFAULT_PREVIEW_DAYS: i64 = 365;
+
+async fn get_recurring_templates(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<Vec<RecurringActivityTemplate>>> {
+    let templates = state.recurring_activity_service.get_templates()?;
+    Ok(Json(templates))
+}
+
+async fn create_recurring_template(
+    State(state): State<Arc<AppState>>,
+    Json(template): Json<NewRecurringActivityTemplate>,
+) -> ApiResult<Json<RecurringActivityTemplate>> {
+    let created = state
+        .recurring_activity_service
+        .create_template(template)
+        .await?;
+    Ok(Json(created))
+}
+
+async fn update_recurring_template(
+    Path(id): Path<String>,
+    State(state): State<Arc<AppState>>,
+    Json(template): Json<RecurringActivityTemplate>,
+) -> ApiResult<Json<RecurringActivityTemplate>> {
+    let updated = state
+        .recurring_activity_service
+        .update_template(RecurringActivityTemplate { id, ..template })
+        .await?;
+    Ok(Json(updated))
+}
+
+async fn delete_recurring_template(
+    Path(id): Path<String>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<StatusCode> {
+    state
+        .recurring_activity_service
+        .delete_template(&id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(serde::Deserialize)]
+struct OccurrencesQuery {
+    through: Option<NaiveDate>,
+}
+
+async fn preview_recurring_occurrences(
+    Path(id): Path<String>,
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<OccurrencesQuery>,
+) -> ApiResult<Json<Vec<RecurringOccurrence>>> {
+    let through = q
+        .through
+        .unwrap_or_else(|| Utc::now().date_naive() + Duration::days(DEFAULT_PREVIEW_DAYS));
+    let occurrences = state
+        .recurring_activity_service
+        .preview_occurrences(&id, through)?;
+    Ok(Json(occurrences))
+}
+
+#[derive(serde::Deserialize, Default)]
+struct GenerateRequest {
+    #[serde(rename = "asOf")]
+    as_of: Option<NaiveDate>,
+}
+
+async fn generate_recurring_activities(
+    State(state): State<Arc<AppState>>,
+    body: Option<Json<GenerateRequest>>,
+) -> ApiResult<Json<RecurringGenerationResult>> {
+    let request = body.map(|Json(inner)| inner).unwrap_or_default();
+    let as_of = request.as_of.unwrap_or_else(|| Utc::now().date_naive());
+    // Created activities emit ActivitiesChanged, which triggers recalculation
+    let result = state.recurring_activity_service.generate_due(as_of).await?;
+    Ok(Json(result))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/recurring-activities",
+            get(get_recurring_templates).post(create_recurring_template),
+        )
+        .route(
+            "/recurring-activities/generate",
+            post(generate_recurring_activities),
+        )
+        .route(
+            "/recurring-activities/{id}",
+            put(update_recurring_template).delete(delete_recurring_template),
+        )
+        .route(
+            "/recurring-activities/{id}/occurrences",
+            get(preview_recurring_occurrences),
+        )
+}
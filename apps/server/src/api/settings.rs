@@ -21,6 +21,9 @@ use wealthfolio_core::{
     quotes::MarketSyncMode,
     settings::{Settings, SettingsServiceTrait, SettingsUpdate},
 };
+use wealthfolio_storage_sqlite::backup::{
+    BackupArchiveInfo, BackupRunResult, BackupSchedule, BackupScheduleUpdate, RestorePreview,
+};
 use wealthfolio_storage_sqlite::db;
 
 async fn get_settings(State(state): State<Arc<AppState>>) -> ApiResult<Json<Settings>> {
@@ -226,9 +229,14 @@ async fn backup_database_route(
     State(state): State<Arc<AppState>>,
 ) -> ApiResult<Json<BackupDatabaseResponse>> {
     let data_root = state.data_root.clone();
-    let backup_path = task::spawn_blocking(move || db::backup_database(&data_root))
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to execute backup task: {}", e))??;
+    let attachment_store = state.attachment_store.clone();
+    let backup_path = task::spawn_blocking(move || -> anyhow::Result<String> {
+        let backup_path = db::backup_database(&data_root)?;
+        attachment_store.pack_archive(&backup_path)?;
+        Ok(backup_path)
+    })
+    .await
+    .map_err(|e| anyhow::anyhow!("Failed to execute backup task: {}", e))??;
 
     let filename = StdPath::new(&backup_path)
         .file_name()
@@ -262,6 +270,7 @@ async fn backup_database_to_path_route(
     Json(body): Json<BackupToPathBody>,
 ) -> ApiResult<Json<BackupToPathResponse>> {
     let data_root = state.data_root.clone();
+    let attachment_store = state.attachment_store.clone();
     let target_dir = body.backup_dir.clone();
 
     let backup_path = task::spawn_blocking(move || -> anyhow::Result<String> {
@@ -278,6 +287,9 @@ async fn backup_database_to_path_route(
 
         db::backup_database_to_file(&data_root, &backup_path_str)
             .with_context(|| format!("Failed to create backup file {}", backup_path_str))?;
+        attachment_store
+            .pack_archive(&backup_path_str)
+            .with_context(|| format!("Failed to add attachments to {}", backup_path_str))?;
 
         Ok(backup_path_str)
     })
@@ -299,10 +311,17 @@ async fn restore_database_route(
     Json(body): Json<RestoreBody>,
 ) -> ApiResult<StatusCode> {
     let data_root = state.data_root.clone();
-    task::spawn_blocking(move || {
+    let attachment_store = state.attachment_store.clone();
+    task::spawn_blocking(move || -> anyhow::Result<()> {
         let normalized_path = normalize_file_path(&body.backup_file_path);
         db::restore_database_safe(&data_root, &normalized_path)
-            .with_context(|| format!("Failed to restore database from {}", normalized_path))
+            .with_context(|| format!("Failed to restore database from {}", normalized_path))?;
+        // Backups carry attachment contents in an archive table; move them
+        // into the file store and drop the table from the live database.
+        attachment_store
+            .unpack_archive(&db::get_db_path(&data_root), true)
+            .context("Failed to restore attachments")?;
+        Ok(())
     })
     .await
     .map_err(|e| anyhow::anyhow!("Failed to execute restore task: {}", e))??;
@@ -310,6 +329,69 @@ async fn restore_database_route(
     Ok(StatusCode::NO_CONTENT)
 }
 
+async fn get_backup_schedule(State(state): State<Arc<AppState>>) -> Json<BackupSchedule> {
+    Json(state.local_backup_service.get_schedule())
+}
+
+async fn update_backup_schedule(
+    State(state): State<Arc<AppState>>,
+    Json(update): Json<BackupScheduleUpdate>,
+) -> ApiResult<Json<BackupSchedule>> {
+    let schedule = state.local_backup_service.update_schedule(update).await?;
+    Ok(Json(schedule))
+}
+
+async fn list_backups(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<Vec<BackupArchiveInfo>>> {
+    let backups = state.local_backup_service.list_backups()?;
+    Ok(Json(backups))
+}
+
+async fn run_backup(State(state): State<Arc<AppState>>) -> ApiResult<Json<BackupRunResult>> {
+    let service = state.local_backup_service.clone();
+    let result = task::spawn_blocking(move || service.run_backup())
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to execute backup task: {}", e))??;
+    Ok(Json(result))
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupArchiveBody {
+    archive_path: String,
+    #[serde(default)]
+    passphrase: Option<String>,
+}
+
+async fn preview_backup_restore(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<BackupArchiveBody>,
+) -> ApiResult<Json<RestorePreview>> {
+    let service = state.local_backup_service.clone();
+    let preview = task::spawn_blocking(move || {
+        let path = normalize_file_path(&body.archive_path);
+        service.preview_restore(&path, body.passphrase.as_deref())
+    })
+    .await
+    .map_err(|e| anyhow::anyhow!("Failed to execute restore preview task: {}", e))??;
+    Ok(Json(preview))
+}
+
+async fn restore_backup(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<BackupArchiveBody>,
+) -> ApiResult<StatusCode> {
+    let service = state.local_backup_service.clone();
+    task::spawn_blocking(move || {
+        let path = normalize_file_path(&body.archive_path);
+        service.restore_backup(&path, body.passphrase.as_deref())
+    })
+    .await
+    .map_err(|e| anyhow::anyhow!("Failed to execute restore task: {}", e))??;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/settings", get(get_settings).put(update_settings))
@@ -325,4 +407,12 @@ pub fn router() -> Router<Arc<AppState>> {
             post(backup_database_to_path_route),
         )
         .route("/utilities/database/restore", post(restore_database_route))
+        .route(
+            "/utilities/backups/schedule",
+            get(get_backup_schedule).put(update_backup_schedule),
+        )
+        .route("/utilities/backups", get(list_backups))
+        .route("/utilities/backups/run", post(run_backup))
+        .route("/utilities/backups/preview", post(preview_backup_restore))
+        .route("/utilities/backups/restore", post(restore_backup))
 }
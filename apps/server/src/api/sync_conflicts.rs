@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use crate::{error::ApiResult, main_lib::AppState};
+use axum::{
+    extract::{Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use wealthfolio_core::sync::{
+    sync_conflicts_to_health_issues, ResolveSyncConflictRequest, SyncFieldConflict,
+    SYNC_CONFLICTS_HEALTH_SOURCE,
+};
+
+/// Publishes open sync conflicts to the Health Center, replacing the previous set.
+pub(crate) async fn publish_sync_conflicts(state: &AppState) {
+    match state.app_sync_repository.list_sync_conflicts(false) {
+        Ok(conflicts) => {
+            state
+                .health_service
+                .publish_issues(
+                    SYNC_CONFLICTS_HEALTH_SOURCE,
+                    sync_conflicts_to_health_issues(&conflicts),
+                )
+                .await;
+        }
+        Err(err) => tracing::warn!("Failed to load sync conflicts: {}", err),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListSyncConflictsQuery {
+    include_resolved: Option<bool>,
+}
+
+async fn list_sync_conflicts(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ListSyncConflictsQuery>,
+) -> ApiResult<Json<Vec<SyncFieldConflict>>> {
+    let conflicts = state
+        .app_sync_repository
+        .list_sync_conflicts(query.include_resolved.unwrap_or(false))?;
+    Ok(Json(conflicts))
+}
+
+async fn resolve_sync_conflict(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ResolveSyncConflictRequest>,
+) -> ApiResult<Json<SyncFieldConflict>> {
+    let conflict = state
+        .app_sync_repository
+        .resolve_sync_conflict(request)
+        .await?;
+    publish_sync_conflicts(&state).await;
+    Ok(Json(conflict))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/sync/conflicts", get(list_sync_conflicts))
+        .route("/sync/conflicts/resolve", post(resolve_sync_conflict))
+}
@@ -0,0 +1,763 @@
+//! Self-hosted device sync relay.
+//!
+//! Serves the `/sync/team`, `/sync/events` and `/sync/snapshots` endpoints that
+//! `DeviceSyncClient` normally calls on the Connect cloud, so desktop and mobile
+//! apps can sync through this server instead. Clients authenticate with one of
+//! the tokens in `WF_SYNC_RELAY_TOKENS`; each token is its own sync team. The
+//! relay only ever stores ciphertext produced by the clients.
+//!
+//! These routes sit outside the JWT layer because devices authenticate with a
+//! relay token, not a web session. Requests a device makes as itself also carry
+//! the device token the relay issued at enrollment, so a holder of the team
+//! token cannot act as another device.
+
+use std::collections::HashSet;
+use std::path::Path as FsPath;
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::{header::AUTHORIZATION, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::main_lib::AppState;
+use wealthfolio_device_sync::crypto::hash_sha256;
+use wealthfolio_device_sync::{
+    ClaimPairingRequest, ClaimPairingResponse, CommitInitializeKeysRequest,
+    CommitInitializeKeysResponse, CommitRotateKeysRequest, CommitRotateKeysResponse,
+    CompletePairingRequest, ConfirmPairingRequest, ConfirmPairingResponse, CreatePairingRequest,
+    CreatePairingResponse, Device, EnrollDeviceResponse, GetPairingResponse, InitializeKeysResult,
+    PairingMessagesResponse, ReconcileReadyStateResponse, RegisterDeviceRequest,
+    ResetTeamSyncResponse, RotateKeysResponse, SnapshotLatestResponse, SnapshotUploadHeaders,
+    SnapshotUploadResponse, SuccessResponse, SyncCursorResponse, SyncPullResponse, SyncPushRequest,
+    SyncPushResponse, UpdateDeviceRequest,
+};
+use wealthfolio_storage_sqlite::sync::{RelayError, SyncRelayStore};
+
+const RELAY_DB_FILE: &str = "sync-relay.db";
+const DEVICE_ID_HEADER: &str = "x-wf-device-id";
+const DEVICE_TOKEN_HEADER: &str = "x-wf-device-token";
+const SNAPSHOT_MAX_SIZE_BYTES: usize = 256 * 1024 * 1024;
+
+/// Relay storage plus the teams allowed to use it.
+pub struct SyncRelay {
+    store: SyncRelayStore,
+    /// SHA-256 of each accepted token; the hash doubles as the team id.
+    team_ids: HashSet<String>,
+}
+
+impl SyncRelay {
+    pub fn open(data_root: &FsPath, tokens: &[String]) -> anyhow::Result<Self> {
+        let store = SyncRelayStore::open(data_root.join(RELAY_DB_FILE))?;
+        Ok(Self {
+            store,
+            team_ids: tokens.iter().map(|token| hash_sha256(token)).collect(),
+        })
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Errors
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Error body in the shape `DeviceSyncClient` parses from the cloud API.
+#[derive(Serialize)]
+struct RelayErrorBody {
+    error: String,
+    code: String,
+    message: String,
+}
+
+struct RelayApiError {
+    status: StatusCode,
+    code: String,
+    message: String,
+}
+
+impl RelayApiError {
+    fn new(status: StatusCode, code: &str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code: code.to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn unauthorized() -> Self {
+        Self::new(
+            StatusCode::UNAUTHORIZED,
+            "UNAUTHORIZED",
+            "Missing or invalid relay token",
+        )
+    }
+
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "INVALID_REQUEST", message)
+    }
+}
+
+impl From<RelayError> for RelayApiError {
+    fn from(err: RelayError) -> Self {
+        let status = match &err {
+            RelayError::NotFound(_) => StatusCode::NOT_FOUND,
+            RelayError::Forbidden(_) | RelayError::Unauthenticated(_) => StatusCode::FORBIDDEN,
+            RelayError::Conflict { .. } => StatusCode::CONFLICT,
+            RelayError::Invalid(_) => StatusCode::BAD_REQUEST,
+            RelayError::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        if status.is_server_error() {
+            tracing::error!("[SyncRelay] {}", err);
+        }
+        Self::new(status, err.code(), err.to_string())
+    }
+}
+
+impl IntoResponse for RelayApiError {
+    fn into_response(self) -> Response {
+        let body = Json(RelayErrorBody {
+            error: self.code.clone(),
+            code: self.code,
+            message: self.message,
+        });
+        (self.status, body).into_response()
+    }
+}
+
+type RelayResult<T> = Result<T, RelayApiError>;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Authenticated caller: the relay and the team derived from the bearer token.
+struct Caller {
+    relay: Arc<SyncRelay>,
+    team_id: String,
+}
+
+impl Caller {
+    fn from_headers(state: &AppState, headers: &HeaderMap) -> RelayResult<Self> {
+        let relay = state
+            .sync_relay
+            .clone()
+            .ok_or_else(|| RelayApiError::new(StatusCode::NOT_FOUND, "NOT_FOUND", "Not Found"))?;
+        let token = headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .ok_or_else(RelayApiError::unauthorized)?;
+        let team_id = hash_sha256(token);
+        if !relay.team_ids.contains(&team_id) {
+            return Err(RelayApiError::unauthorized());
+        }
+        Ok(Self { relay, team_id })
+    }
+
+    /// Runs a store operation off the async runtime.
+    async fn run<T, F>(self, f: F) -> RelayResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&SyncRelayStore, &str) -> Result<T, RelayError> + Send + 'static,
+    {
+        tokio::task::spawn_blocking(move || f(&self.relay.store, &self.team_id))
+            .await
+            .map_err(|e| RelayError::Storage(format!("Relay task failed: {}", e)))?
+            .map_err(RelayApiError::from)
+    }
+
+    /// Runs a store operation as the device named in the request headers,
+    /// after checking its device token. `claimed` is the device a path or body
+    /// names as the actor; it has to be the authenticated device.
+    async fn run_as_device<T, F>(
+        self,
+        headers: &HeaderMap,
+        claimed: Option<&str>,
+        f: F,
+    ) -> RelayResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&SyncRelayStore, &str, &str) -> Result<T, RelayError> + Send + 'static,
+    {
+        let device_id = device_header(headers, DEVICE_ID_HEADER)?;
+        let device_token = device_header(headers, DEVICE_TOKEN_HEADER)?;
+        if let Some(claimed) = claimed {
+            if claimed != device_id {
+                return Err(RelayError::Unauthenticated(format!(
+                    "Request is authenticated as device {}, not {}",
+                    device_id, claimed
+                ))
+                .into());
+            }
+        }
+        self.run(move |store, team| {
+            store.authenticate_device(team, &device_id, &device_token)?;
+            f(store, team, &device_id)
+        })
+        .await
+    }
+}
+
+fn device_header(headers: &HeaderMap, name: &str) -> RelayResult<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| RelayApiError::bad_request(format!("Missing {} header", name)))
+}
+
+fn required_header(headers: &HeaderMap, name: &str) -> RelayResult<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+        .ok_or_else(|| RelayApiError::bad_request(format!("Missing header {}", name)))
+}
+
+fn parse_header<T: std::str::FromStr>(headers: &HeaderMap, name: &str) -> RelayResult<T> {
+    required_header(headers, name)?
+        .trim()
+        .parse()
+        .map_err(|_| RelayApiError::bad_request(format!("Invalid header {}", name)))
+}
+
+fn success() -> Json<SuccessResponse> {
+    Json(SuccessResponse { success: true })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Devices
+// ─────────────────────────────────────────────────────────────────────────────
+
+async fn enroll_device(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<RegisterDeviceRequest>,
+) -> RelayResult<Json<EnrollDeviceResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    let response = caller
+        .run(move |store, team| store.enroll_device(team, body))
+        .await?;
+    Ok(Json(response))
+}
+
+async fn list_devices(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> RelayResult<Json<Vec<Device>>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    Ok(Json(
+        caller.run(|store, team| store.list_devices(team)).await?,
+    ))
+}
+
+async fn get_device(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(device_id): Path<String>,
+) -> RelayResult<Json<Device>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    Ok(Json(
+        caller
+            .run(move |store, team| store.get_device(team, &device_id))
+            .await?,
+    ))
+}
+
+async fn update_device(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(device_id): Path<String>,
+    Json(body): Json<UpdateDeviceRequest>,
+) -> RelayResult<Json<SuccessResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    caller
+        .run(move |store, team| store.update_device(team, &device_id, body))
+        .await?;
+    Ok(success())
+}
+
+async fn delete_device(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(device_id): Path<String>,
+) -> RelayResult<Json<SuccessResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    caller
+        .run(move |store, team| store.delete_device(team, &device_id))
+        .await?;
+    Ok(success())
+}
+
+async fn revoke_device(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(device_id): Path<String>,
+) -> RelayResult<Json<SuccessResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    caller
+        .run(move |store, team| store.revoke_device(team, &device_id))
+        .await?;
+    Ok(success())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Team keys
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Deserialize)]
+struct InitializeKeysBody {
+    device_id: String,
+}
+
+#[derive(Deserialize)]
+struct RotateKeysBody {
+    initiator_device_id: String,
+}
+
+async fn initialize_keys(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<InitializeKeysBody>,
+) -> RelayResult<Json<InitializeKeysResult>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    Ok(Json(
+        caller
+            .run_as_device(
+                &headers,
+                Some(body.device_id.as_str()),
+                |store, team, device| store.initialize_keys(team, device),
+            )
+            .await?,
+    ))
+}
+
+async fn commit_initialize_keys(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<CommitInitializeKeysRequest>,
+) -> RelayResult<Json<CommitInitializeKeysResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    let claimed = body.device_id.clone();
+    Ok(Json(
+        caller
+            .run_as_device(&headers, Some(claimed.as_str()), move |store, team, _| {
+                store.commit_initialize_keys(team, body)
+            })
+            .await?,
+    ))
+}
+
+async fn rotate_keys(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<RotateKeysBody>,
+) -> RelayResult<Json<RotateKeysResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    Ok(Json(
+        caller
+            .run_as_device(
+                &headers,
+                Some(body.initiator_device_id.as_str()),
+                |store, team, device| store.rotate_keys(team, device),
+            )
+            .await?,
+    ))
+}
+
+async fn commit_rotate_keys(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<CommitRotateKeysRequest>,
+) -> RelayResult<Json<CommitRotateKeysResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    Ok(Json(
+        caller
+            .run_as_device(&headers, None, move |store, team, device| {
+                store.commit_rotate_keys(team, device, body)
+            })
+            .await?,
+    ))
+}
+
+async fn reset_team(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> RelayResult<Json<ResetTeamSyncResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    Ok(Json(
+        caller.run(|store, team| store.reset_team(team)).await?,
+    ))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Pairing
+// ─────────────────────────────────────────────────────────────────────────────
+
+async fn create_pairing(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(device_id): Path<String>,
+    Json(body): Json<CreatePairingRequest>,
+) -> RelayResult<Json<CreatePairingResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    Ok(Json(
+        caller
+            .run_as_device(
+                &headers,
+                Some(device_id.as_str()),
+                move |store, team, device| store.create_pairing(team, device, body),
+            )
+            .await?,
+    ))
+}
+
+async fn get_pairing(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((device_id, pairing_id)): Path<(String, String)>,
+) -> RelayResult<Json<GetPairingResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    Ok(Json(
+        caller
+            .run_as_device(
+                &headers,
+                Some(device_id.as_str()),
+                move |store, team, device| store.get_pairing(team, device, &pairing_id),
+            )
+            .await?,
+    ))
+}
+
+async fn approve_pairing(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((device_id, pairing_id)): Path<(String, String)>,
+) -> RelayResult<Json<SuccessResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    caller
+        .run_as_device(
+            &headers,
+            Some(device_id.as_str()),
+            move |store, team, device| store.approve_pairing(team, device, &pairing_id),
+        )
+        .await?;
+    Ok(success())
+}
+
+async fn complete_pairing(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((device_id, pairing_id)): Path<(String, String)>,
+    Json(body): Json<CompletePairingRequest>,
+) -> RelayResult<Json<SuccessResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    caller
+        .run_as_device(
+            &headers,
+            Some(device_id.as_str()),
+            move |store, team, device| store.complete_pairing(team, device, &pairing_id, body),
+        )
+        .await?;
+    Ok(success())
+}
+
+async fn cancel_pairing(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((device_id, pairing_id)): Path<(String, String)>,
+) -> RelayResult<Json<SuccessResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    caller
+        .run_as_device(
+            &headers,
+            Some(device_id.as_str()),
+            move |store, team, device| store.cancel_pairing(team, device, &pairing_id),
+        )
+        .await?;
+    Ok(success())
+}
+
+async fn claim_pairing(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(device_id): Path<String>,
+    Json(body): Json<ClaimPairingRequest>,
+) -> RelayResult<Json<ClaimPairingResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    Ok(Json(
+        caller
+            .run_as_device(
+                &headers,
+                Some(device_id.as_str()),
+                move |store, team, device| store.claim_pairing(team, device, body),
+            )
+            .await?,
+    ))
+}
+
+async fn pairing_messages(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((device_id, pairing_id)): Path<(String, String)>,
+) -> RelayResult<Json<PairingMessagesResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    Ok(Json(
+        caller
+            .run_as_device(
+                &headers,
+                Some(device_id.as_str()),
+                move |store, team, device| store.pairing_messages(team, device, &pairing_id),
+            )
+            .await?,
+    ))
+}
+
+async fn confirm_pairing(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((device_id, pairing_id)): Path<(String, String)>,
+    Json(body): Json<ConfirmPairingRequest>,
+) -> RelayResult<Json<ConfirmPairingResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    Ok(Json(
+        caller
+            .run_as_device(
+                &headers,
+                Some(device_id.as_str()),
+                move |store, team, device| store.confirm_pairing(team, device, &pairing_id, body),
+            )
+            .await?,
+    ))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Events
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Deserialize)]
+struct PullQuery {
+    since: Option<i64>,
+    limit: Option<i64>,
+}
+
+async fn push_events(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<SyncPushRequest>,
+) -> RelayResult<Json<SyncPushResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    Ok(Json(
+        caller
+            .run_as_device(&headers, None, move |store, team, device| {
+                store.push_events(team, device, body)
+            })
+            .await?,
+    ))
+}
+
+async fn pull_events(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<PullQuery>,
+) -> RelayResult<Json<SyncPullResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    Ok(Json(
+        caller
+            .run_as_device(&headers, None, move |store, team, device| {
+                store.pull_events(team, device, query.since, query.limit)
+            })
+            .await?,
+    ))
+}
+
+async fn events_cursor(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> RelayResult<Json<SyncCursorResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    Ok(Json(
+        caller
+            .run_as_device(&headers, None, |store, team, device| {
+                store.events_cursor(team, device)
+            })
+            .await?,
+    ))
+}
+
+async fn reconcile_ready_state(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> RelayResult<Json<ReconcileReadyStateResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    Ok(Json(
+        caller
+            .run_as_device(&headers, None, |store, team, device| {
+                store.reconcile_ready_state(team, device)
+            })
+            .await?,
+    ))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Snapshots
+// ─────────────────────────────────────────────────────────────────────────────
+
+async fn latest_snapshot(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> RelayResult<Json<SnapshotLatestResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    Ok(Json(
+        caller
+            .run_as_device(&headers, None, |store, team, device| {
+                store.latest_snapshot(team, device)
+            })
+            .await?,
+    ))
+}
+
+async fn download_snapshot(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(snapshot_id): Path<String>,
+) -> RelayResult<Response> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    let (snapshot_headers, data) = caller
+        .run_as_device(&headers, None, move |store, team, device| {
+            store.get_snapshot(team, device, &snapshot_id)
+        })
+        .await?;
+
+    let mut response = data.into_response();
+    let response_headers = response.headers_mut();
+    response_headers.insert(
+        "content-type",
+        HeaderValue::from_static("application/octet-stream"),
+    );
+    response_headers.insert(
+        "x-snapshot-schema-version",
+        HeaderValue::from(snapshot_headers.schema_version),
+    );
+    for (name, value) in [
+        (
+            "x-snapshot-covers-tables",
+            snapshot_headers.covers_tables.join(","),
+        ),
+        ("x-snapshot-checksum", snapshot_headers.checksum),
+    ] {
+        let value = HeaderValue::from_str(&value)
+            .map_err(|_| RelayApiError::bad_request(format!("Invalid stored {}", name)))?;
+        response_headers.insert(name, value);
+    }
+    Ok(response)
+}
+
+async fn upload_snapshot(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> RelayResult<Json<SnapshotUploadResponse>> {
+    let caller = Caller::from_headers(&state, &headers)?;
+    let upload_headers = SnapshotUploadHeaders {
+        event_id: required_header(&headers, "x-snapshot-event-id").ok(),
+        schema_version: parse_header(&headers, "x-snapshot-schema-version")?,
+        covers_tables: required_header(&headers, "x-snapshot-covers-tables")?
+            .split(',')
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .collect(),
+        size_bytes: parse_header(&headers, "x-snapshot-size-bytes")?,
+        checksum: required_header(&headers, "x-snapshot-checksum")?,
+        metadata_payload: required_header(&headers, "x-snapshot-metadata-payload")?,
+        payload_key_version: parse_header(&headers, "x-snapshot-payload-key-version")?,
+        base_seq: parse_header(&headers, "x-snapshot-base-seq").ok(),
+    };
+    Ok(Json(
+        caller
+            .run_as_device(&headers, None, move |store, team, device| {
+                store.put_snapshot(team, device, upload_headers, body.to_vec())
+            })
+            .await?,
+    ))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    if !crate::features::device_sync_enabled() {
+        return Router::new();
+    }
+
+    Router::new()
+        // Devices
+        .route("/sync/team/devices", post(enroll_device).get(list_devices))
+        .route(
+            "/sync/team/devices/{device_id}",
+            get(get_device).patch(update_device).delete(delete_device),
+        )
+        .route("/sync/team/devices/{device_id}/revoke", post(revoke_device))
+        // Team keys
+        .route("/sync/team/keys/initialize", post(initialize_keys))
+        .route(
+            "/sync/team/keys/initialize/commit",
+            post(commit_initialize_keys),
+        )
+        .route("/sync/team/keys/rotate", post(rotate_keys))
+        .route("/sync/team/keys/rotate/commit", post(commit_rotate_keys))
+        .route("/sync/team/keys/reset", post(reset_team))
+        // Pairing
+        .route(
+            "/sync/team/devices/{device_id}/pairings",
+            post(create_pairing),
+        )
+        .route(
+            "/sync/team/devices/{device_id}/pairings/claim",
+            post(claim_pairing),
+        )
+        .route(
+            "/sync/team/devices/{device_id}/pairings/{pairing_id}",
+            get(get_pairing),
+        )
+        .route(
+            "/sync/team/devices/{device_id}/pairings/{pairing_id}/approve",
+            post(approve_pairing),
+        )
+        .route(
+            "/sync/team/devices/{device_id}/pairings/{pairing_id}/complete",
+            post(complete_pairing),
+        )
+        .route(
+            "/sync/team/devices/{device_id}/pairings/{pairing_id}/cancel",
+            post(cancel_pairing),
+        )
+        .route(
+            "/sync/team/devices/{device_id}/pairings/{pairing_id}/messages",
+            get(pairing_messages),
+        )
+        .route(
+            "/sync/team/devices/{device_id}/pairings/{pairing_id}/confirm",
+            post(confirm_pairing),
+        )
+        // Events
+        .route("/sync/events/push", post(push_events))
+        .route("/sync/events/pull", get(pull_events))
+        .route("/sync/events/cursor", get(events_cursor))
+        .route(
+            "/sync/events/reconcile-ready-state",
+            get(reconcile_ready_state),
+        )
+        // Snapshots
+        .route("/sync/snapshots/latest", get(latest_snapshot))
+        .route(
+            "/sync/snapshots/upload",
+            post(upload_snapshot).layer(DefaultBodyLimit::max(SNAPSHOT_MAX_SIZE_BYTES)),
+        )
+        .route("/sync/snapshots/{snapshot_id}", get(download_snapshot))
+}
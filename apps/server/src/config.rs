@@ -1,5 +1,7 @@
 use std::{net::SocketAddr, time::Duration};
 
+use wealthfolio_ai::McpAccess;
+
 use crate::auth::{decode_secret_key, AuthConfig};
 
 pub struct Config {
@@ -11,6 +13,10 @@ pub struct Config {
     pub addons_root: String,
     pub secret_key: String,
     pub auth: Option<AuthConfig>,
+    /// Tokens accepted by the built-in device sync relay. Empty disables the relay.
+    pub sync_relay_tokens: Vec<String>,
+    /// Access granted to MCP clients on `/api/v1/mcp`. `None` disables the endpoint.
+    pub mcp_access: Option<McpAccess>,
 }
 
 impl Config {
@@ -64,6 +70,22 @@ impl Config {
                     access_token_ttl: Duration::from_secs(ttl_minutes.saturating_mul(60)),
                 }
             });
+        let sync_relay_tokens = std::env::var("WF_SYNC_RELAY_TOKENS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        // MCP stays off unless an access level is set explicitly
+        let mcp_access = match std::env::var("WF_MCP_ACCESS") {
+            Err(_) => None,
+            Ok(value) if matches!(value.trim(), "" | "off" | "disabled" | "none") => None,
+            Ok(value) => Some(
+                value
+                    .parse()
+                    .unwrap_or_else(|e| panic!("Invalid WF_MCP_ACCESS: {e}")),
+            ),
+        };
         Self {
             listen_addr,
             db_path,
@@ -73,6 +95,8 @@ impl Config {
             addons_root,
             secret_key,
             auth,
+            sync_relay_tokens,
+            mcp_access,
         }
     }
 }
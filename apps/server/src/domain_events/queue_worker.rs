@@ -37,6 +37,10 @@ pub struct QueueWorkerDeps {
     pub fx_service: Arc<dyn wealthfolio_core::fx::FxServiceTrait + Send + Sync>,
     /// Secret store for accessing credentials (e.g., refresh tokens for broker sync)
     pub secret_store: Arc<dyn SecretStore>,
+    /// Compares broker holdings recorded during sync with recalculated snapshots
+    pub holdings_drift_service: Arc<
+        dyn wealthfolio_core::portfolio::reconciliation::HoldingsDriftServiceTrait + Send + Sync,
+    >,
 }
 
 /// Runs the event queue worker.
@@ -349,6 +353,23 @@ async fn run_portfolio_job(
         account_ids.push(PORTFOLIO_TOTAL_ACCOUNT_ID.to_string());
     }
 
+    // Snapshots now include the synced activities; compare them with the
+    // holdings brokers reported during sync.
+    match deps
+        .holdings_drift_service
+        .evaluate_pending(config.account_ids.as_deref())
+        .await
+    {
+        Ok(results) => {
+            crate::api::reconciliation::publish_holdings_drift(
+                deps.health_service.as_ref(),
+                &results,
+            )
+            .await;
+        }
+        Err(err) => tracing::warn!("Holdings drift check failed: {}", err),
+    }
+
     for account_id in account_ids {
         if let Err(err) = deps
             .valuation_service
@@ -70,6 +70,11 @@ impl WebDomainEventSink {
         account_service: Arc<wealthfolio_core::accounts::AccountService>,
         fx_service: Arc<dyn wealthfolio_core::fx::FxServiceTrait + Send + Sync>,
         secret_store: Arc<dyn SecretStore>,
+        holdings_drift_service: Arc<
+            dyn wealthfolio_core::portfolio::reconciliation::HoldingsDriftServiceTrait
+                + Send
+                + Sync,
+        >,
     ) {
         let rx = self
             .rx
@@ -89,6 +94,7 @@ impl WebDomainEventSink {
             account_service,
             fx_service,
             secret_store,
+            holdings_drift_service,
         });
 
         // Spawn the background worker
@@ -1,4 +1,5 @@
 use wealthfolio_connect::DEFAULT_CLOUD_API_URL;
+use wealthfolio_device_sync::SyncRelayConfig;
 
 pub fn connect_sync_enabled() -> bool {
     cfg!(feature = "connect-sync")
@@ -23,3 +24,17 @@ pub fn cloud_api_base_url() -> Option<String> {
         .filter(|v| !v.is_empty())
         .or_else(|| Some(DEFAULT_CLOUD_API_URL.to_string()))
 }
+
+/// Self-hosted relay this server syncs through instead of the Connect cloud.
+pub fn device_sync_relay() -> Option<SyncRelayConfig> {
+    if !device_sync_enabled() {
+        return None;
+    }
+    SyncRelayConfig::from_env()
+}
+
+pub fn device_sync_api_base_url() -> Option<String> {
+    device_sync_relay()
+        .map(|relay| relay.base_url)
+        .or_else(cloud_api_base_url)
+}
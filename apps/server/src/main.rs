@@ -47,9 +47,18 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
-    // Start background broker sync scheduler (4-hour interval)
+    // Start background broker sync scheduler (per-connection schedules)
     scheduler::start_broker_sync_scheduler(state.clone());
 
+    // Start recurring activity generation (runs once now, then hourly)
+    scheduler::start_recurring_activity_scheduler(state.clone());
+
+    // Start scheduled local backups (no-op until enabled in settings)
+    scheduler::start_backup_scheduler(state.clone());
+
+    // Start the weekly AI portfolio digest (checks now, then hourly)
+    scheduler::start_ai_digest_scheduler(state.clone());
+
     let static_dir = std::path::PathBuf::from(&config.static_dir);
     let index_file = static_dir.join("index.html");
     let static_service = ServeDir::new(static_dir).fallback(ServeFile::new(index_file));
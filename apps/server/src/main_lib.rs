@@ -3,24 +3,33 @@ use std::sync::{Arc, RwLock};
 use std::time::Instant;
 
 use crate::{
-    ai_environment::ServerAiEnvironment, auth::AuthManager, config::Config,
-    domain_events::WebDomainEventSink, events::EventBus, secrets::build_secret_store,
+    ai_environment::ServerAiEnvironment, api::sync_relay::SyncRelay, auth::AuthManager,
+    config::Config, domain_events::WebDomainEventSink, events::EventBus,
+    secrets::build_secret_store,
 };
 use tracing_subscriber::prelude::*;
 use tracing_subscriber::{fmt, EnvFilter};
-use wealthfolio_ai::{AiProviderService, AiProviderServiceTrait, ChatConfig, ChatService};
+use wealthfolio_ai::{
+    AiProviderService, AiProviderServiceTrait, ChatConfig, ChatService, McpAccess,
+};
 use wealthfolio_connect::{
     BrokerSyncService, BrokerSyncServiceTrait, CoreImportRunRepositoryAdapter,
-    ImportRunRepositoryTrait,
+    ImportRunRepositoryTrait, SyncScheduleService,
 };
 use wealthfolio_core::addons::{AddonService, AddonServiceTrait};
 use wealthfolio_core::{
     accounts::AccountService,
-    activities::{ActivityService as CoreActivityService, ActivityServiceTrait},
+    activities::{
+        ActivityDuplicateService, ActivityDuplicateServiceTrait,
+        ActivityService as CoreActivityService, ActivityServiceTrait, ActivityStagingService,
+        ActivityStagingServiceTrait,
+    },
     assets::{
         AlternativeAssetRepositoryTrait, AlternativeAssetService, AlternativeAssetServiceTrait,
         AssetClassificationService, AssetService, AssetServiceTrait,
     },
+    attachments::{load_or_create_encryption_key, AttachmentService, AttachmentServiceTrait},
+    audit::{ChangeLogService, ChangeLogServiceTrait},
     events::DomainEventSink,
     fx::{FxService, FxServiceTrait},
     goals::{GoalService, GoalServiceTrait},
@@ -34,10 +43,16 @@ use wealthfolio_core::{
             HoldingsServiceTrait,
         },
         net_worth::{NetWorthService, NetWorthServiceTrait},
+        reconciliation::{
+            HoldingsDriftService, HoldingsDriftServiceTrait, ReconciliationService,
+            ReconciliationServiceTrait,
+        },
+        scenario::{ScenarioService, ScenarioServiceTrait},
         snapshot::{SnapshotService, SnapshotServiceTrait},
         valuation::{ValuationService, ValuationServiceTrait},
     },
     quotes::{QuoteService, QuoteServiceTrait},
+    recurring::{RecurringActivityService, RecurringActivityServiceTrait},
     secrets::SecretStore,
     settings::{SettingsRepositoryTrait, SettingsService, SettingsServiceTrait},
     taxonomies::{TaxonomyService, TaxonomyServiceTrait},
@@ -45,16 +60,23 @@ use wealthfolio_core::{
 use wealthfolio_device_sync::{engine::DeviceSyncRuntimeState, DeviceEnrollService};
 use wealthfolio_storage_sqlite::{
     accounts::AccountRepository,
-    activities::ActivityRepository,
+    activities::{ActivityRepository, DuplicateDecisionRepository, StagedActivityRepository},
     ai_chat::AiChatRepository,
     assets::{AlternativeAssetRepository, AssetRepository},
+    attachments::{AttachmentRepository, FileAttachmentStore},
+    audit::ChangeLogRepository,
+    backup::LocalBackupService,
     db::{self, write_actor},
     fx::FxRepository,
     goals::GoalRepository,
     health::HealthDismissalRepository,
     limits::ContributionLimitRepository,
     market_data::{MarketDataRepository, QuoteSyncStateRepository},
-    portfolio::{snapshot::SnapshotRepository, valuation::ValuationRepository},
+    portfolio::{
+        reconciliation::HoldingsDriftRepository, snapshot::SnapshotRepository,
+        valuation::ValuationRepository,
+    },
+    recurring::RecurringActivityRepository,
     settings::SettingsRepository,
     sync::{AppSyncRepository, BrokerSyncStateRepository, ImportRunRepository, PlatformRepository},
     taxonomies::TaxonomyRepository,
@@ -77,6 +99,7 @@ pub struct AppState {
     pub holdings_service: Arc<dyn HoldingsServiceTrait + Send + Sync>,
     pub valuation_service: Arc<dyn ValuationServiceTrait + Send + Sync>,
     pub allocation_service: Arc<dyn AllocationServiceTrait + Send + Sync>,
+    pub scenario_service: Arc<dyn ScenarioServiceTrait + Send + Sync>,
     pub quote_service: Arc<dyn QuoteServiceTrait + Send + Sync>,
     pub base_currency: Arc<RwLock<String>>,
     pub snapshot_service: Arc<dyn SnapshotServiceTrait + Send + Sync>,
@@ -88,12 +111,22 @@ pub struct AppState {
     pub limits_service: Arc<dyn ContributionLimitServiceTrait + Send + Sync>,
     pub fx_service: Arc<dyn FxServiceTrait + Send + Sync>,
     pub activity_service: Arc<dyn ActivityServiceTrait + Send + Sync>,
+    pub activity_duplicate_service: Arc<dyn ActivityDuplicateServiceTrait + Send + Sync>,
+    pub activity_staging_service: Arc<dyn ActivityStagingServiceTrait + Send + Sync>,
+    pub recurring_activity_service: Arc<dyn RecurringActivityServiceTrait + Send + Sync>,
     pub asset_service: Arc<dyn AssetServiceTrait + Send + Sync>,
+    pub change_log_service: Arc<dyn ChangeLogServiceTrait + Send + Sync>,
+    pub attachment_service: Arc<dyn AttachmentServiceTrait + Send + Sync>,
+    pub attachment_store: Arc<FileAttachmentStore>,
     pub taxonomy_service: Arc<dyn TaxonomyServiceTrait + Send + Sync>,
     pub net_worth_service: Arc<dyn NetWorthServiceTrait + Send + Sync>,
+    pub reconciliation_service: Arc<dyn ReconciliationServiceTrait + Send + Sync>,
+    pub holdings_drift_service: Arc<dyn HoldingsDriftServiceTrait + Send + Sync>,
     pub alternative_asset_service: Arc<dyn AlternativeAssetServiceTrait + Send + Sync>,
     pub addon_service: Arc<dyn AddonServiceTrait + Send + Sync>,
     pub connect_sync_service: Arc<dyn BrokerSyncServiceTrait + Send + Sync>,
+    pub sync_schedule_service: Arc<SyncScheduleService>,
+    pub local_backup_service: Arc<LocalBackupService>,
     pub ai_provider_service: Arc<dyn AiProviderServiceTrait + Send + Sync>,
     pub ai_chat_service: Arc<ChatService<ServerAiEnvironment>>,
     pub data_root: String,
@@ -107,6 +140,10 @@ pub struct AppState {
     pub device_sync_runtime: Arc<DeviceSyncRuntimeState>,
     pub health_service: Arc<dyn HealthServiceTrait + Send + Sync>,
     pub token_cache: tokio::sync::RwLock<Option<CachedAccessToken>>,
+    /// Built-in device sync relay, enabled by `WF_SYNC_RELAY_TOKENS`.
+    pub sync_relay: Option<Arc<SyncRelay>>,
+    /// Access granted to MCP clients, from `WF_MCP_ACCESS`. `None` disables MCP.
+    pub mcp_access: Option<McpAccess>,
 }
 
 pub fn init_tracing() {
@@ -177,8 +214,23 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
     let asset_repository = Arc::new(AssetRepository::new(pool.clone(), writer.clone()));
     let market_data_repository = Arc::new(MarketDataRepository::new(pool.clone(), writer.clone()));
     let activity_repository = Arc::new(ActivityRepository::new(pool.clone(), writer.clone()));
+    let change_log_repository = Arc::new(ChangeLogRepository::new(pool.clone(), writer.clone()));
     let snapshot_repository = Arc::new(SnapshotRepository::new(pool.clone(), writer.clone()));
-    let app_sync_repository = Arc::new(AppSyncRepository::new(pool.clone(), writer.clone()));
+
+    // Attachment contents are encrypted at rest with a key from the secret store
+    let attachment_store = Arc::new(FileAttachmentStore::new(
+        &data_root_path.to_string_lossy(),
+        load_or_create_encryption_key(secret_store.as_ref())?,
+    ));
+    let attachment_service: Arc<dyn AttachmentServiceTrait + Send + Sync> =
+        Arc::new(AttachmentService::new(
+            Arc::new(AttachmentRepository::new(pool.clone(), writer.clone())),
+            attachment_store.clone(),
+        ));
+    let app_sync_repository = Arc::new(
+        AppSyncRepository::new(pool.clone(), writer.clone())
+            .with_attachment_store(attachment_store.clone()),
+    );
     let quote_sync_state_repository =
         Arc::new(QuoteSyncStateRepository::new(pool.clone(), writer.clone()));
 
@@ -212,7 +264,8 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
             quote_service.clone(),
             taxonomy_service.clone(),
         )?
-        .with_event_sink(domain_event_sink.clone()),
+        .with_event_sink(domain_event_sink.clone())
+        .with_change_log(change_log_repository.clone()),
     );
     let snapshot_service = Arc::new(
         SnapshotService::new(
@@ -246,6 +299,20 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
             fx_service.clone(),
         ));
 
+    let reconciliation_service: Arc<dyn ReconciliationServiceTrait + Send + Sync> =
+        Arc::new(ReconciliationService::new(
+            account_repo.clone(),
+            asset_repository.clone(),
+            snapshot_service.clone(),
+        ));
+
+    // Holdings drift: broker holdings recorded during sync, compared after recalculation
+    let holdings_drift_service: Arc<dyn HoldingsDriftServiceTrait + Send + Sync> =
+        Arc::new(HoldingsDriftService::new(
+            Arc::new(HoldingsDriftRepository::new(pool.clone(), writer.clone())),
+            reconciliation_service.clone(),
+        ));
+
     let holdings_valuation_service = Arc::new(HoldingsValuationService::new(
         fx_service.clone(),
         quote_service.clone(),
@@ -263,6 +330,16 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         AllocationService::new(holdings_service.clone(), taxonomy_service.clone()),
     );
 
+    // What-if simulations on top of current holdings
+    let scenario_service: Arc<dyn ScenarioServiceTrait + Send + Sync> =
+        Arc::new(ScenarioService::new(
+            snapshot_service.clone(),
+            holdings_service.clone(),
+            allocation_service.clone(),
+            quote_service.clone(),
+            fx_service.clone(),
+        ));
+
     let performance_service = Arc::new(
         wealthfolio_core::portfolio::performance::PerformanceService::new(
             valuation_service.clone(),
@@ -298,6 +375,8 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
     ));
     let broker_sync_state_repository =
         Arc::new(BrokerSyncStateRepository::new(pool.clone(), writer.clone()));
+    let staged_activity_repository =
+        Arc::new(StagedActivityRepository::new(pool.clone(), writer.clone()));
 
     let activity_service: Arc<dyn ActivityServiceTrait + Send + Sync> = Arc::new(
         CoreActivityService::with_import_run_repository(
@@ -306,11 +385,50 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
             asset_service.clone(),
             fx_service.clone(),
             quote_service.clone(),
+            core_import_run_repository.clone(),
+        )
+        .with_staged_activity_repository(staged_activity_repository.clone())
+        .with_event_sink(domain_event_sink.clone())
+        .with_change_log(change_log_repository.clone()),
+    );
+
+    // Staged import runs awaiting review
+    let activity_staging_service: Arc<dyn ActivityStagingServiceTrait + Send + Sync> =
+        Arc::new(ActivityStagingService::new(
+            staged_activity_repository,
             core_import_run_repository,
+            activity_service.clone(),
+            snapshot_service.clone(),
+        ));
+
+    // Change history and rollback for activities and assets
+    let change_log_service: Arc<dyn ChangeLogServiceTrait + Send + Sync> = Arc::new(
+        ChangeLogService::new(
+            change_log_repository,
+            activity_repository.clone(),
+            asset_repository.clone(),
         )
         .with_event_sink(domain_event_sink.clone()),
     );
 
+    // Fuzzy duplicate detection and review queue
+    let duplicate_decision_repository = Arc::new(DuplicateDecisionRepository::new(
+        pool.clone(),
+        writer.clone(),
+    ));
+    let activity_duplicate_service: Arc<dyn ActivityDuplicateServiceTrait + Send + Sync> = Arc::new(
+        ActivityDuplicateService::new(activity_service.clone(), duplicate_decision_repository),
+    );
+
+    // Recurring activity templates (savings plans, contributions, interest)
+    let recurring_activity_repository = Arc::new(RecurringActivityRepository::new(
+        pool.clone(),
+        writer.clone(),
+    ));
+    let recurring_activity_service: Arc<dyn RecurringActivityServiceTrait + Send + Sync> = Arc::new(
+        RecurringActivityService::new(recurring_activity_repository, activity_service.clone()),
+    );
+
     // Alternative asset repository for alternative assets operations
     let alternative_asset_repository: Arc<dyn AlternativeAssetRepositoryTrait + Send + Sync> =
         Arc::new(AlternativeAssetRepository::new(
@@ -343,12 +461,32 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
             quote_service.clone(),
         )
         .with_event_sink(domain_event_sink.clone())
-        .with_snapshot_service(snapshot_service.clone()),
+        .with_snapshot_service(snapshot_service.clone())
+        .with_duplicate_service(activity_duplicate_service.clone())
+        .with_drift_service(holdings_drift_service.clone())
+        .with_staging_service(activity_staging_service.clone()),
     );
+    let sync_schedule_service = Arc::new(SyncScheduleService::new(
+        settings_repo.clone() as Arc<dyn SettingsRepositoryTrait>
+    ));
 
     // Determine data root directory (parent of DB path)
     let data_root = data_root_path.to_string_lossy().to_string();
 
+    // Scheduled encrypted backups into a user-chosen directory
+    let local_backup_service = Arc::new(LocalBackupService::new(
+        &data_root,
+        settings_repo.clone() as Arc<dyn SettingsRepositoryTrait>,
+        secret_store.clone(),
+        attachment_store.clone(),
+    ));
+
+    // Health service for portfolio health diagnostics
+    let health_dismissal_repository =
+        Arc::new(HealthDismissalRepository::new(pool.clone(), writer.clone()));
+    let health_service: Arc<dyn HealthServiceTrait + Send + Sync> =
+        Arc::new(HealthService::new(health_dismissal_repository));
+
     // AI provider service - catalog is embedded at compile time
     let ai_catalog_json = include_str!("../../../crates/ai/src/ai_providers.json");
     let ai_provider_service: Arc<dyn AiProviderServiceTrait + Send + Sync> =
@@ -376,25 +514,41 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         allocation_service.clone(),
         performance_service.clone(),
         income_service.clone(),
+        net_worth_service.clone(),
+        health_service.clone(),
+        fx_service.clone(),
+        alternative_asset_service.clone(),
+        scenario_service.clone(),
     ));
     let ai_chat_service = Arc::new(ChatService::new(ai_environment, ChatConfig::default()));
 
     // Device enroll service for E2EE sync
-    let cloud_api_url = crate::features::cloud_api_base_url().unwrap_or_default();
+    let cloud_api_url = crate::features::device_sync_api_base_url().unwrap_or_default();
     let device_display_name = "Panorama Server".to_string();
     let app_version = Some(env!("CARGO_PKG_VERSION").to_string());
-    let device_enroll_service = Arc::new(DeviceEnrollService::new(
-        secret_store.clone(),
-        &cloud_api_url,
-        device_display_name,
-        app_version,
-    ));
+    let device_enroll_service = Arc::new(
+        DeviceEnrollService::new(
+            secret_store.clone(),
+            &cloud_api_url,
+            device_display_name,
+            app_version,
+        )
+        .with_relay(crate::features::device_sync_relay()),
+    );
 
-    // Health service for portfolio health diagnostics
-    let health_dismissal_repository =
-        Arc::new(HealthDismissalRepository::new(pool.clone(), writer.clone()));
-    let health_service: Arc<dyn HealthServiceTrait + Send + Sync> =
-        Arc::new(HealthService::new(health_dismissal_repository));
+    let sync_relay =
+        if crate::features::device_sync_enabled() && !config.sync_relay_tokens.is_empty() {
+            tracing::info!(
+                "Device sync relay enabled for {} team(s)",
+                config.sync_relay_tokens.len()
+            );
+            Some(Arc::new(SyncRelay::open(
+                &data_root_path,
+                &config.sync_relay_tokens,
+            )?))
+        } else {
+            None
+        };
 
     let event_bus = EventBus::new(256);
     let device_sync_runtime = Arc::new(DeviceSyncRuntimeState::new());
@@ -411,6 +565,7 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         account_service.clone(),
         fx_service.clone(),
         secret_store.clone(),
+        holdings_drift_service.clone(),
     );
 
     let addon_service: Arc<dyn AddonServiceTrait + Send + Sync> = Arc::new(AddonService::new(
@@ -432,6 +587,7 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         holdings_service,
         valuation_service,
         allocation_service,
+        scenario_service,
         quote_service,
         base_currency,
         snapshot_service,
@@ -442,12 +598,22 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         limits_service,
         fx_service: fx_service.clone(),
         activity_service,
+        activity_duplicate_service,
+        activity_staging_service,
+        recurring_activity_service,
         asset_service,
+        change_log_service,
+        attachment_service,
+        attachment_store,
         taxonomy_service,
         net_worth_service,
+        reconciliation_service,
+        holdings_drift_service,
         alternative_asset_service,
         addon_service,
         connect_sync_service,
+        sync_schedule_service,
+        local_backup_service,
         ai_provider_service,
         ai_chat_service,
         data_root,
@@ -461,5 +627,7 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         device_sync_runtime,
         health_service,
         token_cache: tokio::sync::RwLock::new(None),
+        sync_relay,
+        mcp_access: config.mcp_access,
     }))
 }
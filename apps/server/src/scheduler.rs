@@ -1,97 +1,147 @@
-//! Background scheduler for periodic broker sync.
+//! Background schedulers for periodic broker sync, recurring activities,
+//! local backups and the AI portfolio digest.
 //!
-//! Runs a fixed 4-hour interval sync for the Docker/Web server.
+//! Runs broker syncs on each connection's configured schedule, an hourly
+//! recurring activity generation pass, scheduled encrypted backups and the
+//! weekly AI portfolio digest for the Docker/Web server.
 
 use std::sync::Arc;
 
 #[cfg(feature = "connect-sync")]
+use async_trait::async_trait;
+use chrono::Utc;
 use tokio::time::{interval, Duration};
-#[cfg(not(feature = "connect-sync"))]
-use tracing::info;
 #[cfg(feature = "connect-sync")]
-use tracing::{debug, info, warn};
-
+use tracing::debug;
+use tracing::{info, warn};
 #[cfg(feature = "connect-sync")]
-use crate::api::connect::perform_broker_sync;
-use crate::main_lib::AppState;
+use wealthfolio_connect::{BrokerSyncScheduler, ScheduledSyncRunner, SyncConfig, SyncResult};
+use wealthfolio_storage_sqlite::backup::BackupScheduler;
 
-/// Sync interval: 4 hours (not user-configurable to prevent API abuse)
 #[cfg(feature = "connect-sync")]
-const SYNC_INTERVAL_SECS: u64 = 4 * 60 * 60;
+use crate::api::connect::perform_source_sync;
+use crate::main_lib::AppState;
 
-/// Initial delay before first sync (60 seconds to let server fully start)
-#[cfg(feature = "connect-sync")]
-const INITIAL_DELAY_SECS: u64 = 60;
+/// Recurring activity generation interval: 1 hour. Generation is idempotent,
+/// so frequent runs only matter for picking up the day change promptly.
+const RECURRING_INTERVAL_SECS: u64 = 60 * 60;
 
-/// Starts the background broker sync scheduler.
-#[cfg(feature = "connect-sync")]
-pub fn start_broker_sync_scheduler(state: Arc<AppState>) {
+/// Starts the background recurring activity scheduler.
+///
+/// The first pass runs immediately so activities due while the server was
+/// down are generated on start.
+pub fn start_recurring_activity_scheduler(state: Arc<AppState>) {
     tokio::spawn(async move {
-        info!("Broker sync scheduler started (4-hour interval)");
-
-        // Initial delay before first sync
-        tokio::time::sleep(Duration::from_secs(INITIAL_DELAY_SECS)).await;
+        info!("Recurring activity scheduler started (1-hour interval)");
 
-        // Set up periodic sync - first tick is immediate, subsequent ticks are 4h apart
-        let mut sync_interval = interval(Duration::from_secs(SYNC_INTERVAL_SECS));
+        let mut recurring_interval = interval(Duration::from_secs(RECURRING_INTERVAL_SECS));
 
         loop {
-            sync_interval.tick().await;
-            run_scheduled_sync(&state).await;
+            recurring_interval.tick().await;
+            run_recurring_generation(&state).await;
         }
     });
 }
 
-/// Starts the background broker sync scheduler.
-#[cfg(not(feature = "connect-sync"))]
-pub fn start_broker_sync_scheduler(_state: Arc<AppState>) {
-    info!("Broker sync scheduler disabled: connect-sync feature is not compiled");
+/// How often to check whether the weekly AI digest is due: 1 hour.
+const AI_DIGEST_CHECK_INTERVAL_SECS: u64 = 60 * 60;
+
+/// Starts the background AI digest scheduler.
+///
+/// The digest itself is weekly; checking hourly picks it up soon after it
+/// becomes due, and the first check runs immediately on start.
+pub fn start_ai_digest_scheduler(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        info!("AI digest scheduler started (1-hour check interval)");
+
+        let mut digest_interval = interval(Duration::from_secs(AI_DIGEST_CHECK_INTERVAL_SECS));
+
+        loop {
+            digest_interval.tick().await;
+            match state.ai_chat_service.generate_digest_if_due().await {
+                Ok(Some(thread)) => info!("Generated AI portfolio digest {}", thread.id),
+                Ok(None) => {}
+                Err(e) => warn!("AI portfolio digest failed: {}", e),
+            }
+        }
+    });
 }
 
-/// Runs a single scheduled sync operation.
-#[cfg(feature = "connect-sync")]
-async fn run_scheduled_sync(state: &Arc<AppState>) {
-    info!("Running scheduled broker sync...");
-
-    // Check if user has a refresh token configured (indicates they've logged in)
-    let has_token = state
-        .secret_store
-        .get_secret("sync_refresh_token")
-        .map(|t| t.is_some())
-        .unwrap_or(false);
-
-    if !has_token {
-        debug!("Scheduled sync skipped: no refresh token configured");
-        return;
-    }
+/// Starts the background backup scheduler, which takes encrypted backups on
+/// the configured cadence once enabled.
+pub fn start_backup_scheduler(state: Arc<AppState>) {
+    Arc::new(BackupScheduler::new(state.local_backup_service.clone())).start();
+}
 
-    // Perform the sync using the shared perform_broker_sync from api::connect
-    // This uses SyncOrchestrator which:
-    // - Emits broker:sync-start, broker:sync-complete, broker:sync-error events via SSE
-    // - Handles subscription validation internally
-    // - Syncs connections, accounts, activities, and holdings
-    match perform_broker_sync(state).await {
+/// Generates all recurring activities due as of today.
+async fn run_recurring_generation(state: &Arc<AppState>) {
+    let today = Utc::now().date_naive();
+    match state.recurring_activity_service.generate_due(today).await {
         Ok(result) => {
-            let activities_count = result
-                .activities_synced
-                .as_ref()
-                .map(|a| a.activities_upserted)
-                .unwrap_or(0);
-            info!(
-                "Scheduled broker sync completed: {} activities synced",
-                activities_count
-            );
+            if result.generated > 0 {
+                info!(
+                    "Generated {} recurring activities as of {}",
+                    result.generated, today
+                );
+            }
+            for error in &result.errors {
+                warn!("Recurring activity generation error: {}", error);
+            }
         }
-        Err(e) => {
-            // Check if this is an auth error (expected when user isn't logged in)
-            if e.contains("No refresh token")
-                || e.contains("not authenticated")
-                || e.contains("Session expired")
+        Err(e) => warn!("Recurring activity generation failed: {}", e),
+    }
+}
+
+/// Runs scheduled syncs through the server's broker sync entry points.
+#[cfg(feature = "connect-sync")]
+struct ServerSyncRunner {
+    state: Arc<AppState>,
+}
+
+#[cfg(feature = "connect-sync")]
+#[async_trait]
+impl ScheduledSyncRunner for ServerSyncRunner {
+    async fn run_sync(
+        &self,
+        source_id: &str,
+        config: SyncConfig,
+    ) -> Result<Option<SyncResult>, String> {
+        // perform_source_sync uses SyncOrchestrator, which emits the
+        // broker:sync-* SSE events and handles subscription validation.
+        match perform_source_sync(&self.state, source_id, config).await {
+            // Expected when the Connect session has lapsed
+            Err(e)
+                if e.contains("No refresh token")
+                    || e.contains("not authenticated")
+                    || e.contains("Session expired") =>
             {
-                debug!("Scheduled sync skipped: user not authenticated");
-            } else {
-                warn!("Scheduled broker sync failed: {}", e);
+                debug!(
+                    "Scheduled sync for '{}' skipped: user not authenticated",
+                    source_id
+                );
+                Ok(None)
             }
+            other => other,
         }
     }
 }
+
+/// Starts the background broker sync scheduler, which runs each connection on
+/// its configured schedule (default: every 4 hours).
+#[cfg(feature = "connect-sync")]
+pub fn start_broker_sync_scheduler(state: Arc<AppState>) {
+    let runner = Arc::new(ServerSyncRunner {
+        state: state.clone(),
+    });
+    Arc::new(BrokerSyncScheduler::new(
+        state.sync_schedule_service.clone(),
+        runner,
+    ))
+    .start();
+}
+
+/// Starts the background broker sync scheduler.
+#[cfg(not(feature = "connect-sync"))]
+pub fn start_broker_sync_scheduler(_state: Arc<AppState>) {
+    info!("Broker sync scheduler disabled: connect-sync feature is not compiled");
+}
@@ -0,0 +1,84 @@
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request},
+    Router,
+};
+use serde_json::{json, Value};
+use tempfile::tempdir;
+use tower::ServiceExt;
+use wealthfolio_server::{api::app_router, build_state, config::Config};
+
+async fn post_mcp(app: &Router, uri: &str, message: Value) -> (u16, Option<Value>) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(message.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status().as_u16();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).ok())
+}
+
+fn tool_names(response: &Value) -> Vec<String> {
+    response["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tool| tool["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn mcp_endpoint_serves_tools_with_access_levels() {
+    let tmp = tempdir().unwrap();
+    std::env::set_var("WF_DB_PATH", tmp.path().join("test.db"));
+    std::env::set_var("WF_SECRET_KEY", "!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!");
+
+    // MCP is off unless access is configured explicitly
+    std::env::remove_var("WF_MCP_ACCESS");
+    assert!(Config::from_env().mcp_access.is_none());
+
+    std::env::set_var("WF_MCP_ACCESS", "write");
+    let config = Config::from_env();
+    let state = build_state(&config).await.unwrap();
+    let app = app_router(state, &config);
+
+    let (status, body) = post_mcp(
+        &app,
+        "/api/v1/mcp",
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+    )
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(body.unwrap()["result"]["serverInfo"]["name"], "wealthfolio");
+
+    let (status, body) = post_mcp(
+        &app,
+        "/api/v1/mcp",
+        json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+    )
+    .await;
+    assert_eq!(status, 202);
+    assert!(body.is_none());
+
+    let list = json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" });
+    let (_, body) = post_mcp(&app, "/api/v1/mcp", list.clone()).await;
+    assert!(tool_names(&body.unwrap()).contains(&"record_activity".to_string()));
+
+    // Clients can narrow their own access
+    let (_, body) = post_mcp(&app, "/api/v1/mcp?access=read-only", list).await;
+    let names = tool_names(&body.unwrap());
+    assert!(names.contains(&"get_holdings".to_string()));
+    assert!(!names.contains(&"record_activity".to_string()));
+
+    for key in ["WF_DB_PATH", "WF_SECRET_KEY", "WF_MCP_ACCESS"] {
+        std::env::remove_var(key);
+    }
+}
@@ -0,0 +1,487 @@
+//! End-to-end test for the built-in device sync relay: two devices enroll and
+//! pair through the server using `DeviceSyncClient`, then `run_sync_cycle`
+//! pushes events from one device and replays them on the other.
+
+use async_trait::async_trait;
+use tempfile::tempdir;
+use tokio::sync::Mutex;
+use wealthfolio_core::sync::{
+    SyncEngineStatus, SyncEntity, SyncOperation, SyncOutboxEvent, SyncOutboxStatus,
+};
+use wealthfolio_device_sync::crypto::{hash_pairing_code, hash_sha256};
+use wealthfolio_device_sync::engine::{
+    run_sync_cycle, CredentialStore, OutboxStore, ReplayEvent, ReplayStore, SyncIdentity,
+    SyncTransport, TransportError,
+};
+use wealthfolio_device_sync::{
+    ClaimPairingRequest, CommitInitializeKeysRequest, CompletePairingRequest,
+    ConfirmPairingRequest, CreatePairingRequest, DeviceSyncClient, DeviceSyncError,
+    EnrollDeviceResponse, InitializeKeysResult, PairingStatus, ReconcileReadyStateResponse,
+    RegisterDeviceRequest, SyncCursorResponse, SyncPullResponse, SyncPushRequest, SyncPushResponse,
+    SyncState,
+};
+use wealthfolio_server::{api::app_router, build_state, config::Config};
+
+const RELAY_TOKEN: &str = "household-relay-token";
+
+/// In-memory device for the engine. Payloads pass through unencrypted since
+/// the relay treats them as opaque strings either way.
+struct TestDevice {
+    client: DeviceSyncClient,
+    device_id: String,
+    outbox: Mutex<Vec<SyncOutboxEvent>>,
+    cursor: Mutex<i64>,
+    applied: Mutex<Vec<ReplayEvent>>,
+}
+
+impl TestDevice {
+    fn new(client: DeviceSyncClient, device_id: String) -> Self {
+        Self {
+            client,
+            device_id,
+            outbox: Mutex::new(Vec::new()),
+            cursor: Mutex::new(0),
+            applied: Mutex::new(Vec::new()),
+        }
+    }
+
+    async fn queue_account(&self, name: &str) -> String {
+        let entity_id = uuid::Uuid::new_v4().to_string();
+        self.outbox.lock().await.push(SyncOutboxEvent {
+            event_id: uuid::Uuid::new_v4().to_string(),
+            entity: SyncEntity::Account,
+            entity_id: entity_id.clone(),
+            op: SyncOperation::Create,
+            client_timestamp: chrono::Utc::now().to_rfc3339(),
+            payload: serde_json::json!({ "id": entity_id, "name": name }).to_string(),
+            payload_key_version: 1,
+            sent: false,
+            status: SyncOutboxStatus::Pending,
+            retry_count: 0,
+            next_retry_at: None,
+            last_error: None,
+            last_error_code: None,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        });
+        entity_id
+    }
+}
+
+fn transport_err(e: DeviceSyncError) -> TransportError {
+    TransportError {
+        message: e.to_string(),
+        retry_class: e.retry_class(),
+        error_code: e.error_code().map(str::to_string),
+        details: None,
+    }
+}
+
+#[async_trait]
+impl OutboxStore for TestDevice {
+    async fn list_pending_outbox(&self, limit: i64) -> Result<Vec<SyncOutboxEvent>, String> {
+        let outbox = self.outbox.lock().await;
+        Ok(outbox.iter().take(limit as usize).cloned().collect())
+    }
+
+    async fn mark_outbox_dead(
+        &self,
+        event_ids: Vec<String>,
+        _error_message: Option<String>,
+        _error_code: Option<String>,
+    ) -> Result<(), String> {
+        self.outbox
+            .lock()
+            .await
+            .retain(|event| !event_ids.contains(&event.event_id));
+        Ok(())
+    }
+
+    async fn mark_outbox_sent(&self, event_ids: Vec<String>) -> Result<(), String> {
+        self.outbox
+            .lock()
+            .await
+            .retain(|event| !event_ids.contains(&event.event_id));
+        Ok(())
+    }
+
+    async fn schedule_outbox_retry(
+        &self,
+        _event_ids: Vec<String>,
+        _delay_seconds: i64,
+        _error_message: Option<String>,
+        _error_code: Option<String>,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn mark_push_completed(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn has_pending_outbox(&self) -> Result<bool, String> {
+        Ok(!self.outbox.lock().await.is_empty())
+    }
+}
+
+#[async_trait]
+impl ReplayStore for TestDevice {
+    async fn acquire_cycle_lock(&self) -> Result<i64, String> {
+        Ok(1)
+    }
+
+    async fn verify_cycle_lock(&self, _lock_version: i64) -> Result<bool, String> {
+        Ok(true)
+    }
+
+    async fn get_cursor(&self) -> Result<i64, String> {
+        Ok(*self.cursor.lock().await)
+    }
+
+    async fn set_cursor(&self, cursor: i64) -> Result<(), String> {
+        *self.cursor.lock().await = cursor;
+        Ok(())
+    }
+
+    async fn apply_remote_events_lww_batch(
+        &self,
+        events: Vec<ReplayEvent>,
+    ) -> Result<usize, String> {
+        let count = events.len();
+        self.applied.lock().await.extend(events);
+        Ok(count)
+    }
+
+    async fn apply_remote_event_lww(&self, event: ReplayEvent) -> Result<bool, String> {
+        self.applied.lock().await.push(event);
+        Ok(true)
+    }
+
+    async fn mark_pull_completed(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn mark_cycle_outcome(
+        &self,
+        _status: String,
+        _duration_ms: i64,
+        _next_retry_at: Option<String>,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn mark_engine_error(&self, _message: String) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn prune_applied_events_up_to_seq(&self, _seq: i64) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn get_engine_status(&self) -> Result<SyncEngineStatus, String> {
+        Ok(SyncEngineStatus {
+            cursor: *self.cursor.lock().await,
+            last_push_at: None,
+            last_pull_at: None,
+            last_error: None,
+            consecutive_failures: 0,
+            next_retry_at: None,
+            last_cycle_status: None,
+            last_cycle_duration_ms: None,
+        })
+    }
+}
+
+#[async_trait]
+impl SyncTransport for TestDevice {
+    async fn get_events_cursor(
+        &self,
+        token: &str,
+        device_id: &str,
+    ) -> Result<SyncCursorResponse, TransportError> {
+        self.client
+            .get_events_cursor(token, device_id)
+            .await
+            .map_err(transport_err)
+    }
+
+    async fn push_events(
+        &self,
+        token: &str,
+        device_id: &str,
+        request: SyncPushRequest,
+    ) -> Result<SyncPushResponse, TransportError> {
+        self.client
+            .push_events(token, device_id, request)
+            .await
+            .map_err(transport_err)
+    }
+
+    async fn pull_events(
+        &self,
+        token: &str,
+        device_id: &str,
+        from_cursor: Option<i64>,
+        limit: Option<i64>,
+    ) -> Result<SyncPullResponse, TransportError> {
+        self.client
+            .pull_events(
+                token,
+                device_id,
+                from_cursor,
+                limit.map(|value| value as i32),
+            )
+            .await
+            .map_err(transport_err)
+    }
+
+    async fn get_reconcile_ready_state(
+        &self,
+        token: &str,
+        device_id: &str,
+    ) -> Result<ReconcileReadyStateResponse, TransportError> {
+        self.client
+            .get_reconcile_ready_state(token, device_id)
+            .await
+            .map_err(transport_err)
+    }
+}
+
+#[async_trait]
+impl CredentialStore for TestDevice {
+    fn get_sync_identity(&self) -> Option<SyncIdentity> {
+        Some(SyncIdentity {
+            device_id: Some(self.device_id.clone()),
+            root_key: Some("unused".to_string()),
+            key_version: Some(1),
+        })
+    }
+
+    fn get_access_token(&self) -> Result<String, String> {
+        Ok(RELAY_TOKEN.to_string())
+    }
+
+    async fn get_sync_state(&self) -> Result<SyncState, String> {
+        Ok(SyncState::Ready)
+    }
+
+    async fn persist_device_config(&self, _identity: &SyncIdentity, _trust_state: &str) {}
+
+    fn encrypt_sync_payload(
+        &self,
+        plaintext_payload: &str,
+        _identity: &SyncIdentity,
+        _payload_key_version: i32,
+    ) -> Result<String, String> {
+        Ok(plaintext_payload.to_string())
+    }
+
+    fn decrypt_sync_payload(
+        &self,
+        encrypted_payload: &str,
+        _identity: &SyncIdentity,
+        _payload_key_version: i32,
+    ) -> Result<String, String> {
+        Ok(encrypted_payload.to_string())
+    }
+}
+
+fn register_request(nonce: &str, name: &str) -> RegisterDeviceRequest {
+    RegisterDeviceRequest {
+        device_nonce: nonce.to_string(),
+        display_name: name.to_string(),
+        platform: "linux".to_string(),
+        os_version: None,
+        app_version: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn devices_pair_and_sync_through_the_relay() {
+    let tmp = tempdir().unwrap();
+    std::env::set_var("WF_DB_PATH", tmp.path().join("test.db"));
+    std::env::set_var("WF_SECRET_KEY", "!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!");
+    std::env::set_var("WF_SYNC_RELAY_TOKENS", RELAY_TOKEN);
+    let config = Config::from_env();
+    let state = build_state(&config).await.unwrap();
+    let app = app_router(state, &config);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    let client = DeviceSyncClient::new(&base_url);
+
+    // Unknown tokens are rejected.
+    let err = client
+        .enroll_device("wrong-token", register_request("nonce-a", "Desktop"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.status_code(), Some(401));
+
+    // Device A is the first device and bootstraps the team key.
+    let EnrollDeviceResponse::Bootstrap {
+        device_id: device_a,
+        device_token: token_a,
+        ..
+    } = client
+        .enroll_device(RELAY_TOKEN, register_request("nonce-a", "Desktop"))
+        .await
+        .unwrap()
+    else {
+        panic!("first device should bootstrap");
+    };
+    assert!(token_a.is_some());
+    let client_a = DeviceSyncClient::new(&base_url).with_device_token(token_a);
+
+    // The team token alone does not let a request act as a device.
+    let err = client
+        .initialize_team_keys(RELAY_TOKEN, &device_a)
+        .await
+        .unwrap_err();
+    assert_eq!(err.status_code(), Some(400));
+
+    let InitializeKeysResult::Bootstrap {
+        challenge,
+        nonce,
+        key_version,
+    } = client_a
+        .initialize_team_keys(RELAY_TOKEN, &device_a)
+        .await
+        .unwrap()
+    else {
+        panic!("expected a key bootstrap challenge");
+    };
+    let envelope = "wrapped-root-key".to_string();
+    client_a
+        .commit_initialize_team_keys(
+            RELAY_TOKEN,
+            CommitInitializeKeysRequest {
+                device_id: device_a.clone(),
+                key_version,
+                signature: hash_sha256(&format!("{}:{}:{}", challenge, key_version, envelope)),
+                device_key_envelope: envelope,
+                challenge_response: Some(hash_sha256(&format!("{}:{}", challenge, nonce))),
+                recovery_envelope: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    // Device B has to pair with A before it can sync.
+    let EnrollDeviceResponse::Pair {
+        device_id: device_b,
+        device_token: token_b,
+        ..
+    } = client
+        .enroll_device(RELAY_TOKEN, register_request("nonce-b", "Phone"))
+        .await
+        .unwrap()
+    else {
+        panic!("second device should pair");
+    };
+    let client_b = DeviceSyncClient::new(&base_url).with_device_token(token_b);
+
+    // B cannot act as A: its device token does not match A's ID.
+    let code = "K7M2QX";
+    let err = client_b
+        .create_pairing(
+            RELAY_TOKEN,
+            &device_a,
+            CreatePairingRequest {
+                code_hash: hash_pairing_code(code),
+                ephemeral_public_key: "attacker-pub".to_string(),
+            },
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.status_code(), Some(403));
+    assert_eq!(err.error_code(), Some("INVALID_DEVICE_TOKEN"));
+    let err = client_b
+        .get_events_cursor(RELAY_TOKEN, &device_a)
+        .await
+        .unwrap_err();
+    assert_eq!(err.status_code(), Some(403));
+
+    let pairing = client_a
+        .create_pairing(
+            RELAY_TOKEN,
+            &device_a,
+            CreatePairingRequest {
+                code_hash: hash_pairing_code(code),
+                ephemeral_public_key: "issuer-pub".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+    let claimed = client_b
+        .claim_pairing(
+            RELAY_TOKEN,
+            &device_b,
+            ClaimPairingRequest {
+                code: code.to_string(),
+                ephemeral_public_key: "claimer-pub".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(claimed.session_id, pairing.pairing_id);
+    client_a
+        .approve_pairing(RELAY_TOKEN, &device_a, &pairing.pairing_id)
+        .await
+        .unwrap();
+    client_a
+        .complete_pairing(
+            RELAY_TOKEN,
+            &device_a,
+            &pairing.pairing_id,
+            CompletePairingRequest {
+                encrypted_key_bundle: "sealed-root-key".to_string(),
+                sas_proof: serde_json::json!("123456"),
+                signature: "hmac".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+    let messages = client_b
+        .get_pairing_messages(RELAY_TOKEN, &device_b, &pairing.pairing_id)
+        .await
+        .unwrap();
+    assert_eq!(messages.session_status, PairingStatus::Completed);
+    assert_eq!(messages.messages[0].payload, "sealed-root-key");
+    client_b
+        .confirm_pairing(
+            RELAY_TOKEN,
+            &device_b,
+            &pairing.pairing_id,
+            ConfirmPairingRequest { proof: None },
+        )
+        .await
+        .unwrap();
+
+    // A pushes, B pulls.
+    let device_a = TestDevice::new(client_a, device_a);
+    let device_b = TestDevice::new(client_b, device_b);
+    let entity_id = device_a.queue_account("Brokerage").await;
+
+    let pushed = run_sync_cycle(&device_a, false).await.unwrap();
+    assert_eq!(pushed.status, "ok");
+    assert_eq!(pushed.pushed_count, 1);
+    assert!(device_a.outbox.lock().await.is_empty());
+
+    let pulled = run_sync_cycle(&device_b, false).await.unwrap();
+    assert_eq!(pulled.status, "ok");
+    assert_eq!(pulled.pulled_count, 1);
+    let applied = device_b.applied.lock().await;
+    assert_eq!(applied[0].entity_id, entity_id);
+    assert_eq!(applied[0].payload["name"], "Brokerage");
+    drop(applied);
+
+    // Nothing new: B's next cycle is a no-op.
+    let idle = run_sync_cycle(&device_b, false).await.unwrap();
+    assert_eq!(idle.pulled_count, 0);
+
+    for key in ["WF_DB_PATH", "WF_SECRET_KEY", "WF_SYNC_RELAY_TOKENS"] {
+        std::env::remove_var(key);
+    }
+}
@@ -6,8 +6,10 @@ use log::debug;
 use tauri::State;
 use wealthfolio_core::activities::{
     Activity, ActivityBulkMutationRequest, ActivityBulkMutationResult, ActivityImport,
-    ActivitySearchResponse, ActivityUpdate, ImportActivitiesResult, ImportMappingData, NewActivity,
-    ParseConfig, ParsedCsvResult, Sort,
+    ActivitySearchResponse, ActivityUpdate, ActivityUpsert, DuplicateCandidate,
+    DuplicatePairDecision, DuplicateResolution, ImportActivitiesResult, ImportMappingData,
+    ImportRun, ImportRunReview, NewActivity, ParseConfig, ParsedCsvResult, ReviewMode, Sort,
+    StagedActivity, StagedActivityDecision,
 };
 
 #[allow(clippy::too_many_arguments)]
@@ -152,13 +154,14 @@ pub async fn check_activities_import(
 pub async fn import_activities(
     account_id: String,
     activities: Vec<ActivityImport>,
+    review_mode: Option<ReviewMode>,
     state: State<'_, Arc<ServiceContext>>,
 ) -> Result<ImportActivitiesResult, String> {
     debug!("Importing activities for account: {}", account_id);
     // Domain events handle recalculation and asset enrichment automatically
     state
         .activity_service()
-        .import_activities(account_id, activities)
+        .import_activities(account_id, activities, review_mode.unwrap_or_default())
         .await
         .map_err(|e| e.to_string())
 }
@@ -178,6 +181,101 @@ pub async fn check_existing_duplicates(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn get_duplicate_candidates(
+    account_id: Option<String>,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<DuplicateCandidate>, String> {
+    debug!("Listing duplicate candidates for account: {:?}", account_id);
+    state
+        .activity_duplicate_service()
+        .list_candidates(account_id.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn resolve_duplicate_candidate(
+    resolution: DuplicateResolution,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<DuplicatePairDecision, String> {
+    debug!(
+        "Resolving duplicate pair {} / {} as {:?}",
+        resolution.activity_id, resolution.other_activity_id, resolution.decision
+    );
+    state
+        .activity_duplicate_service()
+        .resolve(resolution)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_import_run_review(
+    import_run_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<ImportRunReview, String> {
+    debug!("Loading review for import run {}", import_run_id);
+    state
+        .activity_staging_service()
+        .get_review(&import_run_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn apply_import_run(
+    import_run_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<ImportRun, String> {
+    debug!("Applying staged import run {}", import_run_id);
+    // Domain events handle recalculation and asset enrichment automatically
+    state
+        .activity_staging_service()
+        .apply_run(&import_run_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn discard_import_run(
+    import_run_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<ImportRun, String> {
+    debug!("Discarding staged import run {}", import_run_id);
+    state
+        .activity_staging_service()
+        .discard_run(&import_run_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_staged_activity(
+    staged_id: String,
+    activity: ActivityUpsert,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<StagedActivity, String> {
+    debug!("Updating staged activity {}", staged_id);
+    state
+        .activity_staging_service()
+        .update_staged_activity(&staged_id, activity)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_staged_activity_decision(
+    staged_id: String,
+    decision: StagedActivityDecision,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<StagedActivity, String> {
+    debug!("Marking staged activity {} as {:?}", staged_id, decision);
+    state
+        .activity_staging_service()
+        .set_decision(&staged_id, decision)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn parse_csv(
     content: Vec<u8>,
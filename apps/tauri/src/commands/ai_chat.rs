@@ -4,12 +4,13 @@
 
 use std::sync::Arc;
 
+use chrono::NaiveDate;
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use tauri::{ipc::Channel, State};
 use wealthfolio_ai::{
     AiError, AiStreamEvent, ChatMessage, ChatThread, ListThreadsRequest, SendMessageRequest,
-    ThreadPage,
+    ThreadPage, UsageReport,
 };
 
 use crate::context::ServiceContext;
@@ -33,6 +34,7 @@ pub struct UpdateThreadRequest {
 /// - `reasoningDelta`: Optional reasoning/thinking content
 /// - `toolCall`: Tool invocation request
 /// - `toolResult`: Tool execution result
+/// - `usageBudgetWarning`: Month-to-date spend is near or over the budget
 /// - `error`: Error event
 /// - `done`: Terminal event with final message
 ///
@@ -216,3 +218,21 @@ pub async fn update_tool_result(
         .await?;
     Ok(message)
 }
+
+// ============================================================================
+// Usage Commands
+// ============================================================================
+
+/// Get token usage and estimated cost by day, provider, model and thread.
+///
+/// `from` and `to` are inclusive UTC days; defaults to the current month to date.
+#[tauri::command]
+pub async fn get_ai_usage_report(
+    context: State<'_, Arc<ServiceContext>>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> CommandResult<UsageReport> {
+    let service = context.ai_chat_service();
+    let report = service.get_usage_report(from, to)?;
+    Ok(report)
+}
@@ -2,8 +2,8 @@ use std::sync::Arc;
 
 use tauri::State;
 use wealthfolio_ai::{
-    AiProvidersResponse, ListModelsResponse, ProviderApiError, SetDefaultProviderRequest,
-    UpdateProviderSettingsRequest,
+    AiProvidersResponse, ListModelsResponse, ProviderApiError, SaveCustomProviderRequest,
+    SetDefaultProviderRequest, SetUsageBudgetRequest, UpdateProviderSettingsRequest,
 };
 
 use crate::context::ServiceContext;
@@ -41,6 +41,42 @@ pub async fn set_default_ai_provider(
     Ok(())
 }
 
+#[tauri::command]
+pub async fn set_ai_usage_budget(
+    context: State<'_, Arc<ServiceContext>>,
+    request: SetUsageBudgetRequest,
+) -> CommandResult<()> {
+    context
+        .ai_provider_service()
+        .set_usage_budget(request)
+        .await?;
+    Ok(())
+}
+
+/// Add or update a user-defined OpenAI-compatible provider. Returns its ID.
+#[tauri::command]
+pub async fn save_custom_ai_provider(
+    context: State<'_, Arc<ServiceContext>>,
+    request: SaveCustomProviderRequest,
+) -> CommandResult<String> {
+    Ok(context
+        .ai_provider_service()
+        .save_custom_provider(request)
+        .await?)
+}
+
+#[tauri::command]
+pub async fn delete_custom_ai_provider(
+    context: State<'_, Arc<ServiceContext>>,
+    provider_id: String,
+) -> CommandResult<()> {
+    context
+        .ai_provider_service()
+        .delete_custom_provider(&provider_id)
+        .await?;
+    Ok(())
+}
+
 /// List available models from a provider.
 /// Fetches models from the provider's API using backend-stored secrets.
 /// Frontend never needs to send API keys - they are retrieved internally.
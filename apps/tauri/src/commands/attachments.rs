@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use crate::context::ServiceContext;
+use log::debug;
+use tauri::State;
+use wealthfolio_core::attachments::{Attachment, AttachmentEntityType, NewAttachment};
+
+#[tauri::command]
+pub async fn list_attachments(
+    entity_type: AttachmentEntityType,
+    entity_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<Attachment>, String> {
+    debug!("Fetching attachments for {}...", entity_id);
+    state
+        .attachment_service()
+        .list_attachments(entity_type, &entity_id)
+        .map_err(|e| format!("Failed to load attachments: {}", e))
+}
+
+#[tauri::command]
+pub async fn upload_attachment(
+    attachment: NewAttachment,
+    content: Vec<u8>,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Attachment, String> {
+    debug!("Adding attachment {}...", attachment.file_name);
+    state
+        .attachment_service()
+        .add_attachment(attachment, content)
+        .await
+        .map_err(|e| format!("Failed to add attachment: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_attachment_content(
+    attachment_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<(Attachment, Vec<u8>), String> {
+    debug!("Reading attachment {}...", attachment_id);
+    state
+        .attachment_service()
+        .get_attachment_content(&attachment_id)
+        .map_err(|e| format!("Failed to read attachment: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_attachment(
+    attachment_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<(), String> {
+    debug!("Deleting attachment {}...", attachment_id);
+    state
+        .attachment_service()
+        .delete_attachment(&attachment_id)
+        .await
+        .map_err(|e| format!("Failed to delete attachment: {}", e))
+}
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use crate::context::ServiceContext;
+use log::debug;
+use tauri::State;
+use wealthfolio_core::audit::{ChangeEntityType, ChangeLogEntry, RollbackResult};
+
+#[tauri::command]
+pub async fn get_entity_history(
+    entity_type: ChangeEntityType,
+    entity_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<ChangeLogEntry>, String> {
+    debug!("Fetching change history for {}...", entity_id);
+    state
+        .change_log_service()
+        .get_entity_history(entity_type, &entity_id)
+        .map_err(|e| format!("Failed to load change history: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_import_run_changes(
+    import_run_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<ChangeLogEntry>, String> {
+    debug!("Fetching changes for import run {}...", import_run_id);
+    state
+        .change_log_service()
+        .get_import_run_changes(&import_run_id)
+        .map_err(|e| format!("Failed to load import run changes: {}", e))
+}
+
+#[tauri::command]
+pub async fn rollback_change(
+    change_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<RollbackResult, String> {
+    debug!("Rolling back change {}...", change_id);
+    // Rollbacks emit ActivitiesChanged, which triggers recalculation
+    state
+        .change_log_service()
+        .rollback_change(&change_id)
+        .await
+        .map_err(|e| format!("Failed to roll back change: {}", e))
+}
+
+#[tauri::command]
+pub async fn rollback_import_run(
+    import_run_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<RollbackResult, String> {
+    debug!("Rolling back import run {}...", import_run_id);
+    state
+        .change_log_service()
+        .rollback_import_run(&import_run_id)
+        .await
+        .map_err(|e| format!("Failed to roll back import run: {}", e))
+}
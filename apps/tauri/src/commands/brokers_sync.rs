@@ -6,11 +6,15 @@ use tauri::{AppHandle, Emitter, State};
 
 use crate::context::ServiceContext;
 use crate::events::{BROKER_SYNC_COMPLETE, BROKER_SYNC_ERROR, BROKER_SYNC_START};
+use crate::secret_store::KeyringSecretStore;
+use wealthfolio_connect::broker::mapping::holdings_to_statement;
 use wealthfolio_connect::{
     broker::BrokerApiClient, fetch_subscription_plans_public, BrokerAccount, BrokerConnection,
-    PlansResponse, Platform, SyncConfig, SyncOrchestrator, SyncProgressPayload,
-    SyncProgressReporter, SyncResult, UserInfo,
+    LocalConnectorInfo, LocalConnectorKind, PlansResponse, Platform, SyncConfig, SyncOrchestrator,
+    SyncProgressPayload, SyncProgressReporter, SyncResult, SyncSchedule, UserInfo,
+    CONNECT_SYNC_SOURCE,
 };
+use wealthfolio_core::portfolio::reconciliation::ReconciliationResult;
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Tauri Progress Reporter
@@ -95,7 +99,10 @@ pub async fn sync_broker_data(
 
     // Spawn background task
     tauri::async_runtime::spawn(async move {
-        match perform_broker_sync(&context, Some(&app_handle)).await {
+        let config = context
+            .sync_schedule_service()
+            .manual_sync_config(CONNECT_SYNC_SOURCE);
+        match perform_broker_sync(&context, Some(&app_handle), config).await {
             Ok(_result) => {
                 info!("[Connect] Broker sync completed successfully");
                 // Events are emitted by the orchestrator via TauriProgressReporter
@@ -128,27 +135,72 @@ pub async fn broker_ingest_run(
 ///
 /// * `context` - Service context
 /// * `app` - Optional AppHandle for progress reporting. If None, progress events are not emitted.
+/// * `config` - Sync options (history limit, repair start date, account subset)
 pub async fn perform_broker_sync(
     context: &Arc<ServiceContext>,
     app: Option<&AppHandle>,
+    config: SyncConfig,
 ) -> Result<SyncResult, String> {
     info!("Starting broker data sync...");
 
     let client = context.connect_service().get_api_client().await?;
+    run_orchestrator(context, app, &client, config).await
+}
 
-    // Create progress reporter and orchestrator
-    // Use TauriProgressReporter if we have an AppHandle, otherwise use NoOp
+/// Run the shared orchestrator against any broker client.
+/// Uses TauriProgressReporter if we have an AppHandle, otherwise NoOp.
+async fn run_orchestrator(
+    context: &Arc<ServiceContext>,
+    app: Option<&AppHandle>,
+    client: &dyn BrokerApiClient,
+    config: SyncConfig,
+) -> Result<SyncResult, String> {
     if let Some(app_handle) = app {
         let reporter = Arc::new(TauriProgressReporter::new(app_handle.clone()));
-        let orchestrator =
-            SyncOrchestrator::new(context.sync_service(), reporter, SyncConfig::default());
-        orchestrator.sync_all(&client).await
+        let orchestrator = SyncOrchestrator::new(context.sync_service(), reporter, config);
+        orchestrator.sync_all(client).await
     } else {
         let reporter = Arc::new(wealthfolio_connect::NoOpProgressReporter);
-        let orchestrator =
-            SyncOrchestrator::new(context.sync_service(), reporter, SyncConfig::default());
-        orchestrator.sync_all(&client).await
+        let orchestrator = SyncOrchestrator::new(context.sync_service(), reporter, config);
+        orchestrator.sync_all(client).await
+    }
+}
+
+/// Run a sync for a schedule source ([`CONNECT_SYNC_SOURCE`] or a local connector ID).
+///
+/// Returns `Ok(None)` when the source is not set up: the plan does not include
+/// broker sync, or no connector credentials are stored.
+pub async fn perform_source_sync(
+    context: &Arc<ServiceContext>,
+    app: Option<&AppHandle>,
+    source_id: &str,
+    config: SyncConfig,
+) -> Result<Option<SyncResult>, String> {
+    if source_id == CONNECT_SYNC_SOURCE {
+        // If we can't check the plan (no token, network error, etc.), skip silently
+        match context.connect_service().has_broker_sync().await {
+            Ok(true) => {}
+            Ok(false) => return Ok(None),
+            Err(e) => {
+                debug!(
+                    "Connect sync skipped: could not verify subscription ({})",
+                    e
+                );
+                return Ok(None);
+            }
+        }
+        return perform_broker_sync(context, app, config).await.map(Some);
     }
+
+    let kind = parse_local_connector(source_id)?;
+    let client = match wealthfolio_connect::create_local_connector(&KeyringSecretStore, kind) {
+        Ok(client) => client,
+        Err(wealthfolio_core::Error::MissingConfigKey(_)) => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+    run_orchestrator(context, app, client.as_ref(), config)
+        .await
+        .map(Some)
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -209,6 +261,52 @@ pub async fn list_broker_accounts(
     Ok(accounts)
 }
 
+/// Reconcile a synced account against the holdings currently reported by the broker
+#[tauri::command]
+pub async fn reconcile_broker_account(
+    account_id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<ReconciliationResult, String> {
+    debug!(
+        "Reconciling account {} against broker holdings...",
+        account_id
+    );
+
+    let account = state
+        .account_service()
+        .get_account(&account_id)
+        .map_err(|e| e.to_string())?;
+    let provider_account_id = account
+        .provider_account_id
+        .clone()
+        .ok_or_else(|| format!("Account {} is not linked to a broker", account_id))?;
+
+    let client = state.connect_service().get_api_client().await?;
+    let holdings = client
+        .get_account_holdings(&provider_account_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let statement = holdings_to_statement(
+        &account.id,
+        &account.currency,
+        chrono::Local::now().date_naive(),
+        holdings.balances.as_deref().unwrap_or_default(),
+        holdings.positions.as_deref().unwrap_or_default(),
+    );
+    let report = state
+        .reconciliation_service()
+        .reconcile(statement)
+        .map_err(|e| e.to_string())?;
+    info!(
+        "Reconciliation of '{}' found {} discrepancies",
+        account.name,
+        report.discrepancies.len()
+    );
+
+    Ok(crate::commands::reconciliation::publish_reconciliation(&state, report).await)
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // User & Subscription Commands
 // ─────────────────────────────────────────────────────────────────────────────
@@ -266,6 +364,146 @@ pub async fn get_user_info(state: State<'_, Arc<ServiceContext>>) -> Result<User
     }
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+// Local Connector Commands (broker APIs called directly, no Connect session)
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn parse_local_connector(connector_id: &str) -> Result<LocalConnectorKind, String> {
+    LocalConnectorKind::from_id(connector_id)
+        .ok_or_else(|| format!("Unknown local connector: {}", connector_id))
+}
+
+/// List local connectors and whether credentials are stored for them.
+#[tauri::command]
+pub async fn list_local_connectors() -> Result<Vec<LocalConnectorInfo>, String> {
+    wealthfolio_connect::list_local_connectors(&KeyringSecretStore).map_err(|e| e.to_string())
+}
+
+/// Store credentials for a local connector (for SimpleFIN, an access URL or setup token).
+#[tauri::command]
+pub async fn configure_local_connector(
+    connector_id: String,
+    credential: String,
+) -> Result<(), String> {
+    let kind = parse_local_connector(&connector_id)?;
+    wealthfolio_connect::configure_local_connector(&KeyringSecretStore, kind, &credential)
+        .await
+        .map_err(|e| e.to_string())?;
+    info!("Stored credentials for local connector '{}'", kind.id());
+    Ok(())
+}
+
+/// Remove stored credentials for a local connector.
+#[tauri::command]
+pub async fn remove_local_connector(connector_id: String) -> Result<(), String> {
+    let kind = parse_local_connector(&connector_id)?;
+    wealthfolio_connect::remove_local_connector(&KeyringSecretStore, kind)
+        .map_err(|e| e.to_string())
+}
+
+/// Sync a local connector (non-blocking, emits the same events as `sync_broker_data`).
+#[tauri::command]
+pub async fn sync_local_connector(
+    connector_id: String,
+    app: AppHandle,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<(), String> {
+    let kind = parse_local_connector(&connector_id)?;
+    let client = wealthfolio_connect::create_local_connector(&KeyringSecretStore, kind)
+        .map_err(|e| e.to_string())?;
+
+    info!("Starting local connector sync for '{}'...", kind.id());
+    let context = state.inner().clone();
+    tauri::async_runtime::spawn(async move {
+        let config = context
+            .sync_schedule_service()
+            .manual_sync_config(kind.id());
+        match run_orchestrator(&context, Some(&app), client.as_ref(), config).await {
+            Ok(_result) => info!("Local connector '{}' sync completed", kind.id()),
+            Err(err) => error!("Local connector '{}' sync failed: {}", kind.id(), err),
+        }
+    });
+
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Sync Schedule & Repair Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Get the sync schedule for every connection source.
+#[tauri::command]
+pub async fn get_sync_schedules(
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<SyncSchedule>, String> {
+    Ok(state.sync_schedule_service().list_schedules())
+}
+
+/// Update the sync schedule for one connection source.
+#[tauri::command]
+pub async fn update_sync_schedule(
+    source_id: String,
+    mut schedule: SyncSchedule,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<SyncSchedule, String> {
+    schedule.source_id = source_id;
+    let saved = state
+        .sync_schedule_service()
+        .update_schedule(schedule)
+        .await
+        .map_err(|e| e.to_string())?;
+    info!(
+        "Updated sync schedule for '{}' (enabled: {})",
+        saved.source_id, saved.enabled
+    );
+    Ok(saved)
+}
+
+/// Re-fetch activities from `from_date` and upsert them (non-blocking, emits
+/// the same events as `sync_broker_data`). Recorded as a REPAIR import run.
+#[tauri::command]
+pub async fn repair_broker_sync(
+    source_id: Option<String>,
+    from_date: chrono::NaiveDate,
+    account_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<(), String> {
+    if from_date > chrono::Utc::now().date_naive() {
+        return Err("Repair start date cannot be in the future".to_string());
+    }
+    let source_id = source_id.unwrap_or_else(|| CONNECT_SYNC_SOURCE.to_string());
+    // Rejects unknown sources before spawning
+    state
+        .sync_schedule_service()
+        .get_schedule(&source_id)
+        .map_err(|e| e.to_string())?;
+
+    let config = SyncConfig {
+        repair_from: Some(from_date),
+        account_ids: account_id.map(|id| vec![id]),
+        ..state.sync_schedule_service().manual_sync_config(&source_id)
+    };
+
+    info!(
+        "Starting repair sync for '{}' from {}...",
+        source_id, from_date
+    );
+    let context = state.inner().clone();
+    tauri::async_runtime::spawn(async move {
+        match perform_source_sync(&context, Some(&app), &source_id, config).await {
+            Ok(Some(_result)) => info!("Repair sync for '{}' completed", source_id),
+            Ok(None) => info!(
+                "Repair sync for '{}' skipped: source not configured",
+                source_id
+            ),
+            Err(err) => error!("Repair sync for '{}' failed: {}", source_id, err),
+        }
+    });
+
+    Ok(())
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Sync State and Import Run Commands
 // ─────────────────────────────────────────────────────────────────────────────
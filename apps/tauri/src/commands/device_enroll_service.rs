@@ -10,10 +10,21 @@ use crate::commands::device_sync::{
     ensure_background_engine_started, ensure_background_engine_stopped,
 };
 use crate::context::ServiceContext;
+use crate::secret_store::KeyringSecretStore;
+use wealthfolio_device_sync::SyncRelayConfig;
 
 // Re-export types for use in other modules
 pub use wealthfolio_device_sync::{EnableSyncResult, SyncState, SyncStateResult};
 
+/// Self-hosted relay the device syncs through instead of Wealthfolio Connect.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceSyncRelayInfo {
+    pub base_url: String,
+    /// Set through `WF_DEVICE_SYNC_RELAY_URL`/`WF_DEVICE_SYNC_RELAY_TOKEN`; cannot be changed in-app.
+    pub from_env: bool,
+}
+
 /// Get the current device sync state.
 /// Returns the state machine status: FRESH, REGISTERED, READY, STALE, or RECOVERY.
 #[tauri::command]
@@ -91,3 +102,57 @@ pub async fn reinitialize_device_sync(
 
     Ok(result)
 }
+
+/// Get the self-hosted sync relay, if one is configured.
+#[tauri::command]
+pub async fn get_device_sync_relay() -> Result<Option<DeviceSyncRelayInfo>, String> {
+    if let Some(relay) = SyncRelayConfig::from_env() {
+        return Ok(Some(DeviceSyncRelayInfo {
+            base_url: relay.base_url,
+            from_env: true,
+        }));
+    }
+    Ok(
+        SyncRelayConfig::load(&KeyringSecretStore).map(|relay| DeviceSyncRelayInfo {
+            base_url: relay.base_url,
+            from_env: false,
+        }),
+    )
+}
+
+/// Sync through a self-hosted relay instead of Wealthfolio Connect.
+/// The relay is a different sync team, so local sync data is cleared first.
+/// Takes effect after the app restarts.
+#[tauri::command]
+pub async fn set_device_sync_relay(
+    base_url: String,
+    token: String,
+    context: State<'_, Arc<ServiceContext>>,
+) -> Result<(), String> {
+    if SyncRelayConfig::from_env().is_some() {
+        return Err("Sync relay is configured by environment variables".to_string());
+    }
+    let relay = SyncRelayConfig::new(&base_url, &token)?;
+    reset_for_relay_change(context.inner()).await?;
+    relay.save(&KeyringSecretStore)
+}
+
+/// Go back to syncing through Wealthfolio Connect. Takes effect after the app restarts.
+#[tauri::command]
+pub async fn clear_device_sync_relay(
+    context: State<'_, Arc<ServiceContext>>,
+) -> Result<(), String> {
+    if SyncRelayConfig::from_env().is_some() {
+        return Err("Sync relay is configured by environment variables".to_string());
+    }
+    reset_for_relay_change(context.inner()).await?;
+    SyncRelayConfig::clear(&KeyringSecretStore)
+}
+
+async fn reset_for_relay_change(context: &Arc<ServiceContext>) -> Result<(), String> {
+    ensure_background_engine_stopped(Arc::clone(context)).await?;
+    context
+        .device_enroll_service()
+        .clear_sync_data()
+        .map_err(|e| e.message)
+}
@@ -243,8 +243,7 @@ impl CredentialStore for TauriEnginePorts {
 
     fn get_access_token(&self) -> Result<String, String> {
         tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current()
-                .block_on(self.context.connect_service().get_valid_access_token())
+            tokio::runtime::Handle::current().block_on(super::get_access_token(&self.context))
         })
     }
 
@@ -21,10 +21,10 @@ use wealthfolio_device_sync::engine as shared_sync_engine;
 use wealthfolio_device_sync::{
     ClaimPairingRequest, ClaimPairingResponse, CommitInitializeKeysRequest,
     CommitInitializeKeysResponse, CommitRotateKeysRequest, CommitRotateKeysResponse,
-    CompletePairingRequest, ConfirmPairingRequest, ConfirmPairingResponse,
-    CreatePairingRequest, CreatePairingResponse, Device, DevicePlatform, DeviceSyncClient,
-    EnrollDeviceResponse, GetPairingResponse, InitializeKeysResult, PairingMessagesResponse,
-    RegisterDeviceRequest, ResetTeamSyncResponse, RotateKeysResponse, SuccessResponse,
+    CompletePairingRequest, ConfirmPairingRequest, ConfirmPairingResponse, CreatePairingRequest,
+    CreatePairingResponse, Device, DevicePlatform, DeviceSyncClient, EnrollDeviceResponse,
+    GetPairingResponse, InitializeKeysResult, PairingMessagesResponse, RegisterDeviceRequest,
+    ResetTeamSyncResponse, RotateKeysResponse, SuccessResponse, SyncRelayConfig,
     UpdateDeviceRequest,
 };
 use wealthfolio_storage_sqlite::sync::SyncTableRowCount;
@@ -37,12 +37,20 @@ pub use engine::{ensure_background_engine_started, ensure_background_engine_stop
 // ─────────────────────────────────────────────────────────────────────────────
 
 fn cloud_api_base_url() -> Result<String, String> {
+    if let Some(relay) = SyncRelayConfig::resolve(&KeyringSecretStore) {
+        return Ok(relay.base_url);
+    }
     crate::services::cloud_api_base_url().ok_or_else(|| {
         "Cloud API base URL is unavailable. Device sync operations are disabled.".to_string()
     })
 }
 
+/// Token for the sync server: the relay token when a self-hosted relay is
+/// configured, otherwise the Connect session token.
 pub(super) async fn get_access_token(context: &Arc<ServiceContext>) -> Result<String, String> {
+    if let Some(relay) = SyncRelayConfig::resolve(&KeyringSecretStore) {
+        return Ok(relay.token);
+    }
     context.connect_service().get_valid_access_token().await
 }
 
@@ -257,7 +265,11 @@ async fn persist_device_config_from_identity(
 }
 
 fn create_client() -> Result<DeviceSyncClient, String> {
-    Ok(DeviceSyncClient::new(&cloud_api_base_url()?))
+    let client = DeviceSyncClient::new(&cloud_api_base_url()?);
+    if SyncRelayConfig::resolve(&KeyringSecretStore).is_none() {
+        return Ok(client);
+    }
+    Ok(client.with_device_token(SyncRelayConfig::load_device_token(&KeyringSecretStore)))
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -627,12 +639,11 @@ pub async fn enroll_device(
         .enroll_device(&token, request)
         .await
         .map_err(|e| e.to_string())?;
+    if let Some(device_token) = result.device_token() {
+        SyncRelayConfig::save_device_token(&KeyringSecretStore, device_token)?;
+    }
 
-    let device_id = match &result {
-        EnrollDeviceResponse::Bootstrap { device_id, .. } => device_id,
-        EnrollDeviceResponse::Pair { device_id, .. } => device_id,
-        EnrollDeviceResponse::Ready { device_id, .. } => device_id,
-    };
+    let device_id = result.device_id();
 
     info!(
         "[DeviceSync] Device enrolled: {} (mode: {:?})",
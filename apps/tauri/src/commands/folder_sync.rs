@@ -1,4 +1,4 @@
-//! Tauri commands for Syncthing-backed folder sync.
+//! Tauri commands for folder sync.
 
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -6,6 +6,7 @@ use std::sync::Arc;
 use chrono::Utc;
 use tauri::State;
 use uuid::Uuid;
+use wealthfolio_core::secrets::SecretStore;
 use wealthfolio_core::sync::{FolderSyncMetadataV1, FOLDER_SYNC_VERSION_V1};
 use wealthfolio_storage_sqlite::settings::SettingsRepository;
 use wealthfolio_storage_sqlite::sync::{
@@ -14,10 +15,16 @@ use wealthfolio_storage_sqlite::sync::{
 };
 
 use crate::context::ServiceContext;
+use crate::secret_store::KeyringSecretStore;
+use crate::services::folder_sync_crypto::{
+    begin_rotation, complete_rotation, create_encryption, open_shared_folder, store_key, unlock,
+    FolderSyncAccess, FolderSyncKey,
+};
 use crate::services::folder_sync_exporter::FolderSyncExporter;
 use crate::services::folder_sync_fs::FolderSyncFsService;
 use crate::services::folder_sync_importer::FolderSyncImporter;
 use crate::services::folder_sync_snapshot::FolderSyncSnapshotService;
+use crate::services::folder_sync_storage::{open_storage, FolderSyncRemoteCredentials};
 
 #[derive(Debug, Clone, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -58,6 +65,8 @@ pub struct FolderSyncHistoryEntryResult {
 #[serde(rename_all = "camelCase")]
 pub struct FolderSyncStateResult {
     pub config: Option<FolderSyncConfigResult>,
+    /// Whether the configured shared folder is encrypted with a passphrase.
+    pub is_encrypted: bool,
     pub status: FolderSyncStatusResult,
     pub history: Vec<FolderSyncHistoryEntryResult>,
 }
@@ -108,14 +117,28 @@ fn map_history(entry: FolderSyncHistoryEntryRecord) -> FolderSyncHistoryEntryRes
     }
 }
 
-pub(crate) fn get_folder_sync_state_internal(
+pub(crate) async fn get_folder_sync_state_internal(
     folder_sync_repository: Arc<FolderSyncRepository>,
+    secret_store: &dyn SecretStore,
 ) -> Result<FolderSyncStateResult, String> {
+    let config = folder_sync_repository
+        .get_config()
+        .map_err(|err| err.to_string())?;
+    let is_encrypted = match config.as_ref() {
+        Some(config) => match open_folder(&config.shared_folder_path, secret_store) {
+            Ok(fs_service) => fs_service
+                .read_metadata()
+                .await
+                .ok()
+                .flatten()
+                .is_some_and(|metadata| metadata.encryption.is_some()),
+            Err(_) => false,
+        },
+        None => false,
+    };
     Ok(FolderSyncStateResult {
-        config: folder_sync_repository
-            .get_config()
-            .map_err(|err| err.to_string())?
-            .map(map_config),
+        config: config.map(map_config),
+        is_encrypted,
         status: map_status(
             folder_sync_repository
                 .get_status()
@@ -130,13 +153,47 @@ pub(crate) fn get_folder_sync_state_internal(
     })
 }
 
+fn open_folder(
+    shared_folder_path: &str,
+    secret_store: &dyn SecretStore,
+) -> Result<FolderSyncFsService, String> {
+    open_storage(shared_folder_path, secret_store).map(FolderSyncFsService::with_storage)
+}
+
+/// Resolves the folder key for an existing folder from `passphrase`.
+/// Plaintext folders created before encryption existed need no passphrase.
+fn unlock_existing_folder(
+    metadata: &FolderSyncMetadataV1,
+    passphrase: Option<&str>,
+) -> Result<Option<FolderSyncKey>, String> {
+    let Some(encryption) = metadata.encryption.as_ref() else {
+        return Ok(None);
+    };
+    let passphrase = passphrase
+        .ok_or_else(|| "This shared folder is encrypted; enter its passphrase".to_string())?;
+    unlock(encryption, passphrase).map(Some)
+}
+
+fn with_optional_key(
+    fs_service: FolderSyncFsService,
+    key: Option<FolderSyncKey>,
+) -> FolderSyncFsService {
+    match key {
+        Some(key) => fs_service.with_key(key),
+        None => fs_service,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn initialize_folder_sync_internal(
     app_sync_repository: Arc<AppSyncRepository>,
     folder_sync_repository: Arc<FolderSyncRepository>,
     settings_repository: Arc<SettingsRepository>,
+    secret_store: &dyn SecretStore,
     app_data_dir: PathBuf,
     shared_folder_path: String,
     device_id: Option<String>,
+    passphrase: Option<String>,
 ) -> Result<FolderSyncCommandResult, String> {
     let existing = folder_sync_repository
         .get_config()
@@ -146,6 +203,36 @@ pub(crate) async fn initialize_folder_sync_internal(
         .unwrap_or_else(|| Uuid::now_v7().to_string());
     let now = Utc::now().to_rfc3339();
 
+    let fs_service = open_folder(&shared_folder_path, secret_store)?;
+    let (metadata, key) = match fs_service.read_metadata().await? {
+        Some(metadata) => {
+            if metadata.encryption.is_none() && passphrase.is_some() {
+                return Err(
+                    "Shared folder is unencrypted; join it, then set a passphrase".to_string(),
+                );
+            }
+            let key = unlock_existing_folder(&metadata, passphrase.as_deref())?;
+            (metadata, key)
+        }
+        None => {
+            let passphrase = passphrase.ok_or_else(|| {
+                "A passphrase is required to encrypt the shared folder".to_string()
+            })?;
+            let (encryption, key) = create_encryption(&passphrase)?;
+            let metadata = FolderSyncMetadataV1 {
+                version: FOLDER_SYNC_VERSION_V1,
+                created_at: now.clone(),
+                created_by_device_id: device_id.clone(),
+                encryption: Some(encryption),
+            };
+            (metadata, Some(key))
+        }
+    };
+    if let Some(key) = key.as_ref() {
+        store_key(secret_store, key)?;
+    }
+    let fs_service = with_optional_key(fs_service, key);
+
     folder_sync_repository
         .upsert_config(
             shared_folder_path.clone(),
@@ -156,20 +243,13 @@ pub(crate) async fn initialize_folder_sync_internal(
         )
         .await
         .map_err(|err| err.to_string())?;
-    FolderSyncFsService::new(PathBuf::from(&shared_folder_path)).initialize_folder(
-        &device_id,
-        &FolderSyncMetadataV1 {
-            version: FOLDER_SYNC_VERSION_V1,
-            created_at: now.clone(),
-            created_by_device_id: device_id.clone(),
-        },
-    )?;
+    fs_service.initialize_folder(&device_id, &metadata).await?;
 
     let snapshot = FolderSyncSnapshotService::new(
         app_sync_repository,
         folder_sync_repository,
         settings_repository,
-        FolderSyncFsService::new(PathBuf::from(&shared_folder_path)),
+        fs_service,
         app_data_dir,
         device_id,
     )
@@ -184,13 +264,16 @@ pub(crate) async fn initialize_folder_sync_internal(
     })
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn join_folder_sync_internal(
     app_sync_repository: Arc<AppSyncRepository>,
     folder_sync_repository: Arc<FolderSyncRepository>,
     settings_repository: Arc<SettingsRepository>,
+    secret_store: &dyn SecretStore,
     app_data_dir: PathBuf,
     shared_folder_path: String,
     device_id: Option<String>,
+    passphrase: Option<String>,
 ) -> Result<FolderSyncCommandResult, String> {
     let existing = folder_sync_repository
         .get_config()
@@ -200,6 +283,18 @@ pub(crate) async fn join_folder_sync_internal(
         .unwrap_or_else(|| Uuid::now_v7().to_string());
     let now = Utc::now().to_rfc3339();
 
+    // Verify the passphrase before touching local state.
+    let fs_service = open_folder(&shared_folder_path, secret_store)?;
+    let metadata = fs_service.read_metadata().await?;
+    let key = match metadata.as_ref() {
+        Some(metadata) => unlock_existing_folder(metadata, passphrase.as_deref())?,
+        None => None,
+    };
+    if let Some(key) = key.as_ref() {
+        store_key(secret_store, key)?;
+    }
+    let fs_service = with_optional_key(fs_service, key);
+
     folder_sync_repository
         .upsert_config(
             shared_folder_path.clone(),
@@ -210,20 +305,23 @@ pub(crate) async fn join_folder_sync_internal(
         )
         .await
         .map_err(|err| err.to_string())?;
-    FolderSyncFsService::new(PathBuf::from(&shared_folder_path)).initialize_folder(
-        &device_id,
-        &FolderSyncMetadataV1 {
-            version: FOLDER_SYNC_VERSION_V1,
-            created_at: now,
-            created_by_device_id: device_id.clone(),
-        },
-    )?;
+    fs_service
+        .initialize_folder(
+            &device_id,
+            &metadata.unwrap_or(FolderSyncMetadataV1 {
+                version: FOLDER_SYNC_VERSION_V1,
+                created_at: now,
+                created_by_device_id: device_id.clone(),
+                encryption: None,
+            }),
+        )
+        .await?;
 
     let result = FolderSyncSnapshotService::new(
         app_sync_repository,
         folder_sync_repository,
         settings_repository,
-        FolderSyncFsService::new(PathBuf::from(&shared_folder_path)),
+        fs_service,
         app_data_dir,
         device_id,
     )
@@ -241,6 +339,7 @@ pub(crate) async fn join_folder_sync_internal(
 pub(crate) async fn retry_folder_sync_now_internal(
     app_sync_repository: Arc<AppSyncRepository>,
     folder_sync_repository: Arc<FolderSyncRepository>,
+    secret_store: &dyn SecretStore,
 ) -> Result<FolderSyncCommandResult, String> {
     let config = folder_sync_repository
         .get_config()
@@ -250,7 +349,12 @@ pub(crate) async fn retry_folder_sync_now_internal(
         return Err("Folder sync is disabled".to_string());
     }
 
-    let fs_service = FolderSyncFsService::new(PathBuf::from(&config.shared_folder_path));
+    let fs_service = match open_shared_folder(&config.shared_folder_path, secret_store).await? {
+        FolderSyncAccess::Ready(fs_service) => fs_service,
+        FolderSyncAccess::PassphraseRequired => {
+            return Err("Folder sync passphrase required".to_string())
+        }
+    };
     let importer = FolderSyncImporter::new(
         app_sync_repository.clone(),
         folder_sync_repository.clone(),
@@ -278,6 +382,123 @@ pub(crate) async fn retry_folder_sync_now_internal(
     })
 }
 
+/// Stores the folder key on this device, e.g. after another device changed
+/// the passphrase.
+pub(crate) async fn unlock_folder_sync_internal(
+    folder_sync_repository: Arc<FolderSyncRepository>,
+    secret_store: &dyn SecretStore,
+    passphrase: String,
+) -> Result<FolderSyncCommandResult, String> {
+    let config = folder_sync_repository
+        .get_config()
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "Folder sync is not configured".to_string())?;
+    let encryption = open_folder(&config.shared_folder_path, secret_store)?
+        .read_metadata()
+        .await?
+        .and_then(|metadata| metadata.encryption)
+        .ok_or_else(|| "Shared folder is not encrypted".to_string())?;
+    let key = unlock(&encryption, &passphrase)?;
+    store_key(secret_store, &key)?;
+
+    let now = Utc::now().to_rfc3339();
+    folder_sync_repository
+        .update_status(FolderSyncStatusUpdate {
+            sync_state: Some("idle".to_string()),
+            last_error: Some(None),
+            updated_at: Some(now),
+            ..Default::default()
+        })
+        .await
+        .map_err(|err| err.to_string())?;
+
+    Ok(FolderSyncCommandResult {
+        status: "unlocked".to_string(),
+        message: "Folder sync unlocked".to_string(),
+        snapshot_id: None,
+        backup_path: None,
+    })
+}
+
+/// Sets a new passphrase and re-encrypts every event file and snapshot in
+/// the shared folder. Also encrypts folders that were created in plaintext.
+/// Other devices must unlock with the new passphrase afterwards.
+pub(crate) async fn change_folder_sync_passphrase_internal(
+    folder_sync_repository: Arc<FolderSyncRepository>,
+    secret_store: &dyn SecretStore,
+    current_passphrase: Option<String>,
+    new_passphrase: String,
+) -> Result<FolderSyncCommandResult, String> {
+    let config = folder_sync_repository
+        .get_config()
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "Folder sync is not configured".to_string())?;
+    let fs_service = open_folder(&config.shared_folder_path, secret_store)?;
+    let metadata = fs_service
+        .read_metadata()
+        .await?
+        .ok_or_else(|| "Shared folder is not initialized".to_string())?;
+    // An earlier attempt may have rewritten the folder but stopped before saving the key.
+    if let Some(encryption) = metadata.encryption.as_ref() {
+        if complete_rotation(secret_store, encryption)?.is_some()
+            && unlock(encryption, &new_passphrase).is_ok()
+        {
+            return Ok(FolderSyncCommandResult {
+                status: "passphrase_changed".to_string(),
+                message: "Finished the interrupted passphrase change".to_string(),
+                snapshot_id: None,
+                backup_path: None,
+            });
+        }
+    }
+    let current_key = unlock_existing_folder(&metadata, current_passphrase.as_deref())?;
+    let was_encrypted = current_key.is_some();
+    let source = with_optional_key(fs_service, current_key);
+
+    let now = Utc::now().to_rfc3339();
+    // The new key is journaled before the rewrite and reused if it is retried.
+    let (encryption, new_key) = begin_rotation(
+        secret_store,
+        &new_passphrase,
+        was_encrypted.then(|| now.clone()),
+    )?;
+    let target = source.clone().with_key(new_key.clone());
+    let rewritten = source
+        .rewrite_folder(
+            &target,
+            &FolderSyncMetadataV1 {
+                encryption: Some(encryption.clone()),
+                ..metadata
+            },
+        )
+        .await?;
+    complete_rotation(secret_store, &encryption)?
+        .ok_or_else(|| format!("Folder sync key '{}' was not saved", new_key.key_id()))?;
+
+    let message = format!(
+        "Re-encrypted {} event file(s) and {} snapshot(s)",
+        rewritten.event_files, rewritten.snapshots
+    );
+    folder_sync_repository
+        .append_history(
+            "passphrase_change".to_string(),
+            "success".to_string(),
+            message.clone(),
+            None,
+            Some(config.device_id),
+            now,
+        )
+        .await
+        .map_err(|err| err.to_string())?;
+
+    Ok(FolderSyncCommandResult {
+        status: "passphrase_changed".to_string(),
+        message,
+        snapshot_id: None,
+        backup_path: None,
+    })
+}
+
 pub(crate) async fn disable_folder_sync_internal(
     folder_sync_repository: Arc<FolderSyncRepository>,
 ) -> Result<FolderSyncCommandResult, String> {
@@ -318,22 +539,37 @@ pub(crate) async fn disable_folder_sync_internal(
 pub async fn get_folder_sync_state(
     state: State<'_, Arc<ServiceContext>>,
 ) -> Result<FolderSyncStateResult, String> {
-    get_folder_sync_state_internal(state.folder_sync_repository())
+    get_folder_sync_state_internal(state.folder_sync_repository(), &KeyringSecretStore).await
+}
+
+/// Saves WebDAV or S3 credentials before a remote shared folder is opened.
+fn save_remote_credentials(
+    remote_credentials: Option<FolderSyncRemoteCredentials>,
+) -> Result<(), String> {
+    match remote_credentials {
+        Some(credentials) => credentials.save(&KeyringSecretStore),
+        None => Ok(()),
+    }
 }
 
 #[tauri::command]
 pub async fn initialize_folder_sync(
     shared_folder_path: String,
     device_id: Option<String>,
+    passphrase: Option<String>,
+    remote_credentials: Option<FolderSyncRemoteCredentials>,
     state: State<'_, Arc<ServiceContext>>,
 ) -> Result<FolderSyncCommandResult, String> {
+    save_remote_credentials(remote_credentials)?;
     initialize_folder_sync_internal(
         state.app_sync_repository(),
         state.folder_sync_repository(),
         state.settings_repository(),
+        &KeyringSecretStore,
         PathBuf::from(state.app_data_dir()),
         shared_folder_path,
         device_id,
+        passphrase,
     )
     .await
 }
@@ -342,15 +578,20 @@ pub async fn initialize_folder_sync(
 pub async fn join_folder_sync(
     shared_folder_path: String,
     device_id: Option<String>,
+    passphrase: Option<String>,
+    remote_credentials: Option<FolderSyncRemoteCredentials>,
     state: State<'_, Arc<ServiceContext>>,
 ) -> Result<FolderSyncCommandResult, String> {
+    save_remote_credentials(remote_credentials)?;
     join_folder_sync_internal(
         state.app_sync_repository(),
         state.folder_sync_repository(),
         state.settings_repository(),
+        &KeyringSecretStore,
         PathBuf::from(state.app_data_dir()),
         shared_folder_path,
         device_id,
+        passphrase,
     )
     .await
 }
@@ -359,8 +600,44 @@ pub async fn join_folder_sync(
 pub async fn retry_folder_sync_now(
     state: State<'_, Arc<ServiceContext>>,
 ) -> Result<FolderSyncCommandResult, String> {
-    retry_folder_sync_now_internal(state.app_sync_repository(), state.folder_sync_repository())
-        .await
+    retry_folder_sync_now_internal(
+        state.app_sync_repository(),
+        state.folder_sync_repository(),
+        &KeyringSecretStore,
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn unlock_folder_sync(
+    passphrase: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<FolderSyncCommandResult, String> {
+    let result = unlock_folder_sync_internal(
+        state.folder_sync_repository(),
+        &KeyringSecretStore,
+        passphrase,
+    )
+    .await?;
+    state.folder_sync_runtime().trigger_foreground();
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn change_folder_sync_passphrase(
+    current_passphrase: Option<String>,
+    new_passphrase: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<FolderSyncCommandResult, String> {
+    let result = change_folder_sync_passphrase_internal(
+        state.folder_sync_repository(),
+        &KeyringSecretStore,
+        current_passphrase,
+        new_passphrase,
+    )
+    .await?;
+    state.folder_sync_runtime().trigger_foreground();
+    Ok(result)
 }
 
 #[tauri::command]
@@ -377,6 +654,7 @@ mod tests {
 
     use diesel::prelude::*;
     use tempfile::tempdir;
+    use wealthfolio_core::secrets::SecretStore;
     use wealthfolio_core::settings::SettingsRepositoryTrait;
     use wealthfolio_core::sync::{
         FolderSyncEventFileV1, SyncEntity, SyncOperation, FOLDER_SYNC_VERSION_V1,
@@ -387,12 +665,17 @@ mod tests {
     use wealthfolio_storage_sqlite::sync::{AppSyncRepository, FolderSyncRepository};
 
     use crate::commands::folder_sync::{
-        disable_folder_sync_internal, get_folder_sync_state_internal,
-        initialize_folder_sync_internal, join_folder_sync_internal, retry_folder_sync_now_internal,
+        change_folder_sync_passphrase_internal, disable_folder_sync_internal,
+        get_folder_sync_state_internal, initialize_folder_sync_internal, join_folder_sync_internal,
+        retry_folder_sync_now_internal, unlock_folder_sync_internal,
     };
+    use crate::services::folder_sync_crypto::tests::MemorySecretStore;
+    use crate::services::folder_sync_crypto::{unlock, FOLDER_SYNC_PENDING_KEY_SECRET};
     use crate::services::folder_sync_fs::FolderSyncFsService;
     use crate::services::folder_sync_snapshot::FolderSyncSnapshotService;
 
+    const PASSPHRASE: &str = "correct horse battery";
+
     struct CommandTestDevice {
         app_data_dir: PathBuf,
         pool: Arc<wealthfolio_storage_sqlite::DbPool>,
@@ -400,6 +683,7 @@ mod tests {
         folder_sync_repository: Arc<FolderSyncRepository>,
         settings_repository: Arc<SettingsRepository>,
         fs_service: FolderSyncFsService,
+        secret_store: MemorySecretStore,
         local_device_id: String,
     }
 
@@ -427,10 +711,65 @@ mod tests {
             folder_sync_repository,
             settings_repository,
             fs_service: FolderSyncFsService::new(shared_root.to_path_buf()),
+            secret_store: MemorySecretStore::default(),
             local_device_id: device_id.to_string(),
         }
     }
 
+    async fn folder_key(fs_service: &FolderSyncFsService, passphrase: &str) -> FolderSyncFsService {
+        let encryption = fs_service
+            .read_metadata()
+            .await
+            .expect("read metadata")
+            .and_then(|metadata| metadata.encryption)
+            .expect("encrypted folder");
+        fs_service
+            .clone()
+            .with_key(unlock(&encryption, passphrase).expect("unlock folder"))
+    }
+
+    async fn seed_platform(device: &CommandTestDevice, platform_id: &str, name: &str) {
+        device
+            .app_sync_repository
+            .apply_remote_event_lww(
+                SyncEntity::Platform,
+                platform_id.to_string(),
+                SyncOperation::Create,
+                format!("evt-{platform_id}"),
+                "2026-03-07T17:00:00Z".to_string(),
+                1,
+                serde_json::json!({
+                    "id": platform_id,
+                    "name": name,
+                    "url": format!("https://broker.example/{platform_id}"),
+                    "external_id": serde_json::Value::Null,
+                    "kind": "BROKERAGE",
+                    "website_url": format!("https://broker.example/{platform_id}"),
+                    "logo_url": serde_json::Value::Null
+                }),
+            )
+            .await
+            .expect("seed platform");
+    }
+
+    async fn join_with(
+        device: &CommandTestDevice,
+        shared_root: &Path,
+        passphrase: Option<&str>,
+    ) -> Result<super::FolderSyncCommandResult, String> {
+        join_folder_sync_internal(
+            device.app_sync_repository.clone(),
+            device.folder_sync_repository.clone(),
+            device.settings_repository.clone(),
+            &device.secret_store,
+            device.app_data_dir.clone(),
+            shared_root.to_string_lossy().into_owned(),
+            Some(device.local_device_id.clone()),
+            passphrase.map(str::to_string),
+        )
+        .await
+    }
+
     fn load_platform_name(
         pool: &Arc<wealthfolio_storage_sqlite::DbPool>,
         platform_id: &str,
@@ -455,9 +794,11 @@ mod tests {
             device.app_sync_repository.clone(),
             device.folder_sync_repository.clone(),
             device.settings_repository.clone(),
+            &device.secret_store,
             device.app_data_dir.clone(),
             shared_root.to_string_lossy().into_owned(),
             Some(device.local_device_id.clone()),
+            Some(PASSPHRASE.to_string()),
         )
         .await
         .expect("initialize folder sync");
@@ -473,6 +814,7 @@ mod tests {
         assert!(!device
             .fs_service
             .list_snapshots()
+            .await
             .expect("list snapshots")
             .is_empty());
     }
@@ -526,9 +868,11 @@ mod tests {
             target.app_sync_repository.clone(),
             target.folder_sync_repository.clone(),
             target.settings_repository.clone(),
+            &target.secret_store,
             target.app_data_dir.clone(),
             shared_root.to_string_lossy().into_owned(),
             Some(target.local_device_id.clone()),
+            None,
         )
         .await
         .expect("join folder sync");
@@ -551,15 +895,17 @@ mod tests {
             target.app_sync_repository.clone(),
             target.folder_sync_repository.clone(),
             target.settings_repository.clone(),
+            &target.secret_store,
             target.app_data_dir.clone(),
             shared_root.to_string_lossy().into_owned(),
             Some(target.local_device_id.clone()),
+            Some(PASSPHRASE.to_string()),
         )
         .await
         .expect("initialize target");
 
-        source
-            .fs_service
+        folder_key(&source.fs_service, PASSPHRASE)
+            .await
             .write_event_file(&FolderSyncEventFileV1 {
                 version: FOLDER_SYNC_VERSION_V1,
                 event_id: "evt-command-retry".to_string(),
@@ -580,11 +926,13 @@ mod tests {
                 schema_version: Some(FOLDER_SYNC_VERSION_V1),
                 app_version: Some("3.0.0".to_string()),
             })
+            .await
             .expect("write event");
 
         let result = retry_folder_sync_now_internal(
             target.app_sync_repository.clone(),
             target.folder_sync_repository.clone(),
+            &target.secret_store,
         )
         .await
         .expect("retry now");
@@ -606,9 +954,11 @@ mod tests {
             device.app_sync_repository.clone(),
             device.folder_sync_repository.clone(),
             device.settings_repository.clone(),
+            &device.secret_store,
             device.app_data_dir.clone(),
             shared_root.to_string_lossy().into_owned(),
             Some(device.local_device_id.clone()),
+            Some(PASSPHRASE.to_string()),
         )
         .await
         .expect("initialize");
@@ -635,17 +985,317 @@ mod tests {
             device.app_sync_repository.clone(),
             device.folder_sync_repository.clone(),
             device.settings_repository.clone(),
+            &device.secret_store,
             device.app_data_dir.clone(),
             shared_root.to_string_lossy().into_owned(),
             Some(device.local_device_id.clone()),
+            Some(PASSPHRASE.to_string()),
         )
         .await
         .expect("initialize");
 
-        let state = get_folder_sync_state_internal(device.folder_sync_repository.clone())
-            .expect("get state");
+        let state = get_folder_sync_state_internal(
+            device.folder_sync_repository.clone(),
+            &device.secret_store,
+        )
+        .await
+        .expect("get state");
         assert!(state.config.is_some());
         assert!(!state.history.is_empty());
         assert!(!state.status.sync_state.is_empty());
     }
+
+    #[tokio::test]
+    async fn initialize_requires_passphrase_and_encrypts_folder() {
+        let root = tempdir().expect("tempdir");
+        let shared_root = root.path().join("PanoramaSync");
+        let device = setup_device(root.path(), "device-a", "device-a", &shared_root).await;
+
+        let err = initialize_folder_sync_internal(
+            device.app_sync_repository.clone(),
+            device.folder_sync_repository.clone(),
+            device.settings_repository.clone(),
+            &device.secret_store,
+            device.app_data_dir.clone(),
+            shared_root.to_string_lossy().into_owned(),
+            Some(device.local_device_id.clone()),
+            None,
+        )
+        .await
+        .expect_err("passphrase is required");
+        assert!(err.contains("passphrase"), "unexpected error: {err}");
+
+        initialize_folder_sync_internal(
+            device.app_sync_repository.clone(),
+            device.folder_sync_repository.clone(),
+            device.settings_repository.clone(),
+            &device.secret_store,
+            device.app_data_dir.clone(),
+            shared_root.to_string_lossy().into_owned(),
+            Some(device.local_device_id.clone()),
+            Some(PASSPHRASE.to_string()),
+        )
+        .await
+        .expect("initialize");
+
+        let snapshots = device
+            .fs_service
+            .list_snapshots()
+            .await
+            .expect("list snapshots");
+        let raw_db = std::fs::read(shared_root.join(&snapshots[0].db_key)).expect("read snapshot");
+        assert!(!raw_db.starts_with(b"SQLite format 3"));
+        let state = get_folder_sync_state_internal(
+            device.folder_sync_repository.clone(),
+            &device.secret_store,
+        )
+        .await
+        .expect("get state");
+        assert!(state.is_encrypted);
+    }
+
+    #[tokio::test]
+    async fn join_encrypted_folder_verifies_passphrase() {
+        let root = tempdir().expect("tempdir");
+        let shared_root = root.path().join("PanoramaSync");
+        let source = setup_device(root.path(), "source-app", "device-a", &shared_root).await;
+        let target = setup_device(root.path(), "target-app", "device-b", &shared_root).await;
+
+        seed_platform(&source, "platform-encrypted-join", "Encrypted Join").await;
+        initialize_folder_sync_internal(
+            source.app_sync_repository.clone(),
+            source.folder_sync_repository.clone(),
+            source.settings_repository.clone(),
+            &source.secret_store,
+            source.app_data_dir.clone(),
+            shared_root.to_string_lossy().into_owned(),
+            Some(source.local_device_id.clone()),
+            Some(PASSPHRASE.to_string()),
+        )
+        .await
+        .expect("initialize source");
+
+        assert!(join_with(&target, &shared_root, None).await.is_err());
+        let err = join_with(&target, &shared_root, Some("wrong horse battery"))
+            .await
+            .expect_err("wrong passphrase");
+        assert_eq!(err, "Incorrect folder sync passphrase");
+        assert!(target
+            .folder_sync_repository
+            .get_config()
+            .expect("get config")
+            .is_none());
+
+        let result = join_with(&target, &shared_root, Some(PASSPHRASE))
+            .await
+            .expect("join with passphrase");
+        assert_eq!(result.status, "joined");
+        assert_eq!(
+            load_platform_name(&target.pool, "platform-encrypted-join").as_deref(),
+            Some("Encrypted Join")
+        );
+    }
+
+    #[tokio::test]
+    async fn changing_passphrase_reencrypts_folder_and_locks_other_devices() {
+        let root = tempdir().expect("tempdir");
+        let shared_root = root.path().join("PanoramaSync");
+        let source = setup_device(root.path(), "source-app", "device-a", &shared_root).await;
+        let target = setup_device(root.path(), "target-app", "device-b", &shared_root).await;
+
+        initialize_folder_sync_internal(
+            source.app_sync_repository.clone(),
+            source.folder_sync_repository.clone(),
+            source.settings_repository.clone(),
+            &source.secret_store,
+            source.app_data_dir.clone(),
+            shared_root.to_string_lossy().into_owned(),
+            Some(source.local_device_id.clone()),
+            Some(PASSPHRASE.to_string()),
+        )
+        .await
+        .expect("initialize source");
+        join_with(&target, &shared_root, Some(PASSPHRASE))
+            .await
+            .expect("join target");
+
+        let err = change_folder_sync_passphrase_internal(
+            source.folder_sync_repository.clone(),
+            &source.secret_store,
+            Some("wrong horse battery".to_string()),
+            "new horse battery".to_string(),
+        )
+        .await
+        .expect_err("current passphrase is verified");
+        assert_eq!(err, "Incorrect folder sync passphrase");
+
+        let result = change_folder_sync_passphrase_internal(
+            source.folder_sync_repository.clone(),
+            &source.secret_store,
+            Some(PASSPHRASE.to_string()),
+            "new horse battery".to_string(),
+        )
+        .await
+        .expect("change passphrase");
+        assert_eq!(result.status, "passphrase_changed");
+        assert_eq!(
+            result.message,
+            "Re-encrypted 0 event file(s) and 1 snapshot(s)"
+        );
+
+        folder_key(&source.fs_service, "new horse battery")
+            .await
+            .write_event_file(&FolderSyncEventFileV1 {
+                version: FOLDER_SYNC_VERSION_V1,
+                event_id: "evt-command-rotated".to_string(),
+                device_id: "device-a".to_string(),
+                entity: SyncEntity::Platform,
+                entity_id: "platform-command-rotated".to_string(),
+                op: SyncOperation::Create,
+                client_timestamp: "2026-03-07T17:10:00Z".to_string(),
+                payload: serde_json::json!({
+                    "id": "platform-command-rotated",
+                    "name": "Command Rotated",
+                    "url": "https://broker.example/command-rotated",
+                    "external_id": serde_json::Value::Null,
+                    "kind": "BROKERAGE",
+                    "website_url": "https://broker.example/command-rotated",
+                    "logo_url": serde_json::Value::Null
+                }),
+                schema_version: Some(FOLDER_SYNC_VERSION_V1),
+                app_version: Some("3.0.0".to_string()),
+            })
+            .await
+            .expect("write event");
+
+        let err = retry_folder_sync_now_internal(
+            target.app_sync_repository.clone(),
+            target.folder_sync_repository.clone(),
+            &target.secret_store,
+        )
+        .await
+        .expect_err("stale key is rejected");
+        assert_eq!(err, "Folder sync passphrase required");
+        assert!(unlock_folder_sync_internal(
+            target.folder_sync_repository.clone(),
+            &target.secret_store,
+            PASSPHRASE.to_string(),
+        )
+        .await
+        .is_err());
+
+        unlock_folder_sync_internal(
+            target.folder_sync_repository.clone(),
+            &target.secret_store,
+            "new horse battery".to_string(),
+        )
+        .await
+        .expect("unlock with new passphrase");
+        retry_folder_sync_now_internal(
+            target.app_sync_repository.clone(),
+            target.folder_sync_repository.clone(),
+            &target.secret_store,
+        )
+        .await
+        .expect("retry after unlock");
+        assert_eq!(
+            load_platform_name(&target.pool, "platform-command-rotated").as_deref(),
+            Some("Command Rotated")
+        );
+    }
+
+    #[tokio::test]
+    async fn interrupted_passphrase_change_resumes_with_journaled_key() {
+        let root = tempdir().expect("tempdir");
+        let shared_root = root.path().join("PanoramaSync");
+        let source = setup_device(root.path(), "source-app", "device-a", &shared_root).await;
+
+        initialize_folder_sync_internal(
+            source.app_sync_repository.clone(),
+            source.folder_sync_repository.clone(),
+            source.settings_repository.clone(),
+            &source.secret_store,
+            source.app_data_dir.clone(),
+            shared_root.to_string_lossy().into_owned(),
+            Some(source.local_device_id.clone()),
+            Some(PASSPHRASE.to_string()),
+        )
+        .await
+        .expect("initialize source");
+
+        let old_folder = folder_key(&source.fs_service, PASSPHRASE).await;
+        for event_id in ["evt-rotate-1", "evt-rotate-2"] {
+            old_folder
+                .write_event_file(&FolderSyncEventFileV1 {
+                    version: FOLDER_SYNC_VERSION_V1,
+                    event_id: event_id.to_string(),
+                    device_id: "device-a".to_string(),
+                    entity: SyncEntity::Platform,
+                    entity_id: format!("platform-{event_id}"),
+                    op: SyncOperation::Create,
+                    client_timestamp: "2026-03-07T17:10:00Z".to_string(),
+                    payload: serde_json::json!({ "id": format!("platform-{event_id}") }),
+                    schema_version: Some(FOLDER_SYNC_VERSION_V1),
+                    app_version: Some("3.0.0".to_string()),
+                })
+                .await
+                .expect("write event");
+        }
+        // An unreadable file sorted last stops the rewrite after the first two files.
+        let broken_file = shared_root.join("events/device-a/evt-rotate-3.json");
+        std::fs::write(&broken_file, b"not json").expect("write broken file");
+
+        change_folder_sync_passphrase_internal(
+            source.folder_sync_repository.clone(),
+            &source.secret_store,
+            Some(PASSPHRASE.to_string()),
+            "new horse battery".to_string(),
+        )
+        .await
+        .expect_err("rewrite is interrupted");
+        assert!(source
+            .secret_store
+            .get_secret(FOLDER_SYNC_PENDING_KEY_SECRET)
+            .expect("read pending key")
+            .is_some());
+
+        let err = change_folder_sync_passphrase_internal(
+            source.folder_sync_repository.clone(),
+            &source.secret_store,
+            Some(PASSPHRASE.to_string()),
+            "other horse battery".to_string(),
+        )
+        .await
+        .expect_err("a different new passphrase cannot resume");
+        assert!(err.contains("interrupted"), "unexpected error: {err}");
+
+        std::fs::remove_file(&broken_file).expect("remove broken file");
+        let result = change_folder_sync_passphrase_internal(
+            source.folder_sync_repository.clone(),
+            &source.secret_store,
+            Some(PASSPHRASE.to_string()),
+            "new horse battery".to_string(),
+        )
+        .await
+        .expect("resume passphrase change");
+        // Event files rewritten by the first attempt are skipped.
+        assert_eq!(
+            result.message,
+            "Re-encrypted 0 event file(s) and 1 snapshot(s)"
+        );
+        assert!(source
+            .secret_store
+            .get_secret(FOLDER_SYNC_PENDING_KEY_SECRET)
+            .expect("read pending key")
+            .is_none());
+
+        let new_folder = folder_key(&source.fs_service, "new horse battery").await;
+        for event_id in ["evt-rotate-1", "evt-rotate-2"] {
+            let event = new_folder
+                .read_event_file(&format!("events/device-a/{event_id}.json"))
+                .await
+                .expect("read re-encrypted event");
+            assert_eq!(event.event_id, event_id);
+        }
+    }
 }
@@ -1,8 +1,11 @@
 use std::sync::Arc;
 
+use crate::commands::reconciliation::republish_holdings_drift;
+use crate::commands::sync_conflicts::publish_sync_conflicts;
 use crate::context::ServiceContext;
 use log::{debug, info, warn};
 use tauri::State;
+use wealthfolio_core::activities::NewActivity;
 use wealthfolio_core::health::{FixAction, HealthConfig, HealthServiceTrait, HealthStatus};
 use wealthfolio_core::quotes::SyncMode;
 
@@ -42,6 +45,8 @@ async fn run_health_checks_internal(
     state: &State<'_, Arc<ServiceContext>>,
     base_currency: &str,
 ) -> Result<HealthStatus, String> {
+    publish_sync_conflicts(state).await;
+    republish_holdings_drift(state).await;
     state
         .health_service()
         .run_full_checks(
@@ -141,6 +146,21 @@ pub async fn execute_health_fix(
         return Ok(());
     }
 
+    // Handle record_activity by creating the drafted activity (e.g., from reconciliation)
+    if action.id == "record_activity" {
+        let activity: NewActivity = serde_json::from_value(action.payload.clone())
+            .map_err(|e| format!("Failed to parse activity: {}", e))?;
+
+        state
+            .activity_service()
+            .create_activity(activity)
+            .await
+            .map_err(|e| format!("Failed to record activity: {}", e))?;
+
+        state.health_service().resolve_published_fix(&action).await;
+        return Ok(());
+    }
+
     state
         .health_service()
         .execute_fix(&action)
@@ -5,6 +5,8 @@ pub mod ai_chat;
 pub mod ai_providers;
 pub mod alternative_assets;
 pub mod asset;
+pub mod attachments;
+pub mod audit;
 #[cfg(feature = "connect-sync")]
 pub mod brokers_sync;
 #[cfg(feature = "device-sync")]
@@ -20,8 +22,11 @@ pub mod market_data;
 pub mod platform;
 pub mod portfolio;
 pub mod providers_settings;
+pub mod reconciliation;
+pub mod recurring;
 pub mod secrets;
 pub mod settings;
+pub mod sync_conflicts;
 #[cfg(feature = "device-sync")]
 pub mod sync_crypto;
 pub mod taxonomy;
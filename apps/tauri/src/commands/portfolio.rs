@@ -24,6 +24,7 @@ use wealthfolio_core::{
         SnapshotSource,
     },
     quotes::MarketSyncMode,
+    scenario::{ScenarioRequest, ScenarioResult},
     valuation::DailyAccountValuation,
 };
 
@@ -162,6 +163,24 @@ pub async fn get_holdings_by_allocation(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn simulate_scenario(
+    state: State<'_, Arc<ServiceContext>>,
+    request: ScenarioRequest,
+) -> Result<ScenarioResult, String> {
+    debug!(
+        "Simulate scenario with {} activities for account: {:?}",
+        request.activities.len(),
+        request.account_id
+    );
+    let base_currency = state.get_base_currency();
+    state
+        .scenario_service()
+        .simulate(request, &base_currency)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_historical_valuations(
     state: State<'_, Arc<ServiceContext>>,
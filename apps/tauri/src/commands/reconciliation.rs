@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use crate::context::ServiceContext;
+use chrono::NaiveDate;
+use log::{debug, warn};
+use tauri::State;
+use wealthfolio_core::health::HealthServiceTrait;
+use wealthfolio_core::portfolio::reconciliation::{
+    holdings_drift_health_source, BrokerStatement, HoldingsDriftCheck, ReconciliationReport,
+    ReconciliationResult, DEFAULT_DRIFT_HISTORY_LIMIT,
+};
+
+/// Converts a report into health issues and publishes them to the Health Center,
+/// replacing any issues from a previous reconciliation of the same account.
+pub async fn publish_reconciliation(
+    state: &ServiceContext,
+    report: ReconciliationReport,
+) -> ReconciliationResult {
+    let issues = state.reconciliation_service().to_health_issues(&report);
+    state
+        .health_service()
+        .publish_issues(
+            &format!("reconciliation:{}", report.account_id),
+            issues.clone(),
+        )
+        .await;
+    ReconciliationResult { report, issues }
+}
+
+/// Publishes post-sync holdings drift results, replacing each account's previous drift issues.
+pub async fn publish_holdings_drift(state: &ServiceContext, results: &[ReconciliationResult]) {
+    let health_service = state.health_service();
+    for result in results {
+        health_service
+            .publish_issues(
+                &holdings_drift_health_source(&result.report.account_id),
+                result.issues.clone(),
+            )
+            .await;
+    }
+}
+
+/// Republishes the latest drift result per account (published issues are kept in memory).
+pub async fn republish_holdings_drift(state: &ServiceContext) {
+    match state.holdings_drift_service().latest_results() {
+        Ok(results) => publish_holdings_drift(state, &results).await,
+        Err(err) => warn!("Failed to load holdings drift results: {}", err),
+    }
+}
+
+/// Reconcile broker-reported positions and cash balances.
+#[tauri::command]
+pub async fn reconcile_broker_statement(
+    statement: BrokerStatement,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<ReconciliationResult, String> {
+    debug!(
+        "Reconciling broker statement for account {} as of {}",
+        statement.account_id, statement.as_of_date
+    );
+    let report = state
+        .reconciliation_service()
+        .reconcile(statement)
+        .map_err(|e| e.to_string())?;
+    Ok(publish_reconciliation(&state, report).await)
+}
+
+/// Reconcile a broker statement CSV export.
+#[tauri::command]
+pub async fn reconcile_broker_statement_csv(
+    account_id: String,
+    as_of_date: String,
+    content: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<ReconciliationResult, String> {
+    debug!(
+        "Reconciling broker statement CSV for account {} as of {}",
+        account_id, as_of_date
+    );
+    let as_of_date = NaiveDate::parse_from_str(&as_of_date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid asOfDate: {}", e))?;
+    let report = state
+        .reconciliation_service()
+        .reconcile_csv(&account_id, as_of_date, content.as_bytes())
+        .map_err(|e| e.to_string())?;
+    Ok(publish_reconciliation(&state, report).await)
+}
+
+/// List post-sync holdings drift checks, newest first.
+#[tauri::command]
+pub async fn get_holdings_drift_history(
+    account_id: Option<String>,
+    limit: Option<i64>,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<HoldingsDriftCheck>, String> {
+    state
+        .holdings_drift_service()
+        .get_history(
+            account_id.as_deref(),
+            limit.unwrap_or(DEFAULT_DRIFT_HISTORY_LIMIT),
+        )
+        .map_err(|e| e.to_string())
+}
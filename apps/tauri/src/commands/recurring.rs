@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use crate::context::ServiceContext;
+use chrono::{Duration, NaiveDate, Utc};
+use log::debug;
+use tauri::State;
+use wealthfolio_core::recurring::{
+    NewRecurringActivityTemplate, RecurringActivityTemplate, RecurringGenerationResult,
+    RecurringOccurrence,
+};
+
+/// Default preview horizon when no `through` date is given.
+const DEFAULT_PREVIEW_DAYS: i64 = 365;
+
+#[tauri::command]
+pub async fn get_recurring_templates(
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<RecurringActivityTemplate>, String> {
+    debug!("Fetching recurring activity templates...");
+    state
+        .recurring_activity_service()
+        .get_templates()
+        .map_err(|e| format!("Failed to load recurring activity templates: {}", e))
+}
+
+#[tauri::command]
+pub async fn create_recurring_template(
+    template: NewRecurringActivityTemplate,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<RecurringActivityTemplate, String> {
+    debug!("Creating recurring activity template...");
+    state
+        .recurring_activity_service()
+        .create_template(template)
+        .await
+        .map_err(|e| format!("Failed to create recurring activity template: {}", e))
+}
+
+#[tauri::command]
+pub async fn update_recurring_template(
+    template: RecurringActivityTemplate,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<RecurringActivityTemplate, String> {
+    debug!("Updating recurring activity template {}...", template.id);
+    state
+        .recurring_activity_service()
+        .update_template(template)
+        .await
+        .map_err(|e| format!("Failed to update recurring activity template: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_recurring_template(
+    id: String,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<(), String> {
+    debug!("Deleting recurring activity template {}...", id);
+    state
+        .recurring_activity_service()
+        .delete_template(&id)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to delete recurring activity template: {}", e))
+}
+
+#[tauri::command]
+pub async fn preview_recurring_occurrences(
+    id: String,
+    through: Option<NaiveDate>,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<RecurringOccurrence>, String> {
+    let through =
+        through.unwrap_or_else(|| Utc::now().date_naive() + Duration::days(DEFAULT_PREVIEW_DAYS));
+    state
+        .recurring_activity_service()
+        .preview_occurrences(&id, through)
+        .map_err(|e| format!("Failed to preview recurring occurrences: {}", e))
+}
+
+#[tauri::command]
+pub async fn generate_recurring_activities(
+    as_of: Option<NaiveDate>,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<RecurringGenerationResult, String> {
+    let as_of = as_of.unwrap_or_else(|| Utc::now().date_naive());
+    debug!("Generating recurring activities due as of {}...", as_of);
+    state
+        .recurring_activity_service()
+        .generate_due(as_of)
+        .await
+        .map_err(|e| format!("Failed to generate recurring activities: {}", e))
+}
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use crate::context::ServiceContext;
+use log::{debug, warn};
+use tauri::State;
+use wealthfolio_core::health::HealthServiceTrait;
+use wealthfolio_core::sync::{
+    sync_conflicts_to_health_issues, ResolveSyncConflictRequest, SyncFieldConflict,
+    SYNC_CONFLICTS_HEALTH_SOURCE,
+};
+
+/// Publishes open sync conflicts to the Health Center, replacing the previous set.
+pub async fn publish_sync_conflicts(state: &ServiceContext) {
+    match state.app_sync_repository().list_sync_conflicts(false) {
+        Ok(conflicts) => {
+            state
+                .health_service()
+                .publish_issues(
+                    SYNC_CONFLICTS_HEALTH_SOURCE,
+                    sync_conflicts_to_health_issues(&conflicts),
+                )
+                .await;
+        }
+        Err(err) => warn!("Failed to load sync conflicts: {}", err),
+    }
+}
+
+/// List field conflicts recorded while merging remote sync events.
+#[tauri::command]
+pub async fn list_sync_conflicts(
+    include_resolved: Option<bool>,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<SyncFieldConflict>, String> {
+    debug!("Listing sync conflicts...");
+    state
+        .app_sync_repository()
+        .list_sync_conflicts(include_resolved.unwrap_or(false))
+        .map_err(|e| format!("Failed to load sync conflicts: {}", e))
+}
+
+/// Resolve a sync conflict with the local, remote or a manual value.
+#[tauri::command]
+pub async fn resolve_sync_conflict(
+    request: ResolveSyncConflictRequest,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<SyncFieldConflict, String> {
+    debug!("Resolving sync conflict {}...", request.conflict_id);
+    let conflict = state
+        .app_sync_repository()
+        .resolve_sync_conflict(request)
+        .await
+        .map_err(|e| format!("Failed to resolve sync conflict: {}", e))?;
+    publish_sync_conflicts(&state).await;
+    Ok(conflict)
+}
@@ -3,8 +3,12 @@ use serde::Serialize;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
+use std::sync::Arc;
 use tauri::Manager;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, State};
+use wealthfolio_storage_sqlite::backup::{
+    BackupArchiveInfo, BackupRunResult, BackupSchedule, BackupScheduleUpdate, RestorePreview,
+};
 use wealthfolio_storage_sqlite::db;
 
 use crate::context::ServiceContext;
@@ -20,6 +24,17 @@ fn normalize_file_path(path: &str) -> String {
     }
 }
 
+/// Adds attachment contents to a backup file so it is self-contained.
+fn pack_attachments(app_handle: &AppHandle, backup_path: &str) -> Result<(), String> {
+    if let Some(context) = app_handle.try_state::<std::sync::Arc<ServiceContext>>() {
+        context
+            .attachment_store()
+            .pack_archive(backup_path)
+            .map_err(|e| format!("Failed to add attachments to backup: {}", e))?;
+    }
+    Ok(())
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AppInfo {
@@ -97,6 +112,7 @@ pub async fn backup_database(app_handle: AppHandle) -> Result<(String, Vec<u8>),
         .to_string();
 
     let backup_path = db::backup_database(&app_data_dir).map_err(|e| e.to_string())?;
+    pack_attachments(&app_handle, &backup_path)?;
 
     // Read the backup file
     let mut file =
@@ -140,10 +156,43 @@ pub async fn backup_database_to_path(
 
     db::backup_database_to_file(&app_data_dir, &backup_path_str)
         .map_err(|e| format!("Failed to backup database: {}", e))?;
+    pack_attachments(&app_handle, &backup_path_str)?;
 
     Ok(backup_path_str)
 }
 
+/// Emits `database-restored` and, on desktop, offers to restart the app.
+fn notify_database_restored(app_handle: &AppHandle) -> Result<(), String> {
+    app_handle
+        .emit("database-restored", ())
+        .map_err(|e| format!("Failed to emit database-restored event: {}", e))?;
+
+    // On desktop builds prompt for restart, but skip showing dialogs on iOS/Android
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    {
+        use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+        let should_restart = app_handle
+            .dialog()
+            .message(
+                "Database restored successfully!\n\n\
+                 For the best experience, it's recommended to restart the application \
+                 to ensure all data is properly refreshed.\n\n\
+                 Would you like to restart now?",
+            )
+            .title("Database Restored - Restart Required")
+            .buttons(MessageDialogButtons::OkCancel)
+            .kind(MessageDialogKind::Info)
+            .blocking_show();
+
+        if should_restart {
+            app_handle.restart();
+        }
+    }
+
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn restore_database(
     app_handle: AppHandle,
@@ -172,33 +221,87 @@ pub async fn restore_database(
     // Use the safe restore function that handles Windows file locking issues
     db::restore_database_safe(&app_data_dir, &normalized_backup_path).map_err(|e| e.to_string())?;
 
-    // After successful restore, emit event and show restart dialog
-    app_handle
-        .emit("database-restored", ())
-        .map_err(|e| format!("Failed to emit database-restored event: {}", e))?;
+    // Backups carry attachment contents in an archive table; move them into
+    // the file store and drop the table from the live database.
+    if let Some(context) = app_handle.try_state::<std::sync::Arc<ServiceContext>>() {
+        context
+            .attachment_store()
+            .unpack_archive(&db::get_db_path(&app_data_dir), true)
+            .map_err(|e| format!("Failed to restore attachments: {}", e))?;
+    }
 
-    // On desktop builds prompt for restart, but skip showing dialogs on iOS/Android
-    #[cfg(not(any(target_os = "ios", target_os = "android")))]
-    {
-        use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+    notify_database_restored(&app_handle)
+}
 
-        let should_restart = app_handle
-            .dialog()
-            .message(
-                "Database restored successfully!\n\n\
-                 For the best experience, it's recommended to restart the application \
-                 to ensure all data is properly refreshed.\n\n\
-                 Would you like to restart now?",
-            )
-            .title("Database Restored - Restart Required")
-            .buttons(MessageDialogButtons::OkCancel)
-            .kind(MessageDialogKind::Info)
-            .blocking_show();
+#[tauri::command]
+pub async fn get_backup_schedule(
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<BackupSchedule, String> {
+    Ok(state.local_backup_service().get_schedule())
+}
 
-        if should_restart {
-            app_handle.restart();
-        }
-    }
+#[tauri::command]
+pub async fn update_backup_schedule(
+    update: BackupScheduleUpdate,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<BackupSchedule, String> {
+    state
+        .local_backup_service()
+        .update_schedule(update)
+        .await
+        .map_err(|e| e.to_string())
+}
 
-    Ok(())
+#[tauri::command]
+pub async fn list_backups(
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<BackupArchiveInfo>, String> {
+    state
+        .local_backup_service()
+        .list_backups()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn run_backup(state: State<'_, Arc<ServiceContext>>) -> Result<BackupRunResult, String> {
+    let service = state.local_backup_service();
+    tauri::async_runtime::spawn_blocking(move || service.run_backup())
+        .await
+        .map_err(|e| format!("Backup task failed: {}", e))?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn preview_backup_restore(
+    archive_path: String,
+    passphrase: Option<String>,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<RestorePreview, String> {
+    let service = state.local_backup_service();
+    let archive_path = normalize_file_path(&archive_path);
+    tauri::async_runtime::spawn_blocking(move || {
+        service.preview_restore(&archive_path, passphrase.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Restore preview task failed: {}", e))?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn restore_backup(
+    app_handle: AppHandle,
+    archive_path: String,
+    passphrase: Option<String>,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<(), String> {
+    let service = state.local_backup_service();
+    let archive_path = normalize_file_path(&archive_path);
+    tauri::async_runtime::spawn_blocking(move || {
+        service.restore_backup(&archive_path, passphrase.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Restore task failed: {}", e))?
+    .map_err(|e| e.to_string())?;
+
+    notify_database_restored(&app_handle)
 }
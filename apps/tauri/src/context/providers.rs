@@ -8,11 +8,14 @@ use tokio::sync::mpsc;
 use wealthfolio_ai::{AiProviderService, ChatConfig, ChatService};
 use wealthfolio_connect::{
     BrokerSyncService, CoreImportRunRepositoryAdapter, ImportRunRepositoryTrait,
+    SyncScheduleService,
 };
 use wealthfolio_core::{
     accounts::AccountService,
-    activities::ActivityService,
+    activities::{ActivityDuplicateService, ActivityService, ActivityStagingService},
     assets::{AlternativeAssetService, AssetClassificationService, AssetService},
+    attachments::{load_or_create_encryption_key, AttachmentService},
+    audit::ChangeLogService,
     events::DomainEvent,
     fx::{FxService, FxServiceTrait},
     goals::GoalService,
@@ -24,26 +27,38 @@ use wealthfolio_core::{
         income::IncomeService,
         net_worth::NetWorthService,
         performance::PerformanceService,
+        reconciliation::{HoldingsDriftService, ReconciliationService},
+        scenario::ScenarioService,
         snapshot::SnapshotService,
         valuation::ValuationService,
     },
     quotes::{QuoteService, QuoteServiceTrait},
+    recurring::RecurringActivityService,
     settings::{SettingsRepositoryTrait, SettingsService, SettingsServiceTrait},
     taxonomies::TaxonomyService,
 };
-use wealthfolio_device_sync::{engine::DeviceSyncRuntimeState, DeviceEnrollService};
+use wealthfolio_device_sync::{
+    engine::DeviceSyncRuntimeState, DeviceEnrollService, SyncRelayConfig,
+};
 use wealthfolio_storage_sqlite::{
     accounts::AccountRepository,
-    activities::ActivityRepository,
+    activities::{ActivityRepository, DuplicateDecisionRepository, StagedActivityRepository},
     ai_chat::AiChatRepository,
     assets::{AlternativeAssetRepository, AssetRepository},
+    attachments::{AttachmentRepository, FileAttachmentStore},
+    audit::ChangeLogRepository,
+    backup::LocalBackupService,
     db::{self, write_actor},
     fx::FxRepository,
     goals::GoalRepository,
     health::HealthDismissalRepository,
     limits::ContributionLimitRepository,
     market_data::{MarketDataRepository, QuoteSyncStateRepository},
-    portfolio::{snapshot::SnapshotRepository, valuation::ValuationRepository},
+    portfolio::{
+        reconciliation::HoldingsDriftRepository, snapshot::SnapshotRepository,
+        valuation::ValuationRepository,
+    },
+    recurring::RecurringActivityRepository,
     settings::SettingsRepository,
     sync::{
         AppSyncRepository, BrokerSyncStateRepository, FolderSyncRepository, ImportRunRepository,
@@ -80,7 +95,6 @@ pub async fn initialize_context(
     ));
     let fx_repository = Arc::new(FxRepository::new(pool.clone(), writer.clone()));
     let snapshot_repository = Arc::new(SnapshotRepository::new(pool.clone(), writer.clone()));
-    let app_sync_repository = Arc::new(AppSyncRepository::new(pool.clone(), writer.clone()));
     let folder_sync_repository = Arc::new(FolderSyncRepository::new(pool.clone(), writer.clone()));
     let valuation_repository = Arc::new(ValuationRepository::new(pool.clone(), writer.clone()));
     let platform_repository = Arc::new(PlatformRepository::new(pool.clone(), writer.clone()));
@@ -109,6 +123,20 @@ pub async fn initialize_context(
 
     let secret_store = shared_secret_store();
 
+    // Attachment contents are encrypted at rest with a key from the secret store
+    let attachment_store = Arc::new(FileAttachmentStore::new(
+        app_data_dir,
+        load_or_create_encryption_key(secret_store.as_ref())?,
+    ));
+    let attachment_service = Arc::new(AttachmentService::new(
+        Arc::new(AttachmentRepository::new(pool.clone(), writer.clone())),
+        attachment_store.clone(),
+    ));
+    let app_sync_repository = Arc::new(
+        AppSyncRepository::new(pool.clone(), writer.clone())
+            .with_attachment_store(attachment_store.clone()),
+    );
+
     // Quote sync state repository for optimized quote syncing
     let quote_sync_state_repository =
         Arc::new(QuoteSyncStateRepository::new(pool.clone(), writer.clone()));
@@ -130,13 +158,17 @@ pub async fn initialize_context(
     let taxonomy_repository = Arc::new(TaxonomyRepository::new(pool.clone(), writer.clone()));
     let taxonomy_service = Arc::new(TaxonomyService::new(taxonomy_repository));
 
+    // Append-only change log for activity and asset mutations
+    let change_log_repository = Arc::new(ChangeLogRepository::new(pool.clone(), writer.clone()));
+
     let asset_service = Arc::new(
         AssetService::with_taxonomy_service(
             asset_repository.clone(),
             quote_service.clone(),
             taxonomy_service.clone(),
         )?
-        .with_event_sink(domain_event_sink.clone()),
+        .with_event_sink(domain_event_sink.clone())
+        .with_change_log(change_log_repository.clone()),
     );
 
     let account_service = Arc::new(AccountService::new(
@@ -155,6 +187,9 @@ pub async fn initialize_context(
         import_run_repository.clone(),
     ));
 
+    let staged_activity_repository =
+        Arc::new(StagedActivityRepository::new(pool.clone(), writer.clone()));
+
     let activity_service = Arc::new(
         ActivityService::with_import_run_repository(
             activity_repository.clone(),
@@ -162,10 +197,34 @@ pub async fn initialize_context(
             asset_service.clone(),
             fx_service.clone(),
             quote_service.clone(),
-            core_import_run_repository,
+            core_import_run_repository.clone(),
+        )
+        .with_staged_activity_repository(staged_activity_repository.clone())
+        .with_event_sink(domain_event_sink.clone())
+        .with_change_log(change_log_repository.clone()),
+    );
+    let change_log_service = Arc::new(
+        ChangeLogService::new(
+            change_log_repository,
+            activity_repository.clone(),
+            asset_repository.clone(),
         )
         .with_event_sink(domain_event_sink.clone()),
     );
+    let activity_duplicate_service = Arc::new(ActivityDuplicateService::new(
+        activity_service.clone(),
+        Arc::new(DuplicateDecisionRepository::new(
+            pool.clone(),
+            writer.clone(),
+        )),
+    ));
+    let recurring_activity_service = Arc::new(RecurringActivityService::new(
+        Arc::new(RecurringActivityRepository::new(
+            pool.clone(),
+            writer.clone(),
+        )),
+        activity_service.clone(),
+    ));
     let goal_service = Arc::new(GoalService::new(goal_repo.clone()));
     let limits_service = Arc::new(ContributionLimitService::new(
         fx_service.clone(),
@@ -191,6 +250,14 @@ pub async fn initialize_context(
         .with_event_sink(domain_event_sink.clone()),
     );
 
+    // Staged import runs awaiting review
+    let activity_staging_service = Arc::new(ActivityStagingService::new(
+        staged_activity_repository,
+        core_import_run_repository,
+        activity_service.clone(),
+        snapshot_service.clone(),
+    ));
+
     let holdings_valuation_service = Arc::new(HoldingsValuationService::new(
         fx_service.clone(),
         quote_service.clone(),
@@ -223,6 +290,15 @@ pub async fn initialize_context(
         taxonomy_service.clone(),
     ));
 
+    // What-if simulations on top of current holdings
+    let scenario_service = Arc::new(ScenarioService::new(
+        snapshot_service.clone(),
+        holdings_service.clone(),
+        allocation_service.clone(),
+        quote_service.clone(),
+        fx_service.clone(),
+    ));
+
     let net_worth_service = Arc::new(NetWorthService::new(
         base_currency.clone(),
         account_repository.clone(),
@@ -233,6 +309,18 @@ pub async fn initialize_context(
         fx_service.clone(),
     ));
 
+    let reconciliation_service = Arc::new(ReconciliationService::new(
+        account_repository.clone(),
+        asset_repository.clone(),
+        snapshot_service.clone(),
+    ));
+
+    // Holdings drift: broker holdings recorded during sync, compared after recalculation
+    let holdings_drift_service = Arc::new(HoldingsDriftService::new(
+        Arc::new(HoldingsDriftRepository::new(pool.clone(), writer.clone())),
+        reconciliation_service.clone(),
+    ));
+
     let alternative_asset_repository = Arc::new(AlternativeAssetRepository::new(
         pool.clone(),
         writer.clone(),
@@ -260,11 +348,31 @@ pub async fn initialize_context(
             quote_service.clone(),
         )
         .with_event_sink(domain_event_sink.clone())
-        .with_snapshot_service(snapshot_service.clone()),
+        .with_snapshot_service(snapshot_service.clone())
+        .with_duplicate_service(activity_duplicate_service.clone())
+        .with_drift_service(holdings_drift_service.clone())
+        .with_staging_service(activity_staging_service.clone()),
     );
 
+    let sync_schedule_service = Arc::new(SyncScheduleService::new(
+        settings_repository.clone() as Arc<dyn SettingsRepositoryTrait>
+    ));
+
+    // Scheduled encrypted backups into a user-chosen directory
+    let local_backup_service = Arc::new(LocalBackupService::new(
+        app_data_dir,
+        settings_repository.clone() as Arc<dyn SettingsRepositoryTrait>,
+        secret_store.clone(),
+        attachment_store.clone(),
+    ));
+
     let connect_service = Arc::new(ConnectService::new(secret_store.clone()));
 
+    // Health service for portfolio health diagnostics
+    let health_dismissal_repository =
+        Arc::new(HealthDismissalRepository::new(pool.clone(), writer.clone()));
+    let health_service = Arc::new(HealthService::new(health_dismissal_repository));
+
     // AI provider service - catalog is embedded at compile time
     let ai_catalog_json = include_str!("../../../../crates/ai/src/ai_providers.json");
     let ai_provider_service = Arc::new(AiProviderService::new(
@@ -291,6 +399,11 @@ pub async fn initialize_context(
         allocation_service.clone(),
         performance_service.clone(),
         income_service.clone(),
+        net_worth_service.clone(),
+        health_service.clone(),
+        fx_service.clone(),
+        alternative_asset_service.clone(),
+        scenario_service.clone(),
     ));
     let ai_chat_service = Arc::new(ChatService::new(ai_environment, ChatConfig::default()));
 
@@ -298,21 +411,21 @@ pub async fn initialize_context(
     let cloud_api_url = crate::services::cloud_api_base_url().unwrap_or_default();
     let device_display_name = get_device_display_name();
     let app_version = Some(env!("CARGO_PKG_VERSION").to_string());
-    let device_enroll_service = Arc::new(DeviceEnrollService::new(
-        secret_store.clone(),
-        &cloud_api_url,
-        device_display_name,
-        app_version,
-    ));
+    let device_enroll_service = Arc::new(
+        DeviceEnrollService::new(
+            secret_store.clone(),
+            &cloud_api_url,
+            device_display_name,
+            app_version,
+        )
+        .with_relay(SyncRelayConfig::resolve(secret_store.as_ref())),
+    );
     let device_sync_runtime = Arc::new(DeviceSyncRuntimeState::new());
 
-    // Health service for portfolio health diagnostics
-    let health_dismissal_repository =
-        Arc::new(HealthDismissalRepository::new(pool.clone(), writer.clone()));
-    let health_service = Arc::new(HealthService::new(health_dismissal_repository));
     let folder_sync_runtime = Arc::new(FolderSyncRuntime::spawn(
         app_sync_repository.clone(),
         folder_sync_repository.clone(),
+        secret_store.clone(),
         std::time::Duration::from_secs(10),
     ));
 
@@ -326,7 +439,13 @@ pub async fn initialize_context(
             settings_service,
             account_service,
             activity_service,
+            activity_duplicate_service,
+            activity_staging_service,
+            recurring_activity_service,
             asset_service,
+            change_log_service,
+            attachment_service,
+            attachment_store,
             goal_service,
             quote_service,
             limits_service,
@@ -340,9 +459,14 @@ pub async fn initialize_context(
             folder_sync_runtime,
             holdings_service,
             allocation_service,
+            scenario_service,
             valuation_service,
             net_worth_service,
+            reconciliation_service,
+            holdings_drift_service,
             sync_service,
+            sync_schedule_service,
+            local_backup_service,
             alternative_asset_service,
             taxonomy_service,
             connect_service,
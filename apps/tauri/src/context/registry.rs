@@ -1,14 +1,17 @@
 use std::sync::{Arc, RwLock};
 use wealthfolio_ai::{AiProviderServiceTrait, ChatService};
-use wealthfolio_connect::BrokerSyncServiceTrait;
+use wealthfolio_connect::{BrokerSyncServiceTrait, SyncScheduleService};
 use wealthfolio_core::{
     self, accounts, activities,
     assets::{self, AlternativeAssetServiceTrait},
+    attachments, audit,
     events::DomainEventSink,
-    fx, goals, health, limits, portfolio, quotes, settings, taxonomies,
+    fx, goals, health, limits, portfolio, quotes, recurring, settings, taxonomies,
 };
 use wealthfolio_device_sync::{engine::DeviceSyncRuntimeState, DeviceEnrollService};
 use wealthfolio_storage_sqlite::{
+    attachments::FileAttachmentStore,
+    backup::LocalBackupService,
     portfolio::snapshot::SnapshotRepository,
     settings::SettingsRepository,
     sync::{AppSyncRepository, FolderSyncRepository},
@@ -34,9 +37,15 @@ pub struct ServiceContext {
     pub settings_repository: Arc<SettingsRepository>,
     pub settings_service: Arc<dyn settings::SettingsServiceTrait>,
     pub activity_service: Arc<dyn activities::ActivityServiceTrait>,
+    pub activity_duplicate_service: Arc<dyn activities::ActivityDuplicateServiceTrait>,
+    pub activity_staging_service: Arc<dyn activities::ActivityStagingServiceTrait>,
+    pub recurring_activity_service: Arc<dyn recurring::RecurringActivityServiceTrait>,
     pub account_service: Arc<dyn accounts::AccountServiceTrait>,
     pub goal_service: Arc<dyn goals::GoalServiceTrait>,
     pub asset_service: Arc<dyn assets::AssetServiceTrait>,
+    pub change_log_service: Arc<dyn audit::ChangeLogServiceTrait>,
+    pub attachment_service: Arc<dyn attachments::AttachmentServiceTrait>,
+    pub attachment_store: Arc<FileAttachmentStore>,
     pub quote_service: Arc<dyn quotes::QuoteServiceTrait>,
     pub limits_service: Arc<dyn limits::ContributionLimitServiceTrait>,
     pub fx_service: Arc<dyn fx::FxServiceTrait>,
@@ -50,9 +59,14 @@ pub struct ServiceContext {
     pub folder_sync_runtime: Arc<FolderSyncRuntime>,
     pub holdings_service: Arc<dyn portfolio::holdings::HoldingsServiceTrait>,
     pub allocation_service: Arc<dyn portfolio::allocation::AllocationServiceTrait>,
+    pub scenario_service: Arc<dyn portfolio::scenario::ScenarioServiceTrait>,
     pub valuation_service: Arc<dyn portfolio::valuation::ValuationServiceTrait>,
     pub net_worth_service: Arc<dyn portfolio::net_worth::NetWorthServiceTrait>,
+    pub reconciliation_service: Arc<dyn portfolio::reconciliation::ReconciliationServiceTrait>,
+    pub holdings_drift_service: Arc<dyn portfolio::reconciliation::HoldingsDriftServiceTrait>,
     pub sync_service: Arc<dyn BrokerSyncServiceTrait>,
+    pub sync_schedule_service: Arc<SyncScheduleService>,
+    pub local_backup_service: Arc<LocalBackupService>,
     pub alternative_asset_service: Arc<dyn AlternativeAssetServiceTrait>,
     pub taxonomy_service: Arc<dyn taxonomies::TaxonomyServiceTrait>,
     pub connect_service: Arc<ConnectService>,
@@ -92,10 +106,34 @@ impl ServiceContext {
         Arc::clone(&self.activity_service)
     }
 
+    pub fn activity_duplicate_service(&self) -> Arc<dyn activities::ActivityDuplicateServiceTrait> {
+        Arc::clone(&self.activity_duplicate_service)
+    }
+
+    pub fn activity_staging_service(&self) -> Arc<dyn activities::ActivityStagingServiceTrait> {
+        Arc::clone(&self.activity_staging_service)
+    }
+
+    pub fn recurring_activity_service(&self) -> Arc<dyn recurring::RecurringActivityServiceTrait> {
+        Arc::clone(&self.recurring_activity_service)
+    }
+
     pub fn asset_service(&self) -> Arc<dyn assets::AssetServiceTrait> {
         Arc::clone(&self.asset_service)
     }
 
+    pub fn change_log_service(&self) -> Arc<dyn audit::ChangeLogServiceTrait> {
+        Arc::clone(&self.change_log_service)
+    }
+
+    pub fn attachment_service(&self) -> Arc<dyn attachments::AttachmentServiceTrait> {
+        Arc::clone(&self.attachment_service)
+    }
+
+    pub fn attachment_store(&self) -> Arc<FileAttachmentStore> {
+        Arc::clone(&self.attachment_store)
+    }
+
     pub fn goal_service(&self) -> Arc<dyn goals::GoalServiceTrait> {
         Arc::clone(&self.goal_service)
     }
@@ -149,6 +187,10 @@ impl ServiceContext {
         Arc::clone(&self.allocation_service)
     }
 
+    pub fn scenario_service(&self) -> Arc<dyn portfolio::scenario::ScenarioServiceTrait> {
+        Arc::clone(&self.scenario_service)
+    }
+
     pub fn valuation_service(&self) -> Arc<dyn portfolio::valuation::ValuationServiceTrait> {
         Arc::clone(&self.valuation_service)
     }
@@ -157,10 +199,30 @@ impl ServiceContext {
         Arc::clone(&self.sync_service)
     }
 
+    pub fn sync_schedule_service(&self) -> Arc<SyncScheduleService> {
+        Arc::clone(&self.sync_schedule_service)
+    }
+
+    pub fn local_backup_service(&self) -> Arc<LocalBackupService> {
+        Arc::clone(&self.local_backup_service)
+    }
+
     pub fn net_worth_service(&self) -> Arc<dyn portfolio::net_worth::NetWorthServiceTrait> {
         Arc::clone(&self.net_worth_service)
     }
 
+    pub fn reconciliation_service(
+        &self,
+    ) -> Arc<dyn portfolio::reconciliation::ReconciliationServiceTrait> {
+        Arc::clone(&self.reconciliation_service)
+    }
+
+    pub fn holdings_drift_service(
+        &self,
+    ) -> Arc<dyn portfolio::reconciliation::HoldingsDriftServiceTrait> {
+        Arc::clone(&self.holdings_drift_service)
+    }
+
     pub fn alternative_asset_service(&self) -> Arc<dyn AlternativeAssetServiceTrait> {
         Arc::clone(&self.alternative_asset_service)
     }
@@ -10,6 +10,8 @@ use std::time::Duration;
 use log::{debug, error, info, warn};
 use tauri::{AppHandle, Emitter};
 use tokio::sync::mpsc;
+#[cfg(feature = "connect-sync")]
+use wealthfolio_connect::SyncConfig;
 use wealthfolio_core::constants::PORTFOLIO_TOTAL_ACCOUNT_ID;
 use wealthfolio_core::events::DomainEvent;
 use wealthfolio_core::health::HealthServiceTrait;
@@ -154,7 +156,13 @@ async fn process_event_batch(
             let app_handle_clone = app_handle.clone();
 
             tokio::spawn(async move {
-                match perform_broker_sync(&context_clone, Some(&app_handle_clone)).await {
+                match perform_broker_sync(
+                    &context_clone,
+                    Some(&app_handle_clone),
+                    SyncConfig::default(),
+                )
+                .await
+                {
                     Ok(result) => {
                         info!(
                             "Broker sync completed after tracking mode change: success={}, message={}",
@@ -371,6 +379,19 @@ async fn run_portfolio_calculation(
         account_ids_vec.push(PORTFOLIO_TOTAL_ACCOUNT_ID.to_string());
     }
 
+    // Snapshots now include the synced activities; compare them with the
+    // holdings brokers reported during sync.
+    match context
+        .holdings_drift_service()
+        .evaluate_pending(account_ids.as_deref())
+        .await
+    {
+        Ok(results) => {
+            crate::commands::reconciliation::publish_holdings_drift(context, &results).await;
+        }
+        Err(err) => warn!("Holdings drift check failed: {}", err),
+    }
+
     // Calculate valuation history for each account
     let valuation_service = context.valuation_service();
     for account_id in account_ids_vec {
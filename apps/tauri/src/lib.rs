@@ -10,6 +10,8 @@ mod scheduler;
 mod secret_store;
 mod services;
 
+#[cfg(desktop)]
+mod mcp;
 #[cfg(desktop)]
 mod menu;
 #[cfg(desktop)]
@@ -108,12 +110,63 @@ mod desktop {
             }
         });
 
-        // Trigger startup sync (async, non-blocking)
-        // After this, user manually triggers sync via button
-        let startup_handle = handle.clone();
-        let startup_context = Arc::clone(&context);
+        // Generate recurring activities that became due while the app was closed.
+        // Created activities emit ActivitiesChanged, which schedules recalculation.
+        let startup_recurring_context = Arc::clone(&context);
+        tauri::async_runtime::spawn(async move {
+            let today = chrono::Utc::now().date_naive();
+            match startup_recurring_context
+                .recurring_activity_service()
+                .generate_due(today)
+                .await
+            {
+                Ok(result) => {
+                    if result.generated > 0 {
+                        log::info!(
+                            "Startup recurring generation created {} activities",
+                            result.generated
+                        );
+                    }
+                    for error in &result.errors {
+                        log::warn!("Startup recurring generation error: {}", error);
+                    }
+                }
+                Err(err) => {
+                    log::warn!("Startup recurring generation failed: {}", err);
+                }
+            }
+        });
+
+        // Write the weekly AI portfolio digest if one became due while the app was closed.
+        let startup_digest_context = Arc::clone(&context);
+        tauri::async_runtime::spawn(async move {
+            match startup_digest_context
+                .ai_chat_service()
+                .generate_digest_if_due()
+                .await
+            {
+                Ok(Some(thread)) => {
+                    log::info!("Startup AI portfolio digest written to thread {}", thread.id);
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    log::warn!("Startup AI portfolio digest failed: {}", err);
+                }
+            }
+        });
+
+        // Start scheduled broker sync (per-connection schedules, first check
+        // shortly after startup)
+        let scheduler_handle = handle.clone();
+        let scheduler_context = Arc::clone(&context);
         tauri::async_runtime::spawn(async move {
-            scheduler::run_startup_sync(&startup_handle, &startup_context).await;
+            scheduler::start_broker_sync_scheduler(scheduler_handle, scheduler_context);
+        });
+
+        // Start scheduled local backups (no-op until enabled in settings)
+        let backup_context = Arc::clone(&context);
+        tauri::async_runtime::spawn(async move {
+            scheduler::start_backup_scheduler(backup_context);
         });
 
         context.folder_sync_runtime().trigger_startup();
@@ -228,6 +281,22 @@ fn get_app_data_dir(handle: &AppHandle) -> Result<String, Box<dyn std::error::Er
 pub fn run() {
     dotenv().ok();
 
+    // `--mcp` serves the AI toolset over stdio instead of opening a window
+    #[cfg(desktop)]
+    {
+        let args: Vec<String> = std::env::args().collect();
+        if let Some(access) = mcp::requested_access(&args) {
+            let code = match access {
+                Ok(access) => mcp::run_stdio(access),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    2
+                }
+            };
+            std::process::exit(code);
+        }
+    }
+
     tauri::Builder::default()
         .plugin(
             tauri_plugin_log::Builder::new()
@@ -302,6 +371,13 @@ pub fn run() {
             commands::activity::get_account_import_mapping,
             commands::activity::save_account_import_mapping,
             commands::activity::check_existing_duplicates,
+            commands::activity::get_duplicate_candidates,
+            commands::activity::resolve_duplicate_candidate,
+            commands::activity::get_import_run_review,
+            commands::activity::apply_import_run,
+            commands::activity::discard_import_run,
+            commands::activity::update_staged_activity,
+            commands::activity::set_staged_activity_decision,
             commands::activity::parse_csv,
             // Settings commands
             commands::settings::get_settings,
@@ -324,6 +400,7 @@ pub fn run() {
             commands::portfolio::get_asset_holdings,
             commands::portfolio::get_portfolio_allocations,
             commands::portfolio::get_holdings_by_allocation,
+            commands::portfolio::simulate_scenario,
             commands::portfolio::get_income_summary,
             commands::portfolio::get_historical_valuations,
             commands::portfolio::get_latest_valuations,
@@ -351,11 +428,21 @@ pub fn run() {
             commands::utilities::backup_database,
             commands::utilities::backup_database_to_path,
             commands::utilities::restore_database,
+            commands::utilities::get_backup_schedule,
+            commands::utilities::update_backup_schedule,
+            commands::utilities::list_backups,
+            commands::utilities::run_backup,
+            commands::utilities::preview_backup_restore,
+            commands::utilities::restore_backup,
             commands::folder_sync::get_folder_sync_state,
             commands::folder_sync::initialize_folder_sync,
             commands::folder_sync::join_folder_sync,
             commands::folder_sync::retry_folder_sync_now,
+            commands::folder_sync::unlock_folder_sync,
+            commands::folder_sync::change_folder_sync_passphrase,
             commands::folder_sync::disable_folder_sync,
+            commands::sync_conflicts::list_sync_conflicts,
+            commands::sync_conflicts::resolve_sync_conflict,
             // Asset commands
             commands::asset::get_asset_profile,
             commands::asset::get_assets,
@@ -419,6 +506,9 @@ pub fn run() {
             commands::ai_providers::get_ai_providers,
             commands::ai_providers::update_ai_provider_settings,
             commands::ai_providers::set_default_ai_provider,
+            commands::ai_providers::set_ai_usage_budget,
+            commands::ai_providers::save_custom_ai_provider,
+            commands::ai_providers::delete_custom_ai_provider,
             commands::ai_providers::list_ai_models,
             // AI chat commands
             commands::ai_chat::stream_ai_chat,
@@ -431,6 +521,7 @@ pub fn run() {
             commands::ai_chat::remove_ai_thread_tag,
             commands::ai_chat::get_ai_thread_tags,
             commands::ai_chat::update_tool_result,
+            commands::ai_chat::get_ai_usage_report,
             // Addon commands
             commands::addon::extract_addon_zip,
             commands::addon::install_addon_zip,
@@ -480,6 +571,22 @@ pub fn run() {
             commands::brokers_sync::get_import_runs,
             #[cfg(feature = "connect-sync")]
             commands::brokers_sync::get_data_import_runs,
+            #[cfg(feature = "connect-sync")]
+            commands::brokers_sync::reconcile_broker_account,
+            #[cfg(feature = "connect-sync")]
+            commands::brokers_sync::list_local_connectors,
+            #[cfg(feature = "connect-sync")]
+            commands::brokers_sync::configure_local_connector,
+            #[cfg(feature = "connect-sync")]
+            commands::brokers_sync::remove_local_connector,
+            #[cfg(feature = "connect-sync")]
+            commands::brokers_sync::sync_local_connector,
+            #[cfg(feature = "connect-sync")]
+            commands::brokers_sync::get_sync_schedules,
+            #[cfg(feature = "connect-sync")]
+            commands::brokers_sync::update_sync_schedule,
+            #[cfg(feature = "connect-sync")]
+            commands::brokers_sync::repair_broker_sync,
             // Device sync commands
             #[cfg(feature = "device-sync")]
             commands::device_sync::enroll_device,
@@ -563,6 +670,12 @@ pub fn run() {
             commands::device_enroll_service::clear_device_sync_data,
             #[cfg(feature = "device-sync")]
             commands::device_enroll_service::reinitialize_device_sync,
+            #[cfg(feature = "device-sync")]
+            commands::device_enroll_service::get_device_sync_relay,
+            #[cfg(feature = "device-sync")]
+            commands::device_enroll_service::set_device_sync_relay,
+            #[cfg(feature = "device-sync")]
+            commands::device_enroll_service::clear_device_sync_relay,
             // Sync crypto commands
             #[cfg(feature = "device-sync")]
             commands::sync_crypto::sync_generate_root_key,
@@ -597,6 +710,25 @@ pub fn run() {
             commands::health::execute_health_fix,
             commands::health::get_health_config,
             commands::health::update_health_config,
+            // Reconciliation commands
+            commands::reconciliation::reconcile_broker_statement,
+            commands::reconciliation::reconcile_broker_statement_csv,
+            commands::reconciliation::get_holdings_drift_history,
+            // Recurring activity commands
+            commands::recurring::get_recurring_templates,
+            commands::recurring::create_recurring_template,
+            commands::recurring::update_recurring_template,
+            commands::recurring::delete_recurring_template,
+            commands::recurring::preview_recurring_occurrences,
+            commands::recurring::generate_recurring_activities,
+            commands::audit::get_entity_history,
+            commands::audit::get_import_run_changes,
+            commands::audit::rollback_change,
+            commands::audit::rollback_import_run,
+            commands::attachments::list_attachments,
+            commands::attachments::upload_attachment,
+            commands::attachments::get_attachment_content,
+            commands::attachments::delete_attachment,
         ])
         .build(tauri::generate_context!())
         .expect("Failed to build Wealthfolio application")
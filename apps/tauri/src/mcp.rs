@@ -0,0 +1,103 @@
+//! Headless Model Context Protocol server over stdio.
+//!
+//! Local agents and editors launch the app binary with `--mcp` to use the
+//! portfolio tools without opening a window:
+//!
+//! ```text
+//! wealthfolio --mcp                          # read-only tools
+//! wealthfolio --mcp --mcp-access read-write  # also the activity drafting tools
+//! ```
+//!
+//! The process opens the same database as the desktop app and exits when the
+//! client closes stdin. Stdout carries protocol messages only.
+
+use std::path::PathBuf;
+
+use wealthfolio_ai::mcp::{serve_stdio, McpAccess, McpServer};
+
+use crate::context;
+
+const MCP_FLAG: &str = "--mcp";
+const MCP_ACCESS_FLAG: &str = "--mcp-access";
+
+/// Returns the requested access when the app was started with `--mcp`.
+pub fn requested_access(args: &[String]) -> Option<Result<McpAccess, String>> {
+    if !args.iter().any(|arg| arg == MCP_FLAG) {
+        return None;
+    }
+    let access = match args.iter().position(|arg| arg == MCP_ACCESS_FLAG) {
+        Some(index) => args
+            .get(index + 1)
+            .ok_or_else(|| format!("{} requires a value", MCP_ACCESS_FLAG))
+            .and_then(|value| value.parse::<McpAccess>().map_err(|e| e.to_string())),
+        None => Ok(McpAccess::ReadOnly),
+    };
+    Some(access)
+}
+
+/// Same directory Tauri resolves for `app_data_dir()`: the platform data
+/// directory joined with the bundle identifier.
+fn app_data_dir() -> Result<String, String> {
+    let config: serde_json::Value = serde_json::from_str(include_str!("../tauri.conf.json"))
+        .map_err(|e| format!("Invalid tauri.conf.json: {}", e))?;
+    let identifier = config["identifier"]
+        .as_str()
+        .ok_or("Missing identifier in tauri.conf.json")?;
+    let data_dir: PathBuf = dirs::data_dir().ok_or("No data directory on this platform")?;
+    Ok(data_dir.join(identifier).to_string_lossy().into_owned())
+}
+
+/// Serves MCP on stdin/stdout until the client disconnects. Returns the
+/// process exit code.
+pub fn run_stdio(access: McpAccess) -> i32 {
+    let result = tauri::async_runtime::block_on(async move {
+        let app_data_dir = app_data_dir()?;
+        let init_result = context::initialize_context(&app_data_dir)
+            .await
+            .map_err(|e| format!("Failed to initialize context: {}", e))?;
+        let env = init_result.context.ai_chat_service().env().clone();
+        let server = McpServer::new(env, access);
+        serve_stdio(&server)
+            .await
+            .map_err(|e| format!("MCP transport error: {}", e))
+    });
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_requested_access() {
+        assert!(requested_access(&args(&["wealthfolio"])).is_none());
+        assert_eq!(
+            requested_access(&args(&["wealthfolio", "--mcp"])),
+            Some(Ok(McpAccess::ReadOnly))
+        );
+        assert_eq!(
+            requested_access(&args(&[
+                "wealthfolio",
+                "--mcp",
+                "--mcp-access",
+                "read-write"
+            ])),
+            Some(Ok(McpAccess::ReadWrite))
+        );
+        assert!(matches!(
+            requested_access(&args(&["wealthfolio", "--mcp", "--mcp-access"])),
+            Some(Err(_))
+        ));
+    }
+}
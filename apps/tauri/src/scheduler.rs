@@ -1,108 +1,103 @@
-//! Startup sync for broker data.
+//! Scheduled sync for broker data and scheduled local backups.
 //!
-//! Syncs broker data once on app startup. After that, user manually triggers sync.
+//! Runs each connection (Wealthfolio Connect and local connectors) on its
+//! configured schedule using the shared [`BrokerSyncScheduler`]. Users can
+//! still trigger a sync manually at any time. Encrypted local backups run on
+//! their own cadence through the shared [`BackupScheduler`].
 
 #[cfg(feature = "connect-sync")]
 use std::sync::Arc;
 
 #[cfg(feature = "connect-sync")]
-use log::{debug, info, warn};
+use async_trait::async_trait;
+#[cfg(feature = "connect-sync")]
+use log::{debug, info};
 #[cfg(not(feature = "connect-sync"))]
 use tauri::AppHandle;
 #[cfg(feature = "connect-sync")]
 use tauri::AppHandle;
 
+#[cfg(feature = "connect-sync")]
+use wealthfolio_connect::{BrokerSyncScheduler, ScheduledSyncRunner, SyncConfig, SyncResult};
 #[cfg(feature = "connect-sync")]
 use wealthfolio_core::quotes::MarketSyncMode;
+use wealthfolio_storage_sqlite::backup::BackupScheduler;
 
 #[cfg(feature = "connect-sync")]
-use crate::commands::brokers_sync::perform_broker_sync;
+use crate::commands::brokers_sync::perform_source_sync;
 use crate::context::ServiceContext;
 
-/// Runs broker sync once on startup (async, non-blocking).
-///
-/// This function:
-/// - Checks if user has an active subscription
-/// - Performs the sync silently (no toast - user didn't request it)
-/// - Triggers portfolio update if activities were synced
+/// Runs scheduled syncs silently (no toast - user didn't request it) and
+/// triggers a portfolio update when data changed.
 #[cfg(feature = "connect-sync")]
-pub async fn run_startup_sync(handle: &AppHandle, context: &Arc<ServiceContext>) {
-    info!("Running startup broker sync...");
-
-    // Check whether the current plan actually includes broker sync.
-    match context.connect_service().has_broker_sync().await {
-        Ok(true) => {
-            // User has active subscription, proceed
-        }
-        Ok(false) => {
-            debug!("Startup sync skipped: broker sync is not available for this account");
-            return;
-        }
-        Err(e) => {
-            // If we can't check subscription (no token, network error, etc.), skip silently
-            debug!(
-                "Startup sync skipped: could not verify subscription ({})",
-                e
-            );
-            return;
-        }
-    }
-
-    // Perform sync (orchestrator emits broker:sync-start and broker:sync-complete events)
-    match perform_broker_sync(context, Some(handle)).await {
-        Ok(result) => {
-            info!(
-                "Startup sync completed: success={}, message={}",
-                result.success, result.message
-            );
-
-            // Note: broker:sync-complete event is emitted by the orchestrator via TauriProgressReporter
+struct TauriSyncRunner {
+    handle: AppHandle,
+    context: Arc<ServiceContext>,
+}
 
-            // Trigger portfolio update if sync was successful
-            // Note: Asset enrichment is handled automatically via domain events (AssetsCreated)
-            if result.success {
-                if let Some(ref activities) = result.activities_synced {
-                    if activities.activities_upserted > 0 {
-                        info!(
-                            "Triggering portfolio update after startup sync ({} activities synced)",
-                            activities.activities_upserted
-                        );
-                        crate::events::emit_portfolio_trigger_recalculate(
-                            handle,
-                            crate::events::PortfolioRequestPayload::builder()
-                                .market_sync_mode(MarketSyncMode::Incremental { asset_ids: None })
-                                .build(),
-                        );
-                    }
+#[cfg(feature = "connect-sync")]
+#[async_trait]
+impl ScheduledSyncRunner for TauriSyncRunner {
+    async fn run_sync(
+        &self,
+        source_id: &str,
+        config: SyncConfig,
+    ) -> Result<Option<SyncResult>, String> {
+        // Orchestrator emits broker:sync-start and broker:sync-complete events
+        let result =
+            match perform_source_sync(&self.context, Some(&self.handle), source_id, config).await {
+                Ok(result) => result,
+                // Expected when the user is not logged in
+                Err(e) if e.contains("No access token") || e.contains("not authenticated") => {
+                    debug!(
+                        "Scheduled sync for '{}' skipped: user not authenticated",
+                        source_id
+                    );
+                    return Ok(None);
                 }
+                Err(e) => return Err(e),
+            };
 
-                if let Some(ref holdings) = result.holdings_synced {
-                    if holdings.positions_upserted > 0 {
-                        info!(
-                            "Triggering portfolio update after holdings sync ({} positions synced)",
-                            holdings.positions_upserted
-                        );
-                        crate::events::emit_portfolio_trigger_recalculate(
-                            handle,
-                            crate::events::PortfolioRequestPayload::builder()
-                                .market_sync_mode(MarketSyncMode::Incremental { asset_ids: None })
-                                .build(),
-                        );
-                    }
-                }
-            }
-        }
-        Err(e) => {
-            // Check if this is an auth error (user not logged in)
-            if e.contains("No access token") || e.contains("not authenticated") {
-                debug!("Startup sync skipped: user not authenticated");
-            } else {
-                warn!("Startup sync failed: {}", e);
-                // Note: broker:sync-error event is emitted by the orchestrator via TauriProgressReporter
+        // Note: Asset enrichment is handled automatically via domain events (AssetsCreated)
+        if let Some(result) = result.as_ref().filter(|r| r.success) {
+            let activities_upserted = result
+                .activities_synced
+                .as_ref()
+                .map_or(0, |a| a.activities_upserted);
+            let positions_upserted = result
+                .holdings_synced
+                .as_ref()
+                .map_or(0, |h| h.positions_upserted);
+            if activities_upserted > 0 || positions_upserted > 0 {
+                info!(
+                    "Triggering portfolio update after scheduled sync ({} activities, {} positions)",
+                    activities_upserted, positions_upserted
+                );
+                crate::events::emit_portfolio_trigger_recalculate(
+                    &self.handle,
+                    crate::events::PortfolioRequestPayload::builder()
+                        .market_sync_mode(MarketSyncMode::Incremental { asset_ids: None })
+                        .build(),
+                );
             }
         }
+
+        Ok(result)
     }
 }
 
+/// Starts the broker sync scheduler. Must be called from within the async runtime.
+#[cfg(feature = "connect-sync")]
+pub fn start_broker_sync_scheduler(handle: AppHandle, context: Arc<ServiceContext>) {
+    let schedule_service = context.sync_schedule_service();
+    let runner = Arc::new(TauriSyncRunner { handle, context });
+    Arc::new(BrokerSyncScheduler::new(schedule_service, runner)).start();
+}
+
 #[cfg(not(feature = "connect-sync"))]
-pub async fn run_startup_sync(_handle: &AppHandle, _context: &std::sync::Arc<ServiceContext>) {}
+pub fn start_broker_sync_scheduler(_handle: AppHandle, _context: std::sync::Arc<ServiceContext>) {}
+
+/// Starts the backup scheduler. Must be called from within the async runtime.
+pub fn start_backup_scheduler(context: std::sync::Arc<ServiceContext>) {
+    std::sync::Arc::new(BackupScheduler::new(context.local_backup_service())).start();
+}
@@ -0,0 +1,421 @@
+//! Passphrase-based encryption for folder sync.
+//!
+//! The folder key is derived from a passphrase with Argon2id using the KDF
+//! parameters stored in `folder.json`. Only the derived key is kept on the
+//! device, in the secret store; the passphrase itself is never persisted.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use wealthfolio_core::secrets::SecretStore;
+use wealthfolio_core::sync::{
+    FolderSyncEncryptedFileV1, FolderSyncEncryptionV1, FolderSyncKdfV1,
+    FOLDER_SYNC_CIPHER_XCHACHA20POLY1305, FOLDER_SYNC_ENCRYPTION_VERSION_V1,
+    FOLDER_SYNC_KDF_ARGON2ID, FOLDER_SYNC_VERSION_V1,
+};
+use wealthfolio_device_sync::crypto::{self, PassphraseKdfParams};
+
+use crate::services::folder_sync_fs::FolderSyncFsService;
+use crate::services::folder_sync_storage::open_storage;
+
+/// Secret store key holding the derived folder key.
+pub const FOLDER_SYNC_KEY_SECRET: &str = "folder_sync_key";
+/// Secret store key holding the header and key of an unfinished passphrase change.
+pub const FOLDER_SYNC_PENDING_KEY_SECRET: &str = "folder_sync_pending_key";
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+// The header is shared folder content, so its KDF cost is bounded before use.
+const KDF_MEMORY_KIB_RANGE: RangeInclusive<u32> = 8 * 1024..=256 * 1024;
+const KDF_ITERATIONS_RANGE: RangeInclusive<u32> = 1..=10;
+const KDF_PARALLELISM_RANGE: RangeInclusive<u32> = 1..=8;
+
+/// A verified folder key, tagged with the key id from the folder header.
+#[derive(Clone)]
+pub struct FolderSyncKey {
+    key_id: String,
+    key: String,
+}
+
+impl fmt::Debug for FolderSyncKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FolderSyncKey")
+            .field("key_id", &self.key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FolderSyncKey {
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    pub fn seal(&self, plaintext: &str) -> Result<FolderSyncEncryptedFileV1, String> {
+        Ok(FolderSyncEncryptedFileV1 {
+            version: FOLDER_SYNC_VERSION_V1,
+            key_id: self.key_id.clone(),
+            ciphertext: crypto::encrypt(&self.key, plaintext)?,
+        })
+    }
+
+    pub fn open(&self, envelope: &FolderSyncEncryptedFileV1) -> Result<String, String> {
+        if envelope.key_id != self.key_id {
+            return Err(format!(
+                "File is encrypted with a different folder key ('{}')",
+                envelope.key_id
+            ));
+        }
+        crypto::decrypt(&self.key, &envelope.ciphertext)
+    }
+
+    pub fn encrypt_bytes(&self, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+        crypto::encrypt_bytes(&self.key, plaintext)
+    }
+
+    pub fn decrypt_bytes(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        crypto::decrypt_bytes(&self.key, data)
+    }
+}
+
+/// Creates a new encryption header and the key derived from `passphrase`.
+pub fn create_encryption(
+    passphrase: &str,
+) -> Result<(FolderSyncEncryptionV1, FolderSyncKey), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!(
+            "Passphrase must be at least {} characters",
+            MIN_PASSPHRASE_LEN
+        ));
+    }
+
+    let params = PassphraseKdfParams::default();
+    let salt = crypto::generate_salt();
+    let key = crypto::derive_passphrase_key(passphrase, &salt, &params)?;
+    let key_id = Uuid::new_v4().to_string();
+    let key_check = crypto::hmac_sha256(&key, &key_id)?;
+
+    let encryption = FolderSyncEncryptionV1 {
+        version: FOLDER_SYNC_ENCRYPTION_VERSION_V1,
+        cipher: FOLDER_SYNC_CIPHER_XCHACHA20POLY1305.to_string(),
+        kdf: FolderSyncKdfV1 {
+            algorithm: FOLDER_SYNC_KDF_ARGON2ID.to_string(),
+            salt,
+            memory_kib: params.memory_kib,
+            iterations: params.iterations,
+            parallelism: params.parallelism,
+        },
+        key_id: key_id.clone(),
+        key_check,
+        rotated_at: None,
+    };
+    Ok((encryption, FolderSyncKey { key_id, key }))
+}
+
+/// Derives the folder key from `passphrase` and verifies it against the header.
+pub fn unlock(
+    encryption: &FolderSyncEncryptionV1,
+    passphrase: &str,
+) -> Result<FolderSyncKey, String> {
+    if encryption.version != FOLDER_SYNC_ENCRYPTION_VERSION_V1
+        || encryption.cipher != FOLDER_SYNC_CIPHER_XCHACHA20POLY1305
+        || encryption.kdf.algorithm != FOLDER_SYNC_KDF_ARGON2ID
+    {
+        return Err(format!(
+            "Unsupported folder encryption '{}' v{} with KDF '{}'",
+            encryption.cipher, encryption.version, encryption.kdf.algorithm
+        ));
+    }
+    let kdf = &encryption.kdf;
+    if !KDF_MEMORY_KIB_RANGE.contains(&kdf.memory_kib)
+        || !KDF_ITERATIONS_RANGE.contains(&kdf.iterations)
+        || !KDF_PARALLELISM_RANGE.contains(&kdf.parallelism)
+    {
+        return Err(format!(
+            "Unsupported folder KDF parameters: {} KiB, {} iteration(s), parallelism {}",
+            kdf.memory_kib, kdf.iterations, kdf.parallelism
+        ));
+    }
+
+    let key = crypto::derive_passphrase_key(
+        passphrase,
+        &kdf.salt,
+        &PassphraseKdfParams {
+            memory_kib: kdf.memory_kib,
+            iterations: kdf.iterations,
+            parallelism: kdf.parallelism,
+        },
+    )?;
+    if !key_matches(encryption, &key) {
+        return Err("Incorrect folder sync passphrase".to_string());
+    }
+    Ok(FolderSyncKey {
+        key_id: encryption.key_id.clone(),
+        key,
+    })
+}
+
+fn key_matches(encryption: &FolderSyncEncryptionV1, key: &str) -> bool {
+    crypto::hmac_sha256(key, &encryption.key_id)
+        .map(|check| check == encryption.key_check)
+        .unwrap_or(false)
+}
+
+/// Loads the stored key if it still matches the folder header.
+/// Returns `None` after another device rotated the passphrase.
+pub fn load_stored_key(
+    secret_store: &dyn SecretStore,
+    encryption: &FolderSyncEncryptionV1,
+) -> Result<Option<FolderSyncKey>, String> {
+    let stored = secret_store
+        .get_secret(FOLDER_SYNC_KEY_SECRET)
+        .map_err(|err| format!("Failed to read folder sync key: {err}"))?;
+    match stored {
+        Some(key) if key_matches(encryption, &key) => Ok(Some(FolderSyncKey {
+            key_id: encryption.key_id.clone(),
+            key,
+        })),
+        // This device may have rewritten the folder but stopped before saving the key.
+        _ => complete_rotation(secret_store, encryption),
+    }
+}
+
+pub fn store_key(secret_store: &dyn SecretStore, key: &FolderSyncKey) -> Result<(), String> {
+    secret_store
+        .set_secret(FOLDER_SYNC_KEY_SECRET, &key.key)
+        .map_err(|err| format!("Failed to save folder sync key: {err}"))
+}
+
+/// Header and key of a passphrase change, saved before any file is rewritten.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PendingRotation {
+    encryption: FolderSyncEncryptionV1,
+    key: String,
+}
+
+fn load_pending_rotation(
+    secret_store: &dyn SecretStore,
+) -> Result<Option<PendingRotation>, String> {
+    let Some(json) = secret_store
+        .get_secret(FOLDER_SYNC_PENDING_KEY_SECRET)
+        .map_err(|err| format!("Failed to read pending folder sync key: {err}"))?
+    else {
+        return Ok(None);
+    };
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|err| format!("Failed to parse pending folder sync key: {err}"))
+}
+
+/// Returns the header and key for changing to `new_passphrase`.
+///
+/// Files are re-encrypted one by one, so the new key is journaled in the
+/// secret store first. A change that was interrupted is resumed with the
+/// journaled key, which keeps the files it already rewrote readable.
+pub fn begin_rotation(
+    secret_store: &dyn SecretStore,
+    new_passphrase: &str,
+    rotated_at: Option<String>,
+) -> Result<(FolderSyncEncryptionV1, FolderSyncKey), String> {
+    if let Some(pending) = load_pending_rotation(secret_store)? {
+        let key = unlock(&pending.encryption, new_passphrase).map_err(|_| {
+            "A previous passphrase change was interrupted; finish it with the same new passphrase"
+                .to_string()
+        })?;
+        return Ok((pending.encryption, key));
+    }
+
+    let (mut encryption, key) = create_encryption(new_passphrase)?;
+    encryption.rotated_at = rotated_at;
+    let pending = PendingRotation {
+        encryption: encryption.clone(),
+        key: key.key.clone(),
+    };
+    let json = serde_json::to_string(&pending)
+        .map_err(|err| format!("Failed to serialize pending folder sync key: {err}"))?;
+    secret_store
+        .set_secret(FOLDER_SYNC_PENDING_KEY_SECRET, &json)
+        .map_err(|err| format!("Failed to save pending folder sync key: {err}"))?;
+    Ok((encryption, key))
+}
+
+/// Switches to the journaled key once the folder header uses it.
+/// Returns `None` when there is no pending change for `encryption`.
+pub fn complete_rotation(
+    secret_store: &dyn SecretStore,
+    encryption: &FolderSyncEncryptionV1,
+) -> Result<Option<FolderSyncKey>, String> {
+    let Some(pending) = load_pending_rotation(secret_store)? else {
+        return Ok(None);
+    };
+    if pending.encryption.key_id != encryption.key_id || !key_matches(encryption, &pending.key) {
+        return Ok(None);
+    }
+
+    let key = FolderSyncKey {
+        key_id: encryption.key_id.clone(),
+        key: pending.key,
+    };
+    store_key(secret_store, &key)?;
+    secret_store
+        .delete_secret(FOLDER_SYNC_PENDING_KEY_SECRET)
+        .map_err(|err| format!("Failed to clear pending folder sync key: {err}"))?;
+    Ok(Some(key))
+}
+
+/// Result of opening the shared folder with the locally stored key.
+#[derive(Debug, Clone)]
+pub enum FolderSyncAccess {
+    Ready(FolderSyncFsService),
+    /// The folder is encrypted and this device has no valid key for it.
+    PassphraseRequired,
+}
+
+/// Opens the configured shared folder, attaching the stored key when it is
+/// encrypted.
+pub async fn open_shared_folder(
+    location: &str,
+    secret_store: &dyn SecretStore,
+) -> Result<FolderSyncAccess, String> {
+    let fs_service = FolderSyncFsService::with_storage(open_storage(location, secret_store)?);
+    let Some(encryption) = fs_service
+        .read_metadata()
+        .await?
+        .and_then(|metadata| metadata.encryption)
+    else {
+        return Ok(FolderSyncAccess::Ready(fs_service));
+    };
+
+    Ok(match load_stored_key(secret_store, &encryption)? {
+        Some(key) => FolderSyncAccess::Ready(fs_service.with_key(key)),
+        None => FolderSyncAccess::PassphraseRequired,
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::collections::HashMap;
+    use std::sync::RwLock;
+
+    use wealthfolio_core::secrets::SecretStore;
+
+    use super::{
+        begin_rotation, complete_rotation, create_encryption, load_stored_key, store_key, unlock,
+        FOLDER_SYNC_PENDING_KEY_SECRET,
+    };
+
+    #[derive(Default)]
+    pub(crate) struct MemorySecretStore {
+        secrets: RwLock<HashMap<String, String>>,
+    }
+
+    impl SecretStore for MemorySecretStore {
+        fn set_secret(&self, service: &str, secret: &str) -> wealthfolio_core::Result<()> {
+            self.secrets
+                .write()
+                .unwrap()
+                .insert(service.to_string(), secret.to_string());
+            Ok(())
+        }
+
+        fn get_secret(&self, service: &str) -> wealthfolio_core::Result<Option<String>> {
+            Ok(self.secrets.read().unwrap().get(service).cloned())
+        }
+
+        fn delete_secret(&self, service: &str) -> wealthfolio_core::Result<()> {
+            self.secrets.write().unwrap().remove(service);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn unlock_accepts_only_the_original_passphrase() {
+        let (encryption, key) = create_encryption("correct horse battery").expect("create");
+
+        let unlocked = unlock(&encryption, "correct horse battery").expect("unlock");
+        assert_eq!(unlocked.key_id(), key.key_id());
+
+        let err = unlock(&encryption, "wrong horse battery").expect_err("wrong passphrase");
+        assert_eq!(err, "Incorrect folder sync passphrase");
+        assert!(create_encryption("short").is_err());
+    }
+
+    #[test]
+    fn unlock_rejects_kdf_parameters_out_of_bounds() {
+        let (encryption, _) = create_encryption("correct horse battery").expect("create");
+
+        let mut hostile = encryption.clone();
+        hostile.kdf.memory_kib = 64 * 1024 * 1024;
+        let err = unlock(&hostile, "correct horse battery").expect_err("memory bound");
+        assert!(
+            err.starts_with("Unsupported folder KDF parameters"),
+            "{err}"
+        );
+
+        let mut hostile = encryption.clone();
+        hostile.kdf.iterations = 1_000_000;
+        assert!(unlock(&hostile, "correct horse battery").is_err());
+
+        let mut hostile = encryption;
+        hostile.kdf.parallelism = 0;
+        assert!(unlock(&hostile, "correct horse battery").is_err());
+    }
+
+    #[test]
+    fn pending_rotation_is_reused_and_completed_once_live() {
+        let store = MemorySecretStore::default();
+        let (pending, key) =
+            begin_rotation(&store, "new horse battery", None).expect("begin rotation");
+        assert!(store
+            .get_secret(FOLDER_SYNC_PENDING_KEY_SECRET)
+            .expect("read")
+            .is_some());
+
+        let (resumed, resumed_key) =
+            begin_rotation(&store, "new horse battery", None).expect("resume rotation");
+        assert_eq!(resumed, pending);
+        assert_eq!(resumed_key.key_id(), key.key_id());
+        assert!(begin_rotation(&store, "other horse battery", None).is_err());
+
+        let (unrelated, _) = create_encryption("correct horse battery").expect("create");
+        assert!(complete_rotation(&store, &unrelated)
+            .expect("complete")
+            .is_none());
+
+        // The header went live but the key was never stored.
+        let loaded = load_stored_key(&store, &pending)
+            .expect("load")
+            .expect("pending key is promoted");
+        assert_eq!(loaded.key_id(), key.key_id());
+        assert!(store
+            .get_secret(FOLDER_SYNC_PENDING_KEY_SECRET)
+            .expect("read")
+            .is_none());
+    }
+
+    #[test]
+    fn stored_key_is_dropped_after_rotation() {
+        let store = MemorySecretStore::default();
+        let (encryption, key) = create_encryption("correct horse battery").expect("create");
+        store_key(&store, &key).expect("store key");
+
+        assert!(load_stored_key(&store, &encryption)
+            .expect("load")
+            .is_some());
+
+        let (rotated, _) = create_encryption("another horse battery").expect("rotate");
+        assert!(load_stored_key(&store, &rotated).expect("load").is_none());
+    }
+
+    #[test]
+    fn sealed_files_round_trip_and_reject_other_keys() {
+        let (_, key) = create_encryption("correct horse battery").expect("create");
+        let (_, other) = create_encryption("another horse battery").expect("create other");
+
+        let envelope = key.seal(r#"{"eventId":"evt-1"}"#).expect("seal");
+        assert!(!envelope.ciphertext.contains("evt-1"));
+        assert_eq!(key.open(&envelope).expect("open"), r#"{"eventId":"evt-1"}"#);
+        assert!(other.open(&envelope).is_err());
+    }
+}
@@ -1,12 +1,9 @@
 //! Export pending local sync mutations into shared-folder event files.
 
-use std::fs;
 use std::sync::Arc;
 
 use chrono::Utc;
-use wealthfolio_core::sync::{
-    event_file_name, FolderSyncEventFileV1, FolderSyncMetadataV1, FOLDER_SYNC_VERSION_V1,
-};
+use wealthfolio_core::sync::{FolderSyncEventFileV1, FolderSyncMetadataV1, FOLDER_SYNC_VERSION_V1};
 use wealthfolio_storage_sqlite::sync::{
     AppSyncRepository, FolderSyncRepository, FolderSyncStatusUpdate,
 };
@@ -45,7 +42,7 @@ impl FolderSyncExporter {
         limit: i64,
     ) -> Result<FolderSyncExportResult, String> {
         let now = Utc::now().to_rfc3339();
-        self.ensure_folder_initialized(&now)?;
+        self.ensure_folder_initialized(&now).await?;
         self.folder_sync_repository
             .update_status(FolderSyncStatusUpdate {
                 sync_state: Some("exporting".to_string()),
@@ -94,8 +91,8 @@ impl FolderSyncExporter {
                 app_version: Some(env!("CARGO_PKG_VERSION").to_string()),
             };
 
-            if let Err(err) = self.fs_service.write_event_file(&event_file) {
-                if !self.is_existing_event_file(&event_file)? {
+            if let Err(err) = self.fs_service.write_event_file(&event_file).await {
+                if !self.is_existing_event_file(&event_file).await? {
                     self.record_export_error(&event.event_id, &err).await?;
                     return Err(err);
                 }
@@ -140,33 +137,30 @@ impl FolderSyncExporter {
         Ok(FolderSyncExportResult { exported_event_ids })
     }
 
-    fn ensure_folder_initialized(&self, timestamp: &str) -> Result<(), String> {
-        self.fs_service.initialize_folder(
-            &self.local_device_id,
-            &FolderSyncMetadataV1 {
-                version: FOLDER_SYNC_VERSION_V1,
-                created_at: timestamp.to_string(),
-                created_by_device_id: self.local_device_id.clone(),
-            },
-        )
+    async fn ensure_folder_initialized(&self, timestamp: &str) -> Result<(), String> {
+        self.fs_service
+            .initialize_folder(
+                &self.local_device_id,
+                &FolderSyncMetadataV1 {
+                    version: FOLDER_SYNC_VERSION_V1,
+                    created_at: timestamp.to_string(),
+                    created_by_device_id: self.local_device_id.clone(),
+                    encryption: None,
+                },
+            )
+            .await
     }
 
-    fn is_existing_event_file(&self, event_file: &FolderSyncEventFileV1) -> Result<bool, String> {
-        let path = self
+    async fn is_existing_event_file(
+        &self,
+        event_file: &FolderSyncEventFileV1,
+    ) -> Result<bool, String> {
+        let existing = self
             .fs_service
-            .root()
-            .join("events")
-            .join(&self.local_device_id)
-            .join(event_file_name(&event_file.event_id));
-        if !path.exists() {
-            return Ok(false);
-        }
-
-        let existing: FolderSyncEventFileV1 = serde_json::from_slice(
-            &fs::read(&path).map_err(|err| format!("Failed to read existing event file: {err}"))?,
-        )
-        .map_err(|err| format!("Failed to parse existing event file: {err}"))?;
-        Ok(existing == *event_file)
+            .find_event_file(&self.local_device_id, &event_file.event_id)
+            .await
+            .map_err(|err| format!("Failed to read existing event file: {err}"))?;
+        Ok(existing.as_ref() == Some(event_file))
     }
 
     async fn record_export_error(&self, event_id: &str, error: &str) -> Result<(), String> {
@@ -1,119 +1,174 @@
-//! File system service for Syncthing-backed folder sync.
+//! Shared folder layout for folder sync, on top of a storage backend.
 
-use std::fs;
-use std::path::{Path, PathBuf};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::sync::Arc;
 
-use uuid::Uuid;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use wealthfolio_core::sync::{
-    event_file_name, snapshot_file_name, FolderSyncEventFileV1, FolderSyncMetadataV1,
-    FolderSyncSnapshotManifestV1, FOLDER_SYNC_METADATA_FILE,
+    event_file_name, snapshot_file_name, FolderSyncEncryptedFileV1, FolderSyncEventFileV1,
+    FolderSyncMetadataV1, FolderSyncSnapshotManifestV1, FOLDER_SYNC_METADATA_FILE,
 };
 
+use crate::services::folder_sync_crypto::FolderSyncKey;
+use crate::services::folder_sync_storage::{FolderSyncStorage, LocalFolderStorage};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FolderSyncEventFileRef {
     pub device_id: String,
     pub event_id: String,
-    pub path: PathBuf,
+    pub key: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FolderSyncSnapshotFileRef {
     pub device_id: String,
     pub snapshot_id: String,
-    pub db_path: PathBuf,
-    pub manifest_path: PathBuf,
+    pub db_key: String,
+    pub manifest_key: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FolderSyncRewriteResult {
+    pub event_files: usize,
+    pub snapshots: usize,
 }
 
+/// Reads and writes the shared folder layout. When a key is attached, event
+/// files, snapshot manifests and snapshot databases are encrypted at rest.
 #[derive(Debug, Clone)]
 pub struct FolderSyncFsService {
-    root: PathBuf,
+    storage: Arc<dyn FolderSyncStorage>,
+    key: Option<FolderSyncKey>,
 }
 
 impl FolderSyncFsService {
+    /// A shared folder in a locally mounted directory.
     pub fn new(root: impl Into<PathBuf>) -> Self {
-        Self { root: root.into() }
+        Self::with_storage(Arc::new(LocalFolderStorage::new(root)))
+    }
+
+    pub fn with_storage(storage: Arc<dyn FolderSyncStorage>) -> Self {
+        Self { storage, key: None }
+    }
+
+    pub fn with_key(mut self, key: FolderSyncKey) -> Self {
+        self.key = Some(key);
+        self
     }
 
-    pub fn root(&self) -> &Path {
-        &self.root
+    pub fn location(&self) -> String {
+        self.storage.location()
     }
 
-    pub fn initialize_folder(
+    pub fn is_encrypted(&self) -> bool {
+        self.key.is_some()
+    }
+
+    pub async fn read_metadata(&self) -> Result<Option<FolderSyncMetadataV1>, String> {
+        let Some(object) = self
+            .storage
+            .read(FOLDER_SYNC_METADATA_FILE)
+            .await
+            .map_err(|err| format!("Failed to read folder metadata: {err}"))?
+        else {
+            return Ok(None);
+        };
+        serde_json::from_slice(&object.bytes)
+            .map(Some)
+            .map_err(|err| format!("Failed to parse folder metadata: {err}"))
+    }
+
+    pub async fn initialize_folder(
         &self,
         local_device_id: &str,
         metadata: &FolderSyncMetadataV1,
     ) -> Result<(), String> {
-        fs::create_dir_all(self.events_device_dir(local_device_id))
+        self.storage
+            .ensure_dir(&events_device_prefix(local_device_id))
+            .await
             .map_err(|err| format!("Failed to create event directory: {err}"))?;
-        fs::create_dir_all(self.snapshots_device_dir(local_device_id))
+        self.storage
+            .ensure_dir(&snapshots_device_prefix(local_device_id))
+            .await
             .map_err(|err| format!("Failed to create snapshot directory: {err}"))?;
 
-        let metadata_path = self.root.join(FOLDER_SYNC_METADATA_FILE);
-        if !metadata_path.exists() {
-            let metadata_json = serde_json::to_vec_pretty(metadata)
-                .map_err(|err| format!("Failed to serialize folder metadata: {err}"))?;
-            write_new_file_atomic(&metadata_path, &metadata_json)?;
-        }
-
+        let metadata_json = serde_json::to_vec_pretty(metadata)
+            .map_err(|err| format!("Failed to serialize folder metadata: {err}"))?;
+        // Another device may have initialized the folder first; its metadata wins.
+        self.storage
+            .create(FOLDER_SYNC_METADATA_FILE, &metadata_json)
+            .await?;
         Ok(())
     }
 
-    pub fn write_event_file(&self, event: &FolderSyncEventFileV1) -> Result<PathBuf, String> {
-        let target = self
-            .events_device_dir(&event.device_id)
-            .join(event_file_name(&event.event_id));
-        let payload = serde_json::to_vec_pretty(event)
+    pub async fn write_event_file(&self, event: &FolderSyncEventFileV1) -> Result<String, String> {
+        let key = event_file_key(&event.device_id, &event.event_id);
+        let payload = self
+            .seal_json(event)
             .map_err(|err| format!("Failed to serialize event file: {err}"))?;
-        write_new_file_atomic(&target, &payload)?;
-        Ok(target)
+        self.create_new(&key, &payload).await?;
+        Ok(key)
     }
 
-    pub fn list_remote_event_files(
+    pub async fn read_event_file(&self, key: &str) -> Result<FolderSyncEventFileV1, String> {
+        let bytes = self
+            .read_bytes(key)
+            .await?
+            .ok_or_else(|| format!("Event file '{key}' not found"))?;
+        self.open_json(&bytes)
+            .map_err(|err| format!("Failed to parse event file '{key}': {err}"))
+    }
+
+    /// Reads an event file by id, if it exists.
+    pub async fn find_event_file(
+        &self,
+        device_id: &str,
+        event_id: &str,
+    ) -> Result<Option<FolderSyncEventFileV1>, String> {
+        let key = event_file_key(device_id, event_id);
+        let Some(bytes) = self.read_bytes(&key).await? else {
+            return Ok(None);
+        };
+        self.open_json(&bytes)
+            .map(Some)
+            .map_err(|err| format!("Failed to parse event file '{key}': {err}"))
+    }
+
+    pub async fn list_remote_event_files(
         &self,
         local_device_id: &str,
     ) -> Result<Vec<FolderSyncEventFileRef>, String> {
-        let events_root = self.root.join("events");
-        if !events_root.exists() {
-            return Ok(Vec::new());
-        }
+        self.list_event_files(Some(local_device_id)).await
+    }
 
+    async fn list_event_files(
+        &self,
+        skip_device_id: Option<&str>,
+    ) -> Result<Vec<FolderSyncEventFileRef>, String> {
         let mut refs = Vec::new();
-        for entry in fs::read_dir(&events_root)
+        for key in self
+            .storage
+            .list("events")
+            .await
             .map_err(|err| format!("Failed to read events directory: {err}"))?
         {
-            let entry = entry.map_err(|err| format!("Failed to read event device entry: {err}"))?;
-            let path = entry.path();
-            if !path.is_dir() {
+            let Some((device_id, file_name)) = split_device_key("events", &key) else {
                 continue;
-            }
-
-            let device_id = entry.file_name().to_string_lossy().to_string();
-            if device_id == local_device_id {
+            };
+            if skip_device_id == Some(device_id) {
                 continue;
             }
+            let Some(event_id) = file_name.strip_suffix(".json") else {
+                continue;
+            };
 
-            for file in fs::read_dir(&path)
-                .map_err(|err| format!("Failed to read device event directory: {err}"))?
-            {
-                let file = file.map_err(|err| format!("Failed to read event file entry: {err}"))?;
-                let file_path = file.path();
-                if !file_path.is_file() {
-                    continue;
-                }
-
-                let Some(file_name) = file_path.file_name().and_then(|value| value.to_str()) else {
-                    continue;
-                };
-                let Some(event_id) = file_name.strip_suffix(".json") else {
-                    continue;
-                };
-
-                refs.push(FolderSyncEventFileRef {
-                    device_id: device_id.clone(),
-                    event_id: event_id.to_string(),
-                    path: file_path,
-                });
-            }
+            refs.push(FolderSyncEventFileRef {
+                device_id: device_id.to_string(),
+                event_id: event_id.to_string(),
+                key: key.clone(),
+            });
         }
 
         refs.sort_by(|left, right| {
@@ -124,89 +179,92 @@ impl FolderSyncFsService {
         Ok(refs)
     }
 
-    pub fn write_snapshot(
+    pub async fn write_snapshot(
         &self,
         manifest: &FolderSyncSnapshotManifestV1,
         sqlite_bytes: &[u8],
     ) -> Result<FolderSyncSnapshotFileRef, String> {
-        let device_dir = self.snapshots_device_dir(&manifest.device_id);
-        fs::create_dir_all(&device_dir)
-            .map_err(|err| format!("Failed to create snapshot device directory: {err}"))?;
-
-        let db_path = device_dir.join(snapshot_file_name(&manifest.snapshot_id));
-        let manifest_path = device_dir.join(snapshot_manifest_file_name(&manifest.snapshot_id));
-
-        if db_path.exists() || manifest_path.exists() {
-            return Err(format!(
+        let device_prefix = snapshots_device_prefix(&manifest.device_id);
+        let db_key = format!(
+            "{device_prefix}/{}",
+            snapshot_file_name(&manifest.snapshot_id)
+        );
+        let manifest_key = format!(
+            "{device_prefix}/{}",
+            snapshot_manifest_file_name(&manifest.snapshot_id)
+        );
+        let already_exists = || {
+            format!(
                 "Snapshot '{}' already exists for device '{}'",
                 manifest.snapshot_id, manifest.device_id
-            ));
-        }
+            )
+        };
 
-        write_new_file_atomic(&db_path, sqlite_bytes)?;
-        let manifest_json = serde_json::to_vec_pretty(manifest)
+        if self.storage.read(&manifest_key).await?.is_some() {
+            return Err(already_exists());
+        }
+        let db_bytes = match &self.key {
+            Some(key) => key.encrypt_bytes(sqlite_bytes)?,
+            None => sqlite_bytes.to_vec(),
+        };
+        if !self.storage.create(&db_key, &db_bytes).await? {
+            return Err(already_exists());
+        }
+        let manifest_json = self
+            .seal_json(manifest)
             .map_err(|err| format!("Failed to serialize snapshot manifest: {err}"))?;
-        if let Err(err) = write_new_file_atomic(&manifest_path, &manifest_json) {
-            let _ = fs::remove_file(&db_path);
-            return Err(err);
+        match self.storage.create(&manifest_key, &manifest_json).await {
+            Ok(true) => {}
+            Ok(false) => {
+                let _ = self.storage.delete(&db_key).await;
+                return Err(already_exists());
+            }
+            Err(err) => {
+                let _ = self.storage.delete(&db_key).await;
+                return Err(err);
+            }
         }
 
         Ok(FolderSyncSnapshotFileRef {
             device_id: manifest.device_id.clone(),
             snapshot_id: manifest.snapshot_id.clone(),
-            db_path,
-            manifest_path,
+            db_key,
+            manifest_key,
         })
     }
 
-    pub fn list_snapshots(&self) -> Result<Vec<FolderSyncSnapshotFileRef>, String> {
-        let snapshots_root = self.root.join("snapshots");
-        if !snapshots_root.exists() {
-            return Ok(Vec::new());
-        }
+    pub async fn list_snapshots(&self) -> Result<Vec<FolderSyncSnapshotFileRef>, String> {
+        let keys = self
+            .storage
+            .list("snapshots")
+            .await
+            .map_err(|err| format!("Failed to read snapshots directory: {err}"))?
+            .into_iter()
+            .collect::<BTreeSet<_>>();
 
         let mut refs = Vec::new();
-        for entry in fs::read_dir(&snapshots_root)
-            .map_err(|err| format!("Failed to read snapshots directory: {err}"))?
-        {
-            let entry =
-                entry.map_err(|err| format!("Failed to read snapshot device entry: {err}"))?;
-            let path = entry.path();
-            if !path.is_dir() {
+        for manifest_key in &keys {
+            let Some((device_id, file_name)) = split_device_key("snapshots", manifest_key) else {
+                continue;
+            };
+            let Some(snapshot_id) = file_name.strip_suffix(".json") else {
+                continue;
+            };
+            let db_key = format!(
+                "{}/{}",
+                snapshots_device_prefix(device_id),
+                snapshot_file_name(snapshot_id)
+            );
+            if !keys.contains(&db_key) {
                 continue;
             }
 
-            let device_id = entry.file_name().to_string_lossy().to_string();
-            for file in fs::read_dir(&path)
-                .map_err(|err| format!("Failed to read snapshot device directory: {err}"))?
-            {
-                let file =
-                    file.map_err(|err| format!("Failed to read snapshot file entry: {err}"))?;
-                let manifest_path = file.path();
-                if !manifest_path.is_file() {
-                    continue;
-                }
-
-                let Some(file_name) = manifest_path.file_name().and_then(|value| value.to_str())
-                else {
-                    continue;
-                };
-                let Some(snapshot_id) = file_name.strip_suffix(".json") else {
-                    continue;
-                };
-
-                let db_path = path.join(snapshot_file_name(snapshot_id));
-                if !db_path.exists() {
-                    continue;
-                }
-
-                refs.push(FolderSyncSnapshotFileRef {
-                    device_id: device_id.clone(),
-                    snapshot_id: snapshot_id.to_string(),
-                    db_path,
-                    manifest_path,
-                });
-            }
+            refs.push(FolderSyncSnapshotFileRef {
+                device_id: device_id.to_string(),
+                snapshot_id: snapshot_id.to_string(),
+                db_key,
+                manifest_key: manifest_key.clone(),
+            });
         }
 
         refs.sort_by(|left, right| {
@@ -217,47 +275,210 @@ impl FolderSyncFsService {
         Ok(refs)
     }
 
-    fn events_device_dir(&self, device_id: &str) -> PathBuf {
-        self.root.join("events").join(device_id)
+    pub async fn read_snapshot_manifest(
+        &self,
+        snapshot_ref: &FolderSyncSnapshotFileRef,
+    ) -> Result<FolderSyncSnapshotManifestV1, String> {
+        let key = &snapshot_ref.manifest_key;
+        let bytes = self
+            .read_bytes(key)
+            .await
+            .map_err(|err| format!("Failed to read snapshot manifest: {err}"))?
+            .ok_or_else(|| format!("Snapshot manifest '{key}' not found"))?;
+        self.open_json(&bytes)
+            .map_err(|err| format!("Failed to parse snapshot manifest '{key}': {err}"))
     }
 
-    fn snapshots_device_dir(&self, device_id: &str) -> PathBuf {
-        self.root.join("snapshots").join(device_id)
+    /// Returns the plaintext SQLite image of a snapshot.
+    pub async fn read_snapshot_db(
+        &self,
+        snapshot_ref: &FolderSyncSnapshotFileRef,
+    ) -> Result<Vec<u8>, String> {
+        let bytes = self
+            .read_bytes(&snapshot_ref.db_key)
+            .await
+            .map_err(|err| format!("Failed to read snapshot database: {err}"))?
+            .ok_or_else(|| format!("Snapshot database '{}' not found", snapshot_ref.db_key))?;
+        match &self.key {
+            Some(key) => key.decrypt_bytes(&bytes).map_err(|err| {
+                format!(
+                    "Failed to decrypt snapshot '{}': {err}",
+                    snapshot_ref.snapshot_id
+                )
+            }),
+            None => Ok(bytes),
+        }
     }
+
+    /// Re-encodes every event file and snapshot in the folder for `target`
+    /// (same storage, new key) and then writes `metadata`. Files already
+    /// encoded for the target key are left alone, so an interrupted rewrite
+    /// can be resumed with the same key, which callers journal beforehand
+    /// with `begin_rotation`. Fails if another device replaced the folder
+    /// metadata in the meantime.
+    pub async fn rewrite_folder(
+        &self,
+        target: &FolderSyncFsService,
+        metadata: &FolderSyncMetadataV1,
+    ) -> Result<FolderSyncRewriteResult, String> {
+        let metadata_etag = self
+            .storage
+            .read(FOLDER_SYNC_METADATA_FILE)
+            .await?
+            .and_then(|object| object.etag);
+        let mut result = FolderSyncRewriteResult::default();
+
+        for event_ref in self.list_event_files(None).await? {
+            let Some(bytes) = self.read_bytes(&event_ref.key).await? else {
+                continue;
+            };
+            if target.is_sealed_for_self(&bytes) {
+                continue;
+            }
+            let event: FolderSyncEventFileV1 = self
+                .open_json(&bytes)
+                .map_err(|err| format!("Failed to read event file '{}': {err}", event_ref.key))?;
+            let payload = target
+                .seal_json(&event)
+                .map_err(|err| format!("Failed to serialize event file: {err}"))?;
+            self.storage.replace(&event_ref.key, &payload, None).await?;
+            result.event_files += 1;
+        }
+
+        for snapshot_ref in self.list_snapshots().await? {
+            let Some(manifest_bytes) = self.read_bytes(&snapshot_ref.manifest_key).await? else {
+                continue;
+            };
+            if target.is_sealed_for_self(&manifest_bytes) {
+                continue;
+            }
+            let manifest = self.read_snapshot_manifest(&snapshot_ref).await?;
+            // An interrupted rewrite may already have replaced the database.
+            let sqlite_bytes = match target.read_snapshot_db(&snapshot_ref).await {
+                Ok(bytes) if target.is_encrypted() => bytes,
+                _ => self.read_snapshot_db(&snapshot_ref).await?,
+            };
+            let db_bytes = match &target.key {
+                Some(key) => key.encrypt_bytes(&sqlite_bytes)?,
+                None => sqlite_bytes,
+            };
+            let manifest_json = target
+                .seal_json(&manifest)
+                .map_err(|err| format!("Failed to serialize snapshot manifest: {err}"))?;
+            // The manifest is written last: it marks the snapshot as rewritten.
+            self.storage
+                .replace(&snapshot_ref.db_key, &db_bytes, None)
+                .await?;
+            self.storage
+                .replace(&snapshot_ref.manifest_key, &manifest_json, None)
+                .await?;
+            result.snapshots += 1;
+        }
+
+        let metadata_json = serde_json::to_vec_pretty(metadata)
+            .map_err(|err| format!("Failed to serialize folder metadata: {err}"))?;
+        if !target
+            .storage
+            .replace(
+                FOLDER_SYNC_METADATA_FILE,
+                &metadata_json,
+                metadata_etag.as_deref(),
+            )
+            .await?
+        {
+            return Err("Shared folder settings changed on another device; try again".to_string());
+        }
+        Ok(result)
+    }
+
+    async fn read_bytes(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        Ok(self.storage.read(key).await?.map(|object| object.bytes))
+    }
+
+    async fn create_new(&self, key: &str, bytes: &[u8]) -> Result<(), String> {
+        if self.storage.create(key, bytes).await? {
+            Ok(())
+        } else {
+            Err(format!("{key} already exists"))
+        }
+    }
+
+    fn seal_json<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, String> {
+        match &self.key {
+            Some(key) => {
+                let plaintext = serde_json::to_string(value).map_err(|err| err.to_string())?;
+                serde_json::to_vec_pretty(&key.seal(&plaintext)?).map_err(|err| err.to_string())
+            }
+            None => serde_json::to_vec_pretty(value).map_err(|err| err.to_string()),
+        }
+    }
+
+    fn open_json<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, String> {
+        match &self.key {
+            Some(key) => {
+                let envelope: FolderSyncEncryptedFileV1 =
+                    serde_json::from_slice(bytes).map_err(|err| err.to_string())?;
+                serde_json::from_str(&key.open(&envelope)?).map_err(|err| err.to_string())
+            }
+            None => serde_json::from_slice(bytes).map_err(|err| err.to_string()),
+        }
+    }
+
+    fn is_sealed_for_self(&self, bytes: &[u8]) -> bool {
+        let Some(key) = &self.key else {
+            return false;
+        };
+        serde_json::from_slice::<FolderSyncEncryptedFileV1>(bytes)
+            .is_ok_and(|envelope| envelope.key_id == key.key_id())
+    }
+}
+
+pub fn event_file_key(device_id: &str, event_id: &str) -> String {
+    format!(
+        "{}/{}",
+        events_device_prefix(device_id),
+        event_file_name(event_id)
+    )
+}
+
+fn events_device_prefix(device_id: &str) -> String {
+    format!("events/{device_id}")
+}
+
+fn snapshots_device_prefix(device_id: &str) -> String {
+    format!("snapshots/{device_id}")
 }
 
 fn snapshot_manifest_file_name(snapshot_id: &str) -> String {
     format!("{}.json", snapshot_id)
 }
 
-fn write_new_file_atomic(target: &Path, bytes: &[u8]) -> Result<(), String> {
-    if target.exists() {
-        return Err(format!("{} already exists", target.display()));
+/// Splits `<root>/<device>/<file>` into device id and file name.
+fn split_device_key<'a>(root: &str, key: &'a str) -> Option<(&'a str, &'a str)> {
+    let rest = key.strip_prefix(root)?.strip_prefix('/')?;
+    let (device_id, file_name) = rest.split_once('/')?;
+    if device_id.is_empty() || file_name.is_empty() || file_name.contains('/') {
+        return None;
     }
-
-    if let Some(parent) = target.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|err| format!("Failed to create parent directory: {err}"))?;
-    }
-
-    let temp_path = target.with_extension(format!("{}.tmp", Uuid::new_v4().simple()));
-    fs::write(&temp_path, bytes).map_err(|err| format!("Failed to write temporary file: {err}"))?;
-    fs::rename(&temp_path, target)
-        .map_err(|err| format!("Failed to finalize file write: {err}"))?;
-    Ok(())
+    Some((device_id, file_name))
 }
 
 #[cfg(test)]
 mod tests {
     use std::fs;
+    use std::path::Path;
+    use std::sync::Arc;
 
     use tempfile::tempdir;
     use wealthfolio_core::sync::{
-        event_file_name, snapshot_file_name, FolderSyncEventFileV1, FolderSyncMetadataV1,
+        snapshot_file_name, FolderSyncEventFileV1, FolderSyncMetadataV1,
         FolderSyncSnapshotManifestV1, FOLDER_SYNC_VERSION_V1,
     };
 
-    use super::{FolderSyncEventFileRef, FolderSyncFsService};
+    use super::{FolderSyncEventFileRef, FolderSyncFsService, FolderSyncRewriteResult};
+    use crate::services::folder_sync_crypto::create_encryption;
+    use crate::services::folder_sync_webdav::tests::spawn_webdav_stub;
+    use crate::services::folder_sync_webdav::WebDavFolderStorage;
     use wealthfolio_core::sync::{SyncEntity, SyncOperation};
 
     fn metadata() -> FolderSyncMetadataV1 {
@@ -265,6 +486,7 @@ mod tests {
             version: FOLDER_SYNC_VERSION_V1,
             created_at: "2026-03-07T14:00:00.000Z".to_string(),
             created_by_device_id: "device-a".to_string(),
+            encryption: None,
         }
     }
 
@@ -295,82 +517,82 @@ mod tests {
         }
     }
 
-    #[test]
-    fn initialize_folder_creates_expected_structure() {
+    fn local_file(root: &Path, key: &str) -> std::path::PathBuf {
+        key.split('/')
+            .fold(root.to_path_buf(), |path, segment| path.join(segment))
+    }
+
+    #[tokio::test]
+    async fn initialize_folder_creates_expected_structure() {
         let root = tempdir().expect("tempdir");
-        let service = FolderSyncFsService::new(root.path().join("PanoramaSync"));
+        let shared_root = root.path().join("PanoramaSync");
+        let service = FolderSyncFsService::new(shared_root.clone());
 
         service
             .initialize_folder("device-a", &metadata())
+            .await
             .expect("initialize folder");
 
-        assert!(service.root().join("folder.json").exists());
-        assert!(service.root().join("events/device-a").is_dir());
-        assert!(service.root().join("snapshots/device-a").is_dir());
+        assert!(shared_root.join("folder.json").exists());
+        assert!(shared_root.join("events/device-a").is_dir());
+        assert!(shared_root.join("snapshots/device-a").is_dir());
     }
 
-    #[test]
-    fn write_event_file_creates_immutable_event_file() {
+    #[tokio::test]
+    async fn write_event_file_creates_immutable_event_file() {
         let root = tempdir().expect("tempdir");
-        let service = FolderSyncFsService::new(root.path().join("PanoramaSync"));
+        let shared_root = root.path().join("PanoramaSync");
+        let service = FolderSyncFsService::new(shared_root.clone());
         service
             .initialize_folder("device-a", &metadata())
+            .await
             .expect("initialize folder");
 
         let event = event("device-a", "evt-1");
-        let path = service.write_event_file(&event).expect("write event file");
+        let key = service
+            .write_event_file(&event)
+            .await
+            .expect("write event file");
 
-        assert_eq!(
-            path.file_name().and_then(|value| value.to_str()),
-            Some(event_file_name("evt-1").as_str())
-        );
-        assert!(path.exists());
+        assert_eq!(key, "events/device-a/evt-1.json");
+        assert!(local_file(&shared_root, &key).exists());
 
-        let overwrite = service.write_event_file(&event);
+        let overwrite = service.write_event_file(&event).await;
         assert!(overwrite.is_err(), "event file should be immutable");
     }
 
-    #[test]
-    fn list_remote_event_files_only_returns_other_devices_sorted_by_device_and_name() {
+    #[tokio::test]
+    async fn list_remote_event_files_only_returns_other_devices_sorted_by_device_and_name() {
         let root = tempdir().expect("tempdir");
         let service = FolderSyncFsService::new(root.path().join("PanoramaSync"));
-        service
-            .initialize_folder("device-a", &metadata())
-            .expect("initialize folder");
-        service
-            .initialize_folder(
-                "device-b",
-                &FolderSyncMetadataV1 {
-                    created_by_device_id: "device-b".to_string(),
-                    ..metadata()
-                },
-            )
-            .expect("initialize device-b");
-        service
-            .initialize_folder(
-                "device-c",
-                &FolderSyncMetadataV1 {
-                    created_by_device_id: "device-c".to_string(),
-                    ..metadata()
-                },
-            )
-            .expect("initialize device-c");
+        for device_id in ["device-a", "device-b", "device-c"] {
+            service
+                .initialize_folder(
+                    device_id,
+                    &FolderSyncMetadataV1 {
+                        created_by_device_id: device_id.to_string(),
+                        ..metadata()
+                    },
+                )
+                .await
+                .expect("initialize folder");
+        }
 
-        service
-            .write_event_file(&event("device-b", "evt-2"))
-            .expect("write remote event 1");
-        service
-            .write_event_file(&event("device-b", "evt-1"))
-            .expect("write remote event 2");
-        service
-            .write_event_file(&event("device-c", "evt-3"))
-            .expect("write remote event 3");
-        service
-            .write_event_file(&event("device-a", "evt-local"))
-            .expect("write local event");
+        for (device_id, event_id) in [
+            ("device-b", "evt-2"),
+            ("device-b", "evt-1"),
+            ("device-c", "evt-3"),
+            ("device-a", "evt-local"),
+        ] {
+            service
+                .write_event_file(&event(device_id, event_id))
+                .await
+                .expect("write event");
+        }
 
         let actual = service
             .list_remote_event_files("device-a")
+            .await
             .expect("list remote event files");
 
         assert_eq!(
@@ -379,64 +601,73 @@ mod tests {
                 FolderSyncEventFileRef {
                     device_id: "device-b".to_string(),
                     event_id: "evt-1".to_string(),
-                    path: service.root().join("events/device-b/evt-1.json"),
+                    key: "events/device-b/evt-1.json".to_string(),
                 },
                 FolderSyncEventFileRef {
                     device_id: "device-b".to_string(),
                     event_id: "evt-2".to_string(),
-                    path: service.root().join("events/device-b/evt-2.json"),
+                    key: "events/device-b/evt-2.json".to_string(),
                 },
                 FolderSyncEventFileRef {
                     device_id: "device-c".to_string(),
                     event_id: "evt-3".to_string(),
-                    path: service.root().join("events/device-c/evt-3.json"),
+                    key: "events/device-c/evt-3.json".to_string(),
                 },
             ]
         );
     }
 
-    #[test]
-    fn write_snapshot_creates_db_and_manifest_and_lists_them() {
+    #[tokio::test]
+    async fn write_snapshot_creates_db_and_manifest_and_lists_them() {
         let root = tempdir().expect("tempdir");
-        let service = FolderSyncFsService::new(root.path().join("PanoramaSync"));
+        let shared_root = root.path().join("PanoramaSync");
+        let service = FolderSyncFsService::new(shared_root.clone());
         service
             .initialize_folder("device-a", &metadata())
+            .await
             .expect("initialize folder");
 
         let manifest = snapshot_manifest("device-a", "snapshot-1");
         let snapshot = service
             .write_snapshot(&manifest, b"SQLite format 3\0demo")
+            .await
             .expect("write snapshot");
 
         assert_eq!(
-            snapshot
-                .db_path
-                .file_name()
-                .and_then(|value| value.to_str()),
-            Some(snapshot_file_name("snapshot-1").as_str())
+            snapshot.db_key,
+            format!("snapshots/device-a/{}", snapshot_file_name("snapshot-1"))
+        );
+        assert!(fs::metadata(local_file(&shared_root, &snapshot.db_key))
+            .expect("db metadata")
+            .is_file());
+        assert!(
+            fs::metadata(local_file(&shared_root, &snapshot.manifest_key))
+                .expect("manifest metadata")
+                .is_file()
         );
-        assert!(snapshot.db_path.exists());
-        assert!(snapshot.manifest_path.exists());
 
-        let snapshots = service.list_snapshots().expect("list snapshots");
+        let snapshots = service.list_snapshots().await.expect("list snapshots");
         assert_eq!(snapshots, vec![snapshot]);
     }
 
-    #[test]
-    fn write_snapshot_refuses_to_overwrite_existing_files() {
+    #[tokio::test]
+    async fn write_snapshot_refuses_to_overwrite_existing_files() {
         let root = tempdir().expect("tempdir");
         let service = FolderSyncFsService::new(root.path().join("PanoramaSync"));
         service
             .initialize_folder("device-a", &metadata())
+            .await
             .expect("initialize folder");
 
         let manifest = snapshot_manifest("device-a", "snapshot-1");
         service
             .write_snapshot(&manifest, b"SQLite format 3\0demo")
+            .await
             .expect("write snapshot");
 
         let err = service
             .write_snapshot(&manifest, b"SQLite format 3\0demo")
+            .await
             .expect_err("snapshot overwrite should fail");
         assert!(
             err.contains("already exists"),
@@ -444,30 +675,216 @@ mod tests {
         );
     }
 
-    #[test]
-    fn write_event_and_snapshot_files_are_real_files() {
+    #[tokio::test]
+    async fn write_event_and_snapshot_files_are_real_files() {
         let root = tempdir().expect("tempdir");
-        let service = FolderSyncFsService::new(root.path().join("PanoramaSync"));
+        let shared_root = root.path().join("PanoramaSync");
+        let service = FolderSyncFsService::new(shared_root.clone());
         service
             .initialize_folder("device-a", &metadata())
+            .await
             .expect("initialize folder");
 
-        let event_path = service
+        let event_key = service
             .write_event_file(&event("device-a", "evt-1"))
+            .await
             .expect("write event");
         let snapshot = service
             .write_snapshot(
                 &snapshot_manifest("device-a", "snapshot-1"),
                 b"SQLite format 3\0demo",
             )
+            .await
             .expect("write snapshot");
 
-        assert!(fs::metadata(event_path).expect("event metadata").is_file());
-        assert!(fs::metadata(snapshot.db_path)
-            .expect("db metadata")
+        assert!(fs::metadata(local_file(&shared_root, &event_key))
+            .expect("event metadata")
             .is_file());
-        assert!(fs::metadata(snapshot.manifest_path)
-            .expect("manifest metadata")
+        assert!(fs::metadata(local_file(&shared_root, &snapshot.db_key))
+            .expect("db metadata")
             .is_file());
+        assert!(
+            fs::metadata(local_file(&shared_root, &snapshot.manifest_key))
+                .expect("manifest metadata")
+                .is_file()
+        );
+    }
+
+    #[tokio::test]
+    async fn encrypted_files_hide_contents_and_round_trip() {
+        let root = tempdir().expect("tempdir");
+        let shared_root = root.path().join("PanoramaSync");
+        let (_, key) = create_encryption("correct horse battery").expect("create key");
+        let service = FolderSyncFsService::new(shared_root.clone()).with_key(key);
+        service
+            .initialize_folder("device-a", &metadata())
+            .await
+            .expect("initialize folder");
+
+        let event = event("device-a", "evt-1");
+        let event_key = service.write_event_file(&event).await.expect("write event");
+        let raw_event =
+            fs::read_to_string(local_file(&shared_root, &event_key)).expect("read raw event");
+        assert!(!raw_event.contains("acc-evt-1"));
+        assert_eq!(
+            service
+                .read_event_file(&event_key)
+                .await
+                .expect("read event"),
+            event
+        );
+
+        let manifest = snapshot_manifest("device-a", "snapshot-1");
+        let snapshot = service
+            .write_snapshot(&manifest, b"SQLite format 3\0demo")
+            .await
+            .expect("write snapshot");
+        let raw_db = fs::read(local_file(&shared_root, &snapshot.db_key)).expect("read raw db");
+        assert!(!raw_db.starts_with(b"SQLite format 3"));
+        assert_eq!(
+            service
+                .read_snapshot_manifest(&snapshot)
+                .await
+                .expect("manifest"),
+            manifest
+        );
+        assert_eq!(
+            service.read_snapshot_db(&snapshot).await.expect("db"),
+            b"SQLite format 3\0demo"
+        );
+
+        let plaintext_reader = FolderSyncFsService::new(shared_root);
+        assert!(plaintext_reader.read_event_file(&event_key).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rewrite_folder_encrypts_plaintext_folder_for_new_key() {
+        let root = tempdir().expect("tempdir");
+        let plaintext = FolderSyncFsService::new(root.path().join("PanoramaSync"));
+        plaintext
+            .initialize_folder("device-a", &metadata())
+            .await
+            .expect("initialize folder");
+        let event_key = plaintext
+            .write_event_file(&event("device-b", "evt-1"))
+            .await
+            .expect("write event");
+        let snapshot = plaintext
+            .write_snapshot(
+                &snapshot_manifest("device-a", "snapshot-1"),
+                b"SQLite format 3\0demo",
+            )
+            .await
+            .expect("write snapshot");
+
+        let (encryption, key) = create_encryption("correct horse battery").expect("create key");
+        let encrypted = plaintext.clone().with_key(key);
+        let result = plaintext
+            .rewrite_folder(
+                &encrypted,
+                &FolderSyncMetadataV1 {
+                    encryption: Some(encryption.clone()),
+                    ..metadata()
+                },
+            )
+            .await
+            .expect("rewrite folder");
+        assert_eq!(
+            result,
+            FolderSyncRewriteResult {
+                event_files: 1,
+                snapshots: 1,
+            }
+        );
+
+        assert!(plaintext.read_event_file(&event_key).await.is_err());
+        assert_eq!(
+            encrypted
+                .read_event_file(&event_key)
+                .await
+                .expect("read event")
+                .event_id,
+            "evt-1"
+        );
+        assert_eq!(
+            encrypted.read_snapshot_db(&snapshot).await.expect("db"),
+            b"SQLite format 3\0demo"
+        );
+        assert_eq!(
+            encrypted
+                .read_metadata()
+                .await
+                .expect("read metadata")
+                .and_then(|metadata| metadata.encryption),
+            Some(encryption)
+        );
+
+        // Rewriting again for the same key is a no-op.
+        let current = encrypted
+            .read_metadata()
+            .await
+            .expect("read metadata")
+            .expect("metadata");
+        let result = encrypted
+            .rewrite_folder(&encrypted, &current)
+            .await
+            .expect("rewrite again");
+        assert_eq!(result, FolderSyncRewriteResult::default());
+    }
+
+    #[tokio::test]
+    async fn shared_folder_layout_works_over_webdav() {
+        let storage = WebDavFolderStorage::new(spawn_webdav_stub().await, None);
+        let (_, key) = create_encryption("correct horse battery").expect("create key");
+        let service = FolderSyncFsService::with_storage(Arc::new(storage)).with_key(key);
+        service
+            .initialize_folder("device-a", &metadata())
+            .await
+            .expect("initialize folder");
+        service
+            .initialize_folder("device-b", &metadata())
+            .await
+            .expect("initialize second device");
+
+        service
+            .write_event_file(&event("device-b", "evt-1"))
+            .await
+            .expect("write event");
+        assert!(service
+            .write_event_file(&event("device-b", "evt-1"))
+            .await
+            .is_err());
+        let snapshot = service
+            .write_snapshot(
+                &snapshot_manifest("device-a", "snapshot-1"),
+                b"SQLite format 3\0demo",
+            )
+            .await
+            .expect("write snapshot");
+
+        let remote_events = service
+            .list_remote_event_files("device-a")
+            .await
+            .expect("list events");
+        assert_eq!(remote_events.len(), 1);
+        assert_eq!(
+            service
+                .read_event_file(&remote_events[0].key)
+                .await
+                .expect("read event"),
+            event("device-b", "evt-1")
+        );
+        assert_eq!(
+            service.list_snapshots().await.expect("list snapshots"),
+            vec![snapshot.clone()]
+        );
+        assert_eq!(
+            service.read_snapshot_db(&snapshot).await.expect("db"),
+            b"SQLite format 3\0demo"
+        );
+        assert_eq!(
+            service.read_metadata().await.expect("metadata"),
+            Some(metadata())
+        );
     }
 }
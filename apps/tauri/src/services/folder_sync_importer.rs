@@ -1,10 +1,9 @@
 //! Import shared-folder event files into the local SQLite database.
 
-use std::fs;
 use std::sync::Arc;
 
 use chrono::Utc;
-use wealthfolio_core::sync::{FolderSyncEventFileV1, FOLDER_SYNC_VERSION_V1};
+use wealthfolio_core::sync::FOLDER_SYNC_VERSION_V1;
 use wealthfolio_storage_sqlite::sync::{
     AppSyncRepository, FolderSyncRepository, FolderSyncStatusUpdate,
 };
@@ -61,7 +60,8 @@ impl FolderSyncImporter {
 
         let event_refs = self
             .fs_service
-            .list_remote_event_files(&self.local_device_id)?;
+            .list_remote_event_files(&self.local_device_id)
+            .await?;
         let discovered_event_ids = event_refs
             .iter()
             .map(|event_ref| event_ref.event_id.clone())
@@ -98,7 +98,7 @@ impl FolderSyncImporter {
                     .mark_event_imported(
                         event_ref.event_id.clone(),
                         event_ref.device_id.clone(),
-                        event_ref.path.to_string_lossy().into_owned(),
+                        event_ref.key.clone(),
                         Utc::now().to_rfc3339(),
                     )
                     .await
@@ -125,7 +125,7 @@ impl FolderSyncImporter {
                     .mark_event_imported(
                         event_ref.event_id.clone(),
                         event_ref.device_id.clone(),
-                        event_ref.path.to_string_lossy().into_owned(),
+                        event_ref.key.clone(),
                         Utc::now().to_rfc3339(),
                     )
                     .await
@@ -134,22 +134,25 @@ impl FolderSyncImporter {
                 continue;
             }
 
-            let event = self.read_event_file(&event_ref.path)?;
+            let event = match self.fs_service.read_event_file(&event_ref.key).await {
+                Ok(event) => event,
+                Err(error) => {
+                    self.record_import_error(&event_ref.event_id, &error)
+                        .await?;
+                    return Err(error);
+                }
+            };
             if event.version != FOLDER_SYNC_VERSION_V1 {
                 let error = format!(
                     "Unsupported folder sync event version '{}' in {}",
-                    event.version,
-                    event_ref.path.display()
+                    event.version, event_ref.key
                 );
                 self.record_import_error(&event_ref.event_id, &error)
                     .await?;
                 return Err(error);
             }
             if event.event_id != event_ref.event_id || event.device_id != event_ref.device_id {
-                let error = format!(
-                    "Folder sync event metadata mismatch for {}",
-                    event_ref.path.display()
-                );
+                let error = format!("Folder sync event metadata mismatch for {}", event_ref.key);
                 self.record_import_error(&event_ref.event_id, &error)
                     .await?;
                 return Err(error);
@@ -174,7 +177,7 @@ impl FolderSyncImporter {
                 .mark_event_imported(
                     event.event_id.clone(),
                     event.device_id,
-                    event_ref.path.to_string_lossy().into_owned(),
+                    event_ref.key.clone(),
                     Utc::now().to_rfc3339(),
                 )
                 .await
@@ -226,13 +229,6 @@ impl FolderSyncImporter {
         })
     }
 
-    fn read_event_file(&self, path: &std::path::Path) -> Result<FolderSyncEventFileV1, String> {
-        serde_json::from_slice(
-            &fs::read(path).map_err(|err| format!("Failed to read event file: {err}"))?,
-        )
-        .map_err(|err| format!("Failed to parse event file '{}': {err}", path.display()))
-    }
-
     async fn record_import_error(&self, event_id: &str, error: &str) -> Result<(), String> {
         let timestamp = Utc::now().to_rfc3339();
         self.folder_sync_repository
@@ -309,8 +305,10 @@ mod tests {
                     version: FOLDER_SYNC_VERSION_V1,
                     created_at: chrono::Utc::now().to_rfc3339(),
                     created_by_device_id: local_device_id.clone(),
+                    encryption: None,
                 },
             )
+            .await
             .expect("initialize folder");
 
         ImporterTestContext {
@@ -364,6 +362,7 @@ mod tests {
         context
             .fs_service
             .write_event_file(&remote_event)
+            .await
             .expect("write remote event");
 
         let importer = FolderSyncImporter::new(
@@ -445,6 +444,7 @@ mod tests {
                 schema_version: Some(FOLDER_SYNC_VERSION_V1),
                 app_version: Some("3.0.0".to_string()),
             })
+            .await
             .expect("write newer remote event");
         context
             .fs_service
@@ -468,6 +468,7 @@ mod tests {
                 schema_version: Some(FOLDER_SYNC_VERSION_V1),
                 app_version: Some("3.0.0".to_string()),
             })
+            .await
             .expect("write older remote event");
 
         let importer = FolderSyncImporter::new(
@@ -1,18 +1,18 @@
 //! Background orchestration for automatic folder sync.
 
-use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
 use chrono::Utc;
 use tokio::sync::{mpsc, watch, Mutex};
 use tokio::task::JoinHandle;
+use wealthfolio_core::secrets::SecretStore;
 use wealthfolio_storage_sqlite::sync::{
     AppSyncRepository, FolderSyncRepository, FolderSyncStatusUpdate,
 };
 
+use crate::services::folder_sync_crypto::{open_shared_folder, FolderSyncAccess};
 use crate::services::folder_sync_exporter::FolderSyncExporter;
-use crate::services::folder_sync_fs::FolderSyncFsService;
 use crate::services::folder_sync_importer::FolderSyncImporter;
 
 #[derive(Debug, Clone, Copy)]
@@ -33,6 +33,7 @@ impl FolderSyncRuntime {
     pub fn spawn(
         app_sync_repository: Arc<AppSyncRepository>,
         folder_sync_repository: Arc<FolderSyncRepository>,
+        secret_store: Arc<dyn SecretStore>,
         poll_interval: Duration,
     ) -> Self {
         let (trigger_tx, mut trigger_rx) = mpsc::unbounded_channel();
@@ -48,6 +49,7 @@ impl FolderSyncRuntime {
                         if let Err(err) = run_cycle(
                             app_sync_repository.clone(),
                             folder_sync_repository.clone(),
+                            secret_store.as_ref(),
                             RuntimeTrigger::Periodic,
                         ).await {
                             record_runtime_error(folder_sync_repository.clone(), err).await;
@@ -57,6 +59,7 @@ impl FolderSyncRuntime {
                         if let Err(err) = run_cycle(
                             app_sync_repository.clone(),
                             folder_sync_repository.clone(),
+                            secret_store.as_ref(),
                             trigger,
                         ).await {
                             record_runtime_error(folder_sync_repository.clone(), err).await;
@@ -101,6 +104,7 @@ impl FolderSyncRuntime {
 async fn run_cycle(
     app_sync_repository: Arc<AppSyncRepository>,
     folder_sync_repository: Arc<FolderSyncRepository>,
+    secret_store: &dyn SecretStore,
     trigger: RuntimeTrigger,
 ) -> Result<(), String> {
     let Some(config) = folder_sync_repository
@@ -113,7 +117,13 @@ async fn run_cycle(
         return Ok(());
     }
 
-    let fs_service = FolderSyncFsService::new(PathBuf::from(&config.shared_folder_path));
+    let fs_service = match open_shared_folder(&config.shared_folder_path, secret_store).await? {
+        FolderSyncAccess::Ready(fs_service) => fs_service,
+        FolderSyncAccess::PassphraseRequired => {
+            record_passphrase_required(folder_sync_repository).await;
+            return Ok(());
+        }
+    };
     let importer = FolderSyncImporter::new(
         app_sync_repository.clone(),
         folder_sync_repository.clone(),
@@ -140,6 +150,26 @@ async fn run_cycle(
     Ok(())
 }
 
+/// The folder is encrypted with a passphrase this device does not have,
+/// typically after another device rotated it. Nothing is read or written.
+async fn record_passphrase_required(folder_sync_repository: Arc<FolderSyncRepository>) {
+    let now = Utc::now().to_rfc3339();
+    if let Err(err) = folder_sync_repository
+        .update_status(FolderSyncStatusUpdate {
+            sync_state: Some("passphrase_required".to_string()),
+            last_checked_at: Some(now.clone()),
+            last_error: Some(Some(
+                "Enter the folder sync passphrase to resume syncing".to_string(),
+            )),
+            updated_at: Some(now),
+            ..Default::default()
+        })
+        .await
+    {
+        log::warn!("Failed to update folder sync runtime status: {}", err);
+    }
+}
+
 async fn record_runtime_error(folder_sync_repository: Arc<FolderSyncRepository>, error: String) {
     let now = Utc::now().to_rfc3339();
     if let Err(err) = folder_sync_repository
@@ -178,7 +208,8 @@ mod tests {
     use diesel::prelude::*;
     use tempfile::tempdir;
     use wealthfolio_core::sync::{
-        FolderSyncEventFileV1, SyncEntity, SyncOperation, FOLDER_SYNC_VERSION_V1,
+        FolderSyncEventFileV1, FolderSyncMetadataV1, SyncEntity, SyncOperation,
+        FOLDER_SYNC_VERSION_V1,
     };
     use wealthfolio_storage_sqlite::db::{self, write_actor, WriteHandle};
     use wealthfolio_storage_sqlite::schema::platforms;
@@ -186,6 +217,8 @@ mod tests {
         insert_outbox_event, AppSyncRepository, FolderSyncRepository, OutboxWriteRequest,
     };
 
+    use crate::services::folder_sync_crypto::tests::MemorySecretStore;
+    use crate::services::folder_sync_crypto::{create_encryption, store_key};
     use crate::services::folder_sync_fs::FolderSyncFsService;
 
     use super::FolderSyncRuntime;
@@ -276,6 +309,7 @@ mod tests {
                 schema_version: Some(FOLDER_SYNC_VERSION_V1),
                 app_version: Some("3.0.0".to_string()),
             })
+            .await
             .expect("write remote event");
     }
 
@@ -299,6 +333,7 @@ mod tests {
         let runtime = FolderSyncRuntime::spawn(
             target.app_sync_repository.clone(),
             target.folder_sync_repository.clone(),
+            Arc::new(MemorySecretStore::default()),
             Duration::from_secs(60),
         );
         runtime.trigger_startup();
@@ -322,6 +357,7 @@ mod tests {
         let runtime = FolderSyncRuntime::spawn(
             target.app_sync_repository.clone(),
             target.folder_sync_repository.clone(),
+            Arc::new(MemorySecretStore::default()),
             Duration::from_secs(60),
         );
 
@@ -356,6 +392,7 @@ mod tests {
         let runtime = FolderSyncRuntime::spawn(
             target.app_sync_repository.clone(),
             target.folder_sync_repository.clone(),
+            Arc::new(MemorySecretStore::default()),
             Duration::from_millis(50),
         );
 
@@ -412,6 +449,7 @@ mod tests {
         let runtime = FolderSyncRuntime::spawn(
             target.app_sync_repository.clone(),
             target.folder_sync_repository.clone(),
+            Arc::new(MemorySecretStore::default()),
             Duration::from_secs(60),
         );
         runtime.trigger_local_mutation();
@@ -434,4 +472,67 @@ mod tests {
 
         runtime.stop().await;
     }
+
+    #[tokio::test]
+    async fn encrypted_folder_without_stored_key_waits_for_passphrase() {
+        let root = tempdir().expect("tempdir");
+        let shared_root = root.path().join("PanoramaSync");
+        let source = setup_device(root.path(), "source-app", "device-a", &shared_root).await;
+        let target = setup_device(root.path(), "target-app", "device-b", &shared_root).await;
+
+        let (encryption, key) = create_encryption("correct horse battery").expect("create key");
+        let encrypted_source = source.fs_service.clone().with_key(key.clone());
+        encrypted_source
+            .initialize_folder(
+                "device-a",
+                &FolderSyncMetadataV1 {
+                    version: FOLDER_SYNC_VERSION_V1,
+                    created_at: "2026-03-07T16:00:00Z".to_string(),
+                    created_by_device_id: "device-a".to_string(),
+                    encryption: Some(encryption),
+                },
+            )
+            .await
+            .expect("initialize encrypted folder");
+        seed_remote_platform_event(
+            &encrypted_source,
+            "device-a",
+            "evt-runtime-encrypted-1",
+            "platform-runtime-encrypted",
+            "Runtime Encrypted",
+            "2026-03-07T16:15:00Z",
+        )
+        .await;
+
+        let secret_store = Arc::new(MemorySecretStore::default());
+        let runtime = FolderSyncRuntime::spawn(
+            target.app_sync_repository.clone(),
+            target.folder_sync_repository.clone(),
+            secret_store.clone(),
+            Duration::from_secs(60),
+        );
+        runtime.trigger_startup();
+        tokio::time::sleep(Duration::from_millis(250)).await;
+
+        let status = target
+            .folder_sync_repository
+            .get_status()
+            .expect("get runtime status");
+        assert_eq!(status.sync_state, "passphrase_required");
+        assert_eq!(
+            load_platform_name(&target.pool, "platform-runtime-encrypted"),
+            None
+        );
+
+        store_key(secret_store.as_ref(), &key).expect("store key");
+        runtime.trigger_foreground();
+        tokio::time::sleep(Duration::from_millis(250)).await;
+
+        assert_eq!(
+            load_platform_name(&target.pool, "platform-runtime-encrypted").as_deref(),
+            Some("Runtime Encrypted")
+        );
+
+        runtime.stop().await;
+    }
 }
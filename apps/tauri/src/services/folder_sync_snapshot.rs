@@ -2,7 +2,7 @@
 
 use std::collections::BTreeMap;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use chrono::{DateTime, Utc};
@@ -86,7 +86,10 @@ impl FolderSyncSnapshotService {
                 .map_err(|err| err.to_string())?,
             shared_settings: self.collect_shared_settings()?,
         };
-        let snapshot_ref = self.fs_service.write_snapshot(&manifest, &sqlite_bytes)?;
+        let snapshot_ref = self
+            .fs_service
+            .write_snapshot(&manifest, &sqlite_bytes)
+            .await?;
         self.folder_sync_repository
             .append_history(
                 "snapshot_export".to_string(),
@@ -106,22 +109,24 @@ impl FolderSyncSnapshotService {
     }
 
     pub async fn join_from_latest_snapshot(&self) -> Result<FolderSyncJoinResult, String> {
-        let latest_snapshot = self.load_latest_snapshot()?;
+        let latest_snapshot = self.load_latest_snapshot().await?;
         let backup_path = self.backup_if_local_shared_data_exists()?;
-        self.app_sync_repository
+        let restore_path = self
+            .materialize_snapshot_db(&latest_snapshot.snapshot_ref)
+            .await?;
+        let restored = self
+            .app_sync_repository
             .restore_snapshot_tables_from_file(
-                latest_snapshot
-                    .snapshot_ref
-                    .db_path
-                    .to_string_lossy()
-                    .into_owned(),
+                restore_path.to_string_lossy().into_owned(),
                 latest_snapshot.manifest.tables.clone(),
                 0,
                 self.local_device_id.clone(),
                 None,
             )
             .await
-            .map_err(|err| err.to_string())?;
+            .map_err(|err| err.to_string());
+        let _ = fs::remove_file(&restore_path);
+        restored?;
         self.apply_shared_settings(&latest_snapshot.manifest)
             .await?;
 
@@ -157,11 +162,14 @@ impl FolderSyncSnapshotService {
         })
     }
 
-    fn load_latest_snapshot(&self) -> Result<LoadedSnapshot, String> {
-        let snapshots = self.fs_service.list_snapshots()?;
+    async fn load_latest_snapshot(&self) -> Result<LoadedSnapshot, String> {
+        let snapshots = self.fs_service.list_snapshots().await?;
         let mut loaded = Vec::with_capacity(snapshots.len());
         for snapshot_ref in snapshots {
-            let manifest = self.read_manifest(&snapshot_ref.manifest_path)?;
+            let manifest = self
+                .fs_service
+                .read_snapshot_manifest(&snapshot_ref)
+                .await?;
             loaded.push(LoadedSnapshot {
                 snapshot_ref,
                 manifest,
@@ -174,16 +182,20 @@ impl FolderSyncSnapshotService {
             .ok_or_else(|| "No folder sync snapshot is available".to_string())
     }
 
-    fn read_manifest(&self, path: &Path) -> Result<FolderSyncSnapshotManifestV1, String> {
-        serde_json::from_slice(
-            &fs::read(path).map_err(|err| format!("Failed to read snapshot manifest: {err}"))?,
-        )
-        .map_err(|err| {
-            format!(
-                "Failed to parse snapshot manifest '{}': {err}",
-                path.display()
-            )
-        })
+    /// Downloads (and decrypts) the snapshot into the app data directory,
+    /// never into the shared folder. The caller removes the staged file.
+    async fn materialize_snapshot_db(
+        &self,
+        snapshot_ref: &FolderSyncSnapshotFileRef,
+    ) -> Result<PathBuf, String> {
+        let sqlite_bytes = self.fs_service.read_snapshot_db(snapshot_ref).await?;
+        let restore_path = self.app_data_dir.join(format!(
+            "folder-sync-restore-{}.db",
+            snapshot_ref.snapshot_id
+        ));
+        fs::write(&restore_path, sqlite_bytes)
+            .map_err(|err| format!("Failed to stage snapshot for restore: {err}"))?;
+        Ok(restore_path)
     }
 
     fn backup_if_local_shared_data_exists(&self) -> Result<Option<String>, String> {
@@ -386,9 +398,10 @@ mod tests {
         );
         let result = service.export_snapshot().await.expect("export snapshot");
 
-        assert!(result.snapshot_ref.db_path.exists());
+        assert!(shared_root.join(&result.snapshot_ref.db_key).exists());
         let manifest: FolderSyncSnapshotManifestV1 = serde_json::from_slice(
-            &std::fs::read(&result.snapshot_ref.manifest_path).expect("read manifest"),
+            &std::fs::read(shared_root.join(&result.snapshot_ref.manifest_key))
+                .expect("read manifest"),
         )
         .expect("parse manifest");
         assert_eq!(manifest.device_id, "device-a");
@@ -497,6 +510,7 @@ mod tests {
                 schema_version: Some(FOLDER_SYNC_VERSION_V1),
                 app_version: Some("3.0.0".to_string()),
             })
+            .await
             .expect("write later event");
 
         let target_service = FolderSyncSnapshotService::new(
@@ -0,0 +1,504 @@
+//! Storage backends for folder sync.
+//!
+//! The shared folder layout is addressed with `/`-separated object keys
+//! relative to the folder root (`folder.json`, `events/<device>/<id>.json`,
+//! ...). A backend stores those objects in a locally mounted directory, a
+//! WebDAV collection or an S3-compatible bucket.
+//!
+//! The configured shared folder path selects the backend:
+//! - `webdav+https://cloud.example.com/remote.php/dav/files/me/Panorama`
+//! - `s3+https://s3.example.com/bucket/prefix?region=us-east-1`
+//! - anything else is a local directory.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use wealthfolio_core::secrets::SecretStore;
+
+use crate::services::folder_sync_s3::S3FolderStorage;
+use crate::services::folder_sync_webdav::WebDavFolderStorage;
+
+/// Secret store key holding credentials for a remote backend.
+pub const FOLDER_SYNC_REMOTE_CREDENTIALS_SECRET: &str = "folder_sync_remote_credentials";
+
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(250);
+
+/// An object read from a backend together with its version tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredObject {
+    pub bytes: Vec<u8>,
+    pub etag: Option<String>,
+}
+
+/// Object storage used by the folder sync services.
+///
+/// Writes are conditional so several devices can append to the same folder:
+/// `create` never overwrites, and `replace` with an etag only succeeds if the
+/// object did not change since it was read.
+#[async_trait]
+pub trait FolderSyncStorage: Send + Sync + std::fmt::Debug {
+    /// Human readable location, used in messages.
+    fn location(&self) -> String;
+
+    async fn read(&self, key: &str) -> Result<Option<StoredObject>, String>;
+
+    /// Writes a new object. Returns `false` if the key already exists.
+    async fn create(&self, key: &str, bytes: &[u8]) -> Result<bool, String>;
+
+    /// Overwrites an object. With `if_match`, returns `false` if the stored
+    /// object no longer has that etag.
+    async fn replace(
+        &self,
+        key: &str,
+        bytes: &[u8],
+        if_match: Option<&str>,
+    ) -> Result<bool, String>;
+
+    async fn delete(&self, key: &str) -> Result<(), String>;
+
+    /// Lists every object key below `prefix`, recursively.
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, String>;
+
+    /// Makes sure `prefix` can hold objects. A no-op for flat key stores.
+    async fn ensure_dir(&self, prefix: &str) -> Result<(), String>;
+}
+
+/// Username and secret for a remote backend. For S3 these are the access key
+/// id and secret access key.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSyncRemoteCredentials {
+    pub username: String,
+    pub secret: String,
+}
+
+impl std::fmt::Debug for FolderSyncRemoteCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FolderSyncRemoteCredentials")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FolderSyncRemoteCredentials {
+    pub fn load(secret_store: &dyn SecretStore) -> Result<Option<Self>, String> {
+        let Some(raw) = secret_store
+            .get_secret(FOLDER_SYNC_REMOTE_CREDENTIALS_SECRET)
+            .map_err(|err| format!("Failed to read folder sync credentials: {err}"))?
+        else {
+            return Ok(None);
+        };
+        serde_json::from_str(&raw)
+            .map(Some)
+            .map_err(|err| format!("Failed to parse folder sync credentials: {err}"))
+    }
+
+    pub fn save(&self, secret_store: &dyn SecretStore) -> Result<(), String> {
+        let raw = serde_json::to_string(self).map_err(|err| err.to_string())?;
+        secret_store
+            .set_secret(FOLDER_SYNC_REMOTE_CREDENTIALS_SECRET, &raw)
+            .map_err(|err| format!("Failed to save folder sync credentials: {err}"))
+    }
+}
+
+/// Where the shared folder lives, parsed from the configured path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FolderSyncLocation {
+    Local(PathBuf),
+    WebDav {
+        base_url: String,
+    },
+    S3 {
+        endpoint: String,
+        bucket: String,
+        prefix: String,
+        region: String,
+    },
+}
+
+impl FolderSyncLocation {
+    pub fn parse(location: &str) -> Result<Self, String> {
+        let location = location.trim();
+        if let Some(url) = location.strip_prefix("webdav+") {
+            let url = reqwest::Url::parse(url)
+                .map_err(|err| format!("Invalid WebDAV folder URL: {err}"))?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err("WebDAV folder URL must use http or https".to_string());
+            }
+            return Ok(Self::WebDav {
+                base_url: url.as_str().trim_end_matches('/').to_string(),
+            });
+        }
+
+        if let Some(url) = location.strip_prefix("s3+") {
+            let url =
+                reqwest::Url::parse(url).map_err(|err| format!("Invalid S3 folder URL: {err}"))?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err("S3 folder URL must use http or https".to_string());
+            }
+            let mut segments = url
+                .path_segments()
+                .into_iter()
+                .flatten()
+                .filter(|segment| !segment.is_empty());
+            let bucket = segments
+                .next()
+                .ok_or_else(|| "S3 folder URL must include a bucket".to_string())?
+                .to_string();
+            let prefix = segments.collect::<Vec<_>>().join("/");
+            let region = url
+                .query_pairs()
+                .find(|(key, _)| key == "region")
+                .map(|(_, value)| value.into_owned())
+                .unwrap_or_else(|| "us-east-1".to_string());
+            let mut endpoint = format!("{}://{}", url.scheme(), url.host_str().unwrap_or_default());
+            if let Some(port) = url.port() {
+                endpoint.push_str(&format!(":{port}"));
+            }
+            return Ok(Self::S3 {
+                endpoint,
+                bucket,
+                prefix,
+                region,
+            });
+        }
+
+        Ok(Self::Local(PathBuf::from(location)))
+    }
+}
+
+/// Builds the backend for a configured shared folder path.
+pub fn open_storage(
+    location: &str,
+    secret_store: &dyn SecretStore,
+) -> Result<Arc<dyn FolderSyncStorage>, String> {
+    let storage: Arc<dyn FolderSyncStorage> = match FolderSyncLocation::parse(location)? {
+        FolderSyncLocation::Local(root) => Arc::new(LocalFolderStorage::new(root)),
+        FolderSyncLocation::WebDav { base_url } => Arc::new(WebDavFolderStorage::new(
+            base_url,
+            FolderSyncRemoteCredentials::load(secret_store)?,
+        )),
+        FolderSyncLocation::S3 {
+            endpoint,
+            bucket,
+            prefix,
+            region,
+        } => {
+            let credentials = FolderSyncRemoteCredentials::load(secret_store)?
+                .ok_or_else(|| "S3 folder sync requires an access key".to_string())?;
+            Arc::new(S3FolderStorage::new(
+                endpoint,
+                bucket,
+                prefix,
+                region,
+                credentials,
+            ))
+        }
+    };
+    Ok(storage)
+}
+
+/// A locally mounted directory, e.g. one kept in sync by Syncthing.
+#[derive(Debug, Clone)]
+pub struct LocalFolderStorage {
+    root: PathBuf,
+}
+
+impl LocalFolderStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        key.split('/')
+            .filter(|segment| !segment.is_empty())
+            .fold(self.root.clone(), |path, segment| path.join(segment))
+    }
+
+    fn collect_keys(&self, dir: &Path, keys: &mut Vec<String>) -> Result<(), String> {
+        for entry in fs::read_dir(dir).map_err(|err| format!("Failed to read directory: {err}"))? {
+            let entry = entry.map_err(|err| format!("Failed to read directory entry: {err}"))?;
+            let path = entry.path();
+            if path.is_dir() {
+                self.collect_keys(&path, keys)?;
+            } else if path.is_file() {
+                let Ok(relative) = path.strip_prefix(&self.root) else {
+                    continue;
+                };
+                keys.push(
+                    relative
+                        .components()
+                        .map(|component| component.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/"),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Local etags combine modification time and size.
+fn local_etag(path: &Path) -> Option<String> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_nanos();
+    Some(format!("{:x}-{:x}", modified, metadata.len()))
+}
+
+fn write_temp_file(target: &Path, bytes: &[u8]) -> Result<PathBuf, String> {
+    let temp_path = target.with_extension(format!("{}.tmp", Uuid::new_v4().simple()));
+    fs::write(&temp_path, bytes).map_err(|err| format!("Failed to write temporary file: {err}"))?;
+    Ok(temp_path)
+}
+
+#[async_trait]
+impl FolderSyncStorage for LocalFolderStorage {
+    fn location(&self) -> String {
+        self.root.display().to_string()
+    }
+
+    async fn read(&self, key: &str) -> Result<Option<StoredObject>, String> {
+        let path = self.path(key);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let etag = local_etag(&path);
+        let bytes = fs::read(&path).map_err(|err| format!("Failed to read {key}: {err}"))?;
+        Ok(Some(StoredObject { bytes, etag }))
+    }
+
+    async fn create(&self, key: &str, bytes: &[u8]) -> Result<bool, String> {
+        let target = self.path(key);
+        if target.exists() {
+            return Ok(false);
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create parent directory: {err}"))?;
+        }
+
+        let temp_path = write_temp_file(&target, bytes)?;
+        fs::rename(&temp_path, &target).map_err(|err| {
+            let _ = fs::remove_file(&temp_path);
+            format!("Failed to finalize file write: {err}")
+        })?;
+        Ok(true)
+    }
+
+    async fn replace(
+        &self,
+        key: &str,
+        bytes: &[u8],
+        if_match: Option<&str>,
+    ) -> Result<bool, String> {
+        let target = self.path(key);
+        if let Some(expected) = if_match {
+            if local_etag(&target).as_deref() != Some(expected) {
+                return Ok(false);
+            }
+        }
+
+        let temp_path = write_temp_file(&target, bytes)?;
+        fs::rename(&temp_path, &target).map_err(|err| {
+            let _ = fs::remove_file(&temp_path);
+            format!("Failed to replace {}: {err}", target.display())
+        })?;
+        Ok(true)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        match fs::remove_file(self.path(key)) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(format!("Failed to delete {key}: {err}")),
+        }
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, String> {
+        let dir = self.path(prefix);
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut keys = Vec::new();
+        self.collect_keys(&dir, &mut keys)?;
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn ensure_dir(&self, prefix: &str) -> Result<(), String> {
+        fs::create_dir_all(self.path(prefix))
+            .map_err(|err| format!("Failed to create {prefix} directory: {err}"))
+    }
+}
+
+/// Sends a request, retrying transport errors, throttling and server errors
+/// with exponential backoff. `build` is called once per attempt so requests
+/// can be re-signed.
+pub(crate) async fn send_with_retry<F>(build: F) -> Result<reqwest::Response, String>
+where
+    F: Fn() -> Result<reqwest::RequestBuilder, String>,
+{
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let result = build()?.send().await;
+        let retryable = match &result {
+            Ok(response) => {
+                let status = response.status();
+                status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            }
+            Err(err) => err.is_connect() || err.is_timeout() || err.is_request(),
+        };
+        if !retryable || attempt >= MAX_ATTEMPTS {
+            return result.map_err(|err| format!("Folder sync request failed: {err}"));
+        }
+        tokio::time::sleep(RETRY_BASE_DELAY * 2u32.pow(attempt - 1)).await;
+    }
+}
+
+/// Turns an unexpected response into an error message.
+pub(crate) async fn response_error(action: &str, response: reqwest::Response) -> String {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    match xml_text_values(&body, "Message").into_iter().next() {
+        Some(detail) if !detail.is_empty() => {
+            format!("Failed to {action}: HTTP {status}: {detail}")
+        }
+        _ => format!("Failed to {action}: HTTP {status}"),
+    }
+}
+
+/// Returns the text of every `<tag>` element, with or without a namespace
+/// prefix. Enough for S3 listings and WebDAV multistatus responses.
+pub(crate) fn xml_text_values(xml: &str, tag: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let Some(name) = rest[..end].split_whitespace().next() else {
+            continue;
+        };
+        let local_name = name.rsplit(':').next().unwrap_or(name);
+        if name.starts_with('/') || rest[..end].ends_with('/') || local_name != tag {
+            continue;
+        }
+        let body = &rest[end + 1..];
+        let closing = format!("</{name}>");
+        let Some(close) = body.find(&closing) else {
+            break;
+        };
+        values.push(xml_unescape(&body[..close]));
+        rest = &body[close + closing.len()..];
+    }
+    values
+}
+
+fn xml_unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::{xml_text_values, FolderSyncLocation, FolderSyncStorage, LocalFolderStorage};
+
+    #[test]
+    fn parses_backend_from_location() {
+        assert_eq!(
+            FolderSyncLocation::parse("/Users/me/Sync/Panorama").expect("local"),
+            FolderSyncLocation::Local("/Users/me/Sync/Panorama".into())
+        );
+        assert_eq!(
+            FolderSyncLocation::parse("webdav+https://cloud.example.com/remote.php/dav/files/me/")
+                .expect("webdav"),
+            FolderSyncLocation::WebDav {
+                base_url: "https://cloud.example.com/remote.php/dav/files/me".to_string(),
+            }
+        );
+        assert_eq!(
+            FolderSyncLocation::parse("s3+http://localhost:9000/panorama/sync/home?region=eu-1")
+                .expect("s3"),
+            FolderSyncLocation::S3 {
+                endpoint: "http://localhost:9000".to_string(),
+                bucket: "panorama".to_string(),
+                prefix: "sync/home".to_string(),
+                region: "eu-1".to_string(),
+            }
+        );
+        assert!(FolderSyncLocation::parse("s3+https://s3.example.com/").is_err());
+    }
+
+    #[tokio::test]
+    async fn local_storage_writes_conditionally() {
+        let root = tempdir().expect("tempdir");
+        let storage = LocalFolderStorage::new(root.path().join("PanoramaSync"));
+
+        assert!(storage
+            .create("events/device-a/evt-1.json", b"one")
+            .await
+            .expect("create"));
+        assert!(!storage
+            .create("events/device-a/evt-1.json", b"two")
+            .await
+            .expect("create again"));
+
+        let stored = storage
+            .read("events/device-a/evt-1.json")
+            .await
+            .expect("read")
+            .expect("object");
+        assert_eq!(stored.bytes, b"one");
+        assert!(!storage
+            .replace("events/device-a/evt-1.json", b"two", Some("stale"))
+            .await
+            .expect("stale replace"));
+        assert!(storage
+            .replace("events/device-a/evt-1.json", b"two", stored.etag.as_deref())
+            .await
+            .expect("replace"));
+
+        assert_eq!(
+            storage.list("events").await.expect("list"),
+            vec!["events/device-a/evt-1.json".to_string()]
+        );
+        storage
+            .delete("events/device-a/evt-1.json")
+            .await
+            .expect("delete");
+        assert!(storage
+            .read("events/device-a/evt-1.json")
+            .await
+            .expect("read deleted")
+            .is_none());
+    }
+
+    #[test]
+    fn extracts_namespaced_xml_values() {
+        let xml = r#"<d:multistatus xmlns:d="DAV:"><d:response><d:href>/dav/a%20b.json</d:href></d:response><d:response><d:href>/dav/c&amp;d.json</d:href></d:response></d:multistatus>"#;
+        assert_eq!(
+            xml_text_values(xml, "href"),
+            vec!["/dav/a%20b.json".to_string(), "/dav/c&d.json".to_string()]
+        );
+    }
+}
@@ -0,0 +1,482 @@
+//! WebDAV backend for folder sync (Nextcloud, ownCloud, Apache mod_dav, ...).
+
+use async_trait::async_trait;
+use reqwest::{Method, StatusCode};
+
+use crate::services::folder_sync_storage::{
+    response_error, send_with_retry, xml_text_values, FolderSyncRemoteCredentials,
+    FolderSyncStorage, StoredObject,
+};
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?><d:propfind xmlns:d="DAV:"><d:prop><d:resourcetype/></d:prop></d:propfind>"#;
+
+#[derive(Debug, Clone)]
+pub struct WebDavFolderStorage {
+    client: reqwest::Client,
+    base_url: String,
+    /// Decoded path of `base_url`, used to turn listed hrefs back into keys.
+    base_path: String,
+    credentials: Option<FolderSyncRemoteCredentials>,
+}
+
+impl WebDavFolderStorage {
+    pub fn new(base_url: String, credentials: Option<FolderSyncRemoteCredentials>) -> Self {
+        let base_url = base_url.trim_end_matches('/').to_string();
+        let base_path = reqwest::Url::parse(&base_url)
+            .ok()
+            .map(|url| decode_path(url.path()))
+            .unwrap_or_default()
+            .trim_end_matches('/')
+            .to_string();
+        Self {
+            client: reqwest::Client::new(),
+            base_url,
+            base_path,
+            credentials,
+        }
+    }
+
+    fn url(&self, key: &str) -> String {
+        let encoded = key
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| urlencoding::encode(segment).into_owned())
+            .collect::<Vec<_>>()
+            .join("/");
+        if encoded.is_empty() {
+            format!("{}/", self.base_url)
+        } else {
+            format!("{}/{}", self.base_url, encoded)
+        }
+    }
+
+    fn request(&self, method: Method, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, url);
+        match &self.credentials {
+            Some(credentials) => {
+                request.basic_auth(&credentials.username, Some(&credentials.secret))
+            }
+            None => request,
+        }
+    }
+
+    async fn put(
+        &self,
+        key: &str,
+        bytes: &[u8],
+        condition: Option<(&'static str, &str)>,
+    ) -> Result<bool, String> {
+        let url = self.url(key);
+        for attempt in 0..2 {
+            let response = send_with_retry(|| {
+                let request = self.request(Method::PUT, &url).body(bytes.to_vec());
+                Ok(match condition {
+                    Some((header, value)) => request.header(header, value),
+                    None => request,
+                })
+            })
+            .await?;
+            match response.status() {
+                StatusCode::PRECONDITION_FAILED => return Ok(false),
+                // The parent collection is missing; create it and try once more.
+                StatusCode::CONFLICT if attempt == 0 => {
+                    if let Some((parent, _)) = key.rsplit_once('/') {
+                        self.ensure_dir(parent).await?;
+                    }
+                }
+                status if status.is_success() => return Ok(true),
+                _ => return Err(response_error(&format!("write {key}"), response).await),
+            }
+        }
+        Err(format!(
+            "Failed to write {key}: parent collection is missing"
+        ))
+    }
+
+    /// Lists one collection level. Returns `(key, is_collection)` pairs.
+    async fn list_collection(&self, prefix: &str) -> Result<Vec<(String, bool)>, String> {
+        let url = format!("{}/", self.url(prefix).trim_end_matches('/'));
+        let propfind = Method::from_bytes(b"PROPFIND").map_err(|err| err.to_string())?;
+        let response = send_with_retry(|| {
+            Ok(self
+                .request(propfind.clone(), &url)
+                .header("Depth", "1")
+                .header("Content-Type", "application/xml; charset=utf-8")
+                .body(PROPFIND_BODY))
+        })
+        .await?;
+        match response.status() {
+            StatusCode::NOT_FOUND => return Ok(Vec::new()),
+            StatusCode::MULTI_STATUS => {}
+            _ => return Err(response_error(&format!("list {prefix}"), response).await),
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|err| format!("Failed to read WebDAV listing: {err}"))?;
+        let prefix = prefix.trim_matches('/');
+        let mut entries = Vec::new();
+        for block in xml_text_values(&body, "response") {
+            let Some(href) = xml_text_values(&block, "href").into_iter().next() else {
+                continue;
+            };
+            let Some(key) = self.href_to_key(&href) else {
+                continue;
+            };
+            if key.is_empty() || key == prefix {
+                continue;
+            }
+            let is_collection = block.contains(":collection") || block.contains("<collection");
+            entries.push((key, is_collection));
+        }
+        Ok(entries)
+    }
+
+    fn href_to_key(&self, href: &str) -> Option<String> {
+        let path = if href.starts_with("http://") || href.starts_with("https://") {
+            reqwest::Url::parse(href).ok()?.path().to_string()
+        } else {
+            href.to_string()
+        };
+        let path = decode_path(&path);
+        let relative = path.strip_prefix(&self.base_path)?;
+        Some(relative.trim_matches('/').to_string())
+    }
+}
+
+fn decode_path(path: &str) -> String {
+    urlencoding::decode(path)
+        .map(|value| value.into_owned())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+#[async_trait]
+impl FolderSyncStorage for WebDavFolderStorage {
+    fn location(&self) -> String {
+        self.base_url.clone()
+    }
+
+    async fn read(&self, key: &str) -> Result<Option<StoredObject>, String> {
+        let url = self.url(key);
+        let response = send_with_retry(|| Ok(self.request(Method::GET, &url))).await?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => {
+                let etag = response
+                    .headers()
+                    .get(reqwest::header::ETAG)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string);
+                let bytes = response
+                    .bytes()
+                    .await
+                    .map_err(|err| format!("Failed to read {key}: {err}"))?;
+                Ok(Some(StoredObject {
+                    bytes: bytes.to_vec(),
+                    etag,
+                }))
+            }
+            _ => Err(response_error(&format!("read {key}"), response).await),
+        }
+    }
+
+    async fn create(&self, key: &str, bytes: &[u8]) -> Result<bool, String> {
+        self.put(key, bytes, Some(("If-None-Match", "*"))).await
+    }
+
+    async fn replace(
+        &self,
+        key: &str,
+        bytes: &[u8],
+        if_match: Option<&str>,
+    ) -> Result<bool, String> {
+        self.put(key, bytes, if_match.map(|etag| ("If-Match", etag)))
+            .await
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        let url = self.url(key);
+        let response = send_with_retry(|| Ok(self.request(Method::DELETE, &url))).await?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(()),
+            status if status.is_success() => Ok(()),
+            _ => Err(response_error(&format!("delete {key}"), response).await),
+        }
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, String> {
+        // Many servers reject `Depth: infinity`, so walk one level at a time.
+        let mut keys = Vec::new();
+        let mut pending = vec![prefix.trim_matches('/').to_string()];
+        while let Some(collection) = pending.pop() {
+            for (key, is_collection) in self.list_collection(&collection).await? {
+                if is_collection {
+                    pending.push(key);
+                } else {
+                    keys.push(key);
+                }
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn ensure_dir(&self, prefix: &str) -> Result<(), String> {
+        let mkcol = Method::from_bytes(b"MKCOL").map_err(|err| err.to_string())?;
+        let mut collection = String::new();
+        for segment in prefix.split('/').filter(|segment| !segment.is_empty()) {
+            if !collection.is_empty() {
+                collection.push('/');
+            }
+            collection.push_str(segment);
+            let url = format!("{}/", self.url(&collection));
+            let response = send_with_retry(|| Ok(self.request(mkcol.clone(), &url))).await?;
+            match response.status() {
+                // 405: the collection already exists.
+                StatusCode::METHOD_NOT_ALLOWED => {}
+                status if status.is_success() => {}
+                _ => return Err(response_error(&format!("create {collection}"), response).await),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::sync::{Arc, Mutex};
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    use super::WebDavFolderStorage;
+    use crate::services::folder_sync_storage::FolderSyncStorage;
+
+    #[derive(Default)]
+    struct StubState {
+        files: BTreeMap<String, (Vec<u8>, u64)>,
+        collections: BTreeSet<String>,
+        version: u64,
+    }
+
+    /// Minimal in-process WebDAV server: GET, PUT (with If-None-Match and
+    /// If-Match), DELETE, MKCOL and PROPFIND with `Depth: 1`. Returns the
+    /// base URL of a collection served from memory.
+    pub(crate) async fn spawn_webdav_stub() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind stub");
+        let address = listener.local_addr().expect("stub address");
+        let state = Arc::new(Mutex::new(StubState::default()));
+        state.lock().unwrap().collections.insert("/dav".to_string());
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve_connection(stream, state.clone()));
+            }
+        });
+        format!("http://{address}/dav")
+    }
+
+    async fn serve_connection(mut stream: TcpStream, state: Arc<Mutex<StubState>>) {
+        let mut buffer = Vec::new();
+        loop {
+            let Some(header_end) = find(&buffer, b"\r\n\r\n") else {
+                let mut chunk = [0u8; 8192];
+                match stream.read(&mut chunk).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(read) => buffer.extend_from_slice(&chunk[..read]),
+                }
+                continue;
+            };
+            let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+            let mut lines = head.lines();
+            let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+            let method = request_line.next().unwrap_or_default().to_string();
+            let path = urlencoding::decode(request_line.next().unwrap_or_default())
+                .map(|value| value.into_owned())
+                .unwrap_or_default();
+            let headers = lines
+                .filter_map(|line| line.split_once(':'))
+                .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+                .collect::<BTreeMap<_, _>>();
+            let length = headers
+                .get("content-length")
+                .and_then(|value| value.parse::<usize>().ok())
+                .unwrap_or(0);
+            while buffer.len() < header_end + 4 + length {
+                let mut chunk = [0u8; 8192];
+                match stream.read(&mut chunk).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(read) => buffer.extend_from_slice(&chunk[..read]),
+                }
+            }
+            let body = buffer[header_end + 4..header_end + 4 + length].to_vec();
+            buffer.drain(..header_end + 4 + length);
+
+            let (status, extra, response_body) =
+                handle(&mut state.lock().unwrap(), &method, &path, &headers, body);
+            let response = format!(
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\n{extra}\r\n",
+                response_body.len()
+            );
+            if stream.write_all(response.as_bytes()).await.is_err()
+                || stream.write_all(&response_body).await.is_err()
+            {
+                return;
+            }
+        }
+    }
+
+    fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        haystack
+            .windows(needle.len())
+            .position(|window| window == needle)
+    }
+
+    fn handle(
+        state: &mut StubState,
+        method: &str,
+        path: &str,
+        headers: &BTreeMap<String, String>,
+        body: Vec<u8>,
+    ) -> (&'static str, String, Vec<u8>) {
+        let path = path.trim_end_matches('/').to_string();
+        let parent = path
+            .rsplit_once('/')
+            .map(|(parent, _)| parent.to_string())
+            .unwrap_or_default();
+        match method {
+            "GET" => match state.files.get(&path) {
+                Some((bytes, version)) => {
+                    ("200 OK", format!("ETag: \"{version}\"\r\n"), bytes.clone())
+                }
+                None => ("404 Not Found", String::new(), Vec::new()),
+            },
+            "PUT" => {
+                if !state.collections.contains(&parent) {
+                    return ("409 Conflict", String::new(), Vec::new());
+                }
+                let current = state
+                    .files
+                    .get(&path)
+                    .map(|(_, version)| format!("\"{version}\""));
+                if headers
+                    .get("if-none-match")
+                    .is_some_and(|value| value == "*")
+                    && current.is_some()
+                {
+                    return ("412 Precondition Failed", String::new(), Vec::new());
+                }
+                if let Some(expected) = headers.get("if-match") {
+                    if current.as_ref() != Some(expected) {
+                        return ("412 Precondition Failed", String::new(), Vec::new());
+                    }
+                }
+                state.version += 1;
+                state.files.insert(path, (body, state.version));
+                ("201 Created", String::new(), Vec::new())
+            }
+            "DELETE" => match state.files.remove(&path) {
+                Some(_) => ("204 No Content", String::new(), Vec::new()),
+                None => ("404 Not Found", String::new(), Vec::new()),
+            },
+            "MKCOL" => {
+                if state.collections.contains(&path) {
+                    ("405 Method Not Allowed", String::new(), Vec::new())
+                } else if !state.collections.contains(&parent) {
+                    ("409 Conflict", String::new(), Vec::new())
+                } else {
+                    state.collections.insert(path);
+                    ("201 Created", String::new(), Vec::new())
+                }
+            }
+            "PROPFIND" => {
+                if !state.collections.contains(&path) {
+                    return ("404 Not Found", String::new(), Vec::new());
+                }
+                let child_prefix = format!("{path}/");
+                let is_child = |candidate: &String| {
+                    candidate
+                        .strip_prefix(&child_prefix)
+                        .is_some_and(|rest| !rest.is_empty() && !rest.contains('/'))
+                };
+                let mut xml =
+                    String::from(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:">"#);
+                xml.push_str(&format!(
+                    "<d:response><d:href>{path}/</d:href><d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop></d:propstat></d:response>"
+                ));
+                for collection in state.collections.iter().filter(|value| is_child(value)) {
+                    xml.push_str(&format!(
+                        "<d:response><d:href>{}/</d:href><d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop></d:propstat></d:response>",
+                        urlencoding::encode(collection).replace("%2F", "/")
+                    ));
+                }
+                for file in state.files.keys().filter(|value| is_child(value)) {
+                    xml.push_str(&format!(
+                        "<d:response><d:href>{}</d:href><d:propstat><d:prop><d:resourcetype/></d:prop></d:propstat></d:response>",
+                        urlencoding::encode(file).replace("%2F", "/")
+                    ));
+                }
+                xml.push_str("</d:multistatus>");
+                ("207 Multi-Status", String::new(), xml.into_bytes())
+            }
+            _ => ("405 Method Not Allowed", String::new(), Vec::new()),
+        }
+    }
+
+    #[tokio::test]
+    async fn webdav_storage_round_trips_against_stub() {
+        let storage = WebDavFolderStorage::new(spawn_webdav_stub().await, None);
+
+        assert!(storage
+            .create("events/device a/evt-1.json", b"one")
+            .await
+            .expect("create"));
+        assert!(!storage
+            .create("events/device a/evt-1.json", b"two")
+            .await
+            .expect("create again"));
+        storage
+            .create("folder.json", b"{}")
+            .await
+            .expect("create metadata");
+
+        let stored = storage
+            .read("events/device a/evt-1.json")
+            .await
+            .expect("read")
+            .expect("object");
+        assert_eq!(stored.bytes, b"one");
+        assert!(!storage
+            .replace("events/device a/evt-1.json", b"two", Some("\"0\""))
+            .await
+            .expect("stale replace"));
+        assert!(storage
+            .replace("events/device a/evt-1.json", b"two", stored.etag.as_deref())
+            .await
+            .expect("replace"));
+
+        assert_eq!(
+            storage.list("").await.expect("list"),
+            vec![
+                "events/device a/evt-1.json".to_string(),
+                "folder.json".to_string()
+            ]
+        );
+        assert!(storage
+            .list("snapshots")
+            .await
+            .expect("list missing")
+            .is_empty());
+
+        storage
+            .delete("events/device a/evt-1.json")
+            .await
+            .expect("delete");
+        assert!(storage
+            .read("events/device a/evt-1.json")
+            .await
+            .expect("read deleted")
+            .is_none());
+    }
+}
@@ -1,6 +1,7 @@
 //! Application services for the Tauri app.
 
 mod connect_service;
+pub mod folder_sync_crypto;
 #[allow(dead_code)]
 pub mod folder_sync_exporter;
 #[allow(dead_code)]
@@ -9,7 +10,10 @@ pub mod folder_sync_fs;
 pub mod folder_sync_importer;
 #[allow(dead_code)]
 pub mod folder_sync_runtime;
+pub mod folder_sync_s3;
 #[allow(dead_code)]
 pub mod folder_sync_snapshot;
+pub mod folder_sync_storage;
+pub mod folder_sync_webdav;
 
 pub use connect_service::{cloud_api_base_url, ConnectService};
@@ -7,9 +7,10 @@
 //! - Multi-turn tool execution
 //! - Emitting structured stream events for the frontend
 
+use chrono::NaiveDate;
 use futures::stream::BoxStream;
 use futures::StreamExt;
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use reqwest::Client as HttpClient;
 use rig::{
     agent::{Agent, MultiTurnStreamItem},
@@ -33,21 +34,31 @@ use std::time::Duration;
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
+use wealthfolio_core::constants::PORTFOLIO_TOTAL_ACCOUNT_ID;
+
+use crate::context::{
+    history_token_budget, plan_history, render_context_sections, summarize_turns, HistoryPlan,
+};
+use crate::digest::DigestService;
 use crate::env::AiEnvironment;
 use crate::error::AiError;
 use crate::providers::ProviderService;
+use crate::redaction::{RedactingTool, RedactionSession, StreamRehydrator};
 use crate::title_generator::truncate_to_title;
 use crate::title_generator::{TitleGenerator, TitleGeneratorConfig, TitleGeneratorTrait};
 use crate::tools::constants::{
-    MAX_ATTACHMENTS_COUNT, MAX_ATTACHMENT_SIZE_BYTES, MAX_HISTORY_CHARS,
-    MAX_TOTAL_ATTACHMENTS_BYTES,
+    MAX_ATTACHMENTS_COUNT, MAX_ATTACHMENT_SIZE_BYTES, MAX_TOTAL_ATTACHMENTS_BYTES,
+};
+use crate::tools::extract_statement::{
+    is_statement_attachment, ExtractStatementTool, ModelStatementExtractor,
 };
 use crate::tools::ToolSet;
 use crate::types::{
-    AiStreamEvent, ChatMessage, ChatMessageContent, ChatMessagePart, ChatMessageRole,
-    ChatRepositoryTrait, ChatThread, ListThreadsRequest, MessageAttachment, SendMessageRequest,
-    SimpleChatMessage, ThreadPage, ToolCall, ToolResultData,
+    AiStreamEvent, ChatMessage, ChatMessageContent, ChatMessagePart, ChatRepositoryTrait,
+    ChatThread, ListThreadsRequest, MessageAttachment, MessageUsage, SendMessageRequest,
+    SimpleChatMessage, ThreadPage, ThreadSummary, ToolCall, ToolResultData,
 };
+use crate::usage::{BudgetStatus, TurnUsage, UsageReport, UsageService};
 
 fn derive_initial_thread_title(first_user_message: &str) -> Option<String> {
     let trimmed = first_user_message.trim();
@@ -74,7 +85,7 @@ pub struct ChatConfig {
 impl Default for ChatConfig {
     fn default() -> Self {
         Self {
-            max_tool_rounds: 5,
+            max_tool_rounds: 6,
             max_tokens: Some(4096),
             temperature: Some(0.7),
         }
@@ -88,7 +99,6 @@ impl Default for ChatConfig {
 /// Chat service for managing threads and streaming responses.
 pub struct ChatService<E: AiEnvironment + 'static> {
     env: Arc<E>,
-    #[allow(dead_code)]
     config: ChatConfig,
 }
 
@@ -216,6 +226,9 @@ impl<E: AiEnvironment + 'static> ChatService<E> {
             }
         }
 
+        // Enforce the monthly usage budget before anything is persisted
+        let budget_warning = UsageService::new(self.env.clone()).check_budget()?;
+
         // Get or create thread
         let (thread, is_new_thread, initial_title) = match &request.thread_id {
             Some(id) => {
@@ -246,34 +259,13 @@ impl<E: AiEnvironment + 'static> ChatService<E> {
             }
         }
 
-        // Build history with a reverse character-budget window:
-        // take messages from most recent backwards until the budget is exhausted.
-        let mut history_messages: Vec<SimpleChatMessage> = Vec::new();
-        let mut budget = MAX_HISTORY_CHARS;
-        for msg in previous_messages.iter().rev() {
-            let text = msg.content.get_text_content();
-            if text.is_empty() {
-                continue;
-            }
-            let simple = match msg.role {
-                ChatMessageRole::User => SimpleChatMessage::user(&text),
-                ChatMessageRole::Assistant => SimpleChatMessage::assistant(&text),
-                _ => continue,
-            };
-            if text.len() > budget {
-                break;
-            }
-            budget -= text.len();
-            history_messages.push(simple);
-        }
-        history_messages.reverse();
-
         // Save user message with attachment placeholders (no binary data stored)
         let mut persist_text = request.content.clone();
         for att in &attachments {
             persist_text.push_str(&format!("\n[Attached: {}]", att.name));
         }
         let user_message = ChatMessage::user(&thread_id, &persist_text);
+        let user_message_id = user_message.id.clone();
         repo.create_message(user_message).await?;
 
         // Get provider settings
@@ -291,6 +283,15 @@ impl<E: AiEnvironment + 'static> ChatService<E> {
 
         debug!("Using provider {} with model {}", provider_id, model_id);
 
+        // Fit history to the model's context window. Turns that no longer fit are
+        // folded into the thread summary once the stream starts.
+        let context_window = provider_service.get_context_window(&provider_id, &model_id);
+        let history = plan_history(
+            &previous_messages,
+            thread.summary.as_ref(),
+            history_token_budget(context_window),
+        );
+
         // Generate IDs for this run
         let run_id = Uuid::now_v7().to_string();
         let message_id = Uuid::now_v7().to_string();
@@ -308,6 +309,7 @@ impl<E: AiEnvironment + 'static> ChatService<E> {
         let initial_title_clone = initial_title.clone();
         let is_new_thread_clone = is_new_thread;
         let thinking_override = request.config.as_ref().and_then(|c| c.thinking);
+        let max_tool_rounds = self.config.max_tool_rounds;
 
         // Spawn the streaming task
         tokio::spawn(async move {
@@ -315,17 +317,20 @@ impl<E: AiEnvironment + 'static> ChatService<E> {
                 env,
                 tx.clone(),
                 content,
-                history_messages,
+                history,
                 attachments,
                 provider_id,
                 model_id,
                 thread_id_clone.clone(),
                 run_id_clone.clone(),
                 message_id_clone,
+                user_message_id,
                 thread_title,
                 initial_title_clone,
                 is_new_thread_clone,
                 thinking_override,
+                max_tool_rounds,
+                budget_warning,
             )
             .await
             {
@@ -364,6 +369,24 @@ impl<E: AiEnvironment + 'static> ChatService<E> {
         ]
     }
 
+    /// Token usage and estimated cost for `[from, to]` (UTC days, inclusive).
+    /// Defaults to the current month to date.
+    pub fn get_usage_report(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<UsageReport, AiError> {
+        UsageService::new(self.env.clone()).get_report(from, to)
+    }
+
+    /// Write the weekly portfolio digest if one is due.
+    /// Returns the new digest thread, or None when not due or nothing to report.
+    pub async fn generate_digest_if_due(&self) -> Result<Option<ChatThread>, AiError> {
+        DigestService::new(self.env.clone())
+            .run_if_due(chrono::Utc::now())
+            .await
+    }
+
     /// Get environment reference.
     pub fn env(&self) -> &Arc<E> {
         &self.env
@@ -374,6 +397,8 @@ impl<E: AiEnvironment + 'static> ChatService<E> {
     /// This is used by the frontend to persist submission state for mutation tools
     /// (e.g., record_activity). After the user confirms and the activity is created,
     /// the frontend calls this to store the created_activity_id in the tool result.
+    /// Patching `{"pinned": true}` keeps the result in the model's context even after
+    /// its turn is summarised (see [`crate::context`]).
     ///
     /// The thread_id is used to search for the message containing the tool_call_id.
     pub async fn update_tool_result(
@@ -492,7 +517,7 @@ fn build_user_prompt(user_message: &str, attachments: &[MessageAttachment]) -> M
         text.push_str("[INSTRUCTION: A CSV file is attached. You MUST call the import_csv tool with the full CSV content in csvContent parameter. Do NOT analyze or summarize the data yourself - use the tool.]\n\n");
     }
     if has_image_or_pdf {
-        text.push_str("[INSTRUCTION: Image or PDF file(s) attached. Examine for financial transaction data and use record_activities to create drafts for all extracted transactions.]\n\n");
+        text.push_str("[INSTRUCTION: Image or PDF file(s) attached. Call the extract_statement tool to extract all financial transactions as reviewable drafts. If that tool is unavailable, examine the files yourself and use record_activities to create the drafts.]\n\n");
     }
     text.push_str(user_message);
     parts.push(UserContent::Text(Text { text }));
@@ -542,33 +567,133 @@ fn build_user_prompt(user_message: &str, attachments: &[MessageAttachment]) -> M
     }
 }
 
+/// Build the agent's tool list, filtered by the provider allowlist
+/// (None = all tools) and wrapped for redaction when it is enabled.
+///
+/// `statement_tool` is only available when the message has image/PDF attachments.
+pub(crate) fn build_allowed_tools<E: AiEnvironment + 'static>(
+    env: &Arc<E>,
+    tools_allowlist: Option<&[String]>,
+    redaction: &Arc<RedactionSession>,
+    statement_tool: Option<ExtractStatementTool<E>>,
+) -> Vec<Box<dyn ToolDyn>> {
+    let tool_set = ToolSet::new(env.clone(), env.base_currency());
+
+    // Build filtered tool list based on provider allowlist
+    let is_allowed = |name: &str| -> bool {
+        match tools_allowlist {
+            None => true, // None = all tools allowed
+            Some(list) => list.iter().any(|t| t == name),
+        }
+    };
+
+    let mut allowed_tools: Vec<Box<dyn ToolDyn>> = Vec::new();
+    if is_allowed("get_holdings") {
+        allowed_tools.push(Box::new(tool_set.holdings));
+    }
+    if is_allowed("get_accounts") {
+        allowed_tools.push(Box::new(tool_set.accounts));
+    }
+    if is_allowed("search_activities") {
+        allowed_tools.push(Box::new(tool_set.activities));
+    }
+    if is_allowed("get_goals") {
+        allowed_tools.push(Box::new(tool_set.goals));
+    }
+    if is_allowed("get_valuation_history") {
+        allowed_tools.push(Box::new(tool_set.valuation));
+    }
+    if is_allowed("get_income") {
+        allowed_tools.push(Box::new(tool_set.income));
+    }
+    if is_allowed("get_asset_allocation") {
+        allowed_tools.push(Box::new(tool_set.allocation));
+    }
+    if is_allowed("get_performance") {
+        allowed_tools.push(Box::new(tool_set.performance));
+    }
+    if is_allowed("get_net_worth") {
+        allowed_tools.push(Box::new(tool_set.net_worth));
+    }
+    if is_allowed("get_alternative_assets") {
+        allowed_tools.push(Box::new(tool_set.alternative_assets));
+    }
+    if is_allowed("get_health_issues") {
+        allowed_tools.push(Box::new(tool_set.health));
+    }
+    if is_allowed("get_exchange_rate") {
+        allowed_tools.push(Box::new(tool_set.exchange_rate));
+    }
+    if is_allowed("get_quote_history") {
+        allowed_tools.push(Box::new(tool_set.quote_history));
+    }
+    if is_allowed("simulate_scenario") {
+        allowed_tools.push(Box::new(tool_set.scenario));
+    }
+    if is_allowed("record_activity") {
+        allowed_tools.push(Box::new(tool_set.record_activity));
+    }
+    if is_allowed("record_activities") {
+        allowed_tools.push(Box::new(tool_set.record_activities));
+    }
+    if is_allowed("import_csv") {
+        allowed_tools.push(Box::new(tool_set.import_csv));
+    }
+    if let Some(tool) = statement_tool.filter(|_| is_allowed("extract_statement")) {
+        allowed_tools.push(Box::new(tool));
+    }
+    if redaction.is_enabled() {
+        allowed_tools = allowed_tools
+            .into_iter()
+            .map(|tool| RedactingTool::wrap(tool, redaction.clone()))
+            .collect();
+    }
+
+    allowed_tools
+}
+
 /// Spawn a chat stream with the appropriate provider.
 #[allow(clippy::too_many_arguments)]
 async fn spawn_chat_stream<E: AiEnvironment + 'static>(
     env: Arc<E>,
     tx: mpsc::Sender<AiStreamEvent>,
     user_message: String,
-    history_messages: Vec<SimpleChatMessage>,
+    mut history: HistoryPlan,
     attachments: Vec<MessageAttachment>,
     provider_id: String,
     model_id: String,
     thread_id: String,
     run_id: String,
     message_id: String,
+    user_message_id: String,
     thread_title: Option<String>,
     initial_title: Option<String>,
     is_new_thread: bool,
     thinking_override: Option<bool>,
+    max_tool_rounds: usize,
+    budget_warning: Option<BudgetStatus>,
 ) -> Result<(), AiError> {
     // Send system event first
     tx.send(AiStreamEvent::system(&thread_id, &run_id, &message_id))
         .await
         .map_err(|e| AiError::Internal(e.to_string()))?;
 
+    if let Some(status) = budget_warning {
+        tx.send(AiStreamEvent::usage_budget_warning(
+            &thread_id,
+            &run_id,
+            status.month_to_date_usd,
+            status.monthly_limit_usd,
+        ))
+        .await
+        .map_err(|e| AiError::Internal(e.to_string()))?;
+    }
+
     // Get provider settings and model capabilities
     let provider_service = ProviderService::new(env.clone());
     let api_key = provider_service.get_api_key(&provider_id)?;
     let provider_url = provider_service.get_provider_url(&provider_id);
+    let is_custom_provider = provider_service.is_custom_provider(&provider_id);
     let mut capabilities = provider_service.get_model_capabilities(&provider_id, &model_id);
 
     // Best-effort preflight for Ollama: if we can list models and the selected model
@@ -620,6 +745,102 @@ async fn spawn_chat_stream<E: AiEnvironment + 'static>(
         );
     }
 
+    // Privacy redaction for this run. Names known up front are registered so they are
+    // also replaced in the prompt and history; tool output registers the rest.
+    let redaction_settings = provider_service.get_redaction_settings(&provider_id);
+    let redaction = Arc::new(if redaction_settings.enabled {
+        RedactionSession::new(&thread_id, redaction_settings)
+    } else {
+        RedactionSession::disabled()
+    });
+    if redaction.is_enabled() {
+        if let Ok(accounts) = env.account_service().list_accounts(None, None, None) {
+            redaction.register_account_names(accounts.iter().map(|a| a.name.as_str()));
+        }
+        if let Ok(holdings) = env
+            .holdings_service()
+            .get_holdings(PORTFOLIO_TOTAL_ACCOUNT_ID, &base_currency)
+            .await
+        {
+            redaction.register_asset_names(
+                holdings
+                    .iter()
+                    .filter_map(|h| h.instrument.as_ref())
+                    .filter_map(|i| i.name.as_deref()),
+            );
+        }
+        if let Some(note) = redaction.preamble_note() {
+            preamble.push_str(&note);
+        }
+    }
+    let user_message = redaction.redact_text(&user_message);
+
+    // Fold turns that no longer fit into the thread summary. If that fails they are
+    // left out of this run and retried with the next message.
+    if let Some(through_message_id) = history.overflow_through_id.take() {
+        let overflow: Vec<SimpleChatMessage> = history
+            .overflow
+            .drain(..)
+            .map(|msg| SimpleChatMessage {
+                role: msg.role,
+                content: redaction.redact_text(&msg.content),
+            })
+            .collect();
+        let previous_summary = history
+            .summary
+            .as_ref()
+            .map(|s| redaction.redact_text(&s.content));
+
+        match summarize_turns(
+            &env,
+            &provider_id,
+            &model_id,
+            previous_summary.as_deref(),
+            &overflow,
+        )
+        .await
+        {
+            Ok(reply) => {
+                let summary = ThreadSummary {
+                    content: redaction.rehydrate(&reply.text),
+                    through_message_id,
+                    updated_at: chrono::Utc::now(),
+                };
+                let chat_repo = env.chat_repository();
+                // The summary may come from a different model than the reply, so its
+                // usage is recorded on the user message that triggered it
+                if let Some(usage) = reply.usage {
+                    if let Ok(Some(mut message)) = chat_repo.get_message(&user_message_id) {
+                        message.usage = Some(usage);
+                        if let Err(e) = chat_repo.update_message(message).await {
+                            warn!(
+                                "Failed to record summary usage for thread {}: {}",
+                                thread_id, e
+                            );
+                        }
+                    }
+                }
+                if let Ok(Some(thread)) = chat_repo.get_thread(&thread_id) {
+                    let updated_thread = ChatThread {
+                        summary: Some(summary.clone()),
+                        ..thread
+                    };
+                    if let Err(e) = chat_repo.update_thread(updated_thread).await {
+                        warn!("Failed to save summary for thread {}: {}", thread_id, e);
+                    }
+                }
+                history.summary = Some(summary);
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to summarise older turns of thread {}: {}",
+                    thread_id, e
+                );
+            }
+        }
+    }
+    preamble.push_str(&render_context_sections(&history, &redaction));
+
     // Create title context for post-stream title generation (clone user_message before move)
     let title_ctx = TitleContext {
         env: env.clone(),
@@ -648,21 +869,32 @@ async fn spawn_chat_stream<E: AiEnvironment + 'static>(
     // Build multimodal user content from text + attachments
     let prompt = build_user_prompt(&user_message, &attachments);
 
+    // Images/PDFs can be extracted into activity drafts by the chat model itself
+    // (vision was checked above).
+    let statement_attachments: Vec<MessageAttachment> = attachments
+        .iter()
+        .filter(|a| is_statement_attachment(a))
+        .cloned()
+        .collect();
+    let statement_attachments =
+        (!statement_attachments.is_empty()).then(|| Arc::new(statement_attachments));
+
     // Build history from previous messages
-    let history: Vec<Message> = history_messages
+    let history: Vec<Message> = history
+        .messages
         .iter()
         .map(|msg| {
             if msg.role.eq_ignore_ascii_case("user") {
                 Message::User {
                     content: OneOrMany::one(UserContent::Text(Text {
-                        text: msg.content.clone(),
+                        text: redaction.redact_text(&msg.content),
                     })),
                 }
             } else {
                 Message::Assistant {
                     id: None,
                     content: OneOrMany::one(AssistantContent::Text(Text {
-                        text: msg.content.clone(),
+                        text: redaction.redact_text(&msg.content),
                     })),
                 }
             }
@@ -698,50 +930,19 @@ async fn spawn_chat_stream<E: AiEnvironment + 'static>(
             build_with_tools_and_stream!($client, $thinking_params, None::<u64>)
         };
         ($client:expr, $thinking_params:expr, $max_tokens:expr) => {{
-            let tool_set = ToolSet::new(env.clone(), env.base_currency());
-
-            // Build filtered tool list based on provider allowlist
-            let is_allowed = |name: &str| -> bool {
-                match &tools_allowlist {
-                    None => true, // None = all tools allowed
-                    Some(list) => list.iter().any(|t| t == name),
-                }
-            };
-
-            let mut allowed_tools: Vec<Box<dyn ToolDyn>> = Vec::new();
-            if is_allowed("get_holdings") {
-                allowed_tools.push(Box::new(tool_set.holdings));
-            }
-            if is_allowed("get_accounts") {
-                allowed_tools.push(Box::new(tool_set.accounts));
-            }
-            if is_allowed("search_activities") {
-                allowed_tools.push(Box::new(tool_set.activities));
-            }
-            if is_allowed("get_goals") {
-                allowed_tools.push(Box::new(tool_set.goals));
-            }
-            if is_allowed("get_valuation_history") {
-                allowed_tools.push(Box::new(tool_set.valuation));
-            }
-            if is_allowed("get_income") {
-                allowed_tools.push(Box::new(tool_set.income));
-            }
-            if is_allowed("get_asset_allocation") {
-                allowed_tools.push(Box::new(tool_set.allocation));
-            }
-            if is_allowed("get_performance") {
-                allowed_tools.push(Box::new(tool_set.performance));
-            }
-            if is_allowed("record_activity") {
-                allowed_tools.push(Box::new(tool_set.record_activity));
-            }
-            if is_allowed("record_activities") {
-                allowed_tools.push(Box::new(tool_set.record_activities));
-            }
-            if is_allowed("import_csv") {
-                allowed_tools.push(Box::new(tool_set.import_csv));
-            }
+            let turn_usage = Arc::new(TurnUsage::default());
+            let statement_tool = statement_attachments.clone().map(|attachments| {
+                ExtractStatementTool::new(
+                    env.clone(),
+                    attachments,
+                    Arc::new(ModelStatementExtractor::new(
+                        $client.completion_model(&model_id),
+                        turn_usage.clone(),
+                    )),
+                )
+            });
+            let allowed_tools =
+                build_allowed_tools(&env, tools_allowlist.as_deref(), &redaction, statement_tool);
 
             let mut builder = $client
                 .agent(&model_id)
@@ -765,7 +966,18 @@ async fn spawn_chat_stream<E: AiEnvironment + 'static>(
 
             let agent = builder.build();
             stream_agent_response(
-                agent, prompt, history, tx, repo, thread_id, run_id, message_id, title_ctx,
+                agent,
+                prompt,
+                history,
+                tx,
+                repo,
+                thread_id,
+                run_id,
+                message_id,
+                title_ctx,
+                redaction,
+                turn_usage,
+                max_tool_rounds,
             )
             .await
             .map_err(|e| remap_provider_error(&provider_id, &model_id, e))
@@ -790,7 +1002,18 @@ async fn spawn_chat_stream<E: AiEnvironment + 'static>(
 
             let agent = builder.build();
             stream_agent_response(
-                agent, prompt, history, tx, repo, thread_id, run_id, message_id, title_ctx,
+                agent,
+                prompt,
+                history,
+                tx,
+                repo,
+                thread_id,
+                run_id,
+                message_id,
+                title_ctx,
+                redaction,
+                Arc::new(TurnUsage::default()),
+                max_tool_rounds,
             )
             .await
             .map_err(|e| remap_provider_error(&provider_id, &model_id, e))
@@ -901,6 +1124,11 @@ async fn spawn_chat_stream<E: AiEnvironment + 'static>(
     // Route to provider with tool support check
     if capabilities.tools {
         match provider_id.as_str() {
+            // User-defined OpenAI-compatible endpoints
+            _ if is_custom_provider => {
+                let client = create_openai_compatible_client(api_key, &provider_id, provider_url)?;
+                build_with_tools_and_stream!(client, None::<serde_json::Value>)
+            }
             "anthropic" => {
                 let client = create_anthropic_client(api_key, &provider_id, provider_url)?;
                 build_with_tools_and_stream!(
@@ -937,6 +1165,10 @@ async fn spawn_chat_stream<E: AiEnvironment + 'static>(
         }
     } else {
         match provider_id.as_str() {
+            _ if is_custom_provider => {
+                let client = create_openai_compatible_client(api_key, &provider_id, provider_url)?;
+                build_without_tools_and_stream!(client, None::<serde_json::Value>)
+            }
             "anthropic" => {
                 let client = create_anthropic_client(api_key, &provider_id, provider_url)?;
                 build_without_tools_and_stream!(
@@ -1026,7 +1258,7 @@ fn create_groq_client(
 /// Create OpenAI client using Completions API (not Responses API).
 /// Responses API has issues with reasoning items in multi-turn conversations.
 /// See: https://community.openai.com/t/error-badrequesterror-400-item-of-type-reasoning-was-provided-without-its-required-following-item/1303809
-fn create_openai_client(
+pub(crate) fn create_openai_client(
     api_key: Option<String>,
     provider_id: &str,
     provider_url: Option<String>,
@@ -1041,6 +1273,25 @@ fn create_openai_client(
         .map_err(|e| AiError::Provider(e.to_string()))
 }
 
+/// Create a client for a user-defined OpenAI-compatible endpoint (LM Studio, vLLM,
+/// llama.cpp server, LocalAI). Local servers usually run without authentication,
+/// so the API key is optional.
+pub(crate) fn create_openai_compatible_client(
+    api_key: Option<String>,
+    provider_id: &str,
+    provider_url: Option<String>,
+) -> Result<openai::CompletionsClient<HttpClient>, AiError> {
+    let url = provider_url.ok_or_else(|| {
+        AiError::InvalidInput(format!("Base URL is not configured for {}", provider_id))
+    })?;
+    let key = api_key.unwrap_or_default();
+    openai::CompletionsClient::builder()
+        .api_key(&key)
+        .base_url(&url)
+        .build()
+        .map_err(|e| AiError::Provider(e.to_string()))
+}
+
 fn create_openrouter_client(
     api_key: Option<String>,
     provider_id: &str,
@@ -1291,9 +1542,15 @@ async fn stream_agent_response<M: CompletionModel + 'static, E: AiEnvironment +
     run_id: String,
     message_id: String,
     title_ctx: TitleContext<E>,
+    redaction: Arc<RedactionSession>,
+    turn_usage: Arc<TurnUsage>,
+    max_tool_rounds: usize,
 ) -> Result<(), AiError> {
-    // Start multi-turn streaming (up to 6 tool rounds)
-    let mut stream = agent.stream_chat(prompt, history).multi_turn(6).await;
+    // Start multi-turn streaming (up to max_tool_rounds tool rounds)
+    let mut stream = agent
+        .stream_chat(prompt, history)
+        .multi_turn(max_tool_rounds)
+        .await;
 
     // Generate/refine title concurrently so it can update the UI during streaming.
     let should_attempt_title = title_ctx.is_new_thread
@@ -1314,6 +1571,7 @@ async fn stream_agent_response<M: CompletionModel + 'static, E: AiEnvironment +
         let provider_id_bg = title_ctx.provider_id.clone();
         let model_id_bg = title_ctx.model_id.clone();
         let initial_title_bg = title_ctx.initial_title.clone();
+        let redaction_bg = redaction.clone();
 
         tokio::spawn(async move {
             debug!("Generating title for thread {} (concurrent)", thread_id_bg);
@@ -1321,6 +1579,7 @@ async fn stream_agent_response<M: CompletionModel + 'static, E: AiEnvironment +
             let new_title = title_gen
                 .generate_title(&user_message_bg, &provider_id_bg, &model_id_bg)
                 .await;
+            let new_title = redaction_bg.rehydrate(&new_title);
 
             let next_title = new_title.trim();
             if next_title.is_empty() {
@@ -1374,12 +1633,20 @@ async fn stream_agent_response<M: CompletionModel + 'static, E: AiEnvironment +
     // Parser for <think> tags (fallback for models that don't use native thinking API)
     let mut think_parser = ThinkTagParser::default();
 
+    // Restore redacted names in streamed text (no-op when redaction is off)
+    let mut text_rehydrator = StreamRehydrator::new(redaction.clone());
+    let mut reasoning_rehydrator = StreamRehydrator::new(redaction.clone());
+
+    // Token usage reported with the final response, persisted on the message
+    let mut message_usage: Option<MessageUsage> = None;
+
     while let Some(chunk) = stream.next().await {
         match chunk {
             // Text streaming - parse for <think> tags as fallback
             Ok(MultiTurnStreamItem::StreamAssistantItem(StreamedAssistantContent::Text(
                 Text { text },
             ))) => {
+                let text = text_rehydrator.process(&text);
                 if !text.is_empty() {
                     // Parse <think> tags and emit ordered segments (models should not think if disabled via API)
                     for segment in think_parser.process(&text) {
@@ -1431,7 +1698,7 @@ async fn stream_agent_response<M: CompletionModel + 'static, E: AiEnvironment +
                 Reasoning { reasoning, .. },
             ))) => {
                 if !reasoning.is_empty() {
-                    let reasoning_text = reasoning.join(" ");
+                    let reasoning_text = redaction.rehydrate(&reasoning.join(" "));
                     // Flush text before reasoning to preserve order
                     if !accumulated_text.is_empty() {
                         content_parts.push(ChatMessagePart::Text {
@@ -1456,6 +1723,7 @@ async fn stream_agent_response<M: CompletionModel + 'static, E: AiEnvironment +
             Ok(MultiTurnStreamItem::StreamAssistantItem(
                 StreamedAssistantContent::ReasoningDelta { reasoning, .. },
             )) => {
+                let reasoning = reasoning_rehydrator.process(&reasoning);
                 if !reasoning.is_empty() {
                     // Flush text before reasoning to preserve order
                     if !accumulated_text.is_empty() {
@@ -1480,6 +1748,32 @@ async fn stream_agent_response<M: CompletionModel + 'static, E: AiEnvironment +
                 tool_call: RigToolCall { id, function, .. },
                 ..
             })) => {
+                // Release text held back for re-hydration before the tool call
+                let pending_reasoning = reasoning_rehydrator.flush();
+                if !pending_reasoning.is_empty() {
+                    accumulated_reasoning.push_str(&pending_reasoning);
+                    tx.send(AiStreamEvent::reasoning_delta(
+                        &thread_id,
+                        &run_id,
+                        &message_id,
+                        &pending_reasoning,
+                    ))
+                    .await
+                    .map_err(|e| AiError::Internal(e.to_string()))?;
+                }
+                let pending_text = text_rehydrator.flush();
+                if !pending_text.is_empty() {
+                    accumulated_text.push_str(&pending_text);
+                    tx.send(AiStreamEvent::text_delta(
+                        &thread_id,
+                        &run_id,
+                        &message_id,
+                        &pending_text,
+                    ))
+                    .await
+                    .map_err(|e| AiError::Internal(e.to_string()))?;
+                }
+
                 // Flush accumulated reasoning and text BEFORE the tool call to preserve order
                 if !accumulated_reasoning.is_empty() {
                     content_parts.push(ChatMessagePart::Reasoning {
@@ -1493,7 +1787,8 @@ async fn stream_agent_response<M: CompletionModel + 'static, E: AiEnvironment +
                 }
 
                 let args: serde_json::Value =
-                    serde_json::from_str(&function.arguments.to_string()).unwrap_or_default();
+                    serde_json::from_str(&redaction.rehydrate(&function.arguments.to_string()))
+                        .unwrap_or_default();
 
                 content_parts.push(ChatMessagePart::ToolCall {
                     tool_call_id: id.clone(),
@@ -1547,6 +1842,11 @@ async fn stream_agent_response<M: CompletionModel + 'static, E: AiEnvironment +
                     .collect::<Vec<_>>()
                     .join("\n");
 
+                // The UI and the stored message get the unredacted output
+                let result_text = redaction
+                    .original_output(&result_text)
+                    .unwrap_or_else(|| redaction.rehydrate(&result_text));
+
                 // Parse result as JSON for structured data
                 let data: serde_json::Value =
                     serde_json::from_str(&result_text).unwrap_or(serde_json::json!(result_text));
@@ -1579,7 +1879,18 @@ async fn stream_agent_response<M: CompletionModel + 'static, E: AiEnvironment +
             // may not stream text deltas for tool-calling responses, and Ollama/DeepSeek may
             // send reasoning natively without streaming text deltas)
             Ok(MultiTurnStreamItem::FinalResponse(final_response)) => {
-                let response_text = final_response.response().to_string();
+                // Usage is aggregated across all tool rounds of the turn
+                let usage = final_response.usage();
+                if usage.input_tokens > 0 || usage.output_tokens > 0 {
+                    message_usage = Some(MessageUsage {
+                        provider_id: title_ctx.provider_id.clone(),
+                        model_id: title_ctx.model_id.clone(),
+                        prompt_tokens: u32::try_from(usage.input_tokens).unwrap_or(u32::MAX),
+                        completion_tokens: u32::try_from(usage.output_tokens).unwrap_or(u32::MAX),
+                    });
+                }
+
+                let response_text = redaction.rehydrate(final_response.response());
                 // Use trim() to handle cases where only whitespace was accumulated
                 if accumulated_text.trim().is_empty() && !response_text.trim().is_empty() {
                     accumulated_text = response_text.clone();
@@ -1614,8 +1925,25 @@ async fn stream_agent_response<M: CompletionModel + 'static, E: AiEnvironment +
         }
     }
 
+    // Flush text held back for re-hydration, then the think parser
+    let pending_reasoning = reasoning_rehydrator.flush();
+    if !pending_reasoning.is_empty() {
+        accumulated_reasoning.push_str(&pending_reasoning);
+        tx.send(AiStreamEvent::reasoning_delta(
+            &thread_id,
+            &run_id,
+            &message_id,
+            &pending_reasoning,
+        ))
+        .await
+        .map_err(|e| AiError::Internal(e.to_string()))?;
+    }
+    let pending_text = text_rehydrator.flush();
+    let mut remaining_segments = think_parser.process(&pending_text);
+    remaining_segments.extend(think_parser.flush());
+
     // Flush any remaining buffered content from the think parser
-    for segment in think_parser.flush() {
+    for segment in remaining_segments {
         match segment {
             ParsedThinkSegment::Text(remaining_text) if !remaining_text.is_empty() => {
                 if !accumulated_reasoning.is_empty() {
@@ -1673,6 +2001,10 @@ async fn stream_agent_response<M: CompletionModel + 'static, E: AiEnvironment +
     // Build final message
     let mut final_message = ChatMessage::assistant_with_id(&message_id, &thread_id);
     final_message.content = ChatMessageContent::new(content_parts);
+    // Tools that call the model themselves (statement extraction) add to the turn
+    final_message.usage =
+        turn_usage.apply(message_usage, &title_ctx.provider_id, &title_ctx.model_id);
+    let usage_stats = final_message.usage.as_ref().map(MessageUsage::to_stats);
 
     // Save assistant message to repository after stream completes
     if let Err(e) = repo.create_message(final_message.clone()).await {
@@ -1685,7 +2017,7 @@ async fn stream_agent_response<M: CompletionModel + 'static, E: AiEnvironment +
         &thread_id,
         &run_id,
         final_message,
-        None,
+        usage_stats,
     ))
     .await
     .map_err(|e| AiError::Internal(e.to_string()))?;
@@ -1693,6 +2025,84 @@ async fn stream_agent_response<M: CompletionModel + 'static, E: AiEnvironment +
     Ok(())
 }
 
+/// Run an agent through the same streaming path as `send_message`, without
+/// title generation or redaction, and collect the emitted events.
+///
+/// Used by the offline eval harness with replayed or scripted models.
+#[cfg(test)]
+pub(crate) async fn collect_agent_events<M, E>(
+    env: Arc<E>,
+    agent: Agent<M>,
+    user_message: &str,
+    provider_id: &str,
+    model_id: &str,
+    max_tool_rounds: usize,
+) -> (Vec<AiStreamEvent>, Result<(), AiError>)
+where
+    M: CompletionModel + 'static,
+    E: AiEnvironment + 'static,
+{
+    let thread_id = "eval-thread".to_string();
+    let run_id = Uuid::new_v4().to_string();
+    let message_id = Uuid::new_v4().to_string();
+
+    let (tx, mut rx) = mpsc::channel::<AiStreamEvent>(100);
+    let collector = tokio::spawn(async move {
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+        events
+    });
+
+    let _ = tx
+        .send(AiStreamEvent::system(&thread_id, &run_id, &message_id))
+        .await;
+
+    let title_ctx = TitleContext {
+        env: env.clone(),
+        current_title: Some("Eval".to_string()),
+        initial_title: None,
+        is_new_thread: false,
+        user_message: user_message.to_string(),
+        provider_id: provider_id.to_string(),
+        model_id: model_id.to_string(),
+    };
+    let result = stream_agent_response(
+        agent,
+        build_user_prompt(user_message, &[]),
+        Vec::new(),
+        tx.clone(),
+        env.chat_repository(),
+        thread_id.clone(),
+        run_id.clone(),
+        message_id,
+        title_ctx,
+        Arc::new(RedactionSession::disabled()),
+        Arc::new(TurnUsage::default()),
+        max_tool_rounds,
+    )
+    .await
+    .map_err(|e| remap_provider_error(provider_id, model_id, e));
+
+    // Mirror the terminal error event sent by send_message's task
+    if let Err(e) = &result {
+        let _ = tx
+            .send(AiStreamEvent::error(
+                &thread_id,
+                &run_id,
+                None,
+                e.code(),
+                &e.to_string(),
+            ))
+            .await;
+    }
+    drop(tx);
+
+    let events = collector.await.unwrap_or_default();
+    (events, result)
+}
+
 // ============================================================================
 // History Building (for future use)
 // ============================================================================
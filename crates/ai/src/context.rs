@@ -0,0 +1,446 @@
+//! Token-budgeted thread history.
+//!
+//! Replaying a whole thread eventually exceeds the model's context window (and
+//! gets expensive), so history is assembled against a token budget derived from
+//! the model's context size:
+//! - the most recent turns are kept verbatim, newest first, until the budget runs out
+//! - tool results the user pinned are always kept
+//! - older turns are folded into a rolling summary stored on the thread,
+//!   generated by the provider's title model
+
+use log::warn;
+use std::sync::Arc;
+
+use crate::env::AiEnvironment;
+use crate::error::AiError;
+use crate::providers::ProviderService;
+use crate::redaction::RedactionSession;
+use crate::title_generator::{prompt_model, PromptReply};
+use crate::tools::constants::{
+    HISTORY_CONTEXT_SHARE_PCT, MAX_HISTORY_TOKENS, MAX_SUMMARY_INPUT_TOKENS, MAX_SUMMARY_TOKENS,
+};
+use crate::types::{
+    ChatMessage, ChatMessagePart, ChatMessageRole, SimpleChatMessage, ThreadSummary,
+};
+
+/// Tool result meta key marking a result the user pinned to the thread context.
+pub const PINNED_META_KEY: &str = "pinned";
+
+/// Rough characters-per-token ratio for English text and JSON.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Per-message overhead for role markers and separators.
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// Estimate the token count of a message's text.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN) + MESSAGE_OVERHEAD_TOKENS
+}
+
+/// Token budget for thread history given the model's context window.
+pub fn history_token_budget(context_window: u32) -> usize {
+    (context_window as usize * HISTORY_CONTEXT_SHARE_PCT / 100).min(MAX_HISTORY_TOKENS)
+}
+
+// ============================================================================
+// History Planning
+// ============================================================================
+
+/// A tool result kept in context because the user pinned it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PinnedToolResult {
+    pub tool_name: String,
+    /// Tool output as JSON text.
+    pub output: String,
+}
+
+/// History assembled for one run.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryPlan {
+    /// Turns sent verbatim, oldest first.
+    pub messages: Vec<SimpleChatMessage>,
+    /// Summary of the turns before `messages`.
+    pub summary: Option<ThreadSummary>,
+    /// Older turns that no longer fit and are not in the summary yet, oldest first.
+    pub overflow: Vec<SimpleChatMessage>,
+    /// ID of the newest overflowed message.
+    pub overflow_through_id: Option<String>,
+    /// Pinned tool results, oldest first.
+    pub pinned_results: Vec<PinnedToolResult>,
+}
+
+/// Assemble history within `budget_tokens`.
+///
+/// The thread summary only applies while the message it was built through is
+/// still part of `messages`; editing an earlier message branches off before it.
+pub fn plan_history(
+    messages: &[ChatMessage],
+    summary: Option<&ThreadSummary>,
+    budget_tokens: usize,
+) -> HistoryPlan {
+    let covered = summary.and_then(|s| {
+        messages
+            .iter()
+            .position(|m| m.id == s.through_message_id)
+            .map(|pos| (s.clone(), pos))
+    });
+    let start = covered.as_ref().map(|(_, pos)| pos + 1).unwrap_or(0);
+    let summary = covered.map(|(s, _)| s);
+
+    let mut remaining = budget_tokens.saturating_sub(
+        summary
+            .as_ref()
+            .map(|s| estimate_tokens(&s.content))
+            .unwrap_or(0),
+    );
+
+    // Pinned results may use up to half of the remaining budget, newest first
+    let mut pinned_budget = remaining / 2;
+    let mut pinned = Vec::new();
+    for result in messages
+        .iter()
+        .rev()
+        .flat_map(|m| collect_pinned_results(m).into_iter().rev())
+    {
+        let tokens = estimate_tokens(&result.output);
+        if tokens > pinned_budget {
+            continue;
+        }
+        pinned_budget -= tokens;
+        remaining -= tokens;
+        pinned.push(result);
+    }
+    pinned.reverse();
+
+    // Keep a contiguous window of recent turns; everything older overflows
+    let mut kept = Vec::new();
+    let mut overflow = Vec::new();
+    let mut overflow_through_id: Option<String> = None;
+    for msg in messages[start..].iter().rev() {
+        let Some(simple) = to_simple_message(msg) else {
+            continue;
+        };
+        let tokens = estimate_tokens(&simple.content);
+        if overflow_through_id.is_none() && tokens <= remaining {
+            remaining -= tokens;
+            kept.push(simple);
+        } else {
+            overflow_through_id.get_or_insert_with(|| msg.id.clone());
+            overflow.push(simple);
+        }
+    }
+    kept.reverse();
+    overflow.reverse();
+
+    HistoryPlan {
+        messages: kept,
+        summary,
+        overflow,
+        overflow_through_id,
+        pinned_results: pinned,
+    }
+}
+
+fn to_simple_message(msg: &ChatMessage) -> Option<SimpleChatMessage> {
+    let text = msg.content.get_text_content();
+    if text.is_empty() {
+        return None;
+    }
+    match msg.role {
+        ChatMessageRole::User => Some(SimpleChatMessage::user(&text)),
+        ChatMessageRole::Assistant => Some(SimpleChatMessage::assistant(&text)),
+        _ => None,
+    }
+}
+
+fn collect_pinned_results(msg: &ChatMessage) -> Vec<PinnedToolResult> {
+    let parts = &msg.content.parts;
+    parts
+        .iter()
+        .filter_map(|part| match part {
+            ChatMessagePart::ToolResult {
+                tool_call_id,
+                success: true,
+                data,
+                meta,
+                ..
+            } if meta
+                .get(PINNED_META_KEY)
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false) =>
+            {
+                let tool_name = parts
+                    .iter()
+                    .find_map(|p| match p {
+                        ChatMessagePart::ToolCall {
+                            tool_call_id: id,
+                            name,
+                            ..
+                        } if id == tool_call_id => Some(name.clone()),
+                        _ => None,
+                    })
+                    .unwrap_or_else(|| "tool".to_string());
+                Some(PinnedToolResult {
+                    tool_name,
+                    output: data.to_string(),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Render the summary and pinned tool results as system prompt sections.
+pub fn render_context_sections(plan: &HistoryPlan, redaction: &RedactionSession) -> String {
+    let mut sections = String::new();
+
+    if let Some(summary) = &plan.summary {
+        sections.push_str(&format!(
+            "\n\n## Earlier in This Conversation\n\
+            Older turns were summarised to fit the context window:\n{}",
+            redaction.redact_text(&summary.content)
+        ));
+    }
+
+    if !plan.pinned_results.is_empty() {
+        sections.push_str(
+            "\n\n## Pinned Tool Results\n\
+            The user pinned these results to keep them in context. Use them instead of \
+            calling the tool again unless newer data is needed.",
+        );
+        for result in &plan.pinned_results {
+            sections.push_str(&format!(
+                "\n\n### {}\n{}",
+                result.tool_name,
+                redaction.redact_tool_output(&result.output)
+            ));
+        }
+    }
+
+    sections
+}
+
+// ============================================================================
+// Summarisation
+// ============================================================================
+
+/// Fold `turns` into the previous summary with the provider's title model,
+/// falling back to the chat model.
+///
+/// The reply's usage names the model that wrote the summary, so the caller
+/// can record it.
+pub(crate) async fn summarize_turns<E: AiEnvironment>(
+    env: &Arc<E>,
+    provider_id: &str,
+    chat_model_id: &str,
+    previous_summary: Option<&str>,
+    turns: &[SimpleChatMessage],
+) -> Result<PromptReply, AiError> {
+    let prompt = build_summary_prompt(previous_summary, turns);
+    let title_model = ProviderService::new(env.clone())
+        .get_title_model(provider_id)
+        .unwrap_or_else(|| chat_model_id.to_string());
+
+    let reply =
+        match prompt_model(env, provider_id, &title_model, &prompt, MAX_SUMMARY_TOKENS).await {
+            Ok(response) => response,
+            Err(e) if title_model != chat_model_id => {
+                warn!(
+                    "Summary generation failed with title model '{}': {}",
+                    title_model, e
+                );
+                prompt_model(env, provider_id, chat_model_id, &prompt, MAX_SUMMARY_TOKENS).await?
+            }
+            Err(e) => return Err(e),
+        };
+
+    let summary = reply.text.trim();
+    if summary.is_empty() {
+        return Err(AiError::Internal("Generated summary is empty".into()));
+    }
+    Ok(PromptReply {
+        text: summary.to_string(),
+        usage: reply.usage,
+    })
+}
+
+fn build_summary_prompt(previous_summary: Option<&str>, turns: &[SimpleChatMessage]) -> String {
+    format!(
+        "You keep a running summary of a conversation between a user and a portfolio \
+assistant. Older turns are being removed from the conversation, so the summary must \
+keep everything later turns may rely on.\n\
+Rules:\n\
+- Keep figures, dates, tickers, account names and conclusions reached\n\
+- Keep the user's goals, preferences and open questions\n\
+- Plain text, at most 250 words\n\
+- Return ONLY the updated summary\n\n\
+Current summary:\n{}\n\n\
+Turns to add:\n{}\n\n\
+Updated summary:",
+        previous_summary.unwrap_or("(none)"),
+        build_transcript(turns, MAX_SUMMARY_INPUT_TOKENS)
+    )
+}
+
+/// Transcript of the most recent turns that fit `max_tokens`, oldest first.
+fn build_transcript(turns: &[SimpleChatMessage], max_tokens: usize) -> String {
+    let mut remaining = max_tokens;
+    let mut lines = Vec::new();
+    for turn in turns.iter().rev() {
+        let speaker = if turn.role.eq_ignore_ascii_case("user") {
+            "User"
+        } else {
+            "Assistant"
+        };
+        let tokens = estimate_tokens(&turn.content);
+        if tokens <= remaining {
+            remaining -= tokens;
+            lines.push(format!("{}: {}", speaker, turn.content));
+        } else {
+            // Keep the start of the turn that crosses the budget, then stop
+            let max_chars = remaining.saturating_sub(MESSAGE_OVERHEAD_TOKENS) * CHARS_PER_TOKEN;
+            if max_chars > 0 {
+                let truncated: String = turn.content.chars().take(max_chars).collect();
+                lines.push(format!("{}: {}...", speaker, truncated));
+            }
+            break;
+        }
+    }
+    lines.reverse();
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ChatMessageContent;
+    use chrono::Utc;
+    use std::collections::HashMap;
+
+    fn message(id: &str, role: ChatMessageRole, text: &str) -> ChatMessage {
+        ChatMessage {
+            id: id.to_string(),
+            thread_id: "thread-1".to_string(),
+            role,
+            content: ChatMessageContent::text(text),
+            created_at: Utc::now(),
+            usage: None,
+        }
+    }
+
+    /// Ten alternating turns of ~100 tokens each.
+    fn long_thread() -> Vec<ChatMessage> {
+        (0..10)
+            .map(|i| {
+                let role = if i % 2 == 0 {
+                    ChatMessageRole::User
+                } else {
+                    ChatMessageRole::Assistant
+                };
+                message(&format!("m{}", i), role, &"x".repeat(384))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), MESSAGE_OVERHEAD_TOKENS);
+        assert_eq!(
+            estimate_tokens(&"a".repeat(400)),
+            100 + MESSAGE_OVERHEAD_TOKENS
+        );
+    }
+
+    #[test]
+    fn test_history_token_budget() {
+        assert_eq!(history_token_budget(8_192), 3_276);
+        assert_eq!(history_token_budget(200_000), MAX_HISTORY_TOKENS);
+    }
+
+    #[test]
+    fn test_plan_keeps_recent_turns_and_overflows_older() {
+        let plan = plan_history(&long_thread(), None, 450);
+
+        assert_eq!(plan.messages.len(), 4);
+        assert_eq!(plan.overflow.len(), 6);
+        assert_eq!(plan.overflow_through_id.as_deref(), Some("m5"));
+        assert_eq!(plan.messages[0].role, "user");
+    }
+
+    #[test]
+    fn test_plan_skips_turns_covered_by_summary() {
+        let summary = ThreadSummary {
+            content: "User compared two ETFs.".to_string(),
+            through_message_id: "m5".to_string(),
+            updated_at: Utc::now(),
+        };
+
+        let plan = plan_history(&long_thread(), Some(&summary), 10_000);
+
+        assert_eq!(plan.summary, Some(summary));
+        assert_eq!(plan.messages.len(), 4);
+        assert!(plan.overflow.is_empty());
+        assert!(plan.overflow_through_id.is_none());
+    }
+
+    #[test]
+    fn test_plan_ignores_summary_past_edited_branch() {
+        let mut messages = long_thread();
+        messages.truncate(4);
+        let summary = ThreadSummary {
+            content: "Covers later messages".to_string(),
+            through_message_id: "m7".to_string(),
+            updated_at: Utc::now(),
+        };
+
+        let plan = plan_history(&messages, Some(&summary), 10_000);
+
+        assert!(plan.summary.is_none());
+        assert_eq!(plan.messages.len(), 4);
+    }
+
+    #[test]
+    fn test_plan_keeps_pinned_tool_results() {
+        let mut messages = long_thread();
+        messages[1].content.parts.extend([
+            ChatMessagePart::ToolCall {
+                tool_call_id: "call-1".to_string(),
+                name: "get_holdings".to_string(),
+                arguments: serde_json::json!({}),
+            },
+            ChatMessagePart::ToolResult {
+                tool_call_id: "call-1".to_string(),
+                success: true,
+                data: serde_json::json!({ "count": 3 }),
+                meta: HashMap::from([(PINNED_META_KEY.to_string(), serde_json::json!(true))]),
+                error: None,
+            },
+        ]);
+
+        let plan = plan_history(&messages, None, 450);
+
+        // The pinned result survives even though its turn overflowed
+        assert_eq!(
+            plan.pinned_results,
+            vec![PinnedToolResult {
+                tool_name: "get_holdings".to_string(),
+                output: r#"{"count":3}"#.to_string(),
+            }]
+        );
+        assert_eq!(plan.overflow_through_id.as_deref(), Some("m5"));
+    }
+
+    #[test]
+    fn test_transcript_keeps_most_recent_turns() {
+        let turns = vec![
+            SimpleChatMessage::user(&"a".repeat(400)),
+            SimpleChatMessage::assistant("Latest answer"),
+        ];
+
+        let transcript = build_transcript(&turns, 60);
+
+        assert!(transcript.ends_with("Assistant: Latest answer"));
+        assert!(transcript.starts_with("User: aaa"));
+        assert!(transcript.contains("..."));
+    }
+}
@@ -0,0 +1,1018 @@
+//! Scheduled portfolio digest.
+//!
+//! Writes a weekly summary into a dedicated thread without a tool-calling loop:
+//! a fixed set of read-only tools is called directly, their output is reduced
+//! to a small [`DigestData`] document, and the configured provider turns it
+//! into prose using the digest prompt template. When no provider is configured,
+//! or generation fails, a deterministic template renders the same data.
+//!
+//! A snapshot of prices, allocation and goal progress is kept in app settings
+//! so the next digest can report what changed since this one.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use log::{debug, warn};
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use wealthfolio_core::constants::PORTFOLIO_TOTAL_ACCOUNT_ID;
+
+use crate::env::AiEnvironment;
+use crate::error::AiError;
+use crate::prompt_template_service::{
+    build_digest_run_config, PromptTemplateService, PromptTemplateServiceTrait, DIGEST_TEMPLATE_ID,
+    DIGEST_TEMPLATE_VERSION,
+};
+use crate::providers::ProviderService;
+use crate::redaction::RedactionSession;
+use crate::title_generator::{prompt_model, PromptReply};
+use crate::tools::activities::{ActivityDto, SearchActivitiesArgs};
+use crate::tools::allocation::{AllocationDto, GetAssetAllocationArgs};
+use crate::tools::constants::MAX_ACTIVITIES_ROWS;
+use crate::tools::goals::{GetGoalsArgs, GoalDto};
+use crate::tools::health::{GetHealthIssuesArgs, HealthIssueDto};
+use crate::tools::holdings::{GetHoldingsArgs, HoldingDto};
+use crate::tools::{
+    GetAssetAllocationTool, GetGoalsTool, GetHealthIssuesTool, GetHoldingsTool,
+    SearchActivitiesTool,
+};
+use crate::types::{
+    ChatMessage, ChatMessageContent, ChatMessagePart, ChatThread, ChatThreadConfig,
+};
+use crate::usage::UsageService;
+
+/// Tag marking digest threads in the thread list.
+pub const DIGEST_THREAD_TAG: &str = "digest";
+
+/// Settings key for the digest schedule and comparison snapshot.
+pub const AI_DIGEST_STATE_KEY: &str = "ai_digest_state";
+
+/// Days between digests.
+pub const DIGEST_INTERVAL_DAYS: i64 = 7;
+
+/// Max tokens for the generated digest.
+const MAX_DIGEST_TOKENS: u32 = 1200;
+
+/// Number of holdings listed as notable movers.
+const MAX_MOVERS: usize = 5;
+
+/// Allocation shifts smaller than this (percentage points) are not called out.
+const DRIFT_THRESHOLD_PCT: f64 = 1.0;
+
+/// Activity types counted as income received.
+const INCOME_ACTIVITY_TYPES: &[&str] = &["DIVIDEND", "INTEREST"];
+
+// ============================================================================
+// Digest Types
+// ============================================================================
+
+/// What a mover's change is measured against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MoverBasis {
+    /// Unit price change since the previous digest.
+    PreviousDigest,
+    /// Latest daily change, used when there is no previous price.
+    Day,
+}
+
+/// A holding with a notable price move.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestMover {
+    pub symbol: String,
+    pub name: Option<String>,
+    pub change_pct: f64,
+    pub basis: MoverBasis,
+    pub market_value: f64,
+}
+
+/// A dividend or interest payment received during the period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestIncome {
+    pub date: String,
+    pub activity_type: String,
+    pub symbol: Option<String>,
+    pub amount: Option<f64>,
+    pub currency: String,
+}
+
+/// Current weight of an asset class and its shift since the previous digest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestAllocation {
+    pub category: String,
+    pub percentage: f64,
+    /// Percentage points; None on the first digest.
+    pub change_pct: Option<f64>,
+}
+
+/// An open data-quality issue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestHealthIssue {
+    pub severity: String,
+    pub title: String,
+    pub message: String,
+}
+
+/// Progress on a goal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestGoal {
+    pub title: String,
+    pub progress_percent: f64,
+    /// Percentage points; None when the goal is new since the previous digest.
+    pub progress_change_pct: Option<f64>,
+    pub target_amount: f64,
+    pub current_amount: f64,
+    pub is_achieved: bool,
+}
+
+/// Everything a digest reports on. This is the only data sent to the provider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestData {
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub currency: String,
+    pub total_value: f64,
+    /// Change since the previous digest; None on the first digest.
+    pub value_change_pct: Option<f64>,
+    pub movers: Vec<DigestMover>,
+    pub income: Vec<DigestIncome>,
+    pub allocation: Vec<DigestAllocation>,
+    pub health_issues: Vec<DigestHealthIssue>,
+    pub goals: Vec<DigestGoal>,
+}
+
+/// Values kept from the previous digest to report changes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestSnapshot {
+    pub total_value: f64,
+    /// Unit price in base currency, keyed by symbol.
+    #[serde(default)]
+    pub prices: HashMap<String, f64>,
+    /// Asset class weight in percent, keyed by category name.
+    #[serde(default)]
+    pub allocation: HashMap<String, f64>,
+    /// Progress in percent, keyed by goal ID.
+    #[serde(default)]
+    pub goals: HashMap<String, f64>,
+}
+
+/// Digest schedule state stored in app settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestState {
+    #[serde(default)]
+    pub last_generated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_thread_id: Option<String>,
+    #[serde(default)]
+    pub snapshot: Option<DigestSnapshot>,
+}
+
+/// Tool output gathered for one digest. A section is None when its tool failed.
+struct DigestSources {
+    currency: String,
+    holdings: Option<Vec<HoldingDto>>,
+    income: Vec<ActivityDto>,
+    allocation: Option<Vec<AllocationDto>>,
+    health_issues: Vec<HealthIssueDto>,
+    goals: Option<Vec<GoalDto>>,
+}
+
+// ============================================================================
+// Digest Service
+// ============================================================================
+
+/// Generates the scheduled portfolio digest.
+pub struct DigestService<E: AiEnvironment> {
+    env: Arc<E>,
+}
+
+impl<E: AiEnvironment + 'static> DigestService<E> {
+    /// Create a new digest service.
+    pub fn new(env: Arc<E>) -> Self {
+        Self { env }
+    }
+
+    /// Generate a digest when none was written in the last week.
+    ///
+    /// Returns the new digest thread, or None when it is not due yet or the
+    /// portfolio has nothing to report.
+    pub async fn run_if_due(&self, now: DateTime<Utc>) -> Result<Option<ChatThread>, AiError> {
+        let state = self.load_state();
+        if let Some(last) = state.last_generated_at {
+            if now - last < Duration::days(DIGEST_INTERVAL_DAYS) {
+                debug!("Portfolio digest not due (last generated {})", last);
+                return Ok(None);
+            }
+        }
+
+        let period_end = now.date_naive();
+        let period_start = state
+            .last_generated_at
+            .map(|t| t.date_naive())
+            .unwrap_or(period_end - Duration::days(DIGEST_INTERVAL_DAYS));
+
+        let sources = self.gather(period_start, period_end).await;
+        let asset_names: Vec<String> = sources
+            .holdings
+            .iter()
+            .flatten()
+            .filter_map(|h| h.name.clone())
+            .collect();
+        let (data, snapshot) =
+            build_digest(period_start, period_end, sources, state.snapshot.as_ref());
+        if data.total_value <= 0.0 && data.goals.is_empty() {
+            debug!("Skipping portfolio digest: no holdings or goals to report");
+            return Ok(None);
+        }
+
+        let mut thread = ChatThread::new();
+        thread.title = Some(format!(
+            "Portfolio digest · {}",
+            period_end.format("%b %-d, %Y")
+        ));
+        thread.tags = vec![DIGEST_THREAD_TAG.to_string()];
+
+        let default_model = ProviderService::new(self.env.clone()).get_default_model();
+        let generated = match &default_model {
+            Some((provider_id, model_id)) => {
+                match self
+                    .generate_text(&thread.id, provider_id, model_id, &data, &asset_names)
+                    .await
+                {
+                    Ok(reply) => Some(reply),
+                    Err(e) => {
+                        warn!(
+                            "Digest generation with {}/{} failed, using fallback: {}",
+                            provider_id, model_id, e
+                        );
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+        let mut usage = None;
+        let text = match (generated, default_model) {
+            (Some(reply), Some((provider_id, model_id))) => {
+                thread.config = Some(ChatThreadConfig::new(
+                    &provider_id,
+                    &model_id,
+                    DIGEST_TEMPLATE_ID,
+                    DIGEST_TEMPLATE_VERSION,
+                ));
+                usage = reply.usage;
+                reply.text
+            }
+            _ => render_fallback(&data),
+        };
+
+        let repo = self.env.chat_repository();
+        let thread = repo.create_thread(thread).await?;
+        repo.add_tag(&thread.id, DIGEST_THREAD_TAG).await?;
+
+        let mut message = ChatMessage::assistant(&thread.id);
+        message.content = ChatMessageContent::new(vec![ChatMessagePart::Text { content: text }]);
+        message.usage = usage;
+        repo.create_message(message).await?;
+
+        self.save_state(&DigestState {
+            last_generated_at: Some(now),
+            last_thread_id: Some(thread.id.clone()),
+            snapshot: Some(snapshot),
+        })
+        .await?;
+
+        Ok(Some(thread))
+    }
+
+    /// Call the read-only tools the digest reports on. Failures leave their
+    /// section empty rather than skipping the digest.
+    async fn gather(&self, period_start: NaiveDate, period_end: NaiveDate) -> DigestSources {
+        let currency = self.env.base_currency();
+
+        let holdings = GetHoldingsTool::new(self.env.clone(), currency.clone())
+            .call(GetHoldingsArgs {
+                account_id: PORTFOLIO_TOTAL_ACCOUNT_ID.to_string(),
+                view_mode: "table".to_string(),
+            })
+            .await
+            .map(|output| output.holdings)
+            .map_err(|e| warn!("Digest: failed to load holdings: {}", e))
+            .ok();
+
+        let mut income = Vec::new();
+        let activities_tool = SearchActivitiesTool::new(self.env.clone());
+        for activity_type in INCOME_ACTIVITY_TYPES {
+            match activities_tool
+                .call(SearchActivitiesArgs {
+                    activity_type: Some(activity_type.to_string()),
+                    date_from: Some(period_start.to_string()),
+                    date_to: Some(period_end.to_string()),
+                    page_size: Some(MAX_ACTIVITIES_ROWS as i64),
+                    ..Default::default()
+                })
+                .await
+            {
+                Ok(output) => income.extend(output.activities),
+                Err(e) => warn!("Digest: failed to load {} activities: {}", activity_type, e),
+            }
+        }
+
+        let allocation = GetAssetAllocationTool::new(self.env.clone(), currency.clone())
+            .call(GetAssetAllocationArgs {
+                account_id: PORTFOLIO_TOTAL_ACCOUNT_ID.to_string(),
+                group_by: "class".to_string(),
+                taxonomy_id: None,
+                category_id: None,
+            })
+            .await
+            .map(|output| output.allocations)
+            .map_err(|e| warn!("Digest: failed to load allocation: {}", e))
+            .ok();
+
+        let health_issues = GetHealthIssuesTool::new(self.env.clone())
+            .call(GetHealthIssuesArgs {
+                min_severity: Some("WARNING".to_string()),
+            })
+            .await
+            .map(|output| output.issues)
+            .map_err(|e| warn!("Digest: failed to load health issues: {}", e))
+            .unwrap_or_default();
+
+        let goals = GetGoalsTool::new(self.env.clone())
+            .call(GetGoalsArgs {})
+            .await
+            .map(|output| output.goals)
+            .map_err(|e| warn!("Digest: failed to load goals: {}", e))
+            .ok();
+
+        DigestSources {
+            currency,
+            holdings,
+            income,
+            allocation,
+            health_issues,
+            goals,
+        }
+    }
+
+    /// Write the digest with the configured provider.
+    async fn generate_text(
+        &self,
+        thread_id: &str,
+        provider_id: &str,
+        model_id: &str,
+        data: &DigestData,
+        asset_names: &[String],
+    ) -> Result<PromptReply, AiError> {
+        // A blocking budget falls back to the template instead of spending more
+        UsageService::new(self.env.clone()).check_budget()?;
+
+        let templates =
+            PromptTemplateService::builtin().map_err(|e| AiError::Internal(e.to_string()))?;
+        let mut instructions = templates
+            .build_system_prompt(&build_digest_run_config(None))
+            .map_err(|e| AiError::Internal(e.to_string()))?;
+
+        let provider_service = ProviderService::new(self.env.clone());
+        let redaction_settings = provider_service.get_redaction_settings(provider_id);
+        let redaction = if redaction_settings.enabled {
+            RedactionSession::new(thread_id, redaction_settings)
+        } else {
+            RedactionSession::disabled()
+        };
+        if redaction.is_enabled() {
+            let accounts = self
+                .env
+                .account_service()
+                .list_accounts(None, None, None)
+                .unwrap_or_default();
+            register_digest_names(
+                &redaction,
+                accounts.iter().map(|a| a.name.as_str()),
+                asset_names,
+                data,
+            );
+            if let Some(note) = redaction.preamble_note() {
+                instructions.push_str(&note);
+            }
+        }
+
+        let data_json =
+            serde_json::to_string(data).map_err(|e| AiError::Internal(e.to_string()))?;
+        let prompt = format!(
+            "{}\n\nDIGEST DATA (JSON):\n{}",
+            instructions,
+            redaction.redact_tool_output(&data_json)
+        );
+
+        let reply =
+            prompt_model(&self.env, provider_id, model_id, &prompt, MAX_DIGEST_TOKENS).await?;
+        let text = redaction.rehydrate(reply.text.trim());
+        if text.is_empty() {
+            return Err(AiError::Provider(
+                "Provider returned an empty digest".into(),
+            ));
+        }
+        Ok(PromptReply {
+            text,
+            usage: reply.usage,
+        })
+    }
+
+    fn load_state(&self) -> DigestState {
+        self.env
+            .settings_service()
+            .get_setting_value(AI_DIGEST_STATE_KEY)
+            .ok()
+            .flatten()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    async fn save_state(&self, state: &DigestState) -> Result<(), AiError> {
+        let json = serde_json::to_string(state).map_err(|e| AiError::Internal(e.to_string()))?;
+        self.env
+            .settings_service()
+            .set_setting_value(AI_DIGEST_STATE_KEY, &json)
+            .await
+            .map_err(|e| AiError::Internal(e.to_string()))
+    }
+}
+
+// ============================================================================
+// Digest Assembly
+// ============================================================================
+
+/// Register every account, asset and goal name up front, as the chat path
+/// does, so they are also replaced where they appear in free text such as
+/// health issue messages.
+fn register_digest_names<'a>(
+    redaction: &RedactionSession,
+    account_names: impl IntoIterator<Item = &'a str>,
+    asset_names: &[String],
+    data: &DigestData,
+) {
+    redaction.register_account_names(account_names);
+    redaction.register_asset_names(asset_names.iter().map(String::as_str));
+    redaction.register_goal_names(data.goals.iter().map(|g| g.title.as_str()));
+}
+
+/// Reduce tool output to the digest document and the snapshot for next time.
+/// Sections whose tool failed carry the previous snapshot values forward.
+fn build_digest(
+    period_start: NaiveDate,
+    period_end: NaiveDate,
+    sources: DigestSources,
+    previous: Option<&DigestSnapshot>,
+) -> (DigestData, DigestSnapshot) {
+    let mut snapshot = previous.cloned().unwrap_or_default();
+
+    let holdings = sources.holdings.unwrap_or_default();
+    let total_value: f64 = holdings.iter().map(|h| h.market_value_base).sum();
+    let value_change_pct = previous
+        .map(|p| p.total_value)
+        .filter(|v| *v > 0.0)
+        .map(|v| (total_value / v - 1.0) * 100.0);
+
+    let prices: HashMap<String, f64> = holdings
+        .iter()
+        .filter(|h| h.quantity > 0.0)
+        .map(|h| (h.symbol.clone(), h.market_value_base / h.quantity))
+        .collect();
+    let mut movers: Vec<DigestMover> = holdings
+        .iter()
+        .filter_map(|h| {
+            let previous_price = previous
+                .and_then(|p| p.prices.get(&h.symbol))
+                .copied()
+                .filter(|p| *p > 0.0);
+            let (change_pct, basis) = match (prices.get(&h.symbol), previous_price) {
+                (Some(price), Some(previous_price)) => (
+                    (price / previous_price - 1.0) * 100.0,
+                    MoverBasis::PreviousDigest,
+                ),
+                _ => (h.day_change_pct? * 100.0, MoverBasis::Day),
+            };
+            Some(DigestMover {
+                symbol: h.symbol.clone(),
+                name: h.name.clone(),
+                change_pct,
+                basis,
+                market_value: h.market_value_base,
+            })
+        })
+        .filter(|m| m.change_pct.abs() >= 0.005)
+        .collect();
+    movers.sort_by(|a, b| b.change_pct.abs().total_cmp(&a.change_pct.abs()));
+    movers.truncate(MAX_MOVERS);
+    if !holdings.is_empty() {
+        snapshot.total_value = total_value;
+        snapshot.prices = prices;
+    }
+
+    let mut income: Vec<DigestIncome> = sources
+        .income
+        .into_iter()
+        .map(|a| DigestIncome {
+            date: a.date,
+            activity_type: a.activity_type,
+            symbol: a.symbol,
+            amount: a.amount,
+            currency: a.currency,
+        })
+        .collect();
+    income.sort_by(|a, b| a.date.cmp(&b.date));
+
+    let allocation = match sources.allocation {
+        Some(categories) => {
+            let allocation: Vec<DigestAllocation> = categories
+                .iter()
+                .map(|c| DigestAllocation {
+                    category: c.category_name.clone(),
+                    percentage: c.percentage,
+                    change_pct: previous.map(|p| {
+                        c.percentage - p.allocation.get(&c.category_name).copied().unwrap_or(0.0)
+                    }),
+                })
+                .collect();
+            snapshot.allocation = categories
+                .into_iter()
+                .map(|c| (c.category_name, c.percentage))
+                .collect();
+            allocation
+        }
+        None => Vec::new(),
+    };
+
+    let health_issues = sources
+        .health_issues
+        .into_iter()
+        .map(|i| DigestHealthIssue {
+            severity: i.severity,
+            title: i.title,
+            message: i.message,
+        })
+        .collect();
+
+    let goals = match sources.goals {
+        Some(goals) => {
+            let digest_goals = goals
+                .iter()
+                .map(|g| DigestGoal {
+                    title: g.title.clone(),
+                    progress_percent: g.progress_percent,
+                    progress_change_pct: previous
+                        .and_then(|p| p.goals.get(&g.id))
+                        .map(|previous| g.progress_percent - previous),
+                    target_amount: g.target_amount,
+                    current_amount: g.current_amount,
+                    is_achieved: g.is_achieved,
+                })
+                .collect();
+            snapshot.goals = goals
+                .into_iter()
+                .map(|g| (g.id, g.progress_percent))
+                .collect();
+            digest_goals
+        }
+        None => Vec::new(),
+    };
+
+    let data = DigestData {
+        period_start,
+        period_end,
+        currency: sources.currency,
+        total_value,
+        value_change_pct,
+        movers,
+        income,
+        allocation,
+        health_issues,
+        goals,
+    };
+    (data, snapshot)
+}
+
+/// Deterministic digest used when no provider is configured or generation fails.
+/// Follows the section order of the digest prompt template.
+pub fn render_fallback(data: &DigestData) -> String {
+    let mut lines = vec!["## Overview".to_string()];
+    let value = format!(
+        "Portfolio value is {:.2} {}",
+        data.total_value, data.currency
+    );
+    lines.push(match data.value_change_pct {
+        Some(change) => format!("{} ({}% since the last digest).", value, signed(change)),
+        None => format!(
+            "{}. This is the first digest, so there is nothing to compare yet.",
+            value
+        ),
+    });
+    lines.push(format!(
+        "Period: {} to {}.",
+        data.period_start.format("%b %-d, %Y"),
+        data.period_end.format("%b %-d, %Y")
+    ));
+
+    if !data.movers.is_empty() {
+        lines.push("\n## Notable movers".to_string());
+        for mover in &data.movers {
+            let label = match &mover.name {
+                Some(name) if !name.is_empty() => format!("{} ({})", mover.symbol, name),
+                _ => mover.symbol.clone(),
+            };
+            let basis = match mover.basis {
+                MoverBasis::PreviousDigest => "since the last digest",
+                MoverBasis::Day => "on the latest trading day",
+            };
+            lines.push(format!(
+                "- {}: {}% {}",
+                label,
+                signed(mover.change_pct),
+                basis
+            ));
+        }
+    }
+
+    if !data.income.is_empty() {
+        lines.push("\n## Income received".to_string());
+        for payment in &data.income {
+            let amount = payment
+                .amount
+                .map(|a| format!("{:.2} {}", a, payment.currency))
+                .unwrap_or_else(|| payment.currency.clone());
+            let kind = payment.activity_type.to_lowercase();
+            lines.push(match &payment.symbol {
+                Some(symbol) => format!("- {}: {} {} from {}", payment.date, amount, kind, symbol),
+                None => format!("- {}: {} {}", payment.date, amount, kind),
+            });
+        }
+    }
+
+    if data.allocation.iter().any(|a| a.change_pct.is_some()) {
+        lines.push("\n## Allocation drift".to_string());
+        let shifts: Vec<&DigestAllocation> = data
+            .allocation
+            .iter()
+            .filter(|a| a.change_pct.is_some_and(|c| c.abs() >= DRIFT_THRESHOLD_PCT))
+            .collect();
+        if shifts.is_empty() {
+            lines.push(format!(
+                "No asset class moved more than {:.1} points.",
+                DRIFT_THRESHOLD_PCT
+            ));
+        }
+        for shift in shifts {
+            lines.push(format!(
+                "- {}: {:.1}% ({} pts)",
+                shift.category,
+                shift.percentage,
+                signed(shift.change_pct.unwrap_or_default())
+            ));
+        }
+    }
+
+    lines.push("\n## Data health".to_string());
+    if data.health_issues.is_empty() {
+        lines.push("No open data issues.".to_string());
+    }
+    for issue in &data.health_issues {
+        lines.push(format!(
+            "- **{}** {}: {}",
+            issue.severity, issue.title, issue.message
+        ));
+    }
+
+    if !data.goals.is_empty() {
+        lines.push("\n## Goal progress".to_string());
+        for goal in &data.goals {
+            let status = if goal.is_achieved {
+                "achieved".to_string()
+            } else {
+                format!("{:.1}% of target", goal.progress_percent)
+            };
+            lines.push(match goal.progress_change_pct {
+                Some(change) => format!("- {}: {} ({} pts)", goal.title, status, signed(change)),
+                None => format!("- {}: {}", goal.title, status),
+            });
+        }
+    }
+
+    lines.push(
+        "\n_Written without an AI provider. Set a default provider in AI settings \
+         for a narrative summary._"
+            .to_string(),
+    );
+    lines.join("\n")
+}
+
+/// One decimal with an explicit sign, e.g. `+1.2` or `-0.4`.
+fn signed(value: f64) -> String {
+    format!("{:+.1}", value)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test_env::{MockEnvironment, MockGoalService};
+    use crate::redaction::{AmountRedaction, RedactionSettings};
+    use wealthfolio_core::goals::Goal;
+
+    fn holding(symbol: &str, quantity: f64, value: f64, day_change: Option<f64>) -> HoldingDto {
+        HoldingDto {
+            account: "TOTAL".to_string(),
+            symbol: symbol.to_string(),
+            name: Some(format!("{} Inc.", symbol)),
+            holding_type: "Security".to_string(),
+            quantity,
+            market_value_base: value,
+            cost_basis_base: None,
+            unrealized_gain_pct: None,
+            day_change_pct: day_change,
+            weight: 0.0,
+            currency: "USD".to_string(),
+        }
+    }
+
+    fn allocation(name: &str, percentage: f64) -> AllocationDto {
+        AllocationDto {
+            category_id: name.to_uppercase(),
+            category_name: name.to_string(),
+            value: 0.0,
+            percentage,
+            color: "#000000".to_string(),
+        }
+    }
+
+    fn goal(id: &str, progress: f64) -> GoalDto {
+        GoalDto {
+            id: id.to_string(),
+            title: "House".to_string(),
+            description: None,
+            target_amount: 100_000.0,
+            current_amount: progress * 1000.0,
+            progress_percent: progress,
+            deadline: None,
+            is_achieved: false,
+        }
+    }
+
+    fn sources(holdings: Vec<HoldingDto>) -> DigestSources {
+        DigestSources {
+            currency: "USD".to_string(),
+            holdings: Some(holdings),
+            income: Vec::new(),
+            allocation: Some(vec![allocation("Equity", 60.0), allocation("Bonds", 40.0)]),
+            health_issues: Vec::new(),
+            goals: Some(vec![goal("g1", 40.0)]),
+        }
+    }
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, d).unwrap()
+    }
+
+    #[test]
+    fn test_first_digest_uses_daily_change_and_has_no_drift() {
+        let (data, snapshot) = build_digest(
+            day(13),
+            day(20),
+            sources(vec![
+                holding("AAPL", 10.0, 2000.0, Some(0.031)),
+                holding("MSFT", 5.0, 2000.0, Some(-0.002)),
+                holding("VTI", 1.0, 300.0, None),
+            ]),
+            None,
+        );
+
+        assert_eq!(data.total_value, 4300.0);
+        assert_eq!(data.value_change_pct, None);
+        assert_eq!(data.movers.len(), 2);
+        assert_eq!(data.movers[0].symbol, "AAPL");
+        assert_eq!(data.movers[0].basis, MoverBasis::Day);
+        assert!((data.movers[0].change_pct - 3.1).abs() < 1e-9);
+        assert!(data.allocation.iter().all(|a| a.change_pct.is_none()));
+
+        assert_eq!(snapshot.prices["AAPL"], 200.0);
+        assert_eq!(snapshot.allocation["Equity"], 60.0);
+        assert_eq!(snapshot.goals["g1"], 40.0);
+    }
+
+    #[test]
+    fn test_digest_compares_against_previous_snapshot() {
+        let previous = DigestSnapshot {
+            total_value: 4000.0,
+            prices: HashMap::from([("AAPL".to_string(), 160.0), ("MSFT".to_string(), 400.0)]),
+            allocation: HashMap::from([("Equity".to_string(), 55.0)]),
+            goals: HashMap::from([("g1".to_string(), 38.5)]),
+        };
+        let (data, _) = build_digest(
+            day(13),
+            day(20),
+            sources(vec![
+                holding("AAPL", 10.0, 2000.0, Some(0.001)),
+                holding("MSFT", 5.0, 2000.0, Some(0.05)),
+            ]),
+            Some(&previous),
+        );
+
+        assert!((data.value_change_pct.unwrap() - 0.0).abs() < 1e-9);
+        // AAPL 160 -> 200 beats MSFT's unchanged price despite its daily move
+        assert_eq!(data.movers.len(), 1);
+        assert_eq!(data.movers[0].symbol, "AAPL");
+        assert_eq!(data.movers[0].basis, MoverBasis::PreviousDigest);
+        assert!((data.movers[0].change_pct - 25.0).abs() < 1e-9);
+
+        let equity = data
+            .allocation
+            .iter()
+            .find(|a| a.category == "Equity")
+            .unwrap();
+        assert_eq!(equity.change_pct, Some(5.0));
+        // Categories new since the last digest drift from zero
+        let bonds = data
+            .allocation
+            .iter()
+            .find(|a| a.category == "Bonds")
+            .unwrap();
+        assert_eq!(bonds.change_pct, Some(40.0));
+        assert_eq!(data.goals[0].progress_change_pct, Some(1.5));
+    }
+
+    #[test]
+    fn test_failed_sections_keep_previous_snapshot() {
+        let previous = DigestSnapshot {
+            total_value: 1000.0,
+            prices: HashMap::from([("AAPL".to_string(), 100.0)]),
+            allocation: HashMap::from([("Equity".to_string(), 100.0)]),
+            goals: HashMap::new(),
+        };
+        let mut failed = sources(vec![holding("AAPL", 10.0, 1100.0, None)]);
+        failed.allocation = None;
+
+        let (data, snapshot) = build_digest(day(13), day(20), failed, Some(&previous));
+        assert!(data.allocation.is_empty());
+        assert_eq!(snapshot.allocation["Equity"], 100.0);
+        assert_eq!(snapshot.prices["AAPL"], 110.0);
+    }
+
+    #[test]
+    fn test_fallback_renders_all_sections() {
+        let previous = DigestSnapshot {
+            total_value: 4000.0,
+            prices: HashMap::from([("AAPL".to_string(), 160.0)]),
+            allocation: HashMap::from([("Equity".to_string(), 60.3), ("Bonds".to_string(), 39.7)]),
+            goals: HashMap::new(),
+        };
+        let mut input = sources(vec![holding("AAPL", 10.0, 2000.0, None)]);
+        input.income = vec![ActivityDto {
+            id: "a1".to_string(),
+            date: "2026-03-15".to_string(),
+            activity_type: "DIVIDEND".to_string(),
+            symbol: Some("AAPL".to_string()),
+            quantity: None,
+            unit_price: None,
+            amount: Some(12.5),
+            fee: None,
+            fx_rate: None,
+            currency: "USD".to_string(),
+            account_id: "acc".to_string(),
+            account_name: None,
+        }];
+        input.health_issues = vec![HealthIssueDto {
+            id: "h1".to_string(),
+            severity: "WARNING".to_string(),
+            category: "PRICE_STALENESS".to_string(),
+            title: "Stale prices".to_string(),
+            message: "2 assets have prices older than 3 days".to_string(),
+            details: None,
+            affected_count: 2,
+            affected_mv_pct: None,
+            affected_items: Vec::new(),
+            suggested_action: None,
+        }];
+
+        let (data, _) = build_digest(day(13), day(20), input, Some(&previous));
+        let text = render_fallback(&data);
+
+        assert!(text.contains("Portfolio value is 2000.00 USD (-50.0% since the last digest)."));
+        assert!(text.contains("- AAPL (AAPL Inc.): +25.0% since the last digest"));
+        assert!(text.contains("- 2026-03-15: 12.50 USD dividend from AAPL"));
+        assert!(text.contains("No asset class moved more than 1.0 points."));
+        assert!(text.contains("- **WARNING** Stale prices: 2 assets have prices older than 3 days"));
+        assert!(text.contains("- House: 40.0% of target"));
+    }
+
+    #[test]
+    fn test_digest_names_are_redacted_in_free_text() {
+        let mut input = sources(vec![
+            holding("AAPL", 10.0, 2000.0, Some(0.031)),
+            holding("MSFT", 5.0, 2000.0, None),
+        ]);
+        input.health_issues = vec![HealthIssueDto {
+            id: "h1".to_string(),
+            severity: "WARNING".to_string(),
+            category: "PRICE_STALENESS".to_string(),
+            title: "Stale prices".to_string(),
+            message: "MSFT Inc. in Main Broker has no price; House is off track".to_string(),
+            details: None,
+            affected_count: 1,
+            affected_mv_pct: None,
+            affected_items: Vec::new(),
+            suggested_action: None,
+        }];
+        let asset_names = vec!["AAPL Inc.".to_string(), "MSFT Inc.".to_string()];
+        let (data, _) = build_digest(day(13), day(20), input, None);
+
+        let redaction = RedactionSession::new(
+            "digest-thread",
+            RedactionSettings {
+                enabled: true,
+                amounts: AmountRedaction::Exact,
+                strip_free_text: true,
+            },
+        );
+        register_digest_names(&redaction, ["Main Broker"], &asset_names, &data);
+        let redacted = redaction.redact_tool_output(&serde_json::to_string(&data).unwrap());
+
+        // MSFT is not a mover, so its name only reaches the prompt through the issue text
+        for name in ["AAPL Inc.", "MSFT Inc.", "Main Broker", "House"] {
+            assert!(!redacted.contains(name), "{} leaked: {}", name, redacted);
+        }
+        assert!(redaction
+            .rehydrate(&redacted)
+            .contains("MSFT Inc. in Main Broker"));
+    }
+
+    #[test]
+    fn test_fallback_without_history_or_issues() {
+        let (data, _) = build_digest(day(13), day(20), sources(Vec::new()), None);
+        let text = render_fallback(&data);
+
+        assert!(text.contains("This is the first digest"));
+        assert!(!text.contains("## Notable movers"));
+        assert!(!text.contains("## Allocation drift"));
+        assert!(text.contains("No open data issues."));
+    }
+
+    #[tokio::test]
+    async fn test_run_if_due_writes_fallback_digest_weekly() {
+        let mut env = MockEnvironment::new();
+        env.goal_service = Arc::new(MockGoalService {
+            goals: vec![Goal {
+                id: "g1".to_string(),
+                title: "House".to_string(),
+                description: None,
+                target_amount: 100_000.0,
+                is_achieved: false,
+            }],
+            allocations: Vec::new(),
+        });
+        let env = Arc::new(env);
+        let service = DigestService::new(env.clone());
+        let now = day(20).and_hms_opt(8, 0, 0).unwrap().and_utc();
+
+        // No provider configured: the deterministic template is used
+        let thread = service.run_if_due(now).await.unwrap().unwrap();
+        assert_eq!(thread.tags, vec![DIGEST_THREAD_TAG.to_string()]);
+        assert!(thread.config.is_none());
+        let messages = env
+            .chat_repository()
+            .get_messages_by_thread(&thread.id)
+            .unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].get_text().contains("## Goal progress"));
+
+        // Not due again within the week
+        let later = now + Duration::days(3);
+        assert!(service.run_if_due(later).await.unwrap().is_none());
+
+        let state = service.load_state();
+        assert_eq!(state.last_generated_at, Some(now));
+        assert_eq!(state.last_thread_id, Some(thread.id));
+    }
+
+    #[tokio::test]
+    async fn test_run_if_due_skips_empty_portfolio() {
+        let env = Arc::new(MockEnvironment::new());
+        let service = DigestService::new(env);
+        let now = day(20).and_hms_opt(8, 0, 0).unwrap().and_utc();
+
+        assert!(service.run_if_due(now).await.unwrap().is_none());
+        assert!(service.load_state().last_generated_at.is_none());
+    }
+}
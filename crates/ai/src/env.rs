@@ -9,10 +9,14 @@ use std::sync::Arc;
 use wealthfolio_core::{
     accounts::AccountServiceTrait,
     activities::ActivityServiceTrait,
+    assets::AlternativeAssetServiceTrait,
+    fx::FxServiceTrait,
     goals::GoalServiceTrait,
+    health::HealthServiceTrait,
     portfolio::{
         allocation::AllocationServiceTrait, holdings::HoldingsServiceTrait,
-        income::IncomeServiceTrait, performance::PerformanceServiceTrait,
+        income::IncomeServiceTrait, net_worth::NetWorthServiceTrait,
+        performance::PerformanceServiceTrait, scenario::ScenarioServiceTrait,
         valuation::ValuationServiceTrait,
     },
     quotes::QuoteServiceTrait,
@@ -29,7 +33,7 @@ use crate::types::ChatRepositoryTrait;
 /// - Secret store for API keys
 /// - Configuration (base currency, etc.)
 /// - Chat repository for thread/message persistence
-/// - Quote service for symbol search
+/// - Quote service for symbol search and quote history
 #[async_trait]
 pub trait AiEnvironment: Send + Sync {
     /// Get the user's base currency (e.g., "USD", "EUR").
@@ -70,12 +74,28 @@ pub trait AiEnvironment: Send + Sync {
 
     /// Get the income service for income/dividend summaries.
     fn income_service(&self) -> Arc<dyn IncomeServiceTrait>;
+
+    /// Get the net worth service for assets/liabilities breakdowns.
+    fn net_worth_service(&self) -> Arc<dyn NetWorthServiceTrait>;
+
+    /// Get the health service for data quality issues.
+    fn health_service(&self) -> Arc<dyn HealthServiceTrait>;
+
+    /// Get the FX service for exchange rates.
+    fn fx_service(&self) -> Arc<dyn FxServiceTrait>;
+
+    /// Get the alternative asset service for properties, vehicles and liabilities.
+    fn alternative_asset_service(&self) -> Arc<dyn AlternativeAssetServiceTrait>;
+
+    /// Get the scenario service for what-if simulations.
+    fn scenario_service(&self) -> Arc<dyn ScenarioServiceTrait>;
 }
 
 #[cfg(test)]
 pub mod test_env {
     use super::*;
     use chrono::{DateTime, NaiveDate, Utc};
+    use rust_decimal::Decimal;
     use std::collections::{HashMap, HashSet};
     use std::sync::RwLock;
     use wealthfolio_core::{
@@ -86,13 +106,26 @@ pub mod test_env {
             ActivityImport, ActivitySearchResponse, ActivitySearchResponseMeta,
             ActivityServiceTrait, ActivityUpdate, ImportMappingData, NewActivity, Sort,
         },
-        assets::{Asset, ProviderProfile},
+        assets::{
+            AlternativeAssetServiceTrait, AlternativeHolding, Asset, AssetServiceTrait,
+            CreateAlternativeAssetRequest, CreateAlternativeAssetResponse, LinkLiabilityRequest,
+            LinkLiabilityResponse, ProviderProfile, UpdateAssetDetailsRequest,
+            UpdateAssetDetailsResponse, UpdateValuationRequest, UpdateValuationResponse,
+        },
         errors::DatabaseError,
+        fx::{ExchangeRate, FxServiceTrait, NewExchangeRate},
         goals::{Goal, GoalServiceTrait, GoalsAllocation, NewGoal},
+        health::checks::{
+            AssetHoldingInfo, ConsistencyIssueInfo, FxPairInfo, LegacyMigrationInfo,
+            QuoteSyncErrorInfo, UnclassifiedAssetInfo, UnconfiguredAccountInfo,
+        },
+        health::{FixAction, HealthConfig, HealthIssue, HealthServiceTrait, HealthStatus},
         holdings::{Holding, HoldingsServiceTrait},
         portfolio::allocation::{AllocationHoldings, AllocationServiceTrait, PortfolioAllocations},
         portfolio::income::{IncomeServiceTrait, IncomeSummary},
+        portfolio::net_worth::{NetWorthHistoryPoint, NetWorthResponse, NetWorthServiceTrait},
         portfolio::performance::{PerformanceMetrics, PerformanceServiceTrait},
+        portfolio::scenario::{ScenarioRequest, ScenarioResult, ScenarioServiceTrait},
         quotes::{
             LatestQuotePair, LatestQuoteSnapshot, ProviderInfo, Quote, QuoteImport,
             QuoteServiceTrait, QuoteSyncState, SymbolSearchResult, SymbolSyncPlan, SyncMode,
@@ -100,6 +133,7 @@ pub mod test_env {
         },
         secrets::SecretStore,
         settings::{Settings, SettingsServiceTrait, SettingsUpdate},
+        taxonomies::TaxonomyServiceTrait,
         valuation::{DailyAccountValuation, ValuationServiceTrait},
         Error as CoreError, Result as CoreResult,
     };
@@ -324,6 +358,7 @@ pub mod test_env {
             &self,
             _account_id: String,
             _activities: Vec<ActivityImport>,
+            _review_mode: wealthfolio_core::activities::ReviewMode,
         ) -> CoreResult<wealthfolio_core::activities::ImportActivitiesResult> {
             unimplemented!("MockActivityService::import_activities")
         }
@@ -399,6 +434,14 @@ pub mod test_env {
         ) -> CoreResult<Vec<Holding>> {
             Ok(Vec::new())
         }
+
+        async fn value_holdings_from_snapshot(
+            &self,
+            _snapshot: &wealthfolio_core::portfolio::snapshot::AccountStateSnapshot,
+            _base_currency: &str,
+        ) -> CoreResult<Vec<Holding>> {
+            Ok(self.holdings.clone())
+        }
     }
 
     /// Mock valuation service for testing.
@@ -687,12 +730,36 @@ pub mod test_env {
         fn get_tags(&self, _thread_id: &str) -> crate::types::ChatRepositoryResult<Vec<String>> {
             Ok(Vec::new())
         }
+
+        fn list_usage(
+            &self,
+            from: chrono::DateTime<chrono::Utc>,
+            to: chrono::DateTime<chrono::Utc>,
+        ) -> crate::types::ChatRepositoryResult<Vec<crate::types::UsageRecord>> {
+            let messages = self.messages.read().unwrap();
+            let mut records: Vec<_> = messages
+                .values()
+                .flatten()
+                .filter(|m| m.created_at >= from && m.created_at < to)
+                .filter_map(|m| {
+                    m.usage.clone().map(|usage| crate::types::UsageRecord {
+                        message_id: m.id.clone(),
+                        thread_id: m.thread_id.clone(),
+                        created_at: m.created_at,
+                        usage,
+                    })
+                })
+                .collect();
+            records.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+            Ok(records)
+        }
     }
 
     /// Mock quote service for testing.
     #[derive(Default)]
     pub struct MockQuoteService {
         pub search_results: RwLock<Vec<SymbolSearchResult>>,
+        pub quotes: Vec<Quote>,
     }
 
     #[async_trait]
@@ -735,8 +802,13 @@ pub mod test_env {
             Ok(HashMap::new())
         }
 
-        fn get_historical_quotes(&self, _symbol: &str) -> CoreResult<Vec<Quote>> {
-            Ok(Vec::new())
+        fn get_historical_quotes(&self, symbol: &str) -> CoreResult<Vec<Quote>> {
+            Ok(self
+                .quotes
+                .iter()
+                .filter(|q| q.asset_id == symbol)
+                .cloned()
+                .collect())
         }
 
         fn get_all_historical_quotes(
@@ -747,11 +819,17 @@ pub mod test_env {
 
         fn get_quotes_in_range(
             &self,
-            _symbols: &HashSet<String>,
-            _start: NaiveDate,
-            _end: NaiveDate,
+            symbols: &HashSet<String>,
+            start: NaiveDate,
+            end: NaiveDate,
         ) -> CoreResult<Vec<Quote>> {
-            Ok(Vec::new())
+            Ok(self
+                .quotes
+                .iter()
+                .filter(|q| symbols.contains(&q.asset_id))
+                .filter(|q| (start..=end).contains(&q.timestamp.date_naive()))
+                .cloned()
+                .collect())
         }
 
         fn get_quotes_in_range_filled(
@@ -930,6 +1008,13 @@ pub mod test_env {
             Ok(PortfolioAllocations::default())
         }
 
+        fn get_allocations_for_holdings(
+            &self,
+            _holdings: &[Holding],
+        ) -> CoreResult<PortfolioAllocations> {
+            Ok(PortfolioAllocations::default())
+        }
+
         async fn get_holdings_by_allocation(
             &self,
             _account_id: &str,
@@ -1036,6 +1121,301 @@ pub mod test_env {
         }
     }
 
+    /// Mock net worth service for testing.
+    #[derive(Default)]
+    pub struct MockNetWorthService {
+        pub response: Option<NetWorthResponse>,
+    }
+
+    #[async_trait]
+    impl NetWorthServiceTrait for MockNetWorthService {
+        async fn get_net_worth(&self, date: NaiveDate) -> CoreResult<NetWorthResponse> {
+            Ok(self
+                .response
+                .clone()
+                .unwrap_or_else(|| NetWorthResponse::empty(date, "USD".to_string())))
+        }
+
+        fn get_net_worth_history(
+            &self,
+            _start_date: NaiveDate,
+            _end_date: NaiveDate,
+        ) -> CoreResult<Vec<NetWorthHistoryPoint>> {
+            Ok(Vec::new())
+        }
+    }
+
+    /// Mock health service for testing.
+    #[derive(Default)]
+    pub struct MockHealthService {
+        pub status: Option<HealthStatus>,
+    }
+
+    #[async_trait]
+    impl HealthServiceTrait for MockHealthService {
+        async fn run_checks(&self, _base_currency: &str) -> CoreResult<HealthStatus> {
+            Ok(self.status.clone().unwrap_or_else(HealthStatus::healthy))
+        }
+
+        async fn run_checks_with_data(
+            &self,
+            base_currency: &str,
+            _total_portfolio_value: f64,
+            _holdings: &[AssetHoldingInfo],
+            _latest_quote_times: &HashMap<String, DateTime<Utc>>,
+            _quote_sync_errors: &[QuoteSyncErrorInfo],
+            _fx_pairs: &[FxPairInfo],
+            _unclassified_assets: &[UnclassifiedAssetInfo],
+            _consistency_issues: &[ConsistencyIssueInfo],
+            _legacy_migration_info: &Option<LegacyMigrationInfo>,
+            _unconfigured_accounts: &[UnconfiguredAccountInfo],
+        ) -> CoreResult<HealthStatus> {
+            self.run_checks(base_currency).await
+        }
+
+        async fn get_cached_status(&self) -> Option<HealthStatus> {
+            self.status.clone()
+        }
+
+        async fn dismiss_issue(&self, _issue_id: &str, _data_hash: &str) -> CoreResult<()> {
+            unimplemented!("MockHealthService::dismiss_issue")
+        }
+
+        async fn restore_issue(&self, _issue_id: &str) -> CoreResult<()> {
+            unimplemented!("MockHealthService::restore_issue")
+        }
+
+        async fn get_dismissed_ids(&self) -> CoreResult<Vec<String>> {
+            Ok(Vec::new())
+        }
+
+        async fn execute_fix(&self, _action: &FixAction) -> CoreResult<()> {
+            unimplemented!("MockHealthService::execute_fix")
+        }
+
+        async fn get_config(&self) -> HealthConfig {
+            HealthConfig::default()
+        }
+
+        async fn update_config(&self, _config: HealthConfig) -> CoreResult<()> {
+            unimplemented!("MockHealthService::update_config")
+        }
+
+        async fn clear_cache(&self) {}
+
+        async fn publish_issues(&self, _source: &str, _issues: Vec<HealthIssue>) {}
+
+        async fn resolve_published_fix(&self, _action: &FixAction) {}
+
+        async fn run_full_checks(
+            &self,
+            base_currency: &str,
+            _account_service: Arc<dyn AccountServiceTrait>,
+            _holdings_service: Arc<dyn HoldingsServiceTrait>,
+            _quote_service: Arc<dyn QuoteServiceTrait>,
+            _asset_service: Arc<dyn AssetServiceTrait>,
+            _taxonomy_service: Arc<dyn TaxonomyServiceTrait>,
+        ) -> CoreResult<HealthStatus> {
+            self.run_checks(base_currency).await
+        }
+    }
+
+    /// Mock FX service for testing. Rates are keyed by "FROM/TO"; the
+    /// inverse pair and same-currency pairs are derived.
+    #[derive(Default)]
+    pub struct MockFxService {
+        pub rates: HashMap<String, Decimal>,
+    }
+
+    impl MockFxService {
+        fn rate(&self, from_currency: &str, to_currency: &str) -> CoreResult<Decimal> {
+            if from_currency == to_currency {
+                return Ok(Decimal::ONE);
+            }
+            if let Some(rate) = self
+                .rates
+                .get(&format!("{}/{}", from_currency, to_currency))
+            {
+                return Ok(*rate);
+            }
+            self.rates
+                .get(&format!("{}/{}", to_currency, from_currency))
+                .filter(|rate| !rate.is_zero())
+                .map(|rate| Decimal::ONE / rate)
+                .ok_or_else(|| {
+                    CoreError::Unexpected(format!(
+                        "No exchange rate for {}/{}",
+                        from_currency, to_currency
+                    ))
+                })
+        }
+    }
+
+    #[async_trait]
+    impl FxServiceTrait for MockFxService {
+        fn initialize(&self) -> CoreResult<()> {
+            Ok(())
+        }
+
+        fn get_historical_rates(
+            &self,
+            _from_currency: &str,
+            _to_currency: &str,
+            _days: i64,
+        ) -> CoreResult<Vec<ExchangeRate>> {
+            Ok(Vec::new())
+        }
+
+        fn get_latest_exchange_rate(
+            &self,
+            from_currency: &str,
+            to_currency: &str,
+        ) -> CoreResult<Decimal> {
+            self.rate(from_currency, to_currency)
+        }
+
+        fn get_exchange_rate_for_date(
+            &self,
+            from_currency: &str,
+            to_currency: &str,
+            _date: NaiveDate,
+        ) -> CoreResult<Decimal> {
+            self.rate(from_currency, to_currency)
+        }
+
+        fn convert_currency(
+            &self,
+            amount: Decimal,
+            from_currency: &str,
+            to_currency: &str,
+        ) -> CoreResult<Decimal> {
+            Ok(amount * self.rate(from_currency, to_currency)?)
+        }
+
+        fn convert_currency_for_date(
+            &self,
+            amount: Decimal,
+            from_currency: &str,
+            to_currency: &str,
+            _date: NaiveDate,
+        ) -> CoreResult<Decimal> {
+            Ok(amount * self.rate(from_currency, to_currency)?)
+        }
+
+        fn get_latest_exchange_rates(&self) -> CoreResult<Vec<ExchangeRate>> {
+            Ok(Vec::new())
+        }
+
+        async fn add_exchange_rate(&self, _new_rate: NewExchangeRate) -> CoreResult<ExchangeRate> {
+            unimplemented!("MockFxService::add_exchange_rate")
+        }
+
+        async fn update_exchange_rate(
+            &self,
+            _from_currency: &str,
+            _to_currency: &str,
+            _rate: Decimal,
+        ) -> CoreResult<ExchangeRate> {
+            unimplemented!("MockFxService::update_exchange_rate")
+        }
+
+        async fn delete_exchange_rate(&self, _rate_id: &str) -> CoreResult<()> {
+            unimplemented!("MockFxService::delete_exchange_rate")
+        }
+
+        async fn register_currency_pair(
+            &self,
+            _from_currency: &str,
+            _to_currency: &str,
+        ) -> CoreResult<()> {
+            Ok(())
+        }
+
+        async fn register_currency_pair_manual(
+            &self,
+            _from_currency: &str,
+            _to_currency: &str,
+        ) -> CoreResult<()> {
+            Ok(())
+        }
+
+        async fn ensure_fx_pairs(&self, _pairs: Vec<(String, String)>) -> CoreResult<()> {
+            Ok(())
+        }
+    }
+
+    /// Mock alternative asset service for testing.
+    #[derive(Default)]
+    pub struct MockAlternativeAssetService {
+        pub holdings: Vec<AlternativeHolding>,
+    }
+
+    #[async_trait]
+    impl AlternativeAssetServiceTrait for MockAlternativeAssetService {
+        async fn create_alternative_asset(
+            &self,
+            _request: CreateAlternativeAssetRequest,
+        ) -> CoreResult<CreateAlternativeAssetResponse> {
+            unimplemented!("MockAlternativeAssetService::create_alternative_asset")
+        }
+
+        async fn update_valuation(
+            &self,
+            _request: UpdateValuationRequest,
+        ) -> CoreResult<UpdateValuationResponse> {
+            unimplemented!("MockAlternativeAssetService::update_valuation")
+        }
+
+        async fn delete_alternative_asset(&self, _asset_id: &str) -> CoreResult<()> {
+            unimplemented!("MockAlternativeAssetService::delete_alternative_asset")
+        }
+
+        async fn link_liability(
+            &self,
+            _request: LinkLiabilityRequest,
+        ) -> CoreResult<LinkLiabilityResponse> {
+            unimplemented!("MockAlternativeAssetService::link_liability")
+        }
+
+        async fn unlink_liability(&self, _liability_id: &str) -> CoreResult<LinkLiabilityResponse> {
+            unimplemented!("MockAlternativeAssetService::unlink_liability")
+        }
+
+        async fn update_asset_details(
+            &self,
+            _request: UpdateAssetDetailsRequest,
+        ) -> CoreResult<UpdateAssetDetailsResponse> {
+            unimplemented!("MockAlternativeAssetService::update_asset_details")
+        }
+
+        fn get_alternative_holdings(&self) -> CoreResult<Vec<AlternativeHolding>> {
+            Ok(self.holdings.clone())
+        }
+
+        async fn sync_panorama_mpf_unit_prices(&self) -> CoreResult<usize> {
+            Ok(0)
+        }
+    }
+
+    /// Mock scenario service for testing.
+    #[derive(Default)]
+    pub struct MockScenarioService {
+        pub result: Option<ScenarioResult>,
+    }
+
+    #[async_trait]
+    impl ScenarioServiceTrait for MockScenarioService {
+        async fn simulate(
+            &self,
+            _request: ScenarioRequest,
+            _base_currency: &str,
+        ) -> CoreResult<ScenarioResult> {
+            self.result.clone().ok_or_else(|| {
+                CoreError::Unexpected("MockScenarioService has no result".to_string())
+            })
+        }
+    }
+
     /// Mock environment for testing.
     pub struct MockEnvironment {
         pub base_currency: String,
@@ -1051,6 +1431,11 @@ pub mod test_env {
         pub allocation_service: Arc<dyn AllocationServiceTrait>,
         pub performance_service: Arc<dyn PerformanceServiceTrait>,
         pub income_service: Arc<dyn IncomeServiceTrait>,
+        pub net_worth_service: Arc<dyn NetWorthServiceTrait>,
+        pub health_service: Arc<dyn HealthServiceTrait>,
+        pub fx_service: Arc<dyn FxServiceTrait>,
+        pub alternative_asset_service: Arc<dyn AlternativeAssetServiceTrait>,
+        pub scenario_service: Arc<dyn ScenarioServiceTrait>,
     }
 
     impl Default for MockEnvironment {
@@ -1075,6 +1460,13 @@ pub mod test_env {
                 allocation_service: Arc::new(MockAllocationService),
                 performance_service: Arc::new(MockPerformanceService),
                 income_service: Arc::new(MockIncomeService),
+                net_worth_service: Arc::new(MockNetWorthService::default()),
+                health_service: Arc::new(MockHealthService::default()),
+                fx_service: Arc::new(MockFxService {
+                    rates: HashMap::from([("USD/HKD".to_string(), Decimal::new(78, 1))]),
+                }),
+                alternative_asset_service: Arc::new(MockAlternativeAssetService::default()),
+                scenario_service: Arc::new(MockScenarioService::default()),
             }
         }
 
@@ -1137,5 +1529,25 @@ pub mod test_env {
         fn income_service(&self) -> Arc<dyn IncomeServiceTrait> {
             self.income_service.clone()
         }
+
+        fn net_worth_service(&self) -> Arc<dyn NetWorthServiceTrait> {
+            self.net_worth_service.clone()
+        }
+
+        fn health_service(&self) -> Arc<dyn HealthServiceTrait> {
+            self.health_service.clone()
+        }
+
+        fn fx_service(&self) -> Arc<dyn FxServiceTrait> {
+            self.fx_service.clone()
+        }
+
+        fn alternative_asset_service(&self) -> Arc<dyn AlternativeAssetServiceTrait> {
+            self.alternative_asset_service.clone()
+        }
+
+        fn scenario_service(&self) -> Arc<dyn ScenarioServiceTrait> {
+            self.scenario_service.clone()
+        }
     }
 }
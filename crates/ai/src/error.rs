@@ -38,6 +38,10 @@ pub enum AiError {
     #[error("Invalid cursor: {0}")]
     InvalidCursor(String),
 
+    /// Monthly usage budget reached and set to block new messages.
+    #[error("{0}")]
+    BudgetExceeded(String),
+
     /// Core error from wealthfolio-core.
     #[error("Core error: {0}")]
     Core(#[from] CoreError),
@@ -76,6 +80,7 @@ impl AiError {
             AiError::ToolExecutionFailed(_) => "TOOL_EXECUTION_FAILED",
             AiError::ThreadNotFound(_) => "THREAD_NOT_FOUND",
             AiError::InvalidCursor(_) => "INVALID_CURSOR",
+            AiError::BudgetExceeded(_) => "BUDGET_EXCEEDED",
             AiError::Core(_) => "CORE_ERROR",
             AiError::Internal(_) => "INTERNAL_ERROR",
         }
@@ -0,0 +1,203 @@
+//! Cassettes: recorded or scripted model streams, one turn per completion request.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+use crate::error::AiError;
+
+/// Current cassette file format version.
+pub const CASSETTE_VERSION: u32 = 1;
+
+/// One streamed event from the model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CassetteEvent {
+    /// Text delta.
+    Text { text: String },
+    /// Reasoning delta.
+    Reasoning { text: String },
+    /// Complete tool call. `arguments` is passed to the tool verbatim, so a
+    /// string value can stand in for malformed arguments.
+    #[serde(rename_all = "camelCase")]
+    ToolCall {
+        id: String,
+        name: String,
+        arguments: Value,
+    },
+    /// Token usage reported with the final response.
+    #[serde(rename_all = "camelCase")]
+    Usage {
+        input_tokens: u64,
+        output_tokens: u64,
+    },
+    /// Provider error. Fails the request when it is the first event,
+    /// otherwise the stream.
+    Error { message: String },
+}
+
+/// Summary of the request a turn answered, for inspection and drift checks.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestSummary {
+    /// Tool names offered to the model.
+    pub tools: Vec<String>,
+    /// Messages in the request, including the prompt.
+    pub message_count: usize,
+}
+
+/// The model's answer to one completion request.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Turn {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<RequestSummary>,
+    pub events: Vec<CassetteEvent>,
+}
+
+impl Turn {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn text(mut self, text: &str) -> Self {
+        self.events.push(CassetteEvent::Text {
+            text: text.to_string(),
+        });
+        self
+    }
+
+    pub fn reasoning(mut self, text: &str) -> Self {
+        self.events.push(CassetteEvent::Reasoning {
+            text: text.to_string(),
+        });
+        self
+    }
+
+    /// Adds a tool call with an ID derived from its position in the turn.
+    pub fn tool_call(mut self, name: &str, arguments: Value) -> Self {
+        let id = format!("call_{}_{}", name, self.events.len());
+        self.events.push(CassetteEvent::ToolCall {
+            id,
+            name: name.to_string(),
+            arguments,
+        });
+        self
+    }
+
+    pub fn usage(mut self, input_tokens: u64, output_tokens: u64) -> Self {
+        self.events.push(CassetteEvent::Usage {
+            input_tokens,
+            output_tokens,
+        });
+        self
+    }
+
+    pub fn error(mut self, message: &str) -> Self {
+        self.events.push(CassetteEvent::Error {
+            message: message.to_string(),
+        });
+        self
+    }
+}
+
+/// A sequence of turns replayed in order, one per completion request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cassette {
+    pub version: u32,
+    /// Provider the cassette was recorded against ("scripted" for hand-written ones).
+    pub provider_id: String,
+    pub model_id: String,
+    pub turns: Vec<Turn>,
+}
+
+impl Cassette {
+    pub fn new(provider_id: &str, model_id: &str) -> Self {
+        Self {
+            version: CASSETTE_VERSION,
+            provider_id: provider_id.to_string(),
+            model_id: model_id.to_string(),
+            turns: Vec::new(),
+        }
+    }
+
+    /// A hand-written cassette.
+    pub fn scripted(turns: Vec<Turn>) -> Self {
+        Self {
+            turns,
+            ..Self::new("scripted", "scripted")
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, AiError> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| AiError::Internal(format!("{}: {}", path.display(), e)))?;
+        let cassette: Cassette = serde_json::from_str(&json)
+            .map_err(|e| AiError::invalid_input(format!("{}: {}", path.display(), e)))?;
+        if cassette.version != CASSETTE_VERSION {
+            return Err(AiError::invalid_input(format!(
+                "{}: unsupported cassette version {}",
+                path.display(),
+                cassette.version
+            )));
+        }
+        Ok(cassette)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AiError> {
+        let json =
+            serde_json::to_string_pretty(self).map_err(|e| AiError::Internal(e.to_string()))?;
+        std::fs::write(path, json + "\n")
+            .map_err(|e| AiError::Internal(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Names of the tools called across all turns, in order.
+    pub fn tool_calls(&self) -> Vec<&str> {
+        self.turns
+            .iter()
+            .flat_map(|turn| &turn.events)
+            .filter_map(|event| match event {
+                CassetteEvent::ToolCall { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_cassette_round_trip() {
+        let cassette = Cassette::scripted(vec![
+            Turn::new()
+                .reasoning("Need holdings")
+                .tool_call("get_holdings", json!({ "accountId": "TOTAL" })),
+            Turn::new().text("You hold nothing yet.").usage(120, 8),
+        ]);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cassette.json");
+
+        cassette.save(&path).unwrap();
+        let loaded = Cassette::load(&path).unwrap();
+
+        assert_eq!(loaded, cassette);
+        assert_eq!(loaded.tool_calls(), vec!["get_holdings"]);
+    }
+
+    #[test]
+    fn test_event_format() {
+        let event = CassetteEvent::ToolCall {
+            id: "call_1".to_string(),
+            name: "get_goals".to_string(),
+            arguments: json!({}),
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({ "type": "toolCall", "id": "call_1", "name": "get_goals", "arguments": {} })
+        );
+    }
+}
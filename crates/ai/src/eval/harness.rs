@@ -1,9 +1,18 @@
 //! Evaluation harness for running golden scenarios.
 
+use rig::{agent::AgentBuilder, completion::CompletionModel};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use super::cassette::Cassette;
+use super::model::ReplayModel;
 use super::scenarios::GoldenScenario;
-use crate::tools::{MAX_ACTIVITIES_ROWS, MAX_HOLDINGS, MAX_VALUATIONS_POINTS};
+use crate::chat::{build_allowed_tools, collect_agent_events, ChatConfig};
+use crate::env::test_env::MockEnvironment;
+use crate::error::AiError;
+use crate::redaction::RedactionSession;
+use crate::tools::{MAX_ACTIVITIES_ROWS, MAX_HOLDINGS, MAX_QUOTE_POINTS, MAX_VALUATIONS_POINTS};
 use crate::types::AiStreamEvent;
 
 /// Result of running a golden scenario.
@@ -79,7 +88,8 @@ pub fn assert_valid_event_ordering(events: &[AiStreamEvent]) -> Result<(), Strin
 pub fn assert_guardrails_respected(results: &[ToolResultSummary]) -> Result<(), String> {
     let max_allowed = MAX_ACTIVITIES_ROWS
         .max(MAX_HOLDINGS)
-        .max(MAX_VALUATIONS_POINTS);
+        .max(MAX_VALUATIONS_POINTS)
+        .max(MAX_QUOTE_POINTS);
 
     for result in results {
         if let Some(count) = result.row_count {
@@ -143,10 +153,124 @@ pub fn validate_eval_result(scenario: &GoldenScenario, result: &EvalResult) -> V
     failures
 }
 
+/// Directory holding the golden scenario cassettes (`<scenario name>.json`).
+pub fn cassette_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src/eval/cassettes")
+}
+
+/// Outcome of driving a model through the chat stream.
+pub struct EvalRun {
+    /// Every event emitted, starting with System.
+    pub events: Vec<AiStreamEvent>,
+    /// Error returned by the stream, after provider error remapping.
+    pub outcome: Result<(), AiError>,
+}
+
+impl EvalRun {
+    /// Summarizes the events for scenario validation.
+    pub fn to_result(&self, scenario_name: &str) -> EvalResult {
+        let mut tool_calls_observed = Vec::new();
+        let mut tool_results_observed = Vec::new();
+        let mut final_text = None;
+        for event in &self.events {
+            match event {
+                AiStreamEvent::ToolCall { tool_call, .. } => {
+                    tool_calls_observed.push(tool_call.name.clone());
+                }
+                AiStreamEvent::ToolResult { result, .. } => {
+                    tool_results_observed.push(ToolResultSummary {
+                        tool_call_id: result.tool_call_id.clone(),
+                        success: result.success,
+                        row_count: row_count(&result.data),
+                        truncated: result.data.get("truncated").and_then(|v| v.as_bool()),
+                        duration_ms: None,
+                    });
+                }
+                AiStreamEvent::Done { message, .. } => final_text = Some(message.get_text()),
+                _ => {}
+            }
+        }
+
+        let mut failures = Vec::new();
+        if let Err(e) = assert_valid_event_ordering(&self.events) {
+            failures.push(e);
+        }
+        if let Err(e) = assert_guardrails_respected(&tool_results_observed) {
+            failures.push(e);
+        }
+
+        EvalResult {
+            scenario_name: scenario_name.to_string(),
+            passed: failures.is_empty(),
+            failures,
+            tool_calls_observed,
+            tool_results_observed,
+            ended_with_done: matches!(self.events.last(), Some(AiStreamEvent::Done { .. })),
+            final_text,
+        }
+    }
+
+    /// The terminal error event's code and message, if the stream failed.
+    pub fn error(&self) -> Option<(&str, &str)> {
+        self.events.iter().rev().find_map(|event| match event {
+            AiStreamEvent::Error { code, message, .. } => Some((code.as_str(), message.as_str())),
+            _ => None,
+        })
+    }
+}
+
+/// Size of the first array in a tool result (holdings, activities, ...).
+fn row_count(data: &serde_json::Value) -> Option<usize> {
+    data.as_object()?
+        .values()
+        .find_map(|value| value.as_array().map(Vec::len))
+}
+
+/// Drives `model` through the production chat stream with all tools over
+/// `MockEnvironment`. No network access; the system prompt is the real one.
+pub async fn run_model<M: CompletionModel + 'static>(
+    model: M,
+    user_query: &str,
+    provider_id: &str,
+    max_tool_rounds: usize,
+) -> EvalRun {
+    let env = Arc::new(MockEnvironment::new());
+    let tools = build_allowed_tools(&env, None, &Arc::new(RedactionSession::disabled()), None);
+    let agent = AgentBuilder::new(model)
+        .preamble(include_str!("../system_prompt.txt").trim())
+        .tools(tools)
+        .build();
+
+    let (events, outcome) =
+        collect_agent_events(env, agent, user_query, provider_id, "eval", max_tool_rounds).await;
+    EvalRun { events, outcome }
+}
+
+/// Replays a scenario's cassette and validates the result against it.
+pub async fn run_scenario(scenario: &GoldenScenario, cassette: Cassette) -> EvalResult {
+    let provider_id = cassette.provider_id.clone();
+    let run = run_model(
+        ReplayModel::new(cassette),
+        scenario.user_query,
+        &provider_id,
+        ChatConfig::default().max_tool_rounds,
+    )
+    .await;
+
+    let mut result = run.to_result(scenario.name);
+    result
+        .failures
+        .extend(validate_eval_result(scenario, &result));
+    result.passed = result.failures.is_empty();
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::eval::{get_golden_scenarios, Turn};
     use crate::types::{ChatMessage, ChatMessageContent, ToolCall, ToolResultData};
+    use serde_json::json;
 
     /// Helper to create a ChatMessage for testing
     fn test_assistant_message(thread_id: &str, text: &str) -> ChatMessage {
@@ -243,4 +367,213 @@ mod tests {
         let result = assert_guardrails_respected(&results);
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_golden_scenarios_replay() {
+        for scenario in get_golden_scenarios() {
+            let path = cassette_dir().join(format!("{}.json", scenario.name));
+            let cassette = Cassette::load(&path).unwrap();
+            let result = run_scenario(&scenario, cassette).await;
+            assert!(
+                result.passed,
+                "{} failed: {:?}",
+                result.scenario_name, result.failures
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scripted_multi_round_tool_calls() {
+        let model = ReplayModel::new(Cassette::scripted(vec![
+            Turn::new().tool_call("get_accounts", json!({})),
+            Turn::new().tool_call("get_holdings", json!({ "accountId": "TOTAL" })),
+            Turn::new().text("Nothing held yet."),
+        ]));
+        let run = run_model(model.clone(), "What do I own?", "scripted", 6).await;
+        let result = run.to_result("multi_round");
+
+        assert!(result.passed, "{:?}", result.failures);
+        assert_eq!(
+            result.tool_calls_observed,
+            vec!["get_accounts", "get_holdings"]
+        );
+        assert_eq!(result.tool_results_observed.len(), 2);
+        assert_eq!(result.final_text.as_deref(), Some("Nothing held yet."));
+        assert_eq!(model.remaining_turns(), 0);
+        // The second request carries the first round's call and result.
+        let requests = model.requests();
+        assert!(requests[1].message_count > requests[0].message_count);
+    }
+
+    #[tokio::test]
+    async fn test_final_usage_is_recorded_on_message() {
+        let model = ReplayModel::new(Cassette::scripted(vec![Turn::new()
+            .text("You hold nothing yet.")
+            .usage(120, 8)]));
+        let run = run_model(model, "What do I own?", "scripted", 6).await;
+
+        let (message, usage) = run
+            .events
+            .iter()
+            .find_map(|event| match event {
+                AiStreamEvent::Done { message, usage, .. } => Some((message, usage)),
+                _ => None,
+            })
+            .expect("stream should end with done");
+
+        let recorded = message.usage.as_ref().expect("usage should be recorded");
+        assert_eq!(recorded.provider_id, "scripted");
+        assert_eq!(recorded.model_id, "eval");
+        assert_eq!(recorded.prompt_tokens, 120);
+        assert_eq!(recorded.completion_tokens, 8);
+        assert_eq!(usage.as_ref().map(|u| u.total_tokens), Some(128));
+    }
+
+    #[tokio::test]
+    async fn test_max_tool_rounds_exhaustion() {
+        let model = ReplayModel::new(Cassette::scripted(vec![
+            Turn::new().tool_call("get_accounts", json!({})),
+            Turn::new().tool_call("get_goals", json!({})),
+            Turn::new().tool_call("get_accounts", json!({})),
+            Turn::new().text("Unreachable"),
+        ]));
+        let run = run_model(model.clone(), "Loop forever", "scripted", 1).await;
+        let result = run.to_result("max_rounds");
+
+        assert!(run.outcome.is_err());
+        assert!(run.error().is_some());
+        assert!(!result.ended_with_done);
+        assert!(result.final_text.is_none());
+        assert!(model.remaining_turns() > 0);
+        assert!(assert_valid_event_ordering(&run.events).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_malformed_tool_arguments() {
+        let model = ReplayModel::new(Cassette::scripted(vec![
+            Turn::new().tool_call("get_holdings", json!("{\"accountId\": ")),
+            Turn::new().text("Sorry, let me try that differently."),
+        ]));
+        let run = run_model(model, "Show me my holdings", "scripted", 6).await;
+        let result = run.to_result("malformed_args");
+
+        // The tool error is fed back to the model and the stream still completes.
+        assert!(run.outcome.is_ok());
+        assert!(result.passed, "{:?}", result.failures);
+        assert_eq!(result.tool_calls_observed, vec!["get_holdings"]);
+        assert_eq!(result.tool_results_observed.len(), 1);
+        assert!(result.ended_with_done);
+    }
+
+    #[tokio::test]
+    async fn test_provider_error_is_remapped() {
+        let cassette = || {
+            Cassette::scripted(vec![
+                Turn::new().error("missing field `model` at line 1 column 40")
+            ])
+        };
+
+        let run = run_model(ReplayModel::new(cassette()), "Hi", "ollama", 6).await;
+        let (code, message) = run.error().unwrap();
+        assert_eq!(code, "PROVIDER_ERROR");
+        assert!(message.contains("Ollama returned an error payload for model 'eval'"));
+        assert!(matches!(run.outcome, Err(AiError::Provider(_))));
+
+        let run = run_model(ReplayModel::new(cassette()), "Hi", "openai", 6).await;
+        let (_, message) = run.error().unwrap();
+        assert!(!message.contains("Ollama"));
+    }
+
+    #[tokio::test]
+    async fn test_mid_stream_provider_error() {
+        let model = ReplayModel::new(Cassette::scripted(vec![Turn::new()
+            .text("Your holdings are")
+            .error("connection reset")]));
+        let run = run_model(model, "Show me my holdings", "scripted", 6).await;
+
+        assert!(run.outcome.is_err());
+        let (_, message) = run.error().unwrap();
+        assert!(message.contains("connection reset"));
+    }
+
+    #[tokio::test]
+    async fn test_custom_provider_streams_through_stub_server() {
+        use crate::eval::StubServer;
+        use rig::client::CompletionClient;
+
+        let server = StubServer::start(&["qwen2.5-7b-instruct"], "You hold three assets.").await;
+        let client = crate::chat::create_openai_compatible_client(
+            None,
+            "custom-lm-studio",
+            Some(server.base_url.clone()),
+        )
+        .unwrap();
+
+        let run = run_model(
+            client.completion_model("qwen2.5-7b-instruct"),
+            "What do I own?",
+            "custom-lm-studio",
+            6,
+        )
+        .await;
+        assert!(run.outcome.is_ok(), "{:?}", run.outcome);
+
+        let result = run.to_result("custom_provider");
+        assert!(result.passed, "{:?}", result.failures);
+        assert_eq!(result.final_text.as_deref(), Some("You hold three assets."));
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/v1/chat/completions");
+        assert_eq!(requests[0].body["model"], "qwen2.5-7b-instruct");
+        assert_eq!(requests[0].body["stream"], true);
+        assert!(requests[0].body["tools"]
+            .as_array()
+            .is_some_and(|tools| !tools.is_empty()));
+    }
+
+    /// Re-records the golden cassettes against a real OpenAI-compatible
+    /// provider. Needs network access and credentials:
+    ///
+    /// ```bash
+    /// WF_EVAL_MODEL=gpt-4o-mini WF_EVAL_API_KEY=... \
+    ///     cargo test -p wealthfolio-ai record_golden_cassettes -- --ignored
+    /// ```
+    ///
+    /// `WF_EVAL_BASE_URL` points the client at another compatible endpoint.
+    #[tokio::test]
+    #[ignore]
+    async fn record_golden_cassettes() {
+        use crate::eval::RecordingModel;
+        use rig::client::CompletionClient;
+
+        let model_id = std::env::var("WF_EVAL_MODEL").expect("WF_EVAL_MODEL is required");
+        let client = crate::chat::create_openai_client(
+            std::env::var("WF_EVAL_API_KEY").ok(),
+            "openai",
+            std::env::var("WF_EVAL_BASE_URL").ok(),
+        )
+        .unwrap();
+
+        for scenario in get_golden_scenarios() {
+            let recorder =
+                RecordingModel::new(client.completion_model(&model_id), "openai", &model_id);
+            let run = run_model(
+                recorder.clone(),
+                scenario.user_query,
+                "openai",
+                ChatConfig::default().max_tool_rounds,
+            )
+            .await;
+            let result = run.to_result(scenario.name);
+            println!(
+                "{}: tools={:?} failures={:?}",
+                scenario.name, result.tool_calls_observed, result.failures
+            );
+
+            let path = cassette_dir().join(format!("{}.json", scenario.name));
+            recorder.cassette().save(&path).unwrap();
+        }
+    }
 }
@@ -6,14 +6,35 @@
 //! - Runs real tools against mock data
 //! - Asserts stream event ordering and guardrail compliance
 //!
+//! Models are replaced by cassettes: one turn of streamed events (text,
+//! reasoning, tool calls, usage, errors) per completion request. Golden
+//! scenarios replay the cassettes in `cassettes/` through the production
+//! stream path; `ReplayModel` also accepts hand-written cassettes for edge
+//! cases. `RecordingModel` wraps a real provider model to capture new ones.
+//! `StubServer` is a loopback OpenAI-compatible endpoint for exercising
+//! user-defined providers through their real HTTP client.
+//!
 //! # Running evals
 //!
 //! ```bash
 //! cargo test -p wealthfolio-ai eval:: -- --nocapture
 //! ```
+//!
+//! # Recording cassettes
+//!
+//! ```bash
+//! WF_EVAL_MODEL=gpt-4o-mini WF_EVAL_API_KEY=... \
+//!     cargo test -p wealthfolio-ai record_golden_cassettes -- --ignored
+//! ```
 
+mod cassette;
 mod harness;
+mod model;
 mod scenarios;
+mod stub_server;
 
+pub use cassette::*;
 pub use harness::*;
+pub use model::*;
 pub use scenarios::*;
+pub use stub_server::*;
@@ -0,0 +1,372 @@
+//! Completion models for offline evals: replay a cassette, or record one from
+//! a real provider.
+
+use futures::StreamExt;
+use rig::{
+    completion::{
+        CompletionError, CompletionModel, CompletionRequest, CompletionResponse, GetTokenUsage,
+        Usage,
+    },
+    message::{Reasoning, Text},
+    streaming::{
+        RawStreamingChoice, RawStreamingToolCall, StreamedAssistantContent,
+        StreamingCompletionResponse,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+use super::cassette::{Cassette, CassetteEvent, RequestSummary, Turn};
+
+fn summarize(request: &CompletionRequest) -> RequestSummary {
+    RequestSummary {
+        tools: request.tools.iter().map(|tool| tool.name.clone()).collect(),
+        message_count: request.chat_history.len(),
+    }
+}
+
+/// Final response payload of a replayed turn.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplayResponse {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+impl GetTokenUsage for ReplayResponse {
+    fn token_usage(&self) -> Option<Usage> {
+        let mut usage = Usage::new();
+        usage.input_tokens = self.input_tokens;
+        usage.output_tokens = self.output_tokens;
+        usage.total_tokens = self.input_tokens + self.output_tokens;
+        Some(usage)
+    }
+}
+
+#[derive(Default)]
+struct ReplayState {
+    next_turn: usize,
+    requests: Vec<RequestSummary>,
+}
+
+/// Replays a cassette: each completion request streams the next turn.
+///
+/// Requests past the last turn fail with a provider error, so a scenario that
+/// loops more than recorded surfaces as a stream error rather than hanging.
+#[derive(Clone)]
+pub struct ReplayModel {
+    cassette: Arc<Cassette>,
+    state: Arc<Mutex<ReplayState>>,
+}
+
+impl ReplayModel {
+    pub fn new(cassette: Cassette) -> Self {
+        Self {
+            cassette: Arc::new(cassette),
+            state: Arc::new(Mutex::new(ReplayState::default())),
+        }
+    }
+
+    /// Requests received so far, in order.
+    pub fn requests(&self) -> Vec<RequestSummary> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// Turns not yet replayed.
+    pub fn remaining_turns(&self) -> usize {
+        self.cassette.turns.len() - self.state.lock().unwrap().next_turn
+    }
+
+    fn next_turn(&self, request: &CompletionRequest) -> Option<Turn> {
+        let mut state = self.state.lock().unwrap();
+        state.requests.push(summarize(request));
+        let turn = self.cassette.turns.get(state.next_turn).cloned();
+        if turn.is_some() {
+            state.next_turn += 1;
+        }
+        turn
+    }
+}
+
+impl CompletionModel for ReplayModel {
+    type Response = ReplayResponse;
+    type StreamingResponse = ReplayResponse;
+    type Client = Cassette;
+
+    fn make(client: &Self::Client, _model: impl Into<String>) -> Self {
+        Self::new(client.clone())
+    }
+
+    async fn completion(
+        &self,
+        _request: CompletionRequest,
+    ) -> Result<CompletionResponse<Self::Response>, CompletionError> {
+        Err(CompletionError::ProviderError(
+            "Replay model only supports streaming".to_string(),
+        ))
+    }
+
+    async fn stream(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<StreamingCompletionResponse<Self::StreamingResponse>, CompletionError> {
+        let turn = self.next_turn(&request).ok_or_else(|| {
+            CompletionError::ProviderError(format!(
+                "Cassette exhausted after {} turns",
+                self.cassette.turns.len()
+            ))
+        })?;
+
+        if let Some(CassetteEvent::Error { message }) = turn.events.first() {
+            return Err(CompletionError::ProviderError(message.clone()));
+        }
+
+        let mut response = ReplayResponse::default();
+        let mut items = Vec::new();
+        for event in turn.events {
+            match event {
+                CassetteEvent::Text { text } => items.push(Ok(RawStreamingChoice::Message(text))),
+                CassetteEvent::Reasoning { text } => {
+                    items.push(Ok(RawStreamingChoice::ReasoningDelta {
+                        id: None,
+                        reasoning: text,
+                    }))
+                }
+                CassetteEvent::ToolCall {
+                    id,
+                    name,
+                    arguments,
+                } => items.push(Ok(RawStreamingChoice::ToolCall(RawStreamingToolCall::new(
+                    id, name, arguments,
+                )))),
+                CassetteEvent::Usage {
+                    input_tokens,
+                    output_tokens,
+                } => {
+                    response.input_tokens += input_tokens;
+                    response.output_tokens += output_tokens;
+                }
+                CassetteEvent::Error { message } => {
+                    items.push(Err(CompletionError::ProviderError(message)));
+                    break;
+                }
+            }
+        }
+        items.push(Ok(RawStreamingChoice::FinalResponse(response)));
+
+        Ok(StreamingCompletionResponse::stream(Box::pin(
+            futures::stream::iter(items),
+        )))
+    }
+}
+
+/// Wraps a real model and records every streamed turn into a cassette.
+///
+/// The wrapped stream is passed through unchanged apart from dropping tool
+/// call deltas; complete tool calls still follow them.
+#[derive(Clone)]
+pub struct RecordingModel<M: CompletionModel> {
+    inner: M,
+    cassette: Arc<Mutex<Cassette>>,
+}
+
+impl<M: CompletionModel> RecordingModel<M> {
+    pub fn new(inner: M, provider_id: &str, model_id: &str) -> Self {
+        Self {
+            inner,
+            cassette: Arc::new(Mutex::new(Cassette::new(provider_id, model_id))),
+        }
+    }
+
+    /// The cassette recorded so far.
+    pub fn cassette(&self) -> Cassette {
+        self.cassette.lock().unwrap().clone()
+    }
+
+    fn record(cassette: &Mutex<Cassette>, turn_index: usize, event: CassetteEvent) {
+        if let Some(turn) = cassette.lock().unwrap().turns.get_mut(turn_index) {
+            turn.events.push(event);
+        }
+    }
+}
+
+impl<M> CompletionModel for RecordingModel<M>
+where
+    M: CompletionModel + 'static,
+    M::StreamingResponse: 'static,
+{
+    type Response = M::Response;
+    type StreamingResponse = M::StreamingResponse;
+    type Client = M::Client;
+
+    fn make(client: &Self::Client, model: impl Into<String>) -> Self {
+        let model = model.into();
+        Self::new(M::make(client, model.clone()), "recorded", &model)
+    }
+
+    async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<CompletionResponse<Self::Response>, CompletionError> {
+        self.inner.completion(request).await
+    }
+
+    async fn stream(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<StreamingCompletionResponse<Self::StreamingResponse>, CompletionError> {
+        let turn_index = {
+            let mut cassette = self.cassette.lock().unwrap();
+            cassette.turns.push(Turn {
+                request: Some(summarize(&request)),
+                events: Vec::new(),
+            });
+            cassette.turns.len() - 1
+        };
+
+        let inner = match self.inner.stream(request).await {
+            Ok(inner) => inner,
+            Err(e) => {
+                Self::record(
+                    &self.cassette,
+                    turn_index,
+                    CassetteEvent::Error {
+                        message: e.to_string(),
+                    },
+                );
+                return Err(e);
+            }
+        };
+
+        let cassette = self.cassette.clone();
+        let recorded = inner.filter_map(move |item| {
+            let (event, choice) = match item {
+                Ok(StreamedAssistantContent::Text(Text { text })) => (
+                    Some(CassetteEvent::Text { text: text.clone() }),
+                    Some(Ok(RawStreamingChoice::Message(text))),
+                ),
+                Ok(StreamedAssistantContent::ReasoningDelta { reasoning, .. }) => (
+                    Some(CassetteEvent::Reasoning {
+                        text: reasoning.clone(),
+                    }),
+                    Some(Ok(RawStreamingChoice::ReasoningDelta {
+                        id: None,
+                        reasoning,
+                    })),
+                ),
+                Ok(StreamedAssistantContent::Reasoning(Reasoning { reasoning, .. })) => {
+                    let text = reasoning.join(" ");
+                    (
+                        Some(CassetteEvent::Reasoning { text: text.clone() }),
+                        Some(Ok(RawStreamingChoice::ReasoningDelta {
+                            id: None,
+                            reasoning: text,
+                        })),
+                    )
+                }
+                Ok(StreamedAssistantContent::ToolCall { tool_call, .. }) => (
+                    Some(CassetteEvent::ToolCall {
+                        id: tool_call.id.clone(),
+                        name: tool_call.function.name.clone(),
+                        arguments: tool_call.function.arguments.clone(),
+                    }),
+                    Some(Ok(RawStreamingChoice::ToolCall(RawStreamingToolCall::new(
+                        tool_call.id,
+                        tool_call.function.name,
+                        tool_call.function.arguments,
+                    )))),
+                ),
+                Ok(StreamedAssistantContent::Final(response)) => (
+                    response.token_usage().map(|usage| CassetteEvent::Usage {
+                        input_tokens: usage.input_tokens,
+                        output_tokens: usage.output_tokens,
+                    }),
+                    Some(Ok(RawStreamingChoice::FinalResponse(response))),
+                ),
+                Ok(_) => (None, None),
+                Err(e) => (
+                    Some(CassetteEvent::Error {
+                        message: e.to_string(),
+                    }),
+                    Some(Err(e)),
+                ),
+            };
+            if let Some(event) = event {
+                Self::record(&cassette, turn_index, event);
+            }
+            futures::future::ready(choice)
+        });
+
+        Ok(StreamingCompletionResponse::stream(Box::pin(recorded)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rig::completion::Message;
+    use serde_json::json;
+
+    fn request(model: &ReplayModel) -> CompletionRequest {
+        model.completion_request(Message::user("hello")).build()
+    }
+
+    #[tokio::test]
+    async fn test_replay_streams_turns_in_order() {
+        let model = ReplayModel::new(Cassette::scripted(vec![
+            Turn::new().tool_call("get_accounts", json!({})),
+            Turn::new().text("Done").usage(10, 2),
+        ]));
+
+        let mut first = model.stream(request(&model)).await.unwrap();
+        let mut tool_calls = 0;
+        while let Some(item) = first.next().await {
+            if let Ok(StreamedAssistantContent::ToolCall { tool_call, .. }) = item {
+                assert_eq!(tool_call.function.name, "get_accounts");
+                tool_calls += 1;
+            }
+        }
+        assert_eq!(tool_calls, 1);
+
+        let mut second = model.stream(request(&model)).await.unwrap();
+        let mut text = String::new();
+        while let Some(item) = second.next().await {
+            if let Ok(StreamedAssistantContent::Text(Text { text: delta })) = item {
+                text.push_str(&delta);
+            }
+        }
+        assert_eq!(text, "Done");
+        assert_eq!(model.remaining_turns(), 0);
+        assert_eq!(model.requests().len(), 2);
+
+        let exhausted = model.stream(request(&model)).await;
+        assert!(exhausted.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replay_error_turn_fails_request() {
+        let model = ReplayModel::new(Cassette::scripted(vec![Turn::new().error("rate limited")]));
+        let error = model.stream(request(&model)).await.err().unwrap();
+        assert!(error.to_string().contains("rate limited"));
+    }
+
+    #[tokio::test]
+    async fn test_recording_captures_replayed_stream() {
+        let source = Cassette::scripted(vec![Turn::new()
+            .reasoning("Check accounts")
+            .tool_call("get_accounts", json!({}))
+            .usage(50, 5)]);
+        let recorder = RecordingModel::new(ReplayModel::new(source.clone()), "replay", "v1");
+
+        let mut stream = recorder
+            .stream(recorder.completion_request(Message::user("hi")).build())
+            .await
+            .unwrap();
+        while stream.next().await.is_some() {}
+
+        let recorded = recorder.cassette();
+        assert_eq!(recorded.provider_id, "replay");
+        assert_eq!(recorded.turns.len(), 1);
+        assert_eq!(recorded.turns[0].events, source.turns[0].events);
+        assert!(recorded.turns[0].request.is_some());
+    }
+}
@@ -94,5 +94,54 @@ pub fn get_golden_scenarios() -> Vec<GoldenScenario> {
             expected_text_contains: vec![],
             multi_round: false,
         },
+        // Scenario 7: Net worth including alternative assets
+        GoldenScenario {
+            name: "net_worth_with_property",
+            user_query: "What's my net worth including the flat?",
+            expected_tool_sequence: vec![ExpectedToolCall::new("get_net_worth")],
+            expected_text_contains: vec![],
+            multi_round: false,
+        },
+        // Scenario 8: Data health issues
+        GoldenScenario {
+            name: "portfolio_value_wrong",
+            user_query: "Why is my portfolio value wrong?",
+            expected_tool_sequence: vec![ExpectedToolCall::new("get_health_issues")],
+            expected_text_contains: vec![],
+            multi_round: false,
+        },
+        // Scenario 9: Historical exchange rate
+        GoldenScenario {
+            name: "fx_rate_on_date",
+            user_query: "What was HKD/USD on 2 May 2024, when I bought my HSBC shares?",
+            expected_tool_sequence: vec![ExpectedToolCall::with_args(
+                "get_exchange_rate",
+                vec!["fromCurrency", "toCurrency", "date"],
+            )],
+            expected_text_contains: vec!["7.8"],
+            multi_round: false,
+        },
+        // Scenario 10: Quote history
+        GoldenScenario {
+            name: "quote_history",
+            user_query: "How has AAPL's price moved over the last year?",
+            expected_tool_sequence: vec![ExpectedToolCall::with_args(
+                "get_quote_history",
+                vec!["symbol"],
+            )],
+            expected_text_contains: vec![],
+            multi_round: false,
+        },
+        // Scenario 11: Alternative assets and liabilities, then net worth
+        GoldenScenario {
+            name: "alternative_assets_and_net_worth",
+            user_query: "List my property and loans, then tell me my net worth",
+            expected_tool_sequence: vec![
+                ExpectedToolCall::new("get_alternative_assets"),
+                ExpectedToolCall::new("get_net_worth"),
+            ],
+            expected_text_contains: vec![],
+            multi_round: true,
+        },
     ]
 }
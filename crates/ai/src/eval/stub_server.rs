@@ -0,0 +1,220 @@
+//! Loopback stub of an OpenAI-compatible server.
+//!
+//! Speaks just enough of the protocol used by LM Studio, vLLM, llama.cpp
+//! server and LocalAI to exercise user-defined providers end to end:
+//! `GET /v1/models` and streaming `POST /v1/chat/completions`. Replies are
+//! canned; every request is recorded for assertions.
+
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// A request received by the stub.
+#[derive(Debug, Clone)]
+pub struct StubRequest {
+    pub method: String,
+    pub path: String,
+    pub authorization: Option<String>,
+    pub body: Value,
+}
+
+/// OpenAI-compatible server on an ephemeral localhost port.
+pub struct StubServer {
+    /// API base including the version path, as a user would enter it.
+    pub base_url: String,
+    requests: Arc<Mutex<Vec<StubRequest>>>,
+}
+
+impl StubServer {
+    /// Serves `models` from the model list and streams `reply` word by word
+    /// for every chat completion.
+    pub async fn start(models: &[&str], reply: &str) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let models: Vec<String> = models.iter().map(|m| m.to_string()).collect();
+        let reply = reply.to_string();
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let models = models.clone();
+                let reply = reply.clone();
+                let recorded = recorded.clone();
+                tokio::spawn(async move {
+                    let _ = handle(stream, &models, &reply, &recorded).await;
+                });
+            }
+        });
+
+        Self { base_url, requests }
+    }
+
+    /// Requests received so far.
+    pub fn requests(&self) -> Vec<StubRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+async fn handle(
+    mut stream: TcpStream,
+    models: &[String],
+    reply: &str,
+    recorded: &Mutex<Vec<StubRequest>>,
+) -> std::io::Result<()> {
+    let request = read_request(&mut stream).await?;
+    recorded.lock().unwrap().push(request.clone());
+
+    let response = match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/v1/models") => {
+            let data: Vec<Value> = models
+                .iter()
+                .map(|id| json!({ "id": id, "object": "model", "owned_by": "stub" }))
+                .collect();
+            http_response(
+                "200 OK",
+                "application/json",
+                &json!({ "object": "list", "data": data }).to_string(),
+            )
+        }
+        ("POST", "/v1/chat/completions") => {
+            let model = request.body["model"].as_str().unwrap_or("stub");
+            if request.body["stream"].as_bool().unwrap_or(false) {
+                http_response("200 OK", "text/event-stream", &sse_body(model, reply))
+            } else {
+                http_response(
+                    "200 OK",
+                    "application/json",
+                    &completion(model, reply).to_string(),
+                )
+            }
+        }
+        _ => http_response(
+            "404 Not Found",
+            "application/json",
+            r#"{"error":{"message":"not found"}}"#,
+        ),
+    };
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<StubRequest> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    let mut authorization = None;
+    for line in lines {
+        if let Some((name, value)) = line.split_once(':') {
+            match name.trim().to_ascii_lowercase().as_str() {
+                "content-length" => content_length = value.trim().parse().unwrap_or(0),
+                "authorization" => authorization = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+    }
+
+    while buffer.len() < header_end + content_length {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+    let body = serde_json::from_slice(&buffer[header_end..]).unwrap_or(Value::Null);
+
+    Ok(StubRequest {
+        method,
+        path,
+        authorization,
+        body,
+    })
+}
+
+fn http_response(status: &str, content_type: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )
+}
+
+fn chunk(model: &str, delta: Value, finish_reason: Option<&str>, usage: Option<Value>) -> Value {
+    json!({
+        "id": "chatcmpl-stub",
+        "object": "chat.completion.chunk",
+        "created": 0,
+        "model": model,
+        "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }],
+        "usage": usage,
+    })
+}
+
+fn sse_body(model: &str, reply: &str) -> String {
+    let mut events = vec![chunk(
+        model,
+        json!({ "role": "assistant", "content": "" }),
+        None,
+        None,
+    )];
+    let words: Vec<&str> = reply.split_inclusive(' ').collect();
+    for word in &words {
+        events.push(chunk(model, json!({ "content": word }), None, None));
+    }
+    events.push(chunk(model, json!({}), Some("stop"), None));
+    events.push(json!({
+        "id": "chatcmpl-stub",
+        "object": "chat.completion.chunk",
+        "created": 0,
+        "model": model,
+        "choices": [],
+        "usage": {
+            "prompt_tokens": 12,
+            "completion_tokens": words.len(),
+            "total_tokens": 12 + words.len(),
+        },
+    }));
+
+    let mut body: String = events
+        .iter()
+        .map(|event| format!("data: {}\n\n", event))
+        .collect();
+    body.push_str("data: [DONE]\n\n");
+    body
+}
+
+fn completion(model: &str, reply: &str) -> Value {
+    json!({
+        "id": "chatcmpl-stub",
+        "object": "chat.completion",
+        "created": 0,
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": reply },
+            "finish_reason": "stop",
+        }],
+        "usage": { "prompt_tokens": 12, "completion_tokens": 3, "total_tokens": 15 },
+    })
+}
@@ -7,14 +7,19 @@
 //! # Architecture
 //!
 //! - `chat`: Main streaming chat service with tool execution loop
+//! - `context`: Token-budgeted thread history with rolling summaries
+//! - `digest`: Scheduled weekly portfolio digest written into its own thread
 //! - `providers`: Provider catalog and rig-core client factory
 //! - `tools`: Tool registry, schemas, and bounded outputs
 //! - `types`: Shared DTOs/events used by Axum/Tauri + frontend
 //! - `env`: Environment abstraction for services/secrets/config
 //! - `title_generator`: Auto-generates thread titles from user messages
 //! - `eval`: Behavioral evaluation harness (test only)
+//! - `mcp`: Model Context Protocol server exposing the toolset to external clients
 //! - `provider_model`: AI provider domain models (catalog, settings, merged views)
 //! - `provider_service`: AI provider service for settings management
+//! - `redaction`: Privacy redaction of tool output for cloud providers
+//! - `usage`: Token usage reports, estimated cost, and monthly budgets
 //! - `prompt_template`: Versioned prompt templates
 //! - `prompt_template_service`: Prompt template service
 //!
@@ -48,24 +53,32 @@
 //! ```
 
 pub mod chat;
+pub mod context;
+pub mod digest;
 pub mod env;
 pub mod error;
 #[cfg(test)]
 pub mod eval;
+pub mod mcp;
 pub mod prompt_template;
 pub mod prompt_template_service;
 pub mod provider_model;
 pub mod provider_service;
 pub mod providers;
+pub mod redaction;
 pub mod title_generator;
 pub mod tools;
 pub mod types;
+pub mod usage;
 
 // Re-export main types for convenience
 pub use chat::{ChatConfig, ChatService};
+pub use digest::{DigestData, DigestService, DIGEST_THREAD_TAG};
 pub use env::AiEnvironment;
 pub use error::AiError;
+pub use mcp::{McpAccess, McpServer};
 pub use providers::ProviderService;
+pub use redaction::{AmountRedaction, RedactionSettings};
 pub use title_generator::{
     truncate_to_title, FakeTitleGenerator, TitleGenerator, TitleGeneratorConfig,
     TitleGeneratorTrait,
@@ -90,12 +103,15 @@ pub use types::{
     ChatThreadConfig,
     // Pagination types
     ListThreadsRequest,
+    MessageUsage,
     SendMessageRequest,
     SimpleChatMessage,
     ThreadPage,
+    ThreadSummary,
     ToolCall,
     ToolResult,
     ToolResultData,
+    UsageRecord,
     UsageStats,
     // Constants
     CHAT_CONFIG_SCHEMA_VERSION,
@@ -116,6 +132,7 @@ pub use provider_model::{
     CatalogModel,
     CatalogProvider,
     ConnectionField,
+    CustomProviderConfig,
     // Provider config types
     FetchedModel,
     ListModelsResponse,
@@ -125,12 +142,15 @@ pub use provider_model::{
     // Update types
     ModelCapabilityOverrideUpdate,
     ModelCapabilityOverrides,
+    ModelPricing,
     // Provider API error
     ProviderApiError,
     ProviderConfig,
     ProviderDefaultConfig,
     ProviderUserSettings,
+    SaveCustomProviderRequest,
     SetDefaultProviderRequest,
+    SetUsageBudgetRequest,
     UpdateProviderSettingsRequest,
     // Constants
     AI_PROVIDER_SETTINGS_KEY,
@@ -140,6 +160,12 @@ pub use provider_model::{
 // Provider service
 pub use provider_service::{AiProviderService, AiProviderServiceTrait};
 
+// Usage accounting
+pub use usage::{
+    BudgetAction, BudgetLevel, BudgetStatus, DailyUsage, ModelUsage, ProviderUsage, ThreadUsage,
+    UsageBudget, UsageReport, UsageService, UsageTotals,
+};
+
 // Prompt template types
 pub use prompt_template::{
     ChatRunConfig, DetailLevel, KnobType, PromptTemplate, PromptTemplateCatalog,
@@ -149,6 +175,6 @@ pub use prompt_template::{
 
 // Prompt template service
 pub use prompt_template_service::{
-    build_run_config_from_context, PromptTemplateInfo, PromptTemplateService,
-    PromptTemplateServiceTrait,
+    build_digest_run_config, build_run_config_from_context, PromptTemplateInfo,
+    PromptTemplateService, PromptTemplateServiceTrait, DIGEST_TEMPLATE_ID, DIGEST_TEMPLATE_VERSION,
 };
@@ -0,0 +1,19 @@
+//! Model Context Protocol server for the portfolio toolset.
+//!
+//! Exposes the same tools as the built-in chat ([`ToolSet`](crate::tools::ToolSet))
+//! to other local agents and editors. [`McpServer`] handles JSON-RPC messages
+//! and is transport-agnostic:
+//!
+//! - `stdio`: newline-delimited JSON over stdin/stdout, used by the desktop app
+//! - Streamable HTTP: the Axum server posts each message to [`McpServer::handle_message`]
+//!
+//! Tools that draft activities are only listed for [`McpAccess::ReadWrite`] clients.
+
+mod server;
+mod stdio;
+
+pub use server::{
+    parse_error, McpAccess, McpServer, MCP_PROTOCOL_VERSION, MCP_SUPPORTED_PROTOCOL_VERSIONS,
+    WRITE_TOOLS,
+};
+pub use stdio::{serve, serve_stdio};
@@ -0,0 +1,390 @@
+//! JSON-RPC handling for the MCP server.
+
+use log::debug;
+use rig::tool::ToolDyn;
+use serde_json::{json, Value};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::env::AiEnvironment;
+use crate::error::AiError;
+use crate::tools::ToolSet;
+
+/// Protocol version advertised when the client asks for one we don't know.
+pub const MCP_PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Protocol versions this server can speak, newest first.
+pub const MCP_SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// Tools that draft changes to the portfolio; hidden from read-only clients.
+pub const WRITE_TOOLS: &[&str] = &["record_activity", "record_activities", "import_csv"];
+
+const SERVER_NAME: &str = "wealthfolio";
+
+const SERVER_INSTRUCTIONS: &str = "Tools over the user's Wealthfolio portfolio: \
+accounts, holdings, allocation, performance, valuation history, activities, income and goals. \
+Amounts are in the base currency unless a currency is given. When write access is granted, \
+record_activity, record_activities and import_csv return activity drafts for the user to confirm.";
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// What an MCP client may do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum McpAccess {
+    ReadOnly,
+    ReadWrite,
+}
+
+impl McpAccess {
+    pub fn allows_writes(self) -> bool {
+        self == McpAccess::ReadWrite
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            McpAccess::ReadOnly => "read-only",
+            McpAccess::ReadWrite => "read-write",
+        }
+    }
+}
+
+impl FromStr for McpAccess {
+    type Err = AiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "read" | "read-only" | "readonly" | "ro" => Ok(McpAccess::ReadOnly),
+            "write" | "read-write" | "readwrite" | "rw" => Ok(McpAccess::ReadWrite),
+            other => Err(AiError::invalid_input(format!(
+                "Unknown MCP access level '{}', expected read or write",
+                other
+            ))),
+        }
+    }
+}
+
+/// MCP server backed by an [`AiEnvironment`].
+///
+/// Tools are built per request so they always use the current base currency.
+pub struct McpServer<E: AiEnvironment> {
+    env: Arc<E>,
+    access: McpAccess,
+}
+
+impl<E: AiEnvironment + 'static> McpServer<E> {
+    pub fn new(env: Arc<E>, access: McpAccess) -> Self {
+        Self { env, access }
+    }
+
+    pub fn access(&self) -> McpAccess {
+        self.access
+    }
+
+    fn tools(&self) -> Vec<Box<dyn ToolDyn>> {
+        let tool_set = ToolSet::new(self.env.clone(), self.env.base_currency());
+        let mut tools: Vec<Box<dyn ToolDyn>> = vec![
+            Box::new(tool_set.accounts),
+            Box::new(tool_set.holdings),
+            Box::new(tool_set.allocation),
+            Box::new(tool_set.performance),
+            Box::new(tool_set.valuation),
+            Box::new(tool_set.activities),
+            Box::new(tool_set.income),
+            Box::new(tool_set.goals),
+            Box::new(tool_set.net_worth),
+            Box::new(tool_set.alternative_assets),
+            Box::new(tool_set.health),
+            Box::new(tool_set.exchange_rate),
+            Box::new(tool_set.quote_history),
+        ];
+        if self.access.allows_writes() {
+            tools.push(Box::new(tool_set.record_activity));
+            tools.push(Box::new(tool_set.record_activities));
+            tools.push(Box::new(tool_set.import_csv));
+        }
+        tools
+    }
+
+    /// Handles a single JSON-RPC message or a batch. Returns `None` when
+    /// nothing needs to be sent back (notifications and responses).
+    pub async fn handle_message(&self, message: Value) -> Option<Value> {
+        match message {
+            Value::Array(batch) => {
+                if batch.is_empty() {
+                    return Some(error_response(
+                        Value::Null,
+                        INVALID_REQUEST,
+                        "Empty batch".to_string(),
+                    ));
+                }
+                let mut responses = Vec::new();
+                for message in batch {
+                    if let Some(response) = self.handle_single(message).await {
+                        responses.push(response);
+                    }
+                }
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            message => self.handle_single(message).await,
+        }
+    }
+
+    async fn handle_single(&self, message: Value) -> Option<Value> {
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // Responses to server requests; we never send any
+            if message.get("result").is_some() || message.get("error").is_some() {
+                return None;
+            }
+            let id = message.get("id").cloned().unwrap_or(Value::Null);
+            return Some(error_response(
+                id,
+                INVALID_REQUEST,
+                "Missing method".to_string(),
+            ));
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        // Notifications carry no id and get no response
+        let Some(id) = message.get("id").cloned() else {
+            debug!("MCP notification: {}", method);
+            return None;
+        };
+
+        let result = match method {
+            "initialize" => Ok(self.initialize(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(self.list_tools().await),
+            "tools/call" => self.call_tool(&params).await,
+            other => Err((METHOD_NOT_FOUND, format!("Method not found: {}", other))),
+        };
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, message),
+        })
+    }
+
+    fn initialize(&self, params: &Value) -> Value {
+        let requested = params.get("protocolVersion").and_then(Value::as_str);
+        let protocol_version = requested
+            .filter(|v| MCP_SUPPORTED_PROTOCOL_VERSIONS.contains(v))
+            .unwrap_or(MCP_PROTOCOL_VERSION);
+
+        json!({
+            "protocolVersion": protocol_version,
+            "capabilities": { "tools": { "listChanged": false } },
+            "serverInfo": {
+                "name": SERVER_NAME,
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "instructions": SERVER_INSTRUCTIONS,
+        })
+    }
+
+    async fn list_tools(&self) -> Value {
+        let mut tools = Vec::new();
+        for tool in self.tools() {
+            let definition = tool.definition(String::new()).await;
+            let read_only = !WRITE_TOOLS.contains(&definition.name.as_str());
+            tools.push(json!({
+                "name": definition.name,
+                "description": definition.description,
+                "inputSchema": definition.parameters,
+                "annotations": { "readOnlyHint": read_only },
+            }));
+        }
+        json!({ "tools": tools })
+    }
+
+    async fn call_tool(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or((INVALID_PARAMS, "Missing tool name".to_string()))?;
+        let arguments = match params.get("arguments") {
+            None | Some(Value::Null) => json!({}),
+            Some(arguments) => arguments.clone(),
+        };
+
+        let tools = self.tools();
+        let Some(tool) = tools.iter().find(|tool| tool.name() == name) else {
+            let error = if WRITE_TOOLS.contains(&name) {
+                AiError::ToolNotAllowed(name.to_string())
+            } else {
+                AiError::ToolNotFound(name.to_string())
+            };
+            return Err((INVALID_PARAMS, error.to_string()));
+        };
+
+        debug!("MCP tool call: {}", name);
+        // Tool failures are reported in the result so the model can react to them
+        let (text, is_error) = match tool.call(arguments.to_string()).await {
+            Ok(output) => (output, false),
+            Err(e) => (
+                AiError::ToolExecutionFailed(e.to_string()).to_string(),
+                true,
+            ),
+        };
+        Ok(json!({
+            "content": [{ "type": "text", "text": text }],
+            "isError": is_error,
+        }))
+    }
+}
+
+/// Response for a message that could not be parsed as JSON.
+pub fn parse_error(error: impl std::fmt::Display) -> Value {
+    error_response(Value::Null, PARSE_ERROR, format!("Parse error: {}", error))
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test_env::MockEnvironment;
+
+    fn server(access: McpAccess) -> McpServer<MockEnvironment> {
+        McpServer::new(Arc::new(MockEnvironment::new()), access)
+    }
+
+    fn request(id: i64, method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+    }
+
+    async fn tool_names(server: &McpServer<MockEnvironment>) -> Vec<String> {
+        let response = server
+            .handle_message(request(1, "tools/list", json!({})))
+            .await
+            .unwrap();
+        response["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_initialize_negotiates_protocol_version() {
+        let server = server(McpAccess::ReadOnly);
+
+        let known = server
+            .handle_message(request(
+                1,
+                "initialize",
+                json!({ "protocolVersion": "2025-03-26" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(known["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(known["result"]["serverInfo"]["name"], "wealthfolio");
+
+        let unknown = server
+            .handle_message(request(
+                2,
+                "initialize",
+                json!({ "protocolVersion": "1999" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(unknown["result"]["protocolVersion"], MCP_PROTOCOL_VERSION);
+    }
+
+    #[tokio::test]
+    async fn test_notifications_get_no_response() {
+        let server = server(McpAccess::ReadOnly);
+        let response = server
+            .handle_message(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+            .await;
+        assert!(response.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_only_hides_write_tools() {
+        let read_only = tool_names(&server(McpAccess::ReadOnly)).await;
+        assert!(read_only.contains(&"get_holdings".to_string()));
+        assert!(WRITE_TOOLS
+            .iter()
+            .all(|tool| !read_only.contains(&tool.to_string())));
+
+        let read_write = tool_names(&server(McpAccess::ReadWrite)).await;
+        assert!(WRITE_TOOLS
+            .iter()
+            .all(|tool| read_write.contains(&tool.to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_call_tool() {
+        let server = server(McpAccess::ReadOnly);
+        let response = server
+            .handle_message(request(
+                3,
+                "tools/call",
+                json!({ "name": "get_accounts", "arguments": {} }),
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(response["id"], 3);
+        assert_eq!(response["result"]["isError"], false);
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        let output: Value = serde_json::from_str(text).unwrap();
+        assert!(output["accounts"].is_array());
+    }
+
+    #[tokio::test]
+    async fn test_write_tool_rejected_when_read_only() {
+        let server = server(McpAccess::ReadOnly);
+        let response = server
+            .handle_message(request(
+                4,
+                "tools/call",
+                json!({ "name": "record_activity", "arguments": {} }),
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+        assert!(response["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("not allowed"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_method() {
+        let server = server(McpAccess::ReadOnly);
+        let response = server
+            .handle_message(request(5, "resources/list", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn test_access_parsing() {
+        assert_eq!(
+            "read-only".parse::<McpAccess>().unwrap(),
+            McpAccess::ReadOnly
+        );
+        assert_eq!("RW".parse::<McpAccess>().unwrap(), McpAccess::ReadWrite);
+        assert_eq!("read".parse::<McpAccess>().unwrap(), McpAccess::ReadOnly);
+        assert_eq!("write".parse::<McpAccess>().unwrap(), McpAccess::ReadWrite);
+        assert!("admin".parse::<McpAccess>().is_err());
+        assert_eq!(
+            McpAccess::ReadWrite.min(McpAccess::ReadOnly),
+            McpAccess::ReadOnly
+        );
+    }
+}
@@ -0,0 +1,78 @@
+//! stdio transport: one JSON-RPC message per line on stdin/stdout.
+
+use serde_json::Value;
+use std::io;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+use super::server::{parse_error, McpServer};
+use crate::env::AiEnvironment;
+
+/// Serves MCP over the process's stdin/stdout until stdin closes.
+///
+/// Nothing else may write to stdout while this runs; log to stderr or a file.
+pub async fn serve_stdio<E: AiEnvironment + 'static>(server: &McpServer<E>) -> io::Result<()> {
+    serve(server, tokio::io::stdin(), tokio::io::stdout()).await
+}
+
+/// Serves newline-delimited JSON-RPC from `reader` to `writer` until EOF.
+pub async fn serve<E, R, W>(server: &McpServer<E>, reader: R, mut writer: W) -> io::Result<()>
+where
+    E: AiEnvironment + 'static,
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await? {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Value>(line) {
+            Ok(message) => server.handle_message(message).await,
+            Err(e) => Some(parse_error(e)),
+        };
+        if let Some(response) = response {
+            let mut bytes = serde_json::to_vec(&response)?;
+            bytes.push(b'\n');
+            writer.write_all(&bytes).await?;
+            writer.flush().await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test_env::MockEnvironment;
+    use crate::mcp::McpAccess;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_serve_round_trip() {
+        let server = McpServer::new(Arc::new(MockEnvironment::new()), McpAccess::ReadOnly);
+        let input = concat!(
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+            "\n\n",
+            "not json\n",
+            r#"{"jsonrpc":"2.0","id":2,"method":"ping"}"#,
+            "\n",
+        );
+        let mut output = Vec::new();
+
+        serve(&server, input.as_bytes(), &mut output).await.unwrap();
+
+        let responses: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[1]["error"]["code"], -32700);
+        assert_eq!(responses[2]["id"], 2);
+    }
+}
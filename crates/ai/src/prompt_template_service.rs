@@ -29,6 +29,12 @@ pub trait PromptTemplateServiceTrait: Send + Sync {
     fn build_system_prompt(&self, config: &ChatRunConfig) -> Result<String>;
 }
 
+/// Template used for the scheduled portfolio digest.
+pub const DIGEST_TEMPLATE_ID: &str = "wealthfolio-digest-v1";
+
+/// Version of the digest template.
+pub const DIGEST_TEMPLATE_VERSION: &str = "1.0.0";
+
 /// Summary info about a template.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -61,6 +67,11 @@ impl PromptTemplateService {
         Ok(Self { catalog })
     }
 
+    /// Create a service over the templates bundled with this crate.
+    pub fn builtin() -> Result<Self> {
+        Self::new(include_str!("prompt_templates.json"))
+    }
+
     /// Get the catalog reference.
     pub fn catalog(&self) -> &PromptTemplateCatalog {
         &self.catalog
@@ -140,6 +151,16 @@ pub fn build_run_config_from_context(
     }
 }
 
+/// Build the run configuration for the scheduled portfolio digest.
+pub fn build_digest_run_config(locale: Option<&str>) -> ChatRunConfig {
+    ChatRunConfig {
+        template_id: DIGEST_TEMPLATE_ID.to_string(),
+        template_version: DIGEST_TEMPLATE_VERSION.to_string(),
+        locale: locale.map(|s| s.to_string()),
+        detail_level: DetailLevel::Standard,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -218,4 +239,18 @@ mod tests {
         let prompt = service.build_system_prompt(&config).unwrap();
         assert!(prompt.contains("Wealthfolio Assistant"));
     }
+
+    #[test]
+    fn test_builtin_digest_template() {
+        let service = PromptTemplateService::builtin().unwrap();
+        let template = service.get_template(DIGEST_TEMPLATE_ID).unwrap();
+        assert_eq!(template.version, DIGEST_TEMPLATE_VERSION);
+
+        let prompt = service
+            .build_system_prompt(&build_digest_run_config(Some("fr-FR")))
+            .unwrap();
+        assert!(prompt.contains("weekly portfolio digest"));
+        assert!(prompt.contains("You have no tools"));
+        assert!(prompt.contains("fr-FR"));
+    }
 }
@@ -3,6 +3,9 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::redaction::RedactionSettings;
+use crate::usage::UsageBudget;
+
 /// Current schema version for AI provider settings.
 /// Increment when making breaking changes to the settings structure.
 pub const AI_PROVIDER_SETTINGS_SCHEMA_VERSION: u32 = 1;
@@ -31,8 +34,38 @@ fn default_streaming() -> bool {
 
 /// A single model definition from the catalog.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CatalogModel {
     pub capabilities: ModelCapabilities,
+    /// Context window size in tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u32>,
+    /// List price in USD, used to estimate the cost of recorded usage.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pricing: Option<ModelPricing>,
+}
+
+/// Model list price in USD per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPricing {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+impl ModelPricing {
+    /// Free usage (local providers, free-tier models).
+    pub const FREE: ModelPricing = ModelPricing {
+        input_per_million: 0.0,
+        output_per_million: 0.0,
+    };
+
+    /// Estimated cost in USD for the given token counts.
+    pub fn cost_usd(&self, prompt_tokens: u64, completion_tokens: u64) -> f64 {
+        (prompt_tokens as f64 * self.input_per_million
+            + completion_tokens as f64 * self.output_per_million)
+            / 1_000_000.0
+    }
 }
 
 /// Connection field definition for provider configuration UI.
@@ -150,6 +183,10 @@ pub struct ProviderUserSettings {
     /// None = all tools enabled (default), Some([]) = no tools, Some([...]) = only specified tools.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tools_allowlist: Option<Vec<String>>,
+    /// Privacy redaction override. None = default for the provider type
+    /// (on for cloud providers, off for local ones).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redaction: Option<RedactionSettings>,
 }
 
 impl Default for ProviderUserSettings {
@@ -163,6 +200,53 @@ impl Default for ProviderUserSettings {
             model_capability_overrides: HashMap::new(),
             favorite_models: Vec::new(),
             tools_allowlist: None,
+            redaction: None,
+        }
+    }
+}
+
+/// Provider type of user-defined OpenAI-compatible entries.
+pub const CUSTOM_PROVIDER_TYPE: &str = "custom";
+
+/// Prefix of generated custom provider IDs, keeps them apart from catalog IDs.
+pub const CUSTOM_PROVIDER_ID_PREFIX: &str = "custom-";
+
+/// A user-defined OpenAI-compatible provider (LM Studio, vLLM, llama.cpp server, LocalAI).
+/// The API key, if any, lives in the secret store like catalog providers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomProviderConfig {
+    pub id: String,
+    pub name: String,
+    /// API base including the version path, e.g. `http://localhost:1234/v1`.
+    pub base_url: String,
+    /// Capabilities assumed for every model of this endpoint, before per-model overrides.
+    pub capabilities: ModelCapabilities,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_model: Option<String>,
+    /// Context window size in tokens, when the served models are known to support more
+    /// than the conservative default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u32>,
+}
+
+impl CustomProviderConfig {
+    /// Whether the endpoint runs on this machine. Local endpoints are free and
+    /// default to no redaction, like catalog local providers.
+    pub fn is_local(&self) -> bool {
+        reqwest::Url::parse(&self.base_url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .map(|host| matches!(host.as_str(), "localhost" | "127.0.0.1" | "[::1]" | "::1"))
+            .unwrap_or(false)
+    }
+
+    /// Provider type used for defaults that depend on where data is sent.
+    pub fn effective_provider_type(&self) -> &'static str {
+        if self.is_local() {
+            "local"
+        } else {
+            "api"
         }
     }
 }
@@ -179,6 +263,19 @@ pub struct AiProviderSettings {
     /// Per-provider user settings keyed by provider ID.
     #[serde(default)]
     pub providers: HashMap<String, ProviderUserSettings>,
+    /// Monthly spending limit across all providers. None = no limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage_budget: Option<UsageBudget>,
+    /// User-defined OpenAI-compatible providers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_providers: Vec<CustomProviderConfig>,
+}
+
+impl AiProviderSettings {
+    /// Find a user-defined provider by ID.
+    pub fn custom_provider(&self, provider_id: &str) -> Option<&CustomProviderConfig> {
+        self.custom_providers.iter().find(|p| p.id == provider_id)
+    }
 }
 
 impl Default for AiProviderSettings {
@@ -187,6 +284,8 @@ impl Default for AiProviderSettings {
             schema_version: AI_PROVIDER_SETTINGS_SCHEMA_VERSION,
             default_provider: None,
             providers: HashMap::new(),
+            usage_budget: None,
+            custom_providers: Vec::new(),
         }
     }
 }
@@ -249,6 +348,8 @@ pub struct MergedProvider {
     /// None = all tools enabled (default), Some([]) = no tools, Some([...]) = only specified tools.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tools_allowlist: Option<Vec<String>>,
+    /// Effective privacy redaction settings (user override or provider-type default).
+    pub redaction: RedactionSettings,
 
     // Computed
     pub has_api_key: bool,
@@ -265,6 +366,12 @@ pub struct AiProvidersResponse {
     pub capabilities: HashMap<String, CapabilityInfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default_provider: Option<String>,
+    /// Monthly spending limit, if configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage_budget: Option<UsageBudget>,
+    /// Stored configuration of user-defined providers, for editing.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_providers: Vec<CustomProviderConfig>,
 }
 
 // ============================================================================
@@ -296,6 +403,10 @@ pub struct UpdateProviderSettingsRequest {
     /// Use Some(None) to clear (all tools enabled), Some(Some([])) to set specific tools.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tools_allowlist: Option<Option<Vec<String>>>,
+    /// Update privacy redaction.
+    /// Use Some(None) to reset to the provider-type default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redaction: Option<Option<RedactionSettings>>,
 }
 
 /// Update for a single model's capability overrides.
@@ -316,6 +427,30 @@ pub struct SetDefaultProviderRequest {
     pub provider_id: Option<String>,
 }
 
+/// Request to add or update a user-defined OpenAI-compatible provider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveCustomProviderRequest {
+    /// Existing provider ID to update; omitted when adding a new provider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    pub base_url: String,
+    pub capabilities: ModelCapabilities,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u32>,
+}
+
+/// Request to set the monthly usage budget.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetUsageBudgetRequest {
+    /// Budget to enforce, or None to remove the limit.
+    pub budget: Option<UsageBudget>,
+}
+
 // ============================================================================
 // Provider API Error Types
 // ============================================================================
@@ -9,11 +9,14 @@ use wealthfolio_core::secrets::SecretStore;
 use wealthfolio_core::settings::SettingsRepositoryTrait;
 
 use crate::provider_model::{
-    default_priority, AiProviderCatalog, AiProviderSettings, AiProvidersResponse, FetchedModel,
-    ListModelsResponse, MergedModel, MergedProvider, ModelCapabilities, ModelCapabilityOverrides,
-    ProviderApiError, ProviderConfig, ProviderUserSettings, SetDefaultProviderRequest,
-    UpdateProviderSettingsRequest, AI_PROVIDER_SETTINGS_KEY, AI_PROVIDER_SETTINGS_SCHEMA_VERSION,
+    default_priority, AiProviderCatalog, AiProviderSettings, AiProvidersResponse,
+    CustomProviderConfig, FetchedModel, ListModelsResponse, MergedModel, MergedProvider,
+    ModelCapabilities, ModelCapabilityOverrides, ProviderApiError, ProviderConfig,
+    ProviderUserSettings, SaveCustomProviderRequest, SetDefaultProviderRequest,
+    SetUsageBudgetRequest, UpdateProviderSettingsRequest, AI_PROVIDER_SETTINGS_KEY,
+    AI_PROVIDER_SETTINGS_SCHEMA_VERSION, CUSTOM_PROVIDER_ID_PREFIX, CUSTOM_PROVIDER_TYPE,
 };
+use crate::redaction::RedactionSettings;
 
 /// Service trait for AI provider operations.
 #[async_trait]
@@ -27,6 +30,15 @@ pub trait AiProviderServiceTrait: Send + Sync {
     /// Set or clear the default provider.
     async fn set_default_provider(&self, request: SetDefaultProviderRequest) -> Result<()>;
 
+    /// Set or clear the monthly usage budget.
+    async fn set_usage_budget(&self, request: SetUsageBudgetRequest) -> Result<()>;
+
+    /// Add or update a user-defined OpenAI-compatible provider. Returns its ID.
+    async fn save_custom_provider(&self, request: SaveCustomProviderRequest) -> Result<String>;
+
+    /// Remove a user-defined provider along with its settings and API key.
+    async fn delete_custom_provider(&self, provider_id: &str) -> Result<()>;
+
     /// Get provider configuration for backend-only use (chat, model listing).
     /// This retrieves the API key from the secret store - never exposed to frontend.
     /// Returns ProviderApiError::MissingApiKey if API key is required but not configured.
@@ -145,8 +157,25 @@ impl AiProviderService {
             .filter(|s| !s.is_empty())
     }
 
+    /// Check if a provider is in the catalog or user-defined.
+    fn provider_exists(&self, provider_id: &str) -> bool {
+        self.catalog.providers.contains_key(provider_id)
+            || self
+                .load_user_settings()
+                .custom_provider(provider_id)
+                .is_some()
+    }
+
     /// Check if provider requires an API key based on catalog.
+    /// User-defined endpoints often run without authentication, so their key is optional.
     fn provider_requires_api_key(&self, provider_id: &str) -> bool {
+        if self
+            .load_user_settings()
+            .custom_provider(provider_id)
+            .is_some()
+        {
+            return false;
+        }
         self.catalog
             .providers
             .get(provider_id)
@@ -161,6 +190,9 @@ impl AiProviderService {
     /// Get the custom URL for a provider from user settings.
     fn get_custom_url(&self, provider_id: &str) -> Option<String> {
         let user_settings = self.load_user_settings();
+        if let Some(custom) = user_settings.custom_provider(provider_id) {
+            return Some(custom.base_url.clone());
+        }
         user_settings
             .providers
             .get(provider_id)
@@ -191,6 +223,129 @@ impl AiProviderService {
             streaming: overrides.streaming.unwrap_or(base.streaming),
         }
     }
+
+    /// Models saved in user settings (selected, favorite or overridden) that are not
+    /// listed in the catalog, so they continue to round-trip through the UI.
+    fn runtime_models(
+        user: &ProviderUserSettings,
+        is_catalog_model: impl Fn(&str) -> bool,
+        base_capabilities: &ModelCapabilities,
+    ) -> Vec<MergedModel> {
+        let mut non_catalog_model_ids: HashSet<String> = HashSet::new();
+        if let Some(selected_model) = &user.selected_model {
+            if !is_catalog_model(selected_model) {
+                non_catalog_model_ids.insert(selected_model.clone());
+            }
+        }
+        for favorite_model in &user.favorite_models {
+            if !is_catalog_model(favorite_model) {
+                non_catalog_model_ids.insert(favorite_model.clone());
+            }
+        }
+        for model_id in user.model_capability_overrides.keys() {
+            if !is_catalog_model(model_id) {
+                non_catalog_model_ids.insert(model_id.clone());
+            }
+        }
+
+        non_catalog_model_ids
+            .into_iter()
+            .map(|model_id| {
+                let has_overrides = user.model_capability_overrides.contains_key(&model_id);
+                let capabilities =
+                    if let Some(overrides) = user.model_capability_overrides.get(&model_id) {
+                        Self::apply_capability_overrides(base_capabilities, overrides)
+                    } else {
+                        base_capabilities.clone()
+                    };
+
+                MergedModel {
+                    id: model_id.clone(),
+                    name: Some(model_id.clone()),
+                    capabilities,
+                    is_catalog: false,
+                    is_favorite: user.favorite_models.contains(&model_id),
+                    has_capability_overrides: has_overrides,
+                }
+            })
+            .collect()
+    }
+
+    /// Merge a user-defined provider with its user settings.
+    fn merge_custom_provider(
+        &self,
+        custom: &CustomProviderConfig,
+        settings: &AiProviderSettings,
+    ) -> MergedProvider {
+        let user = settings
+            .providers
+            .get(&custom.id)
+            .cloned()
+            .unwrap_or_default();
+
+        let mut models = Self::runtime_models(&user, |_| false, &custom.capabilities);
+        models.sort_by(|a, b| a.id.cmp(&b.id));
+
+        MergedProvider {
+            id: custom.id.clone(),
+            name: custom.name.clone(),
+            provider_type: CUSTOM_PROVIDER_TYPE.to_string(),
+            icon: "custom".to_string(),
+            description: "OpenAI-compatible endpoint".to_string(),
+            env_key: String::new(),
+            connection_fields: Vec::new(),
+            models,
+            default_model: custom.default_model.clone().unwrap_or_default(),
+            documentation_url: String::new(),
+            enabled: user.enabled,
+            favorite: user.favorite,
+            selected_model: user.selected_model,
+            custom_url: Some(custom.base_url.clone()),
+            priority: user.priority,
+            favorite_models: user.favorite_models.clone(),
+            model_capability_overrides: user.model_capability_overrides.clone(),
+            tools_allowlist: user.tools_allowlist.clone(),
+            redaction: user.redaction.clone().unwrap_or_else(|| {
+                RedactionSettings::default_for_provider_type(custom.effective_provider_type())
+            }),
+            has_api_key: self.has_api_key(&custom.id),
+            is_default: settings.default_provider.as_ref() == Some(&custom.id),
+            supports_model_listing: true,
+        }
+    }
+
+    /// Generate a stable, unique ID for a new user-defined provider from its name.
+    fn new_custom_provider_id(&self, name: &str, settings: &AiProviderSettings) -> String {
+        let mut slug = String::new();
+        for c in name.trim().to_lowercase().chars() {
+            if c.is_ascii_alphanumeric() {
+                slug.push(c);
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        let slug = slug.trim_end_matches('-');
+        let base = if slug.is_empty() {
+            format!("{}provider", CUSTOM_PROVIDER_ID_PREFIX)
+        } else {
+            format!("{}{}", CUSTOM_PROVIDER_ID_PREFIX, slug)
+        };
+
+        let is_taken = |id: &str| {
+            self.catalog.providers.contains_key(id) || settings.custom_provider(id).is_some()
+        };
+        let mut id = base.clone();
+        let mut suffix = 2;
+        while is_taken(&id) {
+            id = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+        id
+    }
+}
+
+fn invalid_input(message: impl Into<String>) -> wealthfolio_core::errors::Error {
+    wealthfolio_core::errors::Error::Validation(ValidationError::InvalidInput(message.into()))
 }
 
 #[async_trait]
@@ -242,47 +397,17 @@ impl AiProviderServiceTrait for AiProviderService {
 
                 // Preserve user-selected/favorite runtime models that are not part of the static
                 // catalog so they continue to round-trip through the UI.
-                let mut non_catalog_model_ids: HashSet<String> = HashSet::new();
-                if let Some(selected_model) = &user.selected_model {
-                    if !catalog_provider.models.contains_key(selected_model) {
-                        non_catalog_model_ids.insert(selected_model.clone());
-                    }
-                }
-                for favorite_model in &user.favorite_models {
-                    if !catalog_provider.models.contains_key(favorite_model) {
-                        non_catalog_model_ids.insert(favorite_model.clone());
-                    }
-                }
-                for model_id in user.model_capability_overrides.keys() {
-                    if !catalog_provider.models.contains_key(model_id) {
-                        non_catalog_model_ids.insert(model_id.clone());
-                    }
-                }
-
-                for model_id in non_catalog_model_ids {
-                    let has_overrides = user.model_capability_overrides.contains_key(&model_id);
-                    let base_capabilities = ModelCapabilities {
-                        tools: false,
-                        thinking: false,
-                        vision: false,
-                        streaming: true,
-                    };
-                    let capabilities =
-                        if let Some(overrides) = user.model_capability_overrides.get(&model_id) {
-                            Self::apply_capability_overrides(&base_capabilities, overrides)
-                        } else {
-                            base_capabilities
-                        };
-
-                    models.push(MergedModel {
-                        id: model_id.clone(),
-                        name: Some(model_id.clone()),
-                        capabilities,
-                        is_catalog: false,
-                        is_favorite: user.favorite_models.contains(&model_id),
-                        has_capability_overrides: has_overrides,
-                    });
-                }
+                let base_capabilities = ModelCapabilities {
+                    tools: false,
+                    thinking: false,
+                    vision: false,
+                    streaming: true,
+                };
+                models.extend(Self::runtime_models(
+                    &user,
+                    |model_id| catalog_provider.models.contains_key(model_id),
+                    &base_capabilities,
+                ));
 
                 // Sort models alphabetically for consistent ordering
                 models.sort_by(|a, b| a.id.cmp(&b.id));
@@ -314,6 +439,11 @@ impl AiProviderServiceTrait for AiProviderService {
                     favorite_models: user.favorite_models.clone(),
                     model_capability_overrides: user.model_capability_overrides.clone(),
                     tools_allowlist: user.tools_allowlist.clone(),
+                    redaction: user.redaction.clone().unwrap_or_else(|| {
+                        RedactionSettings::default_for_provider_type(
+                            &catalog_provider.provider_type,
+                        )
+                    }),
                     has_api_key: self.has_api_key(id),
                     is_default: user_settings.default_provider.as_ref() == Some(id),
                     supports_model_listing,
@@ -321,6 +451,13 @@ impl AiProviderServiceTrait for AiProviderService {
             })
             .collect();
 
+        providers.extend(
+            user_settings
+                .custom_providers
+                .iter()
+                .map(|custom| self.merge_custom_provider(custom, &user_settings)),
+        );
+
         // Sort by priority (lower first), then by provider ID for stable tiebreaker
         providers.sort_by(|a, b| a.priority.cmp(&b.priority).then_with(|| a.id.cmp(&b.id)));
 
@@ -328,12 +465,14 @@ impl AiProviderServiceTrait for AiProviderService {
             providers,
             capabilities: self.catalog.capabilities.clone(),
             default_provider: user_settings.default_provider,
+            usage_budget: user_settings.usage_budget,
+            custom_providers: user_settings.custom_providers,
         })
     }
 
     async fn update_provider_settings(&self, request: UpdateProviderSettingsRequest) -> Result<()> {
-        // Verify provider exists in catalog
-        if !self.catalog.providers.contains_key(&request.provider_id) {
+        // Verify provider exists in catalog or is user-defined
+        if !self.provider_exists(&request.provider_id) {
             return Err(wealthfolio_core::errors::Error::Validation(
                 ValidationError::InvalidInput(format!("Unknown provider: {}", request.provider_id)),
             ));
@@ -394,6 +533,12 @@ impl AiProviderServiceTrait for AiProviderService {
             provider_settings.tools_allowlist = tools_allowlist;
         }
 
+        // Handle redaction update
+        // Some(Some(settings)) = override, Some(None) = provider-type default
+        if let Some(redaction) = request.redaction {
+            provider_settings.redaction = redaction;
+        }
+
         // Update schema version
         settings.schema_version = AI_PROVIDER_SETTINGS_SCHEMA_VERSION;
 
@@ -403,7 +548,7 @@ impl AiProviderServiceTrait for AiProviderService {
     async fn set_default_provider(&self, request: SetDefaultProviderRequest) -> Result<()> {
         // Verify provider exists if setting a default
         if let Some(ref provider_id) = request.provider_id {
-            if !self.catalog.providers.contains_key(provider_id) {
+            if !self.provider_exists(provider_id) {
                 return Err(wealthfolio_core::errors::Error::Validation(
                     ValidationError::InvalidInput(format!("Unknown provider: {}", provider_id)),
                 ));
@@ -417,12 +562,105 @@ impl AiProviderServiceTrait for AiProviderService {
         self.save_user_settings(&settings).await
     }
 
+    async fn set_usage_budget(&self, request: SetUsageBudgetRequest) -> Result<()> {
+        if let Some(ref budget) = request.budget {
+            if !budget.monthly_limit_usd.is_finite() || budget.monthly_limit_usd <= 0.0 {
+                return Err(wealthfolio_core::errors::Error::Validation(
+                    ValidationError::InvalidInput(
+                        "Monthly budget must be a positive amount".to_string(),
+                    ),
+                ));
+            }
+        }
+
+        let mut settings = self.load_user_settings();
+        settings.usage_budget = request.budget;
+        settings.schema_version = AI_PROVIDER_SETTINGS_SCHEMA_VERSION;
+
+        self.save_user_settings(&settings).await
+    }
+
+    async fn save_custom_provider(&self, request: SaveCustomProviderRequest) -> Result<String> {
+        let name = request.name.trim();
+        if name.is_empty() {
+            return Err(invalid_input("Provider name is required"));
+        }
+        let base_url = request.base_url.trim().trim_end_matches('/');
+        match reqwest::Url::parse(base_url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            _ => {
+                return Err(invalid_input(format!(
+                    "Base URL must be an http(s) URL: {}",
+                    request.base_url
+                )))
+            }
+        }
+
+        let mut settings = self.load_user_settings();
+        let id = match request.id {
+            Some(id) if settings.custom_provider(&id).is_some() => id,
+            Some(id) => return Err(invalid_input(format!("Unknown provider: {}", id))),
+            None => self.new_custom_provider_id(name, &settings),
+        };
+        let default_model = request
+            .default_model
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty());
+
+        let config = CustomProviderConfig {
+            id: id.clone(),
+            name: name.to_string(),
+            base_url: base_url.to_string(),
+            capabilities: request.capabilities,
+            default_model: default_model.clone(),
+            context_window: request.context_window.filter(|tokens| *tokens > 0),
+        };
+
+        if let Some(existing) = settings.custom_providers.iter_mut().find(|p| p.id == id) {
+            *existing = config;
+        } else {
+            settings.custom_providers.push(config);
+            // Adding an endpoint is explicit configuration; make it usable right away.
+            settings.providers.insert(
+                id.clone(),
+                ProviderUserSettings {
+                    enabled: true,
+                    selected_model: default_model.clone(),
+                    favorite_models: default_model.into_iter().collect(),
+                    ..Default::default()
+                },
+            );
+        }
+        settings.schema_version = AI_PROVIDER_SETTINGS_SCHEMA_VERSION;
+
+        self.save_user_settings(&settings).await?;
+        Ok(id)
+    }
+
+    async fn delete_custom_provider(&self, provider_id: &str) -> Result<()> {
+        let mut settings = self.load_user_settings();
+        if settings.custom_provider(provider_id).is_none() {
+            return Err(invalid_input(format!("Unknown provider: {}", provider_id)));
+        }
+
+        settings.custom_providers.retain(|p| p.id != provider_id);
+        settings.providers.remove(provider_id);
+        if settings.default_provider.as_deref() == Some(provider_id) {
+            settings.default_provider = None;
+        }
+        settings.schema_version = AI_PROVIDER_SETTINGS_SCHEMA_VERSION;
+        self.save_user_settings(&settings).await?;
+
+        self.secret_store
+            .delete_secret(&Self::secret_key_for_provider(provider_id))
+    }
+
     fn get_provider_config(
         &self,
         provider_id: &str,
     ) -> std::result::Result<ProviderConfig, ProviderApiError> {
-        // Verify provider exists in catalog
-        if !self.catalog.providers.contains_key(provider_id) {
+        // Verify provider exists in catalog or is user-defined
+        if !self.provider_exists(provider_id) {
             return Err(ProviderApiError::UnknownProvider {
                 provider_id: provider_id.to_string(),
             });
@@ -448,12 +686,23 @@ impl AiProviderServiceTrait for AiProviderService {
     }
 
     fn get_title_model(&self, provider_id: &str) -> Option<String> {
-        self.catalog.providers.get(provider_id).map(|p| {
+        if let Some(p) = self.catalog.providers.get(provider_id) {
             // Use title_model_id if set, otherwise fall back to default_model
-            p.title_model_id
-                .clone()
-                .unwrap_or_else(|| p.default_model.clone())
-        })
+            return Some(
+                p.title_model_id
+                    .clone()
+                    .unwrap_or_else(|| p.default_model.clone()),
+            );
+        }
+
+        // User-defined endpoints serve whatever model the user picked
+        let settings = self.load_user_settings();
+        let custom = settings.custom_provider(provider_id)?;
+        settings
+            .providers
+            .get(provider_id)
+            .and_then(|p| p.selected_model.clone())
+            .or_else(|| custom.default_model.clone())
     }
 
     async fn list_models(
@@ -474,7 +723,13 @@ impl AiProviderServiceTrait for AiProviderService {
             _ => "https://api.openai.com",
         });
 
+        let is_custom = self
+            .load_user_settings()
+            .custom_provider(provider_id)
+            .is_some();
         let models_url = match provider_id {
+            // User-defined base URLs already include the version path
+            _ if is_custom => format!("{}/models", base_url.trim_end_matches('/')),
             "ollama" => format!("{}/api/tags", base_url.trim_end_matches('/')),
             "google" => format!("{}/v1beta/models", base_url.trim_end_matches('/')),
             // OpenAI-compatible: OpenAI, Groq, OpenRouter
@@ -607,7 +862,7 @@ impl AiProviderServiceTrait for AiProviderService {
                     })
                     .collect()
             }
-            // OpenAI-compatible format: OpenAI, Groq, OpenRouter
+            // OpenAI-compatible format: OpenAI, Groq, OpenRouter, user-defined endpoints
             _ => {
                 // OpenAI format: { "data": [{ "id": "gpt-4", ... }] }
                 #[derive(serde::Deserialize)]
@@ -817,4 +1072,199 @@ mod tests {
         assert!(!custom_model.capabilities.vision);
         assert!(custom_model.capabilities.streaming);
     }
+
+    #[tokio::test]
+    async fn test_redaction_defaults_by_provider_type_and_can_be_overridden() {
+        let service = AiProviderService::new(
+            Arc::new(MockSettingsRepository::default()),
+            Arc::new(MockSecretStore::default()),
+            include_str!("ai_providers.json"),
+        )
+        .expect("catalog should load");
+
+        let redaction_for = |service: &AiProviderService, id: &str| {
+            service
+                .get_ai_providers()
+                .expect("providers should load")
+                .providers
+                .into_iter()
+                .find(|provider| provider.id == id)
+                .expect("provider should be present")
+                .redaction
+        };
+        assert!(redaction_for(&service, "openai").enabled);
+        assert!(!redaction_for(&service, "ollama").enabled);
+
+        let request: UpdateProviderSettingsRequest = serde_json::from_value(serde_json::json!({
+            "providerId": "openai",
+            "redaction": { "enabled": true, "amounts": "bucketed" },
+        }))
+        .expect("request should deserialize");
+        service
+            .update_provider_settings(request)
+            .await
+            .expect("settings should save");
+
+        let redaction = redaction_for(&service, "openai");
+        assert_eq!(
+            redaction.amounts,
+            crate::redaction::AmountRedaction::Bucketed
+        );
+        assert!(redaction.strip_free_text);
+    }
+
+    fn custom_provider_request(name: &str, base_url: &str) -> SaveCustomProviderRequest {
+        SaveCustomProviderRequest {
+            id: None,
+            name: name.to_string(),
+            base_url: base_url.to_string(),
+            capabilities: ModelCapabilities {
+                tools: true,
+                thinking: false,
+                vision: false,
+                streaming: true,
+            },
+            default_model: Some("qwen2.5-7b-instruct".to_string()),
+            context_window: Some(32_768),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_provider_round_trip() {
+        let secret_store = Arc::new(MockSecretStore::default());
+        let service = AiProviderService::new(
+            Arc::new(MockSettingsRepository::default()),
+            secret_store.clone(),
+            include_str!("ai_providers.json"),
+        )
+        .expect("catalog should load");
+
+        let id = service
+            .save_custom_provider(custom_provider_request(
+                "LM Studio",
+                "http://localhost:1234/v1/",
+            ))
+            .await
+            .expect("provider should save");
+        assert_eq!(id, "custom-lm-studio");
+
+        let response = service.get_ai_providers().expect("providers should load");
+        assert_eq!(response.custom_providers.len(), 1);
+        assert_eq!(response.custom_providers[0].id, id);
+        let provider = response
+            .providers
+            .into_iter()
+            .find(|provider| provider.id == id)
+            .expect("custom provider should be listed");
+        assert_eq!(provider.provider_type, CUSTOM_PROVIDER_TYPE);
+        assert_eq!(
+            provider.custom_url.as_deref(),
+            Some("http://localhost:1234/v1")
+        );
+        assert!(provider.enabled);
+        assert!(!provider.redaction.enabled);
+        assert_eq!(
+            provider.selected_model.as_deref(),
+            Some("qwen2.5-7b-instruct")
+        );
+        let model = &provider.models[0];
+        assert_eq!(model.id, "qwen2.5-7b-instruct");
+        assert!(model.capabilities.tools);
+        assert!(!model.is_catalog);
+
+        // Same name gets a distinct ID; remote endpoints are redacted by default
+        let second = service
+            .save_custom_provider(custom_provider_request(
+                "LM Studio",
+                "https://llm.example.com/v1",
+            ))
+            .await
+            .expect("provider should save");
+        assert_eq!(second, "custom-lm-studio-2");
+
+        service
+            .set_default_provider(SetDefaultProviderRequest {
+                provider_id: Some(id.clone()),
+            })
+            .await
+            .expect("custom provider can be the default");
+        secret_store
+            .set_secret("ai_custom-lm-studio", "sk-local")
+            .unwrap();
+
+        service
+            .delete_custom_provider(&id)
+            .await
+            .expect("provider should delete");
+        let response = service.get_ai_providers().expect("providers should load");
+        assert!(response.providers.iter().all(|provider| provider.id != id));
+        assert_eq!(response.default_provider, None);
+        assert_eq!(
+            secret_store.get_secret("ai_custom-lm-studio").unwrap(),
+            None
+        );
+        let remaining = response
+            .providers
+            .iter()
+            .find(|provider| provider.id == second)
+            .expect("other custom provider should remain");
+        assert!(remaining.redaction.enabled);
+    }
+
+    #[tokio::test]
+    async fn test_save_custom_provider_rejects_invalid_input() {
+        let service = AiProviderService::new(
+            Arc::new(MockSettingsRepository::default()),
+            Arc::new(MockSecretStore::default()),
+            include_str!("ai_providers.json"),
+        )
+        .expect("catalog should load");
+
+        assert!(service
+            .save_custom_provider(custom_provider_request("vLLM", "localhost:8000"))
+            .await
+            .is_err());
+        assert!(service
+            .save_custom_provider(custom_provider_request(" ", "http://localhost:8000/v1"))
+            .await
+            .is_err());
+
+        let mut update = custom_provider_request("vLLM", "http://localhost:8000/v1");
+        update.id = Some("custom-missing".to_string());
+        assert!(service.save_custom_provider(update).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_models_from_custom_provider() {
+        use crate::eval::StubServer;
+
+        let server = StubServer::start(&["llama-3.1-8b", "qwen2.5-7b-instruct"], "ok").await;
+        let secret_store = Arc::new(MockSecretStore::default());
+        let service = AiProviderService::new(
+            Arc::new(MockSettingsRepository::default()),
+            secret_store.clone(),
+            include_str!("ai_providers.json"),
+        )
+        .expect("catalog should load");
+
+        let id = service
+            .save_custom_provider(custom_provider_request("vLLM", &server.base_url))
+            .await
+            .expect("provider should save");
+        // The key is optional, but sent when configured
+        secret_store
+            .set_secret(&format!("ai_{}", id), "sk-local")
+            .unwrap();
+
+        let response = service.list_models(&id).await.expect("models should list");
+        let ids: Vec<&str> = response.models.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["llama-3.1-8b", "qwen2.5-7b-instruct"]);
+
+        let requests = server.requests();
+        assert_eq!(requests[0].path, "/v1/models");
+        assert_eq!(
+            requests[0].authorization.as_deref(),
+            Some("Bearer sk-local")
+        );
+    }
 }
@@ -13,9 +13,12 @@ use std::sync::Arc;
 use crate::env::AiEnvironment;
 use crate::error::AiError;
 use crate::provider_model::{
-    AiProviderSettings, CapabilityInfo, ConnectionField, ModelCapabilities, ProviderDefaultConfig,
-    AI_PROVIDER_SETTINGS_KEY,
+    AiProviderSettings, CapabilityInfo, ConnectionField, CustomProviderConfig, ModelCapabilities,
+    ModelPricing, ProviderDefaultConfig, AI_PROVIDER_SETTINGS_KEY,
 };
+use crate::redaction::RedactionSettings;
+use crate::tools::constants::DEFAULT_CONTEXT_WINDOW_TOKENS;
+use crate::usage::UsageBudget;
 
 // ============================================================================
 // Provider Catalog (Static JSON)
@@ -61,6 +64,12 @@ struct ProviderCatalogEntry {
 struct ModelCatalogEntry {
     #[serde(default)]
     capabilities: ModelCapabilities,
+    /// Context window size in tokens.
+    #[serde(default)]
+    context_window: Option<u32>,
+    /// List price in USD per million tokens.
+    #[serde(default)]
+    pricing: Option<ModelPricing>,
 }
 
 // ============================================================================
@@ -298,25 +307,51 @@ impl<E: AiEnvironment> ProviderService<E> {
             .map_err(|e| AiError::Internal(e.to_string()))
     }
 
+    /// Load the AI provider settings blob, falling back to defaults if missing/corrupt.
+    fn load_provider_settings(&self) -> AiProviderSettings {
+        self.env
+            .settings_service()
+            .get_setting_value(AI_PROVIDER_SETTINGS_KEY)
+            .ok()
+            .flatten()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Get a user-defined OpenAI-compatible provider.
+    pub fn get_custom_provider(&self, provider_id: &str) -> Option<CustomProviderConfig> {
+        self.load_provider_settings()
+            .custom_provider(provider_id)
+            .cloned()
+    }
+
+    /// Check if a provider is a user-defined OpenAI-compatible endpoint.
+    pub fn is_custom_provider(&self, provider_id: &str) -> bool {
+        self.get_custom_provider(provider_id).is_some()
+    }
+
     /// Get model capabilities for a specific provider/model combination.
     /// Checks user capability overrides first, then falls back to catalog, then defaults.
+    /// User-defined providers use their endpoint-wide capability flags as the base.
     pub fn get_model_capabilities(&self, provider_id: &str, model_id: &str) -> ModelCapabilities {
-        // First, get base capabilities from catalog
+        let settings = self.load_provider_settings();
+
+        // First, get base capabilities from catalog or the user-defined provider
         let catalog_capabilities = PROVIDER_CATALOG
             .providers
             .get(provider_id)
             .and_then(|p| p.models.get(model_id))
-            .map(|m| m.capabilities.clone());
+            .map(|m| m.capabilities.clone())
+            .or_else(|| {
+                settings
+                    .custom_provider(provider_id)
+                    .map(|p| p.capabilities.clone())
+            });
 
         // Check for user capability overrides in the new settings system
-        let user_overrides = self
-            .env
-            .settings_service()
-            .get_setting_value(AI_PROVIDER_SETTINGS_KEY)
-            .ok()
-            .flatten()
-            .and_then(|s| serde_json::from_str::<AiProviderSettings>(&s).ok())
-            .and_then(|settings| settings.providers.get(provider_id).cloned())
+        let user_overrides = settings
+            .providers
+            .get(provider_id)
             .and_then(|provider_settings| {
                 provider_settings
                     .model_capability_overrides
@@ -345,27 +380,89 @@ impl<E: AiEnvironment> ProviderService<E> {
         }
     }
 
+    /// Get the context window size in tokens for a provider/model combination.
+    /// Falls back to a conservative default for models missing from the catalog.
+    pub fn get_context_window(&self, provider_id: &str, model_id: &str) -> u32 {
+        PROVIDER_CATALOG
+            .providers
+            .get(provider_id)
+            .and_then(|p| p.models.get(model_id))
+            .and_then(|m| m.context_window)
+            .or_else(|| {
+                self.get_custom_provider(provider_id)
+                    .and_then(|p| p.context_window)
+            })
+            .unwrap_or(DEFAULT_CONTEXT_WINDOW_TOKENS)
+    }
+
+    /// Get the list price for a provider/model combination.
+    /// Local providers are free; models missing from the catalog have no known price.
+    pub fn get_model_pricing(&self, provider_id: &str, model_id: &str) -> Option<ModelPricing> {
+        let Some(provider) = PROVIDER_CATALOG.providers.get(provider_id) else {
+            // User-defined endpoints have no list price unless they run on this machine
+            let custom = self.get_custom_provider(provider_id)?;
+            return custom.is_local().then_some(ModelPricing::FREE);
+        };
+        if provider.provider_type == "local" {
+            return Some(ModelPricing::FREE);
+        }
+        provider.models.get(model_id).and_then(|m| m.pricing)
+    }
+
     /// Get the title model ID for a provider.
     /// Returns title_model_id if configured, otherwise falls back to default_model.
     pub fn get_title_model(&self, provider_id: &str) -> Option<String> {
-        PROVIDER_CATALOG.providers.get(provider_id).map(|p| {
-            p.title_model_id
-                .clone()
-                .unwrap_or_else(|| p.default_model.clone())
-        })
+        if let Some(p) = PROVIDER_CATALOG.providers.get(provider_id) {
+            return Some(
+                p.title_model_id
+                    .clone()
+                    .unwrap_or_else(|| p.default_model.clone()),
+            );
+        }
+
+        // User-defined endpoints serve whatever model the user picked
+        let settings = self.load_provider_settings();
+        let custom = settings.custom_provider(provider_id)?;
+        settings
+            .providers
+            .get(provider_id)
+            .and_then(|p| p.selected_model.clone())
+            .or_else(|| custom.default_model.clone())
+    }
+
+    /// Get the default provider and its selected model, for background work that
+    /// runs without a chat request. Returns None when no usable provider is configured:
+    /// no default set, provider disabled, or a cloud provider without an API key.
+    pub fn get_default_model(&self) -> Option<(String, String)> {
+        let stored = self.load_provider_settings();
+
+        let provider_id = stored.default_provider.clone()?;
+        let user_settings = stored.providers.get(&provider_id);
+        if !user_settings.map(|p| p.enabled).unwrap_or(false) {
+            return None;
+        }
+
+        let default_model = match PROVIDER_CATALOG.providers.get(&provider_id) {
+            Some(catalog) => {
+                if catalog.provider_type != "local" && !self.has_api_key(&provider_id) {
+                    return None;
+                }
+                Some(catalog.default_model.clone())
+            }
+            // User-defined endpoints don't require a key and may have no default model
+            None => stored.custom_provider(&provider_id)?.default_model.clone(),
+        };
+
+        let model_id = user_settings
+            .and_then(|p| p.selected_model.clone())
+            .or(default_model)?;
+        Some((provider_id, model_id))
     }
 
     /// Get the tools allowlist for a provider.
     /// Returns None if all tools are allowed, Some(list) if only specific tools are allowed.
     pub fn get_tools_allowlist(&self, provider_id: &str) -> Option<Vec<String>> {
-        let stored: AiProviderSettings = self
-            .env
-            .settings_service()
-            .get_setting_value(AI_PROVIDER_SETTINGS_KEY)
-            .ok()
-            .flatten()
-            .and_then(|s| serde_json::from_str(&s).ok())
-            .unwrap_or_default();
+        let stored = self.load_provider_settings();
 
         stored
             .providers
@@ -373,27 +470,54 @@ impl<E: AiEnvironment> ProviderService<E> {
             .and_then(|p| p.tools_allowlist.clone())
     }
 
-    /// Get provider URL (for local providers like Ollama).
-    pub fn get_provider_url(&self, provider_id: &str) -> Option<String> {
-        let stored: AiProviderSettings = self
-            .env
-            .settings_service()
-            .get_setting_value(AI_PROVIDER_SETTINGS_KEY)
-            .ok()
-            .flatten()
-            .and_then(|s| serde_json::from_str(&s).ok())
-            .unwrap_or_default();
+    /// Get the privacy redaction settings for a provider.
+    /// Falls back to the provider-type default: on for cloud providers, off for local ones.
+    pub fn get_redaction_settings(&self, provider_id: &str) -> RedactionSettings {
+        let stored = self.load_provider_settings();
 
-        let url = stored
+        stored
             .providers
             .get(provider_id)
-            .and_then(|p| p.custom_url.clone())
-            .or_else(|| {
-                PROVIDER_CATALOG
+            .and_then(|p| p.redaction.clone())
+            .unwrap_or_else(|| {
+                let provider_type = PROVIDER_CATALOG
                     .providers
                     .get(provider_id)
-                    .and_then(|p| p.default_config.url.clone())
-            });
+                    .map(|p| p.provider_type.as_str())
+                    .or_else(|| {
+                        stored
+                            .custom_provider(provider_id)
+                            .map(|p| p.effective_provider_type())
+                    })
+                    .unwrap_or("api");
+                RedactionSettings::default_for_provider_type(provider_type)
+            })
+    }
+
+    /// Get the monthly usage budget, if one is configured.
+    pub fn get_usage_budget(&self) -> Option<UsageBudget> {
+        let stored = self.load_provider_settings();
+
+        stored.usage_budget
+    }
+
+    /// Get provider URL (for local providers like Ollama, and user-defined endpoints).
+    pub fn get_provider_url(&self, provider_id: &str) -> Option<String> {
+        let stored = self.load_provider_settings();
+
+        let url = match stored.custom_provider(provider_id) {
+            Some(custom) => Some(custom.base_url.clone()),
+            None => stored
+                .providers
+                .get(provider_id)
+                .and_then(|p| p.custom_url.clone())
+                .or_else(|| {
+                    PROVIDER_CATALOG
+                        .providers
+                        .get(provider_id)
+                        .and_then(|p| p.default_config.url.clone())
+                }),
+        };
 
         // Validate URL to prevent panics in rig-core's HTTP client
         url.filter(|u| reqwest::Url::parse(u).is_ok())
@@ -519,4 +643,151 @@ mod tests {
         assert!(!reasoner_caps.vision);
         assert!(reasoner_caps.streaming);
     }
+
+    #[test]
+    fn test_context_window_from_catalog() {
+        let env = Arc::new(MockEnvironment::new());
+        let service = ProviderService::new(env);
+
+        assert_eq!(
+            service.get_context_window("anthropic", "claude-sonnet-4-5-20250929"),
+            200_000
+        );
+        assert_eq!(
+            service.get_context_window("ollama", "some-unknown-model"),
+            DEFAULT_CONTEXT_WINDOW_TOKENS
+        );
+    }
+
+    #[test]
+    fn test_model_pricing_from_catalog() {
+        let env = Arc::new(MockEnvironment::new());
+        let service = ProviderService::new(env);
+
+        let sonnet = service
+            .get_model_pricing("anthropic", "claude-sonnet-4-5-20250929")
+            .expect("catalog model should be priced");
+        assert_eq!(sonnet.input_per_million, 3.0);
+        assert_eq!(sonnet.output_per_million, 15.0);
+
+        // Local models are free, even when not in the catalog
+        assert_eq!(
+            service.get_model_pricing("ollama", "llama3.2"),
+            Some(ModelPricing::FREE)
+        );
+        // Fetched API models have no known price
+        assert_eq!(service.get_model_pricing("openai", "gpt-unknown"), None);
+    }
+
+    #[tokio::test]
+    async fn test_default_model_requires_enabled_provider_with_key() {
+        let env = Arc::new(MockEnvironment::new());
+        let service = ProviderService::new(env.clone());
+        assert_eq!(service.get_default_model(), None);
+
+        let settings = serde_json::json!({
+            "schemaVersion": 1,
+            "defaultProvider": "anthropic",
+            "providers": { "anthropic": { "enabled": true } }
+        });
+        env.settings_service()
+            .set_setting_value(AI_PROVIDER_SETTINGS_KEY, &settings.to_string())
+            .await
+            .unwrap();
+        // Cloud provider without an API key is not usable
+        assert_eq!(service.get_default_model(), None);
+
+        let env = Arc::new(MockEnvironment::new().with_secret("ai_anthropic", "sk-test"));
+        env.settings_service()
+            .set_setting_value(AI_PROVIDER_SETTINGS_KEY, &settings.to_string())
+            .await
+            .unwrap();
+        let service = ProviderService::new(env);
+        let (provider_id, model_id) = service.get_default_model().unwrap();
+        assert_eq!(provider_id, "anthropic");
+        assert_eq!(
+            model_id,
+            PROVIDER_CATALOG.providers["anthropic"].default_model
+        );
+    }
+
+    #[tokio::test]
+    async fn test_custom_provider_defaults() {
+        let env = Arc::new(MockEnvironment::new());
+        let settings = serde_json::json!({
+            "schemaVersion": 1,
+            "defaultProvider": "custom-vllm",
+            "providers": {
+                "custom-vllm": {
+                    "enabled": true,
+                    "modelCapabilityOverrides": { "qwen2.5-vl": { "vision": true } }
+                }
+            },
+            "customProviders": [
+                {
+                    "id": "custom-vllm",
+                    "name": "vLLM",
+                    "baseUrl": "http://localhost:8000/v1",
+                    "capabilities": { "tools": true, "thinking": false, "vision": false },
+                    "defaultModel": "qwen2.5-7b-instruct",
+                    "contextWindow": 32768
+                },
+                {
+                    "id": "custom-remote",
+                    "name": "Remote",
+                    "baseUrl": "https://llm.example.com/v1",
+                    "capabilities": { "tools": false, "thinking": false, "vision": false }
+                }
+            ]
+        });
+        env.settings_service()
+            .set_setting_value(AI_PROVIDER_SETTINGS_KEY, &settings.to_string())
+            .await
+            .unwrap();
+        let service = ProviderService::new(env);
+
+        assert!(service.is_custom_provider("custom-vllm"));
+        assert!(!service.is_custom_provider("openai"));
+        assert_eq!(
+            service.get_provider_url("custom-vllm"),
+            Some("http://localhost:8000/v1".to_string())
+        );
+
+        let caps = service.get_model_capabilities("custom-vllm", "qwen2.5-7b-instruct");
+        assert!(caps.tools);
+        assert!(!caps.vision);
+        assert!(caps.streaming);
+        let vision_caps = service.get_model_capabilities("custom-vllm", "qwen2.5-vl");
+        assert!(vision_caps.tools);
+        assert!(vision_caps.vision);
+
+        assert_eq!(
+            service.get_context_window("custom-vllm", "qwen2.5-7b-instruct"),
+            32_768
+        );
+        assert_eq!(
+            service.get_context_window("custom-remote", "any"),
+            DEFAULT_CONTEXT_WINDOW_TOKENS
+        );
+
+        // Local endpoints are free and unredacted; remote ones are neither
+        assert_eq!(
+            service.get_model_pricing("custom-vllm", "qwen2.5-7b-instruct"),
+            Some(ModelPricing::FREE)
+        );
+        assert_eq!(service.get_model_pricing("custom-remote", "any"), None);
+        assert!(!service.get_redaction_settings("custom-vllm").enabled);
+        assert!(service.get_redaction_settings("custom-remote").enabled);
+
+        // No API key needed to be the default provider
+        assert_eq!(
+            service.get_default_model(),
+            Some(("custom-vllm".to_string(), "qwen2.5-7b-instruct".to_string()))
+        );
+        assert_eq!(
+            service.get_title_model("custom-vllm"),
+            Some("qwen2.5-7b-instruct".to_string())
+        );
+        assert_eq!(service.get_title_model("custom-remote"), None);
+    }
 }
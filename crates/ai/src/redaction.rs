@@ -0,0 +1,1250 @@
+//! Privacy redaction between tool output and cloud LLM providers.
+//!
+//! A [`RedactionSession`] lives for one chat run. It:
+//! - pseudonymises account, asset and goal names with tokens that are stable
+//!   for the thread (e.g. `ACCOUNT_3F9A1C`),
+//! - optionally scales or buckets absolute amounts, leaving percentages alone,
+//! - strips notes and other free text.
+//!
+//! Tools are wrapped in [`RedactingTool`] so the model only sees redacted
+//! output, while the original output is kept for the locally rendered and
+//! persisted tool result. Tokens in the streamed answer are re-hydrated with
+//! [`StreamRehydrator`] before reaching the UI.
+
+use rig::{
+    completion::ToolDefinition,
+    tool::{ToolDyn, ToolError},
+    wasm_compat::WasmBoxedFuture,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// How absolute amounts are presented to the provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AmountRedaction {
+    /// Amounts are sent as-is.
+    #[default]
+    Exact,
+    /// Amounts are multiplied by a per-thread factor; ratios are preserved.
+    Scaled,
+    /// Amounts are replaced by a 1-2-5 range such as `"10000-20000"`.
+    Bucketed,
+}
+
+/// Redaction settings for a provider.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionSettings {
+    pub enabled: bool,
+    #[serde(default)]
+    pub amounts: AmountRedaction,
+    /// Drop notes, descriptions and raw CSV values.
+    #[serde(default = "default_strip_free_text")]
+    pub strip_free_text: bool,
+}
+
+fn default_strip_free_text() -> bool {
+    true
+}
+
+impl RedactionSettings {
+    /// Default for a catalog provider type: on for cloud APIs, off for local models.
+    pub fn default_for_provider_type(provider_type: &str) -> Self {
+        Self {
+            enabled: provider_type != "local",
+            amounts: AmountRedaction::Exact,
+            strip_free_text: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NameKind {
+    Account,
+    Asset,
+    Goal,
+}
+
+impl NameKind {
+    fn prefix(self) -> &'static str {
+        match self {
+            NameKind::Account => "ACCOUNT_",
+            NameKind::Asset => "ASSET_",
+            NameKind::Goal => "GOAL_",
+        }
+    }
+}
+
+const TOKEN_PREFIXES: &[&str] = &["ACCOUNT_", "ASSET_", "GOAL_"];
+
+/// Names shorter than this are not replaced in free text, to avoid
+/// clobbering unrelated words.
+const MIN_TEXT_NAME_LEN: usize = 3;
+
+/// Keys holding absolute amounts (or maps of them) in tool output.
+const AMOUNT_KEYS: &[&str] = &[
+    "amount",
+    "amountAfter",
+    "amountBefore",
+    "byMonth",
+    "byType",
+    "costBasis",
+    "costBasisBase",
+    "currentAmount",
+    "fee",
+    "gain",
+    "gainBase",
+    "gainLossAmount",
+    "income",
+    "marketValue",
+    "marketValueBase",
+    "monthlyAverage",
+    "netContribution",
+    "netWorth",
+    "proceeds",
+    "purchasePrice",
+    "quantity",
+    "targetAmount",
+    "totalAmount",
+    "totalAssets",
+    "totalCurrent",
+    "totalIncome",
+    "totalLiabilities",
+    "totalRealizedGain",
+    "totalTarget",
+    "totalValue",
+    "totalValueAfter",
+    "totalValueBefore",
+    "value",
+];
+
+/// Keys holding user-entered free text.
+const FREE_TEXT_KEYS: &[&str] = &[
+    "accountMappings",
+    "comment",
+    "description",
+    "memo",
+    "notes",
+    "rawValues",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    Name(NameKind),
+    Amount,
+    FreeText,
+    Keep,
+}
+
+fn classify(parent: Option<&str>, key: &str) -> FieldKind {
+    match (parent, key) {
+        // Extraction confidence scores share field names with amounts
+        (Some("confidence"), _) => FieldKind::Keep,
+        (_, "account" | "accountName") => FieldKind::Name(NameKind::Account),
+        (Some("accounts" | "availableAccounts"), "name") => FieldKind::Name(NameKind::Account),
+        // Saved CSV mapping profiles are often named after the account
+        (Some("appliedMapping"), "name") => FieldKind::FreeText,
+        (_, "name" | "assetName") => FieldKind::Name(NameKind::Asset),
+        (Some("goals"), "title") => FieldKind::Name(NameKind::Goal),
+        (_, key) if FREE_TEXT_KEYS.contains(&key) => FieldKind::FreeText,
+        (_, key) if AMOUNT_KEYS.contains(&key) => FieldKind::Amount,
+        _ => FieldKind::Keep,
+    }
+}
+
+/// FNV-1a, stable across builds so tokens stay the same for a thread.
+fn fnv1a(parts: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for part in parts {
+        for byte in part.bytes().chain(std::iter::once(0xff)) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+/// 1-2-5 range containing `value`, e.g. 13_500 -> "10000-20000".
+fn bucket(value: f64) -> String {
+    let magnitude = value.abs();
+    if magnitude < 1.0 {
+        return "0-1".to_string();
+    }
+    let mut power = 10f64.powf(magnitude.log10().floor());
+    let mut steps = [1.0, 2.0, 5.0, 10.0].map(|step| step * power);
+    if magnitude >= steps[3] {
+        // log10 rounding at exact powers of ten
+        power *= 10.0;
+        steps = [1.0, 2.0, 5.0, 10.0].map(|step| step * power);
+    }
+    let index = steps
+        .iter()
+        .rposition(|step| magnitude >= *step)
+        .unwrap_or(0);
+    let sign = if value < 0.0 { "-" } else { "" };
+    format!("{}{}-{}{}", sign, steps[index], sign, steps[index + 1])
+}
+
+#[derive(Default)]
+struct SessionState {
+    /// Original name -> token
+    tokens: HashMap<String, String>,
+    /// Token -> original name
+    names: HashMap<String, String>,
+    /// Redacted tool output -> original tool output
+    outputs: HashMap<String, String>,
+}
+
+/// Redaction state for one chat run.
+pub struct RedactionSession {
+    thread_id: String,
+    settings: RedactionSettings,
+    scale: f64,
+    state: Mutex<SessionState>,
+}
+
+impl RedactionSession {
+    pub fn new(thread_id: &str, settings: RedactionSettings) -> Self {
+        // Per-thread factor in [0.5, 2.0), kept away from 1.0
+        let raw = 0.5 + (fnv1a(&[thread_id, "scale"]) % 1500) as f64 / 1000.0;
+        let scale = if (raw - 1.0).abs() < 0.15 {
+            raw + 0.3
+        } else {
+            raw
+        };
+        Self {
+            thread_id: thread_id.to_string(),
+            settings,
+            scale,
+            state: Mutex::new(SessionState::default()),
+        }
+    }
+
+    /// A session that passes everything through unchanged.
+    pub fn disabled() -> Self {
+        Self::new(
+            "",
+            RedactionSettings {
+                enabled: false,
+                amounts: AmountRedaction::Exact,
+                strip_free_text: false,
+            },
+        )
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.settings.enabled
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, SessionState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn token_for(&self, kind: NameKind, name: &str) -> String {
+        let mut state = self.state();
+        if let Some(token) = state.tokens.get(name) {
+            return token.clone();
+        }
+        let hash = fnv1a(&[&self.thread_id, kind.prefix(), name]);
+        let mut token = format!("{}{:06X}", kind.prefix(), hash & 0xFF_FFFF);
+        let mut suffix = 1;
+        while state.names.contains_key(&token) {
+            suffix += 1;
+            token = format!("{}{:06X}_{}", kind.prefix(), hash & 0xFF_FFFF, suffix);
+        }
+        state.tokens.insert(name.to_string(), token.clone());
+        state.names.insert(token.clone(), name.to_string());
+        token
+    }
+
+    /// Registers account names up front so they are also replaced in the
+    /// user's prompt and the thread history.
+    pub fn register_account_names<'a>(&self, names: impl IntoIterator<Item = &'a str>) {
+        self.register_names(NameKind::Account, names);
+    }
+
+    /// Registers asset names up front, see [`Self::register_account_names`].
+    pub fn register_asset_names<'a>(&self, names: impl IntoIterator<Item = &'a str>) {
+        self.register_names(NameKind::Asset, names);
+    }
+
+    /// Registers goal names up front, see [`Self::register_account_names`].
+    pub fn register_goal_names<'a>(&self, names: impl IntoIterator<Item = &'a str>) {
+        self.register_names(NameKind::Goal, names);
+    }
+
+    fn register_names<'a>(&self, kind: NameKind, names: impl IntoIterator<Item = &'a str>) {
+        if !self.is_enabled() {
+            return;
+        }
+        for name in names {
+            if !name.trim().is_empty() {
+                self.token_for(kind, name);
+            }
+        }
+    }
+
+    /// Replaces known names in free text (prompt, history) with their tokens.
+    pub fn redact_text(&self, text: &str) -> String {
+        if !self.is_enabled() {
+            return text.to_string();
+        }
+        let mut pairs: Vec<(String, String)> = self
+            .state()
+            .tokens
+            .iter()
+            .filter(|(name, _)| name.chars().count() >= MIN_TEXT_NAME_LEN)
+            .map(|(name, token)| (name.clone(), token.clone()))
+            .collect();
+        // Longest first so "Brokerage USD" wins over "Brokerage"
+        pairs.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+        pairs.iter().fold(text.to_string(), |text, (name, token)| {
+            text.replace(name, token)
+        })
+    }
+
+    /// Replaces tokens with the original names.
+    pub fn rehydrate(&self, text: &str) -> String {
+        if !self.is_enabled() || !TOKEN_PREFIXES.iter().any(|p| text.contains(p)) {
+            return text.to_string();
+        }
+        let state = self.state();
+        let mut tokens: Vec<(&String, &String)> = state.names.iter().collect();
+        // Longest first so suffixed tokens are replaced before their base
+        tokens.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+        tokens.iter().fold(text.to_string(), |text, (token, name)| {
+            text.replace(token.as_str(), name)
+        })
+    }
+
+    /// Redacts a tool's JSON output for the provider and remembers the
+    /// original so it can be shown locally.
+    pub fn redact_tool_output(&self, output: &str) -> String {
+        if !self.is_enabled() {
+            return output.to_string();
+        }
+        let redacted = match serde_json::from_str::<Value>(output) {
+            Ok(mut value) => {
+                self.redact_fields(&mut value, None);
+                self.redact_strings(&mut value);
+                value.to_string()
+            }
+            Err(_) => self.redact_text(output),
+        };
+        self.state()
+            .outputs
+            .insert(redacted.clone(), output.to_string());
+        redacted
+    }
+
+    /// The original output for a redacted tool result, if this session produced it.
+    pub fn original_output(&self, redacted: &str) -> Option<String> {
+        self.state().outputs.get(redacted).cloned()
+    }
+
+    fn redact_fields(&self, value: &mut Value, parent: Option<&str>) {
+        match value {
+            Value::Object(map) => self.redact_object(map, parent),
+            Value::Array(items) => {
+                for item in items {
+                    self.redact_fields(item, parent);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn redact_object(&self, map: &mut Map<String, Value>, parent: Option<&str>) {
+        for (key, field) in map.iter_mut() {
+            match classify(parent, key) {
+                FieldKind::Name(kind) => {
+                    if let Some(name) = field.as_str().filter(|name| !name.trim().is_empty()) {
+                        *field = Value::String(self.token_for(kind, name));
+                    }
+                }
+                FieldKind::FreeText if self.settings.strip_free_text => {
+                    *field = match field {
+                        Value::Array(_) => Value::Array(Vec::new()),
+                        Value::Object(_) => Value::Object(Map::new()),
+                        _ => Value::Null,
+                    };
+                }
+                FieldKind::Amount if self.settings.amounts != AmountRedaction::Exact => {
+                    self.redact_amounts(field)
+                }
+                FieldKind::Amount => {}
+                _ => self.redact_fields(field, Some(key)),
+            }
+        }
+    }
+
+    fn redact_amounts(&self, value: &mut Value) {
+        match value {
+            Value::Number(number) => {
+                if let Some(amount) = number.as_f64() {
+                    *value = self.redact_amount(amount);
+                }
+            }
+            // Drafts parsed from CSV carry amounts as strings
+            Value::String(text) => {
+                if let Ok(amount) = text.trim().parse::<f64>() {
+                    *value = self.redact_amount(amount);
+                }
+            }
+            Value::Object(map) => map.values_mut().for_each(|v| self.redact_amounts(v)),
+            Value::Array(items) => items.iter_mut().for_each(|v| self.redact_amounts(v)),
+            _ => {}
+        }
+    }
+
+    fn redact_amount(&self, amount: f64) -> Value {
+        match self.settings.amounts {
+            AmountRedaction::Exact => serde_json::json!(amount),
+            AmountRedaction::Scaled => {
+                serde_json::json!(((amount * self.scale) * 100.0).round() / 100.0)
+            }
+            AmountRedaction::Bucketed => Value::String(bucket(amount)),
+        }
+    }
+
+    /// Replaces known names left in other string fields (scopes, messages).
+    fn redact_strings(&self, value: &mut Value) {
+        match value {
+            Value::String(text) => {
+                let redacted = self.redact_text(text);
+                if redacted != *text {
+                    *text = redacted;
+                }
+            }
+            Value::Object(map) => map.values_mut().for_each(|v| self.redact_strings(v)),
+            Value::Array(items) => items.iter_mut().for_each(|v| self.redact_strings(v)),
+            _ => {}
+        }
+    }
+
+    /// Instructions appended to the system prompt when redaction is on.
+    pub fn preamble_note(&self) -> Option<String> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let mut note = String::from(
+            "\n\n## Privacy\n\
+            Account, asset and goal names appear as placeholders such as ACCOUNT_1A2B3C. \
+            Refer to them with the exact placeholder; the user sees the real names.",
+        );
+        match self.settings.amounts {
+            AmountRedaction::Exact => {}
+            AmountRedaction::Scaled => note.push_str(
+                " Absolute amounts and quantities are multiplied by an undisclosed factor: \
+                compare them and use percentages, but never quote them as the user's actual values.",
+            ),
+            AmountRedaction::Bucketed => note.push_str(
+                " Absolute amounts and quantities are given as ranges such as \"10000-20000\": \
+                describe magnitudes and use percentages, never exact values.",
+            ),
+        }
+        if self.settings.strip_free_text {
+            note.push_str(" Notes and descriptions have been removed.");
+        }
+        Some(note)
+    }
+}
+
+/// Wraps a tool so the provider only sees redacted output.
+pub struct RedactingTool {
+    inner: Box<dyn ToolDyn>,
+    session: Arc<RedactionSession>,
+}
+
+impl RedactingTool {
+    pub fn wrap(inner: Box<dyn ToolDyn>, session: Arc<RedactionSession>) -> Box<dyn ToolDyn> {
+        Box::new(Self { inner, session })
+    }
+}
+
+impl ToolDyn for RedactingTool {
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn definition<'a>(&'a self, prompt: String) -> WasmBoxedFuture<'a, ToolDefinition> {
+        self.inner.definition(prompt)
+    }
+
+    fn call<'a>(&'a self, args: String) -> WasmBoxedFuture<'a, Result<String, ToolError>> {
+        Box::pin(async move {
+            // The model refers to accounts by token (e.g. record_activity's account)
+            let args = self.session.rehydrate(&args);
+            let output = self.inner.call(args).await?;
+            Ok(self.session.redact_tool_output(&output))
+        })
+    }
+}
+
+/// Re-hydrates tokens in streamed text. A trailing word that may be a
+/// partial token is held back until the next delta or [`Self::flush`].
+pub struct StreamRehydrator {
+    session: Arc<RedactionSession>,
+    buffer: String,
+}
+
+impl StreamRehydrator {
+    pub fn new(session: Arc<RedactionSession>) -> Self {
+        Self {
+            session,
+            buffer: String::new(),
+        }
+    }
+
+    pub fn process(&mut self, delta: &str) -> String {
+        if !self.session.is_enabled() {
+            return delta.to_string();
+        }
+        self.buffer.push_str(delta);
+        let split = self
+            .buffer
+            .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .map(|index| {
+                index
+                    + self.buffer[index..]
+                        .chars()
+                        .next()
+                        .map_or(1, char::len_utf8)
+            })
+            .unwrap_or(0);
+        let ready: String = self.buffer.drain(..split).collect();
+        self.session.rehydrate(&ready)
+    }
+
+    pub fn flush(&mut self) -> String {
+        let rest = std::mem::take(&mut self.buffer);
+        self.session.rehydrate(&rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::{
+        accounts::{AccountDto, GetAccountsOutput},
+        activities::{ActivityDto, SearchActivitiesOutput},
+        allocation::{AllocationDto, GetAssetAllocationOutput, HoldingDto as AllocationHoldingDto},
+        alternative_assets::{AlternativeAssetDto, GetAlternativeAssetsOutput},
+        extract_statement::ExtractStatementOutput,
+        goals::{GetGoalsOutput, GoalDto},
+        health::{AffectedItemDto, GetHealthIssuesOutput, HealthIssueDto},
+        holdings::{GetHoldingsOutput, HoldingDto},
+        import_csv::{CleaningAction, CsvActivityDraft, ImportCsvOutput, ValidationSummary},
+        income::{GetIncomeOutput, TopAssetDto},
+        net_worth::{GetNetWorthOutput, NetWorthItemDto},
+        performance::GetPerformanceOutput,
+        record_activities::{ActivityDraftRow, BatchValidationSummary, RecordActivitiesOutput},
+        record_activity::{
+            AccountOption, ActivityDraft, RecordActivityOutput, ResolvedAsset, ValidationResult,
+        },
+        scenario::{CashChangeDto, ConcentrationDto, RealizedGainDto, SimulateScenarioOutput},
+        valuation::{GetValuationHistoryOutput, ValuationPointDto},
+    };
+    use serde_json::json;
+    use wealthfolio_core::activities::ImportMappingData;
+
+    const ACCOUNT: &str = "Fidelity IRA 4821";
+    const ASSET: &str = "Apple Inc.";
+
+    fn session(amounts: AmountRedaction) -> RedactionSession {
+        RedactionSession::new(
+            "thread-1",
+            RedactionSettings {
+                enabled: true,
+                amounts,
+                strip_free_text: true,
+            },
+        )
+    }
+
+    fn redact<T: Serialize>(session: &RedactionSession, output: &T) -> (String, Value) {
+        let original = serde_json::to_string(output).unwrap();
+        let redacted = session.redact_tool_output(&original);
+        assert!(!redacted.contains(ACCOUNT), "account leaked: {}", redacted);
+        assert!(!redacted.contains(ASSET), "asset leaked: {}", redacted);
+        assert_eq!(session.original_output(&redacted), Some(original.clone()));
+        (original, serde_json::from_str(&redacted).unwrap())
+    }
+
+    fn draft() -> ActivityDraft {
+        ActivityDraft {
+            activity_type: "BUY".to_string(),
+            activity_date: "2026-01-17".to_string(),
+            symbol: Some("AAPL".to_string()),
+            asset_id: Some("SEC:AAPL:XNAS".to_string()),
+            asset_name: Some(ASSET.to_string()),
+            quantity: Some(20.0),
+            unit_price: Some(240.0),
+            amount: Some(4800.0),
+            fee: Some(1.0),
+            currency: "USD".to_string(),
+            account_id: Some("acc-1".to_string()),
+            account_name: Some(ACCOUNT.to_string()),
+            subtype: None,
+            notes: Some("Bought for Emma's college fund".to_string()),
+            price_source: "user".to_string(),
+            pricing_mode: "MARKET".to_string(),
+            is_custom_asset: false,
+            asset_kind: None,
+        }
+    }
+
+    fn account_option() -> AccountOption {
+        AccountOption {
+            id: "acc-1".to_string(),
+            name: ACCOUNT.to_string(),
+            currency: "USD".to_string(),
+        }
+    }
+
+    fn resolved_asset() -> ResolvedAsset {
+        ResolvedAsset {
+            asset_id: "SEC:AAPL:XNAS".to_string(),
+            symbol: "AAPL".to_string(),
+            name: ASSET.to_string(),
+            currency: "USD".to_string(),
+            exchange: None,
+            exchange_mic: Some("XNAS".to_string()),
+        }
+    }
+
+    fn validation() -> ValidationResult {
+        ValidationResult {
+            is_valid: true,
+            missing_fields: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_accounts_output() {
+        let session = session(AmountRedaction::Exact);
+        let (_, value) = redact(
+            &session,
+            &GetAccountsOutput {
+                accounts: vec![AccountDto {
+                    id: "acc-1".to_string(),
+                    name: ACCOUNT.to_string(),
+                    account_type: "SECURITIES".to_string(),
+                    currency: "USD".to_string(),
+                    is_active: true,
+                }],
+                count: 1,
+                truncated: None,
+                original_count: None,
+            },
+        );
+        let token = value["accounts"][0]["name"].as_str().unwrap();
+        assert!(token.starts_with("ACCOUNT_"));
+        assert_eq!(value["accounts"][0]["id"], "acc-1");
+        assert_eq!(session.rehydrate(token), ACCOUNT);
+    }
+
+    #[test]
+    fn test_holdings_output_scaled() {
+        let session = session(AmountRedaction::Scaled);
+        let (_, value) = redact(
+            &session,
+            &GetHoldingsOutput {
+                holdings: vec![HoldingDto {
+                    account: ACCOUNT.to_string(),
+                    symbol: "AAPL".to_string(),
+                    name: Some(ASSET.to_string()),
+                    holding_type: "SECURITY".to_string(),
+                    quantity: 10.0,
+                    market_value_base: 2400.0,
+                    cost_basis_base: Some(2000.0),
+                    unrealized_gain_pct: Some(20.0),
+                    day_change_pct: Some(1.5),
+                    weight: 0.6,
+                    currency: "USD".to_string(),
+                }],
+                total_value: 4000.0,
+                currency: "USD".to_string(),
+                account_scope: "TOTAL".to_string(),
+                view_mode: "table".to_string(),
+                truncated: None,
+                original_count: None,
+            },
+        );
+        let holding = &value["holdings"][0];
+        assert!(holding["account"].as_str().unwrap().starts_with("ACCOUNT_"));
+        assert!(holding["name"].as_str().unwrap().starts_with("ASSET_"));
+        assert_eq!(holding["symbol"], "AAPL");
+        // Percentages are kept, amounts scaled by the same factor
+        assert_eq!(holding["unrealizedGainPct"], 20.0);
+        assert_eq!(holding["weight"], 0.6);
+        let market_value = holding["marketValueBase"].as_f64().unwrap();
+        let total = value["totalValue"].as_f64().unwrap();
+        assert_ne!(market_value, 2400.0);
+        assert!((market_value / total - 0.6).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_activities_output() {
+        let session = session(AmountRedaction::Bucketed);
+        let (_, value) = redact(
+            &session,
+            &SearchActivitiesOutput {
+                activities: vec![ActivityDto {
+                    id: "act-1".to_string(),
+                    date: "2026-01-17".to_string(),
+                    activity_type: "DEPOSIT".to_string(),
+                    symbol: None,
+                    quantity: None,
+                    unit_price: None,
+                    amount: Some(13_500.0),
+                    fee: None,
+                    fx_rate: Some(1.0),
+                    currency: "USD".to_string(),
+                    account_id: "acc-1".to_string(),
+                    account_name: Some(ACCOUNT.to_string()),
+                }],
+                count: 1,
+                total_row_count: 1,
+                page: 1,
+                page_size: 50,
+                total_pages: 1,
+                account_scope: ACCOUNT.to_string(),
+                total_amount: Some(13_500.0),
+            },
+        );
+        let activity = &value["activities"][0];
+        assert_eq!(activity["amount"], "10000-20000");
+        assert_eq!(activity["fxRate"], 1.0);
+        assert!(value["accountScope"]
+            .as_str()
+            .unwrap()
+            .starts_with("ACCOUNT_"));
+    }
+
+    #[test]
+    fn test_allocation_output() {
+        let session = session(AmountRedaction::Bucketed);
+        let (_, value) = redact(
+            &session,
+            &GetAssetAllocationOutput {
+                allocations: vec![AllocationDto {
+                    category_id: "EQUITY".to_string(),
+                    category_name: "Equity".to_string(),
+                    value: 2400.0,
+                    percentage: 60.0,
+                    color: "#000".to_string(),
+                }],
+                total_value: 4000.0,
+                currency: "USD".to_string(),
+                group_by: "class".to_string(),
+                taxonomy_id: None,
+                taxonomy_name: None,
+                holdings: Some(vec![AllocationHoldingDto {
+                    symbol: "AAPL".to_string(),
+                    name: Some(ASSET.to_string()),
+                    value: 2400.0,
+                    weight: 0.6,
+                }]),
+                category_name: Some("Equity".to_string()),
+            },
+        );
+        assert_eq!(value["allocations"][0]["categoryName"], "Equity");
+        assert_eq!(value["allocations"][0]["percentage"], 60.0);
+        assert_eq!(value["allocations"][0]["value"], "2000-5000");
+        assert!(value["holdings"][0]["name"]
+            .as_str()
+            .unwrap()
+            .starts_with("ASSET_"));
+    }
+
+    #[test]
+    fn test_goals_output() {
+        let session = session(AmountRedaction::Exact);
+        let (_, value) = redact(
+            &session,
+            &GetGoalsOutput {
+                goals: vec![GoalDto {
+                    id: "goal-1".to_string(),
+                    title: "Emma's college".to_string(),
+                    description: Some("For Emma, starting 2030".to_string()),
+                    target_amount: 100_000.0,
+                    current_amount: 25_000.0,
+                    progress_percent: 25.0,
+                    deadline: None,
+                    is_achieved: false,
+                }],
+                count: 1,
+                total_target: 100_000.0,
+                total_current: 25_000.0,
+                achieved_count: 0,
+                truncated: None,
+                original_count: None,
+            },
+        );
+        let goal = &value["goals"][0];
+        assert!(goal["title"].as_str().unwrap().starts_with("GOAL_"));
+        assert!(goal["description"].is_null());
+        assert_eq!(goal["targetAmount"], 100_000.0);
+    }
+
+    #[test]
+    fn test_income_output() {
+        let session = session(AmountRedaction::Scaled);
+        let (_, value) = redact(
+            &session,
+            &GetIncomeOutput {
+                total_income: 1200.0,
+                currency: "USD".to_string(),
+                monthly_average: 100.0,
+                yoy_growth: Some(5.0),
+                by_type: HashMap::from([("DIVIDEND".to_string(), 1200.0)]),
+                top_assets: vec![TopAssetDto {
+                    symbol: "AAPL".to_string(),
+                    name: ASSET.to_string(),
+                    income: 300.0,
+                }],
+                by_month: HashMap::from([("2026-01".to_string(), 100.0)]),
+                period: "YTD".to_string(),
+            },
+        );
+        assert_eq!(value["yoyGrowth"], 5.0);
+        assert_ne!(value["byType"]["DIVIDEND"], 1200.0);
+        assert_ne!(value["byMonth"]["2026-01"], 100.0);
+        assert!(value["topAssets"][0]["name"]
+            .as_str()
+            .unwrap()
+            .starts_with("ASSET_"));
+    }
+
+    #[test]
+    fn test_performance_output() {
+        let session = session(AmountRedaction::Bucketed);
+        let (_, value) = redact(
+            &session,
+            &GetPerformanceOutput {
+                id: "TOTAL".to_string(),
+                period_start_date: Some("2025-01-01".to_string()),
+                period_end_date: Some("2026-01-01".to_string()),
+                currency: "USD".to_string(),
+                cumulative_twr: Some(0.12),
+                gain_loss_amount: Some(4321.0),
+                annualized_twr: Some(0.12),
+                simple_return: 0.1,
+                annualized_simple_return: 0.1,
+                cumulative_mwr: None,
+                annualized_mwr: None,
+                volatility: 0.2,
+                max_drawdown: -0.1,
+            },
+        );
+        assert_eq!(value["gainLossAmount"], "2000-5000");
+        assert_eq!(value["cumulativeTwr"], 0.12);
+        assert_eq!(value["maxDrawdown"], -0.1);
+    }
+
+    #[test]
+    fn test_valuation_output() {
+        let session = session(AmountRedaction::Scaled);
+        let (_, value) = redact(
+            &session,
+            &GetValuationHistoryOutput {
+                valuations: vec![ValuationPointDto {
+                    date: "2026-01-01".to_string(),
+                    total_value: 1000.0,
+                    net_contribution: 800.0,
+                    currency: "USD".to_string(),
+                }],
+                account_scope: "TOTAL".to_string(),
+                currency: "USD".to_string(),
+                start_date: "2026-01-01".to_string(),
+                end_date: "2026-01-01".to_string(),
+                truncated: None,
+                original_count: None,
+            },
+        );
+        let point = &value["valuations"][0];
+        let ratio =
+            point["netContribution"].as_f64().unwrap() / point["totalValue"].as_f64().unwrap();
+        assert!((ratio - 0.8).abs() < 0.001);
+        assert_eq!(point["date"], "2026-01-01");
+    }
+
+    #[test]
+    fn test_record_activity_output() {
+        let session = session(AmountRedaction::Exact);
+        let (_, value) = redact(
+            &session,
+            &RecordActivityOutput {
+                draft: draft(),
+                validation: validation(),
+                available_accounts: vec![account_option()],
+                resolved_asset: Some(resolved_asset()),
+                available_subtypes: Vec::new(),
+            },
+        );
+        assert!(value["draft"]["notes"].is_null());
+        assert_eq!(value["draft"]["accountId"], "acc-1");
+        assert_eq!(
+            value["draft"]["accountName"],
+            value["availableAccounts"][0]["name"]
+        );
+        assert!(value["resolvedAsset"]["name"]
+            .as_str()
+            .unwrap()
+            .starts_with("ASSET_"));
+    }
+
+    #[test]
+    fn test_record_activities_output() {
+        let session = session(AmountRedaction::Exact);
+        let (_, value) = redact(
+            &session,
+            &RecordActivitiesOutput {
+                drafts: vec![ActivityDraftRow {
+                    row_index: 0,
+                    draft: draft(),
+                    validation: validation(),
+                    errors: Vec::new(),
+                    resolved_asset: Some(resolved_asset()),
+                    available_subtypes: Vec::new(),
+                    warnings: Vec::new(),
+                    confidence: None,
+                }],
+                validation: BatchValidationSummary {
+                    total_rows: 1,
+                    valid_rows: 1,
+                    error_rows: 0,
+                },
+                available_accounts: vec![account_option()],
+                resolved_assets: vec![resolved_asset()],
+            },
+        );
+        assert!(value["drafts"][0]["draft"]["notes"].is_null());
+        assert!(value["resolvedAssets"][0]["name"]
+            .as_str()
+            .unwrap()
+            .starts_with("ASSET_"));
+    }
+
+    #[test]
+    fn test_extract_statement_output() {
+        let session = session(AmountRedaction::Bucketed);
+        let (_, value) = redact(
+            &session,
+            &ExtractStatementOutput {
+                batch: RecordActivitiesOutput {
+                    drafts: vec![ActivityDraftRow {
+                        row_index: 0,
+                        draft: draft(),
+                        validation: validation(),
+                        errors: Vec::new(),
+                        resolved_asset: Some(resolved_asset()),
+                        available_subtypes: Vec::new(),
+                        warnings: vec!["Low confidence: amount".to_string()],
+                        confidence: Some([("amount".to_string(), 0.6)].into_iter().collect()),
+                    }],
+                    validation: BatchValidationSummary {
+                        total_rows: 1,
+                        valid_rows: 1,
+                        error_rows: 0,
+                    },
+                    available_accounts: vec![account_option()],
+                    resolved_assets: vec![resolved_asset()],
+                },
+                sources: vec!["statement.pdf".to_string()],
+                skipped: Vec::new(),
+            },
+        );
+        let row = &value["drafts"][0];
+        assert_eq!(row["draft"]["amount"], json!("2000-5000"));
+        assert_eq!(row["confidence"]["amount"], json!(0.6));
+        assert!(row["draft"]["accountName"]
+            .as_str()
+            .unwrap()
+            .starts_with("ACCOUNT_"));
+    }
+
+    #[test]
+    fn test_import_csv_output() {
+        let session = session(AmountRedaction::Bucketed);
+        let (_, value) = redact(
+            &session,
+            &ImportCsvOutput {
+                activities: vec![CsvActivityDraft {
+                    row_number: 1,
+                    activity_type: Some("BUY".to_string()),
+                    activity_date: Some("2026-01-17".to_string()),
+                    symbol: Some("AAPL".to_string()),
+                    exchange_mic: None,
+                    quantity: Some("20".to_string()),
+                    unit_price: Some("240".to_string()),
+                    amount: Some("4800".to_string()),
+                    fee: None,
+                    fx_rate: None,
+                    currency: Some("USD".to_string()),
+                    notes: Some("IRA rollover".to_string()),
+                    subtype: None,
+                    account_id: Some("acc-1".to_string()),
+                    is_valid: true,
+                    errors: Vec::new(),
+                    warnings: Vec::new(),
+                    raw_values: vec![ACCOUNT.to_string(), "4800".to_string()],
+                }],
+                applied_mapping: ImportMappingData {
+                    account_id: "acc-1".to_string(),
+                    name: ACCOUNT.to_string(),
+                    field_mappings: HashMap::new(),
+                    activity_mappings: HashMap::new(),
+                    symbol_mappings: HashMap::new(),
+                    account_mappings: HashMap::from([(ACCOUNT.to_string(), "acc-1".to_string())]),
+                    symbol_mapping_meta: HashMap::new(),
+                    parse_config: None,
+                },
+                cleaning_actions: vec![CleaningAction {
+                    action_type: "trim".to_string(),
+                    description: "Trimmed whitespace".to_string(),
+                    affected_rows: 1,
+                }],
+                validation: ValidationSummary {
+                    total_rows: 1,
+                    valid_rows: 1,
+                    error_rows: 0,
+                    warning_rows: 0,
+                    global_errors: Vec::new(),
+                },
+                available_accounts: vec![account_option()],
+                detected_headers: vec!["Account".to_string()],
+                total_rows: 1,
+                truncated: None,
+                used_saved_profile: true,
+            },
+        );
+        let row = &value["activities"][0];
+        assert_eq!(row["amount"], "2000-5000");
+        assert_eq!(row["unitPrice"], "240");
+        assert!(row["notes"].is_null());
+        assert_eq!(row["rawValues"], json!([]));
+        assert!(value["appliedMapping"]["name"].is_null());
+    }
+
+    #[test]
+    fn test_net_worth_output() {
+        let session = session(AmountRedaction::Bucketed);
+        let (_, value) = redact(
+            &session,
+            &GetNetWorthOutput {
+                date: "2026-03-31".to_string(),
+                currency: "USD".to_string(),
+                net_worth: 650_000.0,
+                total_assets: 950_000.0,
+                total_liabilities: 300_000.0,
+                assets: vec![NetWorthItemDto {
+                    category: "investment".to_string(),
+                    name: ASSET.to_string(),
+                    value: 450_000.0,
+                    asset_id: Some("SEC:AAPL:XNAS".to_string()),
+                }],
+                liabilities: Vec::new(),
+                oldest_valuation_date: None,
+                stale_assets: Vec::new(),
+                truncated: None,
+                original_count: None,
+            },
+        );
+        assert_eq!(value["netWorth"], "500000-1000000");
+        assert_eq!(value["totalLiabilities"], "200000-500000");
+        assert_eq!(value["assets"][0]["value"], "200000-500000");
+        assert!(value["assets"][0]["name"]
+            .as_str()
+            .unwrap()
+            .starts_with("ASSET_"));
+    }
+
+    #[test]
+    fn test_simulate_scenario_output() {
+        let session = session(AmountRedaction::Bucketed);
+        let concentration = ConcentrationDto {
+            position_count: 2,
+            largest_position: Some("AAPL".to_string()),
+            largest_position_pct: 70.0,
+            top5_pct: 100.0,
+            hhi: 5800.0,
+            effective_positions: 1.72,
+        };
+        let (_, value) = redact(
+            &session,
+            &SimulateScenarioOutput {
+                account_id: "TOTAL".to_string(),
+                currency: "USD".to_string(),
+                total_value_before: 120_000.0,
+                total_value_after: 119_990.0,
+                allocation: Vec::new(),
+                cash: vec![CashChangeDto {
+                    currency: "USD".to_string(),
+                    amount_before: 2_000.0,
+                    amount_after: 32_000.0,
+                }],
+                concentration_before: concentration.clone(),
+                concentration_after: concentration,
+                realized_gains: vec![RealizedGainDto {
+                    symbol: "AAPL".to_string(),
+                    quantity: 100.0,
+                    proceeds: 30_000.0,
+                    cost_basis: 12_000.0,
+                    gain: 18_000.0,
+                    currency: "USD".to_string(),
+                    gain_base: Some(18_000.0),
+                }],
+                total_realized_gain: 18_000.0,
+                warnings: Vec::new(),
+            },
+        );
+        assert_eq!(value["totalValueBefore"], "100000-200000");
+        assert_eq!(value["cash"][0]["amountAfter"], "20000-50000");
+        assert_eq!(value["realizedGains"][0]["costBasis"], "10000-20000");
+        assert_eq!(value["realizedGains"][0]["gainBase"], "10000-20000");
+        assert_eq!(value["totalRealizedGain"], "10000-20000");
+        assert_eq!(value["concentrationAfter"]["hhi"], 5800.0);
+    }
+
+    #[test]
+    fn test_alternative_assets_output() {
+        let session = session(AmountRedaction::Scaled);
+        let (_, value) = redact(
+            &session,
+            &GetAlternativeAssetsOutput {
+                assets: vec![AlternativeAssetDto {
+                    id: "PROP-1".to_string(),
+                    kind: "PROPERTY".to_string(),
+                    name: ASSET.to_string(),
+                    currency: "USD".to_string(),
+                    market_value: 500_000.0,
+                    market_value_base: Some(500_000.0),
+                    purchase_price: Some(400_000.0),
+                    purchase_date: Some("2020-06-01".to_string()),
+                    unrealized_gain_pct: Some(25.0),
+                    valuation_date: "2026-01-01".to_string(),
+                    linked_asset_id: None,
+                    notes: Some("Flat 12B, 8 Harbour Road".to_string()),
+                }],
+                count: 1,
+                currency: "USD".to_string(),
+                total_assets: 500_000.0,
+                total_liabilities: 0.0,
+                truncated: None,
+                original_count: None,
+            },
+        );
+        let asset = &value["assets"][0];
+        assert!(asset["name"].as_str().unwrap().starts_with("ASSET_"));
+        assert!(asset["notes"].is_null());
+        assert_ne!(asset["marketValue"], 500_000.0);
+        assert_ne!(asset["purchasePrice"], 400_000.0);
+        assert_eq!(asset["unrealizedGainPct"], 25.0);
+    }
+
+    #[test]
+    fn test_health_issues_output() {
+        let session = session(AmountRedaction::Exact);
+        let (_, value) = redact(
+            &session,
+            &GetHealthIssuesOutput {
+                issues: vec![HealthIssueDto {
+                    id: "price_stale:SEC:AAPL:XNAS".to_string(),
+                    severity: "WARNING".to_string(),
+                    category: "Price Updates".to_string(),
+                    title: "Stale prices".to_string(),
+                    message: format!("{} has not updated in 3 days", ASSET),
+                    details: None,
+                    affected_count: 1,
+                    affected_mv_pct: Some(12.5),
+                    affected_items: vec![AffectedItemDto {
+                        name: ASSET.to_string(),
+                        symbol: Some("AAPL".to_string()),
+                    }],
+                    suggested_action: Some("Sync Prices".to_string()),
+                }],
+                count: 1,
+                overall_severity: "WARNING".to_string(),
+                checked_at: None,
+                is_stale: false,
+                truncated: None,
+                original_count: None,
+            },
+        );
+        let issue = &value["issues"][0];
+        let token = issue["affectedItems"][0]["name"].as_str().unwrap();
+        assert!(token.starts_with("ASSET_"));
+        assert!(issue["message"].as_str().unwrap().starts_with(token));
+        assert_eq!(issue["affectedMvPct"], 12.5);
+    }
+
+    #[test]
+    fn test_tokens_are_stable_per_thread() {
+        let first = session(AmountRedaction::Exact);
+        let second = session(AmountRedaction::Exact);
+        first.register_account_names([ACCOUNT]);
+        second.register_account_names([ACCOUNT]);
+        assert_eq!(first.redact_text(ACCOUNT), second.redact_text(ACCOUNT));
+
+        let other = RedactionSession::new(
+            "thread-2",
+            RedactionSettings::default_for_provider_type("api"),
+        );
+        other.register_account_names([ACCOUNT]);
+        assert_ne!(first.redact_text(ACCOUNT), other.redact_text(ACCOUNT));
+    }
+
+    #[test]
+    fn test_prompt_redaction_and_rehydration() {
+        let session = session(AmountRedaction::Exact);
+        session.register_account_names([ACCOUNT, "IRA"]);
+        let redacted = session.redact_text("How is my Fidelity IRA 4821 doing vs my IRA?");
+        assert!(!redacted.contains("Fidelity"));
+        assert!(!redacted.contains(" IRA?"));
+        assert_eq!(
+            session.rehydrate(&redacted),
+            "How is my Fidelity IRA 4821 doing vs my IRA?"
+        );
+    }
+
+    #[test]
+    fn test_stream_rehydrator_handles_split_tokens() {
+        let session = Arc::new(session(AmountRedaction::Exact));
+        session.register_account_names([ACCOUNT]);
+        let token = session.redact_text(ACCOUNT);
+        let (head, tail) = token.split_at(5);
+
+        let mut stream = StreamRehydrator::new(session.clone());
+        let mut out = stream.process("Your ");
+        out.push_str(&stream.process(head));
+        out.push_str(&stream.process(tail));
+        out.push_str(&stream.process(" account is up."));
+        out.push_str(&stream.flush());
+        assert_eq!(out, format!("Your {} account is up.", ACCOUNT));
+    }
+
+    #[test]
+    fn test_disabled_session_passes_through() {
+        let session = RedactionSession::disabled();
+        let output = json!({ "accounts": [{ "name": ACCOUNT }] }).to_string();
+        assert_eq!(session.redact_tool_output(&output), output);
+        assert!(session.preamble_note().is_none());
+        assert!(!RedactionSettings::default_for_provider_type("local").enabled);
+        assert!(RedactionSettings::default_for_provider_type("api").enabled);
+    }
+
+    #[test]
+    fn test_bucket() {
+        assert_eq!(bucket(0.5), "0-1");
+        assert_eq!(bucket(1.0), "1-2");
+        assert_eq!(bucket(1000.0), "1000-2000");
+        assert_eq!(bucket(7_500.0), "5000-10000");
+        assert_eq!(bucket(-250.0), "-200--500");
+    }
+}
@@ -15,6 +15,7 @@ use rig::{
 use crate::env::AiEnvironment;
 use crate::error::AiError;
 use crate::providers::ProviderService;
+use crate::types::MessageUsage;
 use std::sync::Arc;
 
 // ============================================================================
@@ -86,10 +87,6 @@ impl<E: AiEnvironment> TitleGenerator<E> {
         provider_id: &str,
         model_id: &str,
     ) -> Result<String, AiError> {
-        let provider_service = ProviderService::new(self.env.clone());
-        let api_key = provider_service.get_api_key(provider_id)?;
-        let provider_url = provider_service.get_provider_url(provider_id);
-
         debug!(
             "Generating title with provider {} model {}",
             provider_id, model_id
@@ -107,108 +104,17 @@ Title:",
             truncate_to_title(user_message, 200)
         );
 
-        let response = match provider_id {
-            "anthropic" => {
-                let key = api_key.ok_or_else(|| AiError::MissingApiKey(provider_id.to_string()))?;
-                let mut builder = anthropic::Client::<HttpClient>::builder().api_key(&key);
-                if let Some(url) = provider_url {
-                    builder = builder.base_url(&url);
-                }
-                let client = builder
-                    .build()
-                    .map_err(|e| AiError::Provider(e.to_string()))?;
-                client
-                    .agent(model_id)
-                    .max_tokens(self.config.max_tokens as u64)
-                    .build()
-                    .prompt(&prompt)
-                    .await
-                    .map_err(|e| AiError::Provider(e.to_string()))?
-            }
-            "gemini" | "google" => {
-                let key = api_key.ok_or_else(|| AiError::MissingApiKey(provider_id.to_string()))?;
-                let mut builder = gemini::Client::<HttpClient>::builder().api_key(&key);
-                if let Some(url) = provider_url {
-                    builder = builder.base_url(&url);
-                }
-                let client = builder
-                    .build()
-                    .map_err(|e| AiError::Provider(e.to_string()))?;
-                client
-                    .agent(model_id)
-                    .build()
-                    .prompt(&prompt)
-                    .await
-                    .map_err(|e| AiError::Provider(e.to_string()))?
-            }
-            "groq" => {
-                let key = api_key.ok_or_else(|| AiError::MissingApiKey(provider_id.to_string()))?;
-                let mut builder = groq::Client::<HttpClient>::builder().api_key(&key);
-                if let Some(url) = provider_url {
-                    builder = builder.base_url(&url);
-                }
-                let client = builder
-                    .build()
-                    .map_err(|e| AiError::Provider(e.to_string()))?;
-                client
-                    .agent(model_id)
-                    .build()
-                    .prompt(&prompt)
-                    .await
-                    .map_err(|e| AiError::Provider(e.to_string()))?
-            }
-            "ollama" => {
-                let mut builder = ollama::Client::<HttpClient>::builder().api_key(Nothing);
-                if let Some(url) = provider_url {
-                    builder = builder.base_url(&url);
-                }
-                let client = builder
-                    .build()
-                    .map_err(|e| AiError::Provider(e.to_string()))?;
-                client
-                    .agent(model_id)
-                    .build()
-                    .prompt(&prompt)
-                    .await
-                    .map_err(|e| AiError::Provider(e.to_string()))?
-            }
-            "openrouter" => {
-                let key = api_key.ok_or_else(|| AiError::MissingApiKey(provider_id.to_string()))?;
-                let mut builder = openrouter::Client::<HttpClient>::builder().api_key(&key);
-                if let Some(url) = provider_url {
-                    builder = builder.base_url(&url);
-                }
-                let client = builder
-                    .build()
-                    .map_err(|e| AiError::Provider(e.to_string()))?;
-                client
-                    .agent(model_id)
-                    .build()
-                    .prompt(&prompt)
-                    .await
-                    .map_err(|e| AiError::Provider(e.to_string()))?
-            }
-            _ => {
-                // Default to OpenAI-compatible
-                let key = api_key.ok_or_else(|| AiError::MissingApiKey(provider_id.to_string()))?;
-                let mut builder = openai::Client::<HttpClient>::builder().api_key(&key);
-                if let Some(url) = provider_url {
-                    builder = builder.base_url(&url);
-                }
-                let client = builder
-                    .build()
-                    .map_err(|e| AiError::Provider(e.to_string()))?;
-                client
-                    .agent(model_id)
-                    .build()
-                    .prompt(&prompt)
-                    .await
-                    .map_err(|e| AiError::Provider(e.to_string()))?
-            }
-        };
+        let reply = prompt_model(
+            &self.env,
+            provider_id,
+            model_id,
+            &prompt,
+            self.config.max_tokens,
+        )
+        .await?;
 
         // Clean up the response (providers sometimes wrap titles in markdown/quotes).
-        let title = clean_generated_title(&response);
+        let title = clean_generated_title(&reply.text);
 
         // Ensure reasonable length
         if title.is_empty() || title.len() > 100 {
@@ -221,6 +127,159 @@ Title:",
     }
 }
 
+/// Text and token usage returned by [`prompt_model`].
+pub(crate) struct PromptReply {
+    pub text: String,
+    /// None when the provider reported no usage.
+    pub usage: Option<MessageUsage>,
+}
+
+/// Send a single prompt to a provider's model and return the text response.
+///
+/// Used for short background completions (thread titles and summaries) that
+/// need neither tools nor streaming.
+pub(crate) async fn prompt_model<E: AiEnvironment>(
+    env: &Arc<E>,
+    provider_id: &str,
+    model_id: &str,
+    prompt: &str,
+    max_tokens: u32,
+) -> Result<PromptReply, AiError> {
+    let provider_service = ProviderService::new(env.clone());
+    let api_key = provider_service.get_api_key(provider_id)?;
+    let provider_url = provider_service.get_provider_url(provider_id);
+    let is_custom_provider = provider_service.is_custom_provider(provider_id);
+
+    let response = match provider_id {
+        _ if is_custom_provider => {
+            let client =
+                crate::chat::create_openai_compatible_client(api_key, provider_id, provider_url)?;
+            client
+                .agent(model_id)
+                .build()
+                .prompt(prompt)
+                .extended_details()
+                .await
+                .map_err(|e| AiError::Provider(e.to_string()))?
+        }
+        "anthropic" => {
+            let key = api_key.ok_or_else(|| AiError::MissingApiKey(provider_id.to_string()))?;
+            let mut builder = anthropic::Client::<HttpClient>::builder().api_key(&key);
+            if let Some(url) = provider_url {
+                builder = builder.base_url(&url);
+            }
+            let client = builder
+                .build()
+                .map_err(|e| AiError::Provider(e.to_string()))?;
+            client
+                .agent(model_id)
+                .max_tokens(max_tokens as u64)
+                .build()
+                .prompt(prompt)
+                .extended_details()
+                .await
+                .map_err(|e| AiError::Provider(e.to_string()))?
+        }
+        "gemini" | "google" => {
+            let key = api_key.ok_or_else(|| AiError::MissingApiKey(provider_id.to_string()))?;
+            let mut builder = gemini::Client::<HttpClient>::builder().api_key(&key);
+            if let Some(url) = provider_url {
+                builder = builder.base_url(&url);
+            }
+            let client = builder
+                .build()
+                .map_err(|e| AiError::Provider(e.to_string()))?;
+            client
+                .agent(model_id)
+                .build()
+                .prompt(prompt)
+                .extended_details()
+                .await
+                .map_err(|e| AiError::Provider(e.to_string()))?
+        }
+        "groq" => {
+            let key = api_key.ok_or_else(|| AiError::MissingApiKey(provider_id.to_string()))?;
+            let mut builder = groq::Client::<HttpClient>::builder().api_key(&key);
+            if let Some(url) = provider_url {
+                builder = builder.base_url(&url);
+            }
+            let client = builder
+                .build()
+                .map_err(|e| AiError::Provider(e.to_string()))?;
+            client
+                .agent(model_id)
+                .build()
+                .prompt(prompt)
+                .extended_details()
+                .await
+                .map_err(|e| AiError::Provider(e.to_string()))?
+        }
+        "ollama" => {
+            let mut builder = ollama::Client::<HttpClient>::builder().api_key(Nothing);
+            if let Some(url) = provider_url {
+                builder = builder.base_url(&url);
+            }
+            let client = builder
+                .build()
+                .map_err(|e| AiError::Provider(e.to_string()))?;
+            client
+                .agent(model_id)
+                .build()
+                .prompt(prompt)
+                .extended_details()
+                .await
+                .map_err(|e| AiError::Provider(e.to_string()))?
+        }
+        "openrouter" => {
+            let key = api_key.ok_or_else(|| AiError::MissingApiKey(provider_id.to_string()))?;
+            let mut builder = openrouter::Client::<HttpClient>::builder().api_key(&key);
+            if let Some(url) = provider_url {
+                builder = builder.base_url(&url);
+            }
+            let client = builder
+                .build()
+                .map_err(|e| AiError::Provider(e.to_string()))?;
+            client
+                .agent(model_id)
+                .build()
+                .prompt(prompt)
+                .extended_details()
+                .await
+                .map_err(|e| AiError::Provider(e.to_string()))?
+        }
+        _ => {
+            // Default to OpenAI-compatible
+            let key = api_key.ok_or_else(|| AiError::MissingApiKey(provider_id.to_string()))?;
+            let mut builder = openai::Client::<HttpClient>::builder().api_key(&key);
+            if let Some(url) = provider_url {
+                builder = builder.base_url(&url);
+            }
+            let client = builder
+                .build()
+                .map_err(|e| AiError::Provider(e.to_string()))?;
+            client
+                .agent(model_id)
+                .build()
+                .prompt(prompt)
+                .extended_details()
+                .await
+                .map_err(|e| AiError::Provider(e.to_string()))?
+        }
+    };
+
+    let usage = &response.total_usage;
+    let usage = (usage.input_tokens > 0 || usage.output_tokens > 0).then(|| MessageUsage {
+        provider_id: provider_id.to_string(),
+        model_id: model_id.to_string(),
+        prompt_tokens: u32::try_from(usage.input_tokens).unwrap_or(u32::MAX),
+        completion_tokens: u32::try_from(usage.output_tokens).unwrap_or(u32::MAX),
+    });
+    Ok(PromptReply {
+        text: response.output,
+        usage,
+    })
+}
+
 fn clean_generated_title(raw: &str) -> String {
     let mut title = raw
         .lines()
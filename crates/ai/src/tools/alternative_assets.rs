@@ -0,0 +1,268 @@
+//! Alternative assets tool - fetch properties, vehicles, collectibles and
+//! liabilities using AlternativeAssetService.
+
+use rig::{completion::ToolDefinition, tool::Tool};
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::constants::MAX_ALTERNATIVE_ASSETS;
+use crate::env::AiEnvironment;
+use crate::error::AiError;
+
+// ============================================================================
+// Tool Arguments and Output
+// ============================================================================
+
+/// Arguments for the get_alternative_assets tool.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAlternativeAssetsArgs {
+    /// Only include this kind, e.g. "PROPERTY" or "LIABILITY".
+    #[serde(default)]
+    pub kind: Option<String>,
+}
+
+/// DTO for an alternative asset or liability in tool output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlternativeAssetDto {
+    pub id: String,
+    pub kind: String,
+    pub name: String,
+    pub currency: String,
+    pub market_value: f64,
+    /// Market value in the base currency; absent if no exchange rate is available.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market_value_base: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purchase_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purchase_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unrealized_gain_pct: Option<f64>,
+    pub valuation_date: String,
+    /// For liabilities, the asset they finance (e.g. the property of a mortgage).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub linked_asset_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+/// Output envelope for alternative assets tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAlternativeAssetsOutput {
+    pub assets: Vec<AlternativeAssetDto>,
+    pub count: usize,
+    pub currency: String,
+    pub total_assets: f64,
+    pub total_liabilities: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_count: Option<usize>,
+}
+
+// ============================================================================
+// Tool Implementation
+// ============================================================================
+
+/// Tool to get alternative assets (property, vehicles, collectibles, metals) and liabilities.
+pub struct GetAlternativeAssetsTool<E: AiEnvironment> {
+    env: Arc<E>,
+    base_currency: String,
+}
+
+impl<E: AiEnvironment> GetAlternativeAssetsTool<E> {
+    pub fn new(env: Arc<E>, base_currency: String) -> Self {
+        Self { env, base_currency }
+    }
+}
+
+impl<E: AiEnvironment> Clone for GetAlternativeAssetsTool<E> {
+    fn clone(&self) -> Self {
+        Self {
+            env: self.env.clone(),
+            base_currency: self.base_currency.clone(),
+        }
+    }
+}
+
+impl<E: AiEnvironment + 'static> Tool for GetAlternativeAssetsTool<E> {
+    const NAME: &'static str = "get_alternative_assets";
+
+    type Error = AiError;
+    type Args = GetAlternativeAssetsArgs;
+    type Output = GetAlternativeAssetsOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Get alternative assets tracked outside investment accounts (property, vehicles, collectibles, precious metals, MPF, private equity) and liabilities (mortgages, loans). Returns the latest valuation of each, its value in the base currency, purchase price and gain, and which asset a liability is linked to.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "kind": {
+                        "type": "string",
+                        "enum": ["PROPERTY", "VEHICLE", "COLLECTIBLE", "PRECIOUS_METAL", "MPF", "PRIVATE_EQUITY", "LIABILITY", "OTHER"],
+                        "description": "Only include this kind. Defaults to all kinds."
+                    }
+                },
+                "required": []
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let kind_filter = args.kind.as_deref().map(str::to_uppercase);
+
+        let mut holdings = self
+            .env
+            .alternative_asset_service()
+            .get_alternative_holdings()
+            .map_err(|e| AiError::ToolExecutionFailed(e.to_string()))?;
+        holdings.retain(|h| {
+            kind_filter
+                .as_deref()
+                .is_none_or(|kind| h.kind.as_db_str() == kind)
+        });
+
+        let fx_service = self.env.fx_service();
+        let assets: Vec<AlternativeAssetDto> = holdings
+            .into_iter()
+            .map(|h| {
+                let market_value_base = fx_service
+                    .convert_currency(h.market_value, &h.currency, &self.base_currency)
+                    .ok()
+                    .and_then(|v| v.to_f64());
+
+                AlternativeAssetDto {
+                    id: h.id,
+                    kind: h.kind.as_db_str().to_string(),
+                    name: h.name,
+                    currency: h.currency,
+                    market_value: h.market_value.to_f64().unwrap_or(0.0),
+                    market_value_base,
+                    purchase_price: h.purchase_price.and_then(|d| d.to_f64()),
+                    purchase_date: h.purchase_date.map(|d| d.format("%Y-%m-%d").to_string()),
+                    unrealized_gain_pct: h.unrealized_gain_pct.and_then(|d| d.to_f64()),
+                    valuation_date: h.valuation_date.format("%Y-%m-%d").to_string(),
+                    linked_asset_id: h.linked_asset_id,
+                    notes: h.notes,
+                }
+            })
+            .collect();
+
+        // Totals cover every asset, not only the returned ones
+        let (total_liabilities, total_assets) = assets
+            .iter()
+            .filter_map(|a| a.market_value_base.map(|v| (a.kind == "LIABILITY", v)))
+            .fold((0.0, 0.0), |(liabilities, assets), (is_liability, v)| {
+                if is_liability {
+                    (liabilities + v, assets)
+                } else {
+                    (liabilities, assets + v)
+                }
+            });
+
+        let original_count = assets.len();
+        let assets: Vec<AlternativeAssetDto> =
+            assets.into_iter().take(MAX_ALTERNATIVE_ASSETS).collect();
+
+        let returned_count = assets.len();
+        let truncated = original_count > returned_count;
+
+        Ok(GetAlternativeAssetsOutput {
+            assets,
+            count: returned_count,
+            currency: self.base_currency.clone(),
+            total_assets,
+            total_liabilities,
+            truncated: if truncated { Some(true) } else { None },
+            original_count: if truncated {
+                Some(original_count)
+            } else {
+                None
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test_env::{MockAlternativeAssetService, MockEnvironment};
+    use chrono::{NaiveDate, Utc};
+    use rust_decimal::Decimal;
+    use wealthfolio_core::assets::{AlternativeHolding, AssetKind};
+
+    fn holding(
+        id: &str,
+        kind: AssetKind,
+        name: &str,
+        currency: &str,
+        value: i64,
+    ) -> AlternativeHolding {
+        AlternativeHolding {
+            id: id.to_string(),
+            kind,
+            name: name.to_string(),
+            symbol: id.to_string(),
+            currency: currency.to_string(),
+            market_value: Decimal::from(value),
+            purchase_price: None,
+            purchase_date: NaiveDate::from_ymd_opt(2020, 6, 1),
+            unrealized_gain: None,
+            unrealized_gain_pct: None,
+            valuation_date: Utc::now(),
+            metadata: None,
+            linked_asset_id: None,
+            notes: None,
+        }
+    }
+
+    fn env() -> MockEnvironment {
+        let mut env = MockEnvironment::new();
+        env.alternative_asset_service = Arc::new(MockAlternativeAssetService {
+            holdings: vec![
+                holding("PROP-1", AssetKind::Property, "Flat", "HKD", 7_800_000),
+                holding("LIAB-1", AssetKind::Liability, "Mortgage", "HKD", 3_900_000),
+                holding("VEH-1", AssetKind::Vehicle, "Car", "USD", 20_000),
+            ],
+        });
+        env
+    }
+
+    #[tokio::test]
+    async fn test_get_alternative_assets_tool() {
+        let tool = GetAlternativeAssetsTool::new(Arc::new(env()), "USD".to_string());
+
+        let output = tool
+            .call(GetAlternativeAssetsArgs::default())
+            .await
+            .unwrap();
+        assert_eq!(output.count, 3);
+        assert!((output.total_assets - 1_020_000.0).abs() < 0.01);
+        assert!((output.total_liabilities - 500_000.0).abs() < 0.01);
+        assert_eq!(
+            output.assets[0].purchase_date.as_deref(),
+            Some("2020-06-01")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_alternative_assets_kind_filter() {
+        let tool = GetAlternativeAssetsTool::new(Arc::new(env()), "USD".to_string());
+
+        let output = tool
+            .call(GetAlternativeAssetsArgs {
+                kind: Some("liability".to_string()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(output.count, 1);
+        assert_eq!(output.assets[0].name, "Mortgage");
+        assert_eq!(output.total_assets, 0.0);
+    }
+}
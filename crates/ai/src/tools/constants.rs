@@ -30,6 +30,24 @@ pub const MAX_GOALS: usize = 50;
 /// Maximum number of accounts returned per tool call.
 pub const MAX_ACCOUNTS: usize = 50;
 
+/// Maximum number of net worth breakdown items returned per section.
+pub const MAX_NET_WORTH_ITEMS: usize = 100;
+
+/// Maximum number of health issues returned per tool call.
+pub const MAX_HEALTH_ISSUES: usize = 50;
+
+/// Maximum number of affected items listed per health issue.
+pub const MAX_AFFECTED_ITEMS: usize = 10;
+
+/// Maximum number of quote data points returned per tool call.
+pub const MAX_QUOTE_POINTS: usize = 400;
+
+/// Maximum number of alternative assets returned per tool call.
+pub const MAX_ALTERNATIVE_ASSETS: usize = 100;
+
+/// Maximum number of hypothetical activities per what-if simulation.
+pub const MAX_SCENARIO_ACTIVITIES: usize = 20;
+
 /// Maximum number of rows to import from CSV per tool call.
 pub const MAX_IMPORT_ROWS: usize = 500;
 
@@ -42,6 +60,20 @@ pub const MAX_TOTAL_ATTACHMENTS_BYTES: usize = 20 * 1024 * 1024;
 /// Maximum number of attachments per message.
 pub const MAX_ATTACHMENTS_COUNT: usize = 10;
 
-/// Maximum total characters of history sent to the LLM (~25K tokens).
-/// Messages are taken from most-recent backwards until this budget is exhausted.
-pub const MAX_HISTORY_CHARS: usize = 100_000;
+/// Maximum estimated tokens of thread history sent to the LLM, regardless of
+/// how large the model's context window is (keeps long threads affordable).
+pub const MAX_HISTORY_TOKENS: usize = 25_000;
+
+/// Share of the model's context window (percent) given to thread history.
+/// The rest is left for the system prompt, tool definitions, tool results of
+/// the current run and the response.
+pub const HISTORY_CONTEXT_SHARE_PCT: usize = 40;
+
+/// Context window assumed for models that are not in the provider catalog.
+pub const DEFAULT_CONTEXT_WINDOW_TOKENS: u32 = 32_000;
+
+/// Maximum estimated tokens of older turns sent for summarisation at once.
+pub const MAX_SUMMARY_INPUT_TOKENS: usize = 12_000;
+
+/// Maximum tokens generated for a thread summary.
+pub const MAX_SUMMARY_TOKENS: u32 = 800;
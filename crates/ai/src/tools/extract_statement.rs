@@ -0,0 +1,809 @@
+//! Extract Statement tool - turn attached statements and screenshots into activity drafts.
+//!
+//! Page images are sent to the extraction model as-is; PDF text is extracted
+//! locally first. The model must answer with a strict JSON document of trades,
+//! dividends and fees, which is checked against import classification and
+//! `NewActivity` rules, then normalized through the `record_activities` draft
+//! pipeline (account + symbol resolution). Nothing is saved: the user reviews
+//! and confirms the drafts in the UI.
+
+use async_trait::async_trait;
+use base64::Engine;
+use futures::StreamExt;
+use log::{debug, warn};
+use rig::{
+    completion::{CompletionModel, GetTokenUsage, Message, ToolDefinition},
+    message::{DocumentSourceKind, Image, ImageMediaType, Text, UserContent},
+    streaming::StreamedAssistantContent,
+    tool::Tool,
+    OneOrMany,
+};
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use wealthfolio_core::activities::{
+    classify_import_activity, ImportSymbolDisposition, NewActivity, SymbolInput,
+};
+
+use crate::env::AiEnvironment;
+use crate::error::AiError;
+use crate::tools::record_activities::{
+    ActivityDraftRow, RecordActivitiesArgs, RecordActivitiesOutput, RecordActivitiesTool,
+};
+use crate::tools::record_activity::{ActivityDraft, RecordActivityArgs, ValidationError};
+use crate::types::MessageAttachment;
+use crate::usage::TurnUsage;
+
+/// Fields with a reported confidence below this are flagged for review.
+pub const LOW_CONFIDENCE_THRESHOLD: f64 = 0.7;
+
+/// Max characters of extracted PDF text sent to the model per attachment.
+const MAX_PDF_TEXT_CHARS: usize = 60_000;
+
+/// Largest PDF read locally; bigger statements should be split or attached as page screenshots.
+const MAX_PDF_BYTES: usize = 20 * 1024 * 1024;
+
+/// Time allowed for reading the text layer of one PDF.
+const PDF_EXTRACT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Output token budget for the extraction answer.
+const MAX_EXTRACTION_TOKENS: u64 = 8192;
+
+const EXTRACTION_PROMPT: &str = r#"You extract investment transactions from brokerage statements, contract notes and screenshots.
+
+Answer with ONE JSON object and nothing else (no prose, no markdown fences), matching this schema:
+{
+  "account": string | null,            // account name or number printed on the document
+  "activities": [
+    {
+      "activityType": "BUY" | "SELL" | "DIVIDEND" | "INTEREST" | "FEE" | "TAX" | "DEPOSIT" | "WITHDRAWAL" | "SPLIT",
+      "activityDate": "YYYY-MM-DD",    // trade date, not settlement date
+      "symbol": string | null,         // ticker or ISIN as printed; null for cash movements
+      "quantity": number | null,       // units, always positive
+      "unitPrice": number | null,      // price per unit, always positive
+      "amount": number | null,         // total cash amount, always positive
+      "fee": number | null,            // commissions and charges for this activity
+      "currency": string | null,       // ISO 4217 code
+      "description": string | null,    // the source line, verbatim
+      "confidence": { "<field>": number }  // 0.0-1.0 for every non-null field above
+    }
+  ]
+}
+
+Rules:
+- One entry per trade, dividend, interest payment, fee or cash movement. Include every one visible.
+- Use null for anything not printed on the document. Never guess values.
+- Numbers are plain JSON numbers: no currency symbols, no thousands separators.
+- Withholding tax on a dividend is a separate TAX entry.
+- Lower the confidence of any value that is blurry, cut off or ambiguous.
+- If the document contains no transactions, answer {"account": null, "activities": []}."#;
+
+// ============================================================================
+// Extraction Model
+// ============================================================================
+
+/// Model backend for statement extraction.
+#[async_trait]
+pub trait StatementExtractor: Send + Sync {
+    /// Send the extraction instructions and document content; return the raw answer text.
+    async fn extract(
+        &self,
+        instructions: &str,
+        content: Vec<UserContent>,
+    ) -> Result<String, AiError>;
+}
+
+/// Extractor backed by a rig completion model. The model must accept images.
+///
+/// Token usage reported by the model is added to `usage`, which the chat turn
+/// records on its assistant message.
+pub struct ModelStatementExtractor<M: CompletionModel> {
+    model: M,
+    usage: Arc<TurnUsage>,
+}
+
+impl<M: CompletionModel> ModelStatementExtractor<M> {
+    pub fn new(model: M, usage: Arc<TurnUsage>) -> Self {
+        Self { model, usage }
+    }
+}
+
+#[async_trait]
+impl<M: CompletionModel + 'static> StatementExtractor for ModelStatementExtractor<M> {
+    async fn extract(
+        &self,
+        instructions: &str,
+        content: Vec<UserContent>,
+    ) -> Result<String, AiError> {
+        let content = OneOrMany::many(content)
+            .map_err(|_| AiError::InvalidInput("Nothing to extract".to_string()))?;
+        let request = self
+            .model
+            .completion_request(Message::User { content })
+            .preamble(instructions.to_string())
+            .temperature(0.0)
+            .max_tokens(MAX_EXTRACTION_TOKENS)
+            .build();
+
+        let mut stream = self
+            .model
+            .stream(request)
+            .await
+            .map_err(|e| AiError::Provider(e.to_string()))?;
+
+        let mut answer = String::new();
+        while let Some(item) = stream.next().await {
+            match item.map_err(|e| AiError::Provider(e.to_string()))? {
+                StreamedAssistantContent::Text(Text { text }) => answer.push_str(&text),
+                StreamedAssistantContent::Final(response) => {
+                    if let Some(usage) = response.token_usage() {
+                        self.usage.add(usage.input_tokens, usage.output_tokens);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(answer)
+    }
+}
+
+// ============================================================================
+// Extraction Schema (Model Output)
+// ============================================================================
+
+/// Document-level extraction result.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractedStatement {
+    #[serde(default)]
+    pub account: Option<String>,
+    pub activities: Vec<ExtractedActivity>,
+}
+
+/// One activity as read from the document.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractedActivity {
+    pub activity_type: String,
+    pub activity_date: String,
+    #[serde(default)]
+    pub symbol: Option<String>,
+    #[serde(default)]
+    pub quantity: Option<f64>,
+    #[serde(default)]
+    pub unit_price: Option<f64>,
+    #[serde(default)]
+    pub amount: Option<f64>,
+    #[serde(default)]
+    pub fee: Option<f64>,
+    #[serde(default)]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Per-field confidence (0.0-1.0), keyed by field name.
+    #[serde(default)]
+    pub confidence: BTreeMap<String, f64>,
+}
+
+/// Parse the model answer, tolerating markdown fences or text around the JSON object.
+pub fn parse_extraction(answer: &str) -> Result<ExtractedStatement, AiError> {
+    let start = answer.find('{');
+    let end = answer.rfind('}');
+    let json = match (start, end) {
+        (Some(start), Some(end)) if start < end => &answer[start..=end],
+        _ => {
+            return Err(AiError::ToolExecutionFailed(
+                "Extraction model did not return a JSON object".to_string(),
+            ))
+        }
+    };
+
+    serde_json::from_str(json).map_err(|e| {
+        AiError::ToolExecutionFailed(format!(
+            "Extraction model returned JSON that does not match the schema: {}",
+            e
+        ))
+    })
+}
+
+// ============================================================================
+// Tool Arguments / Output
+// ============================================================================
+
+/// Arguments for the extract_statement tool.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractStatementArgs {
+    /// Attachment file name to extract (all image/PDF attachments if omitted).
+    pub attachment: Option<String>,
+    /// Account name or ID for all drafts (falls back to the account printed on the document).
+    pub account: Option<String>,
+}
+
+/// Output envelope for extract_statement. Same shape as `record_activities`,
+/// so the batch review UI can confirm the drafts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractStatementOutput {
+    #[serde(flatten)]
+    pub batch: RecordActivitiesOutput,
+    /// Attachments that were sent for extraction.
+    pub sources: Vec<String>,
+    /// Attachments that could not be read, with the reason.
+    pub skipped: Vec<String>,
+}
+
+// ============================================================================
+// Tool Implementation
+// ============================================================================
+
+/// Whether an attachment can be extracted (images and PDFs).
+pub fn is_statement_attachment(attachment: &MessageAttachment) -> bool {
+    attachment.content_type.starts_with("image/") || attachment.content_type == "application/pdf"
+}
+
+/// Tool to extract activity drafts from attached statements and screenshots.
+pub struct ExtractStatementTool<E: AiEnvironment> {
+    env: Arc<E>,
+    attachments: Arc<Vec<MessageAttachment>>,
+    extractor: Arc<dyn StatementExtractor>,
+}
+
+impl<E: AiEnvironment> ExtractStatementTool<E> {
+    pub fn new(
+        env: Arc<E>,
+        attachments: Arc<Vec<MessageAttachment>>,
+        extractor: Arc<dyn StatementExtractor>,
+    ) -> Self {
+        Self {
+            env,
+            attachments,
+            extractor,
+        }
+    }
+}
+
+impl<E: AiEnvironment> Clone for ExtractStatementTool<E> {
+    fn clone(&self) -> Self {
+        Self {
+            env: self.env.clone(),
+            attachments: self.attachments.clone(),
+            extractor: self.extractor.clone(),
+        }
+    }
+}
+
+impl<E: AiEnvironment + 'static> Tool for ExtractStatementTool<E> {
+    const NAME: &'static str = "extract_statement";
+
+    type Error = AiError;
+    type Args = ExtractStatementArgs;
+    type Output = ExtractStatementOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        let names: Vec<&str> = self
+            .attachments
+            .iter()
+            .filter(|a| is_statement_attachment(a))
+            .map(|a| a.name.as_str())
+            .collect();
+
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Extract trades, dividends and fees from the attached statement, contract note or screenshot into reviewable activity drafts with per-field confidence. Attached files: {}",
+                names.join(", ")
+            ),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "attachment": {
+                        "type": "string",
+                        "description": "File name of the attachment to extract. Omit to extract all attached images and PDFs"
+                    },
+                    "account": {
+                        "type": "string",
+                        "description": "Account name or ID for the drafts. Omit to use the account printed on the document"
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let selected: Vec<&MessageAttachment> = self
+            .attachments
+            .iter()
+            .filter(|a| is_statement_attachment(a))
+            .filter(|a| {
+                args.attachment
+                    .as_deref()
+                    .is_none_or(|name| a.name.eq_ignore_ascii_case(name))
+            })
+            .collect();
+
+        if selected.is_empty() {
+            return Err(AiError::ToolExecutionFailed(match &args.attachment {
+                Some(name) => format!("No image or PDF attachment named '{}'", name),
+                None => "No image or PDF attachment to extract".to_string(),
+            }));
+        }
+
+        let mut content = vec![UserContent::Text(Text {
+            text: "Extract all activities from the following document(s).".to_string(),
+        })];
+        let mut sources = Vec::new();
+        let mut skipped = Vec::new();
+        for attachment in selected {
+            match attachment_content(attachment).await {
+                Ok(part) => {
+                    content.push(part);
+                    sources.push(attachment.name.clone());
+                }
+                Err(reason) => {
+                    warn!("Skipping attachment {}: {}", attachment.name, reason);
+                    skipped.push(format!("{}: {}", attachment.name, reason));
+                }
+            }
+        }
+
+        if sources.is_empty() {
+            return Err(AiError::ToolExecutionFailed(format!(
+                "Could not read any attachment. {}",
+                skipped.join("; ")
+            )));
+        }
+
+        debug!("extract_statement: extracting from {:?}", sources);
+        let answer = self.extractor.extract(EXTRACTION_PROMPT, content).await?;
+        let statement = parse_extraction(&answer)?;
+        debug!(
+            "extract_statement: model returned {} activities",
+            statement.activities.len()
+        );
+
+        let account = args
+            .account
+            .filter(|s| !s.trim().is_empty())
+            .or(statement.account);
+
+        let mut rows = Vec::with_capacity(statement.activities.len());
+        let mut activities = Vec::with_capacity(statement.activities.len());
+        for extracted in statement.activities {
+            let (row_args, review) = to_record_args(&extracted, account.clone());
+            activities.push(row_args);
+            rows.push((extracted, review));
+        }
+
+        let mut batch = RecordActivitiesTool::new(self.env.clone())
+            .call(RecordActivitiesArgs { activities })
+            .await?;
+
+        for (row, (extracted, review)) in batch.drafts.iter_mut().zip(rows) {
+            review_row(row, &extracted, review);
+        }
+
+        let valid_rows = batch
+            .drafts
+            .iter()
+            .filter(|row| row.validation.is_valid)
+            .count();
+        batch.validation.valid_rows = valid_rows;
+        batch.validation.error_rows = batch.validation.total_rows.saturating_sub(valid_rows);
+
+        Ok(ExtractStatementOutput {
+            batch,
+            sources,
+            skipped,
+        })
+    }
+}
+
+/// Convert an attachment into model input: images as-is, PDFs as locally extracted text.
+async fn attachment_content(attachment: &MessageAttachment) -> Result<UserContent, String> {
+    let content_type = attachment.content_type.as_str();
+    if content_type.starts_with("image/") {
+        let media_type = match content_type {
+            "image/png" => Some(ImageMediaType::PNG),
+            "image/jpeg" | "image/jpg" => Some(ImageMediaType::JPEG),
+            "image/webp" => Some(ImageMediaType::WEBP),
+            "image/gif" => Some(ImageMediaType::GIF),
+            _ => None,
+        };
+        return Ok(UserContent::Image(Image {
+            data: DocumentSourceKind::Base64(attachment.data.clone()),
+            media_type,
+            detail: None,
+            additional_params: None,
+        }));
+    }
+
+    let text = extract_pdf_text(&attachment.data).await?;
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("PDF has no text layer; attach screenshots of the pages instead".to_string());
+    }
+
+    let truncated = text.chars().count() > MAX_PDF_TEXT_CHARS;
+    let mut text: String = text.chars().take(MAX_PDF_TEXT_CHARS).collect();
+    if truncated {
+        text.push_str("\n[... truncated]");
+    }
+    Ok(UserContent::Text(Text {
+        text: format!("[Statement text from {}]\n{}", attachment.name, text),
+    }))
+}
+
+/// Read the text layer of a base64-encoded PDF.
+///
+/// Parsing is CPU-bound, so it runs on the blocking pool. A parse that runs
+/// past the timeout is abandoned; its thread finishes in the background.
+async fn extract_pdf_text(data: &str) -> Result<String, String> {
+    let data = data.trim();
+    // Base64 encodes 3 bytes in 4 characters; reject before decoding
+    if data.len() / 4 * 3 > MAX_PDF_BYTES {
+        return Err(format!(
+            "PDF is larger than {} MB; attach screenshots of the pages instead",
+            MAX_PDF_BYTES / (1024 * 1024)
+        ));
+    }
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|e| format!("invalid base64 data ({})", e))?;
+
+    // pdf-extract panics on some malformed files instead of returning an error;
+    // the panic surfaces as a join error
+    let task = tokio::task::spawn_blocking(move || pdf_extract::extract_text_from_mem(&bytes));
+    match tokio::time::timeout(PDF_EXTRACT_TIMEOUT, task).await {
+        Ok(Ok(result)) => result.map_err(|e| format!("could not read PDF ({})", e)),
+        Ok(Err(_)) => Err("could not read PDF".to_string()),
+        Err(_) => Err(format!(
+            "reading the PDF took longer than {}s",
+            PDF_EXTRACT_TIMEOUT.as_secs()
+        )),
+    }
+}
+
+/// Map an extracted activity onto `record_activity` arguments, applying the
+/// import symbol classification. Returns the review reason for ambiguous rows.
+fn to_record_args(
+    extracted: &ExtractedActivity,
+    account: Option<String>,
+) -> (RecordActivityArgs, Option<String>) {
+    let activity_type = extracted.activity_type.trim().to_uppercase();
+    let symbol = extracted
+        .symbol
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+
+    let disposition = classify_import_activity(
+        &activity_type,
+        symbol.unwrap_or_default(),
+        extracted.quantity.and_then(Decimal::from_f64),
+        extracted.unit_price.and_then(Decimal::from_f64),
+    );
+    let (symbol, review) = match disposition {
+        ImportSymbolDisposition::ResolveAsset => (symbol.map(str::to_string), None),
+        ImportSymbolDisposition::CashMovement => (None, None),
+        ImportSymbolDisposition::NeedsReview(reason) => (symbol.map(str::to_string), Some(reason)),
+    };
+
+    let args = RecordActivityArgs {
+        activity_type,
+        symbol,
+        activity_date: extracted.activity_date.trim().to_string(),
+        quantity: extracted.quantity.map(f64::abs),
+        unit_price: extracted.unit_price.map(f64::abs),
+        amount: extracted.amount.map(f64::abs),
+        fee: extracted.fee.map(f64::abs),
+        account,
+        subtype: None,
+        notes: extracted
+            .description
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string),
+    };
+    (args, review)
+}
+
+/// Apply extraction-specific checks to a normalized draft row.
+fn review_row(row: &mut ActivityDraftRow, extracted: &ExtractedActivity, review: Option<String>) {
+    if let Some(reason) = review {
+        row.validation.errors.push(ValidationError {
+            field: "symbol".to_string(),
+            message: reason.clone(),
+        });
+        row.errors.push(reason);
+    }
+
+    // Rows that pass draft validation must also be acceptable as a NewActivity.
+    if row.validation.errors.is_empty() && row.validation.missing_fields.is_empty() {
+        if let Err(e) = to_new_activity(&row.draft).validate() {
+            row.validation.errors.push(ValidationError {
+                field: "activity".to_string(),
+                message: e.to_string(),
+            });
+            row.errors.push(e.to_string());
+        }
+    }
+    row.validation.is_valid =
+        row.validation.errors.is_empty() && row.validation.missing_fields.is_empty();
+
+    if let Some(currency) = extracted.currency.as_deref().map(str::trim) {
+        if !currency.is_empty() && !currency.eq_ignore_ascii_case(&row.draft.currency) {
+            row.warnings.push(format!(
+                "Statement currency {} differs from draft currency {}",
+                currency.to_uppercase(),
+                row.draft.currency
+            ));
+        }
+    }
+
+    let confidence: BTreeMap<String, f64> = extracted
+        .confidence
+        .iter()
+        .map(|(field, value)| (field.clone(), value.clamp(0.0, 1.0)))
+        .collect();
+    let low: Vec<&str> = confidence
+        .iter()
+        .filter(|(_, value)| **value < LOW_CONFIDENCE_THRESHOLD)
+        .map(|(field, _)| field.as_str())
+        .collect();
+    if !low.is_empty() {
+        row.warnings
+            .push(format!("Low confidence: {}", low.join(", ")));
+    }
+    if !confidence.is_empty() {
+        row.confidence = Some(confidence);
+    }
+}
+
+fn to_new_activity(draft: &ActivityDraft) -> NewActivity {
+    let decimal = |value: Option<f64>| value.and_then(Decimal::from_f64);
+    NewActivity {
+        id: None,
+        account_id: draft.account_id.clone().unwrap_or_default(),
+        symbol: draft.symbol.as_ref().map(|symbol| SymbolInput {
+            id: draft.asset_id.clone(),
+            symbol: Some(symbol.clone()),
+            name: draft.asset_name.clone(),
+            ..Default::default()
+        }),
+        activity_type: draft.activity_type.clone(),
+        subtype: draft.subtype.clone(),
+        activity_date: draft.activity_date.clone(),
+        quantity: decimal(draft.quantity),
+        unit_price: decimal(draft.unit_price),
+        currency: draft.currency.clone(),
+        fee: decimal(draft.fee),
+        amount: decimal(draft.amount),
+        status: None,
+        notes: draft.notes.clone(),
+        fx_rate: None,
+        metadata: None,
+        needs_review: None,
+        source_system: None,
+        source_record_id: None,
+        source_group_id: None,
+        idempotency_key: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test_env::{MockAccountService, MockEnvironment, MockQuoteService};
+    use crate::eval::{Cassette, ReplayModel, Turn};
+    use chrono::Utc;
+    use std::sync::RwLock;
+    use wealthfolio_core::accounts::Account;
+    use wealthfolio_core::quotes::SymbolSearchResult;
+
+    fn env() -> MockEnvironment {
+        let mut env = MockEnvironment::new();
+        env.account_service = Arc::new(MockAccountService {
+            accounts: vec![Account {
+                id: "acc-1".to_string(),
+                name: "Main Broker".to_string(),
+                currency: "USD".to_string(),
+                is_active: true,
+                created_at: Utc::now().naive_utc(),
+                updated_at: Utc::now().naive_utc(),
+                ..Account::default()
+            }],
+        });
+        env.quote_service = Arc::new(MockQuoteService {
+            search_results: RwLock::new(vec![SymbolSearchResult {
+                symbol: "AAPL".to_string(),
+                long_name: "Apple Inc.".to_string(),
+                exchange_mic: Some("XNAS".to_string()),
+                exchange_name: Some("NASDAQ".to_string()),
+                currency: Some("USD".to_string()),
+                existing_asset_id: Some("SEC:AAPL:XNAS".to_string()),
+                ..SymbolSearchResult::default()
+            }]),
+            ..Default::default()
+        });
+        env
+    }
+
+    fn screenshot() -> MessageAttachment {
+        MessageAttachment {
+            name: "contract-note.png".to_string(),
+            content_type: "image/png".to_string(),
+            data: "iVBORw0KGgo=".to_string(),
+        }
+    }
+
+    /// Tool whose extraction model replays the given answer.
+    fn tool(
+        answer: &str,
+        attachments: Vec<MessageAttachment>,
+    ) -> ExtractStatementTool<MockEnvironment> {
+        let model = ReplayModel::new(Cassette::scripted(vec![Turn::new().text(answer)]));
+        ExtractStatementTool::new(
+            Arc::new(env()),
+            Arc::new(attachments),
+            Arc::new(ModelStatementExtractor::new(
+                model,
+                Arc::new(TurnUsage::default()),
+            )),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_extracts_trade_dividend_and_fee() {
+        let answer = serde_json::json!({
+            "account": "Main Broker",
+            "activities": [
+                {
+                    "activityType": "BUY",
+                    "activityDate": "2026-03-02",
+                    "symbol": "AAPL",
+                    "quantity": 10,
+                    "unitPrice": 182.5,
+                    "fee": 1.0,
+                    "currency": "USD",
+                    "description": "BOT 10 AAPL @ 182.50",
+                    "confidence": { "symbol": 0.98, "quantity": 0.95, "unitPrice": 0.55 }
+                },
+                {
+                    "activityType": "DIVIDEND",
+                    "activityDate": "2026-03-15",
+                    "symbol": "AAPL",
+                    "amount": 2.4,
+                    "currency": "USD"
+                },
+                {
+                    "activityType": "FEE",
+                    "activityDate": "2026-03-31",
+                    "symbol": "USD",
+                    "amount": 5.0
+                }
+            ]
+        });
+        let tool = tool(&format!("```json\n{}\n```", answer), vec![screenshot()]);
+
+        let output = tool
+            .call(ExtractStatementArgs::default())
+            .await
+            .expect("extraction should succeed");
+
+        assert_eq!(output.sources, vec!["contract-note.png"]);
+        assert_eq!(output.batch.validation.total_rows, 3);
+        assert_eq!(output.batch.validation.valid_rows, 3);
+
+        let buy = &output.batch.drafts[0];
+        assert_eq!(buy.draft.account_id.as_deref(), Some("acc-1"));
+        assert_eq!(buy.draft.asset_id.as_deref(), Some("SEC:AAPL:XNAS"));
+        assert_eq!(buy.confidence.as_ref().unwrap()["unitPrice"], 0.55);
+        assert!(buy.warnings.iter().any(|w| w.contains("unitPrice")));
+
+        // Cash placeholder symbol on a fee is cleared by import classification.
+        assert_eq!(output.batch.drafts[2].draft.symbol, None);
+    }
+
+    #[tokio::test]
+    async fn test_extraction_usage_is_added_to_the_turn() {
+        let model = ReplayModel::new(Cassette::scripted(vec![Turn::new()
+            .text(r#"{"activities": []}"#)
+            .usage(1_200, 80)]));
+        let usage = Arc::new(TurnUsage::default());
+        let tool = ExtractStatementTool::new(
+            Arc::new(env()),
+            Arc::new(vec![screenshot()]),
+            Arc::new(ModelStatementExtractor::new(model, usage.clone())),
+        );
+
+        tool.call(ExtractStatementArgs::default()).await.unwrap();
+
+        let recorded = usage.apply(None, "anthropic", "claude").unwrap();
+        assert_eq!(recorded.prompt_tokens, 1_200);
+        assert_eq!(recorded.completion_tokens, 80);
+    }
+
+    #[tokio::test]
+    async fn test_ambiguous_transfer_needs_review() {
+        let answer = r#"{"activities": [{
+            "activityType": "TRANSFER_IN",
+            "activityDate": "2026-03-02",
+            "symbol": "AAPL"
+        }]}"#;
+        let output = tool(answer, vec![screenshot()])
+            .call(ExtractStatementArgs::default())
+            .await
+            .unwrap();
+
+        let row = &output.batch.drafts[0];
+        assert!(!row.validation.is_valid);
+        assert!(row.validation.errors.iter().any(|e| e.field == "symbol"));
+        assert_eq!(output.batch.validation.error_rows, 1);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_answer_is_a_tool_error() {
+        let error = tool("I could not read the image.", vec![screenshot()])
+            .call(ExtractStatementArgs::default())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("JSON"));
+
+        let error = tool(
+            r#"{"activities": [{"activityType": "BUY"}]}"#,
+            vec![screenshot()],
+        )
+        .call(ExtractStatementArgs::default())
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("schema"));
+    }
+
+    #[tokio::test]
+    async fn test_requires_matching_attachment() {
+        let csv = MessageAttachment {
+            name: "trades.csv".to_string(),
+            content_type: "text/csv".to_string(),
+            data: "date,symbol".to_string(),
+        };
+        let error = tool("{}", vec![csv])
+            .call(ExtractStatementArgs::default())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("No image or PDF"));
+
+        let error = tool("{}", vec![screenshot()])
+            .call(ExtractStatementArgs {
+                attachment: Some("other.pdf".to_string()),
+                account: None,
+            })
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("other.pdf"));
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_pdf_is_skipped() {
+        let pdf = MessageAttachment {
+            name: "statement.pdf".to_string(),
+            content_type: "application/pdf".to_string(),
+            data: base64::engine::general_purpose::STANDARD.encode(b"not a pdf"),
+        };
+        let error = tool("{}", vec![pdf])
+            .call(ExtractStatementArgs::default())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("statement.pdf"));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_pdf_is_not_parsed() {
+        let data = "A".repeat(MAX_PDF_BYTES / 3 * 4 + 8);
+        let error = extract_pdf_text(&data).await.unwrap_err();
+        assert!(error.contains("larger than"));
+    }
+}
@@ -0,0 +1,179 @@
+//! Exchange rate tool - fetch FX rates using FxService.
+
+use chrono::NaiveDate;
+use rig::{completion::ToolDefinition, tool::Tool};
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::env::AiEnvironment;
+use crate::error::AiError;
+
+// ============================================================================
+// Tool Arguments and Output
+// ============================================================================
+
+/// Arguments for the get_exchange_rate tool.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetExchangeRateArgs {
+    /// Source currency code (e.g., "HKD").
+    pub from_currency: String,
+    /// Target currency code. Defaults to the base currency.
+    #[serde(default)]
+    pub to_currency: Option<String>,
+    /// Date for a historical rate (YYYY-MM-DD format). Defaults to the latest rate.
+    #[serde(default)]
+    pub date: Option<String>,
+}
+
+/// Output for exchange rate tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetExchangeRateOutput {
+    pub from_currency: String,
+    pub to_currency: String,
+    /// Units of `to_currency` per one unit of `from_currency`.
+    pub rate: f64,
+    pub inverse_rate: f64,
+    /// Requested date, or absent for the latest rate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+}
+
+// ============================================================================
+// Tool Implementation
+// ============================================================================
+
+/// Tool to get the latest or historical exchange rate between two currencies.
+pub struct GetExchangeRateTool<E: AiEnvironment> {
+    env: Arc<E>,
+    base_currency: String,
+}
+
+impl<E: AiEnvironment> GetExchangeRateTool<E> {
+    pub fn new(env: Arc<E>, base_currency: String) -> Self {
+        Self { env, base_currency }
+    }
+}
+
+impl<E: AiEnvironment> Clone for GetExchangeRateTool<E> {
+    fn clone(&self) -> Self {
+        Self {
+            env: self.env.clone(),
+            base_currency: self.base_currency.clone(),
+        }
+    }
+}
+
+impl<E: AiEnvironment + 'static> Tool for GetExchangeRateTool<E> {
+    const NAME: &'static str = "get_exchange_rate";
+
+    type Error = AiError;
+    type Args = GetExchangeRateArgs;
+    type Output = GetExchangeRateOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Get the exchange rate between two currencies, either the latest rate or the rate on a given date (e.g., the rate on a purchase date). Uses the same rates the app uses for portfolio valuation.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "fromCurrency": {
+                        "type": "string",
+                        "description": "Source currency code, e.g. 'HKD'"
+                    },
+                    "toCurrency": {
+                        "type": "string",
+                        "description": "Target currency code. Defaults to the base currency."
+                    },
+                    "date": {
+                        "type": "string",
+                        "description": "Date in YYYY-MM-DD format for a historical rate. Omit for the latest rate."
+                    }
+                },
+                "required": ["fromCurrency"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let from_currency = args.from_currency.trim().to_uppercase();
+        let to_currency = args
+            .to_currency
+            .as_deref()
+            .map(|c| c.trim().to_uppercase())
+            .unwrap_or_else(|| self.base_currency.clone());
+
+        let date = args
+            .date
+            .as_deref()
+            .map(|s| {
+                NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| {
+                    AiError::ToolExecutionFailed(format!(
+                        "Invalid date '{}', expected YYYY-MM-DD",
+                        s
+                    ))
+                })
+            })
+            .transpose()?;
+
+        let fx_service = self.env.fx_service();
+        let rate = match date {
+            Some(date) => fx_service.get_exchange_rate_for_date(&from_currency, &to_currency, date),
+            None => fx_service.get_latest_exchange_rate(&from_currency, &to_currency),
+        }
+        .map_err(|e| AiError::ToolExecutionFailed(e.to_string()))?;
+
+        let rate = rate.to_f64().unwrap_or(0.0);
+        let inverse_rate = if rate != 0.0 { 1.0 / rate } else { 0.0 };
+
+        Ok(GetExchangeRateOutput {
+            from_currency,
+            to_currency,
+            rate,
+            inverse_rate,
+            date: date.map(|d| d.format("%Y-%m-%d").to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test_env::MockEnvironment;
+
+    #[tokio::test]
+    async fn test_get_exchange_rate_for_date() {
+        // The mock environment quotes USD/HKD at 7.8
+        let tool = GetExchangeRateTool::new(Arc::new(MockEnvironment::new()), "USD".to_string());
+
+        let output = tool
+            .call(GetExchangeRateArgs {
+                from_currency: "hkd".to_string(),
+                to_currency: None,
+                date: Some("2024-05-02".to_string()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(output.from_currency, "HKD");
+        assert_eq!(output.to_currency, "USD");
+        assert!((output.inverse_rate - 7.8).abs() < 1e-9);
+        assert_eq!(output.date.as_deref(), Some("2024-05-02"));
+    }
+
+    #[tokio::test]
+    async fn test_get_exchange_rate_missing_pair() {
+        let tool = GetExchangeRateTool::new(Arc::new(MockEnvironment::new()), "USD".to_string());
+
+        let result = tool
+            .call(GetExchangeRateArgs {
+                from_currency: "JPY".to_string(),
+                to_currency: Some("EUR".to_string()),
+                date: None,
+            })
+            .await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,286 @@
+//! Health issues tool - fetch data quality issues using HealthService.
+
+use rig::{completion::ToolDefinition, tool::Tool};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use wealthfolio_core::health::{HealthIssue, Severity};
+
+use super::constants::{MAX_AFFECTED_ITEMS, MAX_HEALTH_ISSUES};
+use crate::env::AiEnvironment;
+use crate::error::AiError;
+
+// ============================================================================
+// Tool Arguments and Output
+// ============================================================================
+
+/// Arguments for the get_health_issues tool.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetHealthIssuesArgs {
+    /// Minimum severity to include: "INFO", "WARNING", "ERROR" or "CRITICAL".
+    #[serde(default)]
+    pub min_severity: Option<String>,
+}
+
+/// DTO for an asset or account affected by a health issue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AffectedItemDto {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+}
+
+/// DTO for a health issue in tool output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthIssueDto {
+    pub id: String,
+    pub severity: String,
+    pub category: String,
+    pub title: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    pub affected_count: u32,
+    /// Share of portfolio market value affected, as a percentage.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub affected_mv_pct: Option<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub affected_items: Vec<AffectedItemDto>,
+    /// Label of the fix or navigation the app offers for this issue.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggested_action: Option<String>,
+}
+
+/// Output envelope for health issues tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetHealthIssuesOutput {
+    pub issues: Vec<HealthIssueDto>,
+    pub count: usize,
+    pub overall_severity: String,
+    /// When the checks last ran; absent if they have not run yet.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checked_at: Option<String>,
+    pub is_stale: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_count: Option<usize>,
+}
+
+// ============================================================================
+// Tool Implementation
+// ============================================================================
+
+/// Tool to get portfolio health issues (stale prices, missing FX rates, etc.).
+pub struct GetHealthIssuesTool<E: AiEnvironment> {
+    env: Arc<E>,
+}
+
+impl<E: AiEnvironment> GetHealthIssuesTool<E> {
+    pub fn new(env: Arc<E>) -> Self {
+        Self { env }
+    }
+}
+
+impl<E: AiEnvironment> Clone for GetHealthIssuesTool<E> {
+    fn clone(&self) -> Self {
+        Self {
+            env: self.env.clone(),
+        }
+    }
+}
+
+fn parse_severity(value: &str) -> Result<Severity, AiError> {
+    match value.to_uppercase().as_str() {
+        "INFO" => Ok(Severity::Info),
+        "WARNING" => Ok(Severity::Warning),
+        "ERROR" => Ok(Severity::Error),
+        "CRITICAL" => Ok(Severity::Critical),
+        _ => Err(AiError::ToolExecutionFailed(format!(
+            "Invalid severity '{}', expected INFO, WARNING, ERROR or CRITICAL",
+            value
+        ))),
+    }
+}
+
+fn to_issue_dto(issue: HealthIssue) -> HealthIssueDto {
+    let suggested_action = issue
+        .fix_action
+        .map(|a| a.label)
+        .or_else(|| issue.navigate_action.map(|a| a.label));
+
+    HealthIssueDto {
+        id: issue.id,
+        severity: issue.severity.as_str().to_string(),
+        category: issue.category.label().to_string(),
+        title: issue.title,
+        message: issue.message,
+        details: issue.details,
+        affected_count: issue.affected_count,
+        affected_mv_pct: issue.affected_mv_pct,
+        affected_items: issue
+            .affected_items
+            .unwrap_or_default()
+            .into_iter()
+            .take(MAX_AFFECTED_ITEMS)
+            .map(|item| AffectedItemDto {
+                name: item.name,
+                symbol: item.symbol,
+            })
+            .collect(),
+        suggested_action,
+    }
+}
+
+impl<E: AiEnvironment + 'static> Tool for GetHealthIssuesTool<E> {
+    const NAME: &'static str = "get_health_issues";
+
+    type Error = AiError;
+    type Args = GetHealthIssuesArgs;
+    type Output = GetHealthIssuesOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Get portfolio data health issues from the latest health check: stale or missing prices, missing exchange rates, unclassified assets, data inconsistencies, account setup problems and broker reconciliation drift. Use this when the user asks why a value looks wrong. Returns severity, explanation, affected assets and the share of portfolio value affected.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "minSeverity": {
+                        "type": "string",
+                        "enum": ["INFO", "WARNING", "ERROR", "CRITICAL"],
+                        "description": "Only include issues at or above this severity. Defaults to all issues."
+                    }
+                },
+                "required": []
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let min_severity = args
+            .min_severity
+            .as_deref()
+            .map(parse_severity)
+            .transpose()?
+            .unwrap_or(Severity::Info);
+
+        // Checks run in the background when portfolio data changes; read
+        // their latest result rather than re-running them per question.
+        let status = self.env.health_service().get_cached_status().await;
+        let checked_at = status.as_ref().map(|s| s.checked_at.to_rfc3339());
+        let is_stale = status.as_ref().is_some_and(|s| s.is_stale);
+        let overall_severity = status
+            .as_ref()
+            .map(|s| s.overall_severity)
+            .unwrap_or_default();
+
+        let mut issues: Vec<HealthIssue> = status
+            .map(|s| s.issues)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|i| i.severity >= min_severity)
+            .collect();
+
+        // Most severe and most impactful first, so truncation drops the least useful
+        issues.sort_by(|a, b| {
+            b.severity.cmp(&a.severity).then_with(|| {
+                b.affected_mv_pct
+                    .unwrap_or(0.0)
+                    .total_cmp(&a.affected_mv_pct.unwrap_or(0.0))
+            })
+        });
+
+        let original_count = issues.len();
+
+        let issues: Vec<HealthIssueDto> = issues
+            .into_iter()
+            .take(MAX_HEALTH_ISSUES)
+            .map(to_issue_dto)
+            .collect();
+
+        let returned_count = issues.len();
+        let truncated = original_count > returned_count;
+
+        Ok(GetHealthIssuesOutput {
+            issues,
+            count: returned_count,
+            overall_severity: overall_severity.as_str().to_string(),
+            checked_at,
+            is_stale,
+            truncated: if truncated { Some(true) } else { None },
+            original_count: if truncated {
+                Some(original_count)
+            } else {
+                None
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test_env::{MockEnvironment, MockHealthService};
+    use wealthfolio_core::health::{AffectedItem, HealthCategory, HealthStatus};
+
+    fn issue(id: &str, severity: Severity, mv_pct: f64) -> HealthIssue {
+        HealthIssue::builder()
+            .id(id)
+            .severity(severity)
+            .category(HealthCategory::PriceStaleness)
+            .title("Stale prices")
+            .message("Prices have not updated in 3 days")
+            .affected_count(1)
+            .affected_mv_pct(mv_pct)
+            .affected_items(vec![AffectedItem {
+                id: "SEC:AAPL:XNAS".to_string(),
+                name: "Apple Inc.".to_string(),
+                symbol: Some("AAPL".to_string()),
+                route: None,
+            }])
+            .data_hash(id)
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_get_health_issues_tool() {
+        let mut env = MockEnvironment::new();
+        env.health_service = Arc::new(MockHealthService {
+            status: Some(HealthStatus::from_issues(vec![
+                issue("minor", Severity::Warning, 2.0),
+                issue("major", Severity::Error, 40.0),
+                issue("info", Severity::Info, 0.0),
+            ])),
+        });
+        let tool = GetHealthIssuesTool::new(Arc::new(env));
+
+        let output = tool
+            .call(GetHealthIssuesArgs {
+                min_severity: Some("warning".to_string()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(output.count, 2);
+        assert_eq!(output.overall_severity, "ERROR");
+        assert_eq!(output.issues[0].id, "major");
+        assert_eq!(output.issues[0].category, "Price Updates");
+        assert_eq!(
+            output.issues[0].affected_items[0].symbol.as_deref(),
+            Some("AAPL")
+        );
+        assert!(output.checked_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_get_health_issues_before_first_check() {
+        let tool = GetHealthIssuesTool::new(Arc::new(MockEnvironment::new()));
+
+        let output = tool.call(GetHealthIssuesArgs::default()).await.unwrap();
+        assert_eq!(output.count, 0);
+        assert!(output.checked_at.is_none());
+    }
+}
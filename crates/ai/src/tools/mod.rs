@@ -9,22 +9,36 @@
 //! - SearchActivitiesTool: Search transactions
 //! - GetIncomeTool: Fetch income summaries (dividends, interest, other income)
 //! - GetGoalsTool: Fetch investment goals with progress
+//! - GetNetWorthTool: Fetch net worth with assets/liabilities breakdown
+//! - GetAlternativeAssetsTool: Fetch properties, vehicles, collectibles and liabilities
+//! - GetHealthIssuesTool: Fetch portfolio data health issues
+//! - GetExchangeRateTool: Fetch latest or historical exchange rates
+//! - GetQuoteHistoryTool: Fetch historical prices for an asset
+//! - SimulateScenarioTool: Simulate hypothetical trades without recording them
 //! - RecordActivityTool: Create activity drafts from natural language
 //! - RecordActivitiesTool: Create multiple activity drafts from natural language
+//! - ExtractStatementTool: Extract activity drafts from attached statements/screenshots
 //!
 //! All tools are designed to work with the AiEnvironment trait for dependency injection.
 
 pub mod accounts;
 pub mod activities;
 pub mod allocation;
+pub mod alternative_assets;
 pub mod constants;
+pub mod extract_statement;
+pub mod fx;
 pub mod goals;
+pub mod health;
 pub mod holdings;
 pub mod import_csv;
 pub mod income;
+pub mod net_worth;
 pub mod performance;
+pub mod quotes;
 pub mod record_activities;
 pub mod record_activity;
+pub mod scenario;
 pub mod valuation;
 
 // Re-export constants
@@ -34,13 +48,20 @@ pub use constants::*;
 pub use accounts::GetAccountsTool;
 pub use activities::SearchActivitiesTool;
 pub use allocation::GetAssetAllocationTool;
+pub use alternative_assets::GetAlternativeAssetsTool;
+pub use extract_statement::ExtractStatementTool;
+pub use fx::GetExchangeRateTool;
 pub use goals::GetGoalsTool;
+pub use health::GetHealthIssuesTool;
 pub use holdings::GetHoldingsTool;
 pub use import_csv::ImportCsvTool;
 pub use income::GetIncomeTool;
+pub use net_worth::GetNetWorthTool;
 pub use performance::GetPerformanceTool;
+pub use quotes::GetQuoteHistoryTool;
 pub use record_activities::RecordActivitiesTool;
 pub use record_activity::RecordActivityTool;
+pub use scenario::SimulateScenarioTool;
 pub use valuation::GetValuationHistoryTool;
 
 use std::sync::Arc;
@@ -57,6 +78,12 @@ pub struct ToolSet<E: AiEnvironment> {
     pub valuation: GetValuationHistoryTool<E>,
     pub goals: GetGoalsTool<E>,
     pub performance: GetPerformanceTool<E>,
+    pub net_worth: GetNetWorthTool<E>,
+    pub alternative_assets: GetAlternativeAssetsTool<E>,
+    pub health: GetHealthIssuesTool<E>,
+    pub exchange_rate: GetExchangeRateTool<E>,
+    pub quote_history: GetQuoteHistoryTool<E>,
+    pub scenario: SimulateScenarioTool<E>,
     pub record_activity: RecordActivityTool<E>,
     pub record_activities: RecordActivitiesTool<E>,
     pub import_csv: ImportCsvTool<E>,
@@ -74,6 +101,12 @@ impl<E: AiEnvironment> ToolSet<E> {
             valuation: GetValuationHistoryTool::new(env.clone(), base_currency.clone()),
             goals: GetGoalsTool::new(env.clone()),
             performance: GetPerformanceTool::new(env.clone(), base_currency.clone()),
+            net_worth: GetNetWorthTool::new(env.clone()),
+            alternative_assets: GetAlternativeAssetsTool::new(env.clone(), base_currency.clone()),
+            health: GetHealthIssuesTool::new(env.clone()),
+            exchange_rate: GetExchangeRateTool::new(env.clone(), base_currency.clone()),
+            quote_history: GetQuoteHistoryTool::new(env.clone(), base_currency.clone()),
+            scenario: SimulateScenarioTool::new(env.clone(), base_currency.clone()),
             record_activity: RecordActivityTool::new(env.clone()),
             record_activities: RecordActivitiesTool::new(env.clone()),
             import_csv: ImportCsvTool::new(env, base_currency),
@@ -0,0 +1,259 @@
+//! Net worth tool - fetch assets, liabilities and net worth using NetWorthService.
+
+use chrono::NaiveDate;
+use rig::{completion::ToolDefinition, tool::Tool};
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use wealthfolio_core::portfolio::net_worth::BreakdownItem;
+
+use super::constants::MAX_NET_WORTH_ITEMS;
+use crate::env::AiEnvironment;
+use crate::error::AiError;
+
+// ============================================================================
+// Tool Arguments and Output
+// ============================================================================
+
+/// Arguments for the get_net_worth tool.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetNetWorthArgs {
+    /// As-of date (YYYY-MM-DD format). Defaults to today.
+    #[serde(default)]
+    pub date: Option<String>,
+}
+
+/// DTO for one asset or liability line in tool output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetWorthItemDto {
+    pub category: String,
+    pub name: String,
+    pub value: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset_id: Option<String>,
+}
+
+/// DTO for an asset whose latest valuation is older than the as-of date.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StaleAssetDto {
+    pub asset_id: String,
+    pub name: Option<String>,
+    pub valuation_date: String,
+    pub days_stale: i64,
+}
+
+/// Output envelope for net worth tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetNetWorthOutput {
+    pub date: String,
+    pub currency: String,
+    pub net_worth: f64,
+    pub total_assets: f64,
+    pub total_liabilities: f64,
+    pub assets: Vec<NetWorthItemDto>,
+    pub liabilities: Vec<NetWorthItemDto>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_valuation_date: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stale_assets: Vec<StaleAssetDto>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_count: Option<usize>,
+}
+
+// ============================================================================
+// Tool Implementation
+// ============================================================================
+
+/// Tool to get net worth with an assets/liabilities breakdown.
+pub struct GetNetWorthTool<E: AiEnvironment> {
+    env: Arc<E>,
+}
+
+impl<E: AiEnvironment> GetNetWorthTool<E> {
+    pub fn new(env: Arc<E>) -> Self {
+        Self { env }
+    }
+}
+
+impl<E: AiEnvironment> Clone for GetNetWorthTool<E> {
+    fn clone(&self) -> Self {
+        Self {
+            env: self.env.clone(),
+        }
+    }
+}
+
+fn to_item_dto(item: BreakdownItem) -> NetWorthItemDto {
+    NetWorthItemDto {
+        category: item.category,
+        name: item.name,
+        value: item.value.to_f64().unwrap_or(0.0),
+        asset_id: item.asset_id,
+    }
+}
+
+impl<E: AiEnvironment + 'static> Tool for GetNetWorthTool<E> {
+    const NAME: &'static str = "get_net_worth";
+
+    type Error = AiError;
+    type Args = GetNetWorthArgs;
+    type Output = GetNetWorthOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Get net worth as of a date: total assets, total liabilities and net worth in the base currency, with a breakdown that includes investment accounts, cash, and alternative assets such as property, vehicles, collectibles and precious metals, plus liabilities such as mortgages and loans. Also lists assets whose valuations are stale.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "date": {
+                        "type": "string",
+                        "description": "As-of date in YYYY-MM-DD format. Defaults to today."
+                    }
+                },
+                "required": []
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let date = match args.date.as_deref() {
+            Some(s) => NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| {
+                AiError::ToolExecutionFailed(format!("Invalid date '{}', expected YYYY-MM-DD", s))
+            })?,
+            None => chrono::Utc::now().date_naive(),
+        };
+
+        let response = self
+            .env
+            .net_worth_service()
+            .get_net_worth(date)
+            .await
+            .map_err(|e| AiError::ToolExecutionFailed(e.to_string()))?;
+
+        let original_count = response.assets.breakdown.len() + response.liabilities.breakdown.len();
+
+        let assets: Vec<NetWorthItemDto> = response
+            .assets
+            .breakdown
+            .into_iter()
+            .take(MAX_NET_WORTH_ITEMS)
+            .map(to_item_dto)
+            .collect();
+        let liabilities: Vec<NetWorthItemDto> = response
+            .liabilities
+            .breakdown
+            .into_iter()
+            .take(MAX_NET_WORTH_ITEMS)
+            .map(to_item_dto)
+            .collect();
+
+        let stale_assets: Vec<StaleAssetDto> = response
+            .stale_assets
+            .into_iter()
+            .take(MAX_NET_WORTH_ITEMS)
+            .map(|s| StaleAssetDto {
+                asset_id: s.asset_id,
+                name: s.name,
+                valuation_date: s.valuation_date.format("%Y-%m-%d").to_string(),
+                days_stale: s.days_stale,
+            })
+            .collect();
+
+        let returned_count = assets.len() + liabilities.len();
+        let truncated = original_count > returned_count;
+
+        Ok(GetNetWorthOutput {
+            date: response.date.format("%Y-%m-%d").to_string(),
+            currency: response.currency,
+            net_worth: response.net_worth.to_f64().unwrap_or(0.0),
+            total_assets: response.assets.total.to_f64().unwrap_or(0.0),
+            total_liabilities: response.liabilities.total.to_f64().unwrap_or(0.0),
+            assets,
+            liabilities,
+            oldest_valuation_date: response
+                .oldest_valuation_date
+                .map(|d| d.format("%Y-%m-%d").to_string()),
+            stale_assets,
+            truncated: if truncated { Some(true) } else { None },
+            original_count: if truncated {
+                Some(original_count)
+            } else {
+                None
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test_env::{MockEnvironment, MockNetWorthService};
+    use rust_decimal::Decimal;
+    use wealthfolio_core::portfolio::net_worth::{
+        AssetsSection, LiabilitiesSection, NetWorthResponse,
+    };
+
+    fn item(category: &str, name: &str, value: i64) -> BreakdownItem {
+        BreakdownItem {
+            category: category.to_string(),
+            name: name.to_string(),
+            value: Decimal::from(value),
+            asset_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_net_worth_tool() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 31).unwrap();
+        let mut response = NetWorthResponse::empty(date, "USD".to_string());
+        response.assets = AssetsSection {
+            total: Decimal::from(950_000),
+            breakdown: vec![
+                item("investments", "Investments", 450_000),
+                item("properties", "Flat", 500_000),
+            ],
+        };
+        response.liabilities = LiabilitiesSection {
+            total: Decimal::from(300_000),
+            breakdown: vec![item("liabilities", "Mortgage", 300_000)],
+        };
+        response.net_worth = Decimal::from(650_000);
+
+        let mut env = MockEnvironment::new();
+        env.net_worth_service = Arc::new(MockNetWorthService {
+            response: Some(response),
+        });
+        let tool = GetNetWorthTool::new(Arc::new(env));
+
+        let output = tool
+            .call(GetNetWorthArgs {
+                date: Some("2026-03-31".to_string()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(output.date, "2026-03-31");
+        assert_eq!(output.net_worth, 650_000.0);
+        assert_eq!(output.assets.len(), 2);
+        assert_eq!(output.liabilities[0].name, "Mortgage");
+        assert!(output.truncated.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_net_worth_rejects_invalid_date() {
+        let tool = GetNetWorthTool::new(Arc::new(MockEnvironment::new()));
+
+        let result = tool
+            .call(GetNetWorthArgs {
+                date: Some("31/03/2026".to_string()),
+            })
+            .await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,292 @@
+//! Quote history tool - fetch historical prices using QuoteService.
+
+use chrono::NaiveDate;
+use rig::{completion::ToolDefinition, tool::Tool};
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use super::constants::{DEFAULT_VALUATIONS_DAYS, MAX_QUOTE_POINTS};
+use crate::env::AiEnvironment;
+use crate::error::AiError;
+
+// ============================================================================
+// Tool Arguments and Output
+// ============================================================================
+
+/// Arguments for the get_quote_history tool.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetQuoteHistoryArgs {
+    /// Ticker symbol of a held asset (e.g., "AAPL") or an asset ID.
+    pub symbol: String,
+    /// Start date (YYYY-MM-DD format).
+    #[serde(default)]
+    pub start_date: Option<String>,
+    /// End date (YYYY-MM-DD format).
+    #[serde(default)]
+    pub end_date: Option<String>,
+}
+
+/// DTO for a single daily quote in tool output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotePointDto {
+    pub date: String,
+    pub close: f64,
+}
+
+/// Output envelope for quote history tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetQuoteHistoryOutput {
+    pub symbol: String,
+    pub asset_id: String,
+    pub currency: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub quotes: Vec<QuotePointDto>,
+    pub count: usize,
+    /// Close-to-close change over the whole range, as a percentage.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change_pct: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub low: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub high: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_count: Option<usize>,
+}
+
+// ============================================================================
+// Tool Implementation
+// ============================================================================
+
+/// Tool to get historical daily prices for an asset.
+pub struct GetQuoteHistoryTool<E: AiEnvironment> {
+    env: Arc<E>,
+    base_currency: String,
+}
+
+impl<E: AiEnvironment> GetQuoteHistoryTool<E> {
+    pub fn new(env: Arc<E>, base_currency: String) -> Self {
+        Self { env, base_currency }
+    }
+
+    /// Quotes are stored by asset ID; map a held ticker to its asset ID and
+    /// otherwise treat the input as an asset ID.
+    async fn resolve_asset_id(&self, symbol: &str) -> String {
+        let holdings = self
+            .env
+            .holdings_service()
+            .get_holdings("TOTAL", &self.base_currency)
+            .await
+            .unwrap_or_default();
+
+        holdings
+            .iter()
+            .filter_map(|h| h.instrument.as_ref())
+            .find(|i| i.id == symbol || i.symbol.eq_ignore_ascii_case(symbol))
+            .map(|i| i.id.clone())
+            .unwrap_or_else(|| symbol.to_string())
+    }
+}
+
+impl<E: AiEnvironment> Clone for GetQuoteHistoryTool<E> {
+    fn clone(&self) -> Self {
+        Self {
+            env: self.env.clone(),
+            base_currency: self.base_currency.clone(),
+        }
+    }
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, AiError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        AiError::ToolExecutionFailed(format!("Invalid date '{}', expected YYYY-MM-DD", value))
+    })
+}
+
+impl<E: AiEnvironment + 'static> Tool for GetQuoteHistoryTool<E> {
+    const NAME: &'static str = "get_quote_history";
+
+    type Error = AiError;
+    type Args = GetQuoteHistoryArgs;
+    type Output = GetQuoteHistoryOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Get historical daily closing prices for an asset from the app's stored quotes. Accepts the ticker symbol of a held asset or an asset ID. Returns price points plus the range's change, low and high. Useful for the price on a purchase date or how a holding moved over a period.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "symbol": {
+                        "type": "string",
+                        "description": "Ticker symbol of a held asset (e.g. 'AAPL') or an asset ID"
+                    },
+                    "startDate": {
+                        "type": "string",
+                        "description": "Start date in YYYY-MM-DD format. Defaults to 365 days before the end date."
+                    },
+                    "endDate": {
+                        "type": "string",
+                        "description": "End date in YYYY-MM-DD format. Defaults to today."
+                    }
+                },
+                "required": ["symbol"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let symbol = args.symbol.trim().to_string();
+        let end_date = match args.end_date.as_deref() {
+            Some(s) => parse_date(s)?,
+            None => chrono::Utc::now().date_naive(),
+        };
+        let start_date = match args.start_date.as_deref() {
+            Some(s) => parse_date(s)?,
+            None => end_date - chrono::Duration::days(DEFAULT_VALUATIONS_DAYS),
+        };
+
+        let asset_id = self.resolve_asset_id(&symbol).await;
+
+        let mut quotes = self
+            .env
+            .quote_service()
+            .get_quotes_in_range(&HashSet::from([asset_id.clone()]), start_date, end_date)
+            .map_err(|e| AiError::ToolExecutionFailed(e.to_string()))?;
+        quotes.sort_by_key(|q| q.timestamp);
+
+        let currency = quotes
+            .last()
+            .map(|q| q.currency.clone())
+            .unwrap_or_default();
+
+        let points: Vec<QuotePointDto> = quotes
+            .iter()
+            .map(|q| QuotePointDto {
+                date: q.timestamp.format("%Y-%m-%d").to_string(),
+                close: q.close.to_f64().unwrap_or(0.0),
+            })
+            .collect();
+
+        // Summary over the full range, before truncation
+        let change_pct = match (points.first(), points.last()) {
+            (Some(first), Some(last)) if first.close != 0.0 => {
+                Some((last.close - first.close) / first.close * 100.0)
+            }
+            _ => None,
+        };
+        let low = points.iter().map(|p| p.close).reduce(f64::min);
+        let high = points.iter().map(|p| p.close).reduce(f64::max);
+
+        let original_count = points.len();
+
+        // Keep the most recent points
+        let points: Vec<QuotePointDto> = points
+            .into_iter()
+            .skip(original_count.saturating_sub(MAX_QUOTE_POINTS))
+            .collect();
+
+        let returned_count = points.len();
+        let truncated = original_count > returned_count;
+
+        Ok(GetQuoteHistoryOutput {
+            symbol,
+            asset_id,
+            currency,
+            start_date: start_date.format("%Y-%m-%d").to_string(),
+            end_date: end_date.format("%Y-%m-%d").to_string(),
+            quotes: points,
+            count: returned_count,
+            change_pct,
+            low,
+            high,
+            truncated: if truncated { Some(true) } else { None },
+            original_count: if truncated {
+                Some(original_count)
+            } else {
+                None
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test_env::{MockEnvironment, MockQuoteService};
+    use chrono::{Duration, TimeZone, Utc};
+    use rust_decimal::Decimal;
+    use wealthfolio_core::quotes::{DataSource, Quote};
+
+    fn quote(asset_id: &str, day: NaiveDate, close: i64) -> Quote {
+        let close = Decimal::from(close);
+        let timestamp = Utc.from_utc_datetime(&day.and_hms_opt(16, 0, 0).unwrap());
+        Quote {
+            id: format!("{}_{}", asset_id, day),
+            asset_id: asset_id.to_string(),
+            timestamp,
+            open: close,
+            high: close,
+            low: close,
+            close,
+            adjclose: close,
+            volume: Decimal::ZERO,
+            currency: "USD".to_string(),
+            data_source: DataSource::Manual,
+            created_at: timestamp,
+            notes: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_quote_history_tool() {
+        let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let quotes = (0..500)
+            .map(|i| quote("SEC:AAPL:XNAS", start + Duration::days(i), 100 + i))
+            .collect();
+        let mut env = MockEnvironment::new();
+        env.quote_service = Arc::new(MockQuoteService {
+            quotes,
+            ..Default::default()
+        });
+        let tool = GetQuoteHistoryTool::new(Arc::new(env), "USD".to_string());
+
+        let output = tool
+            .call(GetQuoteHistoryArgs {
+                symbol: "SEC:AAPL:XNAS".to_string(),
+                start_date: Some("2025-01-01".to_string()),
+                end_date: Some("2026-12-31".to_string()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(output.count, MAX_QUOTE_POINTS);
+        assert_eq!(output.original_count, Some(500));
+        assert_eq!(output.currency, "USD");
+        assert_eq!(output.low, Some(100.0));
+        assert_eq!(output.high, Some(599.0));
+        // Truncation keeps the latest points
+        assert_eq!(output.quotes.last().unwrap().close, 599.0);
+    }
+
+    #[tokio::test]
+    async fn test_get_quote_history_unknown_symbol() {
+        let tool = GetQuoteHistoryTool::new(Arc::new(MockEnvironment::new()), "USD".to_string());
+
+        let output = tool
+            .call(GetQuoteHistoryArgs {
+                symbol: "ZZZZ".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(output.count, 0);
+        assert!(output.change_pct.is_none());
+    }
+}
@@ -6,7 +6,7 @@
 use log::debug;
 use rig::{completion::ToolDefinition, tool::Tool};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::sync::Arc;
 
 use crate::env::AiEnvironment;
@@ -43,6 +43,12 @@ pub struct ActivityDraftRow {
     pub errors: Vec<String>,
     pub resolved_asset: Option<ResolvedAsset>,
     pub available_subtypes: Vec<SubtypeOption>,
+    /// Non-blocking review notes (e.g. low extraction confidence).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Per-field confidence (0.0-1.0) for drafts extracted from documents.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<BTreeMap<String, f64>>,
 }
 
 /// Output envelope for record_activities.
@@ -214,6 +220,8 @@ impl<E: AiEnvironment + 'static> Tool for RecordActivitiesTool<E> {
                         errors: row_errors,
                         resolved_asset: output.resolved_asset,
                         available_subtypes: output.available_subtypes,
+                        warnings: Vec::new(),
+                        confidence: None,
                     });
                 }
                 Err(e) => {
@@ -250,6 +258,8 @@ impl<E: AiEnvironment + 'static> Tool for RecordActivitiesTool<E> {
                         errors: vec![e.to_string()],
                         resolved_asset: None,
                         available_subtypes: Vec::new(),
+                        warnings: Vec::new(),
+                        confidence: None,
                     });
                 }
             }
@@ -502,6 +512,7 @@ mod tests {
                 existing_asset_id: Some("SEC:AAPL:XNAS".to_string()),
                 ..SymbolSearchResult::default()
             }]),
+            ..Default::default()
         });
         let tool = RecordActivitiesTool::new(Arc::new(env));
 
@@ -0,0 +1,555 @@
+//! What-if scenario tool - simulate hypothetical trades using ScenarioService.
+
+use rig::{completion::ToolDefinition, tool::Tool};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use wealthfolio_core::portfolio::allocation::{PortfolioAllocations, TaxonomyAllocation};
+use wealthfolio_core::portfolio::scenario::{
+    ConcentrationMetrics, ScenarioActivity, ScenarioRequest,
+};
+
+use super::constants::MAX_SCENARIO_ACTIVITIES;
+use crate::env::AiEnvironment;
+use crate::error::AiError;
+
+// ============================================================================
+// Tool Arguments and Output
+// ============================================================================
+
+/// Arguments for the simulate_scenario tool.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateScenarioArgs {
+    /// Account ID, or "TOTAL" for all accounts.
+    #[serde(default = "default_account_id")]
+    pub account_id: String,
+    /// Hypothetical activities, applied in order.
+    pub activities: Vec<ScenarioActivityArgs>,
+}
+
+fn default_account_id() -> String {
+    "TOTAL".to_string()
+}
+
+/// One hypothetical activity in the tool arguments.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioActivityArgs {
+    /// BUY, SELL, DEPOSIT, WITHDRAWAL, DIVIDEND, INTEREST, FEE or TAX.
+    pub activity_type: String,
+    /// Ticker of a held asset or an asset ID.
+    #[serde(default)]
+    pub symbol: Option<String>,
+    #[serde(default)]
+    pub quantity: Option<f64>,
+    #[serde(default)]
+    pub unit_price: Option<f64>,
+    #[serde(default)]
+    pub amount: Option<f64>,
+    #[serde(default)]
+    pub fee: Option<f64>,
+    #[serde(default)]
+    pub currency: Option<String>,
+}
+
+/// DTO for one category weight before and after the scenario.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryChangeDto {
+    pub category_id: String,
+    pub category_name: String,
+    pub before_pct: f64,
+    pub after_pct: f64,
+    pub change_pct: f64,
+}
+
+/// DTO for one taxonomy's allocation shift.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxonomyChangeDto {
+    pub taxonomy_id: String,
+    pub taxonomy_name: String,
+    pub categories: Vec<CategoryChangeDto>,
+}
+
+/// DTO for one cash balance before and after the scenario.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CashChangeDto {
+    pub currency: String,
+    pub amount_before: f64,
+    pub amount_after: f64,
+}
+
+/// DTO for concentration metrics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcentrationDto {
+    pub position_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub largest_position: Option<String>,
+    pub largest_position_pct: f64,
+    pub top5_pct: f64,
+    pub hhi: f64,
+    pub effective_positions: f64,
+}
+
+/// DTO for an estimated realized gain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RealizedGainDto {
+    pub symbol: String,
+    pub quantity: f64,
+    pub proceeds: f64,
+    pub cost_basis: f64,
+    pub gain: f64,
+    pub currency: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gain_base: Option<f64>,
+}
+
+/// Output envelope for simulate_scenario tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateScenarioOutput {
+    pub account_id: String,
+    pub currency: String,
+    pub total_value_before: f64,
+    pub total_value_after: f64,
+    pub allocation: Vec<TaxonomyChangeDto>,
+    pub cash: Vec<CashChangeDto>,
+    pub concentration_before: ConcentrationDto,
+    pub concentration_after: ConcentrationDto,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub realized_gains: Vec<RealizedGainDto>,
+    pub total_realized_gain: f64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+// ============================================================================
+// Tool Implementation
+// ============================================================================
+
+/// Tool to simulate hypothetical activities without recording them.
+pub struct SimulateScenarioTool<E: AiEnvironment> {
+    env: Arc<E>,
+    base_currency: String,
+}
+
+impl<E: AiEnvironment> SimulateScenarioTool<E> {
+    pub fn new(env: Arc<E>, base_currency: String) -> Self {
+        Self { env, base_currency }
+    }
+
+    /// Maps held tickers to asset IDs; anything else is passed through as an asset ID.
+    async fn resolve_asset_ids(&self, account_id: &str, symbols: &[&str]) -> Vec<String> {
+        let holdings = self
+            .env
+            .holdings_service()
+            .get_holdings(account_id, &self.base_currency)
+            .await
+            .unwrap_or_default();
+
+        symbols
+            .iter()
+            .map(|symbol| {
+                holdings
+                    .iter()
+                    .filter_map(|h| h.instrument.as_ref())
+                    .find(|i| i.id == *symbol || i.symbol.eq_ignore_ascii_case(symbol))
+                    .map(|i| i.id.clone())
+                    .unwrap_or_else(|| symbol.to_string())
+            })
+            .collect()
+    }
+}
+
+impl<E: AiEnvironment> Clone for SimulateScenarioTool<E> {
+    fn clone(&self) -> Self {
+        Self {
+            env: self.env.clone(),
+            base_currency: self.base_currency.clone(),
+        }
+    }
+}
+
+fn to_decimal(value: Option<f64>) -> Option<Decimal> {
+    value.and_then(|v| Decimal::try_from(v).ok())
+}
+
+fn to_f64(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or(0.0)
+}
+
+fn taxonomies(allocations: &PortfolioAllocations) -> Vec<&TaxonomyAllocation> {
+    let mut list = vec![
+        &allocations.asset_classes,
+        &allocations.sectors,
+        &allocations.regions,
+        &allocations.risk_category,
+        &allocations.security_types,
+    ];
+    list.extend(allocations.custom_groups.iter());
+    list
+}
+
+/// Pairs top-level categories of each taxonomy across both sides, largest shift first.
+fn allocation_changes(
+    before: &PortfolioAllocations,
+    after: &PortfolioAllocations,
+) -> Vec<TaxonomyChangeDto> {
+    let after_taxonomies = taxonomies(after);
+    let mut changes = Vec::new();
+
+    for taxonomy in taxonomies(before) {
+        let after_taxonomy = after_taxonomies
+            .iter()
+            .find(|t| t.taxonomy_id == taxonomy.taxonomy_id);
+        let after_categories = after_taxonomy
+            .map(|t| t.categories.as_slice())
+            .unwrap_or(&[]);
+
+        let mut categories: Vec<CategoryChangeDto> = taxonomy
+            .categories
+            .iter()
+            .chain(after_categories)
+            .map(|c| c.category_id.as_str())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .filter_map(|category_id| {
+                let old = taxonomy
+                    .categories
+                    .iter()
+                    .find(|c| c.category_id == category_id);
+                let new = after_categories
+                    .iter()
+                    .find(|c| c.category_id == category_id);
+                let before_pct = old.map(|c| c.percentage).unwrap_or_default();
+                let after_pct = new.map(|c| c.percentage).unwrap_or_default();
+                Some(CategoryChangeDto {
+                    category_id: category_id.to_string(),
+                    category_name: new.or(old)?.category_name.clone(),
+                    before_pct: to_f64(before_pct),
+                    after_pct: to_f64(after_pct),
+                    change_pct: to_f64(after_pct - before_pct),
+                })
+            })
+            .collect();
+        if categories.is_empty() {
+            continue;
+        }
+        categories.sort_by(|a, b| {
+            b.change_pct
+                .abs()
+                .partial_cmp(&a.change_pct.abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        changes.push(TaxonomyChangeDto {
+            taxonomy_id: taxonomy.taxonomy_id.clone(),
+            taxonomy_name: taxonomy.taxonomy_name.clone(),
+            categories,
+        });
+    }
+
+    changes
+}
+
+fn to_concentration_dto(metrics: ConcentrationMetrics) -> ConcentrationDto {
+    ConcentrationDto {
+        position_count: metrics.position_count,
+        largest_position: metrics.largest_position,
+        largest_position_pct: to_f64(metrics.largest_position_pct),
+        top5_pct: to_f64(metrics.top5_pct),
+        hhi: to_f64(metrics.hhi),
+        effective_positions: to_f64(metrics.effective_positions),
+    }
+}
+
+impl<E: AiEnvironment + 'static> Tool for SimulateScenarioTool<E> {
+    const NAME: &'static str = "simulate_scenario";
+
+    type Error = AiError;
+    type Args = SimulateScenarioArgs;
+    type Output = SimulateScenarioOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Simulate hypothetical trades or cash movements on the current holdings without recording anything. Returns allocation by asset class, sector, region, risk and instrument type before and after, cash per currency, the estimated realized gain of any sells (FIFO against current lots) and concentration metrics. Use for what-if questions such as 'what if I sell half of X and buy Y'. Get quantities from get_holdings first when the user gives fractions or amounts.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "accountId": {
+                        "type": "string",
+                        "description": "Account ID to simulate, or 'TOTAL' for the whole portfolio. Defaults to 'TOTAL'."
+                    },
+                    "activities": {
+                        "type": "array",
+                        "description": "Hypothetical activities, applied in order",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "activityType": {
+                                    "type": "string",
+                                    "enum": ["BUY", "SELL", "DEPOSIT", "WITHDRAWAL", "DIVIDEND", "INTEREST", "FEE", "TAX"]
+                                },
+                                "symbol": {
+                                    "type": "string",
+                                    "description": "Ticker of a held asset or an asset ID. Required for BUY, SELL and DIVIDEND."
+                                },
+                                "quantity": {
+                                    "type": "number",
+                                    "description": "Number of units for BUY/SELL"
+                                },
+                                "unitPrice": {
+                                    "type": "number",
+                                    "description": "Price per unit. Defaults to the latest quote."
+                                },
+                                "amount": {
+                                    "type": "number",
+                                    "description": "Cash amount for non-trade activities"
+                                },
+                                "fee": {
+                                    "type": "number"
+                                },
+                                "currency": {
+                                    "type": "string",
+                                    "description": "Defaults to the asset's currency, then the account currency"
+                                }
+                            },
+                            "required": ["activityType"]
+                        }
+                    }
+                },
+                "required": ["activities"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        if args.activities.is_empty() {
+            return Err(AiError::ToolExecutionFailed(
+                "Provide at least one activity to simulate".to_string(),
+            ));
+        }
+        if args.activities.len() > MAX_SCENARIO_ACTIVITIES {
+            return Err(AiError::ToolExecutionFailed(format!(
+                "A scenario can have at most {} activities",
+                MAX_SCENARIO_ACTIVITIES
+            )));
+        }
+
+        let symbols: Vec<&str> = args
+            .activities
+            .iter()
+            .map(|a| a.symbol.as_deref().map(str::trim).unwrap_or_default())
+            .collect();
+        let asset_ids = self.resolve_asset_ids(&args.account_id, &symbols).await;
+
+        let activities = args
+            .activities
+            .into_iter()
+            .zip(asset_ids)
+            .map(|(activity, asset_id)| ScenarioActivity {
+                activity_type: activity.activity_type,
+                asset_id: Some(asset_id).filter(|id| !id.is_empty()),
+                quantity: to_decimal(activity.quantity),
+                unit_price: to_decimal(activity.unit_price),
+                amount: to_decimal(activity.amount),
+                fee: to_decimal(activity.fee),
+                currency: activity.currency,
+            })
+            .collect();
+
+        let result = self
+            .env
+            .scenario_service()
+            .simulate(
+                ScenarioRequest {
+                    account_id: Some(args.account_id),
+                    activities,
+                },
+                &self.base_currency,
+            )
+            .await
+            .map_err(|e| AiError::ToolExecutionFailed(e.to_string()))?;
+
+        let mut cash: Vec<CashChangeDto> = result
+            .before
+            .cash_balances
+            .keys()
+            .chain(result.after.cash_balances.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|currency| CashChangeDto {
+                currency: currency.clone(),
+                amount_before: to_f64(
+                    result
+                        .before
+                        .cash_balances
+                        .get(currency)
+                        .copied()
+                        .unwrap_or_default(),
+                ),
+                amount_after: to_f64(
+                    result
+                        .after
+                        .cash_balances
+                        .get(currency)
+                        .copied()
+                        .unwrap_or_default(),
+                ),
+            })
+            .collect();
+        cash.retain(|c| c.amount_before != 0.0 || c.amount_after != 0.0);
+
+        Ok(SimulateScenarioOutput {
+            allocation: allocation_changes(&result.before.allocations, &result.after.allocations),
+            cash,
+            total_value_before: to_f64(result.before.total_value),
+            total_value_after: to_f64(result.after.total_value),
+            concentration_before: to_concentration_dto(result.before.concentration),
+            concentration_after: to_concentration_dto(result.after.concentration),
+            realized_gains: result
+                .realized_gains
+                .into_iter()
+                .map(|g| RealizedGainDto {
+                    symbol: g.symbol.unwrap_or(g.asset_id),
+                    quantity: to_f64(g.quantity),
+                    proceeds: to_f64(g.proceeds),
+                    cost_basis: to_f64(g.cost_basis),
+                    gain: to_f64(g.gain),
+                    currency: g.currency,
+                    gain_base: g.gain_base.map(to_f64),
+                })
+                .collect(),
+            total_realized_gain: to_f64(result.total_realized_gain),
+            warnings: result.warnings,
+            account_id: result.account_id,
+            currency: result.base_currency,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test_env::{MockEnvironment, MockScenarioService};
+    use std::collections::HashMap;
+    use wealthfolio_core::portfolio::allocation::CategoryAllocation;
+    use wealthfolio_core::portfolio::scenario::{
+        RealizedGainEstimate, ScenarioResult, ScenarioState,
+    };
+
+    fn state(equity_pct: Decimal, cash: Decimal, largest: &str) -> ScenarioState {
+        let mut allocations = PortfolioAllocations::default();
+        allocations.asset_classes.categories = vec![
+            CategoryAllocation {
+                category_id: "EQUITY".to_string(),
+                category_name: "Equity".to_string(),
+                color: "#000000".to_string(),
+                value: equity_pct * Decimal::from(100),
+                percentage: equity_pct,
+                children: Vec::new(),
+            },
+            CategoryAllocation {
+                category_id: "CASH".to_string(),
+                category_name: "Cash".to_string(),
+                color: "#000000".to_string(),
+                value: (Decimal::from(100) - equity_pct) * Decimal::from(100),
+                percentage: Decimal::from(100) - equity_pct,
+                children: Vec::new(),
+            },
+        ];
+        ScenarioState {
+            total_value: Decimal::from(10000),
+            cash_balances: HashMap::from([("GBP".to_string(), cash)]),
+            cash_value: cash,
+            allocations,
+            concentration: ConcentrationMetrics {
+                position_count: 2,
+                largest_position: Some(largest.to_string()),
+                largest_position_pct: Decimal::from(60),
+                top5_pct: Decimal::from(100),
+                hhi: Decimal::from(5200),
+                effective_positions: Decimal::new(192, 2),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_simulate_scenario_tool() {
+        let mut env = MockEnvironment::new();
+        env.scenario_service = Arc::new(MockScenarioService {
+            result: Some(ScenarioResult {
+                account_id: "TOTAL".to_string(),
+                base_currency: "USD".to_string(),
+                before: state(Decimal::from(90), Decimal::from(1000), "HSBA"),
+                after: state(Decimal::from(70), Decimal::from(3000), "VWRA"),
+                realized_gains: vec![RealizedGainEstimate {
+                    asset_id: "HSBA.L".to_string(),
+                    symbol: Some("HSBA".to_string()),
+                    quantity: Decimal::from(100),
+                    proceeds: Decimal::from(2000),
+                    cost_basis: Decimal::from(1500),
+                    gain: Decimal::from(500),
+                    currency: "GBP".to_string(),
+                    gain_base: Some(Decimal::from(630)),
+                }],
+                total_realized_gain: Decimal::from(630),
+                warnings: Vec::new(),
+            }),
+        });
+        let tool = SimulateScenarioTool::new(Arc::new(env), "USD".to_string());
+
+        let output = tool
+            .call(SimulateScenarioArgs {
+                account_id: "TOTAL".to_string(),
+                activities: vec![ScenarioActivityArgs {
+                    activity_type: "SELL".to_string(),
+                    symbol: Some("HSBA".to_string()),
+                    quantity: Some(100.0),
+                    ..Default::default()
+                }],
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(output.allocation.len(), 1);
+        let classes = &output.allocation[0];
+        assert_eq!(classes.taxonomy_id, "asset_classes");
+        let equity = classes
+            .categories
+            .iter()
+            .find(|c| c.category_id == "EQUITY")
+            .unwrap();
+        assert_eq!(equity.before_pct, 90.0);
+        assert_eq!(equity.after_pct, 70.0);
+        assert_eq!(equity.change_pct, -20.0);
+        assert_eq!(output.cash[0].amount_after, 3000.0);
+        assert_eq!(
+            output.concentration_after.largest_position.as_deref(),
+            Some("VWRA")
+        );
+        assert_eq!(output.realized_gains[0].symbol, "HSBA");
+        assert_eq!(output.total_realized_gain, 630.0);
+    }
+
+    #[tokio::test]
+    async fn test_simulate_scenario_requires_activities() {
+        let tool = SimulateScenarioTool::new(Arc::new(MockEnvironment::new()), "USD".to_string());
+
+        let result = tool
+            .call(SimulateScenarioArgs {
+                account_id: "TOTAL".to_string(),
+                activities: Vec::new(),
+            })
+            .await;
+        assert!(matches!(result, Err(AiError::ToolExecutionFailed(_))));
+    }
+}
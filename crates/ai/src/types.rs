@@ -34,6 +34,12 @@ pub const DEFAULT_TOOLS_ALLOWLIST: &[&str] = &[
     "get_income",
     "get_asset_allocation",
     "get_goals",
+    "get_net_worth",
+    "get_alternative_assets",
+    "get_health_issues",
+    "get_exchange_rate",
+    "get_quote_history",
+    "simulate_scenario",
     "record_activity",
     "record_activities",
     "import_csv",
@@ -172,6 +178,9 @@ pub struct ChatThread {
     /// Captures model, prompt template, and tool allowlist at creation.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub config: Option<ChatThreadConfig>,
+    /// Rolling summary of older turns that no longer fit the model's context.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<ThreadSummary>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -186,6 +195,7 @@ impl ChatThread {
             is_pinned: false,
             tags: Vec::new(),
             config: None,
+            summary: None,
             created_at: now,
             updated_at: now,
         }
@@ -200,6 +210,7 @@ impl ChatThread {
             is_pinned: false,
             tags: Vec::new(),
             config: Some(config),
+            summary: None,
             created_at: now,
             updated_at: now,
         }
@@ -220,6 +231,7 @@ impl ChatThread {
             is_pinned,
             tags: Vec::new(),
             config,
+            summary: None,
             created_at,
             updated_at,
         }
@@ -245,6 +257,17 @@ impl Default for ChatThread {
     }
 }
 
+/// Summary of the older part of a thread, replacing those turns in the prompt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadSummary {
+    /// Summary text.
+    pub content: String,
+    /// ID of the newest message folded into the summary.
+    pub through_message_id: String,
+    pub updated_at: DateTime<Utc>,
+}
+
 /// Message role.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -289,6 +312,9 @@ pub struct ChatMessage {
     pub role: ChatMessageRole,
     pub content: ChatMessageContent,
     pub created_at: DateTime<Utc>,
+    /// Token usage reported by the provider (assistant messages only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<MessageUsage>,
 }
 
 impl ChatMessage {
@@ -300,6 +326,7 @@ impl ChatMessage {
             role: ChatMessageRole::User,
             content: ChatMessageContent::text(text),
             created_at: Utc::now(),
+            usage: None,
         }
     }
 
@@ -311,6 +338,7 @@ impl ChatMessage {
             role: ChatMessageRole::Assistant,
             content: ChatMessageContent::new(vec![]),
             created_at: Utc::now(),
+            usage: None,
         }
     }
 
@@ -322,6 +350,7 @@ impl ChatMessage {
             role: ChatMessageRole::Assistant,
             content: ChatMessageContent::new(vec![]),
             created_at: Utc::now(),
+            usage: None,
         }
     }
 
@@ -496,6 +525,14 @@ pub trait ChatRepositoryTrait: Send + Sync {
     async fn add_tag(&self, thread_id: &str, tag: &str) -> ChatRepositoryResult<()>;
     async fn remove_tag(&self, thread_id: &str, tag: &str) -> ChatRepositoryResult<()>;
     fn get_tags(&self, thread_id: &str) -> ChatRepositoryResult<Vec<String>>;
+
+    // Usage operations
+    /// List messages with recorded usage created in `[from, to)`.
+    fn list_usage(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> ChatRepositoryResult<Vec<UsageRecord>>;
 }
 
 // ============================================================================
@@ -709,6 +746,15 @@ pub enum AiStreamEvent {
         run_id: String,
         title: String,
     },
+
+    /// Usage budget warning - month-to-date spend is near or over the limit.
+    #[serde(rename_all = "camelCase")]
+    UsageBudgetWarning {
+        thread_id: String,
+        run_id: String,
+        spent_usd: f64,
+        limit_usd: f64,
+    },
 }
 
 impl AiStreamEvent {
@@ -807,6 +853,21 @@ impl AiStreamEvent {
             title: title.to_string(),
         }
     }
+
+    /// Create a usage budget warning event.
+    pub fn usage_budget_warning(
+        thread_id: &str,
+        run_id: &str,
+        spent_usd: f64,
+        limit_usd: f64,
+    ) -> Self {
+        Self::UsageBudgetWarning {
+            thread_id: thread_id.to_string(),
+            run_id: run_id.to_string(),
+            spent_usd,
+            limit_usd,
+        }
+    }
 }
 
 /// Token usage statistics.
@@ -821,6 +882,41 @@ pub struct UsageStats {
     pub total_tokens: u32,
 }
 
+/// Token usage persisted on a message.
+///
+/// Assistant messages carry the usage of their reply. A user message carries
+/// the usage of the context summary written before answering it, if any.
+/// Provider and model are kept alongside the counts so cost can be estimated
+/// against the model that produced the text, not the thread's current one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageUsage {
+    pub provider_id: String,
+    pub model_id: String,
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+}
+
+impl MessageUsage {
+    /// Convert to the stats reported on the `Done` event.
+    pub fn to_stats(&self) -> UsageStats {
+        UsageStats {
+            prompt_tokens: self.prompt_tokens,
+            completion_tokens: self.completion_tokens,
+            total_tokens: self.prompt_tokens.saturating_add(self.completion_tokens),
+        }
+    }
+}
+
+/// A message with recorded usage, as returned by [`ChatRepositoryTrait::list_usage`].
+#[derive(Debug, Clone)]
+pub struct UsageRecord {
+    pub message_id: String,
+    pub thread_id: String,
+    pub created_at: DateTime<Utc>,
+    pub usage: MessageUsage,
+}
+
 // ============================================================================
 // Request Types
 // ============================================================================
@@ -1077,6 +1173,35 @@ mod tests {
         assert!(json.contains("My Portfolio Summary"));
     }
 
+    #[test]
+    fn test_usage_budget_warning_event_serialization() {
+        let event = AiStreamEvent::usage_budget_warning("thread-1", "run-1", 42.5, 50.0);
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("usageBudgetWarning"));
+        assert!(json.contains("\"spentUsd\":42.5"));
+        assert!(json.contains("\"limitUsd\":50.0"));
+    }
+
+    #[test]
+    fn test_message_usage_round_trips_and_is_optional() {
+        let mut message = ChatMessage::assistant("thread-1");
+        let json = serde_json::to_string(&message).unwrap();
+        assert!(!json.contains("usage"));
+
+        message.usage = Some(MessageUsage {
+            provider_id: "anthropic".to_string(),
+            model_id: "claude-haiku-4-5-20251001".to_string(),
+            prompt_tokens: 1200,
+            completion_tokens: 300,
+        });
+        let json = serde_json::to_string(&message).unwrap();
+        assert!(json.contains("\"promptTokens\":1200"));
+
+        let back: ChatMessage = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.usage, message.usage);
+        assert_eq!(back.usage.unwrap().to_stats().total_tokens, 1500);
+    }
+
     #[test]
     fn test_simple_chat_message_from_chat_message() {
         let chat_msg = ChatMessage::user("thread-1", "Hello world");
@@ -0,0 +1,667 @@
+//! AI usage accounting.
+//!
+//! Token counts reported by the provider are stored on the message they were
+//! spent for (see [`MessageUsage`]). This module aggregates them by day,
+//! provider, model and thread, estimates cost from the catalog list prices, and
+//! enforces the optional monthly budget before a message is sent.
+//!
+//! Days and months are UTC. Costs are estimates: providers bill on their own
+//! tokenizer and may apply caching discounts we don't see.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::env::AiEnvironment;
+use crate::error::AiError;
+use crate::provider_model::ModelPricing;
+use crate::providers::ProviderService;
+use crate::types::{MessageUsage, UsageRecord};
+
+/// Share of the monthly limit at which the budget starts warning.
+pub const BUDGET_WARNING_THRESHOLD_PCT: f64 = 80.0;
+
+// ============================================================================
+// Budget Types
+// ============================================================================
+
+/// What happens once the monthly budget is spent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BudgetAction {
+    /// Keep sending, but warn on every message.
+    Warn,
+    /// Refuse to send new messages until the next month.
+    Block,
+}
+
+/// Monthly spending limit across all providers (stored in AI provider settings).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageBudget {
+    pub monthly_limit_usd: f64,
+    pub action: BudgetAction,
+}
+
+/// How close month-to-date spend is to the limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BudgetLevel {
+    Ok,
+    Warning,
+    Exceeded,
+}
+
+/// Month-to-date spend against the configured budget.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetStatus {
+    pub monthly_limit_usd: f64,
+    pub action: BudgetAction,
+    pub month_to_date_usd: f64,
+    pub remaining_usd: f64,
+    pub level: BudgetLevel,
+}
+
+impl BudgetStatus {
+    fn new(budget: &UsageBudget, month_to_date_usd: f64) -> Self {
+        let limit = budget.monthly_limit_usd;
+        let level = if month_to_date_usd >= limit {
+            BudgetLevel::Exceeded
+        } else if month_to_date_usd >= limit * BUDGET_WARNING_THRESHOLD_PCT / 100.0 {
+            BudgetLevel::Warning
+        } else {
+            BudgetLevel::Ok
+        };
+
+        Self {
+            monthly_limit_usd: limit,
+            action: budget.action,
+            month_to_date_usd,
+            remaining_usd: (limit - month_to_date_usd).max(0.0),
+            level,
+        }
+    }
+
+    /// Whether new messages must be refused.
+    pub fn is_blocking(&self) -> bool {
+        self.level == BudgetLevel::Exceeded && self.action == BudgetAction::Block
+    }
+}
+
+// ============================================================================
+// Report Types
+// ============================================================================
+
+/// Aggregated token counts and estimated cost.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageTotals {
+    pub message_count: u32,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+    pub estimated_cost_usd: f64,
+    /// Messages from models without a known price, excluded from the estimate.
+    pub unpriced_message_count: u32,
+}
+
+impl UsageTotals {
+    fn add(&mut self, usage: &MessageUsage, cost_usd: Option<f64>) {
+        let prompt = u64::from(usage.prompt_tokens);
+        let completion = u64::from(usage.completion_tokens);
+
+        self.message_count += 1;
+        self.prompt_tokens += prompt;
+        self.completion_tokens += completion;
+        self.total_tokens += prompt + completion;
+        match cost_usd {
+            Some(cost) => self.estimated_cost_usd += cost,
+            None => self.unpriced_message_count += 1,
+        }
+    }
+}
+
+/// Usage for one UTC day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyUsage {
+    pub date: NaiveDate,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+/// Usage for one provider.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderUsage {
+    pub provider_id: String,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+/// Usage for one provider/model pair.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelUsage {
+    pub provider_id: String,
+    pub model_id: String,
+    /// Price used for the estimate, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pricing: Option<ModelPricing>,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+/// Usage for one thread.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadUsage {
+    pub thread_id: String,
+    /// Thread title, or None if the thread has since been deleted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+/// Usage and estimated cost over an inclusive date range.
+///
+/// Days without usage are omitted from `by_day`. Provider, model and thread
+/// breakdowns are sorted by estimated cost, then tokens, descending.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub totals: UsageTotals,
+    pub by_day: Vec<DailyUsage>,
+    pub by_provider: Vec<ProviderUsage>,
+    pub by_model: Vec<ModelUsage>,
+    pub by_thread: Vec<ThreadUsage>,
+    /// Month-to-date budget status, if a budget is configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget: Option<BudgetStatus>,
+}
+
+// ============================================================================
+// Turn Usage
+// ============================================================================
+
+/// Tokens spent by model calls a tool makes during a chat turn (e.g.
+/// statement extraction). They run on the turn's model, so they are added to
+/// the usage recorded on the turn's assistant message.
+#[derive(Debug, Default)]
+pub struct TurnUsage {
+    prompt_tokens: AtomicU64,
+    completion_tokens: AtomicU64,
+}
+
+impl TurnUsage {
+    /// Add the tokens reported for one call.
+    pub fn add(&self, prompt_tokens: u64, completion_tokens: u64) {
+        self.prompt_tokens
+            .fetch_add(prompt_tokens, Ordering::Relaxed);
+        self.completion_tokens
+            .fetch_add(completion_tokens, Ordering::Relaxed);
+    }
+
+    /// Fold the tally into the usage reported for the turn's reply.
+    pub fn apply(
+        &self,
+        usage: Option<MessageUsage>,
+        provider_id: &str,
+        model_id: &str,
+    ) -> Option<MessageUsage> {
+        let prompt = self.prompt_tokens.load(Ordering::Relaxed);
+        let completion = self.completion_tokens.load(Ordering::Relaxed);
+        if prompt == 0 && completion == 0 {
+            return usage;
+        }
+
+        let mut usage = usage.unwrap_or_else(|| MessageUsage {
+            provider_id: provider_id.to_string(),
+            model_id: model_id.to_string(),
+            prompt_tokens: 0,
+            completion_tokens: 0,
+        });
+        usage.prompt_tokens = usage
+            .prompt_tokens
+            .saturating_add(u32::try_from(prompt).unwrap_or(u32::MAX));
+        usage.completion_tokens = usage
+            .completion_tokens
+            .saturating_add(u32::try_from(completion).unwrap_or(u32::MAX));
+        Some(usage)
+    }
+}
+
+// ============================================================================
+// Usage Service
+// ============================================================================
+
+/// Builds usage reports and enforces the monthly budget.
+pub struct UsageService<E: AiEnvironment> {
+    env: Arc<E>,
+}
+
+impl<E: AiEnvironment> UsageService<E> {
+    /// Create a new usage service.
+    pub fn new(env: Arc<E>) -> Self {
+        Self { env }
+    }
+
+    /// Usage report for `[from, to]` (inclusive, UTC days).
+    /// Defaults to the current month to date.
+    pub fn get_report(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<UsageReport, AiError> {
+        let today = Utc::now().date_naive();
+        let from = from.unwrap_or_else(|| month_start(today));
+        let to = to.unwrap_or(today);
+        if from > to {
+            return Err(AiError::InvalidInput(format!(
+                "Invalid usage range: {} is after {}",
+                from, to
+            )));
+        }
+
+        let repo = self.env.chat_repository();
+        let records = repo.list_usage(day_start(from), day_start(to + Duration::days(1)))?;
+
+        let provider_service = ProviderService::new(self.env.clone());
+        let mut report = build_report(from, to, &records, |provider_id, model_id| {
+            provider_service.get_model_pricing(provider_id, model_id)
+        });
+
+        for thread in &mut report.by_thread {
+            thread.title = repo
+                .get_thread(&thread.thread_id)
+                .ok()
+                .flatten()
+                .and_then(|t| t.title);
+        }
+        report.budget = self.budget_status()?;
+
+        Ok(report)
+    }
+
+    /// Month-to-date spend against the budget, or None when no budget is set.
+    pub fn budget_status(&self) -> Result<Option<BudgetStatus>, AiError> {
+        let provider_service = ProviderService::new(self.env.clone());
+        let Some(budget) = provider_service.get_usage_budget() else {
+            return Ok(None);
+        };
+
+        let now = Utc::now();
+        let records = self
+            .env
+            .chat_repository()
+            .list_usage(day_start(month_start(now.date_naive())), now)?;
+        let spent: f64 = records
+            .iter()
+            .filter_map(|r| {
+                provider_service
+                    .get_model_pricing(&r.usage.provider_id, &r.usage.model_id)
+                    .map(|p| {
+                        p.cost_usd(
+                            u64::from(r.usage.prompt_tokens),
+                            u64::from(r.usage.completion_tokens),
+                        )
+                    })
+            })
+            .sum();
+
+        Ok(Some(BudgetStatus::new(&budget, spent)))
+    }
+
+    /// Check the budget before sending a message.
+    ///
+    /// Returns `BudgetExceeded` when the budget blocks sending, the status when
+    /// the user should be warned, and None otherwise.
+    pub fn check_budget(&self) -> Result<Option<BudgetStatus>, AiError> {
+        let Some(status) = self.budget_status()? else {
+            return Ok(None);
+        };
+
+        if status.is_blocking() {
+            return Err(AiError::BudgetExceeded(format!(
+                "Monthly AI budget of ${:.2} reached (${:.2} spent this month). \
+                 Raise or remove the limit in AI provider settings to continue.",
+                status.monthly_limit_usd, status.month_to_date_usd
+            )));
+        }
+
+        Ok((status.level != BudgetLevel::Ok).then_some(status))
+    }
+}
+
+// ============================================================================
+// Aggregation
+// ============================================================================
+
+/// Aggregate usage records into a report (without budget status or thread titles).
+fn build_report<F>(
+    from: NaiveDate,
+    to: NaiveDate,
+    records: &[UsageRecord],
+    pricing: F,
+) -> UsageReport
+where
+    F: Fn(&str, &str) -> Option<ModelPricing>,
+{
+    let mut totals = UsageTotals::default();
+    let mut by_day: HashMap<NaiveDate, UsageTotals> = HashMap::new();
+    let mut by_provider: HashMap<String, UsageTotals> = HashMap::new();
+    let mut by_model: HashMap<(String, String), (Option<ModelPricing>, UsageTotals)> =
+        HashMap::new();
+    let mut by_thread: HashMap<String, UsageTotals> = HashMap::new();
+
+    for record in records {
+        let usage = &record.usage;
+        let model_key = (usage.provider_id.clone(), usage.model_id.clone());
+        let (price, model_totals) = by_model.entry(model_key).or_insert_with(|| {
+            (
+                pricing(&usage.provider_id, &usage.model_id),
+                UsageTotals::default(),
+            )
+        });
+        let cost = price.map(|p| {
+            p.cost_usd(
+                u64::from(usage.prompt_tokens),
+                u64::from(usage.completion_tokens),
+            )
+        });
+
+        model_totals.add(usage, cost);
+        totals.add(usage, cost);
+        by_day
+            .entry(record.created_at.date_naive())
+            .or_default()
+            .add(usage, cost);
+        by_provider
+            .entry(usage.provider_id.clone())
+            .or_default()
+            .add(usage, cost);
+        by_thread
+            .entry(record.thread_id.clone())
+            .or_default()
+            .add(usage, cost);
+    }
+
+    let mut by_day: Vec<DailyUsage> = by_day
+        .into_iter()
+        .map(|(date, totals)| DailyUsage { date, totals })
+        .collect();
+    by_day.sort_by_key(|d| d.date);
+
+    let mut by_provider: Vec<ProviderUsage> = by_provider
+        .into_iter()
+        .map(|(provider_id, totals)| ProviderUsage {
+            provider_id,
+            totals,
+        })
+        .collect();
+    by_provider.sort_by(|a, b| compare_totals(&a.totals, &b.totals));
+
+    let mut by_model: Vec<ModelUsage> = by_model
+        .into_iter()
+        .map(|((provider_id, model_id), (pricing, totals))| ModelUsage {
+            provider_id,
+            model_id,
+            pricing,
+            totals,
+        })
+        .collect();
+    by_model.sort_by(|a, b| compare_totals(&a.totals, &b.totals));
+
+    let mut by_thread: Vec<ThreadUsage> = by_thread
+        .into_iter()
+        .map(|(thread_id, totals)| ThreadUsage {
+            thread_id,
+            title: None,
+            totals,
+        })
+        .collect();
+    by_thread.sort_by(|a, b| compare_totals(&a.totals, &b.totals));
+
+    UsageReport {
+        from,
+        to,
+        totals,
+        by_day,
+        by_provider,
+        by_model,
+        by_thread,
+        budget: None,
+    }
+}
+
+/// Descending by estimated cost, then by total tokens.
+fn compare_totals(a: &UsageTotals, b: &UsageTotals) -> std::cmp::Ordering {
+    b.estimated_cost_usd
+        .total_cmp(&a.estimated_cost_usd)
+        .then(b.total_tokens.cmp(&a.total_tokens))
+}
+
+fn month_start(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+fn day_start(date: NaiveDate) -> DateTime<Utc> {
+    date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test_env::MockEnvironment;
+    use crate::provider_model::{AiProviderSettings, AI_PROVIDER_SETTINGS_KEY};
+    use crate::types::ChatMessage;
+
+    fn record(
+        thread_id: &str,
+        day: u32,
+        model_id: &str,
+        prompt: u32,
+        completion: u32,
+    ) -> UsageRecord {
+        UsageRecord {
+            message_id: uuid::Uuid::new_v4().to_string(),
+            thread_id: thread_id.to_string(),
+            created_at: NaiveDate::from_ymd_opt(2026, 3, day)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+                .and_utc(),
+            usage: MessageUsage {
+                provider_id: "anthropic".to_string(),
+                model_id: model_id.to_string(),
+                prompt_tokens: prompt,
+                completion_tokens: completion,
+            },
+        }
+    }
+
+    fn test_pricing(_provider_id: &str, model_id: &str) -> Option<ModelPricing> {
+        (model_id == "priced").then_some(ModelPricing {
+            input_per_million: 3.0,
+            output_per_million: 15.0,
+        })
+    }
+
+    async fn set_budget(env: &MockEnvironment, budget: UsageBudget) {
+        let settings = AiProviderSettings {
+            usage_budget: Some(budget),
+            ..Default::default()
+        };
+        env.settings_service()
+            .set_setting_value(
+                AI_PROVIDER_SETTINGS_KEY,
+                &serde_json::to_string(&settings).unwrap(),
+            )
+            .await
+            .unwrap();
+    }
+
+    async fn record_reply(env: &MockEnvironment, prompt: u32, completion: u32) {
+        let mut message = ChatMessage::assistant("thread-1");
+        message.usage = Some(MessageUsage {
+            provider_id: "anthropic".to_string(),
+            model_id: "claude-sonnet-4-5-20250929".to_string(),
+            prompt_tokens: prompt,
+            completion_tokens: completion,
+        });
+        env.chat_repository().create_message(message).await.unwrap();
+    }
+
+    #[test]
+    fn test_model_pricing_cost() {
+        let pricing = ModelPricing {
+            input_per_million: 3.0,
+            output_per_million: 15.0,
+        };
+        let cost = pricing.cost_usd(1_000_000, 100_000);
+        assert!((cost - 4.5).abs() < 1e-9);
+        assert_eq!(ModelPricing::FREE.cost_usd(5_000, 5_000), 0.0);
+    }
+
+    #[test]
+    fn test_turn_usage_folds_into_reply_usage() {
+        let turn = TurnUsage::default();
+        assert_eq!(turn.apply(None, "anthropic", "priced"), None);
+
+        turn.add(1_000, 200);
+        turn.add(500, 100);
+        let reply = MessageUsage {
+            provider_id: "anthropic".to_string(),
+            model_id: "priced".to_string(),
+            prompt_tokens: 2_000,
+            completion_tokens: 300,
+        };
+        let usage = turn.apply(Some(reply), "anthropic", "priced").unwrap();
+        assert_eq!(usage.prompt_tokens, 3_500);
+        assert_eq!(usage.completion_tokens, 600);
+
+        // A reply without reported usage still records the tool's calls
+        let usage = turn.apply(None, "anthropic", "priced").unwrap();
+        assert_eq!(usage.model_id, "priced");
+        assert_eq!(usage.prompt_tokens, 1_500);
+    }
+
+    #[test]
+    fn test_build_report_groups_by_day_model_and_thread() {
+        let from = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2026, 3, 31).unwrap();
+        let records = vec![
+            record("t1", 2, "priced", 1_000_000, 0),
+            record("t1", 2, "priced", 0, 100_000),
+            record("t2", 5, "unpriced", 500, 50),
+        ];
+
+        let report = build_report(from, to, &records, test_pricing);
+
+        assert_eq!(report.totals.message_count, 3);
+        assert_eq!(report.totals.total_tokens, 1_100_550);
+        assert!((report.totals.estimated_cost_usd - 4.5).abs() < 1e-9);
+        assert_eq!(report.totals.unpriced_message_count, 1);
+
+        assert_eq!(report.by_day.len(), 2);
+        assert_eq!(report.by_day[0].date.day(), 2);
+        assert_eq!(report.by_day[0].totals.message_count, 2);
+
+        assert_eq!(report.by_provider.len(), 1);
+        assert_eq!(report.by_model.len(), 2);
+        assert_eq!(report.by_model[0].model_id, "priced");
+        assert!(report.by_model[1].pricing.is_none());
+
+        assert_eq!(report.by_thread[0].thread_id, "t1");
+        assert_eq!(report.by_thread[1].totals.unpriced_message_count, 1);
+    }
+
+    #[test]
+    fn test_budget_levels() {
+        let budget = UsageBudget {
+            monthly_limit_usd: 10.0,
+            action: BudgetAction::Block,
+        };
+
+        assert_eq!(BudgetStatus::new(&budget, 2.0).level, BudgetLevel::Ok);
+        assert_eq!(BudgetStatus::new(&budget, 8.0).level, BudgetLevel::Warning);
+
+        let exceeded = BudgetStatus::new(&budget, 12.0);
+        assert_eq!(exceeded.level, BudgetLevel::Exceeded);
+        assert_eq!(exceeded.remaining_usd, 0.0);
+        assert!(exceeded.is_blocking());
+
+        let warn_only = UsageBudget {
+            action: BudgetAction::Warn,
+            ..budget
+        };
+        assert!(!BudgetStatus::new(&warn_only, 12.0).is_blocking());
+    }
+
+    #[tokio::test]
+    async fn test_check_budget_without_budget_is_none() {
+        let env = Arc::new(MockEnvironment::new());
+        record_reply(&env, 1_000_000, 1_000_000).await;
+
+        let service = UsageService::new(env);
+        assert!(service.check_budget().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_check_budget_blocks_once_spent() {
+        let env = Arc::new(MockEnvironment::new());
+        set_budget(
+            &env,
+            UsageBudget {
+                monthly_limit_usd: 5.0,
+                action: BudgetAction::Block,
+            },
+        )
+        .await;
+        let service = UsageService::new(env.clone());
+
+        // 1M prompt tokens on Sonnet = $3.00 (60%), under the warning line
+        record_reply(&env, 1_000_000, 0).await;
+        assert!(service.check_budget().unwrap().is_none());
+
+        // +$1.50 = $4.50 (90%) warns
+        record_reply(&env, 0, 100_000).await;
+        let status = service.check_budget().unwrap().expect("should warn");
+        assert_eq!(status.level, BudgetLevel::Warning);
+
+        // +$1.50 = $6.00 blocks
+        record_reply(&env, 0, 100_000).await;
+        let err = service.check_budget().unwrap_err();
+        assert_eq!(err.code(), "BUDGET_EXCEEDED");
+    }
+
+    #[tokio::test]
+    async fn test_get_report_defaults_to_current_month() {
+        let env = Arc::new(MockEnvironment::new());
+        record_reply(&env, 2_000, 500).await;
+
+        let report = UsageService::new(env).get_report(None, None).unwrap();
+
+        assert_eq!(report.from.day(), 1);
+        assert_eq!(report.to, Utc::now().date_naive());
+        assert_eq!(report.totals.message_count, 1);
+        assert_eq!(report.by_thread[0].thread_id, "thread-1");
+        assert!(report.budget.is_none());
+    }
+
+    #[test]
+    fn test_get_report_rejects_inverted_range() {
+        let env = Arc::new(MockEnvironment::new());
+        let from = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let to = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+
+        let result = UsageService::new(env).get_report(Some(from), Some(to));
+        assert!(matches!(result, Err(AiError::InvalidInput(_))));
+    }
+}
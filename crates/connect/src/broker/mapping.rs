@@ -11,10 +11,14 @@
 use rust_decimal::prelude::FromPrimitive;
 use rust_decimal::Decimal;
 
-use super::models::AccountUniversalActivity;
+use super::models::{AccountUniversalActivity, HoldingsBalance, HoldingsPosition};
+use super::BrokerSyncService;
 use wealthfolio_core::activities::{self, NewActivity, SymbolInput};
 use wealthfolio_core::assets::parse_symbol_with_exchange_suffix;
 use wealthfolio_core::fx::currency::{get_normalization_rule, normalize_amount, resolve_currency};
+use wealthfolio_core::portfolio::reconciliation::{
+    BrokerPosition, BrokerStatement, StatementSource,
+};
 
 /// Minimum confidence score to consider a mapping reliable
 const CONFIDENCE_THRESHOLD: f64 = 0.7;
@@ -249,6 +253,78 @@ pub fn is_broker_crypto(code: Option<&str>) -> bool {
     )
 }
 
+/// Converts broker holdings into a statement for reconciliation.
+///
+/// Symbols are normalized the same way as during holdings sync; option positions
+/// are not included. Positions without a symbol or with zero units are skipped.
+pub fn holdings_to_statement(
+    account_id: &str,
+    account_currency: &str,
+    as_of_date: chrono::NaiveDate,
+    balances: &[HoldingsBalance],
+    positions: &[HoldingsPosition],
+) -> BrokerStatement {
+    let mut statement = BrokerStatement {
+        account_id: account_id.to_string(),
+        as_of_date,
+        source: StatementSource::BrokerSync,
+        positions: Vec::new(),
+        cash_balances: std::collections::HashMap::new(),
+    };
+
+    for balance in balances {
+        if let (Some(currency), Some(cash)) = (
+            balance.currency.as_ref().and_then(|c| c.code.clone()),
+            balance.cash.and_then(Decimal::from_f64),
+        ) {
+            *statement
+                .cash_balances
+                .entry(currency)
+                .or_insert(Decimal::ZERO) += cash;
+        }
+    }
+
+    for pos in positions {
+        let symbol_info = pos.symbol.as_ref().and_then(|s| s.symbol.as_ref());
+        let symbol_type_code = symbol_info
+            .and_then(|s| s.symbol_type.as_ref())
+            .and_then(|t| t.code.clone());
+        let Some((symbol, _)) = BrokerSyncService::normalize_holdings_symbol(
+            symbol_info.and_then(|s| s.raw_symbol.as_deref()),
+            symbol_info.and_then(|s| s.symbol.as_deref()),
+            is_broker_crypto(symbol_type_code.as_deref()),
+        ) else {
+            continue;
+        };
+        let quantity = pos
+            .units
+            .and_then(Decimal::from_f64)
+            .unwrap_or(Decimal::ZERO);
+        if quantity.is_zero() {
+            continue;
+        }
+        let cost_basis = pos
+            .average_purchase_price
+            .and_then(Decimal::from_f64)
+            .map(|avg| avg * quantity);
+        let currency = pos
+            .currency
+            .as_ref()
+            .and_then(|c| c.code.clone())
+            .unwrap_or_else(|| account_currency.to_string());
+
+        statement.positions.push(BrokerPosition {
+            asset_id: None,
+            symbol,
+            quantity,
+            cost_basis,
+            currency,
+        });
+    }
+
+    statement
+}
+
 /// Maps a broker API activity into a `NewActivity` with unresolved `SymbolInput`.
 ///
 /// The returned `NewActivity` has `SymbolInput { symbol, exchange_mic, kind }` set
@@ -679,4 +755,54 @@ mod tests {
             Some("AAPL".to_string())
         );
     }
+
+    #[test]
+    fn test_holdings_to_statement() {
+        use crate::broker::models::{HoldingsCurrency, HoldingsInnerSymbol, HoldingsSymbol};
+
+        let usd = || {
+            Some(HoldingsCurrency {
+                code: Some("USD".to_string()),
+                ..Default::default()
+            })
+        };
+        let balances = vec![HoldingsBalance {
+            currency: usd(),
+            cash: Some(125.5),
+            ..Default::default()
+        }];
+        let positions = vec![
+            HoldingsPosition {
+                symbol: Some(HoldingsSymbol {
+                    symbol: Some(HoldingsInnerSymbol {
+                        symbol: Some("SHOP.TO".to_string()),
+                        raw_symbol: Some("SHOP".to_string()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                units: Some(4.0),
+                average_purchase_price: Some(50.0),
+                ..Default::default()
+            },
+            HoldingsPosition {
+                units: Some(3.0),
+                currency: usd(),
+                ..Default::default()
+            },
+        ];
+        let date = chrono::NaiveDate::from_ymd_opt(2025, 6, 30).unwrap();
+
+        let statement = holdings_to_statement("acct-1", "CAD", date, &balances, &positions);
+
+        assert_eq!(statement.source, StatementSource::BrokerSync);
+        assert_eq!(statement.positions.len(), 1);
+        assert_eq!(statement.positions[0].symbol, "SHOP");
+        assert_eq!(statement.positions[0].currency, "CAD");
+        assert_eq!(statement.positions[0].cost_basis, Decimal::from_f64(200.0));
+        assert_eq!(
+            statement.cash_balances.get("USD"),
+            Decimal::from_f64(125.5).as_ref()
+        );
+    }
 }
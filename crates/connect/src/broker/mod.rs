@@ -6,7 +6,7 @@ mod service;
 mod traits;
 
 pub use models::*;
-pub use orchestrator::{SyncConfig, SyncOrchestrator};
+pub use orchestrator::{ActivityWindow, SyncConfig, SyncOrchestrator};
 pub use progress::{NoOpProgressReporter, SyncProgressPayload, SyncProgressReporter, SyncStatus};
 pub use service::BrokerSyncService;
 pub use traits::*;
@@ -118,6 +118,11 @@ pub struct BrokerAccount {
     /// Whether this account is shared with the household
     #[serde(default)]
     pub shared_with_household: bool,
+
+    /// Provider that sourced the account (e.g., "SIMPLEFIN" for local connectors).
+    /// Connect accounts omit it and default to "SNAPTRADE".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
 }
 
 fn default_sync_enabled() -> bool {
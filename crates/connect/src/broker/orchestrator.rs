@@ -6,6 +6,7 @@
 use std::collections::HashSet;
 use std::sync::Arc;
 
+use chrono::{Days, NaiveDate};
 use log::{debug, error, info, warn};
 
 use super::models::{
@@ -13,8 +14,8 @@ use super::models::{
 };
 use super::progress::{SyncProgressPayload, SyncProgressReporter, SyncStatus};
 use super::traits::{BrokerApiClient, BrokerSyncServiceTrait};
-use crate::broker_ingest::{ImportRunMode, ImportRunStatus, ImportRunSummary};
-use wealthfolio_core::accounts::TrackingMode;
+use crate::broker_ingest::{ImportRunMode, ImportRunStatus, ImportRunSummary, ReviewMode};
+use wealthfolio_core::accounts::{Account, TrackingMode};
 
 /// Configuration for sync operations.
 #[derive(Debug, Clone)]
@@ -23,6 +24,20 @@ pub struct SyncConfig {
     pub page_limit: i64,
     /// Maximum number of pages to fetch per account (safety limit).
     pub max_pages: usize,
+    /// Fetch broker holdings after syncing TRANSACTIONS mode accounts and record
+    /// them for comparison with transaction-derived holdings.
+    pub detect_holdings_drift: bool,
+    /// Limit the first sync of an account to this many days of history
+    /// (recorded as a BACKFILL run). `None` fetches everything the broker has.
+    pub max_history_days: Option<u32>,
+    /// Re-fetch activities from this date regardless of sync state (recorded as
+    /// a REPAIR run). Activities are upserted by broker ID, so replays are idempotent.
+    pub repair_from: Option<NaiveDate>,
+    /// Restrict account data sync to these local account IDs.
+    pub account_ids: Option<Vec<String>>,
+    /// Whether synced activities are staged for review instead of written to
+    /// the ledger. IF_WARNINGS applies clean runs right away.
+    pub review_mode: ReviewMode,
 }
 
 impl Default for SyncConfig {
@@ -30,10 +45,77 @@ impl Default for SyncConfig {
         Self {
             page_limit: 1000,
             max_pages: 10_000,
+            detect_holdings_drift: true,
+            max_history_days: None,
+            repair_from: None,
+            account_ids: None,
+            review_mode: ReviewMode::Never,
         }
     }
 }
 
+/// Date range and import run mode for an account's activity fetch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActivityWindow {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub mode: ImportRunMode,
+}
+
+impl ActivityWindow {
+    /// Computes the fetch window for an account.
+    ///
+    /// - repair: from the requested date (REPAIR)
+    /// - previously synced: from the day before the last successful sync (INCREMENTAL)
+    /// - first sync with a history limit: the last `max_history_days` (BACKFILL)
+    /// - first sync otherwise: everything (INITIAL)
+    ///
+    /// Fails when the history limit reaches past the earliest representable date.
+    pub fn compute(
+        last_successful: Option<NaiveDate>,
+        end_date: NaiveDate,
+        config: &SyncConfig,
+    ) -> Result<Self, String> {
+        if let Some(from) = config.repair_from {
+            return Ok(Self {
+                start_date: Some(from.min(end_date)),
+                end_date: Some(end_date),
+                mode: ImportRunMode::Repair,
+            });
+        }
+
+        if let Some(last) = last_successful {
+            return Ok(Self {
+                start_date: Some((last - Days::new(1)).min(end_date)),
+                end_date: Some(end_date),
+                mode: ImportRunMode::Incremental,
+            });
+        }
+
+        match config.max_history_days {
+            Some(days) => {
+                let start_date = end_date
+                    .checked_sub_days(Days::new(days as u64))
+                    .ok_or_else(|| format!("History limit of {} days is out of range", days))?;
+                Ok(Self {
+                    start_date: Some(start_date),
+                    end_date: Some(end_date),
+                    mode: ImportRunMode::Backfill,
+                })
+            }
+            None => Ok(Self {
+                start_date: None,
+                end_date: None,
+                mode: ImportRunMode::Initial,
+            }),
+        }
+    }
+
+    fn format(date: Option<NaiveDate>) -> Option<String> {
+        date.map(|d| d.format("%Y-%m-%d").to_string())
+    }
+}
+
 /// Orchestrates broker data synchronization.
 ///
 /// This struct encapsulates the sync logic previously duplicated in
@@ -274,6 +356,13 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
                 continue;
             };
 
+            // Skip accounts outside the requested subset (e.g. a single-account repair)
+            if let Some(ids) = &self.config.account_ids {
+                if !ids.contains(&account.id) {
+                    continue;
+                }
+            }
+
             // Skip accounts that are not sync-enabled
             if !sync_enabled_broker_ids.contains(&broker_account_id) {
                 info!(
@@ -337,7 +426,7 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
                     // Create import run for holdings sync
                     let import_run = match self
                         .sync_service
-                        .create_import_run(&account_id, import_mode)
+                        .create_import_run(&account_id, import_mode, ReviewMode::Never)
                         .await
                     {
                         Ok(run) => {
@@ -483,21 +572,16 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
                 continue;
             }
 
-            // Compute query window
-            let (start_date, end_date_filter) =
-                self.compute_activity_query_window(&account_id, end_date)?;
-
-            // Determine import run mode
-            let import_mode = if start_date.is_none() {
-                ImportRunMode::Initial
-            } else {
-                ImportRunMode::Incremental
-            };
+            // Compute query window and import run mode
+            let window = self.compute_activity_query_window(&account_id, end_date)?;
+            let import_mode = window.mode.clone();
+            let start_date = ActivityWindow::format(window.start_date);
+            let end_date_filter = ActivityWindow::format(window.end_date);
 
             // Create import run
             let import_run = match self
                 .sync_service
-                .create_import_run(&account_id, import_mode)
+                .create_import_run(&account_id, import_mode, self.config.review_mode.clone())
                 .await
             {
                 Ok(run) => {
@@ -542,7 +626,7 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
                 )
                 .await
             {
-                Ok((fetched, inserted, assets_created, needs_review, new_asset_ids)) => {
+                Ok((fetched, mut inserted, assets_created, mut needs_review, new_asset_ids)) => {
                     // Build import run summary first (needed for both success and failure paths)
                     let summary = ImportRunSummary {
                         fetched,
@@ -590,6 +674,24 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
                             .sync_service
                             .finalize_import_run(run_id, summary, status, None)
                             .await;
+
+                        // Staged runs without warnings don't need a human look
+                        if needs_review > 0 && self.config.review_mode == ReviewMode::IfWarnings {
+                            match self.sync_service.auto_apply_staged_run(run_id).await {
+                                Ok(Some(applied)) => {
+                                    info!(
+                                        "Applied staged import run {} without review ({} activities)",
+                                        run_id, applied
+                                    );
+                                    inserted = applied as u32;
+                                    needs_review = 0;
+                                }
+                                Ok(None) => {}
+                                Err(e) => {
+                                    error!("Failed to apply staged import run {}: {}", run_id, e)
+                                }
+                            }
+                        }
                     }
 
                     // Emit completion event
@@ -611,6 +713,11 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
                     activities_summary.activities_upserted += inserted as usize;
                     activities_summary.assets_inserted += assets_created as usize;
                     activities_summary.new_asset_ids.extend(new_asset_ids);
+
+                    if self.config.detect_holdings_drift {
+                        self.record_holdings_observation(api_client, &account, &broker_account_id)
+                            .await;
+                    }
                 }
                 Err(err) => {
                     error!("Failed to sync activities for '{}': {}", account_name, err);
@@ -730,6 +837,37 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
         Ok((diff, assets_created, new_asset_ids))
     }
 
+    /// Fetch the broker's current holdings for a TRANSACTIONS mode account and
+    /// record them for drift detection. Failures are logged and never fail the sync.
+    async fn record_holdings_observation(
+        &self,
+        api_client: &dyn BrokerApiClient,
+        account: &Account,
+        broker_account_id: &str,
+    ) {
+        let holdings = match api_client.get_account_holdings(broker_account_id).await {
+            Ok(holdings) => holdings,
+            Err(e) => {
+                warn!(
+                    "Skipping holdings drift check for '{}': failed to fetch holdings: {}",
+                    account.name, e
+                );
+                return;
+            }
+        };
+
+        if let Err(e) = self
+            .sync_service
+            .record_holdings_observation(account, holdings)
+            .await
+        {
+            warn!(
+                "Failed to record holdings observation for '{}': {}",
+                account.name, e
+            );
+        }
+    }
+
     /// Sync activities for a single account with full pagination.
     ///
     /// Returns (fetched, inserted, assets_created, needs_review, new_asset_ids).
@@ -893,26 +1031,18 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
     fn compute_activity_query_window(
         &self,
         account_id: &str,
-        end_date: chrono::NaiveDate,
-    ) -> Result<(Option<String>, Option<String>), String> {
+        end_date: NaiveDate,
+    ) -> Result<ActivityWindow, String> {
         let sync_state = self
             .sync_service
             .get_activity_sync_state(account_id)
             .map_err(|e| format!("Failed to read activity sync state: {}", e))?;
 
-        let from_state = sync_state
+        let last_successful = sync_state
             .and_then(|s| s.last_successful_at)
-            .map(|dt| dt.date_naive())
-            .map(|d| (d - chrono::Days::new(1)).min(end_date));
-
-        if let Some(d) = from_state {
-            return Ok((
-                Some(d.format("%Y-%m-%d").to_string()),
-                Some(end_date.format("%Y-%m-%d").to_string()),
-            ));
-        }
+            .map(|dt| dt.date_naive());
 
-        Ok((None, None))
+        ActivityWindow::compute(last_successful, end_date, &self.config)
     }
 }
 
@@ -925,5 +1055,72 @@ mod tests {
         let config = SyncConfig::default();
         assert_eq!(config.page_limit, 1000);
         assert_eq!(config.max_pages, 10_000);
+        assert!(config.detect_holdings_drift);
+        assert!(config.max_history_days.is_none());
+        assert!(config.repair_from.is_none());
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_activity_window_initial_fetches_everything() {
+        let window =
+            ActivityWindow::compute(None, date(2025, 6, 30), &SyncConfig::default()).unwrap();
+        assert_eq!(window.start_date, None);
+        assert_eq!(window.end_date, None);
+        assert_eq!(window.mode, ImportRunMode::Initial);
+    }
+
+    #[test]
+    fn test_activity_window_incremental_overlaps_one_day() {
+        let window = ActivityWindow::compute(
+            Some(date(2025, 6, 20)),
+            date(2025, 6, 30),
+            &SyncConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(window.start_date, Some(date(2025, 6, 19)));
+        assert_eq!(window.end_date, Some(date(2025, 6, 30)));
+        assert_eq!(window.mode, ImportRunMode::Incremental);
+    }
+
+    #[test]
+    fn test_activity_window_backfill_limits_first_sync() {
+        let config = SyncConfig {
+            max_history_days: Some(365),
+            ..Default::default()
+        };
+        let window = ActivityWindow::compute(None, date(2025, 6, 30), &config).unwrap();
+        assert_eq!(window.start_date, Some(date(2024, 6, 30)));
+        assert_eq!(window.mode, ImportRunMode::Backfill);
+
+        // The history limit only applies to the first sync
+        let window =
+            ActivityWindow::compute(Some(date(2025, 6, 20)), date(2025, 6, 30), &config).unwrap();
+        assert_eq!(window.mode, ImportRunMode::Incremental);
+    }
+
+    #[test]
+    fn test_activity_window_rejects_out_of_range_backfill() {
+        let config = SyncConfig {
+            max_history_days: Some(u32::MAX),
+            ..Default::default()
+        };
+        assert!(ActivityWindow::compute(None, date(2025, 6, 30), &config).is_err());
+    }
+
+    #[test]
+    fn test_activity_window_repair_ignores_sync_state() {
+        let config = SyncConfig {
+            repair_from: Some(date(2024, 1, 15)),
+            ..Default::default()
+        };
+        let window =
+            ActivityWindow::compute(Some(date(2025, 6, 20)), date(2025, 6, 30), &config).unwrap();
+        assert_eq!(window.start_date, Some(date(2024, 1, 15)));
+        assert_eq!(window.end_date, Some(date(2025, 6, 30)));
+        assert_eq!(window.mode, ImportRunMode::Repair);
     }
 }
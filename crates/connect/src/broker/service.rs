@@ -6,9 +6,9 @@ use std::sync::Arc;
 
 use super::mapping;
 use super::models::{
-    AccountUniversalActivity, BrokerAccount, BrokerConnection, HoldingsBalance, HoldingsDiff,
-    HoldingsOptionPosition, HoldingsPosition, NewAccountInfo, SyncAccountsResponse,
-    SyncConnectionsResponse,
+    AccountUniversalActivity, BrokerAccount, BrokerConnection, BrokerHoldingsResponse,
+    HoldingsBalance, HoldingsDiff, HoldingsOptionPosition, HoldingsPosition, NewAccountInfo,
+    SyncAccountsResponse, SyncConnectionsResponse,
 };
 use super::traits::{BrokerSyncServiceTrait, PlatformRepositoryTrait};
 use crate::broker_ingest::{
@@ -22,8 +22,8 @@ use rust_decimal::Decimal;
 use std::collections::{HashMap, HashSet};
 use wealthfolio_core::accounts::{Account, AccountServiceTrait, NewAccount, TrackingMode};
 use wealthfolio_core::activities::{
-    compute_idempotency_key, ActivityRepositoryTrait, ActivityServiceTrait, ActivityUpsert,
-    NewActivity,
+    compute_idempotency_key, ActivityDuplicateServiceTrait, ActivityRepositoryTrait,
+    ActivityServiceTrait, ActivityStagingServiceTrait, ActivityUpsert, DuplicateProbe, NewActivity,
 };
 use wealthfolio_core::assets::{
     parse_crypto_pair_symbol, parse_symbol_with_exchange_suffix, AssetKind, AssetServiceTrait,
@@ -31,6 +31,7 @@ use wealthfolio_core::assets::{
 };
 use wealthfolio_core::errors::Result;
 use wealthfolio_core::events::{DomainEvent, DomainEventSink, NoOpDomainEventSink};
+use wealthfolio_core::portfolio::reconciliation::HoldingsDriftServiceTrait;
 use wealthfolio_core::portfolio::snapshot::{
     AccountStateSnapshot, Position, SnapshotRepositoryTrait, SnapshotServiceTrait, SnapshotSource,
 };
@@ -54,6 +55,9 @@ pub struct BrokerSyncService {
     snapshot_repository: Arc<dyn SnapshotRepositoryTrait>,
     quote_service: Arc<dyn QuoteServiceTrait>,
     snapshot_service: Option<Arc<dyn SnapshotServiceTrait>>,
+    duplicate_service: Option<Arc<dyn ActivityDuplicateServiceTrait>>,
+    drift_service: Option<Arc<dyn HoldingsDriftServiceTrait>>,
+    staging_service: Option<Arc<dyn ActivityStagingServiceTrait>>,
     event_sink: Arc<dyn DomainEventSink>,
 }
 
@@ -81,6 +85,9 @@ impl BrokerSyncService {
             snapshot_repository,
             quote_service,
             snapshot_service: None,
+            duplicate_service: None,
+            drift_service: None,
+            staging_service: None,
             event_sink: Arc::new(NoOpDomainEventSink),
         }
     }
@@ -94,6 +101,117 @@ impl BrokerSyncService {
         self
     }
 
+    /// Sets the duplicate service used to flag synced activities that look like
+    /// existing manual or imported entries.
+    pub fn with_duplicate_service(
+        mut self,
+        duplicate_service: Arc<dyn ActivityDuplicateServiceTrait>,
+    ) -> Self {
+        self.duplicate_service = Some(duplicate_service);
+        self
+    }
+
+    /// Sets the drift service that records broker holdings fetched during
+    /// TRANSACTIONS mode syncs for comparison with computed holdings.
+    pub fn with_drift_service(mut self, drift_service: Arc<dyn HoldingsDriftServiceTrait>) -> Self {
+        self.drift_service = Some(drift_service);
+        self
+    }
+
+    /// Sets the staging service that holds activities of runs created with a
+    /// review mode until the user approves them.
+    pub fn with_staging_service(
+        mut self,
+        staging_service: Arc<dyn ActivityStagingServiceTrait>,
+    ) -> Self {
+        self.staging_service = Some(staging_service);
+        self
+    }
+
+    /// Staging service for the run, if its activities must be reviewed first.
+    fn staging_for_run(
+        &self,
+        import_run_id: Option<&str>,
+    ) -> Result<Option<Arc<dyn ActivityStagingServiceTrait>>> {
+        let (Some(staging_service), Some(run_id)) = (&self.staging_service, import_run_id) else {
+            return Ok(None);
+        };
+        let review_mode = self
+            .import_run_repository
+            .get_by_id(run_id)?
+            .map(|run| run.review_mode)
+            .unwrap_or_default();
+        Ok((review_mode != ReviewMode::Never).then(|| staging_service.clone()))
+    }
+
+    /// Marks upserts that fuzzy-match an existing activity as needing review.
+    /// Returns the number of newly flagged activities.
+    fn flag_possible_duplicates(
+        &self,
+        account_id: &str,
+        activity_upserts: &mut [ActivityUpsert],
+    ) -> usize {
+        let Some(duplicate_service) = &self.duplicate_service else {
+            return 0;
+        };
+
+        let probes: Vec<DuplicateProbe> = activity_upserts
+            .iter()
+            .filter_map(|upsert| {
+                let date = DateTime::parse_from_rfc3339(&upsert.activity_date).ok()?;
+                Some(DuplicateProbe {
+                    activity_id: Some(upsert.id.clone()),
+                    account_id: upsert.account_id.clone(),
+                    activity_type: upsert.activity_type.clone(),
+                    asset_id: upsert.asset_id.clone(),
+                    symbol: None,
+                    date: date.with_timezone(&Utc).date_naive(),
+                    quantity: upsert.quantity,
+                    amount: upsert.amount,
+                    currency: upsert.currency.clone(),
+                    source_system: upsert.source_system.clone(),
+                    source_record_id: upsert.source_record_id.clone(),
+                    idempotency_key: upsert.idempotency_key.clone(),
+                })
+            })
+            .collect();
+
+        let matches = match duplicate_service.find_matches(account_id, &probes) {
+            Ok(matches) => matches,
+            Err(e) => {
+                warn!(
+                    "Skipping duplicate detection for account {}: {}",
+                    account_id, e
+                );
+                return 0;
+            }
+        };
+
+        let flagged: HashMap<&str, &str> = probes
+            .iter()
+            .zip(matches.iter())
+            .filter_map(|(probe, m)| {
+                let (existing_id, _) = m.as_ref()?;
+                Some((probe.activity_id.as_deref()?, existing_id.as_str()))
+            })
+            .collect();
+
+        let mut count = 0;
+        for upsert in activity_upserts.iter_mut() {
+            if let Some(existing_id) = flagged.get(upsert.id.as_str()) {
+                debug!(
+                    "Synced activity {} looks like a duplicate of {}",
+                    upsert.id, existing_id
+                );
+                if !upsert.needs_review.unwrap_or(false) {
+                    upsert.needs_review = Some(true);
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
     /// Sets the domain event sink for emitting events during broker sync.
     pub fn with_event_sink(mut self, event_sink: Arc<dyn DomainEventSink>) -> Self {
         self.event_sink = event_sink;
@@ -227,7 +345,12 @@ impl BrokerSyncServiceTrait for BrokerSyncService {
                 platform_id,
                 account_number: broker_account.account_number.clone(),
                 meta: broker_account.to_meta_json(),
-                provider: Some("SNAPTRADE".to_string()),
+                provider: Some(
+                    broker_account
+                        .provider
+                        .clone()
+                        .unwrap_or_else(|| "SNAPTRADE".to_string()),
+                ),
                 provider_account_id: Some(provider_account_id.clone()),
                 is_archived: false,
                 tracking_mode: TrackingMode::NotSet,
@@ -419,8 +542,19 @@ impl BrokerSyncServiceTrait for BrokerSyncService {
             });
         }
 
+        let needs_review_count =
+            needs_review_count + self.flag_possible_duplicates(&account_id, &mut activity_upserts);
         let activities_count = activity_upserts.len();
 
+        if let Some(staging_service) = self.staging_for_run(import_run_id.as_deref())? {
+            let run_id = import_run_id.unwrap_or_default();
+            let staged = staging_service
+                .stage_activities(&run_id, &account_id, activity_upserts)
+                .await?;
+            // Staged rows wait for review; nothing reached the ledger yet
+            return Ok((0, assets_created, new_asset_ids, staged.staged));
+        }
+
         debug!(
             "Preparing to upsert {} activities and {} assets for account {}",
             activities_count, assets_created, account_id
@@ -505,18 +639,38 @@ impl BrokerSyncServiceTrait for BrokerSyncService {
         Ok(runs)
     }
 
-    async fn create_import_run(&self, account_id: &str, mode: ImportRunMode) -> Result<ImportRun> {
+    async fn create_import_run(
+        &self,
+        account_id: &str,
+        mode: ImportRunMode,
+        review_mode: ReviewMode,
+    ) -> Result<ImportRun> {
         let import_run = ImportRun::new(
             account_id.to_string(),
             DEFAULT_BROKERAGE_PROVIDER.to_string(),
             ImportRunType::Sync,
             mode,
-            ReviewMode::Never,
+            review_mode,
         );
 
         self.import_run_repository.create(import_run).await
     }
 
+    async fn auto_apply_staged_run(&self, run_id: &str) -> Result<Option<usize>> {
+        let Some(staging_service) = self.staging_for_run(Some(run_id))? else {
+            return Ok(None);
+        };
+        let review = staging_service.get_review(run_id)?;
+        if review.activities.is_empty() || review.has_warnings() {
+            return Ok(None);
+        }
+
+        let run = staging_service.apply_run(run_id).await?;
+        Ok(Some(
+            run.summary.map_or(0, |s| (s.inserted + s.updated) as usize),
+        ))
+    }
+
     async fn finalize_import_run(
         &self,
         run_id: &str,
@@ -951,6 +1105,26 @@ impl BrokerSyncServiceTrait for BrokerSyncService {
         saved_diff.snapshot_saved = true;
         Ok((saved_diff, assets_created, new_asset_ids))
     }
+
+    async fn record_holdings_observation(
+        &self,
+        account: &Account,
+        holdings: BrokerHoldingsResponse,
+    ) -> Result<()> {
+        let Some(drift_service) = &self.drift_service else {
+            return Ok(());
+        };
+
+        let statement = mapping::holdings_to_statement(
+            &account.id,
+            &account.currency,
+            valuation_date_today(),
+            holdings.balances.as_deref().unwrap_or_default(),
+            holdings.positions.as_deref().unwrap_or_default(),
+        );
+        drift_service.record_observation(statement).await?;
+        Ok(())
+    }
 }
 
 impl BrokerSyncService {
@@ -1104,7 +1278,7 @@ impl BrokerSyncService {
             && a.currency == b.currency
     }
 
-    fn normalize_holdings_symbol(
+    pub(crate) fn normalize_holdings_symbol(
         raw_symbol: Option<&str>,
         api_symbol: Option<&str>,
         is_crypto: bool,
@@ -1578,7 +1752,10 @@ mod tests {
             today,
         ));
         assert!(
-            !BrokerSyncService::should_preserve_manual_snapshot_for_date(Some(&broker_today), today)
+            !BrokerSyncService::should_preserve_manual_snapshot_for_date(
+                Some(&broker_today),
+                today
+            )
         );
         assert!(
             !BrokerSyncService::should_preserve_manual_snapshot_for_date(
@@ -8,7 +8,9 @@ use super::models::{
     HoldingsPosition, PaginatedUniversalActivity, SyncAccountsResponse, SyncConnectionsResponse,
 };
 use crate::broker_ingest::BrokerSyncState;
-use crate::broker_ingest::{ImportRun, ImportRunMode, ImportRunStatus, ImportRunSummary};
+use crate::broker_ingest::{
+    ImportRun, ImportRunMode, ImportRunStatus, ImportRunSummary, ReviewMode,
+};
 use crate::platform::Platform;
 use wealthfolio_core::accounts::Account;
 use wealthfolio_core::errors::Result;
@@ -129,8 +131,19 @@ pub trait BrokerSyncServiceTrait: Send + Sync {
         offset: i64,
     ) -> Result<Vec<ImportRun>>;
 
-    /// Create a new import run for broker sync.
-    async fn create_import_run(&self, account_id: &str, mode: ImportRunMode) -> Result<ImportRun>;
+    /// Create a new import run for broker sync. With a review mode other than
+    /// `Never`, the run's activities are staged for review instead of upserted.
+    async fn create_import_run(
+        &self,
+        account_id: &str,
+        mode: ImportRunMode,
+        review_mode: ReviewMode,
+    ) -> Result<ImportRun>;
+
+    /// Applies a staged run right away when nothing in it needs review.
+    /// Returns the number of activities written, or `None` if the run stays
+    /// staged (warnings found, or nothing staged).
+    async fn auto_apply_staged_run(&self, run_id: &str) -> Result<Option<usize>>;
 
     /// Finalize an import run with summary and status.
     async fn finalize_import_run(
@@ -150,4 +163,13 @@ pub trait BrokerSyncServiceTrait: Send + Sync {
         positions: Vec<HoldingsPosition>,
         option_positions: Vec<HoldingsOptionPosition>,
     ) -> Result<(HoldingsDiff, usize, Vec<String>)>;
+
+    /// Record the holdings a broker reports for a TRANSACTIONS mode account so they
+    /// can be compared with transaction-derived holdings once snapshots are
+    /// recalculated. No-op when holdings drift detection is not configured.
+    async fn record_holdings_observation(
+        &self,
+        account: &Account,
+        holdings: BrokerHoldingsResponse,
+    ) -> Result<()>;
 }
@@ -7,7 +7,11 @@
 pub mod broker;
 pub mod broker_ingest;
 pub mod client;
+#[cfg(feature = "broker")]
+pub mod local;
 pub mod platform;
+#[cfg(feature = "broker")]
+pub mod schedule;
 pub mod token_lifecycle;
 
 // Re-export commonly used types
@@ -33,4 +37,14 @@ pub use broker_ingest::{
     ImportRunMode, ImportRunRepositoryTrait, ImportRunStatus, ImportRunSummary, ImportRunType,
     ReviewMode,
 };
+#[cfg(feature = "broker")]
+pub use local::{
+    configure_local_connector, create_local_connector, list_local_connectors,
+    remove_local_connector, LocalConnectorInfo, LocalConnectorKind, SimpleFinClient,
+};
 pub use platform::Platform;
+#[cfg(feature = "broker")]
+pub use schedule::{
+    BrokerSyncScheduler, QuietHours, ScheduleTrigger, ScheduledSyncRunner, SyncSchedule,
+    SyncScheduleService, CONNECT_SYNC_SOURCE,
+};
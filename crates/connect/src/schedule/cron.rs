@@ -0,0 +1,257 @@
+//! Five-field cron expression parsing.
+//!
+//! Supports the standard `minute hour day-of-month month day-of-week` layout
+//! with `*`, lists (`1,15`), ranges (`1-5`), steps (`*/15`, `8-18/2`), day names
+//! (`MON`-`SUN`, with 0 and 7 both meaning Sunday), and the `@hourly`, `@daily`
+//! and `@weekly` shortcuts. As in Vixie cron, when both day fields are
+//! restricted a date matches if either one does.
+
+use chrono::{Datelike, Days, Duration, NaiveDateTime, NaiveTime, Timelike};
+
+use wealthfolio_core::errors::{Error, Result, ValidationError};
+
+/// How far ahead `next_after` searches before giving up (covers leap days).
+const MAX_SEARCH_DAYS: u64 = 366 * 5;
+
+const DAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+fn invalid(message: String) -> Error {
+    Error::Validation(ValidationError::InvalidInput(message))
+}
+
+/// A parsed cron expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpression {
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    days_of_month: Vec<bool>,
+    months: Vec<bool>,
+    days_of_week: Vec<bool>,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl CronExpression {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let expanded = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(invalid(format!(
+                "Cron expression '{}' must have 5 fields (minute hour day month weekday)",
+                expression
+            )));
+        }
+
+        let mut days_of_week = parse_field(fields[4], 0, 7, "day of week")?;
+        // 7 is an alias for Sunday
+        if days_of_week[7] {
+            days_of_week[0] = true;
+        }
+        days_of_week.truncate(7);
+
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59, "minute")?,
+            hours: parse_field(fields[1], 0, 23, "hour")?,
+            days_of_month: parse_field(fields[2], 1, 31, "day of month")?,
+            months: parse_field(fields[3], 1, 12, "month")?,
+            days_of_week,
+            day_of_month_restricted: fields[2] != "*",
+            day_of_week_restricted: fields[4] != "*",
+        })
+    }
+
+    fn matches_date(&self, date: chrono::NaiveDate) -> bool {
+        if !self.months[date.month() as usize] {
+            return false;
+        }
+        let dom = self.days_of_month[date.day() as usize];
+        let dow = self.days_of_week[date.weekday().num_days_from_sunday() as usize];
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => dom || dow,
+            (true, false) => dom,
+            (false, true) => dow,
+            (false, false) => true,
+        }
+    }
+
+    /// Returns the first matching minute strictly after `after`.
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = after
+            .with_second(0)
+            .and_then(|t| t.with_nanosecond(0))
+            .unwrap_or(after)
+            + Duration::minutes(1);
+
+        for offset in 0..MAX_SEARCH_DAYS {
+            let date = start.date().checked_add_days(Days::new(offset))?;
+            if !self.matches_date(date) {
+                continue;
+            }
+            let (first_hour, first_minute) = if offset == 0 {
+                (start.hour(), start.minute())
+            } else {
+                (0, 0)
+            };
+            for hour in first_hour..24 {
+                if !self.hours[hour as usize] {
+                    continue;
+                }
+                let from_minute = if hour == first_hour { first_minute } else { 0 };
+                if let Some(minute) = (from_minute..60).find(|m| self.minutes[*m as usize]) {
+                    return NaiveTime::from_hms_opt(hour, minute, 0).map(|t| date.and_time(t));
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the shortest gap between the next `samples` runs after `from`.
+    pub fn min_gap(&self, from: NaiveDateTime, samples: usize) -> Option<Duration> {
+        let mut previous = self.next_after(from)?;
+        let mut gap: Option<Duration> = None;
+        for _ in 1..samples {
+            let next = self.next_after(previous)?;
+            let current = next - previous;
+            gap = Some(gap.map_or(current, |g| g.min(current)));
+            previous = next;
+        }
+        gap
+    }
+}
+
+fn parse_value(value: &str, name: &str) -> Result<u32> {
+    if name == "day of week" {
+        if let Some(index) = DAY_NAMES.iter().position(|d| d.eq_ignore_ascii_case(value)) {
+            return Ok(index as u32);
+        }
+    }
+    value
+        .parse::<u32>()
+        .map_err(|_| invalid(format!("Invalid {} value '{}'", name, value)))
+}
+
+/// Parses one field into a lookup table indexed by value (`0..=max`).
+fn parse_field(field: &str, min: u32, max: u32, name: &str) -> Result<Vec<bool>> {
+    let mut allowed = vec![false; max as usize + 1];
+
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| invalid(format!("Invalid {} step '{}'", name, step)))?;
+                (range, step)
+            }
+            None => (item, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse_value(start, name)?, parse_value(end, name)?)
+        } else {
+            let value = parse_value(range, name)?;
+            // "5/15" means every 15 starting at 5
+            (value, if item.contains('/') { max } else { value })
+        };
+
+        if start < min || end > max || start > end {
+            return Err(invalid(format!(
+                "{} '{}' is outside {}-{}",
+                name, range, min, max
+            )));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            allowed[value as usize] = true;
+        }
+    }
+
+    Ok(allowed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_every_six_hours() {
+        let cron = CronExpression::parse("0 */6 * * *").unwrap();
+        assert_eq!(
+            cron.next_after(at(2025, 6, 30, 7, 30)),
+            Some(at(2025, 6, 30, 12, 0))
+        );
+        assert_eq!(
+            cron.next_after(at(2025, 6, 30, 12, 0)),
+            Some(at(2025, 6, 30, 18, 0))
+        );
+        assert_eq!(
+            cron.next_after(at(2025, 6, 30, 18, 0)),
+            Some(at(2025, 7, 1, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_weekdays_by_name() {
+        // 2025-06-28 is a Saturday
+        let cron = CronExpression::parse("30 7 * * MON-FRI").unwrap();
+        assert_eq!(
+            cron.next_after(at(2025, 6, 28, 9, 0)),
+            Some(at(2025, 6, 30, 7, 30))
+        );
+    }
+
+    #[test]
+    fn test_day_fields_match_either_when_both_restricted() {
+        // 1st of the month or any Sunday; 2025-06-29 is a Sunday
+        let cron = CronExpression::parse("0 6 1 * 7").unwrap();
+        assert_eq!(
+            cron.next_after(at(2025, 6, 27, 0, 0)),
+            Some(at(2025, 6, 29, 6, 0))
+        );
+        assert_eq!(
+            cron.next_after(at(2025, 6, 29, 6, 0)),
+            Some(at(2025, 7, 1, 6, 0))
+        );
+    }
+
+    #[test]
+    fn test_shortcuts_and_lists() {
+        let cron = CronExpression::parse("@daily").unwrap();
+        assert_eq!(
+            cron.next_after(at(2025, 6, 30, 0, 0)),
+            Some(at(2025, 7, 1, 0, 0))
+        );
+
+        let cron = CronExpression::parse("0 8,20 * * *").unwrap();
+        assert_eq!(
+            cron.min_gap(at(2025, 6, 30, 0, 0), 4),
+            Some(Duration::hours(12))
+        );
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        assert!(CronExpression::parse("0 * * *").is_err());
+        assert!(CronExpression::parse("60 * * * *").is_err());
+        assert!(CronExpression::parse("0 */0 * * *").is_err());
+        assert!(CronExpression::parse("0 5-2 * * *").is_err());
+        assert!(CronExpression::parse("0 0 * * FUNDAY").is_err());
+    }
+}
@@ -0,0 +1,23 @@
+//! Scheduled broker sync.
+//!
+//! Each connection source (Wealthfolio Connect or a local connector) has a
+//! [`SyncSchedule`] stored in app settings: an interval or cron trigger, an
+//! optional quiet-hours window, and an optional history limit for the first
+//! sync of new accounts. [`BrokerSyncScheduler`] checks the schedules every
+//! minute and hands due runs to an app-provided [`ScheduledSyncRunner`], so the
+//! server and desktop apps share the same timing rules.
+
+mod cron;
+mod model;
+mod scheduler;
+mod service;
+#[cfg(test)]
+mod test_support;
+
+pub use cron::CronExpression;
+pub use model::{
+    QuietHours, ScheduleTrigger, SyncSchedule, BROKER_SYNC_SCHEDULES_KEY, CONNECT_SYNC_SOURCE,
+    DEFAULT_SYNC_INTERVAL_MINUTES, MAX_BACKFILL_DAYS, MIN_SYNC_INTERVAL_MINUTES,
+};
+pub use scheduler::{BrokerSyncScheduler, ScheduledSyncRunner};
+pub use service::SyncScheduleService;
@@ -0,0 +1,369 @@
+//! Broker sync schedule models.
+
+use chrono::{Duration, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use wealthfolio_core::errors::{Error, Result, ValidationError};
+
+use super::cron::CronExpression;
+use crate::broker::SyncConfig;
+use crate::broker_ingest::ReviewMode;
+
+/// App setting holding the JSON-encoded schedule list. Not in the shared
+/// settings allowlist, so schedules stay per device.
+pub const BROKER_SYNC_SCHEDULES_KEY: &str = "broker_sync_schedules";
+
+/// Schedule source ID for Wealthfolio Connect. Local connectors use their
+/// [`LocalConnectorKind`](crate::LocalConnectorKind) ID.
+pub const CONNECT_SYNC_SOURCE: &str = "connect";
+
+/// Interval used when no schedule has been configured (4 hours).
+pub const DEFAULT_SYNC_INTERVAL_MINUTES: u32 = 240;
+
+/// Shortest allowed gap between scheduled runs, to stay within broker rate limits.
+pub const MIN_SYNC_INTERVAL_MINUTES: u32 = 60;
+
+/// Longest allowed first-sync history limit (100 years).
+pub const MAX_BACKFILL_DAYS: u32 = 36_500;
+
+/// Number of upcoming cron runs sampled when checking the minimum gap.
+const CRON_GAP_SAMPLES: usize = 48;
+
+const TIME_FORMAT: &str = "%H:%M";
+
+fn invalid(message: String) -> Error {
+    Error::Validation(ValidationError::InvalidInput(message))
+}
+
+/// When a scheduled sync fires.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ScheduleTrigger {
+    /// Every `minutes` since the previous run.
+    Interval { minutes: u32 },
+    /// A five-field cron expression evaluated in local time.
+    Cron { expression: String },
+}
+
+impl Default for ScheduleTrigger {
+    fn default() -> Self {
+        ScheduleTrigger::Interval {
+            minutes: DEFAULT_SYNC_INTERVAL_MINUTES,
+        }
+    }
+}
+
+/// Local-time window ("HH:MM") during which scheduled syncs are suppressed.
+/// `start` after `end` wraps past midnight (e.g. 22:00-07:00).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+impl QuietHours {
+    fn parse_time(value: &str) -> Result<NaiveTime> {
+        NaiveTime::parse_from_str(value.trim(), TIME_FORMAT).map_err(|_| {
+            invalid(format!(
+                "Invalid quiet hours time '{}', expected HH:MM",
+                value
+            ))
+        })
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let start = Self::parse_time(&self.start)?;
+        let end = Self::parse_time(&self.end)?;
+        if start == end {
+            return Err(invalid("Quiet hours start and end must differ".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Whether `time` falls in the window (start inclusive, end exclusive).
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        let (Ok(start), Ok(end)) = (Self::parse_time(&self.start), Self::parse_time(&self.end))
+        else {
+            return false;
+        };
+        if start <= end {
+            time >= start && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+}
+
+/// Sync schedule for one connection source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSchedule {
+    /// [`CONNECT_SYNC_SOURCE`] or a local connector ID
+    pub source_id: String,
+    pub enabled: bool,
+    #[serde(default)]
+    pub trigger: ScheduleTrigger,
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+    /// Limit the first sync of new accounts to this many days of history.
+    #[serde(default)]
+    pub backfill_days: Option<u32>,
+    /// Whether synced activities wait for review before reaching the ledger.
+    #[serde(default)]
+    pub review_mode: ReviewMode,
+}
+
+impl SyncSchedule {
+    /// Default schedule for a source: enabled, every 4 hours, full history.
+    pub fn default_for(source_id: &str) -> Self {
+        Self {
+            source_id: source_id.to_string(),
+            enabled: true,
+            trigger: ScheduleTrigger::default(),
+            quiet_hours: None,
+            backfill_days: None,
+            review_mode: ReviewMode::Never,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match &self.trigger {
+            ScheduleTrigger::Interval { minutes } => {
+                if *minutes < MIN_SYNC_INTERVAL_MINUTES {
+                    return Err(invalid(format!(
+                        "Sync interval must be at least {} minutes",
+                        MIN_SYNC_INTERVAL_MINUTES
+                    )));
+                }
+            }
+            ScheduleTrigger::Cron { expression } => {
+                let cron = CronExpression::parse(expression)?;
+                let now = chrono::Local::now().naive_local();
+                match cron.min_gap(now, CRON_GAP_SAMPLES) {
+                    Some(gap) if gap < Duration::minutes(MIN_SYNC_INTERVAL_MINUTES as i64) => {
+                        return Err(invalid(format!(
+                            "Cron schedule '{}' runs more often than every {} minutes",
+                            expression, MIN_SYNC_INTERVAL_MINUTES
+                        )));
+                    }
+                    None if cron.next_after(now).is_none() => {
+                        return Err(invalid(format!(
+                            "Cron schedule '{}' never runs",
+                            expression
+                        )));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if let Some(quiet_hours) = &self.quiet_hours {
+            quiet_hours.validate()?;
+        }
+
+        if let Some(days) = self.backfill_days {
+            if days == 0 || days > MAX_BACKFILL_DAYS {
+                return Err(invalid(format!(
+                    "Backfill days must be between 1 and {}",
+                    MAX_BACKFILL_DAYS
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether a run is due at `now` (local time).
+    ///
+    /// `last_run` is the previous scheduled run in this process; `anchor` is
+    /// used in its place for cron triggers so the first run waits for the next
+    /// matching time instead of firing at startup.
+    pub fn is_due(
+        &self,
+        last_run: Option<NaiveDateTime>,
+        anchor: NaiveDateTime,
+        now: NaiveDateTime,
+    ) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        if let Some(quiet_hours) = &self.quiet_hours {
+            if quiet_hours.contains(now.time()) {
+                return false;
+            }
+        }
+        if let Some(last) = last_run {
+            if now - last < Duration::minutes(MIN_SYNC_INTERVAL_MINUTES as i64) {
+                return false;
+            }
+        }
+
+        match &self.trigger {
+            ScheduleTrigger::Interval { minutes } => match last_run {
+                Some(last) => now - last >= Duration::minutes(*minutes as i64),
+                None => true,
+            },
+            ScheduleTrigger::Cron { expression } => {
+                let Ok(cron) = CronExpression::parse(expression) else {
+                    return false;
+                };
+                cron.next_after(last_run.unwrap_or(anchor))
+                    .is_some_and(|next| next <= now)
+            }
+        }
+    }
+
+    /// Sync configuration for a scheduled run of this source.
+    pub fn sync_config(&self) -> SyncConfig {
+        SyncConfig {
+            max_history_days: self.backfill_days,
+            review_mode: self.review_mode.clone(),
+            ..SyncConfig::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 6, 30)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    }
+
+    fn quiet(start: &str, end: &str) -> QuietHours {
+        QuietHours {
+            start: start.to_string(),
+            end: end.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_quiet_hours_wrap_midnight() {
+        let night = quiet("22:00", "07:00");
+        assert!(night.contains(at(23, 30).time()));
+        assert!(night.contains(at(6, 59).time()));
+        assert!(!night.contains(at(7, 0).time()));
+        assert!(!night.contains(at(12, 0).time()));
+
+        let lunch = quiet("12:00", "13:00");
+        assert!(lunch.contains(at(12, 30).time()));
+        assert!(!lunch.contains(at(13, 0).time()));
+    }
+
+    #[test]
+    fn test_interval_schedule_is_due() {
+        let schedule = SyncSchedule::default_for(CONNECT_SYNC_SOURCE);
+        let anchor = at(0, 0);
+
+        assert!(schedule.is_due(None, anchor, at(1, 0)));
+        assert!(!schedule.is_due(Some(at(1, 0)), anchor, at(4, 59)));
+        assert!(schedule.is_due(Some(at(1, 0)), anchor, at(5, 0)));
+
+        let disabled = SyncSchedule {
+            enabled: false,
+            ..schedule
+        };
+        assert!(!disabled.is_due(None, anchor, at(1, 0)));
+    }
+
+    #[test]
+    fn test_cron_schedule_waits_for_next_match() {
+        let schedule = SyncSchedule {
+            trigger: ScheduleTrigger::Cron {
+                expression: "0 6,18 * * *".to_string(),
+            },
+            ..SyncSchedule::default_for(CONNECT_SYNC_SOURCE)
+        };
+        let anchor = at(2, 0);
+
+        assert!(!schedule.is_due(None, anchor, at(5, 59)));
+        assert!(schedule.is_due(None, anchor, at(6, 0)));
+        assert!(!schedule.is_due(Some(at(6, 0)), anchor, at(17, 0)));
+        assert!(schedule.is_due(Some(at(6, 0)), anchor, at(18, 1)));
+    }
+
+    #[test]
+    fn test_quiet_hours_suppress_due_runs() {
+        let schedule = SyncSchedule {
+            quiet_hours: Some(quiet("22:00", "07:00")),
+            ..SyncSchedule::default_for(CONNECT_SYNC_SOURCE)
+        };
+        assert!(!schedule.is_due(None, at(0, 0), at(3, 0)));
+        assert!(schedule.is_due(None, at(0, 0), at(7, 0)));
+    }
+
+    #[test]
+    fn test_validate_rejects_too_frequent_schedules() {
+        let mut schedule = SyncSchedule::default_for(CONNECT_SYNC_SOURCE);
+        assert!(schedule.validate().is_ok());
+
+        schedule.trigger = ScheduleTrigger::Interval { minutes: 15 };
+        assert!(schedule.validate().is_err());
+
+        schedule.trigger = ScheduleTrigger::Cron {
+            expression: "*/30 * * * *".to_string(),
+        };
+        assert!(schedule.validate().is_err());
+
+        schedule.trigger = ScheduleTrigger::Cron {
+            expression: "0 */2 * * *".to_string(),
+        };
+        assert!(schedule.validate().is_ok());
+
+        schedule.quiet_hours = Some(quiet("25:00", "07:00"));
+        assert!(schedule.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_bounds_backfill_days() {
+        let mut schedule = SyncSchedule::default_for("simplefin");
+        for (days, valid) in [
+            (0, false),
+            (1, true),
+            (MAX_BACKFILL_DAYS, true),
+            (u32::MAX, false),
+        ] {
+            schedule.backfill_days = Some(days);
+            assert_eq!(
+                schedule.validate().is_ok(),
+                valid,
+                "backfill_days = {}",
+                days
+            );
+        }
+    }
+
+    #[test]
+    fn test_sync_config_applies_backfill_limit() {
+        let schedule = SyncSchedule {
+            backfill_days: Some(90),
+            review_mode: ReviewMode::IfWarnings,
+            ..SyncSchedule::default_for("simplefin")
+        };
+        let config = schedule.sync_config();
+        assert_eq!(config.max_history_days, Some(90));
+        assert_eq!(config.review_mode, ReviewMode::IfWarnings);
+        assert!(config.repair_from.is_none());
+    }
+
+    #[test]
+    fn test_schedule_json_shape() {
+        let json = r#"{"sourceId":"connect","enabled":true,
+            "trigger":{"type":"cron","expression":"0 6 * * *"},
+            "quietHours":{"start":"22:00","end":"07:00"},"backfillDays":365}"#;
+        let schedule: SyncSchedule = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            schedule.trigger,
+            ScheduleTrigger::Cron {
+                expression: "0 6 * * *".to_string()
+            }
+        );
+        assert_eq!(schedule.backfill_days, Some(365));
+        assert_eq!(schedule.review_mode, ReviewMode::Never);
+    }
+}
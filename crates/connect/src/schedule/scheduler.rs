@@ -0,0 +1,194 @@
+//! Background scheduler shared by the server and desktop apps.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{Local, NaiveDateTime};
+use log::{debug, error, info};
+
+use super::service::SyncScheduleService;
+use crate::broker::{SyncConfig, SyncResult};
+
+/// Delay before the first schedule check, so startup work settles first.
+const INITIAL_DELAY_SECS: u64 = 60;
+
+/// How often schedules are checked.
+const TICK_INTERVAL_SECS: u64 = 60;
+
+/// Runs a sync for one schedule source. Implemented by each app on top of
+/// its own broker sync entry points.
+#[async_trait]
+pub trait ScheduledSyncRunner: Send + Sync {
+    /// Returns `Ok(None)` when the source is not set up (not signed in, no
+    /// credentials stored) and the run was skipped.
+    async fn run_sync(
+        &self,
+        source_id: &str,
+        config: SyncConfig,
+    ) -> Result<Option<SyncResult>, String>;
+}
+
+/// Fires broker syncs according to the configured [`SyncSchedule`](super::SyncSchedule)s.
+pub struct BrokerSyncScheduler {
+    schedule_service: Arc<SyncScheduleService>,
+    runner: Arc<dyn ScheduledSyncRunner>,
+    last_runs: Mutex<HashMap<String, NaiveDateTime>>,
+    started_at: NaiveDateTime,
+}
+
+impl BrokerSyncScheduler {
+    pub fn new(
+        schedule_service: Arc<SyncScheduleService>,
+        runner: Arc<dyn ScheduledSyncRunner>,
+    ) -> Self {
+        Self {
+            schedule_service,
+            runner,
+            last_runs: Mutex::new(HashMap::new()),
+            started_at: Local::now().naive_local(),
+        }
+    }
+
+    /// Spawns the scheduler loop on the current tokio runtime.
+    pub fn start(self: Arc<Self>) {
+        info!(
+            "Starting broker sync scheduler (first check in {}s)",
+            INITIAL_DELAY_SECS
+        );
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(INITIAL_DELAY_SECS)).await;
+
+            let mut interval = tokio::time::interval(Duration::from_secs(TICK_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                self.run_due(Local::now().naive_local()).await;
+            }
+        });
+    }
+
+    /// Runs every schedule that is due at `now`, one at a time, and returns
+    /// the source IDs that were attempted.
+    pub async fn run_due(&self, now: NaiveDateTime) -> Vec<String> {
+        let mut attempted = Vec::new();
+
+        for schedule in self.schedule_service.list_schedules() {
+            let last_run = self
+                .last_runs
+                .lock()
+                .unwrap()
+                .get(&schedule.source_id)
+                .copied();
+            if !schedule.is_due(last_run, self.started_at, now) {
+                continue;
+            }
+
+            // Record the attempt first so a failing source waits for its next slot
+            self.last_runs
+                .lock()
+                .unwrap()
+                .insert(schedule.source_id.clone(), now);
+            attempted.push(schedule.source_id.clone());
+
+            match self
+                .runner
+                .run_sync(&schedule.source_id, schedule.sync_config())
+                .await
+            {
+                Ok(Some(result)) if result.success => {
+                    info!(
+                        "Scheduled sync for '{}' completed: {}",
+                        schedule.source_id, result.message
+                    );
+                }
+                Ok(Some(result)) => {
+                    error!(
+                        "Scheduled sync for '{}' completed with errors: {}",
+                        schedule.source_id, result.message
+                    );
+                }
+                Ok(None) => {
+                    debug!(
+                        "Scheduled sync for '{}' skipped: source not configured",
+                        schedule.source_id
+                    );
+                }
+                Err(e) => {
+                    error!("Scheduled sync for '{}' failed: {}", schedule.source_id, e);
+                }
+            }
+        }
+
+        attempted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::model::{SyncSchedule, CONNECT_SYNC_SOURCE};
+    use crate::schedule::test_support::MockSettingsRepository;
+    use async_trait::async_trait;
+    use chrono::NaiveDate;
+
+    #[derive(Default)]
+    struct RecordingRunner {
+        calls: Mutex<Vec<(String, SyncConfig)>>,
+    }
+
+    #[async_trait]
+    impl ScheduledSyncRunner for RecordingRunner {
+        async fn run_sync(
+            &self,
+            source_id: &str,
+            config: SyncConfig,
+        ) -> std::result::Result<Option<SyncResult>, String> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push((source_id.to_string(), config));
+            Ok(None)
+        }
+    }
+
+    fn at(h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 6, 30)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_run_due_respects_schedules() {
+        let schedule_service = Arc::new(SyncScheduleService::new(Arc::new(
+            MockSettingsRepository::default(),
+        )));
+        schedule_service
+            .update_schedule(SyncSchedule {
+                enabled: false,
+                ..SyncSchedule::default_for("simplefin")
+            })
+            .await
+            .unwrap();
+        schedule_service
+            .update_schedule(SyncSchedule {
+                backfill_days: Some(30),
+                ..SyncSchedule::default_for(CONNECT_SYNC_SOURCE)
+            })
+            .await
+            .unwrap();
+
+        let runner = Arc::new(RecordingRunner::default());
+        let scheduler = BrokerSyncScheduler::new(schedule_service, runner.clone());
+
+        assert_eq!(scheduler.run_due(at(1, 0)).await, vec![CONNECT_SYNC_SOURCE]);
+        // Not due again until the 4 hour interval has passed
+        assert!(scheduler.run_due(at(3, 0)).await.is_empty());
+        assert_eq!(scheduler.run_due(at(5, 0)).await, vec![CONNECT_SYNC_SOURCE]);
+
+        let calls = runner.calls.lock().unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].1.max_history_days, Some(30));
+    }
+}
@@ -0,0 +1,152 @@
+//! Persistence for broker sync schedules.
+
+use std::sync::Arc;
+
+use log::warn;
+use wealthfolio_core::errors::{Error, Result, ValidationError};
+use wealthfolio_core::settings::SettingsRepositoryTrait;
+
+use super::model::{SyncSchedule, BROKER_SYNC_SCHEDULES_KEY, CONNECT_SYNC_SOURCE};
+use crate::broker::SyncConfig;
+use crate::local::LocalConnectorKind;
+
+/// Sources that always have a schedule, configured or not.
+fn known_sources() -> Vec<&'static str> {
+    std::iter::once(CONNECT_SYNC_SOURCE)
+        .chain(LocalConnectorKind::ALL.iter().map(|kind| kind.id()))
+        .collect()
+}
+
+/// Loads and saves per-source sync schedules in app settings.
+pub struct SyncScheduleService {
+    settings_repo: Arc<dyn SettingsRepositoryTrait>,
+}
+
+impl SyncScheduleService {
+    pub fn new(settings_repo: Arc<dyn SettingsRepositoryTrait>) -> Self {
+        Self { settings_repo }
+    }
+
+    /// Stored schedules, or an empty list if missing/corrupt.
+    fn load_stored(&self) -> Vec<SyncSchedule> {
+        match self.settings_repo.get_setting(BROKER_SYNC_SCHEDULES_KEY) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable broker sync schedules: {}", e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Returns a schedule for every known source, using defaults for sources
+    /// that have not been configured.
+    pub fn list_schedules(&self) -> Vec<SyncSchedule> {
+        let stored = self.load_stored();
+        known_sources()
+            .into_iter()
+            .map(|source_id| {
+                stored
+                    .iter()
+                    .find(|s| s.source_id == source_id)
+                    .cloned()
+                    .unwrap_or_else(|| SyncSchedule::default_for(source_id))
+            })
+            .collect()
+    }
+
+    pub fn get_schedule(&self, source_id: &str) -> Result<SyncSchedule> {
+        self.list_schedules()
+            .into_iter()
+            .find(|s| s.source_id == source_id)
+            .ok_or_else(|| {
+                Error::Validation(ValidationError::InvalidInput(format!(
+                    "Unknown sync source '{}'",
+                    source_id
+                )))
+            })
+    }
+
+    /// Sync configuration for a user-triggered sync of a source. Only the
+    /// review mode carries over; the backfill limit applies to scheduled runs.
+    pub fn manual_sync_config(&self, source_id: &str) -> SyncConfig {
+        let review_mode = self
+            .get_schedule(source_id)
+            .map(|s| s.review_mode)
+            .unwrap_or_default();
+        SyncConfig {
+            review_mode,
+            ..SyncConfig::default()
+        }
+    }
+
+    /// Validates and stores the schedule for its source.
+    pub async fn update_schedule(&self, schedule: SyncSchedule) -> Result<SyncSchedule> {
+        // Rejects unknown sources
+        self.get_schedule(&schedule.source_id)?;
+        schedule.validate()?;
+
+        let mut schedules = self.list_schedules();
+        if let Some(existing) = schedules
+            .iter_mut()
+            .find(|s| s.source_id == schedule.source_id)
+        {
+            *existing = schedule.clone();
+        }
+
+        let json = serde_json::to_string(&schedules)?;
+        self.settings_repo
+            .update_setting(BROKER_SYNC_SCHEDULES_KEY, &json)
+            .await?;
+        Ok(schedule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::model::ScheduleTrigger;
+    use crate::schedule::test_support::MockSettingsRepository;
+
+    fn service() -> SyncScheduleService {
+        SyncScheduleService::new(Arc::new(MockSettingsRepository::default()))
+    }
+
+    #[test]
+    fn test_list_schedules_defaults_every_source() {
+        let schedules = service().list_schedules();
+        let ids: Vec<_> = schedules.iter().map(|s| s.source_id.as_str()).collect();
+        assert_eq!(ids, vec![CONNECT_SYNC_SOURCE, "simplefin"]);
+        assert!(schedules.iter().all(|s| s.enabled));
+    }
+
+    #[tokio::test]
+    async fn test_update_schedule_persists_one_source() {
+        let service = service();
+        let schedule = SyncSchedule {
+            enabled: false,
+            trigger: ScheduleTrigger::Cron {
+                expression: "0 6 * * *".to_string(),
+            },
+            ..SyncSchedule::default_for("simplefin")
+        };
+
+        service.update_schedule(schedule.clone()).await.unwrap();
+
+        assert_eq!(service.get_schedule("simplefin").unwrap(), schedule);
+        assert!(service.get_schedule(CONNECT_SYNC_SOURCE).unwrap().enabled);
+    }
+
+    #[tokio::test]
+    async fn test_update_schedule_rejects_invalid_input() {
+        let service = service();
+
+        let unknown = SyncSchedule::default_for("ibkr");
+        assert!(service.update_schedule(unknown).await.is_err());
+
+        let too_fast = SyncSchedule {
+            trigger: ScheduleTrigger::Interval { minutes: 5 },
+            ..SyncSchedule::default_for(CONNECT_SYNC_SOURCE)
+        };
+        assert!(service.update_schedule(too_fast).await.is_err());
+    }
+}
@@ -0,0 +1,51 @@
+//! Test helpers shared by the schedule modules.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use wealthfolio_core::errors::{Error, Result, ValidationError};
+use wealthfolio_core::settings::{Settings, SettingsRepositoryTrait, SettingsUpdate};
+
+/// In-memory settings store keyed by setting name.
+#[derive(Default)]
+pub(crate) struct MockSettingsRepository {
+    settings: RwLock<HashMap<String, String>>,
+}
+
+#[async_trait]
+impl SettingsRepositoryTrait for MockSettingsRepository {
+    fn get_settings(&self) -> Result<Settings> {
+        Ok(Settings::default())
+    }
+
+    async fn update_settings(&self, _new_settings: &SettingsUpdate) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_setting(&self, setting_key: &str) -> Result<String> {
+        self.settings
+            .read()
+            .unwrap()
+            .get(setting_key)
+            .cloned()
+            .ok_or_else(|| {
+                Error::Validation(ValidationError::InvalidInput(format!(
+                    "missing setting: {}",
+                    setting_key
+                )))
+            })
+    }
+
+    async fn update_setting(&self, setting_key: &str, setting_value: &str) -> Result<()> {
+        self.settings
+            .write()
+            .unwrap()
+            .insert(setting_key.to_string(), setting_value.to_string());
+        Ok(())
+    }
+
+    fn get_distinct_currencies_excluding_base(&self, _base_currency: &str) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+}
@@ -1022,6 +1022,9 @@ pub struct ImportActivitiesSummary {
     pub duplicates: u32,
     /// Number of new assets created during import
     pub assets_created: u32,
+    /// Number of activities held on the import run for review
+    #[serde(default)]
+    pub staged: u32,
     /// Whether the import was successful (no validation errors)
     pub success: bool,
 }
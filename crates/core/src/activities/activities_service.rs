@@ -12,7 +12,11 @@ use crate::activities::activities_constants::{
 use crate::activities::activities_errors::ActivityError;
 use crate::activities::activities_model::*;
 use crate::activities::csv_parser::{self, ParseConfig, ParsedCsvResult};
+use crate::activities::duplicates_model::{DuplicateMatchConfig, DuplicateProbe};
+use crate::activities::duplicates_service::find_best_duplicate;
 use crate::activities::idempotency::compute_idempotency_key;
+use crate::activities::staging_service::stage_rows;
+use crate::activities::staging_traits::StagedActivityRepositoryTrait;
 use crate::activities::{ActivityRepositoryTrait, ActivityServiceTrait};
 use crate::activities::{
     ImportRun, ImportRunMode, ImportRunRepositoryTrait, ImportRunSummary, ImportRunType, ReviewMode,
@@ -22,6 +26,10 @@ use crate::assets::{
     resolve_quote_ccy_precedence, AssetKind, AssetServiceTrait, InstrumentType,
     QuoteCcyResolutionSource, QuoteMode,
 };
+use crate::audit::{
+    change_source_for_system, ChangeContext, ChangeLogRepositoryTrait, ChangeSource,
+    NewChangeLogEntry,
+};
 use crate::events::{DomainEvent, DomainEventSink, NoOpDomainEventSink};
 use crate::fx::currency::{get_normalization_rule, normalize_amount, resolve_currency};
 use crate::fx::FxServiceTrait;
@@ -39,6 +47,8 @@ pub struct ActivityService {
     fx_service: Arc<dyn FxServiceTrait>,
     quote_service: Arc<dyn QuoteServiceTrait>,
     import_run_repository: Option<Arc<dyn ImportRunRepositoryTrait>>,
+    staged_activity_repository: Option<Arc<dyn StagedActivityRepositoryTrait>>,
+    change_log: Option<Arc<dyn ChangeLogRepositoryTrait>>,
     event_sink: Arc<dyn DomainEventSink>,
 }
 
@@ -162,6 +172,8 @@ impl ActivityService {
             fx_service,
             quote_service,
             import_run_repository: None,
+            staged_activity_repository: None,
+            change_log: None,
             event_sink: Arc::new(NoOpDomainEventSink),
         }
     }
@@ -182,6 +194,8 @@ impl ActivityService {
             fx_service,
             quote_service,
             import_run_repository: Some(import_run_repository),
+            staged_activity_repository: None,
+            change_log: None,
             event_sink: Arc::new(NoOpDomainEventSink),
         }
     }
@@ -194,6 +208,234 @@ impl ActivityService {
         self
     }
 
+    /// Sets the staging area used by imports that require review.
+    pub fn with_staged_activity_repository(
+        mut self,
+        staged_activity_repository: Arc<dyn StagedActivityRepositoryTrait>,
+    ) -> Self {
+        self.staged_activity_repository = Some(staged_activity_repository);
+        self
+    }
+
+    /// Sets the change log that records every activity mutation.
+    pub fn with_change_log(mut self, change_log: Arc<dyn ChangeLogRepositoryTrait>) -> Self {
+        self.change_log = Some(change_log);
+        self
+    }
+
+    /// Records imported activities, located by their idempotency keys since
+    /// the repository assigns ids on insert.
+    async fn record_import_changes(
+        &self,
+        import_run_id: &str,
+        account_ids: &[String],
+        idempotency_keys: &[String],
+    ) {
+        if self.change_log.is_none() || idempotency_keys.is_empty() {
+            return;
+        }
+        let inserted_ids: HashSet<String> = match self
+            .activity_repository
+            .check_existing_duplicates(idempotency_keys)
+        {
+            Ok(ids) => ids.into_values().collect(),
+            Err(e) => {
+                warn!(
+                    "Failed to resolve imported activities for change log: {}",
+                    e
+                );
+                return;
+            }
+        };
+        let activities = match self
+            .activity_repository
+            .get_activities_by_account_ids(account_ids)
+        {
+            Ok(activities) => activities,
+            Err(e) => {
+                warn!("Failed to load imported activities for change log: {}", e);
+                return;
+            }
+        };
+
+        let context = ChangeContext::new(ChangeSource::Import, Some(import_run_id.to_string()));
+        let changes = activities
+            .iter()
+            .filter(|a| inserted_ids.contains(&a.id))
+            .filter_map(|a| NewChangeLogEntry::activity(None, Some(a), &context))
+            .collect();
+        self.record_changes(changes).await;
+    }
+
+    /// Holds prepared import rows on the import run for review instead of
+    /// inserting them. Applied later through the staging service.
+    async fn stage_import(
+        &self,
+        staged_repository: &dyn StagedActivityRepositoryTrait,
+        mut import_run: ImportRun,
+        validated_activities: Vec<ActivityImport>,
+        activities_to_stage: Vec<NewActivity>,
+        duplicate_count: u32,
+        assets_created_count: u32,
+    ) -> Result<ImportActivitiesResult> {
+        let total_count = validated_activities.len() as u32;
+        let import_run_id = import_run.id.clone();
+        let upserts: Vec<ActivityUpsert> = activities_to_stage
+            .into_iter()
+            .map(|activity| ActivityUpsert {
+                id: activity.id.clone().unwrap_or_default(),
+                asset_id: activity.get_symbol_id().map(str::to_string),
+                account_id: activity.account_id,
+                activity_type: activity.activity_type,
+                subtype: activity.subtype,
+                activity_date: activity.activity_date,
+                quantity: activity.quantity,
+                unit_price: activity.unit_price,
+                currency: activity.currency,
+                fee: activity.fee,
+                amount: activity.amount,
+                status: activity.status,
+                notes: activity.notes,
+                fx_rate: activity.fx_rate,
+                metadata: activity.metadata,
+                needs_review: activity.needs_review,
+                source_system: activity.source_system.or(Some("CSV".to_string())),
+                source_record_id: activity.source_record_id,
+                source_group_id: activity.source_group_id,
+                idempotency_key: activity.idempotency_key,
+                import_run_id: Some(import_run_id.clone()),
+            })
+            .collect();
+
+        // Duplicates against the ledger were already removed above
+        let staged = stage_rows(
+            staged_repository,
+            &import_run_id,
+            &import_run.account_id,
+            upserts,
+            &HashSet::new(),
+        )
+        .await?;
+        let skipped = duplicate_count + (staged.previously_rejected + staged.already_known) as u32;
+
+        if let Some(ref repo) = self.import_run_repository {
+            import_run.mark_needs_review();
+            import_run.summary = Some(ImportRunSummary {
+                fetched: total_count,
+                skipped,
+                assets_created: assets_created_count,
+                ..Default::default()
+            });
+            if let Err(e) = repo.update(import_run).await {
+                warn!("Failed to update import run with review status: {}", e);
+            }
+        }
+
+        Ok(ImportActivitiesResult {
+            activities: validated_activities,
+            import_run_id,
+            summary: ImportActivitiesSummary {
+                total: total_count,
+                imported: 0,
+                skipped,
+                duplicates: duplicate_count,
+                assets_created: assets_created_count,
+                staged: staged.staged as u32,
+                success: true,
+            },
+        })
+    }
+
+    /// Loads the current state of activities that an upsert may touch, plus the
+    /// ids of existing activities matched by idempotency key.
+    #[allow(clippy::type_complexity)]
+    fn snapshot_for_upsert(
+        &self,
+        account_ids: &[String],
+        rows: &[(String, Option<String>, Option<String>)],
+    ) -> Option<(HashMap<String, Activity>, HashMap<String, String>)> {
+        let keys: Vec<String> = rows.iter().filter_map(|(_, key, _)| key.clone()).collect();
+        let snapshot = self
+            .activity_repository
+            .get_activities_by_account_ids(account_ids)
+            .and_then(|activities| {
+                let id_by_key = self.activity_repository.check_existing_duplicates(&keys)?;
+                let by_id = activities.into_iter().map(|a| (a.id.clone(), a)).collect();
+                Ok((by_id, id_by_key))
+            });
+        match snapshot {
+            Ok(snapshot) => Some(snapshot),
+            Err(e) => {
+                warn!("Failed to snapshot activities for change log: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Records creates and updates made by a broker sync upsert. Rows that
+    /// were skipped or left unchanged are not recorded.
+    async fn record_upsert_changes(
+        &self,
+        account_ids: &[String],
+        rows: &[(String, Option<String>, Option<String>)],
+        before_by_id: &HashMap<String, Activity>,
+        id_by_key: &HashMap<String, String>,
+    ) {
+        let after_by_id: HashMap<String, Activity> = match self
+            .activity_repository
+            .get_activities_by_account_ids(account_ids)
+        {
+            Ok(activities) => activities.into_iter().map(|a| (a.id.clone(), a)).collect(),
+            Err(e) => {
+                warn!("Failed to load synced activities for change log: {}", e);
+                return;
+            }
+        };
+
+        let comparable = |activity: &Activity| {
+            let mut value = serde_json::to_value(activity).unwrap_or_default();
+            if let Some(obj) = value.as_object_mut() {
+                obj.remove("updatedAt");
+            }
+            value
+        };
+
+        let mut changes = Vec::new();
+        for (row_id, key, import_run_id) in rows {
+            let id = if before_by_id.contains_key(row_id) {
+                row_id
+            } else {
+                key.as_ref()
+                    .and_then(|k| id_by_key.get(k))
+                    .unwrap_or(row_id)
+            };
+            let Some(after) = after_by_id.get(id) else {
+                continue;
+            };
+            let before = before_by_id.get(id);
+            if before.is_some_and(|b| comparable(b) == comparable(after)) {
+                continue;
+            }
+            let context = ChangeContext::new(ChangeSource::BrokerSync, import_run_id.clone());
+            changes.extend(NewChangeLogEntry::activity(before, Some(after), &context));
+        }
+        self.record_changes(changes).await;
+    }
+
+    /// Appends entries to the change log. Failures are logged rather than
+    /// failing the mutation that already succeeded.
+    async fn record_changes(&self, entries: Vec<NewChangeLogEntry>) {
+        let Some(change_log) = self.change_log.as_ref() else {
+            return;
+        };
+        if entries.is_empty() {
+            return;
+        }
+        if let Err(e) = change_log.append(entries).await {
+            warn!("Failed to record activity changes: {}", e);
+        }
+    }
+
     fn get_base_currency_or_usd(&self) -> String {
         resolve_currency(&[self
             .account_service
@@ -263,6 +505,90 @@ impl ActivityService {
         ))
     }
 
+    fn build_import_duplicate_probe(
+        activity: &ActivityImport,
+        default_account_id: &str,
+    ) -> Option<DuplicateProbe> {
+        let date = Self::parse_import_date_for_idempotency(&activity.date)?;
+        let symbol = activity.symbol.trim();
+        Some(DuplicateProbe {
+            account_id: activity
+                .account_id
+                .clone()
+                .unwrap_or_else(|| default_account_id.to_string()),
+            activity_type: activity.activity_type.clone(),
+            symbol: (!symbol.is_empty() && !symbol.starts_with("CASH:"))
+                .then(|| symbol.to_string()),
+            date: date.date_naive(),
+            quantity: activity.quantity,
+            amount: activity.amount,
+            currency: activity.currency.clone(),
+            source_system: Some("CSV".to_string()),
+            ..Default::default()
+        })
+    }
+
+    /// Flags import rows that closely resemble an existing activity of the
+    /// account without being an exact duplicate (e.g. the same trade entered
+    /// manually or synced from a broker with slightly different values).
+    fn flag_possible_duplicates(&self, account_id: &str, activities: &mut [ActivityImport]) {
+        let existing = match self
+            .activity_repository
+            .get_activities_by_account_id(account_id)
+        {
+            Ok(existing) if !existing.is_empty() => existing,
+            Ok(_) => return,
+            Err(e) => {
+                warn!("Skipping fuzzy duplicate check for {}: {}", account_id, e);
+                return;
+            }
+        };
+
+        let mut symbols: HashMap<String, Option<String>> = HashMap::new();
+        let existing_probes: Vec<DuplicateProbe> = existing
+            .iter()
+            .map(|a| {
+                let symbol = a.asset_id.as_ref().and_then(|asset_id| {
+                    symbols
+                        .entry(asset_id.clone())
+                        .or_insert_with(|| {
+                            self.asset_service
+                                .get_asset_by_id(asset_id)
+                                .ok()
+                                .and_then(|asset| asset.instrument_symbol.or(asset.display_code))
+                        })
+                        .clone()
+                });
+                DuplicateProbe::from_activity(a, symbol)
+            })
+            .collect();
+        let config = DuplicateMatchConfig::default();
+
+        for activity in activities.iter_mut() {
+            if !activity.is_valid
+                || activity.duplicate_of_id.is_some()
+                || activity.duplicate_of_line_number.is_some()
+            {
+                continue;
+            }
+            let Some(probe) = Self::build_import_duplicate_probe(activity, account_id) else {
+                continue;
+            };
+            if let Some((idx, m)) = find_best_duplicate(&probe, &existing_probes, &config) {
+                let matched = &existing[idx];
+                let mut message = format!(
+                    "Possible duplicate of {} activity on {}",
+                    matched.activity_type,
+                    matched.effective_date()
+                );
+                if !m.reasons.is_empty() {
+                    message.push_str(&format!(" ({})", m.reasons.join(", ")));
+                }
+                Self::add_activity_warning(activity, "_possible_duplicate", &message);
+            }
+        }
+    }
+
     fn add_activity_warning(activity: &mut ActivityImport, key: &str, message: &str) {
         let warnings = activity.warnings.get_or_insert_with(HashMap::new);
         let entry = warnings.entry(key.to_string()).or_default();
@@ -1409,6 +1735,17 @@ impl ActivityServiceTrait for ActivityService {
         let prepared = self.prepare_new_activity(activity).await?;
         let created = self.activity_repository.create_activity(prepared).await?;
 
+        let context = ChangeContext::new(
+            change_source_for_system(created.source_system.as_deref()),
+            None,
+        );
+        self.record_changes(
+            NewChangeLogEntry::activity(None, Some(&created), &context)
+                .into_iter()
+                .collect(),
+        )
+        .await;
+
         // Emit domain event after successful creation
         let account_ids = vec![created.account_id.clone()];
         let asset_ids = created.asset_id.clone().into_iter().collect();
@@ -1431,6 +1768,13 @@ impl ActivityServiceTrait for ActivityService {
         let prepared = self.prepare_update_activity(activity).await?;
         let updated = self.activity_repository.update_activity(prepared).await?;
 
+        self.record_changes(
+            NewChangeLogEntry::activity(Some(&existing), Some(&updated), &ChangeContext::manual())
+                .into_iter()
+                .collect(),
+        )
+        .await;
+
         // Emit domain event after successful update
         // Include BOTH old and new account_ids and asset_ids (if they differ)
         let mut account_ids_set: HashSet<String> = HashSet::new();
@@ -1470,6 +1814,13 @@ impl ActivityServiceTrait for ActivityService {
             .delete_activity(activity_id)
             .await?;
 
+        self.record_changes(
+            NewChangeLogEntry::activity(Some(&deleted), None, &ChangeContext::manual())
+                .into_iter()
+                .collect(),
+        )
+        .await;
+
         // Emit domain event after successful deletion
         let account_ids = vec![deleted.account_id.clone()];
         let asset_ids = deleted.asset_id.clone().into_iter().collect();
@@ -1497,6 +1848,8 @@ impl ActivityServiceTrait for ActivityService {
         let mut old_account_ids: HashSet<String> = HashSet::new();
         let mut old_asset_ids: HashSet<String> = HashSet::new();
         let mut old_currencies: HashSet<String> = HashSet::new();
+        // Previous state of updated/deleted activities, for the change log
+        let mut before_by_id: HashMap<String, Activity> = HashMap::new();
 
         // Use prepare_activities for all creates at once
         if !request.creates.is_empty() {
@@ -1586,6 +1939,7 @@ impl ActivityServiceTrait for ActivityService {
                         old_asset_ids.insert(asset_id.clone());
                     }
                     old_currencies.insert(existing.currency.clone());
+                    before_by_id.insert(existing.id.clone(), existing);
                 }
                 Err(_) => {
                     // Activity doesn't exist - will fail during prepare_update_activity
@@ -1614,6 +1968,7 @@ impl ActivityServiceTrait for ActivityService {
                     }
                     old_currencies.insert(existing.currency.clone());
                     valid_delete_ids.push(delete_id.clone());
+                    before_by_id.insert(existing.id.clone(), existing);
                 }
                 Err(err) => {
                     errors.push(ActivityBulkMutationError {
@@ -1640,6 +1995,28 @@ impl ActivityServiceTrait for ActivityService {
 
         persisted.errors = errors;
 
+        let manual = ChangeContext::manual();
+        let mut changes: Vec<NewChangeLogEntry> = Vec::new();
+        for activity in &persisted.created {
+            let context = ChangeContext::new(
+                change_source_for_system(activity.source_system.as_deref()),
+                None,
+            );
+            changes.extend(NewChangeLogEntry::activity(None, Some(activity), &context));
+        }
+        for activity in &persisted.updated {
+            changes.extend(NewChangeLogEntry::activity(
+                before_by_id.get(&activity.id),
+                Some(activity),
+                &manual,
+            ));
+        }
+        for activity in &persisted.deleted {
+            let before = before_by_id.get(&activity.id).unwrap_or(activity);
+            changes.extend(NewChangeLogEntry::activity(Some(before), None, &manual));
+        }
+        self.record_changes(changes).await;
+
         // Emit ONE aggregated domain event for all mutations
         // Start with OLD values captured before updates/deletes (to recalculate old locations)
         let mut account_ids_set: HashSet<String> = old_account_ids;
@@ -2043,6 +2420,8 @@ impl ActivityServiceTrait for ActivityService {
             }
         }
 
+        self.flag_possible_duplicates(&account_id, &mut activities_with_status);
+
         Ok(activities_with_status)
     }
 
@@ -2051,6 +2430,7 @@ impl ActivityServiceTrait for ActivityService {
         &self,
         account_id: String,
         activities: Vec<ActivityImport>,
+        review_mode: ReviewMode,
     ) -> Result<ImportActivitiesResult> {
         let account = self.account_service.get_account(&account_id)?;
         let total_count = activities.len() as u32;
@@ -2061,7 +2441,7 @@ impl ActivityServiceTrait for ActivityService {
             "CSV".to_string(),
             ImportRunType::Import,
             ImportRunMode::Initial,
-            ReviewMode::Never,
+            review_mode.clone(),
         );
         let import_run_id = import_run.id.clone();
 
@@ -2116,6 +2496,7 @@ impl ActivityServiceTrait for ActivityService {
                     skipped: skipped_count,
                     duplicates: 0,
                     assets_created: 0,
+                    staged: 0,
                     success: false,
                 },
             });
@@ -2182,6 +2563,7 @@ impl ActivityServiceTrait for ActivityService {
                     skipped: skipped_count,
                     duplicates: 0,
                     assets_created: prepare_result.assets_created,
+                    staged: 0,
                     success: false,
                 },
             });
@@ -2330,6 +2712,29 @@ impl ActivityServiceTrait for ActivityService {
             }
         }
 
+        let needs_review = match review_mode {
+            ReviewMode::Never => false,
+            ReviewMode::Always => true,
+            ReviewMode::IfWarnings => validated_activities
+                .iter()
+                .any(|a| a.warnings.as_ref().is_some_and(|w| !w.is_empty())),
+        };
+        if needs_review {
+            if let Some(staged_repository) = self.staged_activity_repository.clone() {
+                return self
+                    .stage_import(
+                        staged_repository.as_ref(),
+                        import_run,
+                        validated_activities,
+                        activities_to_insert,
+                        duplicate_count,
+                        assets_created_count,
+                    )
+                    .await;
+            }
+            warn!("Import review requested but no staging area is configured; importing directly");
+        }
+
         // Collect unique asset_ids and currencies before consuming activities
         let asset_ids: Vec<String> = activities_to_insert
             .iter()
@@ -2344,12 +2749,28 @@ impl ActivityServiceTrait for ActivityService {
             .into_iter()
             .collect();
 
+        let inserted_keys: Vec<String> = activities_to_insert
+            .iter()
+            .filter_map(|a| a.idempotency_key.clone())
+            .collect();
+        let inserted_account_ids: Vec<String> = activities_to_insert
+            .iter()
+            .map(|a| a.account_id.clone())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+
         let count = self
             .activity_repository
             .create_activities(activities_to_insert)
             .await?;
         debug!("Successfully imported {} activities", count);
 
+        if count > 0 {
+            self.record_import_changes(&import_run_id, &inserted_account_ids, &inserted_keys)
+                .await;
+        }
+
         // Emit domain event after successful import
         if count > 0 {
             self.event_sink.emit(DomainEvent::activities_changed(
@@ -2387,6 +2808,7 @@ impl ActivityServiceTrait for ActivityService {
                 skipped: duplicate_count,
                 duplicates: duplicate_count,
                 assets_created: assets_created_count,
+                staged: 0,
                 success: true,
             },
         })
@@ -2478,9 +2900,31 @@ impl ActivityServiceTrait for ActivityService {
             .into_iter()
             .collect();
 
+        // Snapshot affected activities so the change log can record what the sync changed
+        let change_rows: Vec<(String, Option<String>, Option<String>)> = activities
+            .iter()
+            .map(|a| {
+                (
+                    a.id.clone(),
+                    a.idempotency_key.clone(),
+                    a.import_run_id.clone(),
+                )
+            })
+            .collect();
+        let before = if self.change_log.is_some() {
+            self.snapshot_for_upsert(&account_ids, &change_rows)
+        } else {
+            None
+        };
+
         // Perform the upsert via repository
         let result = self.activity_repository.bulk_upsert(activities).await?;
 
+        if let Some((before_by_id, id_by_key)) = before {
+            self.record_upsert_changes(&account_ids, &change_rows, &before_by_id, &id_by_key)
+                .await;
+        }
+
         // Emit single aggregated event if any activities were affected
         if result.upserted > 0 {
             self.event_sink.emit(DomainEvent::activities_changed(
@@ -585,8 +585,15 @@ mod tests {
             Ok(self.activities.lock().unwrap().clone())
         }
 
-        fn get_activities_by_account_id(&self, _account_id: &str) -> Result<Vec<Activity>> {
-            unimplemented!()
+        fn get_activities_by_account_id(&self, account_id: &str) -> Result<Vec<Activity>> {
+            Ok(self
+                .activities
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|a| a.account_id == account_id)
+                .cloned()
+                .collect())
         }
 
         fn get_activities_by_account_ids(&self, _account_ids: &[String]) -> Result<Vec<Activity>> {
@@ -1884,6 +1891,111 @@ mod tests {
         assert_eq!(checked.quote_ccy.as_deref(), Some("GBp"));
     }
 
+    #[tokio::test]
+    async fn test_check_import_flags_possible_duplicate_of_synced_activity() {
+        let account_service = Arc::new(MockAccountService::new());
+        let asset_service = Arc::new(MockAssetService::new());
+        let fx_service = Arc::new(MockFxService::new());
+        let activity_repository = Arc::new(MockActivityRepository::new());
+
+        account_service.add_account(create_test_account("acc-1", "GBP"));
+        asset_service.add_asset(create_test_asset_with_instrument(
+            "azn-uuid",
+            "AZN",
+            Some("XLON"),
+            Some(InstrumentType::Equity),
+            "GBp",
+        ));
+
+        // Same trade already synced from a broker, settled a day later with a rounded amount
+        let synced_date = DateTime::parse_from_rfc3339("2024-01-16T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        activity_repository
+            .activities
+            .lock()
+            .unwrap()
+            .push(Activity {
+                id: "broker-1".to_string(),
+                account_id: "acc-1".to_string(),
+                asset_id: Some("azn-uuid".to_string()),
+                activity_type: "BUY".to_string(),
+                activity_type_override: None,
+                source_type: None,
+                subtype: None,
+                status: ActivityStatus::Posted,
+                activity_date: synced_date,
+                settlement_date: None,
+                quantity: Some(dec!(10)),
+                unit_price: Some(dec!(120.1)),
+                amount: Some(dec!(1201)),
+                fee: None,
+                currency: "GBP".to_string(),
+                fx_rate: None,
+                notes: None,
+                metadata: None,
+                source_system: Some("SNAPTRADE".to_string()),
+                source_record_id: Some("trade-1".to_string()),
+                source_group_id: None,
+                idempotency_key: None,
+                import_run_id: None,
+                is_user_modified: false,
+                needs_review: false,
+                created_at: synced_date,
+                updated_at: synced_date,
+            });
+
+        let quote_service = Arc::new(MockQuoteService::default());
+        let activity_service = ActivityService::new(
+            activity_repository,
+            account_service,
+            asset_service,
+            fx_service,
+            quote_service,
+        );
+
+        let import = ActivityImport {
+            id: None,
+            date: "2024-01-15".to_string(),
+            symbol: "AZN".to_string(),
+            activity_type: "BUY".to_string(),
+            quantity: Some(dec!(10)),
+            unit_price: Some(dec!(120)),
+            currency: "GBP".to_string(),
+            fee: Some(dec!(0)),
+            amount: Some(dec!(1200)),
+            comment: None,
+            account_id: Some("acc-1".to_string()),
+            account_name: None,
+            symbol_name: None,
+            exchange_mic: Some("XLON".to_string()),
+            quote_ccy: None,
+            instrument_type: None,
+            quote_mode: None,
+            errors: None,
+            warnings: None,
+            duplicate_of_id: None,
+            duplicate_of_line_number: None,
+            is_draft: false,
+            is_valid: true,
+            line_number: Some(1),
+            fx_rate: None,
+            subtype: None,
+        };
+
+        let result = activity_service
+            .check_activities_import("acc-1".to_string(), vec![import])
+            .await
+            .expect("import check should succeed");
+
+        let warnings = result[0].warnings.as_ref().expect("warnings present");
+        let messages = warnings
+            .get("_possible_duplicate")
+            .expect("possible duplicate warning");
+        assert!(messages[0].contains("1 day(s) apart"));
+        assert!(result[0].duplicate_of_id.is_none());
+    }
+
     #[tokio::test]
     async fn test_create_activity_creates_broker_fallback_quote_for_market_mode_asset() {
         let account_service = Arc::new(MockAccountService::new());
@@ -144,10 +144,13 @@ pub trait ActivityServiceTrait: Send + Sync {
         account_id: String,
         activities: Vec<ActivityImport>,
     ) -> Result<Vec<ActivityImport>>;
+    /// Imports validated activities. With a review mode other than `Never`,
+    /// rows are staged on the import run for approval instead of inserted.
     async fn import_activities(
         &self,
         account_id: String,
         activities: Vec<ActivityImport>,
+        review_mode: super::ReviewMode,
     ) -> Result<ImportActivitiesResult>;
     async fn save_import_mapping(
         &self,
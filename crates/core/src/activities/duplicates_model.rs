@@ -0,0 +1,149 @@
+//! Domain models for fuzzy duplicate detection between activities.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use super::activities_model::Activity;
+
+/// Tunable thresholds used when scoring activity pairs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateMatchConfig {
+    /// Maximum number of days between two activity dates
+    pub date_window_days: i64,
+    /// Maximum relative difference between quantities (0.01 = 1%)
+    pub quantity_tolerance: Decimal,
+    /// Maximum relative difference between amounts (0.01 = 1%)
+    pub amount_tolerance: Decimal,
+    /// Minimum score (0..1) for a pair to be reported as a candidate
+    pub min_score: Decimal,
+}
+
+impl Default for DuplicateMatchConfig {
+    fn default() -> Self {
+        Self {
+            date_window_days: 3,
+            quantity_tolerance: dec!(0.01),
+            amount_tolerance: dec!(0.02),
+            min_score: dec!(0.7),
+        }
+    }
+}
+
+/// Normalized view of an activity used for pairwise comparison.
+///
+/// Built either from a persisted [`Activity`] or from an import row, so that
+/// import preview, broker ingest and the review queue share a single scorer.
+#[derive(Debug, Clone, Default)]
+pub struct DuplicateProbe {
+    /// Persisted activity ID (None for import rows)
+    pub activity_id: Option<String>,
+    pub account_id: String,
+    pub activity_type: String,
+    pub asset_id: Option<String>,
+    /// Ticker used when asset IDs are not comparable (e.g. unresolved import rows)
+    pub symbol: Option<String>,
+    pub date: NaiveDate,
+    pub quantity: Option<Decimal>,
+    pub amount: Option<Decimal>,
+    pub currency: String,
+    pub source_system: Option<String>,
+    pub source_record_id: Option<String>,
+    pub idempotency_key: Option<String>,
+}
+
+impl DuplicateProbe {
+    /// Builds a probe from a persisted activity, with an optional resolved ticker.
+    pub fn from_activity(activity: &Activity, symbol: Option<String>) -> Self {
+        Self {
+            activity_id: Some(activity.id.clone()),
+            account_id: activity.account_id.clone(),
+            activity_type: activity.effective_type().to_string(),
+            asset_id: activity.asset_id.clone(),
+            symbol,
+            date: activity.effective_date(),
+            quantity: activity.quantity,
+            amount: activity.amount,
+            currency: activity.currency.clone(),
+            source_system: activity.source_system.clone(),
+            source_record_id: activity.source_record_id.clone(),
+            idempotency_key: activity.idempotency_key.clone(),
+        }
+    }
+}
+
+/// Result of scoring one pair of activities.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateMatch {
+    /// Similarity score between 0 and 1
+    pub score: Decimal,
+    /// Human-readable notes on how the pair differs
+    pub reasons: Vec<String>,
+}
+
+/// A pair of persisted activities that look like the same real-world event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateCandidate {
+    /// Order-independent key identifying the pair
+    pub pair_key: String,
+    pub activity: Activity,
+    pub other_activity: Activity,
+    pub score: Decimal,
+    pub reasons: Vec<String>,
+    /// Activity the review queue suggests keeping when merging
+    pub suggested_keep_id: String,
+}
+
+/// User decision on a duplicate candidate pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DuplicateDecision {
+    /// Keep one activity and delete the other
+    Merge,
+    /// Both activities are legitimate; stop flagging the pair
+    KeepBoth,
+}
+
+impl DuplicateDecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DuplicateDecision::Merge => "MERGE",
+            DuplicateDecision::KeepBoth => "KEEP_BOTH",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "MERGE" => Some(DuplicateDecision::Merge),
+            "KEEP_BOTH" => Some(DuplicateDecision::KeepBoth),
+            _ => None,
+        }
+    }
+}
+
+/// Persisted decision for a candidate pair.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicatePairDecision {
+    pub pair_key: String,
+    pub activity_id: String,
+    pub other_activity_id: String,
+    pub decision: DuplicateDecision,
+    pub decided_at: DateTime<Utc>,
+}
+
+/// Request to resolve a candidate pair from the review queue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateResolution {
+    pub activity_id: String,
+    pub other_activity_id: String,
+    pub decision: DuplicateDecision,
+    /// Activity whose values are kept on merge (defaults to the suggested one)
+    #[serde(default)]
+    pub keep_activity_id: Option<String>,
+}
@@ -0,0 +1,444 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use log::debug;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use super::activities_model::{Activity, ActivityUpdate, SymbolInput};
+use super::activities_traits::ActivityServiceTrait;
+use super::duplicates_model::*;
+use super::duplicates_traits::{ActivityDuplicateServiceTrait, DuplicateDecisionRepositoryTrait};
+use crate::errors::{Error, Result, ValidationError};
+
+/// Weight of date proximity in the final score; the remainder goes to values.
+const DATE_WEIGHT: Decimal = dec!(0.4);
+const VALUE_WEIGHT: Decimal = dec!(0.6);
+
+/// Returns an order-independent key for a pair of activity IDs.
+pub fn duplicate_pair_key(activity_id: &str, other_activity_id: &str) -> String {
+    if activity_id <= other_activity_id {
+        format!("{}|{}", activity_id, other_activity_id)
+    } else {
+        format!("{}|{}", other_activity_id, activity_id)
+    }
+}
+
+fn same_text(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+/// Two records carrying different provider IDs from the same provider are
+/// distinct events, however similar they look.
+fn is_distinct_provider_record(a: &DuplicateProbe, b: &DuplicateProbe) -> bool {
+    match (
+        a.source_system.as_deref(),
+        b.source_system.as_deref(),
+        a.source_record_id.as_deref(),
+        b.source_record_id.as_deref(),
+    ) {
+        (Some(sa), Some(sb), Some(ra), Some(rb)) => same_text(sa, sb) && ra != rb,
+        _ => false,
+    }
+}
+
+fn assets_match(a: &DuplicateProbe, b: &DuplicateProbe) -> bool {
+    if let (Some(aa), Some(ab)) = (a.asset_id.as_deref(), b.asset_id.as_deref()) {
+        if aa == ab {
+            return true;
+        }
+    }
+    match (a.symbol.as_deref(), b.symbol.as_deref()) {
+        (Some(sa), Some(sb)) => same_text(sa, sb),
+        _ => {
+            let a_has_asset = a.asset_id.is_some() || a.symbol.is_some();
+            let b_has_asset = b.asset_id.is_some() || b.symbol.is_some();
+            // Cash activities carry no asset on either side
+            !a_has_asset && !b_has_asset
+        }
+    }
+}
+
+/// Compares two values by magnitude. Returns `None` when outside tolerance,
+/// `Some(None)` when the values are not comparable, and otherwise the
+/// relative difference.
+fn relative_difference(
+    a: Option<Decimal>,
+    b: Option<Decimal>,
+    tolerance: Decimal,
+) -> Option<Option<Decimal>> {
+    let (Some(a), Some(b)) = (a, b) else {
+        return Some(None);
+    };
+    let (a, b) = (a.abs(), b.abs());
+    let max = a.max(b);
+    if max.is_zero() {
+        return Some(None);
+    }
+    let diff = (a - b).abs() / max;
+    if diff > tolerance {
+        None
+    } else {
+        Some(Some(diff))
+    }
+}
+
+fn closeness(diff: Decimal, tolerance: Decimal) -> Decimal {
+    if diff.is_zero() || tolerance.is_zero() {
+        Decimal::ONE
+    } else {
+        Decimal::ONE - (diff / tolerance) * dec!(0.5)
+    }
+}
+
+/// Scores how likely two activities describe the same real-world event.
+///
+/// Account, activity type, currency and asset must agree; the dates must fall
+/// within the configured window and every comparable value (quantity, amount)
+/// must be within tolerance. Returns `None` when the pair is not a candidate.
+pub fn score_duplicate_pair(
+    a: &DuplicateProbe,
+    b: &DuplicateProbe,
+    config: &DuplicateMatchConfig,
+) -> Option<DuplicateMatch> {
+    if a.activity_id.is_some() && a.activity_id == b.activity_id {
+        return None;
+    }
+    if a.account_id != b.account_id
+        || !same_text(&a.activity_type, &b.activity_type)
+        || !same_text(&a.currency, &b.currency)
+        || is_distinct_provider_record(a, b)
+        || !assets_match(a, b)
+    {
+        return None;
+    }
+
+    let days = (a.date - b.date).num_days().abs();
+    if days > config.date_window_days {
+        return None;
+    }
+    let date_score =
+        Decimal::ONE - Decimal::from(days) / Decimal::from(config.date_window_days + 1);
+
+    let mut reasons = Vec::new();
+    if days > 0 {
+        reasons.push(format!("Dates are {} day(s) apart", days));
+    }
+
+    let mut value_scores = Vec::new();
+    for (label, va, vb, tolerance) in [
+        (
+            "Quantity",
+            a.quantity,
+            b.quantity,
+            config.quantity_tolerance,
+        ),
+        ("Amount", a.amount, b.amount, config.amount_tolerance),
+    ] {
+        let diff = relative_difference(va, vb, tolerance)?;
+        if let Some(diff) = diff {
+            if !diff.is_zero() {
+                reasons.push(format!(
+                    "{} differs by {}%",
+                    label,
+                    (diff * dec!(100)).round_dp(2).normalize()
+                ));
+            }
+            value_scores.push(closeness(diff, tolerance));
+        }
+    }
+    if value_scores.is_empty() {
+        return None;
+    }
+    let value_score =
+        value_scores.iter().copied().sum::<Decimal>() / Decimal::from(value_scores.len());
+
+    if let (Some(sa), Some(sb)) = (a.source_system.as_deref(), b.source_system.as_deref()) {
+        if !same_text(sa, sb) {
+            reasons.push(format!("Recorded by both {} and {}", sa, sb));
+        }
+    }
+
+    let score = (DATE_WEIGHT * date_score + VALUE_WEIGHT * value_score).round_dp(4);
+    if score < config.min_score {
+        return None;
+    }
+    Some(DuplicateMatch { score, reasons })
+}
+
+/// Returns the index and match of the highest-scoring candidate for `probe`.
+pub fn find_best_duplicate(
+    probe: &DuplicateProbe,
+    candidates: &[DuplicateProbe],
+    config: &DuplicateMatchConfig,
+) -> Option<(usize, DuplicateMatch)> {
+    candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, candidate)| {
+            score_duplicate_pair(probe, candidate, config).map(|m| (idx, m))
+        })
+        .max_by(|(_, a), (_, b)| a.score.cmp(&b.score))
+}
+
+/// Finds all candidate pairs among `probes`, returned as index pairs.
+///
+/// Probes are compared within a sliding date window, so the scan stays close
+/// to linear for typical activity histories.
+pub fn find_duplicate_pairs(
+    probes: &[DuplicateProbe],
+    config: &DuplicateMatchConfig,
+) -> Vec<(usize, usize, DuplicateMatch)> {
+    let mut order: Vec<usize> = (0..probes.len()).collect();
+    order.sort_by_key(|&idx| probes[idx].date);
+
+    let mut pairs = Vec::new();
+    for (pos, &i) in order.iter().enumerate() {
+        for &j in &order[pos + 1..] {
+            if (probes[j].date - probes[i].date).num_days() > config.date_window_days {
+                break;
+            }
+            if let Some(m) = score_duplicate_pair(&probes[i], &probes[j], config) {
+                pairs.push((i, j, m));
+            }
+        }
+    }
+    pairs
+}
+
+/// Picks the activity to keep when merging: a broker-synced record wins
+/// (deleting it would only bring it back on the next sync), then the older one.
+pub fn suggested_keep_id(activity: &Activity, other: &Activity) -> String {
+    let synced = |a: &Activity| a.source_record_id.is_some();
+    match (synced(activity), synced(other)) {
+        (true, false) => activity.id.clone(),
+        (false, true) => other.id.clone(),
+        _ if other.created_at < activity.created_at => other.id.clone(),
+        _ => activity.id.clone(),
+    }
+}
+
+/// Service for fuzzy duplicate detection and the duplicate review queue.
+pub struct ActivityDuplicateService {
+    activity_service: Arc<dyn ActivityServiceTrait>,
+    decision_repository: Arc<dyn DuplicateDecisionRepositoryTrait>,
+    config: DuplicateMatchConfig,
+}
+
+impl ActivityDuplicateService {
+    pub fn new(
+        activity_service: Arc<dyn ActivityServiceTrait>,
+        decision_repository: Arc<dyn DuplicateDecisionRepositoryTrait>,
+    ) -> Self {
+        Self {
+            activity_service,
+            decision_repository,
+            config: DuplicateMatchConfig::default(),
+        }
+    }
+
+    /// Overrides the default matching thresholds.
+    pub fn with_config(mut self, config: DuplicateMatchConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    fn decided_pair_keys(&self) -> Result<HashSet<String>> {
+        Ok(self
+            .decision_repository
+            .get_decisions()?
+            .into_iter()
+            .map(|d| d.pair_key)
+            .collect())
+    }
+}
+
+/// Update that gives the synced activity the values of the manual one while
+/// keeping the synced row's identity.
+fn merged_update(synced: &Activity, manual: &Activity) -> ActivityUpdate {
+    ActivityUpdate {
+        id: synced.id.clone(),
+        account_id: synced.account_id.clone(),
+        symbol: manual.asset_id.as_ref().map(|asset_id| SymbolInput {
+            id: Some(asset_id.clone()),
+            ..Default::default()
+        }),
+        activity_type: manual.activity_type.clone(),
+        subtype: manual.subtype.clone(),
+        activity_date: manual.activity_date.to_rfc3339(),
+        quantity: Some(manual.quantity),
+        unit_price: Some(manual.unit_price),
+        currency: manual.currency.clone(),
+        fee: Some(manual.fee),
+        amount: Some(manual.amount),
+        status: Some(manual.status.clone()),
+        notes: manual.notes.clone().or_else(|| synced.notes.clone()),
+        fx_rate: Some(manual.fx_rate),
+        metadata: manual
+            .metadata
+            .as_ref()
+            .or(synced.metadata.as_ref())
+            .map(|m| m.to_string()),
+    }
+}
+
+#[async_trait]
+impl ActivityDuplicateServiceTrait for ActivityDuplicateService {
+    fn list_candidates(&self, account_id: Option<&str>) -> Result<Vec<DuplicateCandidate>> {
+        let activities = match account_id {
+            Some(id) => self.activity_service.get_activities_by_account_id(id)?,
+            None => self.activity_service.get_activities()?,
+        };
+        let decided = self.decided_pair_keys()?;
+
+        let mut by_account: HashMap<&str, Vec<&Activity>> = HashMap::new();
+        for activity in &activities {
+            by_account
+                .entry(activity.account_id.as_str())
+                .or_default()
+                .push(activity);
+        }
+
+        let mut candidates = Vec::new();
+        for account_activities in by_account.values() {
+            let probes: Vec<DuplicateProbe> = account_activities
+                .iter()
+                .map(|a| DuplicateProbe::from_activity(a, None))
+                .collect();
+            for (i, j, m) in find_duplicate_pairs(&probes, &self.config) {
+                let (activity, other) = (account_activities[i], account_activities[j]);
+                let pair_key = duplicate_pair_key(&activity.id, &other.id);
+                if decided.contains(&pair_key) {
+                    continue;
+                }
+                candidates.push(DuplicateCandidate {
+                    pair_key,
+                    suggested_keep_id: suggested_keep_id(activity, other),
+                    activity: activity.clone(),
+                    other_activity: other.clone(),
+                    score: m.score,
+                    reasons: m.reasons,
+                });
+            }
+        }
+
+        candidates.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| b.activity.activity_date.cmp(&a.activity.activity_date))
+        });
+        Ok(candidates)
+    }
+
+    fn find_matches(
+        &self,
+        account_id: &str,
+        probes: &[DuplicateProbe],
+    ) -> Result<Vec<Option<(String, DuplicateMatch)>>> {
+        if probes.is_empty() {
+            return Ok(Vec::new());
+        }
+        let existing = self
+            .activity_service
+            .get_activities_by_account_id(account_id)?;
+        let existing_probes: Vec<DuplicateProbe> = existing
+            .iter()
+            .map(|a| DuplicateProbe::from_activity(a, None))
+            .collect();
+        let decided = self.decided_pair_keys()?;
+
+        Ok(probes
+            .iter()
+            .map(|probe| {
+                existing_probes
+                    .iter()
+                    .filter(|candidate| {
+                        // Exact duplicates are already deduplicated by idempotency key
+                        probe.idempotency_key.is_none()
+                            || candidate.idempotency_key != probe.idempotency_key
+                    })
+                    .filter(
+                        |candidate| match (&probe.activity_id, &candidate.activity_id) {
+                            (Some(a), Some(b)) => !decided.contains(&duplicate_pair_key(a, b)),
+                            _ => true,
+                        },
+                    )
+                    .filter_map(|candidate| {
+                        score_duplicate_pair(probe, candidate, &self.config)
+                            .map(|m| (candidate.activity_id.clone().unwrap_or_default(), m))
+                    })
+                    .max_by(|(_, a), (_, b)| a.score.cmp(&b.score))
+            })
+            .collect())
+    }
+
+    async fn resolve(&self, resolution: DuplicateResolution) -> Result<DuplicatePairDecision> {
+        if resolution.activity_id == resolution.other_activity_id {
+            return Err(Error::Validation(ValidationError::InvalidInput(
+                "A duplicate pair needs two different activities".to_string(),
+            )));
+        }
+        let activity = self
+            .activity_service
+            .get_activity(&resolution.activity_id)?;
+        let other = self
+            .activity_service
+            .get_activity(&resolution.other_activity_id)?;
+
+        if resolution.decision == DuplicateDecision::Merge {
+            let keep_id = resolution
+                .keep_activity_id
+                .clone()
+                .unwrap_or_else(|| suggested_keep_id(&activity, &other));
+            let (keep, remove) = if keep_id == activity.id {
+                (&activity, &other)
+            } else if keep_id == other.id {
+                (&other, &activity)
+            } else {
+                return Err(Error::Validation(ValidationError::InvalidInput(format!(
+                    "Activity {} is not part of the duplicate pair",
+                    keep_id
+                ))));
+            };
+
+            let remove_id = if remove.source_record_id.is_none() {
+                remove.id.clone()
+            } else if keep.source_record_id.is_none() {
+                // Deleting the synced row would bring it back on the next broker
+                // sync. Keep it, carrying over the manual row's values; the
+                // update marks it user-modified so sync leaves them alone.
+                debug!(
+                    "Merging duplicate activities: moving {} onto synced {}",
+                    keep.id, remove.id
+                );
+                self.activity_service
+                    .update_activity(merged_update(remove, keep))
+                    .await?;
+                keep.id.clone()
+            } else {
+                return Err(Error::Validation(ValidationError::InvalidInput(
+                    "Both activities come from a broker sync; keep both or delete one at the broker"
+                        .to_string(),
+                )));
+            };
+            debug!(
+                "Merging duplicate activities: keeping {}, deleting {}",
+                keep_id, remove_id
+            );
+            self.activity_service.delete_activity(remove_id).await?;
+        }
+
+        let decision = DuplicatePairDecision {
+            pair_key: duplicate_pair_key(&activity.id, &other.id),
+            activity_id: activity.id,
+            other_activity_id: other.id,
+            decision: resolution.decision,
+            decided_at: Utc::now(),
+        };
+        self.decision_repository
+            .save_decision(decision.clone())
+            .await?;
+        Ok(decision)
+    }
+}
@@ -0,0 +1,557 @@
+#[cfg(test)]
+mod tests {
+    use crate::activities::activities_model::*;
+    use crate::activities::{
+        duplicate_pair_key, find_duplicate_pairs, score_duplicate_pair, suggested_keep_id,
+        ActivityDuplicateService, ActivityDuplicateServiceTrait, ActivityServiceTrait,
+        DuplicateDecision, DuplicateDecisionRepositoryTrait, DuplicateMatchConfig,
+        DuplicatePairDecision, DuplicateProbe, DuplicateResolution, ParseConfig, ParsedCsvResult,
+    };
+    use crate::errors::{Error, Result};
+    use async_trait::async_trait;
+    use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    // --- Mock ActivityService ---
+    #[derive(Default)]
+    struct MockActivityService {
+        activities: Mutex<Vec<Activity>>,
+    }
+
+    #[async_trait]
+    impl ActivityServiceTrait for MockActivityService {
+        fn get_activity(&self, activity_id: &str) -> Result<Activity> {
+            self.activities
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|a| a.id == activity_id)
+                .cloned()
+                .ok_or_else(|| Error::Unexpected("Activity not found".to_string()))
+        }
+        fn get_activities(&self) -> Result<Vec<Activity>> {
+            Ok(self.activities.lock().unwrap().clone())
+        }
+        fn get_activities_by_account_id(&self, account_id: &str) -> Result<Vec<Activity>> {
+            Ok(self
+                .activities
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|a| a.account_id == account_id)
+                .cloned()
+                .collect())
+        }
+        fn get_activities_by_account_ids(&self, _account_ids: &[String]) -> Result<Vec<Activity>> {
+            unimplemented!()
+        }
+        fn get_trading_activities(&self) -> Result<Vec<Activity>> {
+            unimplemented!()
+        }
+        fn get_income_activities(&self) -> Result<Vec<Activity>> {
+            unimplemented!()
+        }
+        fn search_activities(
+            &self,
+            _page: i64,
+            _page_size: i64,
+            _account_id_filter: Option<Vec<String>>,
+            _activity_type_filter: Option<Vec<String>>,
+            _asset_id_keyword: Option<String>,
+            _sort: Option<Sort>,
+            _needs_review_filter: Option<bool>,
+            _date_from: Option<NaiveDate>,
+            _date_to: Option<NaiveDate>,
+        ) -> Result<ActivitySearchResponse> {
+            unimplemented!()
+        }
+        fn get_first_activity_date(
+            &self,
+            _account_ids: Option<&[String]>,
+        ) -> Result<Option<DateTime<Utc>>> {
+            unimplemented!()
+        }
+        fn get_import_mapping(&self, _account_id: String) -> Result<ImportMappingData> {
+            unimplemented!()
+        }
+        async fn create_activity(&self, _activity: NewActivity) -> Result<Activity> {
+            unimplemented!()
+        }
+        async fn update_activity(&self, update: ActivityUpdate) -> Result<Activity> {
+            let mut activities = self.activities.lock().unwrap();
+            let activity = activities
+                .iter_mut()
+                .find(|a| a.id == update.id)
+                .ok_or_else(|| Error::Unexpected("Activity not found".to_string()))?;
+            activity.quantity = update.quantity.flatten();
+            activity.amount = update.amount.flatten();
+            activity.notes = update.notes;
+            activity.is_user_modified = true;
+            Ok(activity.clone())
+        }
+        async fn delete_activity(&self, activity_id: String) -> Result<Activity> {
+            let mut activities = self.activities.lock().unwrap();
+            let idx = activities
+                .iter()
+                .position(|a| a.id == activity_id)
+                .ok_or_else(|| Error::Unexpected("Activity not found".to_string()))?;
+            Ok(activities.remove(idx))
+        }
+        async fn bulk_mutate_activities(
+            &self,
+            _request: ActivityBulkMutationRequest,
+        ) -> Result<ActivityBulkMutationResult> {
+            unimplemented!()
+        }
+        async fn check_activities_import(
+            &self,
+            _account_id: String,
+            _activities: Vec<ActivityImport>,
+        ) -> Result<Vec<ActivityImport>> {
+            unimplemented!()
+        }
+        async fn import_activities(
+            &self,
+            _account_id: String,
+            _activities: Vec<ActivityImport>,
+            _review_mode: crate::activities::ReviewMode,
+        ) -> Result<ImportActivitiesResult> {
+            unimplemented!()
+        }
+        async fn save_import_mapping(
+            &self,
+            _mapping_data: ImportMappingData,
+        ) -> Result<ImportMappingData> {
+            unimplemented!()
+        }
+        fn check_existing_duplicates(
+            &self,
+            _idempotency_keys: Vec<String>,
+        ) -> Result<HashMap<String, String>> {
+            unimplemented!()
+        }
+        fn parse_csv(&self, _content: &[u8], _config: &ParseConfig) -> Result<ParsedCsvResult> {
+            unimplemented!()
+        }
+        /// Mirrors the repository: matches by ID or idempotency key and skips
+        /// user-modified rows.
+        async fn upsert_activities_bulk(
+            &self,
+            upserts: Vec<ActivityUpsert>,
+        ) -> Result<BulkUpsertResult> {
+            let mut activities = self.activities.lock().unwrap();
+            let mut result = BulkUpsertResult::default();
+            for upsert in upserts {
+                let existing = activities.iter_mut().find(|a| {
+                    a.id == upsert.id
+                        || (upsert.idempotency_key.is_some()
+                            && a.idempotency_key == upsert.idempotency_key)
+                });
+                match existing {
+                    Some(activity) if activity.is_user_modified => result.skipped += 1,
+                    Some(activity) => {
+                        activity.quantity = upsert.quantity;
+                        activity.amount = upsert.amount;
+                        result.updated += 1;
+                    }
+                    None => {
+                        let mut activity = activities[0].clone();
+                        activity.id = upsert.id;
+                        activity.quantity = upsert.quantity;
+                        activity.amount = upsert.amount;
+                        activity.source_system = upsert.source_system;
+                        activity.source_record_id = upsert.source_record_id;
+                        activity.idempotency_key = upsert.idempotency_key;
+                        activity.is_user_modified = false;
+                        activities.push(activity);
+                        result.created += 1;
+                    }
+                }
+            }
+            Ok(result)
+        }
+        async fn prepare_activities(
+            &self,
+            _activities: Vec<NewActivity>,
+            _account: &crate::accounts::Account,
+        ) -> Result<PrepareActivitiesResult> {
+            unimplemented!()
+        }
+    }
+
+    // --- Mock DuplicateDecisionRepository ---
+    #[derive(Default)]
+    struct MockDecisionRepository {
+        decisions: Mutex<Vec<DuplicatePairDecision>>,
+    }
+
+    #[async_trait]
+    impl DuplicateDecisionRepositoryTrait for MockDecisionRepository {
+        fn get_decisions(&self) -> Result<Vec<DuplicatePairDecision>> {
+            Ok(self.decisions.lock().unwrap().clone())
+        }
+        fn get_decision(&self, pair_key: &str) -> Result<Option<DuplicatePairDecision>> {
+            Ok(self
+                .decisions
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|d| d.pair_key == pair_key)
+                .cloned())
+        }
+        async fn save_decision(&self, decision: DuplicatePairDecision) -> Result<()> {
+            let mut decisions = self.decisions.lock().unwrap();
+            decisions.retain(|d| d.pair_key != decision.pair_key);
+            decisions.push(decision);
+            Ok(())
+        }
+    }
+
+    fn activity(id: &str, date: &str, quantity: Decimal, amount: Decimal) -> Activity {
+        let day = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        let ts = Utc.from_utc_datetime(&day.and_hms_opt(12, 0, 0).unwrap());
+        Activity {
+            id: id.to_string(),
+            account_id: "acc-1".to_string(),
+            asset_id: Some("aapl".to_string()),
+            activity_type: "BUY".to_string(),
+            activity_type_override: None,
+            source_type: None,
+            subtype: None,
+            status: ActivityStatus::Posted,
+            activity_date: ts,
+            settlement_date: None,
+            quantity: Some(quantity),
+            unit_price: None,
+            amount: Some(amount),
+            fee: None,
+            currency: "USD".to_string(),
+            fx_rate: None,
+            notes: None,
+            metadata: None,
+            source_system: Some("MANUAL".to_string()),
+            source_record_id: None,
+            source_group_id: None,
+            idempotency_key: None,
+            import_run_id: None,
+            is_user_modified: false,
+            needs_review: false,
+            created_at: ts,
+            updated_at: ts,
+        }
+    }
+
+    fn synced(mut activity: Activity, record_id: &str) -> Activity {
+        activity.source_system = Some("SNAPTRADE".to_string());
+        activity.source_record_id = Some(record_id.to_string());
+        activity
+    }
+
+    fn probe(activity: &Activity) -> DuplicateProbe {
+        DuplicateProbe::from_activity(activity, None)
+    }
+
+    fn service(
+        activities: Vec<Activity>,
+    ) -> (
+        ActivityDuplicateService,
+        Arc<MockActivityService>,
+        Arc<MockDecisionRepository>,
+    ) {
+        let activity_service = Arc::new(MockActivityService {
+            activities: Mutex::new(activities),
+        });
+        let decisions = Arc::new(MockDecisionRepository::default());
+        (
+            ActivityDuplicateService::new(activity_service.clone(), decisions.clone()),
+            activity_service,
+            decisions,
+        )
+    }
+
+    #[test]
+    fn test_pair_key_is_order_independent() {
+        assert_eq!(duplicate_pair_key("a", "b"), duplicate_pair_key("b", "a"));
+    }
+
+    #[test]
+    fn test_identical_activities_score_one() {
+        let config = DuplicateMatchConfig::default();
+        let a = activity("a", "2024-03-01", dec!(10), dec!(1500));
+        let b = activity("b", "2024-03-01", dec!(10), dec!(1500));
+
+        let m = score_duplicate_pair(&probe(&a), &probe(&b), &config).unwrap();
+        assert_eq!(m.score, Decimal::ONE);
+        assert!(m.reasons.is_empty());
+    }
+
+    #[test]
+    fn test_near_match_within_window_and_tolerance() {
+        let config = DuplicateMatchConfig::default();
+        let a = activity("a", "2024-03-01", dec!(10), dec!(1500));
+        let b = synced(activity("b", "2024-03-03", dec!(10), dec!(1505)), "t-1");
+
+        let m = score_duplicate_pair(&probe(&a), &probe(&b), &config).unwrap();
+        assert!(m.score >= config.min_score && m.score < Decimal::ONE);
+        assert!(m.reasons.iter().any(|r| r == "Dates are 2 day(s) apart"));
+        assert!(m.reasons.iter().any(|r| r.starts_with("Amount differs by")));
+        assert!(m.reasons.iter().any(|r| r.contains("MANUAL and SNAPTRADE")));
+    }
+
+    #[test]
+    fn test_sign_conventions_are_ignored() {
+        let config = DuplicateMatchConfig::default();
+        let a = activity("a", "2024-03-01", dec!(10), dec!(1500));
+        let b = activity("b", "2024-03-01", dec!(10), dec!(-1500));
+
+        assert!(score_duplicate_pair(&probe(&a), &probe(&b), &config).is_some());
+    }
+
+    #[test]
+    fn test_rejects_pairs_outside_window_or_tolerance() {
+        let config = DuplicateMatchConfig::default();
+        let a = activity("a", "2024-03-01", dec!(10), dec!(1500));
+
+        let late = activity("b", "2024-03-06", dec!(10), dec!(1500));
+        assert!(score_duplicate_pair(&probe(&a), &probe(&late), &config).is_none());
+
+        let bigger = activity("c", "2024-03-01", dec!(12), dec!(1500));
+        assert!(score_duplicate_pair(&probe(&a), &probe(&bigger), &config).is_none());
+    }
+
+    #[test]
+    fn test_rejects_different_type_currency_or_asset() {
+        let config = DuplicateMatchConfig::default();
+        let a = activity("a", "2024-03-01", dec!(10), dec!(1500));
+
+        let mut sell = activity("b", "2024-03-01", dec!(10), dec!(1500));
+        sell.activity_type = "SELL".to_string();
+        assert!(score_duplicate_pair(&probe(&a), &probe(&sell), &config).is_none());
+
+        let mut eur = activity("c", "2024-03-01", dec!(10), dec!(1500));
+        eur.currency = "EUR".to_string();
+        assert!(score_duplicate_pair(&probe(&a), &probe(&eur), &config).is_none());
+
+        let mut msft = activity("d", "2024-03-01", dec!(10), dec!(1500));
+        msft.asset_id = Some("msft".to_string());
+        assert!(score_duplicate_pair(&probe(&a), &probe(&msft), &config).is_none());
+    }
+
+    #[test]
+    fn test_distinct_provider_records_are_not_duplicates() {
+        let config = DuplicateMatchConfig::default();
+        let a = synced(activity("a", "2024-03-01", dec!(10), dec!(1500)), "t-1");
+        let b = synced(activity("b", "2024-03-01", dec!(10), dec!(1500)), "t-2");
+
+        assert!(score_duplicate_pair(&probe(&a), &probe(&b), &config).is_none());
+    }
+
+    #[test]
+    fn test_symbol_matches_when_asset_ids_are_unknown() {
+        let config = DuplicateMatchConfig::default();
+        let existing = activity("a", "2024-03-01", dec!(10), dec!(1500));
+        let existing_probe = DuplicateProbe::from_activity(&existing, Some("AAPL".to_string()));
+        let import_probe = DuplicateProbe {
+            account_id: "acc-1".to_string(),
+            activity_type: "BUY".to_string(),
+            symbol: Some("aapl".to_string()),
+            date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            quantity: Some(dec!(10)),
+            amount: Some(dec!(1500)),
+            currency: "USD".to_string(),
+            ..Default::default()
+        };
+
+        assert!(score_duplicate_pair(&import_probe, &existing_probe, &config).is_some());
+    }
+
+    #[test]
+    fn test_find_duplicate_pairs_uses_date_window() {
+        let config = DuplicateMatchConfig::default();
+        let activities = [
+            activity("a", "2024-03-01", dec!(10), dec!(1500)),
+            activity("b", "2024-03-20", dec!(10), dec!(1500)),
+            activity("c", "2024-03-02", dec!(10), dec!(1500)),
+        ];
+        let probes: Vec<DuplicateProbe> = activities.iter().map(probe).collect();
+
+        let pairs = find_duplicate_pairs(&probes, &config);
+        assert_eq!(pairs.len(), 1);
+        let (i, j, _) = &pairs[0];
+        let mut ids = [activities[*i].id.as_str(), activities[*j].id.as_str()];
+        ids.sort();
+        assert_eq!(ids, ["a", "c"]);
+    }
+
+    #[test]
+    fn test_suggested_keep_prefers_synced_then_older() {
+        let manual = activity("manual", "2024-03-01", dec!(10), dec!(1500));
+        let broker = synced(
+            activity("broker", "2024-03-02", dec!(10), dec!(1500)),
+            "t-1",
+        );
+        assert_eq!(suggested_keep_id(&manual, &broker), "broker");
+
+        let older = activity("older", "2024-03-01", dec!(10), dec!(1500));
+        let newer = activity("newer", "2024-03-02", dec!(10), dec!(1500));
+        assert_eq!(suggested_keep_id(&newer, &older), "older");
+    }
+
+    #[tokio::test]
+    async fn test_keep_both_decision_is_remembered() {
+        let (service, _, _) = service(vec![
+            activity("a", "2024-03-01", dec!(10), dec!(1500)),
+            activity("b", "2024-03-01", dec!(10), dec!(1500)),
+        ]);
+
+        let candidates = service.list_candidates(Some("acc-1")).unwrap();
+        assert_eq!(candidates.len(), 1);
+
+        service
+            .resolve(DuplicateResolution {
+                activity_id: "a".to_string(),
+                other_activity_id: "b".to_string(),
+                decision: DuplicateDecision::KeepBoth,
+                keep_activity_id: None,
+            })
+            .await
+            .unwrap();
+
+        assert!(service.list_candidates(Some("acc-1")).unwrap().is_empty());
+
+        let incoming = probe(&activity("a", "2024-03-01", dec!(10), dec!(1500)));
+        let matches = service.find_matches("acc-1", &[incoming]).unwrap();
+        assert!(matches[0].is_none());
+    }
+
+    #[tokio::test]
+    async fn test_merge_deletes_the_other_activity() {
+        let manual = activity("manual", "2024-03-01", dec!(10), dec!(1500));
+        let broker = synced(
+            activity("broker", "2024-03-02", dec!(10), dec!(1500)),
+            "t-1",
+        );
+        let (service, activities, decisions) = service(vec![manual, broker]);
+
+        let decision = service
+            .resolve(DuplicateResolution {
+                activity_id: "manual".to_string(),
+                other_activity_id: "broker".to_string(),
+                decision: DuplicateDecision::Merge,
+                keep_activity_id: None,
+            })
+            .await
+            .unwrap();
+
+        let remaining = activities.get_activities().unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, "broker");
+        assert_eq!(decision.pair_key, duplicate_pair_key("manual", "broker"));
+        assert!(decisions
+            .get_decision(&decision.pair_key)
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_merge_keeping_manual_row_survives_broker_sync() {
+        let mut manual = activity("manual", "2024-03-01", dec!(10), dec!(1510));
+        manual.notes = Some("Fixed the fee".to_string());
+        let mut broker = synced(
+            activity("broker", "2024-03-02", dec!(10), dec!(1500)),
+            "t-1",
+        );
+        broker.idempotency_key = Some("key-t-1".to_string());
+        let (service, activities, _) = service(vec![manual, broker]);
+
+        service
+            .resolve(DuplicateResolution {
+                activity_id: "manual".to_string(),
+                other_activity_id: "broker".to_string(),
+                decision: DuplicateDecision::Merge,
+                keep_activity_id: Some("manual".to_string()),
+            })
+            .await
+            .unwrap();
+
+        // The synced row stays, carrying the manual values
+        let remaining = activities.get_activities().unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, "broker");
+        assert_eq!(remaining[0].amount, Some(dec!(1510)));
+        assert_eq!(remaining[0].notes.as_deref(), Some("Fixed the fee"));
+
+        // The next broker sync neither recreates nor overwrites it
+        let result = activities
+            .upsert_activities_bulk(vec![ActivityUpsert {
+                id: "broker".to_string(),
+                account_id: "acc-1".to_string(),
+                asset_id: Some("aapl".to_string()),
+                activity_type: "BUY".to_string(),
+                subtype: None,
+                activity_date: "2024-03-02T12:00:00Z".to_string(),
+                quantity: Some(dec!(10)),
+                unit_price: None,
+                currency: "USD".to_string(),
+                fee: None,
+                amount: Some(dec!(1500)),
+                status: None,
+                notes: None,
+                fx_rate: None,
+                metadata: None,
+                needs_review: None,
+                source_system: Some("SNAPTRADE".to_string()),
+                source_record_id: Some("t-1".to_string()),
+                source_group_id: None,
+                idempotency_key: Some("key-t-1".to_string()),
+                import_run_id: None,
+            }])
+            .await
+            .unwrap();
+
+        assert_eq!(result.created, 0);
+        let remaining = activities.get_activities().unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].amount, Some(dec!(1510)));
+    }
+
+    #[tokio::test]
+    async fn test_merge_refuses_to_delete_either_synced_row() {
+        let (service, activities, _) = service(vec![
+            synced(activity("a", "2024-03-01", dec!(10), dec!(1500)), "t-1"),
+            synced(activity("b", "2024-03-01", dec!(10), dec!(1500)), "t-2"),
+        ]);
+
+        let result = service
+            .resolve(DuplicateResolution {
+                activity_id: "a".to_string(),
+                other_activity_id: "b".to_string(),
+                decision: DuplicateDecision::Merge,
+                keep_activity_id: None,
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(activities.get_activities().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_merge_rejects_keep_id_outside_pair() {
+        let (service, _, _) = service(vec![
+            activity("a", "2024-03-01", dec!(10), dec!(1500)),
+            activity("b", "2024-03-01", dec!(10), dec!(1500)),
+        ]);
+
+        let result = service
+            .resolve(DuplicateResolution {
+                activity_id: "a".to_string(),
+                other_activity_id: "b".to_string(),
+                decision: DuplicateDecision::Merge,
+                keep_activity_id: Some("c".to_string()),
+            })
+            .await;
+        assert!(result.is_err());
+    }
+}
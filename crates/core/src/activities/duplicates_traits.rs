@@ -0,0 +1,32 @@
+use super::duplicates_model::*;
+use crate::Result;
+use async_trait::async_trait;
+
+/// Persistence for user decisions on duplicate candidate pairs.
+#[async_trait]
+pub trait DuplicateDecisionRepositoryTrait: Send + Sync {
+    fn get_decisions(&self) -> Result<Vec<DuplicatePairDecision>>;
+    fn get_decision(&self, pair_key: &str) -> Result<Option<DuplicatePairDecision>>;
+    async fn save_decision(&self, decision: DuplicatePairDecision) -> Result<()>;
+}
+
+/// Trait defining the contract for fuzzy duplicate detection and review.
+#[async_trait]
+pub trait ActivityDuplicateServiceTrait: Send + Sync {
+    /// Lists undecided duplicate candidates, optionally scoped to one account.
+    fn list_candidates(&self, account_id: Option<&str>) -> Result<Vec<DuplicateCandidate>>;
+
+    /// For each probe, returns the best undecided match among persisted
+    /// activities of the account, if any.
+    fn find_matches(
+        &self,
+        account_id: &str,
+        probes: &[DuplicateProbe],
+    ) -> Result<Vec<Option<(String, DuplicateMatch)>>>;
+
+    /// Applies a merge or keep-both decision and remembers it.
+    ///
+    /// A broker-synced activity is never deleted by a merge: when the manual
+    /// side is kept, its values are moved onto the synced row instead.
+    async fn resolve(&self, resolution: DuplicateResolution) -> Result<DuplicatePairDecision>;
+}
@@ -175,6 +175,13 @@ impl ImportRun {
         self.finished_at = Some(Utc::now());
         self.updated_at = Utc::now();
     }
+
+    /// Mark as cancelled (staged changes discarded)
+    pub fn cancel(&mut self) {
+        self.status = ImportRunStatus::Cancelled;
+        self.finished_at = Some(Utc::now());
+        self.updated_at = Utc::now();
+    }
 }
 
 /// Trait for ImportRun persistence operations
@@ -7,8 +7,14 @@ mod activities_service;
 mod activities_traits;
 mod compiler;
 mod csv_parser;
+mod duplicates_model;
+mod duplicates_service;
+mod duplicates_traits;
 mod idempotency;
 mod import_run_model;
+mod staging_model;
+mod staging_service;
+mod staging_traits;
 
 #[cfg(test)]
 mod activities_service_tests;
@@ -16,6 +22,12 @@ mod activities_service_tests;
 #[cfg(test)]
 mod activities_model_tests;
 
+#[cfg(test)]
+mod duplicates_service_tests;
+
+#[cfg(test)]
+mod staging_service_tests;
+
 pub use activities_constants::*;
 pub use activities_errors::ActivityError;
 pub use activities_model::{
@@ -30,6 +42,15 @@ pub use activities_service::ActivityService;
 pub use activities_traits::{ActivityRepositoryTrait, ActivityServiceTrait};
 pub use compiler::{ActivityCompiler, DefaultActivityCompiler};
 pub use csv_parser::{parse_csv, ParseConfig, ParseError, ParsedCsvResult};
+pub use duplicates_model::{
+    DuplicateCandidate, DuplicateDecision, DuplicateMatch, DuplicateMatchConfig,
+    DuplicatePairDecision, DuplicateProbe, DuplicateResolution,
+};
+pub use duplicates_service::{
+    duplicate_pair_key, find_best_duplicate, find_duplicate_pairs, score_duplicate_pair,
+    suggested_keep_id, ActivityDuplicateService,
+};
+pub use duplicates_traits::{ActivityDuplicateServiceTrait, DuplicateDecisionRepositoryTrait};
 pub use idempotency::{
     compute_activity_idempotency_key, compute_idempotency_key, generate_manual_idempotency_key,
 };
@@ -37,3 +58,9 @@ pub use import_run_model::{
     ImportRun, ImportRunMode, ImportRunRepositoryTrait, ImportRunStatus, ImportRunSummary,
     ImportRunType, ReviewMode,
 };
+pub use staging_model::{
+    ImportImpact, ImportRunReview, PositionImpact, RejectedActivityKey, StageActivitiesResult,
+    StagedActivity, StagedActivityDecision, StagedActivityStatus,
+};
+pub use staging_service::ActivityStagingService;
+pub use staging_traits::{ActivityStagingServiceTrait, StagedActivityRepositoryTrait};
@@ -0,0 +1,244 @@
+//! Domain models for import runs held for review before reaching the ledger.
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use super::activities_model::{Activity, ActivityStatus, ActivityUpsert};
+use super::import_run_model::{ImportRun, ImportRunStatus};
+use crate::errors::{Error, Result, ValidationError};
+use crate::portfolio::snapshot::HoldingsCalculationWarning;
+
+/// Review state of a staged activity row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StagedActivityStatus {
+    /// Not decided yet; applied with the run unless rejected
+    #[default]
+    Pending,
+    /// Explicitly accepted (or edited) by the user
+    Accepted,
+    /// Rejected; never written to the ledger
+    Rejected,
+    /// Written to the ledger when the run was applied
+    Applied,
+}
+
+impl StagedActivityStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StagedActivityStatus::Pending => "PENDING",
+            StagedActivityStatus::Accepted => "ACCEPTED",
+            StagedActivityStatus::Rejected => "REJECTED",
+            StagedActivityStatus::Applied => "APPLIED",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "PENDING" => Some(StagedActivityStatus::Pending),
+            "ACCEPTED" => Some(StagedActivityStatus::Accepted),
+            "REJECTED" => Some(StagedActivityStatus::Rejected),
+            "APPLIED" => Some(StagedActivityStatus::Applied),
+            _ => None,
+        }
+    }
+}
+
+/// User decision on a single staged row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StagedActivityDecision {
+    Accept,
+    Reject,
+}
+
+/// An activity produced by an import run, waiting for approval.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StagedActivity {
+    pub id: String,
+    pub import_run_id: String,
+    pub account_id: String,
+    /// Idempotency key of the row as imported. Kept when the row is edited so
+    /// a rejection still matches the provider record on the next sync.
+    pub idempotency_key: String,
+    /// Activity payload written to the ledger on apply
+    pub activity: ActivityUpsert,
+    pub status: StagedActivityStatus,
+    /// Whether the user changed the payload during review
+    pub edited: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl StagedActivity {
+    /// Stages an activity for the given run. Rows without an idempotency key
+    /// fall back to their activity ID.
+    pub fn new(import_run_id: &str, mut activity: ActivityUpsert) -> Self {
+        let now = Utc::now();
+        if activity.id.is_empty() {
+            activity.id = uuid::Uuid::new_v4().to_string();
+        }
+        activity.import_run_id = Some(import_run_id.to_string());
+        let idempotency_key = activity
+            .idempotency_key
+            .clone()
+            .unwrap_or_else(|| activity.id.clone());
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            import_run_id: import_run_id.to_string(),
+            account_id: activity.account_id.clone(),
+            idempotency_key,
+            activity,
+            status: StagedActivityStatus::Pending,
+            edited: false,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Whether the row will be written to the ledger when the run is applied.
+    pub fn is_included(&self) -> bool {
+        matches!(
+            self.status,
+            StagedActivityStatus::Pending | StagedActivityStatus::Accepted
+        )
+    }
+
+    /// Builds an in-memory ledger activity for dry-running the holdings
+    /// calculator. Nothing is persisted.
+    pub fn preview_activity(&self) -> Result<Activity> {
+        let upsert = &self.activity;
+        let activity_date = parse_activity_date(&upsert.activity_date)?;
+        let metadata = upsert
+            .metadata
+            .as_deref()
+            .and_then(|m| serde_json::from_str(m).ok());
+
+        Ok(Activity {
+            id: upsert.id.clone(),
+            account_id: upsert.account_id.clone(),
+            asset_id: upsert.asset_id.clone(),
+            activity_type: upsert.activity_type.clone(),
+            activity_type_override: None,
+            source_type: None,
+            subtype: upsert.subtype.clone(),
+            status: upsert.status.clone().unwrap_or(ActivityStatus::Posted),
+            activity_date,
+            settlement_date: None,
+            quantity: upsert.quantity,
+            unit_price: upsert.unit_price,
+            amount: upsert.amount,
+            fee: upsert.fee,
+            currency: upsert.currency.clone(),
+            fx_rate: upsert.fx_rate,
+            notes: upsert.notes.clone(),
+            metadata,
+            source_system: upsert.source_system.clone(),
+            source_record_id: upsert.source_record_id.clone(),
+            source_group_id: upsert.source_group_id.clone(),
+            idempotency_key: upsert.idempotency_key.clone(),
+            import_run_id: Some(self.import_run_id.clone()),
+            is_user_modified: self.edited,
+            needs_review: upsert.needs_review.unwrap_or(false),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+/// Parses an RFC 3339 timestamp or a plain `YYYY-MM-DD` date.
+fn parse_activity_date(value: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(|d| Utc.from_utc_datetime(&d.and_hms_opt(0, 0, 0).unwrap_or_default()))
+        })
+        .map_err(|_| {
+            Error::Validation(ValidationError::InvalidInput(format!(
+                "Invalid activity date '{}'",
+                value
+            )))
+        })
+}
+
+/// Idempotency key the user rejected during review. Matching rows are not
+/// staged again by later runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedActivityKey {
+    pub account_id: String,
+    pub idempotency_key: String,
+    pub import_run_id: String,
+    pub rejected_at: DateTime<Utc>,
+}
+
+/// Outcome of staging a batch of activities.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageActivitiesResult {
+    /// Rows added to the run for review
+    pub staged: usize,
+    /// Rows skipped because the user rejected them before
+    pub previously_rejected: usize,
+    /// Rows skipped because they are already in the ledger or awaiting review
+    pub already_known: usize,
+}
+
+/// Change of one position between the current holdings and the preview.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionImpact {
+    pub asset_id: String,
+    pub currency: String,
+    pub quantity_before: Decimal,
+    pub quantity_after: Decimal,
+    pub cost_basis_before: Decimal,
+    pub cost_basis_after: Decimal,
+}
+
+/// Holdings and cash of an account before and after applying the staged rows.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportImpact {
+    pub account_id: String,
+    pub currency: String,
+    /// Cash balances by currency, only for currencies that change
+    pub cash_before: std::collections::HashMap<String, Decimal>,
+    pub cash_after: std::collections::HashMap<String, Decimal>,
+    /// Positions whose quantity or cost basis change
+    pub positions: Vec<PositionImpact>,
+    /// Calculator warnings raised by the staged rows (e.g. selling more than held)
+    pub warnings: Vec<HoldingsCalculationWarning>,
+}
+
+/// Everything the review screen needs for one import run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportRunReview {
+    pub run: ImportRun,
+    pub activities: Vec<StagedActivity>,
+    /// Impact of the included rows; `None` once the run is no longer reviewable
+    pub impact: Option<ImportImpact>,
+}
+
+impl ImportRunReview {
+    /// Whether the run has anything that warrants a human look: rows flagged
+    /// as needing review, or calculator warnings in the preview.
+    pub fn has_warnings(&self) -> bool {
+        self.activities
+            .iter()
+            .any(|a| a.is_included() && a.activity.needs_review.unwrap_or(false))
+            || self.impact.as_ref().is_some_and(|i| !i.warnings.is_empty())
+    }
+}
+
+/// Whether rows of a run in this status can still be edited or decided.
+pub(crate) fn is_reviewable(status: &ImportRunStatus) -> bool {
+    matches!(
+        status,
+        ImportRunStatus::Running | ImportRunStatus::NeedsReview
+    )
+}
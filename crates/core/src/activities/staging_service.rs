@@ -0,0 +1,371 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use log::debug;
+use rust_decimal::Decimal;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+
+use super::activities_model::ActivityUpsert;
+use super::activities_traits::ActivityServiceTrait;
+use super::import_run_model::{ImportRun, ImportRunRepositoryTrait, ImportRunSummary};
+use super::staging_model::*;
+use super::staging_traits::{ActivityStagingServiceTrait, StagedActivityRepositoryTrait};
+use crate::errors::{DatabaseError, Error, Result, ValidationError};
+use crate::portfolio::snapshot::{AccountStateSnapshot, SnapshotServiceTrait};
+
+/// Stages rows for review, skipping keys the user rejected before, keys in
+/// `existing_keys` (already in the ledger) and keys awaiting review in
+/// another run. Shared by broker sync and CSV import.
+pub(crate) async fn stage_rows(
+    repository: &dyn StagedActivityRepositoryTrait,
+    import_run_id: &str,
+    account_id: &str,
+    activities: Vec<ActivityUpsert>,
+    existing_keys: &HashSet<String>,
+) -> Result<StageActivitiesResult> {
+    let rejected = repository.get_rejected_keys(account_id)?;
+    let mut known = repository.get_pending_keys(account_id)?;
+    known.extend(existing_keys.iter().cloned());
+
+    let mut result = StageActivitiesResult::default();
+    let mut rows = Vec::with_capacity(activities.len());
+    for activity in activities {
+        let row = StagedActivity::new(import_run_id, activity);
+        if rejected.contains(&row.idempotency_key) {
+            result.previously_rejected += 1;
+        } else if !known.insert(row.idempotency_key.clone()) {
+            result.already_known += 1;
+        } else {
+            rows.push(row);
+        }
+    }
+
+    if !rows.is_empty() {
+        result.staged = repository.insert_staged(rows).await?;
+    }
+    debug!(
+        "Staged {} activities for run {} ({} previously rejected, {} already known)",
+        result.staged, import_run_id, result.previously_rejected, result.already_known
+    );
+    Ok(result)
+}
+
+fn not_found(what: &str, id: &str) -> Error {
+    Error::Database(DatabaseError::NotFound(format!("{} {}", what, id)))
+}
+
+/// Service for reviewing import runs before their activities reach the ledger.
+pub struct ActivityStagingService {
+    staged_repository: Arc<dyn StagedActivityRepositoryTrait>,
+    import_run_repository: Arc<dyn ImportRunRepositoryTrait>,
+    activity_service: Arc<dyn ActivityServiceTrait>,
+    snapshot_service: Arc<dyn SnapshotServiceTrait>,
+}
+
+impl ActivityStagingService {
+    pub fn new(
+        staged_repository: Arc<dyn StagedActivityRepositoryTrait>,
+        import_run_repository: Arc<dyn ImportRunRepositoryTrait>,
+        activity_service: Arc<dyn ActivityServiceTrait>,
+        snapshot_service: Arc<dyn SnapshotServiceTrait>,
+    ) -> Self {
+        Self {
+            staged_repository,
+            import_run_repository,
+            activity_service,
+            snapshot_service,
+        }
+    }
+
+    fn get_run(&self, import_run_id: &str) -> Result<ImportRun> {
+        self.import_run_repository
+            .get_by_id(import_run_id)?
+            .ok_or_else(|| not_found("Import run", import_run_id))
+    }
+
+    fn get_reviewable_run(&self, import_run_id: &str) -> Result<ImportRun> {
+        let run = self.get_run(import_run_id)?;
+        if !is_reviewable(&run.status) {
+            return Err(Error::Validation(ValidationError::InvalidInput(format!(
+                "Import run {} is no longer awaiting review",
+                import_run_id
+            ))));
+        }
+        Ok(run)
+    }
+
+    fn get_reviewable_row(&self, staged_id: &str) -> Result<StagedActivity> {
+        let row = self
+            .staged_repository
+            .get_staged(staged_id)?
+            .ok_or_else(|| not_found("Staged activity", staged_id))?;
+        self.get_reviewable_run(&row.import_run_id)?;
+        Ok(row)
+    }
+
+    /// Dry-runs the included rows on top of the account's current holdings.
+    fn compute_impact(&self, run: &ImportRun, rows: &[StagedActivity]) -> Result<ImportImpact> {
+        let previews = rows
+            .iter()
+            .filter(|row| row.is_included())
+            .map(StagedActivity::preview_activity)
+            .collect::<Result<Vec<_>>>()?;
+
+        let before = self
+            .snapshot_service
+            .get_latest_holdings_snapshot(&run.account_id)?
+            .unwrap_or_default();
+        let after = self
+            .snapshot_service
+            .preview_holdings_with_activities(&run.account_id, &previews)?;
+
+        Ok(Self::diff_snapshots(
+            &run.account_id,
+            &before,
+            &after.snapshot,
+            after.warnings,
+        ))
+    }
+
+    fn diff_snapshots(
+        account_id: &str,
+        before: &AccountStateSnapshot,
+        after: &AccountStateSnapshot,
+        warnings: Vec<crate::portfolio::snapshot::HoldingsCalculationWarning>,
+    ) -> ImportImpact {
+        let mut cash_before = HashMap::new();
+        let mut cash_after = HashMap::new();
+        let currencies: BTreeSet<&String> = before
+            .cash_balances
+            .keys()
+            .chain(after.cash_balances.keys())
+            .collect();
+        for currency in currencies {
+            let old = before.cash_balances.get(currency).copied();
+            let new = after.cash_balances.get(currency).copied();
+            if old.unwrap_or_default() != new.unwrap_or_default() {
+                cash_before.insert(currency.clone(), old.unwrap_or(Decimal::ZERO));
+                cash_after.insert(currency.clone(), new.unwrap_or(Decimal::ZERO));
+            }
+        }
+
+        let asset_ids: BTreeSet<&String> = before
+            .positions
+            .keys()
+            .chain(after.positions.keys())
+            .collect();
+        let positions = asset_ids
+            .into_iter()
+            .filter_map(|asset_id| {
+                let old = before.positions.get(asset_id);
+                let new = after.positions.get(asset_id);
+                let impact = PositionImpact {
+                    asset_id: asset_id.clone(),
+                    currency: new.or(old).map(|p| p.currency.clone()).unwrap_or_default(),
+                    quantity_before: old.map_or(Decimal::ZERO, |p| p.quantity),
+                    quantity_after: new.map_or(Decimal::ZERO, |p| p.quantity),
+                    cost_basis_before: old.map_or(Decimal::ZERO, |p| p.total_cost_basis),
+                    cost_basis_after: new.map_or(Decimal::ZERO, |p| p.total_cost_basis),
+                };
+                let changed = impact.quantity_before != impact.quantity_after
+                    || impact.cost_basis_before != impact.cost_basis_after;
+                changed.then_some(impact)
+            })
+            .collect();
+
+        ImportImpact {
+            account_id: account_id.to_string(),
+            currency: after.currency.clone(),
+            cash_before,
+            cash_after,
+            positions,
+            warnings,
+        }
+    }
+
+    fn rejected_key(row: &StagedActivity) -> RejectedActivityKey {
+        RejectedActivityKey {
+            account_id: row.account_id.clone(),
+            idempotency_key: row.idempotency_key.clone(),
+            import_run_id: row.import_run_id.clone(),
+            rejected_at: Utc::now(),
+        }
+    }
+}
+
+#[async_trait]
+impl ActivityStagingServiceTrait for ActivityStagingService {
+    async fn stage_activities(
+        &self,
+        import_run_id: &str,
+        account_id: &str,
+        activities: Vec<ActivityUpsert>,
+    ) -> Result<StageActivitiesResult> {
+        if activities.is_empty() {
+            return Ok(StageActivitiesResult::default());
+        }
+
+        let keys: Vec<String> = activities
+            .iter()
+            .filter_map(|a| a.idempotency_key.clone())
+            .collect();
+        let existing: HashSet<String> = if keys.is_empty() {
+            HashSet::new()
+        } else {
+            self.activity_service
+                .check_existing_duplicates(keys)?
+                .into_keys()
+                .collect()
+        };
+
+        stage_rows(
+            self.staged_repository.as_ref(),
+            import_run_id,
+            account_id,
+            activities,
+            &existing,
+        )
+        .await
+    }
+
+    fn get_review(&self, import_run_id: &str) -> Result<ImportRunReview> {
+        let run = self.get_run(import_run_id)?;
+        let activities = self.staged_repository.list_for_run(import_run_id)?;
+        let impact = if is_reviewable(&run.status) {
+            Some(self.compute_impact(&run, &activities)?)
+        } else {
+            None
+        };
+        Ok(ImportRunReview {
+            run,
+            activities,
+            impact,
+        })
+    }
+
+    async fn update_staged_activity(
+        &self,
+        staged_id: &str,
+        activity: ActivityUpsert,
+    ) -> Result<StagedActivity> {
+        let mut row = self.get_reviewable_row(staged_id)?;
+
+        // Identity and provenance stay with the staged row; only economics change
+        row.activity = ActivityUpsert {
+            id: row.activity.id.clone(),
+            account_id: row.activity.account_id.clone(),
+            source_system: row.activity.source_system.clone(),
+            source_record_id: row.activity.source_record_id.clone(),
+            source_group_id: row.activity.source_group_id.clone(),
+            idempotency_key: row.activity.idempotency_key.clone(),
+            import_run_id: row.activity.import_run_id.clone(),
+            ..activity
+        };
+        row.status = StagedActivityStatus::Accepted;
+        row.edited = true;
+        row.updated_at = Utc::now();
+        // Fail early on payloads the preview (and the ledger) cannot read
+        row.preview_activity()?;
+
+        self.staged_repository
+            .update_staged(vec![row.clone()])
+            .await?;
+        Ok(row)
+    }
+
+    async fn set_decision(
+        &self,
+        staged_id: &str,
+        decision: StagedActivityDecision,
+    ) -> Result<StagedActivity> {
+        let mut row = self.get_reviewable_row(staged_id)?;
+        row.status = match decision {
+            StagedActivityDecision::Accept => StagedActivityStatus::Accepted,
+            StagedActivityDecision::Reject => StagedActivityStatus::Rejected,
+        };
+        row.updated_at = Utc::now();
+        self.staged_repository
+            .update_staged(vec![row.clone()])
+            .await?;
+        Ok(row)
+    }
+
+    async fn apply_run(&self, import_run_id: &str) -> Result<ImportRun> {
+        let mut run = self.get_reviewable_run(import_run_id)?;
+        let rows = self.staged_repository.list_for_run(import_run_id)?;
+        let (mut included, rejected): (Vec<_>, Vec<_>) = rows
+            .into_iter()
+            .filter(|row| row.status != StagedActivityStatus::Applied)
+            .partition(StagedActivity::is_included);
+
+        let upserts: Vec<ActivityUpsert> = included.iter().map(|r| r.activity.clone()).collect();
+        let bulk_result = if upserts.is_empty() {
+            Default::default()
+        } else {
+            self.activity_service
+                .upsert_activities_bulk(upserts)
+                .await?
+        };
+
+        if !rejected.is_empty() {
+            self.staged_repository
+                .save_rejected_keys(rejected.iter().map(Self::rejected_key).collect())
+                .await?;
+        }
+
+        let now = Utc::now();
+        for row in &mut included {
+            row.status = StagedActivityStatus::Applied;
+            row.updated_at = now;
+        }
+        if !included.is_empty() {
+            self.staged_repository.update_staged(included).await?;
+        }
+
+        let mut summary = run.summary.take().unwrap_or_default();
+        summary.inserted = bulk_result.created as u32;
+        summary.updated = bulk_result.updated as u32;
+        summary.skipped += (rejected.len() + bulk_result.skipped) as u32;
+        run.summary = Some(summary);
+        run.complete();
+
+        debug!(
+            "Applied import run {}: {} upserted, {} rejected",
+            import_run_id,
+            bulk_result.upserted,
+            rejected.len()
+        );
+        self.import_run_repository.update(run).await
+    }
+
+    async fn discard_run(&self, import_run_id: &str) -> Result<ImportRun> {
+        let mut run = self.get_reviewable_run(import_run_id)?;
+        let mut rows: Vec<StagedActivity> = self
+            .staged_repository
+            .list_for_run(import_run_id)?
+            .into_iter()
+            .filter(|row| row.status != StagedActivityStatus::Applied)
+            .collect();
+        let discarded = rows.len() as u32;
+
+        if !rows.is_empty() {
+            self.staged_repository
+                .save_rejected_keys(rows.iter().map(Self::rejected_key).collect())
+                .await?;
+
+            let now = Utc::now();
+            for row in &mut rows {
+                row.status = StagedActivityStatus::Rejected;
+                row.updated_at = now;
+            }
+            self.staged_repository.update_staged(rows).await?;
+        }
+
+        let summary = run.summary.get_or_insert_with(ImportRunSummary::default);
+        summary.inserted = 0;
+        summary.updated = 0;
+        summary.skipped += discarded;
+        run.cancel();
+        self.import_run_repository.update(run).await
+    }
+}
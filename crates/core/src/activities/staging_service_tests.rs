@@ -0,0 +1,660 @@
+#[cfg(test)]
+mod tests {
+    use crate::activities::activities_model::*;
+    use crate::activities::{
+        ActivityServiceTrait, ActivityStagingService, ActivityStagingServiceTrait, ImportRun,
+        ImportRunMode, ImportRunRepositoryTrait, ImportRunStatus, ImportRunType, ParseConfig,
+        ParsedCsvResult, RejectedActivityKey, ReviewMode, StagedActivity, StagedActivityDecision,
+        StagedActivityRepositoryTrait, StagedActivityStatus,
+    };
+    use crate::errors::Result;
+    use crate::portfolio::snapshot::{
+        AccountStateSnapshot, HoldingsCalculationResult, HoldingsCalculationWarning, Position,
+        SnapshotServiceTrait,
+    };
+    use async_trait::async_trait;
+    use chrono::{DateTime, NaiveDate, Utc};
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use std::collections::{HashMap, HashSet};
+    use std::sync::{Arc, Mutex};
+
+    // --- Mock ActivityService ---
+    #[derive(Default)]
+    struct MockActivityService {
+        existing_keys: Mutex<HashMap<String, String>>,
+        upserted: Mutex<Vec<ActivityUpsert>>,
+    }
+
+    #[async_trait]
+    impl ActivityServiceTrait for MockActivityService {
+        fn get_activity(&self, _activity_id: &str) -> Result<Activity> {
+            unimplemented!()
+        }
+        fn get_activities(&self) -> Result<Vec<Activity>> {
+            unimplemented!()
+        }
+        fn get_activities_by_account_id(&self, _account_id: &str) -> Result<Vec<Activity>> {
+            unimplemented!()
+        }
+        fn get_activities_by_account_ids(&self, _account_ids: &[String]) -> Result<Vec<Activity>> {
+            unimplemented!()
+        }
+        fn get_trading_activities(&self) -> Result<Vec<Activity>> {
+            unimplemented!()
+        }
+        fn get_income_activities(&self) -> Result<Vec<Activity>> {
+            unimplemented!()
+        }
+        fn search_activities(
+            &self,
+            _page: i64,
+            _page_size: i64,
+            _account_id_filter: Option<Vec<String>>,
+            _activity_type_filter: Option<Vec<String>>,
+            _asset_id_keyword: Option<String>,
+            _sort: Option<Sort>,
+            _needs_review_filter: Option<bool>,
+            _date_from: Option<NaiveDate>,
+            _date_to: Option<NaiveDate>,
+        ) -> Result<ActivitySearchResponse> {
+            unimplemented!()
+        }
+        fn get_first_activity_date(
+            &self,
+            _account_ids: Option<&[String]>,
+        ) -> Result<Option<DateTime<Utc>>> {
+            unimplemented!()
+        }
+        fn get_import_mapping(&self, _account_id: String) -> Result<ImportMappingData> {
+            unimplemented!()
+        }
+        async fn create_activity(&self, _activity: NewActivity) -> Result<Activity> {
+            unimplemented!()
+        }
+        async fn update_activity(&self, _activity: ActivityUpdate) -> Result<Activity> {
+            unimplemented!()
+        }
+        async fn delete_activity(&self, _activity_id: String) -> Result<Activity> {
+            unimplemented!()
+        }
+        async fn bulk_mutate_activities(
+            &self,
+            _request: ActivityBulkMutationRequest,
+        ) -> Result<ActivityBulkMutationResult> {
+            unimplemented!()
+        }
+        async fn check_activities_import(
+            &self,
+            _account_id: String,
+            _activities: Vec<ActivityImport>,
+        ) -> Result<Vec<ActivityImport>> {
+            unimplemented!()
+        }
+        async fn import_activities(
+            &self,
+            _account_id: String,
+            _activities: Vec<ActivityImport>,
+            _review_mode: ReviewMode,
+        ) -> Result<ImportActivitiesResult> {
+            unimplemented!()
+        }
+        async fn save_import_mapping(
+            &self,
+            _mapping_data: ImportMappingData,
+        ) -> Result<ImportMappingData> {
+            unimplemented!()
+        }
+        fn check_existing_duplicates(
+            &self,
+            idempotency_keys: Vec<String>,
+        ) -> Result<HashMap<String, String>> {
+            let existing = self.existing_keys.lock().unwrap();
+            Ok(idempotency_keys
+                .into_iter()
+                .filter_map(|k| existing.get(&k).map(|id| (k, id.clone())))
+                .collect())
+        }
+        fn parse_csv(&self, _content: &[u8], _config: &ParseConfig) -> Result<ParsedCsvResult> {
+            unimplemented!()
+        }
+        async fn upsert_activities_bulk(
+            &self,
+            activities: Vec<ActivityUpsert>,
+        ) -> Result<BulkUpsertResult> {
+            let count = activities.len();
+            self.upserted.lock().unwrap().extend(activities);
+            Ok(BulkUpsertResult {
+                upserted: count,
+                created: count,
+                updated: 0,
+                skipped: 0,
+            })
+        }
+        async fn prepare_activities(
+            &self,
+            _activities: Vec<NewActivity>,
+            _account: &crate::accounts::Account,
+        ) -> Result<PrepareActivitiesResult> {
+            unimplemented!()
+        }
+    }
+
+    // --- Mock StagedActivityRepository ---
+    #[derive(Default)]
+    struct MockStagedRepository {
+        rows: Mutex<Vec<StagedActivity>>,
+        rejected: Mutex<Vec<RejectedActivityKey>>,
+    }
+
+    #[async_trait]
+    impl StagedActivityRepositoryTrait for MockStagedRepository {
+        async fn insert_staged(&self, rows: Vec<StagedActivity>) -> Result<usize> {
+            let count = rows.len();
+            self.rows.lock().unwrap().extend(rows);
+            Ok(count)
+        }
+        fn get_staged(&self, id: &str) -> Result<Option<StagedActivity>> {
+            Ok(self
+                .rows
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|r| r.id == id)
+                .cloned())
+        }
+        fn list_for_run(&self, import_run_id: &str) -> Result<Vec<StagedActivity>> {
+            Ok(self
+                .rows
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|r| r.import_run_id == import_run_id)
+                .cloned()
+                .collect())
+        }
+        async fn update_staged(&self, updated: Vec<StagedActivity>) -> Result<()> {
+            let mut rows = self.rows.lock().unwrap();
+            for row in updated {
+                if let Some(existing) = rows.iter_mut().find(|r| r.id == row.id) {
+                    *existing = row;
+                }
+            }
+            Ok(())
+        }
+        fn get_pending_keys(&self, account_id: &str) -> Result<HashSet<String>> {
+            Ok(self
+                .rows
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|r| r.account_id == account_id && r.is_included())
+                .map(|r| r.idempotency_key.clone())
+                .collect())
+        }
+        fn get_rejected_keys(&self, account_id: &str) -> Result<HashSet<String>> {
+            Ok(self
+                .rejected
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|k| k.account_id == account_id)
+                .map(|k| k.idempotency_key.clone())
+                .collect())
+        }
+        async fn save_rejected_keys(&self, keys: Vec<RejectedActivityKey>) -> Result<()> {
+            self.rejected.lock().unwrap().extend(keys);
+            Ok(())
+        }
+    }
+
+    // --- Mock ImportRunRepository ---
+    #[derive(Default)]
+    struct MockImportRunRepository {
+        runs: Mutex<HashMap<String, ImportRun>>,
+    }
+
+    #[async_trait]
+    impl ImportRunRepositoryTrait for MockImportRunRepository {
+        async fn create(&self, import_run: ImportRun) -> Result<ImportRun> {
+            self.runs
+                .lock()
+                .unwrap()
+                .insert(import_run.id.clone(), import_run.clone());
+            Ok(import_run)
+        }
+        async fn update(&self, import_run: ImportRun) -> Result<ImportRun> {
+            self.create(import_run).await
+        }
+        fn get_by_id(&self, id: &str) -> Result<Option<ImportRun>> {
+            Ok(self.runs.lock().unwrap().get(id).cloned())
+        }
+        fn get_recent_for_account(&self, _account_id: &str, _limit: i64) -> Result<Vec<ImportRun>> {
+            unimplemented!()
+        }
+    }
+
+    // --- Mock SnapshotService ---
+    /// Adds BUY quantities and DEPOSIT amounts on top of `latest`, and warns on SELLs.
+    #[derive(Default)]
+    struct MockSnapshotService {
+        latest: Option<AccountStateSnapshot>,
+        previewed: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl SnapshotServiceTrait for MockSnapshotService {
+        async fn calculate_holdings_snapshots(
+            &self,
+            _account_ids: Option<&[String]>,
+        ) -> Result<usize> {
+            unimplemented!()
+        }
+        async fn force_recalculate_holdings_snapshots(
+            &self,
+            _account_ids: Option<&[String]>,
+        ) -> Result<usize> {
+            unimplemented!()
+        }
+        fn get_holdings_keyframes(
+            &self,
+            _account_id: &str,
+            _start_date: Option<NaiveDate>,
+            _end_date: Option<NaiveDate>,
+        ) -> Result<Vec<AccountStateSnapshot>> {
+            unimplemented!()
+        }
+        fn get_daily_holdings_snapshots(
+            &self,
+            _account_id: &str,
+            _start_date: Option<NaiveDate>,
+            _end_date: Option<NaiveDate>,
+        ) -> Result<Vec<AccountStateSnapshot>> {
+            unimplemented!()
+        }
+        fn get_latest_holdings_snapshot(
+            &self,
+            _account_id: &str,
+        ) -> Result<Option<AccountStateSnapshot>> {
+            Ok(self.latest.clone())
+        }
+        fn preview_holdings_with_activities(
+            &self,
+            account_id: &str,
+            activities: &[Activity],
+        ) -> Result<HoldingsCalculationResult> {
+            let mut result =
+                HoldingsCalculationResult::new(self.latest.clone().unwrap_or_default());
+            for activity in activities {
+                self.previewed.lock().unwrap().push(activity.id.clone());
+                match activity.activity_type.as_str() {
+                    "BUY" => {
+                        let asset_id = activity.asset_id.clone().unwrap();
+                        let position = result
+                            .snapshot
+                            .positions
+                            .entry(asset_id.clone())
+                            .or_insert_with(|| Position {
+                                asset_id,
+                                currency: activity.currency.clone(),
+                                ..Default::default()
+                            });
+                        position.quantity += activity.quantity.unwrap_or_default();
+                    }
+                    "DEPOSIT" => {
+                        *result
+                            .snapshot
+                            .cash_balances
+                            .entry(activity.currency.clone())
+                            .or_default() += activity.amount.unwrap_or_default();
+                    }
+                    _ => result.warnings.push(HoldingsCalculationWarning {
+                        activity_id: activity.id.clone(),
+                        account_id: account_id.to_string(),
+                        date: activity.effective_date(),
+                        message: "Insufficient quantity".to_string(),
+                    }),
+                }
+            }
+            Ok(result)
+        }
+        async fn calculate_total_portfolio_snapshots(&self) -> Result<usize> {
+            unimplemented!()
+        }
+        async fn force_recalculate_total_portfolio_snapshots(&self) -> Result<usize> {
+            unimplemented!()
+        }
+        async fn save_manual_snapshot(
+            &self,
+            _account_id: &str,
+            _snapshot: AccountStateSnapshot,
+        ) -> Result<()> {
+            unimplemented!()
+        }
+        async fn update_snapshots_source(
+            &self,
+            _account_id: &str,
+            _new_source: &str,
+        ) -> Result<usize> {
+            unimplemented!()
+        }
+        async fn ensure_holdings_history(&self, _account_id: &str) -> Result<()> {
+            unimplemented!()
+        }
+    }
+
+    struct Fixture {
+        service: ActivityStagingService,
+        activities: Arc<MockActivityService>,
+        staged: Arc<MockStagedRepository>,
+        runs: Arc<MockImportRunRepository>,
+        snapshots: Arc<MockSnapshotService>,
+    }
+
+    fn fixture(latest: Option<AccountStateSnapshot>) -> Fixture {
+        let activities = Arc::new(MockActivityService::default());
+        let staged = Arc::new(MockStagedRepository::default());
+        let runs = Arc::new(MockImportRunRepository::default());
+        let snapshots = Arc::new(MockSnapshotService {
+            latest,
+            ..Default::default()
+        });
+        Fixture {
+            service: ActivityStagingService::new(
+                staged.clone(),
+                runs.clone(),
+                activities.clone(),
+                snapshots.clone(),
+            ),
+            activities,
+            staged,
+            runs,
+            snapshots,
+        }
+    }
+
+    async fn new_run(fixture: &Fixture) -> String {
+        let mut run = ImportRun::new(
+            "acc-1".to_string(),
+            "snaptrade".to_string(),
+            ImportRunType::Sync,
+            ImportRunMode::Incremental,
+            ReviewMode::Always,
+        );
+        run.mark_needs_review();
+        fixture.runs.create(run).await.unwrap().id
+    }
+
+    fn upsert(id: &str, activity_type: &str, key: &str) -> ActivityUpsert {
+        let is_buy = activity_type == "BUY";
+        ActivityUpsert {
+            id: id.to_string(),
+            account_id: "acc-1".to_string(),
+            asset_id: is_buy.then(|| "AAPL".to_string()),
+            activity_type: activity_type.to_string(),
+            subtype: None,
+            activity_date: "2025-03-10T00:00:00Z".to_string(),
+            quantity: is_buy.then_some(dec!(10)),
+            unit_price: is_buy.then_some(dec!(150)),
+            currency: "USD".to_string(),
+            fee: None,
+            amount: Some(dec!(1500)),
+            status: None,
+            notes: None,
+            fx_rate: None,
+            metadata: None,
+            needs_review: None,
+            source_system: Some("SNAPTRADE".to_string()),
+            source_record_id: Some(id.to_string()),
+            source_group_id: None,
+            idempotency_key: Some(key.to_string()),
+            import_run_id: None,
+        }
+    }
+
+    fn staged_id(fixture: &Fixture, activity_id: &str) -> String {
+        fixture
+            .staged
+            .rows
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|r| r.activity.id == activity_id)
+            .map(|r| r.id.clone())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_stage_skips_rejected_existing_and_pending_keys() {
+        let fixture = fixture(None);
+        fixture
+            .activities
+            .existing_keys
+            .lock()
+            .unwrap()
+            .insert("k-ledger".to_string(), "existing".to_string());
+        fixture
+            .staged
+            .save_rejected_keys(vec![RejectedActivityKey {
+                account_id: "acc-1".to_string(),
+                idempotency_key: "k-rejected".to_string(),
+                import_run_id: "old-run".to_string(),
+                rejected_at: Utc::now(),
+            }])
+            .await
+            .unwrap();
+
+        let first_run = new_run(&fixture).await;
+        let result = fixture
+            .service
+            .stage_activities(
+                &first_run,
+                "acc-1",
+                vec![
+                    upsert("a1", "BUY", "k-new"),
+                    upsert("a2", "BUY", "k-ledger"),
+                    upsert("a3", "BUY", "k-rejected"),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.staged, 1);
+        assert_eq!(result.already_known, 1);
+        assert_eq!(result.previously_rejected, 1);
+
+        // Still awaiting review, so a second sync does not propose it again
+        let second_run = new_run(&fixture).await;
+        let result = fixture
+            .service
+            .stage_activities(&second_run, "acc-1", vec![upsert("a1", "BUY", "k-new")])
+            .await
+            .unwrap();
+        assert_eq!(result.staged, 0);
+        assert_eq!(result.already_known, 1);
+
+        let staged = fixture.staged.list_for_run(&first_run).unwrap();
+        assert_eq!(
+            staged[0].activity.import_run_id.as_deref(),
+            Some(&*first_run)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_run_writes_included_rows_and_remembers_rejections() {
+        let fixture = fixture(None);
+        let run_id = new_run(&fixture).await;
+        fixture
+            .service
+            .stage_activities(
+                &run_id,
+                "acc-1",
+                vec![upsert("a1", "BUY", "k1"), upsert("a2", "DEPOSIT", "k2")],
+            )
+            .await
+            .unwrap();
+
+        fixture
+            .service
+            .set_decision(&staged_id(&fixture, "a2"), StagedActivityDecision::Reject)
+            .await
+            .unwrap();
+
+        let run = fixture.service.apply_run(&run_id).await.unwrap();
+        assert_eq!(run.status, ImportRunStatus::Applied);
+        assert_eq!(run.summary.as_ref().unwrap().inserted, 1);
+        assert_eq!(run.summary.as_ref().unwrap().skipped, 1);
+
+        let upserted = fixture.activities.upserted.lock().unwrap().clone();
+        assert_eq!(upserted.len(), 1);
+        assert_eq!(upserted[0].id, "a1");
+
+        let statuses: Vec<_> = fixture
+            .staged
+            .list_for_run(&run_id)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.status)
+            .collect();
+        assert!(statuses.contains(&StagedActivityStatus::Applied));
+        assert!(statuses.contains(&StagedActivityStatus::Rejected));
+
+        // The rejected row is not proposed by the next sync
+        let next_run = new_run(&fixture).await;
+        let result = fixture
+            .service
+            .stage_activities(&next_run, "acc-1", vec![upsert("a2", "DEPOSIT", "k2")])
+            .await
+            .unwrap();
+        assert_eq!(result.staged, 0);
+        assert_eq!(result.previously_rejected, 1);
+
+        // Applied runs are closed for review
+        assert!(fixture.service.apply_run(&run_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_edit_keeps_identity_and_original_key() {
+        let fixture = fixture(None);
+        let run_id = new_run(&fixture).await;
+        fixture
+            .service
+            .stage_activities(&run_id, "acc-1", vec![upsert("a1", "BUY", "k1")])
+            .await
+            .unwrap();
+
+        let mut edited = upsert("other-id", "BUY", "other-key");
+        edited.quantity = Some(dec!(12));
+        let row = fixture
+            .service
+            .update_staged_activity(&staged_id(&fixture, "a1"), edited)
+            .await
+            .unwrap();
+
+        assert!(row.edited);
+        assert_eq!(row.status, StagedActivityStatus::Accepted);
+        assert_eq!(row.activity.id, "a1");
+        assert_eq!(row.activity.quantity, Some(dec!(12)));
+        assert_eq!(row.activity.idempotency_key.as_deref(), Some("k1"));
+        assert_eq!(row.idempotency_key, "k1");
+
+        let mut bad_date = upsert("a1", "BUY", "k1");
+        bad_date.activity_date = "yesterday".to_string();
+        assert!(fixture
+            .service
+            .update_staged_activity(&row.id, bad_date)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_review_reports_impact_of_included_rows() {
+        let mut latest = AccountStateSnapshot {
+            account_id: "acc-1".to_string(),
+            currency: "USD".to_string(),
+            ..Default::default()
+        };
+        latest.cash_balances.insert("USD".to_string(), dec!(100));
+        latest.cash_balances.insert("EUR".to_string(), dec!(50));
+        let fixture = fixture(Some(latest));
+
+        let run_id = new_run(&fixture).await;
+        fixture
+            .service
+            .stage_activities(
+                &run_id,
+                "acc-1",
+                vec![
+                    upsert("a1", "BUY", "k1"),
+                    upsert("a2", "DEPOSIT", "k2"),
+                    upsert("a3", "SELL", "k3"),
+                ],
+            )
+            .await
+            .unwrap();
+        fixture
+            .service
+            .set_decision(&staged_id(&fixture, "a3"), StagedActivityDecision::Reject)
+            .await
+            .unwrap();
+
+        let review = fixture.service.get_review(&run_id).unwrap();
+        assert_eq!(review.activities.len(), 3);
+        assert!(!review.has_warnings());
+
+        let impact = review.impact.unwrap();
+        assert_eq!(impact.cash_before.get("USD"), Some(&dec!(100)));
+        assert_eq!(impact.cash_after.get("USD"), Some(&dec!(1600)));
+        assert!(!impact.cash_after.contains_key("EUR"));
+        assert_eq!(impact.positions.len(), 1);
+        assert_eq!(impact.positions[0].asset_id, "AAPL");
+        assert_eq!(impact.positions[0].quantity_before, Decimal::ZERO);
+        assert_eq!(impact.positions[0].quantity_after, dec!(10));
+
+        // Rejected rows are left out of the dry run
+        assert!(!fixture
+            .snapshots
+            .previewed
+            .lock()
+            .unwrap()
+            .contains(&"a3".to_string()));
+
+        fixture
+            .service
+            .set_decision(&staged_id(&fixture, "a3"), StagedActivityDecision::Accept)
+            .await
+            .unwrap();
+        assert!(fixture.service.get_review(&run_id).unwrap().has_warnings());
+    }
+
+    #[tokio::test]
+    async fn test_discard_run_rejects_everything() {
+        let fixture = fixture(None);
+        let run_id = new_run(&fixture).await;
+        fixture
+            .service
+            .stage_activities(
+                &run_id,
+                "acc-1",
+                vec![upsert("a1", "BUY", "k1"), upsert("a2", "DEPOSIT", "k2")],
+            )
+            .await
+            .unwrap();
+
+        let run = fixture.service.discard_run(&run_id).await.unwrap();
+        assert_eq!(run.status, ImportRunStatus::Cancelled);
+        assert!(fixture.activities.upserted.lock().unwrap().is_empty());
+        assert_eq!(fixture.staged.get_rejected_keys("acc-1").unwrap().len(), 2);
+
+        let review = fixture.service.get_review(&run_id).unwrap();
+        assert!(review.impact.is_none());
+        assert!(review
+            .activities
+            .iter()
+            .all(|r| r.status == StagedActivityStatus::Rejected));
+        assert!(fixture
+            .service
+            .set_decision(&review.activities[0].id, StagedActivityDecision::Accept)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::HashSet;
+
+use super::activities_model::ActivityUpsert;
+use super::import_run_model::ImportRun;
+use super::staging_model::*;
+use crate::Result;
+use async_trait::async_trait;
+
+/// Persistence for staged activity rows and remembered rejections.
+#[async_trait]
+pub trait StagedActivityRepositoryTrait: Send + Sync {
+    async fn insert_staged(&self, rows: Vec<StagedActivity>) -> Result<usize>;
+    fn get_staged(&self, id: &str) -> Result<Option<StagedActivity>>;
+    fn list_for_run(&self, import_run_id: &str) -> Result<Vec<StagedActivity>>;
+    async fn update_staged(&self, rows: Vec<StagedActivity>) -> Result<()>;
+
+    /// Idempotency keys of rows still awaiting review (pending or accepted) for an account.
+    fn get_pending_keys(&self, account_id: &str) -> Result<HashSet<String>>;
+
+    fn get_rejected_keys(&self, account_id: &str) -> Result<HashSet<String>>;
+    async fn save_rejected_keys(&self, keys: Vec<RejectedActivityKey>) -> Result<()>;
+}
+
+/// Trait defining the contract for reviewing import runs before they reach the ledger.
+#[async_trait]
+pub trait ActivityStagingServiceTrait: Send + Sync {
+    /// Stages activities under an import run, skipping rows the user rejected
+    /// before and rows already in the ledger or awaiting review.
+    async fn stage_activities(
+        &self,
+        import_run_id: &str,
+        account_id: &str,
+        activities: Vec<ActivityUpsert>,
+    ) -> Result<StageActivitiesResult>;
+
+    /// Returns the run, its staged rows and the holdings/cash impact of the
+    /// included rows.
+    fn get_review(&self, import_run_id: &str) -> Result<ImportRunReview>;
+
+    /// Replaces the payload of a staged row and marks it accepted.
+    async fn update_staged_activity(
+        &self,
+        staged_id: &str,
+        activity: ActivityUpsert,
+    ) -> Result<StagedActivity>;
+
+    async fn set_decision(
+        &self,
+        staged_id: &str,
+        decision: StagedActivityDecision,
+    ) -> Result<StagedActivity>;
+
+    /// Writes all non-rejected rows to the ledger, remembers rejected keys and
+    /// marks the run applied.
+    async fn apply_run(&self, import_run_id: &str) -> Result<ImportRun>;
+
+    /// Rejects every remaining row and cancels the run.
+    async fn discard_run(&self, import_run_id: &str) -> Result<ImportRun>;
+}
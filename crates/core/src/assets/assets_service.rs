@@ -2,6 +2,7 @@ use log::{debug, error, info, warn};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use crate::audit::{ChangeContext, ChangeLogRepositoryTrait, NewChangeLogEntry};
 use crate::events::{DomainEvent, DomainEventSink, NoOpDomainEventSink};
 use crate::quotes::QuoteServiceTrait;
 use crate::taxonomies::TaxonomyServiceTrait;
@@ -42,6 +43,7 @@ pub struct AssetService {
     asset_repository: Arc<dyn AssetRepositoryTrait>,
     taxonomy_service: Option<Arc<dyn TaxonomyServiceTrait>>,
     event_sink: Arc<dyn DomainEventSink>,
+    change_log: Option<Arc<dyn ChangeLogRepositoryTrait>>,
 }
 
 impl AssetService {
@@ -220,6 +222,7 @@ impl AssetService {
             asset_repository,
             taxonomy_service: None,
             event_sink: Arc::new(NoOpDomainEventSink),
+            change_log: None,
         })
     }
 
@@ -234,6 +237,7 @@ impl AssetService {
             asset_repository,
             taxonomy_service: Some(taxonomy_service),
             event_sink: Arc::new(NoOpDomainEventSink),
+            change_log: None,
         })
     }
 
@@ -245,6 +249,24 @@ impl AssetService {
         self
     }
 
+    /// Sets the change log that records user edits to assets.
+    pub fn with_change_log(mut self, change_log: Arc<dyn ChangeLogRepositoryTrait>) -> Self {
+        self.change_log = Some(change_log);
+        self
+    }
+
+    /// Appends an asset change to the change log, logging failures.
+    async fn record_change(&self, before: Option<&Asset>, after: Option<&Asset>) {
+        let Some(change_log) = self.change_log.as_ref() else {
+            return;
+        };
+        if let Some(entry) = NewChangeLogEntry::asset(before, after, &ChangeContext::manual()) {
+            if let Err(e) = change_log.append(vec![entry]).await {
+                warn!("Failed to record asset change: {}", e);
+            }
+        }
+    }
+
     /// Builds a NewAsset from an AssetSpec without any I/O.
     fn new_asset_from_spec(&self, spec: &AssetSpec) -> NewAsset {
         let canonical = canonicalize_market_identity(
@@ -333,7 +355,10 @@ impl AssetServiceTrait for AssetService {
             warn!("Failed to delete sync state for {}: {}", asset_id, e);
         }
 
-        self.asset_repository.delete(asset_id).await
+        let existing = self.asset_repository.get_by_id(asset_id).ok();
+        self.asset_repository.delete(asset_id).await?;
+        self.record_change(existing.as_ref(), None).await;
+        Ok(())
     }
 
     /// Updates an asset profile
@@ -410,6 +435,8 @@ impl AssetServiceTrait for AssetService {
             .asset_repository
             .update_profile(asset_id, payload)
             .await?;
+        self.record_change(Some(&existing_asset), Some(&asset))
+            .await;
 
         self.event_sink
             .emit(DomainEvent::assets_updated(vec![asset.id.clone()]));
@@ -453,6 +480,7 @@ impl AssetServiceTrait for AssetService {
             .unwrap_or(new_asset.quote_ccy);
 
         let asset = self.asset_repository.create(new_asset).await?;
+        self.record_change(None, Some(&asset)).await;
 
         // Emit event for newly created asset
         self.event_sink
@@ -814,10 +842,12 @@ impl AssetServiceTrait for AssetService {
 
     /// Updates the quote mode for an asset (MARKET, MANUAL)
     async fn update_quote_mode(&self, asset_id: &str, quote_mode: &str) -> Result<Asset> {
+        let existing = self.asset_repository.get_by_id(asset_id).ok();
         let asset = self
             .asset_repository
             .update_quote_mode(asset_id, quote_mode)
             .await?;
+        self.record_change(existing.as_ref(), Some(&asset)).await;
 
         self.event_sink
             .emit(DomainEvent::assets_updated(vec![asset.id.clone()]));
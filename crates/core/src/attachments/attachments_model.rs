@@ -0,0 +1,69 @@
+//! Domain models for attachments.
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Largest file accepted as an attachment (25 MiB).
+pub const ATTACHMENT_MAX_SIZE_BYTES: usize = 25 * 1024 * 1024;
+
+/// Kind of entity an attachment belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AttachmentEntityType {
+    Activity,
+    Asset,
+    Account,
+}
+
+impl AttachmentEntityType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttachmentEntityType::Activity => "ACTIVITY",
+            AttachmentEntityType::Asset => "ASSET",
+            AttachmentEntityType::Account => "ACCOUNT",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "ACTIVITY" => Some(AttachmentEntityType::Activity),
+            "ASSET" => Some(AttachmentEntityType::Asset),
+            "ACCOUNT" => Some(AttachmentEntityType::Account),
+            _ => None,
+        }
+    }
+}
+
+/// Metadata of a stored attachment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    pub id: String,
+    pub entity_type: AttachmentEntityType,
+    pub entity_id: String,
+    pub file_name: String,
+    pub content_type: Option<String>,
+    pub size_bytes: i64,
+    /// Lowercase hex SHA-256 of the file contents; the blob store key
+    pub content_hash: String,
+    pub notes: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+/// Input model for adding an attachment. The file contents are passed
+/// separately.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewAttachment {
+    pub entity_type: AttachmentEntityType,
+    pub entity_id: String,
+    pub file_name: String,
+    pub content_type: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// Computes the content address of a file.
+pub fn content_hash(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
@@ -0,0 +1,143 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use log::warn;
+use rand::RngCore;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::attachments_model::*;
+use super::attachments_traits::{
+    AttachmentBlobStoreTrait, AttachmentRepositoryTrait, AttachmentServiceTrait,
+};
+use crate::errors::{Error, Result, ValidationError};
+use crate::secrets::SecretStore;
+
+/// Secret store entry holding the attachment encryption key.
+const ENCRYPTION_KEY_SECRET: &str = "attachments_encryption_key";
+
+/// Loads the attachment encryption key from the secret store, generating and
+/// saving a new one on first use.
+pub fn load_or_create_encryption_key(secret_store: &dyn SecretStore) -> Result<[u8; 32]> {
+    if let Some(encoded) = secret_store.get_secret(ENCRYPTION_KEY_SECRET)? {
+        let bytes = hex::decode(encoded.trim())
+            .map_err(|e| Error::Secret(format!("Invalid attachment key: {}", e)))?;
+        return bytes
+            .try_into()
+            .map_err(|_| Error::Secret("Attachment key must be 32 bytes".to_string()));
+    }
+
+    let mut key = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut key);
+    secret_store.set_secret(ENCRYPTION_KEY_SECRET, &hex::encode(key))?;
+    Ok(key)
+}
+
+fn invalid(message: &str) -> Error {
+    Error::Validation(ValidationError::InvalidInput(message.to_string()))
+}
+
+/// Service for managing attachments
+pub struct AttachmentService {
+    repository: Arc<dyn AttachmentRepositoryTrait>,
+    blob_store: Arc<dyn AttachmentBlobStoreTrait>,
+}
+
+impl AttachmentService {
+    pub fn new(
+        repository: Arc<dyn AttachmentRepositoryTrait>,
+        blob_store: Arc<dyn AttachmentBlobStoreTrait>,
+    ) -> Self {
+        Self {
+            repository,
+            blob_store,
+        }
+    }
+}
+
+#[async_trait]
+impl AttachmentServiceTrait for AttachmentService {
+    fn list_attachments(
+        &self,
+        entity_type: AttachmentEntityType,
+        entity_id: &str,
+    ) -> Result<Vec<Attachment>> {
+        self.repository.list_attachments(entity_type, entity_id)
+    }
+
+    async fn add_attachment(&self, attachment: NewAttachment, data: Vec<u8>) -> Result<Attachment> {
+        if attachment.entity_id.trim().is_empty() {
+            return Err(invalid("Attachment entity id is required"));
+        }
+        // Keep only the final path component of client-supplied names
+        let file_name = attachment
+            .file_name
+            .rsplit(&['/', '\\'][..])
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        if file_name.is_empty() {
+            return Err(invalid("Attachment file name is required"));
+        }
+        if data.is_empty() {
+            return Err(invalid("Attachment file is empty"));
+        }
+        if data.len() > ATTACHMENT_MAX_SIZE_BYTES {
+            return Err(invalid(&format!(
+                "Attachment exceeds the {} MiB limit",
+                ATTACHMENT_MAX_SIZE_BYTES / (1024 * 1024)
+            )));
+        }
+
+        let hash = content_hash(&data);
+        if !self.blob_store.exists(&hash) {
+            self.blob_store.put(&hash, &data)?;
+        }
+
+        self.repository
+            .insert_attachment(Attachment {
+                id: Uuid::new_v4().to_string(),
+                entity_type: attachment.entity_type,
+                entity_id: attachment.entity_id,
+                file_name,
+                content_type: attachment.content_type.filter(|c| !c.trim().is_empty()),
+                size_bytes: data.len() as i64,
+                content_hash: hash,
+                notes: attachment.notes,
+                created_at: Utc::now().naive_utc(),
+            })
+            .await
+    }
+
+    fn get_attachment_content(&self, attachment_id: &str) -> Result<(Attachment, Vec<u8>)> {
+        let attachment = self.repository.get_attachment(attachment_id)?;
+        let data = self.blob_store.get(&attachment.content_hash)?;
+        if content_hash(&data) != attachment.content_hash {
+            return Err(Error::Unexpected(format!(
+                "Attachment {} failed its integrity check",
+                attachment.id
+            )));
+        }
+        Ok((attachment, data))
+    }
+
+    async fn delete_attachment(&self, attachment_id: &str) -> Result<()> {
+        let attachment = self.repository.get_attachment(attachment_id)?;
+        self.repository.delete_attachment(attachment_id).await?;
+
+        // Files are shared between attachments with identical contents
+        if self
+            .repository
+            .count_by_content_hash(&attachment.content_hash)?
+            == 0
+        {
+            if let Err(e) = self.blob_store.delete(&attachment.content_hash) {
+                warn!(
+                    "Failed to remove attachment file {}: {}",
+                    attachment.content_hash, e
+                );
+            }
+        }
+        Ok(())
+    }
+}
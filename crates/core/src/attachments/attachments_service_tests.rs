@@ -0,0 +1,226 @@
+#[cfg(test)]
+mod tests {
+    use crate::attachments::{
+        content_hash, Attachment, AttachmentBlobStoreTrait, AttachmentEntityType,
+        AttachmentRepositoryTrait, AttachmentService, AttachmentServiceTrait, NewAttachment,
+        ATTACHMENT_MAX_SIZE_BYTES,
+    };
+    use crate::errors::{DatabaseError, Error, Result};
+    use async_trait::async_trait;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    // --- Mock AttachmentRepository ---
+    #[derive(Default)]
+    struct MockAttachmentRepository {
+        attachments: Mutex<Vec<Attachment>>,
+    }
+
+    #[async_trait]
+    impl AttachmentRepositoryTrait for MockAttachmentRepository {
+        fn get_attachment(&self, attachment_id: &str) -> Result<Attachment> {
+            self.attachments
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|a| a.id == attachment_id)
+                .cloned()
+                .ok_or_else(|| Error::Database(DatabaseError::NotFound(attachment_id.to_string())))
+        }
+
+        fn list_attachments(
+            &self,
+            entity_type: AttachmentEntityType,
+            entity_id: &str,
+        ) -> Result<Vec<Attachment>> {
+            Ok(self
+                .attachments
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|a| a.entity_type == entity_type && a.entity_id == entity_id)
+                .cloned()
+                .collect())
+        }
+
+        fn count_by_content_hash(&self, content_hash: &str) -> Result<i64> {
+            Ok(self
+                .attachments
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|a| a.content_hash == content_hash)
+                .count() as i64)
+        }
+
+        async fn insert_attachment(&self, attachment: Attachment) -> Result<Attachment> {
+            self.attachments.lock().unwrap().push(attachment.clone());
+            Ok(attachment)
+        }
+
+        async fn delete_attachment(&self, attachment_id: &str) -> Result<usize> {
+            let mut attachments = self.attachments.lock().unwrap();
+            let before = attachments.len();
+            attachments.retain(|a| a.id != attachment_id);
+            Ok(before - attachments.len())
+        }
+    }
+
+    // --- Mock AttachmentBlobStore ---
+    #[derive(Default)]
+    struct MockBlobStore {
+        blobs: Mutex<HashMap<String, Vec<u8>>>,
+        puts: Mutex<usize>,
+    }
+
+    impl AttachmentBlobStoreTrait for MockBlobStore {
+        fn exists(&self, content_hash: &str) -> bool {
+            self.blobs.lock().unwrap().contains_key(content_hash)
+        }
+
+        fn put(&self, content_hash: &str, data: &[u8]) -> Result<()> {
+            *self.puts.lock().unwrap() += 1;
+            self.blobs
+                .lock()
+                .unwrap()
+                .insert(content_hash.to_string(), data.to_vec());
+            Ok(())
+        }
+
+        fn get(&self, content_hash: &str) -> Result<Vec<u8>> {
+            self.blobs
+                .lock()
+                .unwrap()
+                .get(content_hash)
+                .cloned()
+                .ok_or_else(|| Error::Unexpected(format!("Missing blob {}", content_hash)))
+        }
+
+        fn delete(&self, content_hash: &str) -> Result<()> {
+            self.blobs.lock().unwrap().remove(content_hash);
+            Ok(())
+        }
+    }
+
+    fn setup() -> (
+        AttachmentService,
+        Arc<MockAttachmentRepository>,
+        Arc<MockBlobStore>,
+    ) {
+        let repo = Arc::new(MockAttachmentRepository::default());
+        let blobs = Arc::new(MockBlobStore::default());
+        let service = AttachmentService::new(repo.clone(), blobs.clone());
+        (service, repo, blobs)
+    }
+
+    fn new_attachment(entity_id: &str, file_name: &str) -> NewAttachment {
+        NewAttachment {
+            entity_type: AttachmentEntityType::Activity,
+            entity_id: entity_id.to_string(),
+            file_name: file_name.to_string(),
+            content_type: Some("application/pdf".to_string()),
+            notes: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_add_attachment_stores_content_addressed_blob() {
+        let (service, _repo, blobs) = setup();
+        let data = b"contract note".to_vec();
+
+        let attachment = service
+            .add_attachment(new_attachment("act-1", "note.pdf"), data.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(attachment.content_hash, content_hash(&data));
+        assert_eq!(attachment.size_bytes, data.len() as i64);
+        assert!(blobs.exists(&attachment.content_hash));
+
+        let (loaded, content) = service.get_attachment_content(&attachment.id).unwrap();
+        assert_eq!(loaded, attachment);
+        assert_eq!(content, data);
+    }
+
+    #[tokio::test]
+    async fn test_identical_files_share_one_blob() {
+        let (service, _repo, blobs) = setup();
+        let data = b"dividend advice".to_vec();
+
+        let first = service
+            .add_attachment(new_attachment("act-1", "advice.pdf"), data.clone())
+            .await
+            .unwrap();
+        let second = service
+            .add_attachment(new_attachment("act-2", "advice.pdf"), data)
+            .await
+            .unwrap();
+
+        assert_eq!(first.content_hash, second.content_hash);
+        assert_eq!(*blobs.puts.lock().unwrap(), 1);
+
+        // The blob survives until the last reference is gone
+        service.delete_attachment(&first.id).await.unwrap();
+        assert!(blobs.exists(&second.content_hash));
+        service.delete_attachment(&second.id).await.unwrap();
+        assert!(!blobs.exists(&second.content_hash));
+    }
+
+    #[tokio::test]
+    async fn test_add_attachment_strips_directories_from_file_name() {
+        let (service, _repo, _blobs) = setup();
+
+        let attachment = service
+            .add_attachment(
+                new_attachment("act-1", "C:\\Users\\me\\../deed.pdf"),
+                b"deed".to_vec(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(attachment.file_name, "deed.pdf");
+    }
+
+    #[tokio::test]
+    async fn test_add_attachment_rejects_invalid_input() {
+        let (service, repo, _blobs) = setup();
+
+        let empty = service
+            .add_attachment(new_attachment("act-1", "empty.pdf"), Vec::new())
+            .await;
+        assert!(matches!(empty, Err(Error::Validation(_))));
+
+        let no_entity = service
+            .add_attachment(new_attachment(" ", "note.pdf"), b"x".to_vec())
+            .await;
+        assert!(matches!(no_entity, Err(Error::Validation(_))));
+
+        let too_large = service
+            .add_attachment(
+                new_attachment("act-1", "huge.pdf"),
+                vec![0u8; ATTACHMENT_MAX_SIZE_BYTES + 1],
+            )
+            .await;
+        assert!(matches!(too_large, Err(Error::Validation(_))));
+
+        assert!(repo.attachments.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_attachment_content_detects_corruption() {
+        let (service, _repo, blobs) = setup();
+        let attachment = service
+            .add_attachment(new_attachment("act-1", "note.pdf"), b"original".to_vec())
+            .await
+            .unwrap();
+
+        blobs
+            .blobs
+            .lock()
+            .unwrap()
+            .insert(attachment.content_hash.clone(), b"tampered".to_vec());
+
+        let result = service.get_attachment_content(&attachment.id);
+        assert!(matches!(result, Err(Error::Unexpected(_))));
+    }
+}
@@ -0,0 +1,47 @@
+use async_trait::async_trait;
+
+use super::attachments_model::{Attachment, AttachmentEntityType, NewAttachment};
+use crate::errors::Result;
+
+/// Trait for attachment metadata persistence
+#[async_trait]
+pub trait AttachmentRepositoryTrait: Send + Sync {
+    fn get_attachment(&self, attachment_id: &str) -> Result<Attachment>;
+    /// Attachments of one entity, newest first.
+    fn list_attachments(
+        &self,
+        entity_type: AttachmentEntityType,
+        entity_id: &str,
+    ) -> Result<Vec<Attachment>>;
+    /// Number of attachments referencing the given content hash.
+    fn count_by_content_hash(&self, content_hash: &str) -> Result<i64>;
+    async fn insert_attachment(&self, attachment: Attachment) -> Result<Attachment>;
+    async fn delete_attachment(&self, attachment_id: &str) -> Result<usize>;
+}
+
+/// Trait for content-addressed file storage
+pub trait AttachmentBlobStoreTrait: Send + Sync {
+    fn exists(&self, content_hash: &str) -> bool;
+    fn put(&self, content_hash: &str, data: &[u8]) -> Result<()>;
+    fn get(&self, content_hash: &str) -> Result<Vec<u8>>;
+    fn delete(&self, content_hash: &str) -> Result<()>;
+}
+
+/// Trait for attachment service operations
+#[async_trait]
+pub trait AttachmentServiceTrait: Send + Sync {
+    fn list_attachments(
+        &self,
+        entity_type: AttachmentEntityType,
+        entity_id: &str,
+    ) -> Result<Vec<Attachment>>;
+
+    /// Stores the file (once per distinct content) and links it to the entity.
+    async fn add_attachment(&self, attachment: NewAttachment, data: Vec<u8>) -> Result<Attachment>;
+
+    /// Returns the attachment metadata with its decrypted contents.
+    fn get_attachment_content(&self, attachment_id: &str) -> Result<(Attachment, Vec<u8>)>;
+
+    /// Removes the attachment, and its file once no attachment references it.
+    async fn delete_attachment(&self, attachment_id: &str) -> Result<()>;
+}
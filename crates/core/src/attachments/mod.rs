@@ -0,0 +1,21 @@
+//! Attachments module - files (receipts, contract notes, statements, deeds)
+//! linked to activities, assets and accounts.
+//!
+//! File contents are content-addressed by SHA-256, so identical files are
+//! stored once and shared between attachments. The blob store encrypts them
+//! at rest with a per-installation key kept in the secret store.
+
+mod attachments_model;
+mod attachments_service;
+mod attachments_traits;
+
+#[cfg(test)]
+mod attachments_service_tests;
+
+pub use attachments_model::{
+    content_hash, Attachment, AttachmentEntityType, NewAttachment, ATTACHMENT_MAX_SIZE_BYTES,
+};
+pub use attachments_service::{load_or_create_encryption_key, AttachmentService};
+pub use attachments_traits::{
+    AttachmentBlobStoreTrait, AttachmentRepositoryTrait, AttachmentServiceTrait,
+};
@@ -0,0 +1,228 @@
+//! Domain models for the activity and asset change log.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::activities::Activity;
+use crate::assets::Asset;
+use crate::recurring::RECURRING_SOURCE_SYSTEM;
+
+/// Source system set on activities created from AI assistant drafts.
+pub const AI_ASSISTANT_SOURCE_SYSTEM: &str = "AI_ASSISTANT";
+
+/// Kind of entity a change log entry refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ChangeEntityType {
+    Activity,
+    Asset,
+}
+
+impl ChangeEntityType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeEntityType::Activity => "ACTIVITY",
+            ChangeEntityType::Asset => "ASSET",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "ACTIVITY" => Some(ChangeEntityType::Activity),
+            "ASSET" => Some(ChangeEntityType::Asset),
+            _ => None,
+        }
+    }
+}
+
+/// Mutation recorded by a change log entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ChangeOperation {
+    Create,
+    Update,
+    Delete,
+}
+
+impl ChangeOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeOperation::Create => "CREATE",
+            ChangeOperation::Update => "UPDATE",
+            ChangeOperation::Delete => "DELETE",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "CREATE" => Some(ChangeOperation::Create),
+            "UPDATE" => Some(ChangeOperation::Update),
+            "DELETE" => Some(ChangeOperation::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// What caused a change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ChangeSource {
+    /// User edit through the app or API
+    #[default]
+    Manual,
+    /// CSV import; `source_ref` is the import run id
+    Import,
+    /// Broker sync; `source_ref` is the import run id
+    BrokerSync,
+    /// Confirmed AI assistant draft
+    AiTool,
+    /// Generated from a recurring activity template
+    Recurring,
+    /// Undo of an earlier change; `source_ref` is the undone change id
+    Rollback,
+}
+
+impl ChangeSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeSource::Manual => "MANUAL",
+            ChangeSource::Import => "IMPORT",
+            ChangeSource::BrokerSync => "BROKER_SYNC",
+            ChangeSource::AiTool => "AI_TOOL",
+            ChangeSource::Recurring => "RECURRING",
+            ChangeSource::Rollback => "ROLLBACK",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "IMPORT" => ChangeSource::Import,
+            "BROKER_SYNC" => ChangeSource::BrokerSync,
+            "AI_TOOL" => ChangeSource::AiTool,
+            "RECURRING" => ChangeSource::Recurring,
+            "ROLLBACK" => ChangeSource::Rollback,
+            _ => ChangeSource::Manual,
+        }
+    }
+}
+
+/// Maps an activity's `source_system` to the change source of a
+/// single-activity mutation.
+pub fn change_source_for_system(source_system: Option<&str>) -> ChangeSource {
+    match source_system {
+        Some(AI_ASSISTANT_SOURCE_SYSTEM) => ChangeSource::AiTool,
+        Some(RECURRING_SOURCE_SYSTEM) => ChangeSource::Recurring,
+        _ => ChangeSource::Manual,
+    }
+}
+
+/// Source attribution shared by all entries of one mutation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangeContext {
+    pub source: ChangeSource,
+    pub source_ref: Option<String>,
+}
+
+impl ChangeContext {
+    pub fn new(source: ChangeSource, source_ref: Option<String>) -> Self {
+        Self { source, source_ref }
+    }
+
+    pub fn manual() -> Self {
+        Self::default()
+    }
+}
+
+/// A recorded change. Entries are never modified or deleted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeLogEntry {
+    pub id: String,
+    pub entity_type: ChangeEntityType,
+    pub entity_id: String,
+    pub operation: ChangeOperation,
+    pub source: ChangeSource,
+    /// Import run id for imports and broker sync, undone change id for rollbacks
+    pub source_ref: Option<String>,
+    /// Owning account for activity changes
+    pub account_id: Option<String>,
+    /// Entity state before the change (None for creates)
+    pub before: Option<Value>,
+    /// Entity state after the change (None for deletes)
+    pub after: Option<Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Input model for appending a change log entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewChangeLogEntry {
+    pub entity_type: ChangeEntityType,
+    pub entity_id: String,
+    pub operation: ChangeOperation,
+    pub source: ChangeSource,
+    pub source_ref: Option<String>,
+    pub account_id: Option<String>,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+impl NewChangeLogEntry {
+    /// Builds an entry for an activity change. The operation is derived
+    /// from which states are present.
+    pub fn activity(
+        before: Option<&Activity>,
+        after: Option<&Activity>,
+        context: &ChangeContext,
+    ) -> Option<Self> {
+        let current = after.or(before)?;
+        Some(Self {
+            entity_type: ChangeEntityType::Activity,
+            entity_id: current.id.clone(),
+            operation: operation_for(before.is_some(), after.is_some()),
+            source: context.source,
+            source_ref: context.source_ref.clone(),
+            account_id: Some(current.account_id.clone()),
+            before: before.and_then(|a| serde_json::to_value(a).ok()),
+            after: after.and_then(|a| serde_json::to_value(a).ok()),
+        })
+    }
+
+    /// Builds an entry for an asset change.
+    pub fn asset(
+        before: Option<&Asset>,
+        after: Option<&Asset>,
+        context: &ChangeContext,
+    ) -> Option<Self> {
+        let current = after.or(before)?;
+        Some(Self {
+            entity_type: ChangeEntityType::Asset,
+            entity_id: current.id.clone(),
+            operation: operation_for(before.is_some(), after.is_some()),
+            source: context.source,
+            source_ref: context.source_ref.clone(),
+            account_id: None,
+            before: before.and_then(|a| serde_json::to_value(a).ok()),
+            after: after.and_then(|a| serde_json::to_value(a).ok()),
+        })
+    }
+}
+
+fn operation_for(has_before: bool, has_after: bool) -> ChangeOperation {
+    match (has_before, has_after) {
+        (false, _) => ChangeOperation::Create,
+        (true, true) => ChangeOperation::Update,
+        (true, false) => ChangeOperation::Delete,
+    }
+}
+
+/// Outcome of rolling back one change or a whole import run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RollbackResult {
+    pub reverted: usize,
+    pub skipped: usize,
+    /// Why individual changes were skipped
+    pub warnings: Vec<String>,
+}
@@ -0,0 +1,406 @@
+use async_trait::async_trait;
+use log::{debug, warn};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use super::audit_model::*;
+use super::audit_traits::{ChangeLogRepositoryTrait, ChangeLogServiceTrait};
+use crate::activities::{
+    Activity, ActivityRepositoryTrait, ActivityUpdate, ActivityUpsert, SymbolInput,
+};
+use crate::assets::{Asset, AssetRepositoryTrait, NewAsset, UpdateAssetProfile};
+use crate::errors::{Error, Result, ValidationError};
+use crate::events::{DomainEvent, DomainEventSink, NoOpDomainEventSink};
+
+fn invalid(message: String) -> Error {
+    Error::Validation(ValidationError::InvalidInput(message))
+}
+
+/// Deserializes a recorded entity state.
+fn snapshot<T: DeserializeOwned>(entry: &ChangeLogEntry, state: &Option<Value>) -> Result<T> {
+    let value = state.clone().ok_or_else(|| {
+        Error::Unexpected(format!("Change {} has no recorded entity state", entry.id))
+    })?;
+    serde_json::from_value(value).map_err(|e| {
+        Error::Unexpected(format!(
+            "Change {} has an unreadable entity state: {}",
+            entry.id, e
+        ))
+    })
+}
+
+/// Fails if the entity changed after the logged change, so a rollback never
+/// silently discards later edits.
+fn ensure_unchanged<T: serde::Serialize>(entry: &ChangeLogEntry, current: &T) -> Result<()> {
+    let recorded = entry
+        .after
+        .as_ref()
+        .and_then(|after| after.get("updatedAt"));
+    let current = serde_json::to_value(current)
+        .ok()
+        .and_then(|value| value.get("updatedAt").cloned());
+    if recorded.is_some() && recorded == current.as_ref() {
+        Ok(())
+    } else {
+        Err(invalid(format!(
+            "{} {} was modified after this change",
+            entry.entity_type.as_str(),
+            entry.entity_id
+        )))
+    }
+}
+
+fn update_from_snapshot(activity: &Activity) -> ActivityUpdate {
+    ActivityUpdate {
+        id: activity.id.clone(),
+        account_id: activity.account_id.clone(),
+        symbol: activity.asset_id.as_ref().map(|asset_id| SymbolInput {
+            id: Some(asset_id.clone()),
+            ..Default::default()
+        }),
+        activity_type: activity.activity_type.clone(),
+        subtype: activity.subtype.clone(),
+        activity_date: activity.activity_date.to_rfc3339(),
+        quantity: Some(activity.quantity),
+        unit_price: Some(activity.unit_price),
+        currency: activity.currency.clone(),
+        fee: Some(activity.fee),
+        amount: Some(activity.amount),
+        status: Some(activity.status.clone()),
+        notes: activity.notes.clone(),
+        fx_rate: Some(activity.fx_rate),
+        metadata: activity.metadata.as_ref().map(|m| m.to_string()),
+    }
+}
+
+fn upsert_from_snapshot(activity: &Activity) -> ActivityUpsert {
+    ActivityUpsert {
+        id: activity.id.clone(),
+        account_id: activity.account_id.clone(),
+        asset_id: activity.asset_id.clone(),
+        activity_type: activity.activity_type.clone(),
+        subtype: activity.subtype.clone(),
+        activity_date: activity.activity_date.to_rfc3339(),
+        quantity: activity.quantity,
+        unit_price: activity.unit_price,
+        currency: activity.currency.clone(),
+        fee: activity.fee,
+        amount: activity.amount,
+        status: Some(activity.status.clone()),
+        notes: activity.notes.clone(),
+        fx_rate: activity.fx_rate,
+        metadata: activity.metadata.as_ref().map(|m| m.to_string()),
+        needs_review: Some(activity.needs_review),
+        source_system: activity.source_system.clone(),
+        source_record_id: activity.source_record_id.clone(),
+        source_group_id: activity.source_group_id.clone(),
+        idempotency_key: activity.idempotency_key.clone(),
+        import_run_id: activity.import_run_id.clone(),
+    }
+}
+
+fn profile_from_snapshot(asset: &Asset) -> UpdateAssetProfile {
+    UpdateAssetProfile {
+        name: asset.name.clone(),
+        display_code: asset.display_code.clone(),
+        notes: asset.notes.clone().unwrap_or_default(),
+        kind: Some(asset.kind.clone()),
+        quote_mode: Some(asset.quote_mode),
+        quote_ccy: Some(asset.quote_ccy.clone()),
+        instrument_type: asset.instrument_type.clone(),
+        instrument_symbol: asset.instrument_symbol.clone(),
+        instrument_exchange_mic: asset.instrument_exchange_mic.clone(),
+        provider_config: asset.provider_config.clone(),
+        metadata: asset.metadata.clone(),
+    }
+}
+
+fn new_asset_from_snapshot(asset: &Asset) -> NewAsset {
+    NewAsset {
+        id: Some(asset.id.clone()),
+        kind: asset.kind.clone(),
+        name: asset.name.clone(),
+        display_code: asset.display_code.clone(),
+        is_active: asset.is_active,
+        quote_mode: asset.quote_mode,
+        quote_ccy: asset.quote_ccy.clone(),
+        instrument_type: asset.instrument_type.clone(),
+        instrument_symbol: asset.instrument_symbol.clone(),
+        instrument_exchange_mic: asset.instrument_exchange_mic.clone(),
+        provider_config: asset.provider_config.clone(),
+        notes: asset.notes.clone(),
+        metadata: asset.metadata.clone(),
+    }
+}
+
+/// Accounts, assets and currencies touched by a rollback, used for the
+/// recalculation events emitted once it completes.
+#[derive(Default)]
+struct AffectedEntities {
+    account_ids: HashSet<String>,
+    asset_ids: HashSet<String>,
+    currencies: HashSet<String>,
+    updated_assets: HashSet<String>,
+}
+
+impl AffectedEntities {
+    fn add_activity(&mut self, activity: &Activity) {
+        self.account_ids.insert(activity.account_id.clone());
+        if let Some(ref asset_id) = activity.asset_id {
+            self.asset_ids.insert(asset_id.clone());
+        }
+        self.currencies.insert(activity.currency.clone());
+    }
+
+    fn emit(self, event_sink: &dyn DomainEventSink) {
+        if !self.account_ids.is_empty() {
+            event_sink.emit(DomainEvent::activities_changed(
+                self.account_ids.into_iter().collect(),
+                self.asset_ids.into_iter().collect(),
+                self.currencies.into_iter().collect(),
+            ));
+        }
+        if !self.updated_assets.is_empty() {
+            event_sink.emit(DomainEvent::assets_updated(
+                self.updated_assets.into_iter().collect(),
+            ));
+        }
+    }
+}
+
+/// Service for browsing the change log and rolling changes back.
+pub struct ChangeLogService {
+    repository: Arc<dyn ChangeLogRepositoryTrait>,
+    activity_repository: Arc<dyn ActivityRepositoryTrait>,
+    asset_repository: Arc<dyn AssetRepositoryTrait>,
+    event_sink: Arc<dyn DomainEventSink>,
+}
+
+impl ChangeLogService {
+    pub fn new(
+        repository: Arc<dyn ChangeLogRepositoryTrait>,
+        activity_repository: Arc<dyn ActivityRepositoryTrait>,
+        asset_repository: Arc<dyn AssetRepositoryTrait>,
+    ) -> Self {
+        Self {
+            repository,
+            activity_repository,
+            asset_repository,
+            event_sink: Arc::new(NoOpDomainEventSink),
+        }
+    }
+
+    /// Sets the domain event sink used to trigger recalculation after rollbacks.
+    pub fn with_event_sink(mut self, event_sink: Arc<dyn DomainEventSink>) -> Self {
+        self.event_sink = event_sink;
+        self
+    }
+
+    async fn record(&self, entry: Option<NewChangeLogEntry>) {
+        if let Some(entry) = entry {
+            if let Err(e) = self.repository.append(vec![entry]).await {
+                warn!("Failed to record rollback in change log: {}", e);
+            }
+        }
+    }
+
+    async fn revert(&self, entry: &ChangeLogEntry, affected: &mut AffectedEntities) -> Result<()> {
+        let already_reverted = self
+            .repository
+            .get_entries_by_source_ref(&entry.id)?
+            .iter()
+            .any(|e| e.source == ChangeSource::Rollback);
+        if already_reverted {
+            return Err(invalid(format!(
+                "Change {} was already rolled back",
+                entry.id
+            )));
+        }
+
+        let context = ChangeContext::new(ChangeSource::Rollback, Some(entry.id.clone()));
+        match entry.entity_type {
+            ChangeEntityType::Activity => self.revert_activity(entry, &context, affected).await,
+            ChangeEntityType::Asset => self.revert_asset(entry, &context, affected).await,
+        }
+    }
+
+    async fn revert_activity(
+        &self,
+        entry: &ChangeLogEntry,
+        context: &ChangeContext,
+        affected: &mut AffectedEntities,
+    ) -> Result<()> {
+        let current = self.activity_repository.get_activity(&entry.entity_id).ok();
+
+        match entry.operation {
+            ChangeOperation::Create | ChangeOperation::Update => {
+                let current = current.ok_or_else(|| {
+                    invalid(format!("Activity {} no longer exists", entry.entity_id))
+                })?;
+                ensure_unchanged(entry, &current)?;
+
+                if entry.operation == ChangeOperation::Create {
+                    let deleted = self
+                        .activity_repository
+                        .delete_activity(current.id.clone())
+                        .await?;
+                    affected.add_activity(&deleted);
+                    self.record(NewChangeLogEntry::activity(Some(&current), None, context))
+                        .await;
+                } else {
+                    let before: Activity = snapshot(entry, &entry.before)?;
+                    let restored = self
+                        .activity_repository
+                        .update_activity(update_from_snapshot(&before))
+                        .await?;
+                    affected.add_activity(&current);
+                    affected.add_activity(&restored);
+                    self.record(NewChangeLogEntry::activity(
+                        Some(&current),
+                        Some(&restored),
+                        context,
+                    ))
+                    .await;
+                }
+            }
+            ChangeOperation::Delete => {
+                if current.is_some() {
+                    return Err(invalid(format!(
+                        "Activity {} already exists",
+                        entry.entity_id
+                    )));
+                }
+                let before: Activity = snapshot(entry, &entry.before)?;
+                // Upsert keeps the original id and source identity
+                self.activity_repository
+                    .bulk_upsert(vec![upsert_from_snapshot(&before)])
+                    .await?;
+                let restored = self.activity_repository.get_activity(&before.id)?;
+                affected.add_activity(&restored);
+                self.record(NewChangeLogEntry::activity(None, Some(&restored), context))
+                    .await;
+            }
+        }
+        Ok(())
+    }
+
+    async fn revert_asset(
+        &self,
+        entry: &ChangeLogEntry,
+        context: &ChangeContext,
+        affected: &mut AffectedEntities,
+    ) -> Result<()> {
+        let current = self.asset_repository.get_by_id(&entry.entity_id).ok();
+
+        match entry.operation {
+            ChangeOperation::Create | ChangeOperation::Update => {
+                let current = current.ok_or_else(|| {
+                    invalid(format!("Asset {} no longer exists", entry.entity_id))
+                })?;
+                ensure_unchanged(entry, &current)?;
+
+                if entry.operation == ChangeOperation::Create {
+                    self.asset_repository.delete(&current.id).await?;
+                    self.record(NewChangeLogEntry::asset(Some(&current), None, context))
+                        .await;
+                } else {
+                    let before: Asset = snapshot(entry, &entry.before)?;
+                    let restored = self
+                        .asset_repository
+                        .update_profile(&before.id, profile_from_snapshot(&before))
+                        .await?;
+                    affected.updated_assets.insert(restored.id.clone());
+                    self.record(NewChangeLogEntry::asset(
+                        Some(&current),
+                        Some(&restored),
+                        context,
+                    ))
+                    .await;
+                }
+            }
+            ChangeOperation::Delete => {
+                if current.is_some() {
+                    return Err(invalid(format!("Asset {} already exists", entry.entity_id)));
+                }
+                let before: Asset = snapshot(entry, &entry.before)?;
+                let restored = self
+                    .asset_repository
+                    .create(new_asset_from_snapshot(&before))
+                    .await?;
+                affected.updated_assets.insert(restored.id.clone());
+                self.record(NewChangeLogEntry::asset(None, Some(&restored), context))
+                    .await;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ChangeLogServiceTrait for ChangeLogService {
+    fn get_entity_history(
+        &self,
+        entity_type: ChangeEntityType,
+        entity_id: &str,
+    ) -> Result<Vec<ChangeLogEntry>> {
+        self.repository.get_entity_history(entity_type, entity_id)
+    }
+
+    fn get_import_run_changes(&self, import_run_id: &str) -> Result<Vec<ChangeLogEntry>> {
+        Ok(self
+            .repository
+            .get_entries_by_source_ref(import_run_id)?
+            .into_iter()
+            .filter(|e| matches!(e.source, ChangeSource::Import | ChangeSource::BrokerSync))
+            .collect())
+    }
+
+    async fn rollback_change(&self, entry_id: &str) -> Result<RollbackResult> {
+        let entry = self.repository.get_entry(entry_id)?;
+        let mut affected = AffectedEntities::default();
+        self.revert(&entry, &mut affected).await?;
+        affected.emit(self.event_sink.as_ref());
+
+        Ok(RollbackResult {
+            reverted: 1,
+            ..Default::default()
+        })
+    }
+
+    async fn rollback_import_run(&self, import_run_id: &str) -> Result<RollbackResult> {
+        let entries = self.get_import_run_changes(import_run_id)?;
+        if entries.is_empty() {
+            return Err(invalid(format!(
+                "No changes recorded for import run {}",
+                import_run_id
+            )));
+        }
+
+        let mut result = RollbackResult::default();
+        let mut affected = AffectedEntities::default();
+        for entry in &entries {
+            match self.revert(entry, &mut affected).await {
+                Ok(()) => result.reverted += 1,
+                Err(e) => {
+                    result.skipped += 1;
+                    result.warnings.push(format!(
+                        "{} of {} {}: {}",
+                        entry.operation.as_str(),
+                        entry.entity_type.as_str(),
+                        entry.entity_id,
+                        e
+                    ));
+                }
+            }
+        }
+        affected.emit(self.event_sink.as_ref());
+
+        debug!(
+            "Rolled back import run {}: {} reverted, {} skipped",
+            import_run_id, result.reverted, result.skipped
+        );
+        Ok(result)
+    }
+}
@@ -0,0 +1,485 @@
+#[cfg(test)]
+mod tests {
+    use crate::activities::*;
+    use crate::assets::{Asset, AssetRepositoryTrait, NewAsset, UpdateAssetProfile};
+    use crate::audit::{
+        ChangeContext, ChangeEntityType, ChangeLogEntry, ChangeLogRepositoryTrait,
+        ChangeLogService, ChangeLogServiceTrait, ChangeOperation, ChangeSource, NewChangeLogEntry,
+    };
+    use crate::errors::{DatabaseError, Error, Result};
+    use crate::events::{DomainEvent, MockDomainEventSink};
+    use crate::limits::ContributionActivity;
+    use async_trait::async_trait;
+    use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    fn activity(id: &str, quantity: Decimal) -> Activity {
+        let now = Utc::now();
+        Activity {
+            id: id.to_string(),
+            account_id: "acc-1".to_string(),
+            asset_id: Some("AAPL".to_string()),
+            activity_type: "BUY".to_string(),
+            activity_type_override: None,
+            source_type: None,
+            subtype: None,
+            status: ActivityStatus::Posted,
+            activity_date: now,
+            settlement_date: None,
+            quantity: Some(quantity),
+            unit_price: Some(dec!(100)),
+            amount: None,
+            fee: Some(dec!(0)),
+            currency: "USD".to_string(),
+            fx_rate: None,
+            notes: None,
+            metadata: None,
+            source_system: None,
+            source_record_id: None,
+            source_group_id: None,
+            idempotency_key: None,
+            import_run_id: None,
+            is_user_modified: false,
+            needs_review: false,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn touched(mut activity: Activity) -> Activity {
+        activity.updated_at += Duration::seconds(1);
+        activity
+    }
+
+    // --- Mock ChangeLogRepository ---
+    #[derive(Default)]
+    struct MockChangeLogRepository {
+        entries: Mutex<Vec<ChangeLogEntry>>,
+    }
+
+    impl MockChangeLogRepository {
+        fn newest_first(&self, filter: impl Fn(&ChangeLogEntry) -> bool) -> Vec<ChangeLogEntry> {
+            let entries = self.entries.lock().unwrap();
+            entries
+                .iter()
+                .rev()
+                .filter(|e| filter(e))
+                .cloned()
+                .collect()
+        }
+    }
+
+    #[async_trait]
+    impl ChangeLogRepositoryTrait for MockChangeLogRepository {
+        async fn append(&self, new_entries: Vec<NewChangeLogEntry>) -> Result<usize> {
+            let mut entries = self.entries.lock().unwrap();
+            let count = new_entries.len();
+            for entry in new_entries {
+                let id = format!("change-{}", entries.len() + 1);
+                entries.push(ChangeLogEntry {
+                    id,
+                    entity_type: entry.entity_type,
+                    entity_id: entry.entity_id,
+                    operation: entry.operation,
+                    source: entry.source,
+                    source_ref: entry.source_ref,
+                    account_id: entry.account_id,
+                    before: entry.before,
+                    after: entry.after,
+                    created_at: Utc::now(),
+                });
+            }
+            Ok(count)
+        }
+
+        fn get_entry(&self, entry_id: &str) -> Result<ChangeLogEntry> {
+            self.newest_first(|e| e.id == entry_id)
+                .into_iter()
+                .next()
+                .ok_or_else(|| Error::Database(DatabaseError::NotFound(entry_id.to_string())))
+        }
+
+        fn get_entity_history(
+            &self,
+            entity_type: ChangeEntityType,
+            entity_id: &str,
+        ) -> Result<Vec<ChangeLogEntry>> {
+            Ok(self.newest_first(|e| e.entity_type == entity_type && e.entity_id == entity_id))
+        }
+
+        fn get_entries_by_source_ref(&self, source_ref: &str) -> Result<Vec<ChangeLogEntry>> {
+            Ok(self.newest_first(|e| e.source_ref.as_deref() == Some(source_ref)))
+        }
+    }
+
+    // --- Mock ActivityRepository ---
+    #[derive(Default)]
+    struct MockActivityRepository {
+        activities: Mutex<HashMap<String, Activity>>,
+    }
+
+    impl MockActivityRepository {
+        fn put(&self, activity: Activity) {
+            self.activities
+                .lock()
+                .unwrap()
+                .insert(activity.id.clone(), activity);
+        }
+
+        fn get(&self, id: &str) -> Option<Activity> {
+            self.activities.lock().unwrap().get(id).cloned()
+        }
+    }
+
+    #[async_trait]
+    impl ActivityRepositoryTrait for MockActivityRepository {
+        fn get_activity(&self, activity_id: &str) -> Result<Activity> {
+            self.get(activity_id)
+                .ok_or_else(|| Error::Database(DatabaseError::NotFound(activity_id.to_string())))
+        }
+
+        async fn update_activity(&self, update: ActivityUpdate) -> Result<Activity> {
+            let current = self.get_activity(&update.id)?;
+            let mut updated = touched(current);
+            if let Some(quantity) = update.quantity {
+                updated.quantity = quantity;
+            }
+            updated.notes = update.notes;
+            self.put(updated.clone());
+            Ok(updated)
+        }
+
+        async fn delete_activity(&self, activity_id: String) -> Result<Activity> {
+            self.activities
+                .lock()
+                .unwrap()
+                .remove(&activity_id)
+                .ok_or_else(|| Error::Database(DatabaseError::NotFound(activity_id)))
+        }
+
+        async fn bulk_upsert(&self, rows: Vec<ActivityUpsert>) -> Result<BulkUpsertResult> {
+            let count = rows.len();
+            for row in rows {
+                let mut restored = activity(&row.id, row.quantity.unwrap_or_default());
+                restored.account_id = row.account_id;
+                restored.notes = row.notes;
+                self.put(restored);
+            }
+            Ok(BulkUpsertResult {
+                upserted: count,
+                created: count,
+                ..Default::default()
+            })
+        }
+
+        // Stub implementations for other trait methods
+        fn get_activities(&self) -> Result<Vec<Activity>> {
+            unimplemented!()
+        }
+        fn get_activities_by_account_id(&self, _: &str) -> Result<Vec<Activity>> {
+            unimplemented!()
+        }
+        fn get_activities_by_account_ids(&self, _: &[String]) -> Result<Vec<Activity>> {
+            unimplemented!()
+        }
+        fn get_trading_activities(&self) -> Result<Vec<Activity>> {
+            unimplemented!()
+        }
+        fn get_income_activities(&self) -> Result<Vec<Activity>> {
+            unimplemented!()
+        }
+        fn get_contribution_activities(
+            &self,
+            _: &[String],
+            _: NaiveDateTime,
+            _: NaiveDateTime,
+        ) -> Result<Vec<ContributionActivity>> {
+            unimplemented!()
+        }
+        fn search_activities(
+            &self,
+            _: i64,
+            _: i64,
+            _: Option<Vec<String>>,
+            _: Option<Vec<String>>,
+            _: Option<String>,
+            _: Option<Sort>,
+            _: Option<bool>,
+            _: Option<NaiveDate>,
+            _: Option<NaiveDate>,
+        ) -> Result<ActivitySearchResponse> {
+            unimplemented!()
+        }
+        async fn create_activity(&self, _: NewActivity) -> Result<Activity> {
+            unimplemented!()
+        }
+        async fn bulk_mutate_activities(
+            &self,
+            _: Vec<NewActivity>,
+            _: Vec<ActivityUpdate>,
+            _: Vec<String>,
+        ) -> Result<ActivityBulkMutationResult> {
+            unimplemented!()
+        }
+        async fn create_activities(&self, _: Vec<NewActivity>) -> Result<usize> {
+            unimplemented!()
+        }
+        fn get_first_activity_date(&self, _: Option<&[String]>) -> Result<Option<DateTime<Utc>>> {
+            unimplemented!()
+        }
+        fn get_import_mapping(&self, _: &str) -> Result<Option<ImportMapping>> {
+            unimplemented!()
+        }
+        async fn save_import_mapping(&self, _: &ImportMapping) -> Result<()> {
+            unimplemented!()
+        }
+        fn calculate_average_cost(&self, _: &str, _: &str) -> Result<Decimal> {
+            unimplemented!()
+        }
+        fn get_income_activities_data(&self) -> Result<Vec<IncomeData>> {
+            unimplemented!()
+        }
+        fn get_first_activity_date_overall(&self) -> Result<DateTime<Utc>> {
+            unimplemented!()
+        }
+        fn get_activity_bounds_for_assets(
+            &self,
+            _: &[String],
+        ) -> Result<HashMap<String, (Option<NaiveDate>, Option<NaiveDate>)>> {
+            unimplemented!()
+        }
+        fn check_existing_duplicates(&self, _: &[String]) -> Result<HashMap<String, String>> {
+            unimplemented!()
+        }
+        async fn reassign_asset(&self, _: &str, _: &str) -> Result<u32> {
+            unimplemented!()
+        }
+        async fn get_activity_accounts_and_currencies_by_asset_id(
+            &self,
+            _: &str,
+        ) -> Result<(Vec<String>, Vec<String>)> {
+            unimplemented!()
+        }
+    }
+
+    // --- Mock AssetRepository (asset rollbacks are not exercised here) ---
+    struct MockAssetRepository;
+
+    #[async_trait]
+    impl AssetRepositoryTrait for MockAssetRepository {
+        async fn create(&self, _: NewAsset) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn create_batch(&self, _: Vec<NewAsset>) -> Result<Vec<Asset>> {
+            unimplemented!()
+        }
+        async fn update_profile(&self, _: &str, _: UpdateAssetProfile) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn update_quote_mode(&self, _: &str, _: &str) -> Result<Asset> {
+            unimplemented!()
+        }
+        fn get_by_id(&self, _: &str) -> Result<Asset> {
+            unimplemented!()
+        }
+        fn list(&self) -> Result<Vec<Asset>> {
+            unimplemented!()
+        }
+        fn list_by_asset_ids(&self, _: &[String]) -> Result<Vec<Asset>> {
+            unimplemented!()
+        }
+        async fn delete(&self, _: &str) -> Result<()> {
+            unimplemented!()
+        }
+        fn search_by_symbol(&self, _: &str) -> Result<Vec<Asset>> {
+            unimplemented!()
+        }
+        fn find_by_instrument_key(&self, _: &str) -> Result<Option<Asset>> {
+            unimplemented!()
+        }
+        async fn cleanup_legacy_metadata(&self, _: &str) -> Result<()> {
+            unimplemented!()
+        }
+        async fn deactivate(&self, _: &str) -> Result<()> {
+            unimplemented!()
+        }
+        async fn reactivate(&self, _: &str) -> Result<()> {
+            unimplemented!()
+        }
+        async fn copy_user_metadata(&self, _: &str, _: &str) -> Result<()> {
+            unimplemented!()
+        }
+        async fn deactivate_orphaned_investments(&self) -> Result<Vec<String>> {
+            unimplemented!()
+        }
+    }
+
+    struct Fixture {
+        log: Arc<MockChangeLogRepository>,
+        activities: Arc<MockActivityRepository>,
+        events: MockDomainEventSink,
+        service: ChangeLogService,
+    }
+
+    fn fixture() -> Fixture {
+        let log = Arc::new(MockChangeLogRepository::default());
+        let activities = Arc::new(MockActivityRepository::default());
+        let events = MockDomainEventSink::new();
+        let service = ChangeLogService::new(
+            log.clone(),
+            activities.clone(),
+            Arc::new(MockAssetRepository),
+        )
+        .with_event_sink(Arc::new(events.clone()));
+        Fixture {
+            log,
+            activities,
+            events,
+            service,
+        }
+    }
+
+    /// Applies a change to the mock store and records it, returning the entry id.
+    async fn apply(
+        fx: &Fixture,
+        before: Option<&Activity>,
+        after: Option<&Activity>,
+        context: &ChangeContext,
+    ) -> String {
+        match (before, after) {
+            (_, Some(after)) => fx.activities.put(after.clone()),
+            (Some(before), None) => {
+                fx.activities.activities.lock().unwrap().remove(&before.id);
+            }
+            (None, None) => unreachable!(),
+        }
+        let entry = NewChangeLogEntry::activity(before, after, context).unwrap();
+        fx.log.append(vec![entry]).await.unwrap();
+        fx.log.entries.lock().unwrap().last().unwrap().id.clone()
+    }
+
+    #[tokio::test]
+    async fn test_rollback_create_deletes_activity() {
+        let fx = fixture();
+        let created = activity("a1", dec!(10));
+        let entry_id = apply(&fx, None, Some(&created), &ChangeContext::manual()).await;
+
+        let result = fx.service.rollback_change(&entry_id).await.unwrap();
+
+        assert_eq!(result.reverted, 1);
+        assert!(fx.activities.get("a1").is_none());
+        let history = fx
+            .service
+            .get_entity_history(ChangeEntityType::Activity, "a1")
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].operation, ChangeOperation::Delete);
+        assert_eq!(history[0].source, ChangeSource::Rollback);
+        assert_eq!(history[0].source_ref.as_deref(), Some(entry_id.as_str()));
+        assert!(matches!(
+            fx.events.events().as_slice(),
+            [DomainEvent::ActivitiesChanged { account_ids, .. }] if account_ids == &vec!["acc-1".to_string()]
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rollback_update_restores_previous_state() {
+        let fx = fixture();
+        let original = activity("a1", dec!(10));
+        fx.activities.put(original.clone());
+        let mut edited = touched(original.clone());
+        edited.quantity = Some(dec!(25));
+        let entry_id = apply(
+            &fx,
+            Some(&original),
+            Some(&edited),
+            &ChangeContext::manual(),
+        )
+        .await;
+
+        fx.service.rollback_change(&entry_id).await.unwrap();
+
+        assert_eq!(fx.activities.get("a1").unwrap().quantity, Some(dec!(10)));
+        assert_eq!(fx.events.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rollback_delete_reinserts_activity() {
+        let fx = fixture();
+        let mut original = activity("a1", dec!(10));
+        original.notes = Some("keep me".to_string());
+        fx.activities.put(original.clone());
+        let entry_id = apply(&fx, Some(&original), None, &ChangeContext::manual()).await;
+
+        fx.service.rollback_change(&entry_id).await.unwrap();
+
+        let restored = fx.activities.get("a1").unwrap();
+        assert_eq!(restored.quantity, Some(dec!(10)));
+        assert_eq!(restored.notes.as_deref(), Some("keep me"));
+    }
+
+    #[tokio::test]
+    async fn test_rollback_refuses_when_entity_changed_later() {
+        let fx = fixture();
+        let created = activity("a1", dec!(10));
+        let entry_id = apply(&fx, None, Some(&created), &ChangeContext::manual()).await;
+        let mut edited = touched(created.clone());
+        edited.quantity = Some(dec!(11));
+        apply(&fx, Some(&created), Some(&edited), &ChangeContext::manual()).await;
+
+        let err = fx.service.rollback_change(&entry_id).await.unwrap_err();
+
+        assert!(matches!(err, Error::Validation(_)));
+        assert!(fx.activities.get("a1").is_some());
+        assert!(fx.events.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rollback_refuses_second_rollback() {
+        let fx = fixture();
+        let original = activity("a1", dec!(10));
+        let entry_id = apply(&fx, Some(&original), None, &ChangeContext::manual()).await;
+
+        fx.service.rollback_change(&entry_id).await.unwrap();
+        fx.activities.activities.lock().unwrap().clear();
+        let err = fx.service.rollback_change(&entry_id).await.unwrap_err();
+
+        assert!(matches!(err, Error::Validation(_)));
+        assert!(fx.activities.get("a1").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rollback_import_run_reverts_changes_and_skips_conflicts() {
+        let fx = fixture();
+        let context = ChangeContext::new(ChangeSource::Import, Some("run-1".to_string()));
+        let first = activity("a1", dec!(1));
+        let second = activity("a2", dec!(2));
+        apply(&fx, None, Some(&first), &context).await;
+        apply(&fx, None, Some(&second), &context).await;
+        // A later manual edit protects a2 from the rollback
+        let mut edited = touched(second.clone());
+        edited.quantity = Some(dec!(3));
+        apply(&fx, Some(&second), Some(&edited), &ChangeContext::manual()).await;
+
+        assert_eq!(fx.service.get_import_run_changes("run-1").unwrap().len(), 2);
+        let result = fx.service.rollback_import_run("run-1").await.unwrap();
+
+        assert_eq!(result.reverted, 1);
+        assert_eq!(result.skipped, 1);
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].contains("a2"));
+        assert!(fx.activities.get("a1").is_none());
+        assert_eq!(fx.activities.get("a2").unwrap().quantity, Some(dec!(3)));
+        assert_eq!(fx.events.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rollback_import_run_without_changes_fails() {
+        let fx = fixture();
+        let err = fx.service.rollback_import_run("missing").await.unwrap_err();
+        assert!(matches!(err, Error::Validation(_)));
+    }
+}
@@ -0,0 +1,44 @@
+use async_trait::async_trait;
+
+use super::audit_model::{ChangeEntityType, ChangeLogEntry, NewChangeLogEntry, RollbackResult};
+use crate::errors::Result;
+
+/// Trait for append-only change log persistence
+#[async_trait]
+pub trait ChangeLogRepositoryTrait: Send + Sync {
+    /// Appends entries, returning the number written.
+    async fn append(&self, entries: Vec<NewChangeLogEntry>) -> Result<usize>;
+    fn get_entry(&self, entry_id: &str) -> Result<ChangeLogEntry>;
+    /// Entries for one entity, newest first.
+    fn get_entity_history(
+        &self,
+        entity_type: ChangeEntityType,
+        entity_id: &str,
+    ) -> Result<Vec<ChangeLogEntry>>;
+    /// Entries with the given source reference (import run id or undone change id),
+    /// newest first.
+    fn get_entries_by_source_ref(&self, source_ref: &str) -> Result<Vec<ChangeLogEntry>>;
+}
+
+/// Trait for browsing history and rolling back changes
+#[async_trait]
+pub trait ChangeLogServiceTrait: Send + Sync {
+    fn get_entity_history(
+        &self,
+        entity_type: ChangeEntityType,
+        entity_id: &str,
+    ) -> Result<Vec<ChangeLogEntry>>;
+
+    /// Changes made by an import run (CSV import or broker sync).
+    fn get_import_run_changes(&self, import_run_id: &str) -> Result<Vec<ChangeLogEntry>>;
+
+    /// Restores the entity to its state before the given change.
+    ///
+    /// Fails if the entity was modified after the change or the change was
+    /// already rolled back.
+    async fn rollback_change(&self, entry_id: &str) -> Result<RollbackResult>;
+
+    /// Rolls back every change made by an import run, newest first. Changes
+    /// that can no longer be rolled back are skipped with a warning.
+    async fn rollback_import_run(&self, import_run_id: &str) -> Result<RollbackResult>;
+}
@@ -0,0 +1,16 @@
+//! Audit module - append-only change log for activities and assets, with
+//! history browsing and rollback of single changes or whole import runs.
+
+mod audit_model;
+mod audit_service;
+mod audit_traits;
+
+#[cfg(test)]
+mod audit_service_tests;
+
+pub use audit_model::{
+    change_source_for_system, ChangeContext, ChangeEntityType, ChangeLogEntry, ChangeOperation,
+    ChangeSource, NewChangeLogEntry, RollbackResult, AI_ASSISTANT_SOURCE_SYSTEM,
+};
+pub use audit_service::ChangeLogService;
+pub use audit_traits::{ChangeLogRepositoryTrait, ChangeLogServiceTrait};
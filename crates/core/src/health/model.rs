@@ -70,6 +70,12 @@ pub enum HealthCategory {
     DataConsistency,
     /// Issues related to account configuration (tracking mode, etc.)
     AccountConfiguration,
+    /// Differences between computed holdings and broker-reported positions
+    Reconciliation,
+    /// Broker holdings drifting from transaction-derived holdings after a sync
+    HoldingsDrift,
+    /// Fields edited on two devices that need a manual choice
+    SyncConflicts,
 }
 
 impl HealthCategory {
@@ -81,6 +87,9 @@ impl HealthCategory {
             HealthCategory::Classification => "CLASSIFICATION",
             HealthCategory::DataConsistency => "DATA_CONSISTENCY",
             HealthCategory::AccountConfiguration => "ACCOUNT_CONFIGURATION",
+            HealthCategory::Reconciliation => "RECONCILIATION",
+            HealthCategory::HoldingsDrift => "HOLDINGS_DRIFT",
+            HealthCategory::SyncConflicts => "SYNC_CONFLICTS",
         }
     }
 
@@ -92,6 +101,9 @@ impl HealthCategory {
             HealthCategory::Classification => "Classifications",
             HealthCategory::DataConsistency => "Data Consistency",
             HealthCategory::AccountConfiguration => "Account Setup",
+            HealthCategory::Reconciliation => "Broker Reconciliation",
+            HealthCategory::HoldingsDrift => "Holdings Drift",
+            HealthCategory::SyncConflicts => "Sync Conflicts",
         }
     }
 }
@@ -158,6 +170,16 @@ impl FixAction {
         }
     }
 
+    /// Creates a new fix action that records a draft activity (e.g., a reconciliation
+    /// ADJUSTMENT or SPLIT). The payload is the `NewActivity` to create.
+    pub fn record_activity(label: impl Into<String>, activity: Value) -> Self {
+        Self {
+            id: "record_activity".to_string(),
+            label: label.into(),
+            payload: activity,
+        }
+    }
+
     /// Creates a new fix action for retrying sync on failed assets.
     pub fn retry_sync(asset_ids: Vec<String>) -> Self {
         Self {
@@ -43,6 +43,9 @@ pub struct HealthService {
     /// Cached health status
     cached_status: RwLock<Option<CachedStatus>>,
 
+    /// Issues published by other services, keyed by source
+    published_issues: RwLock<HashMap<String, Vec<HealthIssue>>>,
+
     /// Individual check implementations
     price_check: PriceStalenessCheck,
     quote_sync_check: QuoteSyncCheck,
@@ -59,6 +62,7 @@ impl HealthService {
             dismissal_store,
             config: RwLock::new(HealthConfig::default()),
             cached_status: RwLock::new(None),
+            published_issues: RwLock::new(HashMap::new()),
             price_check: PriceStalenessCheck::new(),
             quote_sync_check: QuoteSyncCheck::new(),
             fx_check: FxIntegrityCheck::new(),
@@ -77,6 +81,7 @@ impl HealthService {
             dismissal_store,
             config: RwLock::new(config),
             cached_status: RwLock::new(None),
+            published_issues: RwLock::new(HashMap::new()),
             price_check: PriceStalenessCheck::new(),
             quote_sync_check: QuoteSyncCheck::new(),
             fx_check: FxIntegrityCheck::new(),
@@ -184,6 +189,11 @@ impl HealthService {
         );
         all_issues.extend(account_config_issues);
 
+        // Include issues published by other services (e.g., broker reconciliation)
+        let published = self.published_issues.read().await;
+        all_issues.extend(published.values().flatten().cloned());
+        drop(published);
+
         // Filter out dismissed issues (unless data has changed)
         let filtered_issues = self.filter_dismissed_issues(all_issues).await?;
 
@@ -500,6 +510,28 @@ impl HealthServiceTrait for HealthService {
         debug!("Health status cache cleared");
     }
 
+    async fn publish_issues(&self, source: &str, issues: Vec<HealthIssue>) {
+        debug!("Publishing {} health issues from {}", issues.len(), source);
+        let mut published = self.published_issues.write().await;
+        if issues.is_empty() {
+            published.remove(source);
+        } else {
+            published.insert(source.to_string(), issues);
+        }
+        drop(published);
+        self.clear_cache().await;
+    }
+
+    async fn resolve_published_fix(&self, action: &FixAction) {
+        let mut published = self.published_issues.write().await;
+        for issues in published.values_mut() {
+            issues.retain(|issue| issue.fix_action.as_ref() != Some(action));
+        }
+        published.retain(|_, issues| !issues.is_empty());
+        drop(published);
+        self.clear_cache().await;
+    }
+
     async fn get_config(&self) -> HealthConfig {
         self.config.read().await.clone()
     }
@@ -768,4 +800,60 @@ mod tests {
 
         assert_eq!(status.total_count(), 0);
     }
+
+    #[tokio::test]
+    async fn test_published_issues_included_until_resolved() {
+        let store = Arc::new(MockDismissalStore::new());
+        let service = HealthService::new(store);
+
+        let fix = FixAction::record_activity("Record Fee", serde_json::json!({ "amount": "5" }));
+        let issue = HealthIssue::builder()
+            .id("reconciliation:ACC1:cash_delta:USD")
+            .severity(crate::health::Severity::Warning)
+            .category(crate::health::HealthCategory::Reconciliation)
+            .title("USD cash differs from broker")
+            .message("Broker reports 95 USD, computed cash is 100.")
+            .fix_action(fix.clone())
+            .data_hash("abc")
+            .build();
+        service
+            .publish_issues("reconciliation:ACC1", vec![issue])
+            .await;
+
+        let status = service
+            .run_checks_with_data(
+                "USD",
+                0.0,
+                &[],
+                &HashMap::new(),
+                &[],
+                &[],
+                &[],
+                &[],
+                &None,
+                &[],
+            )
+            .await
+            .unwrap();
+        assert_eq!(status.total_count(), 1);
+
+        service.resolve_published_fix(&fix).await;
+
+        let status = service
+            .run_checks_with_data(
+                "USD",
+                0.0,
+                &[],
+                &HashMap::new(),
+                &[],
+                &[],
+                &[],
+                &[],
+                &None,
+                &[],
+            )
+            .await
+            .unwrap();
+        assert_eq!(status.total_count(), 0);
+    }
 }
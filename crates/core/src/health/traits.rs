@@ -294,6 +294,23 @@ pub trait HealthServiceTrait: Send + Sync {
     /// Clears the cached health status, forcing fresh checks on next request.
     async fn clear_cache(&self);
 
+    /// Publishes issues produced outside the regular checks (e.g., broker reconciliation).
+    ///
+    /// Issues are grouped by `source`; publishing replaces all previous issues of
+    /// that source. Published issues are included in every subsequent check run.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Key identifying the producer (e.g., `reconciliation:<account_id>`)
+    /// * `issues` - The issues to publish (empty to clear the source)
+    async fn publish_issues(&self, source: &str, issues: Vec<HealthIssue>);
+
+    /// Removes published issues whose fix action matches `action`.
+    ///
+    /// Called after a fix for a published issue has been applied outside the
+    /// health service.
+    async fn resolve_published_fix(&self, action: &FixAction);
+
     /// Runs all health checks by gathering data from the provided services.
     ///
     /// This is the preferred method for running health checks as it handles all
@@ -8,6 +8,8 @@ pub mod accounts;
 pub mod activities;
 pub mod addons;
 pub mod assets;
+pub mod attachments;
+pub mod audit;
 pub mod constants;
 pub mod errors;
 pub mod events;
@@ -17,6 +19,7 @@ pub mod health;
 pub mod limits;
 pub mod portfolio;
 pub mod quotes;
+pub mod recurring;
 pub mod secrets;
 pub mod settings;
 pub mod sync;
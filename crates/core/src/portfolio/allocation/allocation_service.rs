@@ -25,6 +25,10 @@ pub trait AllocationServiceTrait: Send + Sync {
         base_currency: &str,
     ) -> Result<PortfolioAllocations>;
 
+    /// Computes taxonomy allocations for an already valued set of holdings,
+    /// e.g. holdings produced by a what-if simulation.
+    fn get_allocations_for_holdings(&self, holdings: &[Holding]) -> Result<PortfolioAllocations>;
+
     /// Returns holdings filtered by a taxonomy category with full category metadata.
     /// Used for drill-down views when user clicks on an allocation category.
     async fn get_holdings_by_allocation(
@@ -628,17 +632,20 @@ impl AllocationServiceTrait for AllocationService {
             account_id, base_currency
         );
 
-        // 1. Get holdings
         let holdings = self
             .holdings_service
             .get_holdings(account_id, base_currency)
             .await?;
 
+        self.get_allocations_for_holdings(&holdings)
+    }
+
+    fn get_allocations_for_holdings(&self, holdings: &[Holding]) -> Result<PortfolioAllocations> {
         if holdings.is_empty() {
             return Ok(PortfolioAllocations::default());
         }
 
-        // 2. Compute total portfolio value (excluding cash for some allocations)
+        // 1. Compute total portfolio value (excluding cash for some allocations)
         let total_value: Decimal = holdings
             .iter()
             .filter(|h| h.holding_type != HoldingType::Cash)
@@ -647,16 +654,16 @@ impl AllocationServiceTrait for AllocationService {
 
         let total_with_cash: Decimal = holdings.iter().map(|h| h.market_value.base).sum();
 
-        // 3. Get all taxonomies with categories
+        // 2. Get all taxonomies with categories
         let taxonomies = self.taxonomy_service.get_taxonomies_with_categories()?;
 
-        // 4. Collect all asset IDs from holdings
+        // 3. Collect all asset IDs from holdings
         let asset_ids: Vec<String> = holdings
             .iter()
             .filter_map(|h| h.instrument.as_ref().map(|i| i.id.clone()))
             .collect();
 
-        // 5. Get all assignments for these assets
+        // 4. Get all assignments for these assets
         let mut assignments_by_asset: HashMap<String, Vec<(String, String, i32)>> = HashMap::new();
 
         for asset_id in &asset_ids {
@@ -670,7 +677,7 @@ impl AllocationServiceTrait for AllocationService {
             }
         }
 
-        // 6. Find each taxonomy and its categories
+        // 5. Find each taxonomy and its categories
         let mut asset_classes_alloc =
             TaxonomyAllocation::empty("asset_classes", "Asset Classes", "#879a39");
         let mut sectors_alloc = TaxonomyAllocation::empty("industries_gics", "Sectors", "#da702c");
@@ -689,7 +696,7 @@ impl AllocationServiceTrait for AllocationService {
                     // Asset classes include cash, use total_with_cash
                     // Cash holdings now have proper instruments with classifications
                     asset_classes_alloc = self.aggregate_by_taxonomy(
-                        &holdings,
+                        holdings,
                         &taxonomy.id,
                         &taxonomy.name,
                         &taxonomy.color,
@@ -701,7 +708,7 @@ impl AllocationServiceTrait for AllocationService {
                 }
                 "industries_gics" => {
                     sectors_alloc = self.aggregate_by_taxonomy(
-                        &holdings,
+                        holdings,
                         &taxonomy.id,
                         "Sectors", // Use friendly name
                         &taxonomy.color,
@@ -713,7 +720,7 @@ impl AllocationServiceTrait for AllocationService {
                 }
                 "regions" => {
                     regions_alloc = self.aggregate_by_taxonomy(
-                        &holdings,
+                        holdings,
                         &taxonomy.id,
                         "Regions",
                         &taxonomy.color,
@@ -725,7 +732,7 @@ impl AllocationServiceTrait for AllocationService {
                 }
                 "risk_category" => {
                     risk_alloc = self.aggregate_by_taxonomy(
-                        &holdings,
+                        holdings,
                         &taxonomy.id,
                         "Risk Category",
                         &taxonomy.color,
@@ -737,7 +744,7 @@ impl AllocationServiceTrait for AllocationService {
                 }
                 "instrument_type" => {
                     security_types_alloc = self.aggregate_by_taxonomy(
-                        &holdings,
+                        holdings,
                         &taxonomy.id,
                         "Instrument Type",
                         &taxonomy.color,
@@ -750,7 +757,7 @@ impl AllocationServiceTrait for AllocationService {
                 _ if !taxonomy.is_system => {
                     // User-created custom taxonomies only (skip system placeholder "custom_groups")
                     let custom_alloc = self.aggregate_by_taxonomy(
-                        &holdings,
+                        holdings,
                         &taxonomy.id,
                         &taxonomy.name,
                         &taxonomy.color,
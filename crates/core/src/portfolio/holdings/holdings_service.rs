@@ -34,6 +34,14 @@ pub trait HoldingsServiceTrait: Send + Sync {
         snapshot: &snapshot::AccountStateSnapshot,
         base_currency: &str,
     ) -> Result<Vec<Holding>>;
+
+    /// Values an arbitrary snapshot, such as a simulated one, with live quotes and weights.
+    /// Uses the same valuation path as `get_holdings` but never reads stored snapshots.
+    async fn value_holdings_from_snapshot(
+        &self,
+        snapshot: &snapshot::AccountStateSnapshot,
+        base_currency: &str,
+    ) -> Result<Vec<Holding>>;
 }
 
 pub struct HoldingsService {
@@ -386,18 +394,8 @@ impl HoldingsServiceTrait for HoldingsService {
             }
         };
 
-        let mut holdings = self
-            .build_live_holdings_from_snapshot(account_id, &latest_snapshot, base_currency, None)
-            .await;
-        self.value_holdings_best_effort(account_id, &mut holdings)
-            .await;
-        apply_portfolio_weights(account_id, &mut holdings);
-
-        for holding_view in &mut holdings {
-            normalize_holding_currency(holding_view);
-        }
-
-        Ok(holdings)
+        self.value_holdings_from_snapshot(&latest_snapshot, base_currency)
+            .await
     }
 
     async fn get_holding(
@@ -639,6 +637,26 @@ impl HoldingsServiceTrait for HoldingsService {
 
         Ok(holdings)
     }
+
+    async fn value_holdings_from_snapshot(
+        &self,
+        snapshot: &snapshot::AccountStateSnapshot,
+        base_currency: &str,
+    ) -> Result<Vec<Holding>> {
+        let account_id = snapshot.account_id.as_str();
+        let mut holdings = self
+            .build_live_holdings_from_snapshot(account_id, snapshot, base_currency, None)
+            .await;
+        self.value_holdings_best_effort(account_id, &mut holdings)
+            .await;
+        apply_portfolio_weights(account_id, &mut holdings);
+
+        for holding_view in &mut holdings {
+            normalize_holding_currency(holding_view);
+        }
+
+        Ok(holdings)
+    }
 }
 
 #[cfg(test)]
@@ -3,5 +3,7 @@ pub mod holdings;
 pub mod income;
 pub mod net_worth;
 pub mod performance;
+pub mod reconciliation;
+pub mod scenario;
 pub mod snapshot;
 pub mod valuation;
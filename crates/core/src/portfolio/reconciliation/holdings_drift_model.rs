@@ -0,0 +1,70 @@
+//! Holdings drift domain models.
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use super::reconciliation_model::{BrokerStatement, ReconciliationReport};
+
+/// Health issue source prefix for holdings drift issues (one source per account).
+pub const HOLDINGS_DRIFT_HEALTH_SOURCE: &str = "holdings_drift";
+
+/// Returns the health issue source used for an account's drift issues.
+pub fn holdings_drift_health_source(account_id: &str) -> String {
+    format!("{}:{}", HOLDINGS_DRIFT_HEALTH_SOURCE, account_id)
+}
+
+/// Lifecycle of a holdings drift check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HoldingsDriftStatus {
+    /// Broker holdings captured, waiting for the account's snapshots to be recalculated
+    Pending,
+    /// Compared with computed holdings; `report` is set
+    Checked,
+    /// A newer observation for the same account replaced this one before it was checked
+    Superseded,
+    /// The comparison could not run (e.g. the account was deleted); `error` is set
+    Failed,
+}
+
+impl HoldingsDriftStatus {
+    /// Returns the string representation of this status.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HoldingsDriftStatus::Pending => "PENDING",
+            HoldingsDriftStatus::Checked => "CHECKED",
+            HoldingsDriftStatus::Superseded => "SUPERSEDED",
+            HoldingsDriftStatus::Failed => "FAILED",
+        }
+    }
+
+    /// Parses a stored status string.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "PENDING" => Some(HoldingsDriftStatus::Pending),
+            "CHECKED" => Some(HoldingsDriftStatus::Checked),
+            "SUPERSEDED" => Some(HoldingsDriftStatus::Superseded),
+            "FAILED" => Some(HoldingsDriftStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// One comparison of broker-reported holdings with transaction-derived holdings,
+/// kept as discrepancy history for the account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HoldingsDriftCheck {
+    pub id: String,
+    pub account_id: String,
+    pub as_of_date: NaiveDate,
+    pub status: HoldingsDriftStatus,
+    /// Positions and cash reported by the broker during sync
+    pub statement: BrokerStatement,
+    /// Comparison result once checked
+    pub report: Option<ReconciliationReport>,
+    pub discrepancy_count: i32,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub checked_at: Option<NaiveDateTime>,
+}
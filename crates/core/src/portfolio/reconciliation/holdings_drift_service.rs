@@ -0,0 +1,161 @@
+//! Holdings drift detection service.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use log::{debug, info, warn};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::holdings_drift_model::{HoldingsDriftCheck, HoldingsDriftStatus};
+use super::reconciliation_model::{BrokerStatement, ReconciliationResult, StatementSource};
+use super::reconciliation_service::drift_report_to_health_issues;
+use super::reconciliation_traits::{
+    HoldingsDriftRepositoryTrait, HoldingsDriftServiceTrait, ReconciliationServiceTrait,
+};
+use crate::errors::Result;
+
+/// Default number of checks returned by [`HoldingsDriftServiceTrait::get_history`].
+pub const DEFAULT_DRIFT_HISTORY_LIMIT: i64 = 50;
+
+/// Service that records broker holdings observations and reconciles them
+/// against transaction-derived holdings after recalculation.
+pub struct HoldingsDriftService {
+    repository: Arc<dyn HoldingsDriftRepositoryTrait>,
+    reconciliation_service: Arc<dyn ReconciliationServiceTrait>,
+}
+
+impl HoldingsDriftService {
+    pub fn new(
+        repository: Arc<dyn HoldingsDriftRepositoryTrait>,
+        reconciliation_service: Arc<dyn ReconciliationServiceTrait>,
+    ) -> Self {
+        Self {
+            repository,
+            reconciliation_service,
+        }
+    }
+
+    /// Reconciles one pending check and persists the outcome.
+    async fn evaluate_check(
+        &self,
+        mut check: HoldingsDriftCheck,
+    ) -> Result<Option<ReconciliationResult>> {
+        check.checked_at = Some(Utc::now().naive_utc());
+
+        let result = match self
+            .reconciliation_service
+            .reconcile(check.statement.clone())
+        {
+            Ok(report) => {
+                check.status = HoldingsDriftStatus::Checked;
+                check.discrepancy_count = report.discrepancies.len() as i32;
+                check.error = None;
+                let issues = drift_report_to_health_issues(&report);
+                check.report = Some(report.clone());
+                Some(ReconciliationResult { report, issues })
+            }
+            Err(e) => {
+                warn!(
+                    "Holdings drift check for account {} failed: {}",
+                    check.account_id, e
+                );
+                check.status = HoldingsDriftStatus::Failed;
+                check.error = Some(e.to_string());
+                None
+            }
+        };
+
+        self.repository.upsert_check(check).await?;
+        Ok(result)
+    }
+}
+
+#[async_trait]
+impl HoldingsDriftServiceTrait for HoldingsDriftService {
+    async fn record_observation(&self, statement: BrokerStatement) -> Result<HoldingsDriftCheck> {
+        let check = HoldingsDriftCheck {
+            id: Uuid::new_v4().to_string(),
+            account_id: statement.account_id.clone(),
+            as_of_date: statement.as_of_date,
+            status: HoldingsDriftStatus::Pending,
+            statement: BrokerStatement {
+                source: StatementSource::BrokerSync,
+                ..statement
+            },
+            report: None,
+            discrepancy_count: 0,
+            error: None,
+            created_at: Utc::now().naive_utc(),
+            checked_at: None,
+        };
+        debug!(
+            "Recorded holdings observation for account {} ({} positions)",
+            check.account_id,
+            check.statement.positions.len()
+        );
+        self.repository.upsert_check(check).await
+    }
+
+    async fn evaluate_pending(
+        &self,
+        account_ids: Option<&[String]>,
+    ) -> Result<Vec<ReconciliationResult>> {
+        let pending = self.repository.list_pending()?;
+
+        // Pending checks come oldest first, so the last one per account wins.
+        let mut latest: HashMap<String, HoldingsDriftCheck> = HashMap::new();
+        let mut superseded = Vec::new();
+        for check in pending {
+            if let Some(ids) = account_ids {
+                if !ids.iter().any(|id| id == &check.account_id) {
+                    continue;
+                }
+            }
+            if let Some(previous) = latest.insert(check.account_id.clone(), check) {
+                superseded.push(previous);
+            }
+        }
+
+        for mut check in superseded {
+            check.status = HoldingsDriftStatus::Superseded;
+            check.checked_at = Some(Utc::now().naive_utc());
+            self.repository.upsert_check(check).await?;
+        }
+
+        let mut checks: Vec<HoldingsDriftCheck> = latest.into_values().collect();
+        checks.sort_by(|a, b| a.account_id.cmp(&b.account_id));
+
+        let mut results = Vec::with_capacity(checks.len());
+        for check in checks {
+            if let Some(result) = self.evaluate_check(check).await? {
+                if !result.report.is_reconciled() {
+                    info!(
+                        "Holdings drift detected for account {}: {} discrepancies",
+                        result.report.account_id,
+                        result.report.discrepancies.len()
+                    );
+                }
+                results.push(result);
+            }
+        }
+        Ok(results)
+    }
+
+    fn latest_results(&self) -> Result<Vec<ReconciliationResult>> {
+        Ok(self
+            .repository
+            .latest_checked()?
+            .into_iter()
+            .filter_map(|check| check.report)
+            .map(|report| {
+                let issues = drift_report_to_health_issues(&report);
+                ReconciliationResult { report, issues }
+            })
+            .collect())
+    }
+
+    fn get_history(&self, account_id: Option<&str>, limit: i64) -> Result<Vec<HoldingsDriftCheck>> {
+        self.repository.list_checks(account_id, limit)
+    }
+}
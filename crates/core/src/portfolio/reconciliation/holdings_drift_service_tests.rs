@@ -0,0 +1,275 @@
+//! Unit tests for holdings drift detection.
+
+use super::*;
+use crate::errors::{Error, Result};
+use crate::health::HealthCategory;
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate, Utc};
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+// ============================================================================
+// Mocks
+// ============================================================================
+
+#[derive(Default)]
+struct MockDriftRepository {
+    checks: Mutex<Vec<HoldingsDriftCheck>>,
+}
+
+impl MockDriftRepository {
+    fn all(&self) -> Vec<HoldingsDriftCheck> {
+        self.checks.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl HoldingsDriftRepositoryTrait for MockDriftRepository {
+    async fn upsert_check(&self, check: HoldingsDriftCheck) -> Result<HoldingsDriftCheck> {
+        let mut checks = self.checks.lock().unwrap();
+        match checks.iter_mut().find(|c| c.id == check.id) {
+            Some(existing) => *existing = check.clone(),
+            None => checks.push(check.clone()),
+        }
+        Ok(check)
+    }
+
+    fn list_pending(&self) -> Result<Vec<HoldingsDriftCheck>> {
+        let mut pending: Vec<_> = self
+            .all()
+            .into_iter()
+            .filter(|c| c.status == HoldingsDriftStatus::Pending)
+            .collect();
+        pending.sort_by_key(|c| c.created_at);
+        Ok(pending)
+    }
+
+    fn list_checks(&self, account_id: Option<&str>, limit: i64) -> Result<Vec<HoldingsDriftCheck>> {
+        let mut checks: Vec<_> = self
+            .all()
+            .into_iter()
+            .filter(|c| account_id.is_none_or(|id| c.account_id == id))
+            .collect();
+        checks.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        checks.truncate(limit as usize);
+        Ok(checks)
+    }
+
+    fn latest_checked(&self) -> Result<Vec<HoldingsDriftCheck>> {
+        let mut latest: HashMap<String, HoldingsDriftCheck> = HashMap::new();
+        for check in self.all() {
+            if check.status != HoldingsDriftStatus::Checked {
+                continue;
+            }
+            let newer = latest
+                .get(&check.account_id)
+                .is_none_or(|c| c.created_at < check.created_at);
+            if newer {
+                latest.insert(check.account_id.clone(), check);
+            }
+        }
+        Ok(latest.into_values().collect())
+    }
+}
+
+/// Reports one quantity mismatch per broker position whose quantity is not 10.
+#[derive(Default)]
+struct MockReconciliationService {
+    reconciled: Mutex<Vec<BrokerStatement>>,
+}
+
+impl ReconciliationServiceTrait for MockReconciliationService {
+    fn reconcile(&self, statement: BrokerStatement) -> Result<ReconciliationReport> {
+        self.reconciled.lock().unwrap().push(statement.clone());
+        if statement.account_id == "MISSING" {
+            return Err(Error::Unexpected("Account not found".to_string()));
+        }
+        let discrepancies = statement
+            .positions
+            .iter()
+            .filter(|p| p.quantity != dec!(10))
+            .map(|p| Discrepancy {
+                kind: DiscrepancyKind::QuantityMismatch,
+                asset_id: p.asset_id.clone(),
+                symbol: p.symbol.clone(),
+                currency: p.currency.clone(),
+                broker_quantity: Some(p.quantity),
+                local_quantity: Some(dec!(10)),
+                broker_cost_basis: None,
+                local_cost_basis: None,
+                delta: p.quantity - dec!(10),
+                suggested_fix: None,
+            })
+            .collect();
+        Ok(ReconciliationReport {
+            account_id: statement.account_id.clone(),
+            account_name: "Brokerage".to_string(),
+            as_of_date: statement.as_of_date,
+            snapshot_date: Some(statement.as_of_date),
+            source: statement.source,
+            positions_compared: statement.positions.len(),
+            discrepancies,
+        })
+    }
+
+    fn reconcile_csv(
+        &self,
+        _account_id: &str,
+        _as_of_date: NaiveDate,
+        _content: &[u8],
+    ) -> Result<ReconciliationReport> {
+        unimplemented!()
+    }
+
+    fn to_health_issues(&self, report: &ReconciliationReport) -> Vec<crate::health::HealthIssue> {
+        report_to_health_issues(report)
+    }
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn statement(account_id: &str, quantity: rust_decimal::Decimal) -> BrokerStatement {
+    BrokerStatement {
+        account_id: account_id.to_string(),
+        as_of_date: NaiveDate::from_ymd_opt(2025, 6, 30).unwrap(),
+        source: StatementSource::File,
+        positions: vec![BrokerPosition {
+            asset_id: Some("SEC:AAPL:XNAS".to_string()),
+            symbol: "AAPL".to_string(),
+            quantity,
+            cost_basis: None,
+            currency: "USD".to_string(),
+        }],
+        cash_balances: HashMap::new(),
+    }
+}
+
+fn setup() -> (
+    HoldingsDriftService,
+    Arc<MockDriftRepository>,
+    Arc<MockReconciliationService>,
+) {
+    let repository = Arc::new(MockDriftRepository::default());
+    let reconciliation = Arc::new(MockReconciliationService::default());
+    let service = HoldingsDriftService::new(repository.clone(), reconciliation.clone());
+    (service, repository, reconciliation)
+}
+
+/// Records an observation and backdates it so ordering does not depend on timing.
+async fn observe(
+    service: &HoldingsDriftService,
+    repository: &MockDriftRepository,
+    statement: BrokerStatement,
+    minutes_ago: i64,
+) -> HoldingsDriftCheck {
+    let mut check = service.record_observation(statement).await.unwrap();
+    check.created_at = Utc::now().naive_utc() - Duration::minutes(minutes_ago);
+    repository.upsert_check(check).await.unwrap()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_record_observation_is_pending_broker_sync() {
+    let (service, repository, _) = setup();
+
+    let check = service
+        .record_observation(statement("ACC1", dec!(10)))
+        .await
+        .unwrap();
+
+    assert_eq!(check.status, HoldingsDriftStatus::Pending);
+    assert_eq!(check.statement.source, StatementSource::BrokerSync);
+    assert!(check.report.is_none());
+    assert_eq!(repository.list_pending().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_evaluate_pending_reports_drift_as_health_issues() {
+    let (service, repository, _) = setup();
+    observe(&service, &repository, statement("ACC1", dec!(12)), 0).await;
+
+    let results = service.evaluate_pending(None).await.unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].report.discrepancies.len(), 1);
+    assert_eq!(results[0].issues.len(), 1);
+    let issue = &results[0].issues[0];
+    assert_eq!(issue.category, HealthCategory::HoldingsDrift);
+    assert!(issue.id.starts_with("holdings_drift:ACC1:"));
+
+    let stored = repository.all();
+    assert_eq!(stored[0].status, HoldingsDriftStatus::Checked);
+    assert_eq!(stored[0].discrepancy_count, 1);
+    assert!(stored[0].checked_at.is_some());
+    assert!(repository.list_pending().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_evaluate_pending_supersedes_older_observations() {
+    let (service, repository, reconciliation) = setup();
+    let older = observe(&service, &repository, statement("ACC1", dec!(5)), 10).await;
+    let newer = observe(&service, &repository, statement("ACC1", dec!(10)), 1).await;
+
+    let results = service.evaluate_pending(None).await.unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert!(results[0].report.is_reconciled());
+    assert_eq!(reconciliation.reconciled.lock().unwrap().len(), 1);
+
+    let history = service.get_history(Some("ACC1"), 10).unwrap();
+    let status = |id: &str| history.iter().find(|c| c.id == id).unwrap().status;
+    assert_eq!(status(&older.id), HoldingsDriftStatus::Superseded);
+    assert_eq!(status(&newer.id), HoldingsDriftStatus::Checked);
+}
+
+#[tokio::test]
+async fn test_evaluate_pending_only_touches_requested_accounts() {
+    let (service, repository, _) = setup();
+    observe(&service, &repository, statement("ACC1", dec!(12)), 2).await;
+    let other = observe(&service, &repository, statement("ACC2", dec!(12)), 1).await;
+
+    let results = service
+        .evaluate_pending(Some(&["ACC1".to_string()]))
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].report.account_id, "ACC1");
+    let pending = repository.list_pending().unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].id, other.id);
+}
+
+#[tokio::test]
+async fn test_failed_check_is_recorded_without_result() {
+    let (service, repository, _) = setup();
+    observe(&service, &repository, statement("MISSING", dec!(12)), 0).await;
+
+    let results = service.evaluate_pending(None).await.unwrap();
+
+    assert!(results.is_empty());
+    let stored = repository.all();
+    assert_eq!(stored[0].status, HoldingsDriftStatus::Failed);
+    assert!(stored[0].error.is_some());
+}
+
+#[tokio::test]
+async fn test_latest_results_rebuilds_issues_from_checked_reports() {
+    let (service, repository, _) = setup();
+    observe(&service, &repository, statement("ACC1", dec!(12)), 5).await;
+    service.evaluate_pending(None).await.unwrap();
+    observe(&service, &repository, statement("ACC1", dec!(10)), 0).await;
+
+    // The newer observation is still pending, so the last checked report stands.
+    let results = service.latest_results().unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].issues.len(), 1);
+    assert_eq!(results[0].issues[0].category, HealthCategory::HoldingsDrift);
+}
@@ -0,0 +1,29 @@
+//! Broker reconciliation module.
+//!
+//! Compares positions and cash reported by a broker (statement file or Connect
+//! holdings) with the computed holdings snapshot for the same date, and proposes
+//! draft activities (SPLIT, ADJUSTMENT, TRANSFER, FEE, CREDIT) that would close
+//! each gap.
+//!
+//! Broker syncs also record the holdings they fetch; once snapshots are
+//! recalculated those observations are compared the same way, so drift between
+//! broker and transaction-derived holdings surfaces as health issues.
+
+mod holdings_drift_model;
+mod holdings_drift_service;
+mod reconciliation_model;
+mod reconciliation_service;
+mod reconciliation_traits;
+mod statement_parser;
+
+pub use holdings_drift_model::*;
+pub use holdings_drift_service::*;
+pub use reconciliation_model::*;
+pub use reconciliation_service::*;
+pub use reconciliation_traits::*;
+pub use statement_parser::*;
+
+#[cfg(test)]
+mod holdings_drift_service_tests;
+#[cfg(test)]
+mod reconciliation_service_tests;
@@ -0,0 +1,179 @@
+//! Broker reconciliation domain models.
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::activities::NewActivity;
+use crate::health::HealthIssue;
+
+/// Where a broker statement came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StatementSource {
+    /// Uploaded statement file (CSV export from the broker)
+    #[default]
+    File,
+    /// Holdings fetched from a Connect broker sync
+    BrokerSync,
+    /// Entered by hand
+    Manual,
+}
+
+/// A single position as reported by the broker.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokerPosition {
+    /// Local asset ID, when the caller already resolved it.
+    #[serde(default)]
+    pub asset_id: Option<String>,
+    /// Ticker as reported by the broker (used to resolve the local asset).
+    pub symbol: String,
+    pub quantity: Decimal,
+    /// Total cost basis reported by the broker, in `currency`.
+    #[serde(default)]
+    pub cost_basis: Option<Decimal>,
+    pub currency: String,
+}
+
+/// Positions and cash balances reported by a broker for one account on one date.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokerStatement {
+    pub account_id: String,
+    pub as_of_date: NaiveDate,
+    #[serde(default)]
+    pub source: StatementSource,
+    #[serde(default)]
+    pub positions: Vec<BrokerPosition>,
+    /// currency -> cash amount
+    #[serde(default)]
+    pub cash_balances: HashMap<String, Decimal>,
+}
+
+/// Thresholds below which differences are treated as rounding noise.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationTolerance {
+    /// Absolute quantity difference ignored (default: 0.0001)
+    pub quantity: Decimal,
+    /// Relative cost basis difference ignored (default: 0.005 = 0.5%)
+    pub cost_basis_pct: Decimal,
+    /// Absolute cash difference ignored, per currency (default: 0.01)
+    pub cash: Decimal,
+}
+
+impl Default for ReconciliationTolerance {
+    fn default() -> Self {
+        Self {
+            quantity: dec!(0.0001),
+            cost_basis_pct: dec!(0.005),
+            cash: dec!(0.01),
+        }
+    }
+}
+
+/// The kind of difference found between the broker and computed holdings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DiscrepancyKind {
+    /// Both sides hold the asset but quantities differ
+    QuantityMismatch,
+    /// Quantities match but total cost basis differs
+    CostBasisMismatch,
+    /// The broker reports a position that is not in the computed holdings
+    MissingLocally,
+    /// The computed holdings contain a position the broker does not report
+    MissingAtBroker,
+    /// Cash balance in one currency differs
+    CashDelta,
+}
+
+impl DiscrepancyKind {
+    /// Returns the string representation of this kind.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiscrepancyKind::QuantityMismatch => "QUANTITY_MISMATCH",
+            DiscrepancyKind::CostBasisMismatch => "COST_BASIS_MISMATCH",
+            DiscrepancyKind::MissingLocally => "MISSING_LOCALLY",
+            DiscrepancyKind::MissingAtBroker => "MISSING_AT_BROKER",
+            DiscrepancyKind::CashDelta => "CASH_DELTA",
+        }
+    }
+}
+
+/// The kind of correction proposed for a discrepancy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SuggestedFixKind {
+    /// A missing SPLIT explains the quantity difference
+    Split,
+    /// A quantity or cost basis ADJUSTMENT
+    Adjustment,
+    /// The position was moved in or out of the account
+    Transfer,
+    /// A missed FEE explains a lower broker cash balance
+    Fee,
+    /// A missed CREDIT explains a higher broker cash balance
+    Credit,
+}
+
+/// A proposed correction, expressed as a draft activity the user can record.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestedFix {
+    pub kind: SuggestedFixKind,
+    /// Short explanation shown next to the draft activity
+    pub description: String,
+    pub activity: NewActivity,
+}
+
+/// One difference between the broker statement and the computed holdings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Discrepancy {
+    pub kind: DiscrepancyKind,
+    /// Local asset ID (None for cash deltas and unresolved broker symbols)
+    pub asset_id: Option<String>,
+    /// Ticker for display, or the currency code for cash deltas
+    pub symbol: String,
+    pub currency: String,
+    pub broker_quantity: Option<Decimal>,
+    pub local_quantity: Option<Decimal>,
+    pub broker_cost_basis: Option<Decimal>,
+    pub local_cost_basis: Option<Decimal>,
+    /// broker - local, in quantity units (or currency units for cash)
+    pub delta: Decimal,
+    pub suggested_fix: Option<SuggestedFix>,
+}
+
+/// Result of reconciling one broker statement against computed holdings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationReport {
+    pub account_id: String,
+    pub account_name: String,
+    pub as_of_date: NaiveDate,
+    /// Date of the holdings snapshot used for comparison (None if the account has no holdings yet)
+    pub snapshot_date: Option<NaiveDate>,
+    pub source: StatementSource,
+    pub positions_compared: usize,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+impl ReconciliationReport {
+    /// Returns true when the broker and computed holdings agree within tolerance.
+    pub fn is_reconciled(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+}
+
+/// A reconciliation report together with the health issues derived from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationResult {
+    pub report: ReconciliationReport,
+    pub issues: Vec<HealthIssue>,
+}
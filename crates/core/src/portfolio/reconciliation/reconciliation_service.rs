@@ -0,0 +1,733 @@
+//! Broker reconciliation service implementation.
+
+use chrono::NaiveDate;
+use log::{debug, info};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use super::reconciliation_model::{
+    BrokerPosition, BrokerStatement, Discrepancy, DiscrepancyKind, ReconciliationReport,
+    ReconciliationTolerance, SuggestedFix, SuggestedFixKind,
+};
+use super::reconciliation_traits::ReconciliationServiceTrait;
+use super::statement_parser::parse_statement_csv;
+use crate::accounts::AccountRepositoryTrait;
+use crate::activities::{
+    NewActivity, SymbolInput, ACTIVITY_TYPE_ADJUSTMENT, ACTIVITY_TYPE_CREDIT, ACTIVITY_TYPE_FEE,
+    ACTIVITY_TYPE_SPLIT, ACTIVITY_TYPE_TRANSFER_IN, ACTIVITY_TYPE_TRANSFER_OUT,
+};
+use crate::assets::{Asset, AssetRepositoryTrait};
+use crate::errors::Result;
+use crate::health::{
+    AffectedItem, FixAction, HealthCategory, HealthIssue, NavigateAction, Severity,
+};
+use crate::portfolio::snapshot::{AccountStateSnapshot, SnapshotServiceTrait};
+
+/// `source_system` recorded on activities created from a reconciliation fix.
+pub const RECONCILIATION_SOURCE_SYSTEM: &str = "RECONCILIATION";
+
+/// Tolerance used when deciding whether a quantity ratio is a clean split ratio.
+const SPLIT_RATIO_TOLERANCE: Decimal = dec!(0.001);
+
+/// Service that compares broker-reported positions with computed holdings.
+pub struct ReconciliationService {
+    account_repository: Arc<dyn AccountRepositoryTrait>,
+    asset_repository: Arc<dyn AssetRepositoryTrait>,
+    snapshot_service: Arc<dyn SnapshotServiceTrait>,
+    tolerance: ReconciliationTolerance,
+}
+
+impl ReconciliationService {
+    /// Creates a new ReconciliationService with default tolerances.
+    pub fn new(
+        account_repository: Arc<dyn AccountRepositoryTrait>,
+        asset_repository: Arc<dyn AssetRepositoryTrait>,
+        snapshot_service: Arc<dyn SnapshotServiceTrait>,
+    ) -> Self {
+        Self {
+            account_repository,
+            asset_repository,
+            snapshot_service,
+            tolerance: ReconciliationTolerance::default(),
+        }
+    }
+
+    /// Overrides the default tolerances.
+    pub fn with_tolerance(mut self, tolerance: ReconciliationTolerance) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Loads the holdings snapshot for the account as of the given date.
+    fn snapshot_for_date(
+        &self,
+        account_id: &str,
+        date: NaiveDate,
+    ) -> Result<Option<AccountStateSnapshot>> {
+        let mut snapshots = self.snapshot_service.get_daily_holdings_snapshots(
+            account_id,
+            Some(date),
+            Some(date),
+        )?;
+        Ok(snapshots.pop())
+    }
+
+    /// Resolves broker symbols to local asset IDs, preferring assets already held.
+    fn resolve_asset_ids(
+        &self,
+        statement: &mut BrokerStatement,
+        held_assets: &[Asset],
+    ) -> Result<()> {
+        let mut by_symbol: HashMap<String, String> = HashMap::new();
+        for asset in held_assets {
+            for code in [&asset.instrument_symbol, &asset.display_code]
+                .into_iter()
+                .flatten()
+            {
+                by_symbol
+                    .entry(code.to_uppercase())
+                    .or_insert_with(|| asset.id.clone());
+            }
+        }
+
+        for position in statement.positions.iter_mut() {
+            if position.asset_id.is_some() {
+                continue;
+            }
+            let symbol = position.symbol.to_uppercase();
+            if let Some(asset_id) = by_symbol.get(&symbol) {
+                position.asset_id = Some(asset_id.clone());
+                continue;
+            }
+            // Fall back to any local asset with a matching symbol
+            position.asset_id = self
+                .asset_repository
+                .search_by_symbol(&symbol)?
+                .into_iter()
+                .find(|asset| {
+                    asset
+                        .instrument_symbol
+                        .as_deref()
+                        .or(asset.display_code.as_deref())
+                        .map(|code| code.eq_ignore_ascii_case(&symbol))
+                        .unwrap_or(false)
+                })
+                .map(|asset| asset.id);
+        }
+        Ok(())
+    }
+}
+
+impl ReconciliationServiceTrait for ReconciliationService {
+    fn reconcile(&self, mut statement: BrokerStatement) -> Result<ReconciliationReport> {
+        let account = self.account_repository.get_by_id(&statement.account_id)?;
+        let snapshot = self.snapshot_for_date(&account.id, statement.as_of_date)?;
+
+        let held_asset_ids: Vec<String> = snapshot
+            .as_ref()
+            .map(|s| s.positions.keys().cloned().collect())
+            .unwrap_or_default();
+        let held_assets = if held_asset_ids.is_empty() {
+            Vec::new()
+        } else {
+            self.asset_repository.list_by_asset_ids(&held_asset_ids)?
+        };
+        self.resolve_asset_ids(&mut statement, &held_assets)?;
+
+        let symbols: HashMap<String, String> = held_assets
+            .iter()
+            .map(|asset| {
+                let symbol = asset
+                    .display_code
+                    .clone()
+                    .or_else(|| asset.instrument_symbol.clone())
+                    .unwrap_or_else(|| asset.id.clone());
+                (asset.id.clone(), symbol)
+            })
+            .collect();
+
+        let report = reconcile_statement(
+            &statement,
+            &account.name,
+            snapshot.as_ref(),
+            &symbols,
+            &self.tolerance,
+        );
+
+        info!(
+            "Reconciled account {} as of {}: {} discrepancies across {} positions",
+            report.account_id,
+            report.as_of_date,
+            report.discrepancies.len(),
+            report.positions_compared
+        );
+        Ok(report)
+    }
+
+    fn reconcile_csv(
+        &self,
+        account_id: &str,
+        as_of_date: NaiveDate,
+        content: &[u8],
+    ) -> Result<ReconciliationReport> {
+        let account = self.account_repository.get_by_id(account_id)?;
+        let statement = parse_statement_csv(account_id, as_of_date, &account.currency, content)?;
+        debug!(
+            "Parsed broker statement for {}: {} positions, {} cash balances",
+            account_id,
+            statement.positions.len(),
+            statement.cash_balances.len()
+        );
+        self.reconcile(statement)
+    }
+
+    fn to_health_issues(&self, report: &ReconciliationReport) -> Vec<HealthIssue> {
+        report_to_health_issues(report)
+    }
+}
+
+/// Compares a broker statement with a computed holdings snapshot.
+///
+/// Statement positions are matched to snapshot positions by `asset_id`; positions
+/// without a resolved asset ID are reported as missing locally. `symbols` maps
+/// local asset IDs to display symbols.
+pub fn reconcile_statement(
+    statement: &BrokerStatement,
+    account_name: &str,
+    snapshot: Option<&AccountStateSnapshot>,
+    symbols: &HashMap<String, String>,
+    tolerance: &ReconciliationTolerance,
+) -> ReconciliationReport {
+    let account_id = statement.account_id.as_str();
+    let date = statement.as_of_date;
+    let mut discrepancies = Vec::new();
+
+    // Aggregate broker positions by asset (brokers may report several tax lots)
+    let mut broker_by_asset: HashMap<String, (String, Decimal, Option<Decimal>, String)> =
+        HashMap::new();
+    for position in &statement.positions {
+        let Some(asset_id) = position.asset_id.clone() else {
+            discrepancies.push(missing_locally(account_id, date, None, position));
+            continue;
+        };
+        let entry = broker_by_asset.entry(asset_id).or_insert_with(|| {
+            (
+                position.symbol.clone(),
+                Decimal::ZERO,
+                Some(Decimal::ZERO),
+                position.currency.clone(),
+            )
+        });
+        entry.1 += position.quantity;
+        entry.2 = match (entry.2, position.cost_basis) {
+            (Some(total), Some(basis)) => Some(total + basis),
+            _ => None,
+        };
+    }
+
+    let empty = HashMap::new();
+    let local_positions = snapshot.map(|s| &s.positions).unwrap_or(&empty);
+
+    let asset_ids: BTreeSet<&String> = broker_by_asset
+        .keys()
+        .chain(local_positions.keys())
+        .collect();
+    for asset_id in &asset_ids {
+        let broker = broker_by_asset.get(*asset_id);
+        let local = local_positions
+            .get(*asset_id)
+            .filter(|p| !p.quantity.is_zero());
+
+        match (broker, local) {
+            (Some((symbol, quantity, cost_basis, currency)), None) => {
+                if quantity.abs() <= tolerance.quantity {
+                    continue;
+                }
+                let position = BrokerPosition {
+                    asset_id: Some((*asset_id).clone()),
+                    symbol: symbol.clone(),
+                    quantity: *quantity,
+                    cost_basis: *cost_basis,
+                    currency: currency.clone(),
+                };
+                discrepancies.push(missing_locally(
+                    account_id,
+                    date,
+                    Some(asset_id.as_str()),
+                    &position,
+                ));
+            }
+            (None, Some(local)) => {
+                let symbol = symbols
+                    .get(*asset_id)
+                    .cloned()
+                    .unwrap_or_else(|| (*asset_id).clone());
+                let fix = SuggestedFix {
+                    kind: SuggestedFixKind::Transfer,
+                    description: format!(
+                        "Record a transfer out of {} {} that the broker no longer reports",
+                        local.quantity.normalize(),
+                        symbol
+                    ),
+                    activity: draft_activity(
+                        account_id,
+                        date,
+                        ACTIVITY_TYPE_TRANSFER_OUT,
+                        Some(asset_id.as_str()),
+                        &local.currency,
+                        Some(local.quantity),
+                        Some(local.average_cost),
+                        None,
+                    ),
+                };
+                discrepancies.push(Discrepancy {
+                    kind: DiscrepancyKind::MissingAtBroker,
+                    asset_id: Some((*asset_id).clone()),
+                    symbol,
+                    currency: local.currency.clone(),
+                    broker_quantity: None,
+                    local_quantity: Some(local.quantity),
+                    broker_cost_basis: None,
+                    local_cost_basis: Some(local.total_cost_basis),
+                    delta: -local.quantity,
+                    suggested_fix: Some(fix),
+                });
+            }
+            (Some((symbol, broker_qty, broker_basis, currency)), Some(local)) => {
+                let delta = *broker_qty - local.quantity;
+                let same_currency = currency.eq_ignore_ascii_case(&local.currency);
+                if delta.abs() > tolerance.quantity {
+                    let basis_unchanged = match broker_basis {
+                        Some(basis) if same_currency => {
+                            within_pct(*basis, local.total_cost_basis, tolerance.cost_basis_pct)
+                        }
+                        _ => true,
+                    };
+                    let split_ratio = if basis_unchanged {
+                        detect_split_ratio(*broker_qty, local.quantity)
+                    } else {
+                        None
+                    };
+                    let fix = match split_ratio {
+                        Some(ratio) => SuggestedFix {
+                            kind: SuggestedFixKind::Split,
+                            description: format!(
+                                "Quantity changed by a factor of {} with unchanged cost basis; record the missing split",
+                                ratio.normalize()
+                            ),
+                            activity: draft_activity(
+                                account_id,
+                                date,
+                                ACTIVITY_TYPE_SPLIT,
+                                Some(asset_id.as_str()),
+                                &local.currency,
+                                None,
+                                None,
+                                Some(ratio),
+                            ),
+                        },
+                        None => SuggestedFix {
+                            kind: SuggestedFixKind::Adjustment,
+                            description: format!(
+                                "Adjust the quantity by {} to match the broker",
+                                delta.normalize()
+                            ),
+                            activity: draft_activity(
+                                account_id,
+                                date,
+                                ACTIVITY_TYPE_ADJUSTMENT,
+                                Some(asset_id.as_str()),
+                                &local.currency,
+                                Some(delta),
+                                None,
+                                None,
+                            ),
+                        },
+                    };
+                    discrepancies.push(Discrepancy {
+                        kind: DiscrepancyKind::QuantityMismatch,
+                        asset_id: Some((*asset_id).clone()),
+                        symbol: symbol.clone(),
+                        currency: local.currency.clone(),
+                        broker_quantity: Some(*broker_qty),
+                        local_quantity: Some(local.quantity),
+                        broker_cost_basis: *broker_basis,
+                        local_cost_basis: Some(local.total_cost_basis),
+                        delta,
+                        suggested_fix: Some(fix),
+                    });
+                } else if let Some(basis) = broker_basis.filter(|_| same_currency) {
+                    if !within_pct(basis, local.total_cost_basis, tolerance.cost_basis_pct) {
+                        let basis_delta = basis - local.total_cost_basis;
+                        let fix = SuggestedFix {
+                            kind: SuggestedFixKind::Adjustment,
+                            description: format!(
+                                "Adjust the cost basis by {} {} to match the broker",
+                                basis_delta.round_dp(2),
+                                local.currency
+                            ),
+                            activity: draft_activity(
+                                account_id,
+                                date,
+                                ACTIVITY_TYPE_ADJUSTMENT,
+                                Some(asset_id.as_str()),
+                                &local.currency,
+                                None,
+                                None,
+                                Some(basis_delta),
+                            ),
+                        };
+                        discrepancies.push(Discrepancy {
+                            kind: DiscrepancyKind::CostBasisMismatch,
+                            asset_id: Some((*asset_id).clone()),
+                            symbol: symbol.clone(),
+                            currency: local.currency.clone(),
+                            broker_quantity: Some(*broker_qty),
+                            local_quantity: Some(local.quantity),
+                            broker_cost_basis: Some(basis),
+                            local_cost_basis: Some(local.total_cost_basis),
+                            delta: basis_delta,
+                            suggested_fix: Some(fix),
+                        });
+                    }
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    // Cash balances per currency
+    let empty_cash = HashMap::new();
+    let local_cash = snapshot.map(|s| &s.cash_balances).unwrap_or(&empty_cash);
+    let currencies: BTreeSet<&String> = statement
+        .cash_balances
+        .keys()
+        .chain(local_cash.keys())
+        .collect();
+    for currency in currencies {
+        let broker = statement
+            .cash_balances
+            .get(currency)
+            .copied()
+            .unwrap_or(Decimal::ZERO);
+        let local = local_cash.get(currency).copied().unwrap_or(Decimal::ZERO);
+        let delta = broker - local;
+        if delta.abs() <= tolerance.cash {
+            continue;
+        }
+        let (kind, activity_type, description) = if delta.is_sign_negative() {
+            (
+                SuggestedFixKind::Fee,
+                ACTIVITY_TYPE_FEE,
+                format!(
+                    "Broker cash is {} {} lower; record the missed fee",
+                    delta.abs().round_dp(2),
+                    currency
+                ),
+            )
+        } else {
+            (
+                SuggestedFixKind::Credit,
+                ACTIVITY_TYPE_CREDIT,
+                format!(
+                    "Broker cash is {} {} higher; record the missed credit",
+                    delta.round_dp(2),
+                    currency
+                ),
+            )
+        };
+        discrepancies.push(Discrepancy {
+            kind: DiscrepancyKind::CashDelta,
+            asset_id: None,
+            symbol: currency.clone(),
+            currency: currency.clone(),
+            broker_quantity: Some(broker),
+            local_quantity: Some(local),
+            broker_cost_basis: None,
+            local_cost_basis: None,
+            delta,
+            suggested_fix: Some(SuggestedFix {
+                kind,
+                description,
+                activity: draft_activity(
+                    account_id,
+                    date,
+                    activity_type,
+                    None,
+                    currency,
+                    None,
+                    None,
+                    Some(delta.abs()),
+                ),
+            }),
+        });
+    }
+
+    ReconciliationReport {
+        account_id: account_id.to_string(),
+        account_name: account_name.to_string(),
+        as_of_date: date,
+        snapshot_date: snapshot.map(|s| s.snapshot_date),
+        source: statement.source,
+        positions_compared: asset_ids.len(),
+        discrepancies,
+    }
+}
+
+/// Converts each discrepancy of a report into a health issue with its suggested fix.
+pub fn report_to_health_issues(report: &ReconciliationReport) -> Vec<HealthIssue> {
+    discrepancies_to_health_issues(report, HealthCategory::Reconciliation, "reconciliation")
+}
+
+/// Converts the discrepancies of a post-sync drift check into health issues.
+///
+/// Same issues as [`report_to_health_issues`], filed under the holdings drift
+/// category so they can be told apart from manual statement reconciliations.
+pub fn drift_report_to_health_issues(report: &ReconciliationReport) -> Vec<HealthIssue> {
+    discrepancies_to_health_issues(report, HealthCategory::HoldingsDrift, "holdings_drift")
+}
+
+fn discrepancies_to_health_issues(
+    report: &ReconciliationReport,
+    category: HealthCategory,
+    id_prefix: &str,
+) -> Vec<HealthIssue> {
+    report
+        .discrepancies
+        .iter()
+        .map(|d| {
+            let (severity, title, message) = match d.kind {
+                DiscrepancyKind::QuantityMismatch => (
+                    Severity::Error,
+                    format!("{} quantity differs from broker", d.symbol),
+                    format!(
+                        "{} reports {} units, computed holdings show {}.",
+                        report.account_name,
+                        fmt_opt(d.broker_quantity),
+                        fmt_opt(d.local_quantity)
+                    ),
+                ),
+                DiscrepancyKind::CostBasisMismatch => (
+                    Severity::Warning,
+                    format!("{} cost basis differs from broker", d.symbol),
+                    format!(
+                        "{} reports a cost basis of {}, computed holdings show {}.",
+                        report.account_name,
+                        fmt_opt(d.broker_cost_basis.map(|v| v.round_dp(2))),
+                        fmt_opt(d.local_cost_basis.map(|v| v.round_dp(2)))
+                    ),
+                ),
+                DiscrepancyKind::MissingLocally => (
+                    Severity::Error,
+                    format!("{} missing from holdings", d.symbol),
+                    format!(
+                        "{} reports {} units that are not in computed holdings.",
+                        report.account_name,
+                        fmt_opt(d.broker_quantity)
+                    ),
+                ),
+                DiscrepancyKind::MissingAtBroker => (
+                    Severity::Error,
+                    format!("{} not reported by broker", d.symbol),
+                    format!(
+                        "Computed holdings show {} units that {} does not report.",
+                        fmt_opt(d.local_quantity),
+                        report.account_name
+                    ),
+                ),
+                DiscrepancyKind::CashDelta => (
+                    Severity::Warning,
+                    format!("{} cash differs from broker", d.currency),
+                    format!(
+                        "{} reports {} {}, computed cash is {}.",
+                        report.account_name,
+                        fmt_opt(d.broker_quantity.map(|v| v.round_dp(2))),
+                        d.currency,
+                        fmt_opt(d.local_quantity.map(|v| v.round_dp(2)))
+                    ),
+                ),
+            };
+
+            let affected_item = match &d.asset_id {
+                Some(asset_id) => AffectedItem::asset(asset_id, &d.symbol),
+                None => AffectedItem::account(&report.account_id, &report.account_name),
+            };
+
+            let mut builder = HealthIssue::builder()
+                .id(format!(
+                    "{}:{}:{}:{}",
+                    id_prefix,
+                    report.account_id,
+                    d.kind.as_str().to_lowercase(),
+                    d.asset_id.as_deref().unwrap_or(&d.symbol)
+                ))
+                .severity(severity)
+                .category(category)
+                .title(title)
+                .message(message)
+                .affected_count(1)
+                .affected_items(vec![affected_item])
+                .navigate_action(NavigateAction::to_activities(Some(&d.symbol)))
+                .data_hash(compute_data_hash(report.as_of_date, d));
+
+            if let Some(fix) = &d.suggested_fix {
+                let label = match fix.kind {
+                    SuggestedFixKind::Split => "Record Split",
+                    SuggestedFixKind::Adjustment => "Record Adjustment",
+                    SuggestedFixKind::Transfer => "Record Transfer",
+                    SuggestedFixKind::Fee => "Record Fee",
+                    SuggestedFixKind::Credit => "Record Credit",
+                };
+                builder = builder.details(fix.description.clone());
+                if let Ok(payload) = serde_json::to_value(&fix.activity) {
+                    builder = builder.fix_action(FixAction::record_activity(label, payload));
+                }
+            }
+
+            builder.build()
+        })
+        .collect()
+}
+
+/// Builds the "missing locally" discrepancy for a broker position.
+fn missing_locally(
+    account_id: &str,
+    date: NaiveDate,
+    asset_id: Option<&str>,
+    position: &BrokerPosition,
+) -> Discrepancy {
+    let unit_cost = position
+        .cost_basis
+        .filter(|_| !position.quantity.is_zero())
+        .map(|basis| basis / position.quantity);
+    let mut activity = draft_activity(
+        account_id,
+        date,
+        ACTIVITY_TYPE_TRANSFER_IN,
+        asset_id,
+        &position.currency,
+        Some(position.quantity),
+        unit_cost,
+        None,
+    );
+    if asset_id.is_none() {
+        // Let activity creation resolve or create the asset from the broker symbol
+        activity.symbol = Some(SymbolInput {
+            symbol: Some(position.symbol.clone()),
+            ..Default::default()
+        });
+    }
+
+    Discrepancy {
+        kind: DiscrepancyKind::MissingLocally,
+        asset_id: asset_id.map(str::to_string),
+        symbol: position.symbol.clone(),
+        currency: position.currency.clone(),
+        broker_quantity: Some(position.quantity),
+        local_quantity: None,
+        broker_cost_basis: position.cost_basis,
+        local_cost_basis: None,
+        delta: position.quantity,
+        suggested_fix: Some(SuggestedFix {
+            kind: SuggestedFixKind::Transfer,
+            description: format!(
+                "Record a transfer in of {} {} reported by the broker",
+                position.quantity.normalize(),
+                position.symbol
+            ),
+            activity,
+        }),
+    }
+}
+
+/// Builds a draft activity for a suggested fix.
+#[allow(clippy::too_many_arguments)]
+fn draft_activity(
+    account_id: &str,
+    date: NaiveDate,
+    activity_type: &str,
+    asset_id: Option<&str>,
+    currency: &str,
+    quantity: Option<Decimal>,
+    unit_price: Option<Decimal>,
+    amount: Option<Decimal>,
+) -> NewActivity {
+    NewActivity {
+        id: None,
+        account_id: account_id.to_string(),
+        symbol: asset_id.map(|id| SymbolInput {
+            id: Some(id.to_string()),
+            ..Default::default()
+        }),
+        activity_type: activity_type.to_string(),
+        subtype: None,
+        activity_date: date.format("%Y-%m-%d").to_string(),
+        quantity,
+        unit_price,
+        currency: currency.to_string(),
+        fee: None,
+        amount,
+        status: None,
+        notes: Some(format!(
+            "Reconciliation against broker statement on {}",
+            date.format("%Y-%m-%d")
+        )),
+        fx_rate: None,
+        metadata: None,
+        needs_review: None,
+        source_system: Some(RECONCILIATION_SOURCE_SYSTEM.to_string()),
+        source_record_id: None,
+        source_group_id: None,
+        idempotency_key: None,
+    }
+}
+
+/// Returns the split ratio (new / old) when the quantity change looks like a clean
+/// forward (2:1, 3:1, ...) or reverse (1:2, 1:10, ...) split.
+fn detect_split_ratio(broker_quantity: Decimal, local_quantity: Decimal) -> Option<Decimal> {
+    if broker_quantity <= Decimal::ZERO || local_quantity <= Decimal::ZERO {
+        return None;
+    }
+    let ratio = broker_quantity / local_quantity;
+    let is_whole = |value: Decimal| {
+        let rounded = value.round();
+        rounded >= Decimal::TWO && (value - rounded).abs() <= SPLIT_RATIO_TOLERANCE * rounded
+    };
+    if is_whole(ratio) {
+        return Some(ratio.round());
+    }
+    let inverse = local_quantity / broker_quantity;
+    if is_whole(inverse) {
+        return Some(Decimal::ONE / inverse.round());
+    }
+    None
+}
+
+/// True when `a` and `b` differ by at most `pct` relative to the larger magnitude.
+fn within_pct(a: Decimal, b: Decimal, pct: Decimal) -> bool {
+    let scale = a.abs().max(b.abs());
+    if scale.is_zero() {
+        return true;
+    }
+    (a - b).abs() <= scale * pct
+}
+
+fn fmt_opt(value: Option<Decimal>) -> String {
+    value
+        .map(|v| v.normalize().to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Computes a data hash for issue identity and change detection.
+fn compute_data_hash(date: NaiveDate, discrepancy: &Discrepancy) -> String {
+    let mut hasher = DefaultHasher::new();
+    date.hash(&mut hasher);
+    discrepancy.kind.hash(&mut hasher);
+    discrepancy.symbol.hash(&mut hasher);
+    discrepancy.delta.normalize().to_string().hash(&mut hasher);
+    format!("{:x}", hasher.finish())
+}
@@ -0,0 +1,492 @@
+//! Unit tests for broker reconciliation.
+
+use super::*;
+use crate::activities::{
+    ACTIVITY_TYPE_ADJUSTMENT, ACTIVITY_TYPE_CREDIT, ACTIVITY_TYPE_FEE, ACTIVITY_TYPE_SPLIT,
+    ACTIVITY_TYPE_TRANSFER_IN, ACTIVITY_TYPE_TRANSFER_OUT,
+};
+use crate::health::{HealthCategory, Severity};
+use crate::portfolio::snapshot::{AccountStateSnapshot, Position};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 6, 30).unwrap()
+}
+
+fn position(asset_id: &str, quantity: Decimal, cost_basis: Decimal) -> Position {
+    Position {
+        id: format!("ACC1_{}", asset_id),
+        account_id: "ACC1".to_string(),
+        asset_id: asset_id.to_string(),
+        quantity,
+        average_cost: if quantity.is_zero() {
+            Decimal::ZERO
+        } else {
+            cost_basis / quantity
+        },
+        total_cost_basis: cost_basis,
+        currency: "USD".to_string(),
+        ..Default::default()
+    }
+}
+
+fn snapshot(positions: Vec<Position>, cash: Vec<(&str, Decimal)>) -> AccountStateSnapshot {
+    AccountStateSnapshot {
+        account_id: "ACC1".to_string(),
+        snapshot_date: date(),
+        currency: "USD".to_string(),
+        positions: positions
+            .into_iter()
+            .map(|p| (p.asset_id.clone(), p))
+            .collect(),
+        cash_balances: cash
+            .into_iter()
+            .map(|(ccy, amount)| (ccy.to_string(), amount))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn broker_position(
+    asset_id: Option<&str>,
+    symbol: &str,
+    quantity: Decimal,
+    cost_basis: Option<Decimal>,
+) -> BrokerPosition {
+    BrokerPosition {
+        asset_id: asset_id.map(str::to_string),
+        symbol: symbol.to_string(),
+        quantity,
+        cost_basis,
+        currency: "USD".to_string(),
+    }
+}
+
+fn statement(positions: Vec<BrokerPosition>, cash: Vec<(&str, Decimal)>) -> BrokerStatement {
+    BrokerStatement {
+        account_id: "ACC1".to_string(),
+        as_of_date: date(),
+        source: StatementSource::File,
+        positions,
+        cash_balances: cash
+            .into_iter()
+            .map(|(ccy, amount)| (ccy.to_string(), amount))
+            .collect(),
+    }
+}
+
+fn symbols() -> HashMap<String, String> {
+    HashMap::from([
+        ("SEC:AAPL:XNAS".to_string(), "AAPL".to_string()),
+        ("SEC:MSFT:XNAS".to_string(), "MSFT".to_string()),
+    ])
+}
+
+fn reconcile(statement: &BrokerStatement, snapshot: &AccountStateSnapshot) -> ReconciliationReport {
+    reconcile_statement(
+        statement,
+        "Brokerage",
+        Some(snapshot),
+        &symbols(),
+        &ReconciliationTolerance::default(),
+    )
+}
+
+// ============================================================================
+// reconcile_statement
+// ============================================================================
+
+#[test]
+fn test_matching_positions_are_reconciled() {
+    let snap = snapshot(
+        vec![position("SEC:AAPL:XNAS", dec!(10), dec!(1500))],
+        vec![("USD", dec!(250.00))],
+    );
+    let stmt = statement(
+        vec![broker_position(
+            Some("SEC:AAPL:XNAS"),
+            "AAPL",
+            dec!(10.00001),
+            Some(dec!(1502)),
+        )],
+        vec![("USD", dec!(250.005))],
+    );
+
+    let report = reconcile(&stmt, &snap);
+
+    assert!(report.is_reconciled(), "{:?}", report.discrepancies);
+    assert_eq!(report.positions_compared, 1);
+    assert_eq!(report.snapshot_date, Some(date()));
+}
+
+#[test]
+fn test_forward_split_is_detected() {
+    let snap = snapshot(
+        vec![position("SEC:AAPL:XNAS", dec!(10), dec!(1500))],
+        vec![],
+    );
+    let stmt = statement(
+        vec![broker_position(
+            Some("SEC:AAPL:XNAS"),
+            "AAPL",
+            dec!(40),
+            Some(dec!(1500)),
+        )],
+        vec![],
+    );
+
+    let report = reconcile(&stmt, &snap);
+
+    assert_eq!(report.discrepancies.len(), 1);
+    let d = &report.discrepancies[0];
+    assert_eq!(d.kind, DiscrepancyKind::QuantityMismatch);
+    assert_eq!(d.delta, dec!(30));
+    let fix = d.suggested_fix.as_ref().unwrap();
+    assert_eq!(fix.kind, SuggestedFixKind::Split);
+    assert_eq!(fix.activity.activity_type, ACTIVITY_TYPE_SPLIT);
+    assert_eq!(fix.activity.amount, Some(dec!(4)));
+    assert_eq!(fix.activity.activity_date, "2025-06-30");
+    assert_eq!(
+        fix.activity.symbol.as_ref().and_then(|s| s.id.as_deref()),
+        Some("SEC:AAPL:XNAS")
+    );
+}
+
+#[test]
+fn test_reverse_split_is_detected() {
+    let snap = snapshot(
+        vec![position("SEC:AAPL:XNAS", dec!(100), dec!(500))],
+        vec![],
+    );
+    let stmt = statement(
+        vec![broker_position(
+            Some("SEC:AAPL:XNAS"),
+            "AAPL",
+            dec!(10),
+            None,
+        )],
+        vec![],
+    );
+
+    let report = reconcile(&stmt, &snap);
+
+    let fix = report.discrepancies[0].suggested_fix.as_ref().unwrap();
+    assert_eq!(fix.kind, SuggestedFixKind::Split);
+    assert_eq!(fix.activity.amount, Some(dec!(0.1)));
+}
+
+#[test]
+fn test_quantity_mismatch_suggests_adjustment() {
+    let snap = snapshot(
+        vec![position("SEC:AAPL:XNAS", dec!(10), dec!(1500))],
+        vec![],
+    );
+    let stmt = statement(
+        vec![broker_position(
+            Some("SEC:AAPL:XNAS"),
+            "AAPL",
+            dec!(12.5),
+            Some(dec!(1875)),
+        )],
+        vec![],
+    );
+
+    let report = reconcile(&stmt, &snap);
+
+    let d = &report.discrepancies[0];
+    assert_eq!(d.kind, DiscrepancyKind::QuantityMismatch);
+    let fix = d.suggested_fix.as_ref().unwrap();
+    assert_eq!(fix.kind, SuggestedFixKind::Adjustment);
+    assert_eq!(fix.activity.activity_type, ACTIVITY_TYPE_ADJUSTMENT);
+    assert_eq!(fix.activity.quantity, Some(dec!(2.5)));
+}
+
+#[test]
+fn test_doubled_quantity_with_doubled_cost_is_not_a_split() {
+    let snap = snapshot(
+        vec![position("SEC:AAPL:XNAS", dec!(10), dec!(1500))],
+        vec![],
+    );
+    let stmt = statement(
+        vec![broker_position(
+            Some("SEC:AAPL:XNAS"),
+            "AAPL",
+            dec!(20),
+            Some(dec!(3000)),
+        )],
+        vec![],
+    );
+
+    let report = reconcile(&stmt, &snap);
+
+    let fix = report.discrepancies[0].suggested_fix.as_ref().unwrap();
+    assert_eq!(fix.kind, SuggestedFixKind::Adjustment);
+}
+
+#[test]
+fn test_cost_basis_mismatch() {
+    let snap = snapshot(
+        vec![position("SEC:AAPL:XNAS", dec!(10), dec!(1500))],
+        vec![],
+    );
+    let stmt = statement(
+        vec![broker_position(
+            Some("SEC:AAPL:XNAS"),
+            "AAPL",
+            dec!(10),
+            Some(dec!(1450)),
+        )],
+        vec![],
+    );
+
+    let report = reconcile(&stmt, &snap);
+
+    assert_eq!(report.discrepancies.len(), 1);
+    let d = &report.discrepancies[0];
+    assert_eq!(d.kind, DiscrepancyKind::CostBasisMismatch);
+    assert_eq!(d.delta, dec!(-50));
+    let fix = d.suggested_fix.as_ref().unwrap();
+    assert_eq!(fix.activity.activity_type, ACTIVITY_TYPE_ADJUSTMENT);
+    assert_eq!(fix.activity.amount, Some(dec!(-50)));
+    assert_eq!(fix.activity.quantity, None);
+}
+
+#[test]
+fn test_missing_positions_on_both_sides() {
+    let snap = snapshot(vec![position("SEC:MSFT:XNAS", dec!(5), dec!(1000))], vec![]);
+    let stmt = statement(
+        vec![broker_position(None, "VTI", dec!(8), Some(dec!(1600)))],
+        vec![],
+    );
+
+    let report = reconcile(&stmt, &snap);
+
+    assert_eq!(report.discrepancies.len(), 2);
+
+    let missing_locally = report
+        .discrepancies
+        .iter()
+        .find(|d| d.kind == DiscrepancyKind::MissingLocally)
+        .unwrap();
+    let fix = missing_locally.suggested_fix.as_ref().unwrap();
+    assert_eq!(fix.activity.activity_type, ACTIVITY_TYPE_TRANSFER_IN);
+    assert_eq!(fix.activity.quantity, Some(dec!(8)));
+    assert_eq!(fix.activity.unit_price, Some(dec!(200)));
+    assert_eq!(
+        fix.activity
+            .symbol
+            .as_ref()
+            .and_then(|s| s.symbol.as_deref()),
+        Some("VTI")
+    );
+
+    let missing_at_broker = report
+        .discrepancies
+        .iter()
+        .find(|d| d.kind == DiscrepancyKind::MissingAtBroker)
+        .unwrap();
+    assert_eq!(missing_at_broker.symbol, "MSFT");
+    let fix = missing_at_broker.suggested_fix.as_ref().unwrap();
+    assert_eq!(fix.activity.activity_type, ACTIVITY_TYPE_TRANSFER_OUT);
+    assert_eq!(fix.activity.quantity, Some(dec!(5)));
+}
+
+#[test]
+fn test_cash_deltas_suggest_fee_and_credit() {
+    let snap = snapshot(vec![], vec![("USD", dec!(1000)), ("EUR", dec!(50))]);
+    let stmt = statement(vec![], vec![("USD", dec!(987.50)), ("EUR", dec!(60))]);
+
+    let report = reconcile(&stmt, &snap);
+
+    assert_eq!(report.discrepancies.len(), 2);
+    let usd = report
+        .discrepancies
+        .iter()
+        .find(|d| d.currency == "USD")
+        .unwrap();
+    let fix = usd.suggested_fix.as_ref().unwrap();
+    assert_eq!(fix.kind, SuggestedFixKind::Fee);
+    assert_eq!(fix.activity.activity_type, ACTIVITY_TYPE_FEE);
+    assert_eq!(fix.activity.amount, Some(dec!(12.50)));
+
+    let eur = report
+        .discrepancies
+        .iter()
+        .find(|d| d.currency == "EUR")
+        .unwrap();
+    let fix = eur.suggested_fix.as_ref().unwrap();
+    assert_eq!(fix.kind, SuggestedFixKind::Credit);
+    assert_eq!(fix.activity.activity_type, ACTIVITY_TYPE_CREDIT);
+    assert_eq!(fix.activity.amount, Some(dec!(10)));
+}
+
+#[test]
+fn test_broker_tax_lots_are_aggregated() {
+    let snap = snapshot(
+        vec![position("SEC:AAPL:XNAS", dec!(10), dec!(1500))],
+        vec![],
+    );
+    let stmt = statement(
+        vec![
+            broker_position(Some("SEC:AAPL:XNAS"), "AAPL", dec!(4), Some(dec!(500))),
+            broker_position(Some("SEC:AAPL:XNAS"), "AAPL", dec!(6), Some(dec!(1000))),
+        ],
+        vec![],
+    );
+
+    let report = reconcile(&stmt, &snap);
+
+    assert!(report.is_reconciled());
+}
+
+#[test]
+fn test_no_snapshot_reports_everything_missing_locally() {
+    let stmt = statement(
+        vec![broker_position(
+            Some("SEC:AAPL:XNAS"),
+            "AAPL",
+            dec!(10),
+            None,
+        )],
+        vec![],
+    );
+
+    let report = reconcile_statement(
+        &stmt,
+        "Brokerage",
+        None,
+        &symbols(),
+        &ReconciliationTolerance::default(),
+    );
+
+    assert_eq!(report.snapshot_date, None);
+    assert_eq!(report.discrepancies.len(), 1);
+    assert_eq!(
+        report.discrepancies[0].kind,
+        DiscrepancyKind::MissingLocally
+    );
+}
+
+// ============================================================================
+// Health issues
+// ============================================================================
+
+#[test]
+fn test_report_to_health_issues() {
+    let snap = snapshot(
+        vec![position("SEC:AAPL:XNAS", dec!(10), dec!(1500))],
+        vec![("USD", dec!(100))],
+    );
+    let stmt = statement(
+        vec![broker_position(
+            Some("SEC:AAPL:XNAS"),
+            "AAPL",
+            dec!(20),
+            Some(dec!(1500)),
+        )],
+        vec![("USD", dec!(90))],
+    );
+    let report = reconcile(&stmt, &snap);
+
+    let issues = report_to_health_issues(&report);
+
+    assert_eq!(issues.len(), 2);
+    assert!(issues
+        .iter()
+        .all(|i| i.category == HealthCategory::Reconciliation));
+
+    let quantity = issues
+        .iter()
+        .find(|i| i.id == "reconciliation:ACC1:quantity_mismatch:SEC:AAPL:XNAS")
+        .unwrap();
+    assert_eq!(quantity.severity, Severity::Error);
+    let fix = quantity.fix_action.as_ref().unwrap();
+    assert_eq!(fix.id, "record_activity");
+    assert_eq!(fix.payload["activityType"], "SPLIT");
+    assert_eq!(fix.payload["sourceSystem"], RECONCILIATION_SOURCE_SYSTEM);
+
+    let cash = issues
+        .iter()
+        .find(|i| i.id == "reconciliation:ACC1:cash_delta:USD")
+        .unwrap();
+    assert_eq!(cash.severity, Severity::Warning);
+    assert_eq!(
+        cash.fix_action.as_ref().unwrap().payload["activityType"],
+        "FEE"
+    );
+}
+
+#[test]
+fn test_health_issue_hash_changes_with_delta() {
+    let snap = snapshot(vec![], vec![("USD", dec!(100))]);
+    let first = report_to_health_issues(&reconcile(
+        &statement(vec![], vec![("USD", dec!(90))]),
+        &snap,
+    ));
+    let second = report_to_health_issues(&reconcile(
+        &statement(vec![], vec![("USD", dec!(80))]),
+        &snap,
+    ));
+
+    assert_eq!(first[0].id, second[0].id);
+    assert_ne!(first[0].data_hash, second[0].data_hash);
+}
+
+// ============================================================================
+// Statement parsing
+// ============================================================================
+
+#[test]
+fn test_parse_statement_csv() {
+    let csv = "Symbol,Quantity,Cost Basis,Currency,Type\n\
+               aapl,10,\"1,500.00\",USD,Stock\n\
+               VOD,200,(12.5),GBP,Stock\n\
+               CASH,,,,\n\
+               USD Cash,,,USD,Cash\n";
+
+    let stmt = parse_statement_csv("ACC1", date(), "USD", csv.as_bytes()).unwrap();
+
+    assert_eq!(stmt.source, StatementSource::File);
+    assert_eq!(stmt.positions.len(), 2);
+    assert_eq!(stmt.positions[0].symbol, "AAPL");
+    assert_eq!(stmt.positions[0].cost_basis, Some(dec!(1500.00)));
+    assert_eq!(stmt.positions[1].currency, "GBP");
+    assert_eq!(stmt.positions[1].cost_basis, Some(dec!(-12.5)));
+    assert_eq!(stmt.cash_balances.get("USD"), Some(&Decimal::ZERO));
+}
+
+#[test]
+fn test_parse_statement_csv_cash_amounts() {
+    let csv = "Ticker;Units;Market Value;CCY\n\
+               MSFT;5;;\n\
+               CASH;;1234.56;\n\
+               CASH;;100;EUR\n";
+
+    let stmt = parse_statement_csv("ACC1", date(), "CAD", csv.as_bytes()).unwrap();
+
+    assert_eq!(stmt.positions.len(), 1);
+    assert_eq!(stmt.positions[0].currency, "CAD");
+    assert_eq!(stmt.cash_balances.get("CAD"), Some(&dec!(1234.56)));
+    assert_eq!(stmt.cash_balances.get("EUR"), Some(&dec!(100)));
+}
+
+#[test]
+fn test_parse_statement_csv_requires_symbol_column() {
+    let csv = "Name,Quantity\nApple,10\n";
+
+    assert!(parse_statement_csv("ACC1", date(), "USD", csv.as_bytes()).is_err());
+}
+
+#[test]
+fn test_parse_statement_csv_rejects_invalid_quantity() {
+    let csv = "Symbol,Quantity\nAAPL,ten\n";
+
+    assert!(parse_statement_csv("ACC1", date(), "USD", csv.as_bytes()).is_err());
+}
@@ -0,0 +1,69 @@
+//! Broker reconciliation service traits.
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use super::holdings_drift_model::HoldingsDriftCheck;
+use super::reconciliation_model::{BrokerStatement, ReconciliationReport, ReconciliationResult};
+use crate::errors::Result;
+use crate::health::HealthIssue;
+
+/// Trait defining the contract for broker reconciliation operations.
+pub trait ReconciliationServiceTrait: Send + Sync {
+    /// Compares a broker statement with the computed holdings on the statement date.
+    ///
+    /// Broker symbols without an `asset_id` are resolved against local assets first.
+    fn reconcile(&self, statement: BrokerStatement) -> Result<ReconciliationReport>;
+
+    /// Parses a broker statement CSV and reconciles it.
+    fn reconcile_csv(
+        &self,
+        account_id: &str,
+        as_of_date: NaiveDate,
+        content: &[u8],
+    ) -> Result<ReconciliationReport>;
+
+    /// Converts a report into health issues, one per discrepancy, each carrying
+    /// its suggested fix as a one-click `record_activity` action.
+    fn to_health_issues(&self, report: &ReconciliationReport) -> Vec<HealthIssue>;
+}
+
+/// Persistence for post-sync holdings drift checks.
+#[async_trait]
+pub trait HoldingsDriftRepositoryTrait: Send + Sync {
+    /// Inserts a check or replaces the stored one with the same ID.
+    async fn upsert_check(&self, check: HoldingsDriftCheck) -> Result<HoldingsDriftCheck>;
+
+    /// Returns pending checks, oldest first.
+    fn list_pending(&self) -> Result<Vec<HoldingsDriftCheck>>;
+
+    /// Returns checks newest first, optionally for a single account.
+    fn list_checks(&self, account_id: Option<&str>, limit: i64) -> Result<Vec<HoldingsDriftCheck>>;
+
+    /// Returns the most recent `Checked` entry for every account.
+    fn latest_checked(&self) -> Result<Vec<HoldingsDriftCheck>>;
+}
+
+/// Detects drift between broker-reported holdings and transaction-derived holdings.
+///
+/// Broker syncs record observations; the comparison runs once the account's
+/// snapshots have been recalculated with the synced activities.
+#[async_trait]
+pub trait HoldingsDriftServiceTrait: Send + Sync {
+    /// Stores the holdings a broker reported during sync as a pending check.
+    async fn record_observation(&self, statement: BrokerStatement) -> Result<HoldingsDriftCheck>;
+
+    /// Reconciles pending checks (for the given accounts, or all) against current
+    /// holdings. Only the newest pending check per account is compared; older ones
+    /// are marked superseded.
+    async fn evaluate_pending(
+        &self,
+        account_ids: Option<&[String]>,
+    ) -> Result<Vec<ReconciliationResult>>;
+
+    /// Returns the latest drift result per account, for republishing health issues.
+    fn latest_results(&self) -> Result<Vec<ReconciliationResult>>;
+
+    /// Returns the discrepancy history, newest first.
+    fn get_history(&self, account_id: Option<&str>, limit: i64) -> Result<Vec<HoldingsDriftCheck>>;
+}
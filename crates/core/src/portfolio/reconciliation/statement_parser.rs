@@ -0,0 +1,151 @@
+//! Broker statement file parsing.
+//!
+//! Accepts a simple CSV export with one row per position. Recognized headers
+//! (case and punctuation insensitive):
+//! - `symbol` / `ticker` - required
+//! - `quantity` / `qty` / `units` / `shares` - required
+//! - `cost basis` / `book value` / `total cost` - optional
+//! - `currency` / `ccy` - optional, falls back to the account currency
+//! - `type` / `asset type` - optional, `CASH` marks a cash balance row
+//! - `amount` / `market value` - optional, used as the cash amount for cash rows
+//!
+//! Rows whose symbol is `CASH` (or whose type is `CASH`) are treated as cash
+//! balances in the row currency.
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use super::reconciliation_model::{BrokerPosition, BrokerStatement, StatementSource};
+use crate::activities::{parse_csv, ParseConfig};
+use crate::errors::{Error, Result, ValidationError};
+
+const SYMBOL_HEADERS: &[&str] = &["symbol", "ticker"];
+const QUANTITY_HEADERS: &[&str] = &["quantity", "qty", "units", "shares"];
+const COST_BASIS_HEADERS: &[&str] = &["costbasis", "bookvalue", "totalcost"];
+const CURRENCY_HEADERS: &[&str] = &["currency", "ccy"];
+const TYPE_HEADERS: &[&str] = &["type", "assettype"];
+const AMOUNT_HEADERS: &[&str] = &["amount", "marketvalue"];
+
+/// Parses a broker statement CSV into a `BrokerStatement`.
+pub fn parse_statement_csv(
+    account_id: &str,
+    as_of_date: NaiveDate,
+    account_currency: &str,
+    content: &[u8],
+) -> Result<BrokerStatement> {
+    let parsed = parse_csv(content, &ParseConfig::default())?;
+
+    let headers: Vec<String> = parsed.headers.iter().map(|h| normalize_header(h)).collect();
+    let find = |candidates: &[&str]| {
+        headers
+            .iter()
+            .position(|h| candidates.contains(&h.as_str()))
+    };
+
+    let symbol_idx =
+        find(SYMBOL_HEADERS).ok_or_else(|| ValidationError::MissingField("symbol".to_string()))?;
+    let quantity_idx = find(QUANTITY_HEADERS);
+    let cost_basis_idx = find(COST_BASIS_HEADERS);
+    let currency_idx = find(CURRENCY_HEADERS);
+    let type_idx = find(TYPE_HEADERS);
+    let amount_idx = find(AMOUNT_HEADERS);
+
+    let mut positions: Vec<BrokerPosition> = Vec::new();
+    let mut cash_balances: HashMap<String, Decimal> = HashMap::new();
+
+    for (row_idx, row) in parsed.rows.iter().enumerate() {
+        let cell = |idx: Option<usize>| {
+            idx.and_then(|i| row.get(i))
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+        };
+
+        let symbol = match cell(Some(symbol_idx)) {
+            Some(symbol) => symbol.to_uppercase(),
+            None => continue,
+        };
+        let currency = cell(currency_idx)
+            .map(str::to_uppercase)
+            .unwrap_or_else(|| account_currency.to_uppercase());
+        let is_cash = symbol == "CASH"
+            || cell(type_idx)
+                .map(|t| t.eq_ignore_ascii_case("cash"))
+                .unwrap_or(false);
+
+        if is_cash {
+            let amount = cell(amount_idx)
+                .or_else(|| cell(quantity_idx))
+                .map(|v| parse_amount(v, row_idx, "amount"))
+                .transpose()?
+                .unwrap_or(Decimal::ZERO);
+            *cash_balances.entry(currency).or_insert(Decimal::ZERO) += amount;
+            continue;
+        }
+
+        let quantity = cell(quantity_idx)
+            .ok_or_else(|| {
+                Error::Validation(ValidationError::InvalidInput(format!(
+                    "Row {}: missing quantity for {}",
+                    row_idx + 1,
+                    symbol
+                )))
+            })
+            .and_then(|v| parse_amount(v, row_idx, "quantity"))?;
+        let cost_basis = cell(cost_basis_idx)
+            .map(|v| parse_amount(v, row_idx, "cost basis"))
+            .transpose()?;
+
+        positions.push(BrokerPosition {
+            asset_id: None,
+            symbol,
+            quantity,
+            cost_basis,
+            currency,
+        });
+    }
+
+    Ok(BrokerStatement {
+        account_id: account_id.to_string(),
+        as_of_date,
+        source: StatementSource::File,
+        positions,
+        cash_balances,
+    })
+}
+
+/// Lowercases a header and strips everything but letters and digits.
+fn normalize_header(header: &str) -> String {
+    header
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Parses a numeric cell, tolerating thousands separators and accounting negatives.
+fn parse_amount(value: &str, row_idx: usize, field: &str) -> Result<Decimal> {
+    let trimmed = value.trim();
+    let (negative, body) = if trimmed.starts_with('(') && trimmed.ends_with(')') {
+        (true, &trimmed[1..trimmed.len() - 1])
+    } else {
+        (false, trimmed)
+    };
+    let cleaned: String = body
+        .chars()
+        .filter(|c| !matches!(c, ',' | ' ' | '$'))
+        .collect();
+    let parsed = Decimal::from_str(&cleaned)
+        .or_else(|_| Decimal::from_scientific(&cleaned))
+        .map_err(|e| {
+            Error::Validation(ValidationError::InvalidInput(format!(
+                "Row {}: invalid {} '{}': {}",
+                row_idx + 1,
+                field,
+                value,
+                e
+            )))
+        })?;
+    Ok(if negative { -parsed } else { parsed })
+}
@@ -0,0 +1,13 @@
+//! What-if scenario module.
+//!
+//! Applies hypothetical activities to the latest holdings snapshot in memory and
+//! compares allocation, cash and concentration before and after, without persisting anything.
+
+mod scenario_model;
+mod scenario_service;
+
+pub use scenario_model::*;
+pub use scenario_service::*;
+
+#[cfg(test)]
+mod scenario_service_tests;
@@ -0,0 +1,108 @@
+//! What-if scenario models.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::portfolio::allocation::PortfolioAllocations;
+
+/// A hypothetical activity applied on top of the current holdings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioActivity {
+    /// Activity type (BUY, SELL, DEPOSIT, WITHDRAWAL, DIVIDEND, ...)
+    pub activity_type: String,
+    /// Asset ID for trades and income; omitted for pure cash movements
+    #[serde(default)]
+    pub asset_id: Option<String>,
+    #[serde(default)]
+    pub quantity: Option<Decimal>,
+    /// Unit price; trades default to the latest quote when omitted
+    #[serde(default)]
+    pub unit_price: Option<Decimal>,
+    /// Cash amount for non-trade activities
+    #[serde(default)]
+    pub amount: Option<Decimal>,
+    #[serde(default)]
+    pub fee: Option<Decimal>,
+    /// Defaults to the position or quote currency, then the account currency
+    #[serde(default)]
+    pub currency: Option<String>,
+}
+
+/// Request to simulate hypothetical activities.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioRequest {
+    /// Account to simulate; defaults to the total portfolio
+    #[serde(default)]
+    pub account_id: Option<String>,
+    pub activities: Vec<ScenarioActivity>,
+}
+
+/// How concentrated the invested (non-cash) part of the portfolio is.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcentrationMetrics {
+    /// Number of non-cash positions with a positive value
+    pub position_count: usize,
+    /// Symbol of the largest position
+    pub largest_position: Option<String>,
+    /// Weight of the largest position in percent (0-100) of invested value
+    pub largest_position_pct: Decimal,
+    /// Combined weight of the five largest positions in percent (0-100)
+    pub top5_pct: Decimal,
+    /// Herfindahl-Hirschman index of position weights (0-10000)
+    pub hhi: Decimal,
+    /// Number of equally weighted positions with the same concentration (10000 / HHI)
+    pub effective_positions: Decimal,
+}
+
+/// Portfolio state on one side of a scenario.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioState {
+    /// Total value in base currency, cash included
+    pub total_value: Decimal,
+    /// Cash balances per currency, in that currency
+    pub cash_balances: HashMap<String, Decimal>,
+    /// Cash value in base currency
+    pub cash_value: Decimal,
+    pub allocations: PortfolioAllocations,
+    pub concentration: ConcentrationMetrics,
+}
+
+/// Estimated realized gain for one simulated sale, matched FIFO against current lots.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RealizedGainEstimate {
+    pub asset_id: String,
+    pub symbol: Option<String>,
+    /// Quantity actually matched against held lots
+    pub quantity: Decimal,
+    /// Proceeds net of fees, in the position currency
+    pub proceeds: Decimal,
+    /// Cost basis of the relieved lots, in the position currency
+    pub cost_basis: Decimal,
+    /// Gain in the position currency
+    pub gain: Decimal,
+    /// Position currency
+    pub currency: String,
+    /// Gain converted to base currency; `None` when no rate is available
+    pub gain_base: Option<Decimal>,
+}
+
+/// Result of a what-if simulation. Nothing is persisted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioResult {
+    pub account_id: String,
+    pub base_currency: String,
+    pub before: ScenarioState,
+    pub after: ScenarioState,
+    pub realized_gains: Vec<RealizedGainEstimate>,
+    /// Sum of the estimates that could be converted to base currency
+    pub total_realized_gain: Decimal,
+    /// Activities or conversions that could not be applied
+    pub warnings: Vec<String>,
+}
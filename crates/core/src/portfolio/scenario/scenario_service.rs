@@ -0,0 +1,399 @@
+//! Service for simulating hypothetical activities against current holdings.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use log::debug;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::activities::{
+    Activity, ActivityStatus, ACTIVITY_TYPE_BUY, ACTIVITY_TYPE_SELL, SYMBOL_REQUIRED_TYPES,
+};
+use crate::constants::PORTFOLIO_TOTAL_ACCOUNT_ID;
+use crate::errors::{Error, Result, ValidationError};
+use crate::fx::FxServiceTrait;
+use crate::portfolio::allocation::AllocationServiceTrait;
+use crate::portfolio::holdings::{Holding, HoldingType, HoldingsServiceTrait};
+use crate::portfolio::snapshot::{AccountStateSnapshot, Position, SnapshotServiceTrait};
+use crate::quotes::QuoteServiceTrait;
+
+use super::{
+    ConcentrationMetrics, RealizedGainEstimate, ScenarioActivity, ScenarioRequest, ScenarioResult,
+    ScenarioState,
+};
+
+/// Trait for what-if scenario simulation.
+#[async_trait]
+pub trait ScenarioServiceTrait: Send + Sync {
+    /// Applies hypothetical activities to the latest holdings in memory and compares
+    /// allocations, cash and concentration before and after. Nothing is persisted.
+    async fn simulate(
+        &self,
+        request: ScenarioRequest,
+        base_currency: &str,
+    ) -> Result<ScenarioResult>;
+}
+
+/// Runs what-if scenarios through the holdings calculator and live valuation path.
+pub struct ScenarioService {
+    snapshot_service: Arc<dyn SnapshotServiceTrait>,
+    holdings_service: Arc<dyn HoldingsServiceTrait>,
+    allocation_service: Arc<dyn AllocationServiceTrait>,
+    quote_service: Arc<dyn QuoteServiceTrait>,
+    fx_service: Arc<dyn FxServiceTrait>,
+}
+
+impl ScenarioService {
+    pub fn new(
+        snapshot_service: Arc<dyn SnapshotServiceTrait>,
+        holdings_service: Arc<dyn HoldingsServiceTrait>,
+        allocation_service: Arc<dyn AllocationServiceTrait>,
+        quote_service: Arc<dyn QuoteServiceTrait>,
+        fx_service: Arc<dyn FxServiceTrait>,
+    ) -> Self {
+        Self {
+            snapshot_service,
+            holdings_service,
+            allocation_service,
+            quote_service,
+            fx_service,
+        }
+    }
+
+    /// Turns scenario inputs into posted activities dated today, filling in
+    /// missing prices from the latest quote and missing currencies from the position.
+    fn build_activities(
+        &self,
+        snapshot: &AccountStateSnapshot,
+        inputs: &[ScenarioActivity],
+    ) -> Result<Vec<Activity>> {
+        let now = Utc::now();
+        let mut activities = Vec::with_capacity(inputs.len());
+
+        for (index, input) in inputs.iter().enumerate() {
+            let activity_type = input.activity_type.trim().to_uppercase();
+            let asset_id = input
+                .asset_id
+                .as_deref()
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(str::to_string);
+
+            if SYMBOL_REQUIRED_TYPES.contains(&activity_type.as_str()) && asset_id.is_none() {
+                return Err(invalid(format!(
+                    "Scenario activity {} ({}) requires an assetId",
+                    index + 1,
+                    activity_type
+                )));
+            }
+
+            let is_trade =
+                activity_type == ACTIVITY_TYPE_BUY || activity_type == ACTIVITY_TYPE_SELL;
+            let mut unit_price = input.unit_price;
+            let mut quote_currency = None;
+            if is_trade {
+                if !input.quantity.is_some_and(|q| q > Decimal::ZERO) {
+                    return Err(invalid(format!(
+                        "Scenario activity {} ({}) requires a positive quantity",
+                        index + 1,
+                        activity_type
+                    )));
+                }
+                if unit_price.is_none() {
+                    let asset_id = asset_id.as_deref().unwrap_or_default();
+                    let quote = self.quote_service.get_latest_quote(asset_id).map_err(|_| {
+                        invalid(format!(
+                            "No quote found for {}; provide a unitPrice",
+                            asset_id
+                        ))
+                    })?;
+                    unit_price = Some(quote.close);
+                    quote_currency = Some(quote.currency);
+                }
+            }
+
+            let currency = input
+                .currency
+                .clone()
+                .filter(|c| !c.trim().is_empty())
+                .or(quote_currency)
+                .or_else(|| {
+                    asset_id
+                        .as_ref()
+                        .and_then(|id| snapshot.positions.get(id))
+                        .map(|p| p.currency.clone())
+                })
+                .unwrap_or_else(|| snapshot.currency.clone());
+
+            activities.push(Activity {
+                id: format!("SCENARIO-{}", index + 1),
+                account_id: snapshot.account_id.clone(),
+                asset_id,
+                activity_type,
+                activity_type_override: None,
+                source_type: None,
+                subtype: None,
+                status: ActivityStatus::Posted,
+                activity_date: now,
+                settlement_date: None,
+                quantity: input.quantity,
+                unit_price,
+                amount: input.amount,
+                fee: input.fee,
+                currency,
+                fx_rate: None,
+                notes: None,
+                metadata: None,
+                source_system: None,
+                source_record_id: None,
+                source_group_id: None,
+                idempotency_key: None,
+                import_run_id: None,
+                is_user_modified: false,
+                needs_review: false,
+                created_at: now,
+                updated_at: now,
+            });
+        }
+
+        Ok(activities)
+    }
+
+    /// Estimates realized gains for the simulated sells against the lots held today.
+    fn estimate_realized_gains(
+        &self,
+        snapshot: &AccountStateSnapshot,
+        holdings: &[Holding],
+        activities: &[Activity],
+        base_currency: &str,
+        warnings: &mut Vec<String>,
+    ) -> Vec<RealizedGainEstimate> {
+        let mut positions: HashMap<&str, Position> = HashMap::new();
+        let mut estimates = Vec::new();
+
+        for activity in activities
+            .iter()
+            .filter(|a| a.activity_type == ACTIVITY_TYPE_SELL)
+        {
+            let asset_id = activity.asset_id.as_deref().unwrap_or_default();
+            let Some(held) = snapshot.positions.get(asset_id) else {
+                warnings.push(format!(
+                    "{} is not held; no realized gain estimated for {}",
+                    asset_id, activity.id
+                ));
+                continue;
+            };
+            // Later sells of the same asset continue from the lots left by earlier ones.
+            let position = positions.entry(asset_id).or_insert_with(|| held.clone());
+
+            let mut proceeds_per_unit = activity.price();
+            let mut fee = activity.fee_amt();
+            if activity.currency != position.currency {
+                match (
+                    self.fx_service.convert_currency(
+                        proceeds_per_unit,
+                        &activity.currency,
+                        &position.currency,
+                    ),
+                    self.fx_service
+                        .convert_currency(fee, &activity.currency, &position.currency),
+                ) {
+                    (Ok(price), Ok(converted_fee)) => {
+                        proceeds_per_unit = price;
+                        fee = converted_fee;
+                    }
+                    _ => {
+                        warnings.push(format!(
+                            "No {}/{} rate; no realized gain estimated for {}",
+                            activity.currency, position.currency, activity.id
+                        ));
+                        continue;
+                    }
+                }
+            }
+
+            let mut estimate =
+                match estimate_sale_gain(position, activity.qty(), proceeds_per_unit, fee) {
+                    Ok(estimate) => estimate,
+                    Err(e) => {
+                        warnings.push(format!("Could not estimate {}: {}", activity.id, e));
+                        continue;
+                    }
+                };
+            estimate.symbol = holdings
+                .iter()
+                .filter_map(|h| h.instrument.as_ref())
+                .find(|i| i.id == asset_id)
+                .map(|i| i.symbol.clone());
+            match self
+                .fx_service
+                .convert_currency(estimate.gain, &estimate.currency, base_currency)
+            {
+                Ok(gain_base) => estimate.gain_base = Some(gain_base),
+                Err(_) => warnings.push(format!(
+                    "No {}/{} rate; realized gain for {} left out of the total",
+                    estimate.currency, base_currency, activity.id
+                )),
+            }
+            estimates.push(estimate);
+        }
+
+        estimates
+    }
+
+    fn describe_state(
+        &self,
+        snapshot: &AccountStateSnapshot,
+        holdings: &[Holding],
+    ) -> Result<ScenarioState> {
+        Ok(ScenarioState {
+            total_value: holdings.iter().map(|h| h.market_value.base).sum(),
+            cash_balances: snapshot
+                .cash_balances
+                .iter()
+                .filter(|(_, amount)| !amount.is_zero())
+                .map(|(currency, amount)| (currency.clone(), *amount))
+                .collect(),
+            cash_value: holdings
+                .iter()
+                .filter(|h| h.holding_type == HoldingType::Cash)
+                .map(|h| h.market_value.base)
+                .sum(),
+            allocations: self
+                .allocation_service
+                .get_allocations_for_holdings(holdings)?,
+            concentration: concentration_metrics(holdings),
+        })
+    }
+}
+
+fn invalid(message: String) -> Error {
+    Error::Validation(ValidationError::InvalidInput(message))
+}
+
+/// Relieves `quantity` from `position` FIFO and prices the sale.
+pub(crate) fn estimate_sale_gain(
+    position: &mut Position,
+    quantity: Decimal,
+    unit_price: Decimal,
+    fee: Decimal,
+) -> Result<RealizedGainEstimate> {
+    let (quantity_sold, cost_basis) = position.reduce_lots_fifo(quantity)?;
+    // Charge the fee in proportion to the part of the order that matched held lots.
+    let fee = if quantity > Decimal::ZERO {
+        fee * quantity_sold / quantity
+    } else {
+        Decimal::ZERO
+    };
+    let proceeds = quantity_sold * unit_price - fee;
+
+    Ok(RealizedGainEstimate {
+        asset_id: position.asset_id.clone(),
+        symbol: None,
+        quantity: quantity_sold,
+        proceeds,
+        cost_basis,
+        gain: proceeds - cost_basis,
+        currency: position.currency.clone(),
+        gain_base: None,
+    })
+}
+
+/// Computes concentration of the non-cash holdings by base market value.
+pub(crate) fn concentration_metrics(holdings: &[Holding]) -> ConcentrationMetrics {
+    let mut positions: Vec<(&Holding, Decimal)> = holdings
+        .iter()
+        .filter(|h| h.holding_type != HoldingType::Cash && h.market_value.base > Decimal::ZERO)
+        .map(|h| (h, h.market_value.base))
+        .collect();
+    let invested: Decimal = positions.iter().map(|(_, value)| *value).sum();
+    if invested <= Decimal::ZERO {
+        return ConcentrationMetrics::default();
+    }
+    positions.sort_by(|a, b| b.1.cmp(&a.1));
+
+    let weights: Vec<Decimal> = positions
+        .iter()
+        .map(|(_, value)| *value / invested * dec!(100))
+        .collect();
+    let hhi: Decimal = weights.iter().map(|w| w * w).sum();
+
+    ConcentrationMetrics {
+        position_count: positions.len(),
+        largest_position: positions
+            .first()
+            .and_then(|(h, _)| h.instrument.as_ref())
+            .map(|i| i.symbol.clone()),
+        largest_position_pct: weights[0].round_dp(2),
+        top5_pct: weights.iter().take(5).sum::<Decimal>().round_dp(2),
+        hhi: hhi.round_dp(2),
+        effective_positions: (dec!(10000) / hhi).round_dp(2),
+    }
+}
+
+#[async_trait]
+impl ScenarioServiceTrait for ScenarioService {
+    async fn simulate(
+        &self,
+        request: ScenarioRequest,
+        base_currency: &str,
+    ) -> Result<ScenarioResult> {
+        if request.activities.is_empty() {
+            return Err(invalid(
+                "A scenario needs at least one activity".to_string(),
+            ));
+        }
+        let account_id = request
+            .account_id
+            .as_deref()
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .unwrap_or(PORTFOLIO_TOTAL_ACCOUNT_ID);
+        debug!(
+            "Simulating {} activities for account {}",
+            request.activities.len(),
+            account_id
+        );
+
+        let before_snapshot = self
+            .snapshot_service
+            .get_latest_holdings_snapshot(account_id)?
+            .ok_or_else(|| invalid(format!("No holdings found for account {}", account_id)))?;
+        let before_holdings = self
+            .holdings_service
+            .value_holdings_from_snapshot(&before_snapshot, base_currency)
+            .await?;
+
+        let activities = self.build_activities(&before_snapshot, &request.activities)?;
+        let mut warnings = Vec::new();
+        let realized_gains = self.estimate_realized_gains(
+            &before_snapshot,
+            &before_holdings,
+            &activities,
+            base_currency,
+            &mut warnings,
+        );
+
+        let preview = self
+            .snapshot_service
+            .preview_holdings_with_activities(account_id, &activities)?;
+        warnings.extend(preview.warnings.iter().map(|w| w.message.clone()));
+        let after_holdings = self
+            .holdings_service
+            .value_holdings_from_snapshot(&preview.snapshot, base_currency)
+            .await?;
+
+        Ok(ScenarioResult {
+            account_id: account_id.to_string(),
+            base_currency: base_currency.to_string(),
+            before: self.describe_state(&before_snapshot, &before_holdings)?,
+            after: self.describe_state(&preview.snapshot, &after_holdings)?,
+            total_realized_gain: realized_gains.iter().filter_map(|g| g.gain_base).sum(),
+            realized_gains,
+            warnings,
+        })
+    }
+}
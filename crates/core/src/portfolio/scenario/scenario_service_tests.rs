@@ -0,0 +1,133 @@
+//! Unit tests for scenario simulation helpers.
+
+use super::scenario_service::{concentration_metrics, estimate_sale_gain};
+use crate::portfolio::holdings::{Holding, HoldingType, Instrument, MonetaryValue};
+use crate::portfolio::snapshot::Position;
+use chrono::{NaiveDate, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+fn holding(symbol: &str, holding_type: HoldingType, value: Decimal) -> Holding {
+    Holding {
+        id: format!("SEC-TOTAL-{}", symbol),
+        account_id: "TOTAL".to_string(),
+        holding_type,
+        instrument: Some(Instrument {
+            id: symbol.to_string(),
+            symbol: symbol.to_string(),
+            name: None,
+            currency: "USD".to_string(),
+            notes: None,
+            pricing_mode: "MARKET".to_string(),
+            preferred_provider: None,
+            classifications: None,
+        }),
+        asset_kind: None,
+        quantity: Decimal::ONE,
+        open_date: None,
+        lots: None,
+        local_currency: "USD".to_string(),
+        base_currency: "USD".to_string(),
+        fx_rate: Some(Decimal::ONE),
+        market_value: MonetaryValue {
+            local: value,
+            base: value,
+        },
+        cost_basis: None,
+        price: None,
+        purchase_price: None,
+        unrealized_gain: None,
+        unrealized_gain_pct: None,
+        realized_gain: None,
+        realized_gain_pct: None,
+        total_gain: None,
+        total_gain_pct: None,
+        day_change: None,
+        day_change_pct: None,
+        prev_close_value: None,
+        weight: Decimal::ZERO,
+        as_of_date: NaiveDate::from_ymd_opt(2026, 1, 1).expect("valid date"),
+        metadata: None,
+    }
+}
+
+fn position_with_lots(lots: &[(Decimal, Decimal)]) -> Position {
+    let mut position = Position::new(
+        "ACC1".to_string(),
+        "HSBA.L".to_string(),
+        "GBP".to_string(),
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+    );
+    for (day, (quantity, price)) in lots.iter().enumerate() {
+        position
+            .add_lot_values(
+                format!("LOT{}", day + 1),
+                *quantity,
+                *price,
+                Decimal::ZERO,
+                Utc.with_ymd_and_hms(2024, 1, day as u32 + 1, 0, 0, 0)
+                    .unwrap(),
+                None,
+            )
+            .unwrap();
+    }
+    position
+}
+
+#[test]
+fn test_sale_gain_uses_fifo_lots() {
+    let mut position = position_with_lots(&[(dec!(100), dec!(5)), (dec!(100), dec!(7))]);
+
+    let estimate = estimate_sale_gain(&mut position, dec!(150), dec!(8), dec!(3)).unwrap();
+
+    assert_eq!(estimate.quantity, dec!(150));
+    // 100 @ 5 + 50 @ 7
+    assert_eq!(estimate.cost_basis, dec!(850));
+    assert_eq!(estimate.proceeds, dec!(1197));
+    assert_eq!(estimate.gain, dec!(347));
+    assert_eq!(estimate.currency, "GBP");
+    assert_eq!(position.quantity, dec!(50));
+}
+
+#[test]
+fn test_sale_gain_caps_quantity_at_held_lots() {
+    let mut position = position_with_lots(&[(dec!(10), dec!(5))]);
+
+    let estimate = estimate_sale_gain(&mut position, dec!(20), dec!(6), dec!(2)).unwrap();
+
+    assert_eq!(estimate.quantity, dec!(10));
+    // Only half of the order matched, so only half of the fee is charged.
+    assert_eq!(estimate.proceeds, dec!(59));
+    assert_eq!(estimate.gain, dec!(9));
+}
+
+#[test]
+fn test_concentration_ignores_cash() {
+    let holdings = vec![
+        holding("VWRA", HoldingType::Security, dec!(600)),
+        holding("HSBA", HoldingType::Security, dec!(300)),
+        holding("AAPL", HoldingType::Security, dec!(100)),
+        holding("USD", HoldingType::Cash, dec!(5000)),
+    ];
+
+    let metrics = concentration_metrics(&holdings);
+
+    assert_eq!(metrics.position_count, 3);
+    assert_eq!(metrics.largest_position.as_deref(), Some("VWRA"));
+    assert_eq!(metrics.largest_position_pct, dec!(60));
+    assert_eq!(metrics.top5_pct, dec!(100));
+    // 60² + 30² + 10²
+    assert_eq!(metrics.hhi, dec!(4600));
+    assert_eq!(metrics.effective_positions, dec!(2.17));
+}
+
+#[test]
+fn test_concentration_empty_without_positions() {
+    let holdings = vec![holding("USD", HoldingType::Cash, dec!(100))];
+
+    let metrics = concentration_metrics(&holdings);
+
+    assert_eq!(metrics.position_count, 0);
+    assert_eq!(metrics.largest_position, None);
+    assert_eq!(metrics.hhi, Decimal::ZERO);
+}
@@ -2,6 +2,7 @@
 
 #[cfg(test)]
 mod tests {
+    use crate::activities::Activity;
     use crate::assets::{
         Asset, AssetMetadata, AssetProfileEnrichmentStats, AssetServiceTrait, AssetSpec,
         EnsureAssetsResult, NewAsset, ProviderProfile, UpdateAssetProfile,
@@ -9,8 +10,8 @@ mod tests {
     use crate::errors::{Error, Result};
     use crate::fx::{ExchangeRate, FxServiceTrait, NewExchangeRate};
     use crate::portfolio::snapshot::{
-        AccountStateSnapshot, ManualHoldingInput, ManualSnapshotRequest, ManualSnapshotService,
-        SnapshotServiceTrait, SnapshotSource,
+        AccountStateSnapshot, HoldingsCalculationResult, ManualHoldingInput, ManualSnapshotRequest,
+        ManualSnapshotService, SnapshotServiceTrait, SnapshotSource,
     };
     use crate::quotes::{
         LatestQuotePair, LatestQuoteSnapshot, ProviderInfo, Quote, QuoteImport, QuoteServiceTrait,
@@ -322,6 +323,14 @@ mod tests {
             Ok(None)
         }
 
+        fn preview_holdings_with_activities(
+            &self,
+            _account_id: &str,
+            _activities: &[Activity],
+        ) -> Result<HoldingsCalculationResult> {
+            unimplemented!()
+        }
+
         async fn calculate_total_portfolio_snapshots(&self) -> Result<usize> {
             unimplemented!()
         }
@@ -11,7 +11,8 @@ use crate::events::{DomainEvent, DomainEventSink, NoOpDomainEventSink};
 use crate::fx::FxServiceTrait;
 use crate::portfolio::performance::{classify_flow_for_scope, FlowType, PerformanceScope};
 use crate::portfolio::snapshot::{
-    AccountStateSnapshot, HoldingsCalculationWarning, Lot, Position, SnapshotSource,
+    AccountStateSnapshot, HoldingsCalculationResult, HoldingsCalculationWarning, Lot, Position,
+    SnapshotSource,
 };
 use crate::utils::time_utils::{get_days_between, valuation_date_today};
 
@@ -68,6 +69,15 @@ pub trait SnapshotServiceTrait: Send + Sync {
         account_id: &str,
     ) -> Result<Option<AccountStateSnapshot>>;
 
+    /// Dry-runs the holdings calculator: applies `activities` on top of the account's
+    /// latest holdings snapshot in date order, without persisting anything.
+    /// Used to preview the impact of staged imports before they are approved.
+    fn preview_holdings_with_activities(
+        &self,
+        account_id: &str,
+        activities: &[Activity],
+    ) -> Result<HoldingsCalculationResult>;
+
     /// Calculates and stores aggregated "TOTAL" portfolio snapshots based on individual account holdings.
     /// This should typically be run after `calculate_holdings_snapshots` has processed individual accounts.
     /// Uses incremental calculation: skips if TOTAL snapshots are already up-to-date with individual keyframes.
@@ -1543,6 +1553,45 @@ impl SnapshotServiceTrait for SnapshotService {
         }
     }
 
+    fn preview_holdings_with_activities(
+        &self,
+        account_id: &str,
+        activities: &[Activity],
+    ) -> Result<HoldingsCalculationResult> {
+        let start_snapshot = match self.get_latest_holdings_snapshot(account_id)? {
+            Some(snapshot) => snapshot,
+            None => {
+                let account = self.account_repository.get_by_id(account_id)?;
+                AccountStateSnapshot {
+                    account_id: account_id.to_string(),
+                    currency: account.currency,
+                    ..Default::default()
+                }
+            }
+        };
+
+        let compiled = DefaultActivityCompiler::new().compile_all(activities)?;
+        let mut activities_by_date: BTreeMap<NaiveDate, Vec<Activity>> = BTreeMap::new();
+        for activity in compiled.into_iter().filter(|a| a.is_posted()) {
+            activities_by_date
+                .entry(activity.effective_date())
+                .or_default()
+                .push(activity);
+        }
+
+        let mut result = HoldingsCalculationResult::new(start_snapshot);
+        for (date, activities_on_date) in activities_by_date {
+            let next = self.holdings_calculator.calculate_next_holdings(
+                &result.snapshot,
+                &activities_on_date,
+                date,
+            )?;
+            result.snapshot = next.snapshot;
+            result.warnings.extend(next.warnings);
+        }
+        Ok(result)
+    }
+
     async fn calculate_total_portfolio_snapshots(&self) -> Result<usize> {
         self.calculate_total_portfolio_snapshots_impl(false).await
     }
@@ -0,0 +1,23 @@
+//! Recurring activities module - templates with RRULE-style schedules that
+//! generate activities (savings plans, MPF contributions, interest, rent).
+
+mod recurring_formula;
+mod recurring_model;
+mod recurring_schedule;
+mod recurring_service;
+mod recurring_traits;
+
+#[cfg(test)]
+mod recurring_service_tests;
+
+pub use recurring_formula::evaluate_formula;
+pub use recurring_model::{
+    BusinessDayAdjustment, NewRecurringActivityTemplate, RecurringActivityTemplate,
+    RecurringGenerationResult, RecurringOccurrence, RECURRING_SOURCE_SYSTEM,
+};
+pub use recurring_schedule::{adjust_for_business_day, Frequency, RecurrenceRule};
+pub use recurring_service::{
+    build_occurrence_activity, compute_occurrences, recurring_idempotency_key,
+    RecurringActivityService,
+};
+pub use recurring_traits::{RecurringActivityRepositoryTrait, RecurringActivityServiceTrait};
@@ -0,0 +1,229 @@
+//! Arithmetic formulas for occurrence amounts.
+//!
+//! A formula is a plain arithmetic expression over decimals with `+ - * / ^`
+//! and parentheses. The following variables are available:
+//!
+//! - `n`: 1-based occurrence number
+//! - `months`: whole months since the template start date
+//! - `years`: whole years since the template start date
+//! - `days`: days since the previous occurrence (or since the start date)
+//! - `amount`, `quantity`, `unit_price`: the template's base values (0 if unset)
+//!
+//! Examples: `500 * 1.03 ^ years` for a contribution that grows 3% per year,
+//! `100000 * 0.04 * days / 365` for fixed deposit interest.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, MathematicalOps};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::errors::{Error, Result, ValidationError};
+
+fn invalid(message: String) -> Error {
+    Error::Validation(ValidationError::InvalidInput(message))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(Decimal),
+    Ident(String),
+    Op(char),
+    LParen,
+    RParen,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let value = Decimal::from_str(&text)
+                .map_err(|_| invalid(format!("Invalid number '{}' in formula", text)))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else if "+-*/^".contains(c) {
+            tokens.push(Token::Op(c));
+            i += 1;
+        } else if c == '(' {
+            tokens.push(Token::LParen);
+            i += 1;
+        } else if c == ')' {
+            tokens.push(Token::RParen);
+            i += 1;
+        } else {
+            return Err(invalid(format!("Unexpected character '{}' in formula", c)));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    variables: &'a HashMap<&'a str, Decimal>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expr(&mut self) -> Result<Decimal> {
+        let mut value = self.term()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek().cloned() {
+            self.pos += 1;
+            let rhs = self.term()?;
+            value = if op == '+' {
+                value.checked_add(rhs)
+            } else {
+                value.checked_sub(rhs)
+            }
+            .ok_or_else(|| invalid("Formula overflowed".to_string()))?;
+        }
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<Decimal> {
+        let mut value = self.unary()?;
+        while let Some(Token::Op(op @ ('*' | '/'))) = self.peek().cloned() {
+            self.pos += 1;
+            let rhs = self.unary()?;
+            value = if op == '*' {
+                value.checked_mul(rhs)
+            } else {
+                if rhs.is_zero() {
+                    return Err(invalid("Division by zero in formula".to_string()));
+                }
+                value.checked_div(rhs)
+            }
+            .ok_or_else(|| invalid("Formula overflowed".to_string()))?;
+        }
+        Ok(value)
+    }
+
+    fn power(&mut self) -> Result<Decimal> {
+        let base = self.primary()?;
+        if let Some(Token::Op('^')) = self.peek() {
+            self.pos += 1;
+            // Right-associative: 2 ^ 3 ^ 2 == 2 ^ 9
+            let exponent = self.unary()?;
+            let result = if exponent.fract().is_zero() {
+                exponent.to_i64().and_then(|e| base.checked_powi(e))
+            } else {
+                base.checked_powd(exponent)
+            };
+            return result.ok_or_else(|| invalid("Formula overflowed".to_string()));
+        }
+        Ok(base)
+    }
+
+    fn unary(&mut self) -> Result<Decimal> {
+        if let Some(Token::Op('-')) = self.peek() {
+            self.pos += 1;
+            return Ok(-self.unary()?);
+        }
+        self.power()
+    }
+
+    fn primary(&mut self) -> Result<Decimal> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(value),
+            Some(Token::Ident(name)) => self
+                .variables
+                .get(name.as_str())
+                .copied()
+                .ok_or_else(|| invalid(format!("Unknown variable '{}' in formula", name))),
+            Some(Token::LParen) => {
+                let value = self.expr()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(value),
+                    _ => Err(invalid("Missing ')' in formula".to_string())),
+                }
+            }
+            _ => Err(invalid("Incomplete formula".to_string())),
+        }
+    }
+}
+
+/// Evaluates an amount formula with the given variables.
+pub fn evaluate_formula(formula: &str, variables: &HashMap<&str, Decimal>) -> Result<Decimal> {
+    let mut parser = Parser {
+        tokens: tokenize(formula)?,
+        pos: 0,
+        variables,
+    };
+    if parser.tokens.is_empty() {
+        return Err(invalid("Formula is empty".to_string()));
+    }
+    let value = parser.expr()?;
+    if parser.pos != parser.tokens.len() {
+        return Err(invalid(format!(
+            "Unexpected trailing input in formula '{}'",
+            formula
+        )));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn vars() -> HashMap<&'static str, Decimal> {
+        HashMap::from([("n", dec!(3)), ("years", dec!(2)), ("days", dec!(31))])
+    }
+
+    #[test]
+    fn test_precedence_and_parentheses() {
+        assert_eq!(evaluate_formula("1 + 2 * 3", &vars()).unwrap(), dec!(7));
+        assert_eq!(evaluate_formula("(1 + 2) * 3", &vars()).unwrap(), dec!(9));
+        assert_eq!(evaluate_formula("-2 ^ 2", &vars()).unwrap(), dec!(-4));
+        assert_eq!(evaluate_formula("2 ^ -1", &vars()).unwrap(), dec!(0.5));
+        assert_eq!(evaluate_formula("2 ^ 3 ^ 2", &vars()).unwrap(), dec!(512));
+    }
+
+    #[test]
+    fn test_variables() {
+        assert_eq!(
+            evaluate_formula("500 * 1.1 ^ years", &vars()).unwrap(),
+            dec!(605)
+        );
+        assert_eq!(
+            evaluate_formula("36500 * 0.04 * days / 365", &vars()).unwrap(),
+            dec!(124)
+        );
+        assert_eq!(evaluate_formula("100 * n", &vars()).unwrap(), dec!(300));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(evaluate_formula("", &vars()).is_err());
+        assert!(evaluate_formula("1 +", &vars()).is_err());
+        assert!(evaluate_formula("(1 + 2", &vars()).is_err());
+        assert!(evaluate_formula("1 / 0", &vars()).is_err());
+        assert!(evaluate_formula("rate * 2", &vars()).is_err());
+        assert!(evaluate_formula("2 $ 3", &vars()).is_err());
+        assert!(evaluate_formula("1 2", &vars()).is_err());
+    }
+}
@@ -0,0 +1,129 @@
+//! Domain models for recurring activity templates.
+
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::activities::ActivityStatus;
+
+/// Source system recorded on activities generated from templates.
+pub const RECURRING_SOURCE_SYSTEM: &str = "RECURRING";
+
+/// How an occurrence falling on a weekend is moved to a business day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BusinessDayAdjustment {
+    /// Keep the nominal date
+    #[default]
+    None,
+    /// Move to the next business day
+    Following,
+    /// Move to the previous business day
+    Preceding,
+    /// Next business day unless that crosses into the next month
+    ModifiedFollowing,
+}
+
+impl BusinessDayAdjustment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BusinessDayAdjustment::None => "NONE",
+            BusinessDayAdjustment::Following => "FOLLOWING",
+            BusinessDayAdjustment::Preceding => "PRECEDING",
+            BusinessDayAdjustment::ModifiedFollowing => "MODIFIED_FOLLOWING",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "FOLLOWING" => BusinessDayAdjustment::Following,
+            "PRECEDING" => BusinessDayAdjustment::Preceding,
+            "MODIFIED_FOLLOWING" => BusinessDayAdjustment::ModifiedFollowing,
+            _ => BusinessDayAdjustment::None,
+        }
+    }
+}
+
+/// Template describing an activity that repeats on a schedule
+/// (savings plans, MPF contributions, deposit interest, rent).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringActivityTemplate {
+    pub id: String,
+    pub account_id: String,
+    pub name: String,
+    pub activity_type: String,
+    pub subtype: Option<String>,
+    /// Asset for trade/income templates; None for cash activities
+    pub asset_id: Option<String>,
+    pub quantity: Option<Decimal>,
+    pub unit_price: Option<Decimal>,
+    /// Fixed amount per occurrence
+    pub amount: Option<Decimal>,
+    /// Formula evaluated per occurrence; takes precedence over `amount`
+    pub amount_formula: Option<String>,
+    pub fee: Option<Decimal>,
+    pub currency: String,
+    pub notes: Option<String>,
+    /// RRULE-style schedule, e.g. `FREQ=MONTHLY;BYMONTHDAY=25`
+    pub rrule: String,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub business_day_adjustment: BusinessDayAdjustment,
+    /// Status given to generated activities (POSTED or PENDING)
+    pub generate_status: ActivityStatus,
+    pub is_active: bool,
+    /// Latest nominal occurrence date that has been generated
+    pub last_generated_date: Option<NaiveDate>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// Input model for creating a recurring activity template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewRecurringActivityTemplate {
+    pub id: Option<String>,
+    pub account_id: String,
+    pub name: String,
+    pub activity_type: String,
+    pub subtype: Option<String>,
+    pub asset_id: Option<String>,
+    pub quantity: Option<Decimal>,
+    pub unit_price: Option<Decimal>,
+    pub amount: Option<Decimal>,
+    pub amount_formula: Option<String>,
+    pub fee: Option<Decimal>,
+    pub currency: String,
+    pub notes: Option<String>,
+    pub rrule: String,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub business_day_adjustment: BusinessDayAdjustment,
+    #[serde(default)]
+    pub generate_status: ActivityStatus,
+}
+
+/// A single scheduled occurrence of a template.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringOccurrence {
+    /// 1-based position in the schedule
+    pub index: u32,
+    /// Date produced by the rule, used for idempotency
+    pub nominal_date: NaiveDate,
+    /// Date after business-day adjustment, used as the activity date
+    pub activity_date: NaiveDate,
+    pub amount: Option<Decimal>,
+}
+
+/// Summary of a generation run across all templates.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringGenerationResult {
+    pub generated: usize,
+    pub skipped_existing: usize,
+    pub activity_ids: Vec<String>,
+    pub errors: Vec<String>,
+}
@@ -0,0 +1,365 @@
+//! RRULE-style schedule parsing and occurrence expansion.
+//!
+//! Supports the subset of RFC 5545 needed for contribution and income plans:
+//! `FREQ` (DAILY, WEEKLY, MONTHLY, YEARLY), `INTERVAL`, `BYMONTHDAY`
+//! (negative values count from month end), `BYDAY` (weekly only), `BYMONTH`
+//! (yearly only), `COUNT` and `UNTIL`. Unlike RFC 5545, a month day past the
+//! end of a short month is clamped to its last day instead of being skipped,
+//! so "monthly on the 31st" still fires in February.
+
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
+
+use super::recurring_model::BusinessDayAdjustment;
+use crate::errors::{Error, Result, ValidationError};
+
+/// Upper bound on expanded occurrences, guarding against runaway rules.
+const MAX_OCCURRENCES: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// Parsed recurrence rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub by_month_day: Option<i32>,
+    pub by_day: Vec<Weekday>,
+    pub by_month: Option<u32>,
+    pub count: Option<u32>,
+    pub until: Option<NaiveDate>,
+}
+
+fn invalid(message: String) -> Error {
+    Error::Validation(ValidationError::InvalidInput(message))
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday> {
+    match value {
+        "MO" => Ok(Weekday::Mon),
+        "TU" => Ok(Weekday::Tue),
+        "WE" => Ok(Weekday::Wed),
+        "TH" => Ok(Weekday::Thu),
+        "FR" => Ok(Weekday::Fri),
+        "SA" => Ok(Weekday::Sat),
+        "SU" => Ok(Weekday::Sun),
+        _ => Err(invalid(format!("Invalid BYDAY value '{}'", value))),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T> {
+    value
+        .parse::<T>()
+        .map_err(|_| invalid(format!("Invalid {} value '{}'", key, value)))
+}
+
+fn last_day_of_month(year: i32, month: u32) -> u32 {
+    let first = NaiveDate::from_ymd_opt(year, month, 1).unwrap_or_default();
+    first
+        .checked_add_months(Months::new(1))
+        .and_then(|next| next.pred_opt())
+        .map(|d| d.day())
+        .unwrap_or(28)
+}
+
+/// Resolves a (possibly negative) month day, clamping to the month length.
+fn month_day(year: i32, month: u32, day: i32) -> Option<NaiveDate> {
+    let last = last_day_of_month(year, month) as i32;
+    let resolved = if day < 0 { last + day + 1 } else { day };
+    NaiveDate::from_ymd_opt(year, month, resolved.clamp(1, last) as u32)
+}
+
+impl RecurrenceRule {
+    /// Parses a rule such as `FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=25;COUNT=12`.
+    /// An optional `RRULE:` prefix is accepted.
+    pub fn parse(rule: &str) -> Result<Self> {
+        let body = rule.trim();
+        let body = body.strip_prefix("RRULE:").unwrap_or(body);
+
+        let mut frequency = None;
+        let mut parsed = RecurrenceRule {
+            frequency: Frequency::Monthly,
+            interval: 1,
+            by_month_day: None,
+            by_day: Vec::new(),
+            by_month: None,
+            count: None,
+            until: None,
+        };
+
+        for part in body.split(';').filter(|p| !p.trim().is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| invalid(format!("Invalid rule part '{}'", part)))?;
+            let key = key.trim().to_ascii_uppercase();
+            let value = value.trim().to_ascii_uppercase();
+            match key.as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(invalid(format!("Unsupported FREQ '{}'", value))),
+                    })
+                }
+                "INTERVAL" => parsed.interval = parse_number(&key, &value)?,
+                "BYMONTHDAY" => {
+                    let day: i32 = parse_number(&key, &value)?;
+                    if day == 0 || !(-31..=31).contains(&day) {
+                        return Err(invalid(format!("Invalid BYMONTHDAY '{}'", value)));
+                    }
+                    parsed.by_month_day = Some(day);
+                }
+                "BYDAY" => {
+                    parsed.by_day = value
+                        .split(',')
+                        .map(|d| parse_weekday(d.trim()))
+                        .collect::<Result<Vec<_>>>()?;
+                }
+                "BYMONTH" => {
+                    let month: u32 = parse_number(&key, &value)?;
+                    if !(1..=12).contains(&month) {
+                        return Err(invalid(format!("Invalid BYMONTH '{}'", value)));
+                    }
+                    parsed.by_month = Some(month);
+                }
+                "COUNT" => parsed.count = Some(parse_number(&key, &value)?),
+                "UNTIL" => {
+                    let date_part = value.get(..8).unwrap_or(&value);
+                    parsed.until = Some(
+                        NaiveDate::parse_from_str(date_part, "%Y%m%d")
+                            .or_else(|_| NaiveDate::parse_from_str(&value, "%Y-%m-%d"))
+                            .map_err(|_| invalid(format!("Invalid UNTIL '{}'", value)))?,
+                    );
+                }
+                _ => return Err(invalid(format!("Unsupported rule part '{}'", key))),
+            }
+        }
+
+        parsed.frequency = frequency.ok_or_else(|| invalid("Rule is missing FREQ".to_string()))?;
+        if parsed.interval == 0 {
+            return Err(invalid("INTERVAL must be at least 1".to_string()));
+        }
+        if !parsed.by_day.is_empty() && parsed.frequency != Frequency::Weekly {
+            return Err(invalid(
+                "BYDAY is only supported with FREQ=WEEKLY".to_string(),
+            ));
+        }
+        Ok(parsed)
+    }
+
+    /// Candidate dates for the `period`-th period after `start`, in order.
+    fn period_dates(&self, start: NaiveDate, period: u32) -> Vec<NaiveDate> {
+        let step = period.saturating_mul(self.interval);
+        match self.frequency {
+            Frequency::Daily => start
+                .checked_add_signed(Duration::days(step as i64))
+                .into_iter()
+                .collect(),
+            Frequency::Weekly => {
+                let week_start = start
+                    .checked_add_signed(Duration::weeks(step as i64))
+                    .unwrap_or(start);
+                if self.by_day.is_empty() {
+                    return vec![week_start];
+                }
+                let monday =
+                    week_start - Duration::days(week_start.weekday().num_days_from_monday() as i64);
+                let mut days: Vec<NaiveDate> = self
+                    .by_day
+                    .iter()
+                    .map(|d| monday + Duration::days(d.num_days_from_monday() as i64))
+                    .collect();
+                days.sort();
+                days.dedup();
+                days
+            }
+            Frequency::Monthly => start
+                .with_day(1)
+                .and_then(|first| first.checked_add_months(Months::new(step)))
+                .and_then(|m| {
+                    let day = self.by_month_day.unwrap_or(start.day() as i32);
+                    month_day(m.year(), m.month(), day)
+                })
+                .into_iter()
+                .collect(),
+            Frequency::Yearly => {
+                let year = start.year() + step as i32;
+                let month = self.by_month.unwrap_or(start.month());
+                let day = self.by_month_day.unwrap_or(start.day() as i32);
+                month_day(year, month, day).into_iter().collect()
+            }
+        }
+    }
+
+    /// Expands nominal occurrence dates from `start` up to and including
+    /// `through`, honoring `COUNT`, `UNTIL` and an optional template end date.
+    pub fn occurrences(
+        &self,
+        start: NaiveDate,
+        end_date: Option<NaiveDate>,
+        through: NaiveDate,
+    ) -> Vec<NaiveDate> {
+        let bound = [Some(through), self.until, end_date]
+            .into_iter()
+            .flatten()
+            .min()
+            .unwrap_or(through);
+
+        let mut dates = Vec::new();
+        let mut period = 0u32;
+        'periods: while dates.len() < MAX_OCCURRENCES {
+            let candidates = self.period_dates(start, period);
+            if candidates.is_empty() {
+                break;
+            }
+            for date in candidates {
+                if date < start {
+                    continue;
+                }
+                if date > bound {
+                    break 'periods;
+                }
+                if self.count.is_some_and(|c| dates.len() as u32 >= c) {
+                    break 'periods;
+                }
+                dates.push(date);
+            }
+            period += 1;
+        }
+        dates
+    }
+}
+
+fn is_business_day(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// Moves a weekend date to a business day according to `adjustment`.
+pub fn adjust_for_business_day(date: NaiveDate, adjustment: BusinessDayAdjustment) -> NaiveDate {
+    let step = |d: NaiveDate, forward: bool| {
+        let mut d = d;
+        while !is_business_day(d) {
+            d = if forward {
+                d + Duration::days(1)
+            } else {
+                d - Duration::days(1)
+            };
+        }
+        d
+    };
+    match adjustment {
+        BusinessDayAdjustment::None => date,
+        BusinessDayAdjustment::Following => step(date, true),
+        BusinessDayAdjustment::Preceding => step(date, false),
+        BusinessDayAdjustment::ModifiedFollowing => {
+            let next = step(date, true);
+            if next.month() == date.month() {
+                next
+            } else {
+                step(date, false)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_rules() {
+        assert!(RecurrenceRule::parse("INTERVAL=2").is_err());
+        assert!(RecurrenceRule::parse("FREQ=HOURLY").is_err());
+        assert!(RecurrenceRule::parse("FREQ=MONTHLY;BYMONTHDAY=0").is_err());
+        assert!(RecurrenceRule::parse("FREQ=MONTHLY;BYDAY=MO").is_err());
+        assert!(RecurrenceRule::parse("RRULE:FREQ=MONTHLY;BYMONTHDAY=15").is_ok());
+    }
+
+    #[test]
+    fn test_monthly_clamps_to_month_end() {
+        let rule = RecurrenceRule::parse("FREQ=MONTHLY;BYMONTHDAY=31").unwrap();
+        let dates = rule.occurrences(d(2024, 1, 1), None, d(2024, 4, 30));
+        assert_eq!(
+            dates,
+            vec![
+                d(2024, 1, 31),
+                d(2024, 2, 29),
+                d(2024, 3, 31),
+                d(2024, 4, 30)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_monthly_last_day_and_count() {
+        let rule = RecurrenceRule::parse("FREQ=MONTHLY;BYMONTHDAY=-1;COUNT=2").unwrap();
+        let dates = rule.occurrences(d(2024, 1, 15), None, d(2024, 12, 31));
+        assert_eq!(dates, vec![d(2024, 1, 31), d(2024, 2, 29)]);
+    }
+
+    #[test]
+    fn test_interval_until_and_end_date() {
+        let rule = RecurrenceRule::parse("FREQ=MONTHLY;INTERVAL=3;UNTIL=20241231").unwrap();
+        let dates = rule.occurrences(d(2024, 1, 10), None, d(2030, 1, 1));
+        assert_eq!(
+            dates,
+            vec![
+                d(2024, 1, 10),
+                d(2024, 4, 10),
+                d(2024, 7, 10),
+                d(2024, 10, 10)
+            ]
+        );
+
+        let dates = rule.occurrences(d(2024, 1, 10), Some(d(2024, 5, 1)), d(2030, 1, 1));
+        assert_eq!(dates.len(), 2);
+    }
+
+    #[test]
+    fn test_weekly_by_day() {
+        let rule = RecurrenceRule::parse("FREQ=WEEKLY;BYDAY=MO,TH").unwrap();
+        // 2024-03-06 is a Wednesday: the Monday of that week is skipped
+        let dates = rule.occurrences(d(2024, 3, 6), None, d(2024, 3, 14));
+        assert_eq!(dates, vec![d(2024, 3, 7), d(2024, 3, 11), d(2024, 3, 14)]);
+    }
+
+    #[test]
+    fn test_yearly_by_month() {
+        let rule = RecurrenceRule::parse("FREQ=YEARLY;BYMONTH=4;BYMONTHDAY=5").unwrap();
+        let dates = rule.occurrences(d(2024, 1, 1), None, d(2025, 12, 31));
+        assert_eq!(dates, vec![d(2024, 4, 5), d(2025, 4, 5)]);
+    }
+
+    #[test]
+    fn test_business_day_adjustment() {
+        // 2024-06-01 is a Saturday, 2024-08-31 is a Saturday at month end
+        let sat = d(2024, 6, 1);
+        assert_eq!(
+            adjust_for_business_day(sat, BusinessDayAdjustment::None),
+            sat
+        );
+        assert_eq!(
+            adjust_for_business_day(sat, BusinessDayAdjustment::Following),
+            d(2024, 6, 3)
+        );
+        assert_eq!(
+            adjust_for_business_day(sat, BusinessDayAdjustment::Preceding),
+            d(2024, 5, 31)
+        );
+        assert_eq!(
+            adjust_for_business_day(d(2024, 8, 31), BusinessDayAdjustment::ModifiedFollowing),
+            d(2024, 8, 30)
+        );
+    }
+}
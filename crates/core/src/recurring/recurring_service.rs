@@ -0,0 +1,346 @@
+use async_trait::async_trait;
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use log::{debug, warn};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::recurring_formula::evaluate_formula;
+use super::recurring_model::*;
+use super::recurring_schedule::{adjust_for_business_day, RecurrenceRule};
+use super::recurring_traits::{RecurringActivityRepositoryTrait, RecurringActivityServiceTrait};
+use crate::activities::{ActivityServiceTrait, ActivityStatus, NewActivity, SymbolInput};
+use crate::errors::{Error, Result, ValidationError};
+
+/// Nominal dates are expanded this far past `as_of` so that occurrences moved
+/// backwards by `PRECEDING` adjustment are picked up on time.
+const ADJUSTMENT_LOOKAHEAD_DAYS: i64 = 7;
+
+/// Idempotency key for the activity generated from one template occurrence.
+pub fn recurring_idempotency_key(template_id: &str, nominal_date: NaiveDate) -> String {
+    format!(
+        "recurring:{}:{}",
+        template_id,
+        nominal_date.format("%Y-%m-%d")
+    )
+}
+
+fn whole_months_between(from: NaiveDate, to: NaiveDate) -> i64 {
+    let months = (to.year() - from.year()) as i64 * 12 + to.month() as i64 - from.month() as i64;
+    if to.day() < from.day() {
+        (months - 1).max(0)
+    } else {
+        months.max(0)
+    }
+}
+
+fn formula_variables(
+    template: &RecurringActivityTemplate,
+    index: u32,
+    nominal: NaiveDate,
+    previous: NaiveDate,
+) -> HashMap<&'static str, Decimal> {
+    let months = whole_months_between(template.start_date, nominal);
+    HashMap::from([
+        ("n", Decimal::from(index)),
+        ("months", Decimal::from(months)),
+        ("years", Decimal::from(months / 12)),
+        ("days", Decimal::from((nominal - previous).num_days())),
+        ("amount", template.amount.unwrap_or(Decimal::ZERO)),
+        ("quantity", template.quantity.unwrap_or(Decimal::ZERO)),
+        ("unit_price", template.unit_price.unwrap_or(Decimal::ZERO)),
+    ])
+}
+
+/// Expands a template's occurrences up to and including `through` (nominal dates),
+/// with business-day adjusted dates and per-occurrence amounts.
+pub fn compute_occurrences(
+    template: &RecurringActivityTemplate,
+    through: NaiveDate,
+) -> Result<Vec<RecurringOccurrence>> {
+    let rule = RecurrenceRule::parse(&template.rrule)?;
+    let dates = rule.occurrences(template.start_date, template.end_date, through);
+
+    let mut previous = template.start_date;
+    let mut occurrences = Vec::with_capacity(dates.len());
+    for (i, nominal) in dates.into_iter().enumerate() {
+        let index = i as u32 + 1;
+        let amount = match template.amount_formula.as_deref().map(str::trim) {
+            Some(formula) if !formula.is_empty() => Some(
+                evaluate_formula(
+                    formula,
+                    &formula_variables(template, index, nominal, previous),
+                )?
+                .round_dp(4),
+            ),
+            _ => template.amount,
+        };
+        occurrences.push(RecurringOccurrence {
+            index,
+            nominal_date: nominal,
+            activity_date: adjust_for_business_day(nominal, template.business_day_adjustment),
+            amount,
+        });
+        previous = nominal;
+    }
+    Ok(occurrences)
+}
+
+/// Builds the activity for one occurrence of a template.
+pub fn build_occurrence_activity(
+    template: &RecurringActivityTemplate,
+    occurrence: &RecurringOccurrence,
+) -> NewActivity {
+    let nominal = occurrence.nominal_date.format("%Y-%m-%d").to_string();
+    NewActivity {
+        id: None,
+        account_id: template.account_id.clone(),
+        symbol: template.asset_id.as_ref().map(|asset_id| SymbolInput {
+            id: Some(asset_id.clone()),
+            ..Default::default()
+        }),
+        activity_type: template.activity_type.clone(),
+        subtype: template.subtype.clone(),
+        activity_date: occurrence.activity_date.format("%Y-%m-%d").to_string(),
+        quantity: template.quantity,
+        unit_price: template.unit_price,
+        currency: template.currency.clone(),
+        fee: template.fee,
+        amount: occurrence.amount,
+        status: Some(template.generate_status.clone()),
+        notes: template
+            .notes
+            .clone()
+            .or_else(|| Some(template.name.clone())),
+        fx_rate: None,
+        metadata: Some(
+            serde_json::json!({
+                "recurringTemplateId": template.id,
+                "occurrenceDate": nominal,
+                "occurrenceIndex": occurrence.index,
+            })
+            .to_string(),
+        ),
+        needs_review: None,
+        source_system: Some(RECURRING_SOURCE_SYSTEM.to_string()),
+        source_record_id: Some(format!("{}:{}", template.id, nominal)),
+        source_group_id: Some(template.id.clone()),
+        idempotency_key: Some(recurring_idempotency_key(
+            &template.id,
+            occurrence.nominal_date,
+        )),
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::Validation(ValidationError::InvalidInput(message.to_string()))
+}
+
+fn validate_template(template: &RecurringActivityTemplate) -> Result<()> {
+    if template.account_id.trim().is_empty() {
+        return Err(invalid("Account is required"));
+    }
+    if template.name.trim().is_empty() {
+        return Err(invalid("Template name is required"));
+    }
+    if template.activity_type.trim().is_empty() {
+        return Err(invalid("Activity type is required"));
+    }
+    if template.currency.trim().is_empty() {
+        return Err(invalid("Currency is required"));
+    }
+    if !matches!(
+        template.generate_status,
+        ActivityStatus::Posted | ActivityStatus::Pending
+    ) {
+        return Err(invalid(
+            "Recurring activities can only be generated as posted or pending",
+        ));
+    }
+    if template
+        .end_date
+        .is_some_and(|end| end < template.start_date)
+    {
+        return Err(invalid("End date must not be before the start date"));
+    }
+    let has_formula = template
+        .amount_formula
+        .as_deref()
+        .is_some_and(|f| !f.trim().is_empty());
+    if !has_formula && template.amount.is_none() && template.quantity.is_none() {
+        return Err(invalid(
+            "Either an amount, an amount formula or a quantity is required",
+        ));
+    }
+    // Validates the rule and evaluates the formula for the first occurrence
+    compute_occurrences(template, template.start_date + Duration::days(366))?;
+    Ok(())
+}
+
+/// Service for managing recurring activity templates and generating their activities.
+pub struct RecurringActivityService {
+    repository: Arc<dyn RecurringActivityRepositoryTrait>,
+    activity_service: Arc<dyn ActivityServiceTrait>,
+}
+
+impl RecurringActivityService {
+    pub fn new(
+        repository: Arc<dyn RecurringActivityRepositoryTrait>,
+        activity_service: Arc<dyn ActivityServiceTrait>,
+    ) -> Self {
+        Self {
+            repository,
+            activity_service,
+        }
+    }
+
+    /// Generates due occurrences of one template. Returns the last nominal
+    /// date that was handled, if any.
+    async fn generate_template(
+        &self,
+        template: &RecurringActivityTemplate,
+        as_of: NaiveDate,
+        result: &mut RecurringGenerationResult,
+    ) -> Result<Option<NaiveDate>> {
+        let due: Vec<RecurringOccurrence> =
+            compute_occurrences(template, as_of + Duration::days(ADJUSTMENT_LOOKAHEAD_DAYS))?
+                .into_iter()
+                .filter(|o| o.activity_date <= as_of)
+                .filter(|o| {
+                    template
+                        .last_generated_date
+                        .is_none_or(|l| o.nominal_date > l)
+                })
+                .collect();
+        if due.is_empty() {
+            return Ok(None);
+        }
+
+        let keys: Vec<String> = due
+            .iter()
+            .map(|o| recurring_idempotency_key(&template.id, o.nominal_date))
+            .collect();
+        let existing = self.activity_service.check_existing_duplicates(keys)?;
+
+        let mut last_handled = None;
+        for occurrence in &due {
+            let key = recurring_idempotency_key(&template.id, occurrence.nominal_date);
+            if existing.contains_key(&key) {
+                result.skipped_existing += 1;
+            } else {
+                let activity = self
+                    .activity_service
+                    .create_activity(build_occurrence_activity(template, occurrence))
+                    .await?;
+                result.generated += 1;
+                result.activity_ids.push(activity.id);
+            }
+            last_handled = Some(occurrence.nominal_date);
+        }
+        Ok(last_handled)
+    }
+}
+
+#[async_trait]
+impl RecurringActivityServiceTrait for RecurringActivityService {
+    fn get_templates(&self) -> Result<Vec<RecurringActivityTemplate>> {
+        self.repository.list_templates()
+    }
+
+    async fn create_template(
+        &self,
+        template: NewRecurringActivityTemplate,
+    ) -> Result<RecurringActivityTemplate> {
+        let now = Utc::now().naive_utc();
+        let template = RecurringActivityTemplate {
+            id: template
+                .id
+                .filter(|id| !id.trim().is_empty())
+                .unwrap_or_else(|| Uuid::new_v4().to_string()),
+            account_id: template.account_id,
+            name: template.name,
+            activity_type: template.activity_type,
+            subtype: template.subtype,
+            asset_id: template.asset_id,
+            quantity: template.quantity,
+            unit_price: template.unit_price,
+            amount: template.amount,
+            amount_formula: template.amount_formula,
+            fee: template.fee,
+            currency: template.currency,
+            notes: template.notes,
+            rrule: template.rrule,
+            start_date: template.start_date,
+            end_date: template.end_date,
+            business_day_adjustment: template.business_day_adjustment,
+            generate_status: template.generate_status,
+            is_active: true,
+            last_generated_date: None,
+            created_at: now,
+            updated_at: now,
+        };
+        validate_template(&template)?;
+        self.repository.insert_template(template).await
+    }
+
+    async fn update_template(
+        &self,
+        template: RecurringActivityTemplate,
+    ) -> Result<RecurringActivityTemplate> {
+        let existing = self.repository.get_template(&template.id)?;
+        let template = RecurringActivityTemplate {
+            // Generation progress is owned by the service, not the caller
+            last_generated_date: existing.last_generated_date,
+            created_at: existing.created_at,
+            updated_at: Utc::now().naive_utc(),
+            ..template
+        };
+        validate_template(&template)?;
+        self.repository.update_template(template).await
+    }
+
+    async fn delete_template(&self, template_id: &str) -> Result<usize> {
+        self.repository.delete_template(template_id).await
+    }
+
+    fn preview_occurrences(
+        &self,
+        template_id: &str,
+        through: NaiveDate,
+    ) -> Result<Vec<RecurringOccurrence>> {
+        let template = self.repository.get_template(template_id)?;
+        compute_occurrences(&template, through)
+    }
+
+    async fn generate_due(&self, as_of: NaiveDate) -> Result<RecurringGenerationResult> {
+        let mut result = RecurringGenerationResult::default();
+
+        for template in self.repository.list_templates()? {
+            if !template.is_active {
+                continue;
+            }
+            match self.generate_template(&template, as_of, &mut result).await {
+                Ok(Some(last)) => {
+                    self.repository
+                        .set_last_generated_date(&template.id, last)
+                        .await?;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    warn!(
+                        "Failed to generate recurring activities for template {}: {}",
+                        template.id, e
+                    );
+                    result.errors.push(format!("{}: {}", template.name, e));
+                }
+            }
+        }
+
+        debug!(
+            "Recurring generation as of {}: {} generated, {} already present",
+            as_of, result.generated, result.skipped_existing
+        );
+        Ok(result)
+    }
+}
@@ -0,0 +1,421 @@
+#[cfg(test)]
+mod tests {
+    use crate::activities::*;
+    use crate::errors::{Error, Result};
+    use crate::recurring::{
+        compute_occurrences, recurring_idempotency_key, BusinessDayAdjustment,
+        NewRecurringActivityTemplate, RecurringActivityRepositoryTrait, RecurringActivityService,
+        RecurringActivityServiceTrait, RecurringActivityTemplate, RECURRING_SOURCE_SYSTEM,
+    };
+    use async_trait::async_trait;
+    use chrono::{DateTime, NaiveDate, Utc};
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    fn stored_activity(id: String, new: &NewActivity) -> Activity {
+        let now = Utc::now();
+        Activity {
+            id,
+            account_id: new.account_id.clone(),
+            asset_id: new.get_symbol_id().map(|s| s.to_string()),
+            activity_type: new.activity_type.clone(),
+            activity_type_override: None,
+            source_type: None,
+            subtype: new.subtype.clone(),
+            status: new.status.clone().unwrap_or_default(),
+            activity_date: now,
+            settlement_date: None,
+            quantity: new.quantity,
+            unit_price: new.unit_price,
+            amount: new.amount,
+            fee: new.fee,
+            currency: new.currency.clone(),
+            fx_rate: None,
+            notes: new.notes.clone(),
+            metadata: None,
+            source_system: new.source_system.clone(),
+            source_record_id: new.source_record_id.clone(),
+            source_group_id: new.source_group_id.clone(),
+            idempotency_key: new.idempotency_key.clone(),
+            import_run_id: None,
+            is_user_modified: false,
+            needs_review: false,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    // --- Mock ActivityService ---
+    #[derive(Default)]
+    struct MockActivityService {
+        activities: Mutex<Vec<Activity>>,
+        created: Mutex<Vec<(String, NewActivity)>>,
+    }
+
+    #[async_trait]
+    impl ActivityServiceTrait for MockActivityService {
+        fn get_activity(&self, activity_id: &str) -> Result<Activity> {
+            self.activities
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|a| a.id == activity_id)
+                .cloned()
+                .ok_or_else(|| Error::Unexpected("Activity not found".to_string()))
+        }
+        fn get_activities(&self) -> Result<Vec<Activity>> {
+            Ok(self.activities.lock().unwrap().clone())
+        }
+        fn get_activities_by_account_id(&self, account_id: &str) -> Result<Vec<Activity>> {
+            Ok(self
+                .activities
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|a| a.account_id == account_id)
+                .cloned()
+                .collect())
+        }
+        fn get_activities_by_account_ids(&self, _account_ids: &[String]) -> Result<Vec<Activity>> {
+            unimplemented!()
+        }
+        fn get_trading_activities(&self) -> Result<Vec<Activity>> {
+            unimplemented!()
+        }
+        fn get_income_activities(&self) -> Result<Vec<Activity>> {
+            unimplemented!()
+        }
+        fn search_activities(
+            &self,
+            _page: i64,
+            _page_size: i64,
+            _account_id_filter: Option<Vec<String>>,
+            _activity_type_filter: Option<Vec<String>>,
+            _asset_id_keyword: Option<String>,
+            _sort: Option<Sort>,
+            _needs_review_filter: Option<bool>,
+            _date_from: Option<NaiveDate>,
+            _date_to: Option<NaiveDate>,
+        ) -> Result<ActivitySearchResponse> {
+            unimplemented!()
+        }
+        fn get_first_activity_date(
+            &self,
+            _account_ids: Option<&[String]>,
+        ) -> Result<Option<DateTime<Utc>>> {
+            unimplemented!()
+        }
+        fn get_import_mapping(&self, _account_id: String) -> Result<ImportMappingData> {
+            unimplemented!()
+        }
+        async fn create_activity(&self, activity: NewActivity) -> Result<Activity> {
+            let mut created = self.created.lock().unwrap();
+            let id = format!("generated-{}", created.len() + 1);
+            let stored = stored_activity(id.clone(), &activity);
+            created.push((id, activity));
+            Ok(stored)
+        }
+        async fn update_activity(&self, _activity: ActivityUpdate) -> Result<Activity> {
+            unimplemented!()
+        }
+        async fn delete_activity(&self, activity_id: String) -> Result<Activity> {
+            let mut activities = self.activities.lock().unwrap();
+            let idx = activities
+                .iter()
+                .position(|a| a.id == activity_id)
+                .ok_or_else(|| Error::Unexpected("Activity not found".to_string()))?;
+            Ok(activities.remove(idx))
+        }
+        async fn bulk_mutate_activities(
+            &self,
+            _request: ActivityBulkMutationRequest,
+        ) -> Result<ActivityBulkMutationResult> {
+            unimplemented!()
+        }
+        async fn check_activities_import(
+            &self,
+            _account_id: String,
+            _activities: Vec<ActivityImport>,
+        ) -> Result<Vec<ActivityImport>> {
+            unimplemented!()
+        }
+        async fn import_activities(
+            &self,
+            _account_id: String,
+            _activities: Vec<ActivityImport>,
+            _review_mode: crate::activities::ReviewMode,
+        ) -> Result<ImportActivitiesResult> {
+            unimplemented!()
+        }
+        async fn save_import_mapping(
+            &self,
+            _mapping_data: ImportMappingData,
+        ) -> Result<ImportMappingData> {
+            unimplemented!()
+        }
+        fn check_existing_duplicates(
+            &self,
+            idempotency_keys: Vec<String>,
+        ) -> Result<HashMap<String, String>> {
+            let created = self.created.lock().unwrap();
+            Ok(created
+                .iter()
+                .filter_map(|(id, a)| {
+                    let key = a.idempotency_key.clone()?;
+                    idempotency_keys.contains(&key).then(|| (key, id.clone()))
+                })
+                .collect())
+        }
+        fn parse_csv(&self, _content: &[u8], _config: &ParseConfig) -> Result<ParsedCsvResult> {
+            unimplemented!()
+        }
+        async fn upsert_activities_bulk(
+            &self,
+            _activities: Vec<ActivityUpsert>,
+        ) -> Result<BulkUpsertResult> {
+            unimplemented!()
+        }
+        async fn prepare_activities(
+            &self,
+            _activities: Vec<NewActivity>,
+            _account: &crate::accounts::Account,
+        ) -> Result<PrepareActivitiesResult> {
+            unimplemented!()
+        }
+    }
+
+    // --- Mock RecurringActivityRepository ---
+    #[derive(Default)]
+    struct MockRecurringRepository {
+        templates: Mutex<Vec<RecurringActivityTemplate>>,
+    }
+
+    #[async_trait]
+    impl RecurringActivityRepositoryTrait for MockRecurringRepository {
+        fn list_templates(&self) -> Result<Vec<RecurringActivityTemplate>> {
+            Ok(self.templates.lock().unwrap().clone())
+        }
+        fn get_template(&self, template_id: &str) -> Result<RecurringActivityTemplate> {
+            self.templates
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|t| t.id == template_id)
+                .cloned()
+                .ok_or_else(|| Error::Unexpected("Template not found".to_string()))
+        }
+        async fn insert_template(
+            &self,
+            template: RecurringActivityTemplate,
+        ) -> Result<RecurringActivityTemplate> {
+            self.templates.lock().unwrap().push(template.clone());
+            Ok(template)
+        }
+        async fn update_template(
+            &self,
+            template: RecurringActivityTemplate,
+        ) -> Result<RecurringActivityTemplate> {
+            let mut templates = self.templates.lock().unwrap();
+            templates.retain(|t| t.id != template.id);
+            templates.push(template.clone());
+            Ok(template)
+        }
+        async fn delete_template(&self, template_id: &str) -> Result<usize> {
+            let mut templates = self.templates.lock().unwrap();
+            let before = templates.len();
+            templates.retain(|t| t.id != template_id);
+            Ok(before - templates.len())
+        }
+        async fn set_last_generated_date(&self, template_id: &str, date: NaiveDate) -> Result<()> {
+            let mut templates = self.templates.lock().unwrap();
+            if let Some(t) = templates.iter_mut().find(|t| t.id == template_id) {
+                t.last_generated_date = Some(date);
+            }
+            Ok(())
+        }
+    }
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn new_template(rrule: &str) -> NewRecurringActivityTemplate {
+        NewRecurringActivityTemplate {
+            id: Some("tpl-1".to_string()),
+            account_id: "acc-1".to_string(),
+            name: "MPF contribution".to_string(),
+            activity_type: ACTIVITY_TYPE_DEPOSIT.to_string(),
+            subtype: None,
+            asset_id: None,
+            quantity: None,
+            unit_price: None,
+            amount: Some(dec!(1500)),
+            amount_formula: None,
+            fee: None,
+            currency: "HKD".to_string(),
+            notes: None,
+            rrule: rrule.to_string(),
+            start_date: d(2024, 1, 1),
+            end_date: None,
+            business_day_adjustment: BusinessDayAdjustment::None,
+            generate_status: ActivityStatus::Posted,
+        }
+    }
+
+    fn service() -> (
+        RecurringActivityService,
+        Arc<MockRecurringRepository>,
+        Arc<MockActivityService>,
+    ) {
+        let repository = Arc::new(MockRecurringRepository::default());
+        let activity_service = Arc::new(MockActivityService::default());
+        (
+            RecurringActivityService::new(repository.clone(), activity_service.clone()),
+            repository,
+            activity_service,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_create_template_validates_rule_and_amount() {
+        let (service, _, _) = service();
+
+        assert!(service
+            .create_template(new_template("FREQ=FORTNIGHTLY"))
+            .await
+            .is_err());
+
+        let mut missing_amount = new_template("FREQ=MONTHLY");
+        missing_amount.amount = None;
+        assert!(service.create_template(missing_amount).await.is_err());
+
+        let mut draft = new_template("FREQ=MONTHLY");
+        draft.generate_status = ActivityStatus::Draft;
+        assert!(service.create_template(draft).await.is_err());
+
+        let created = service
+            .create_template(new_template("FREQ=MONTHLY;BYMONTHDAY=25"))
+            .await
+            .unwrap();
+        assert!(created.is_active);
+        assert!(created.last_generated_date.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_generate_due_is_idempotent() {
+        let (service, repository, activities) = service();
+        service
+            .create_template(new_template("FREQ=MONTHLY;BYMONTHDAY=25"))
+            .await
+            .unwrap();
+
+        let result = service.generate_due(d(2024, 3, 31)).await.unwrap();
+        assert_eq!(result.generated, 3);
+        assert!(result.errors.is_empty());
+        assert_eq!(
+            repository
+                .get_template("tpl-1")
+                .unwrap()
+                .last_generated_date,
+            Some(d(2024, 3, 25))
+        );
+
+        let created = activities.created.lock().unwrap().clone();
+        assert_eq!(created[0].1.activity_date, "2024-01-25");
+        assert_eq!(
+            created[0].1.idempotency_key.as_deref(),
+            Some(recurring_idempotency_key("tpl-1", d(2024, 1, 25)).as_str())
+        );
+        assert_eq!(
+            created[0].1.source_system.as_deref(),
+            Some(RECURRING_SOURCE_SYSTEM)
+        );
+
+        // Running again for the same date creates nothing new
+        let again = service.generate_due(d(2024, 3, 31)).await.unwrap();
+        assert_eq!(again.generated, 0);
+
+        // Progress lost (e.g. restored template): existing activities are detected by key
+        {
+            let mut templates = repository.templates.lock().unwrap();
+            templates[0].last_generated_date = None;
+        }
+        let replay = service.generate_due(d(2024, 4, 30)).await.unwrap();
+        assert_eq!(replay.generated, 1);
+        assert_eq!(replay.skipped_existing, 3);
+    }
+
+    #[tokio::test]
+    async fn test_generate_due_uses_status_and_business_day() {
+        let (service, _, activities) = service();
+        let mut template = new_template("FREQ=MONTHLY;BYMONTHDAY=1;COUNT=1");
+        // 2024-06-01 is a Saturday
+        template.start_date = d(2024, 6, 1);
+        template.business_day_adjustment = BusinessDayAdjustment::Following;
+        template.generate_status = ActivityStatus::Pending;
+        service.create_template(template).await.unwrap();
+
+        let early = service.generate_due(d(2024, 6, 2)).await.unwrap();
+        assert_eq!(early.generated, 0);
+
+        let due = service.generate_due(d(2024, 6, 3)).await.unwrap();
+        assert_eq!(due.generated, 1);
+        let created = activities.created.lock().unwrap().clone();
+        assert_eq!(created[0].1.activity_date, "2024-06-03");
+        assert_eq!(created[0].1.status, Some(ActivityStatus::Pending));
+    }
+
+    #[tokio::test]
+    async fn test_inactive_templates_are_skipped() {
+        let (service, repository, _) = service();
+        let mut template = service
+            .create_template(new_template("FREQ=MONTHLY"))
+            .await
+            .unwrap();
+        template.is_active = false;
+        service.update_template(template).await.unwrap();
+
+        let result = service.generate_due(d(2024, 12, 31)).await.unwrap();
+        assert_eq!(result.generated, 0);
+        assert!(repository
+            .get_template("tpl-1")
+            .unwrap()
+            .last_generated_date
+            .is_none());
+    }
+
+    #[test]
+    fn test_formula_amounts_per_occurrence() {
+        let now = Utc::now().naive_utc();
+        let template = RecurringActivityTemplate {
+            id: "tpl-1".to_string(),
+            account_id: "acc-1".to_string(),
+            name: "Fixed deposit interest".to_string(),
+            activity_type: ACTIVITY_TYPE_INTEREST.to_string(),
+            subtype: None,
+            asset_id: None,
+            quantity: None,
+            unit_price: None,
+            amount: Some(dec!(36500)),
+            amount_formula: Some("amount * 0.04 * days / 365".to_string()),
+            fee: None,
+            currency: "HKD".to_string(),
+            notes: None,
+            rrule: "FREQ=MONTHLY;BYMONTHDAY=-1".to_string(),
+            start_date: d(2024, 1, 31),
+            end_date: None,
+            business_day_adjustment: BusinessDayAdjustment::None,
+            generate_status: ActivityStatus::Posted,
+            is_active: true,
+            last_generated_date: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let occurrences = compute_occurrences(&template, d(2024, 3, 31)).unwrap();
+        let amounts: Vec<_> = occurrences.iter().map(|o| o.amount.unwrap()).collect();
+        // Jan 31 (0 days since start), Feb 29 (29 days), Mar 31 (31 days)
+        assert_eq!(amounts, vec![dec!(0), dec!(116), dec!(124)]);
+    }
+}
@@ -0,0 +1,51 @@
+use chrono::NaiveDate;
+
+use crate::errors::Result;
+use crate::recurring::recurring_model::{
+    NewRecurringActivityTemplate, RecurringActivityTemplate, RecurringGenerationResult,
+    RecurringOccurrence,
+};
+use async_trait::async_trait;
+
+/// Trait for recurring activity template persistence
+#[async_trait]
+pub trait RecurringActivityRepositoryTrait: Send + Sync {
+    fn list_templates(&self) -> Result<Vec<RecurringActivityTemplate>>;
+    fn get_template(&self, template_id: &str) -> Result<RecurringActivityTemplate>;
+    async fn insert_template(
+        &self,
+        template: RecurringActivityTemplate,
+    ) -> Result<RecurringActivityTemplate>;
+    async fn update_template(
+        &self,
+        template: RecurringActivityTemplate,
+    ) -> Result<RecurringActivityTemplate>;
+    async fn delete_template(&self, template_id: &str) -> Result<usize>;
+    async fn set_last_generated_date(&self, template_id: &str, date: NaiveDate) -> Result<()>;
+}
+
+/// Trait for recurring activity service operations
+#[async_trait]
+pub trait RecurringActivityServiceTrait: Send + Sync {
+    fn get_templates(&self) -> Result<Vec<RecurringActivityTemplate>>;
+    async fn create_template(
+        &self,
+        template: NewRecurringActivityTemplate,
+    ) -> Result<RecurringActivityTemplate>;
+    async fn update_template(
+        &self,
+        template: RecurringActivityTemplate,
+    ) -> Result<RecurringActivityTemplate>;
+    async fn delete_template(&self, template_id: &str) -> Result<usize>;
+
+    /// Lists the template's occurrences up to and including `through`.
+    fn preview_occurrences(
+        &self,
+        template_id: &str,
+        through: NaiveDate,
+    ) -> Result<Vec<RecurringOccurrence>>;
+
+    /// Creates activities for every occurrence of every active template that
+    /// is due on or before `as_of` and has not been generated yet.
+    async fn generate_due(&self, as_of: NaiveDate) -> Result<RecurringGenerationResult>;
+}
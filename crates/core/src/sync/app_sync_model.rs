@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 /// Canonical list of local tables that participate in app-side device sync.
 /// Order matters: parent tables before children (FK dependencies).
-pub const APP_SYNC_TABLES: [&str; 15] = [
+pub const APP_SYNC_TABLES: [&str; 21] = [
     // Base tables (no FK deps)
     "platforms",
     "assets",
@@ -13,8 +13,14 @@ pub const APP_SYNC_TABLES: [&str; 15] = [
     "goals",
     "ai_threads",
     "contribution_limits",
+    "taxonomies",
+    "app_settings",
+    "health_issue_dismissals",
+    "market_data_providers",
     // Depends on: platforms
     "accounts",
+    // Depends on: taxonomies
+    "taxonomy_categories",
     // Depends on: accounts
     "import_runs",
     // Depends on: accounts, assets, import_runs, goals, ai_threads
@@ -25,8 +31,27 @@ pub const APP_SYNC_TABLES: [&str; 15] = [
     "ai_messages",
     "ai_thread_tags",
     "holdings_snapshots",
+    // References activities, assets or accounts (polymorphic, no FK)
+    "attachments",
 ];
 
+/// App settings shared across devices. Other keys (theme, font, instance id,
+/// sync state, desktop window preferences) stay device-local.
+pub const APP_SYNC_SETTING_KEYS: [&str; 5] = [
+    "base_currency",
+    "onboarding_completed",
+    "wealthfolio_connect_visible",
+    "ai_settings",
+    "ai_provider_settings",
+];
+
+pub fn is_synced_setting_key(key: &str) -> bool {
+    APP_SYNC_SETTING_KEYS.contains(&key)
+}
+
+/// Reserved payload key holding the payload schema version.
+pub const SYNC_PAYLOAD_SCHEMA_VERSION_KEY: &str = "_schema_version";
+
 /// Entity names used by incremental sync events.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -45,6 +70,81 @@ pub enum SyncEntity {
     ContributionLimit,
     Platform,
     Snapshot,
+    Taxonomy,
+    TaxonomyCategory,
+    AppSetting,
+    HealthIssueDismissal,
+    MarketDataProvider,
+}
+
+impl SyncEntity {
+    /// Whether the sync id is derived from the entity's natural key columns
+    /// instead of being the row id. Their payloads always carry the key columns.
+    pub fn uses_derived_id(&self) -> bool {
+        matches!(
+            self,
+            SyncEntity::Taxonomy
+                | SyncEntity::TaxonomyCategory
+                | SyncEntity::AppSetting
+                | SyncEntity::HealthIssueDismissal
+                | SyncEntity::MarketDataProvider
+        )
+    }
+
+    /// Payload schema version written by this build.
+    ///
+    /// Entities synced before payloads were versioned return `None` and keep
+    /// their unversioned shape so peers on earlier builds still apply them.
+    pub fn payload_schema_version(&self) -> Option<i32> {
+        match self {
+            SyncEntity::Taxonomy
+            | SyncEntity::TaxonomyCategory
+            | SyncEntity::AppSetting
+            | SyncEntity::HealthIssueDismissal
+            | SyncEntity::MarketDataProvider => Some(1),
+            _ => None,
+        }
+    }
+}
+
+/// Derives the sync id of an entity keyed by natural key values, so every
+/// device computes the same UUID for the same row.
+pub fn derived_sync_entity_id(entity: SyncEntity, key_values: &[&str]) -> String {
+    let entity_name = serde_json::to_string(&entity).unwrap_or_default();
+    let name = format!(
+        "{}:{}",
+        entity_name.trim_matches('"'),
+        key_values.join("\u{1f}")
+    );
+    uuid::Uuid::new_v5(&uuid::Uuid::NAMESPACE_OID, name.as_bytes()).to_string()
+}
+
+/// Removes the schema version from a payload and upgrades the payload to the
+/// shape this build writes. Payloads without a version are version 1.
+pub fn upgrade_sync_payload(
+    entity: SyncEntity,
+    payload: &mut serde_json::Map<String, serde_json::Value>,
+) -> Result<(), String> {
+    let version = match payload.remove(SYNC_PAYLOAD_SCHEMA_VERSION_KEY) {
+        None | Some(serde_json::Value::Null) => 1,
+        Some(value) => value
+            .as_i64()
+            .and_then(|value| i32::try_from(value).ok())
+            .ok_or_else(|| format!("Invalid sync payload schema version: {}", value))?,
+    };
+    let current = entity.payload_schema_version().unwrap_or(1);
+    if version < 1 {
+        return Err(format!("Invalid sync payload schema version: {}", version));
+    }
+    if version > current {
+        return Err(format!(
+            "{:?} payload schema version {} is newer than supported version {}; update the app to apply it",
+            entity, version, current
+        ));
+    }
+    // No payload shape has changed yet. Upgrade steps go here, one version at
+    // a time, when one does.
+    Ok(())
 }
 
 /// Supported sync operations.
@@ -213,7 +313,10 @@ pub trait EntitySyncAdapter: Send + Sync {
 
 #[cfg(test)]
 mod tests {
-    use super::{should_apply_lww, SyncEntity};
+    use super::{
+        derived_sync_entity_id, should_apply_lww, upgrade_sync_payload, SyncEntity,
+        SYNC_PAYLOAD_SCHEMA_VERSION_KEY,
+    };
 
     #[test]
     fn lww_newer_timestamp_wins() {
@@ -262,6 +365,11 @@ mod tests {
             SyncEntity::ContributionLimit,
             SyncEntity::Platform,
             SyncEntity::Snapshot,
+            SyncEntity::Taxonomy,
+            SyncEntity::TaxonomyCategory,
+            SyncEntity::AppSetting,
+            SyncEntity::HealthIssueDismissal,
+            SyncEntity::MarketDataProvider,
         ]
         .iter()
         .map(|entity| serde_json::to_string(entity).expect("serialize sync entity"))
@@ -282,8 +390,63 @@ mod tests {
             "\"contribution_limit\"",
             "\"platform\"",
             "\"snapshot\"",
+            "\"taxonomy\"",
+            "\"taxonomy_category\"",
+            "\"app_setting\"",
+            "\"health_issue_dismissal\"",
+            "\"market_data_provider\"",
         ];
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn derived_ids_are_stable_uuids_scoped_by_entity_and_key() {
+        let id = derived_sync_entity_id(SyncEntity::TaxonomyCategory, &["regions", "CASH"]);
+        assert!(uuid::Uuid::parse_str(&id).is_ok());
+        assert_eq!(
+            id,
+            derived_sync_entity_id(SyncEntity::TaxonomyCategory, &["regions", "CASH"])
+        );
+        assert_ne!(
+            id,
+            derived_sync_entity_id(SyncEntity::TaxonomyCategory, &["asset_classes", "CASH"])
+        );
+        assert_ne!(
+            derived_sync_entity_id(SyncEntity::Taxonomy, &["regions"]),
+            derived_sync_entity_id(SyncEntity::MarketDataProvider, &["regions"])
+        );
+    }
+
+    #[test]
+    fn payload_upgrade_strips_version_and_accepts_unversioned_payloads() {
+        let mut versioned =
+            serde_json::json!({ "id": "tax-1", SYNC_PAYLOAD_SCHEMA_VERSION_KEY: 1 });
+        let fields = versioned.as_object_mut().expect("object");
+        upgrade_sync_payload(SyncEntity::Taxonomy, fields).expect("upgrade v1");
+        assert!(!fields.contains_key(SYNC_PAYLOAD_SCHEMA_VERSION_KEY));
+
+        let mut legacy = serde_json::json!({ "id": "acc-1" });
+        upgrade_sync_payload(SyncEntity::Account, legacy.as_object_mut().expect("object"))
+            .expect("upgrade unversioned");
+        assert_eq!(legacy, serde_json::json!({ "id": "acc-1" }));
+    }
+
+    #[test]
+    fn payload_upgrade_rejects_unknown_versions() {
+        for version in [
+            serde_json::json!(0),
+            serde_json::json!(2),
+            serde_json::json!("v1"),
+        ] {
+            let mut payload = serde_json::json!({ "id": "tax-1" });
+            payload[SYNC_PAYLOAD_SCHEMA_VERSION_KEY] = version.clone();
+            assert!(
+                upgrade_sync_payload(SyncEntity::Taxonomy, payload.as_object_mut().unwrap())
+                    .is_err(),
+                "version {} should be rejected",
+                version
+            );
+        }
+    }
 }
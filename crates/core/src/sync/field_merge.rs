@@ -0,0 +1,348 @@
+//! Field-level merge for incremental sync events and conflict review.
+//!
+//! Create/update payloads carry a map of the fields the writer changed, each
+//! with the client timestamp of the change and the timestamp of the version it
+//! replaced (its base). A receiver applies a remote field when it has not
+//! changed that field since the remote's base; when both sides changed the
+//! same field from a common base, the local value is kept and a conflict is
+//! recorded for review. Events without the map fall back to whole-entity LWW.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{should_apply_lww, SyncEntity};
+use crate::health::{HealthCategory, HealthIssue, NavigateAction, Severity};
+
+/// Reserved payload key holding the per-field change map.
+pub const SYNC_FIELD_VERSIONS_KEY: &str = "_field_versions";
+
+/// Bookkeeping fields touched by every write. They merge by timestamp and are
+/// never reported as conflicts.
+pub const SYNC_BOOKKEEPING_FIELDS: [&str; 2] = ["created_at", "updated_at"];
+
+/// Change metadata for one field in a sync payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncFieldVersion {
+    /// Client timestamp of the change.
+    pub updated_at: String,
+    /// Timestamp of the version the writer changed, if it had one.
+    pub base: Option<String>,
+}
+
+/// Changed fields of one event, keyed by column name.
+pub type SyncFieldVersions = BTreeMap<String, SyncFieldVersion>;
+
+/// Last known value and version of a field on this device.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncFieldState {
+    pub value: Value,
+    pub updated_at: String,
+    pub event_id: String,
+}
+
+/// Outcome of merging one remote field into local state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldMergeDecision {
+    TakeRemote,
+    KeepLocal,
+    Conflict,
+}
+
+/// Removes and parses the field change map from a payload object.
+pub fn take_field_versions(
+    payload: &mut serde_json::Map<String, Value>,
+) -> Result<Option<SyncFieldVersions>, String> {
+    payload
+        .remove(SYNC_FIELD_VERSIONS_KEY)
+        .map(|raw| {
+            serde_json::from_value(raw)
+                .map_err(|err| format!("Invalid sync field versions: {}", err))
+        })
+        .transpose()
+}
+
+/// Decides how a remote field change merges with the local field state.
+pub fn merge_field(
+    field: &str,
+    local: Option<&SyncFieldState>,
+    remote_value: &Value,
+    remote: &SyncFieldVersion,
+    remote_event_id: &str,
+) -> FieldMergeDecision {
+    let Some(local) = local else {
+        return FieldMergeDecision::TakeRemote;
+    };
+    let remote_is_newer = should_apply_lww(
+        &local.updated_at,
+        &local.event_id,
+        &remote.updated_at,
+        remote_event_id,
+    );
+
+    if &local.value == remote_value || SYNC_BOOKKEEPING_FIELDS.contains(&field) {
+        return if remote_is_newer {
+            FieldMergeDecision::TakeRemote
+        } else {
+            FieldMergeDecision::KeepLocal
+        };
+    }
+
+    let Some(base) = remote.base.as_deref() else {
+        // The writer had no field history yet; order by timestamp alone.
+        return if remote_is_newer {
+            FieldMergeDecision::TakeRemote
+        } else {
+            FieldMergeDecision::KeepLocal
+        };
+    };
+
+    // A base at or after our version means the writer already saw our change.
+    if !timestamp_is_before(base, &local.updated_at) {
+        FieldMergeDecision::TakeRemote
+    } else {
+        FieldMergeDecision::Conflict
+    }
+}
+
+fn timestamp_is_before(left: &str, right: &str) -> bool {
+    match (
+        chrono::DateTime::parse_from_rfc3339(left),
+        chrono::DateTime::parse_from_rfc3339(right),
+    ) {
+        (Ok(left), Ok(right)) => left.timestamp_millis() < right.timestamp_millis(),
+        _ => left < right,
+    }
+}
+
+/// How a field conflict was settled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncConflictResolution {
+    Local,
+    Remote,
+    Manual,
+    /// A later remote change to the field replaced both values.
+    Superseded,
+}
+
+/// A field changed on this device and on another device from the same base.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncFieldConflict {
+    pub id: String,
+    pub entity: SyncEntity,
+    pub entity_id: String,
+    pub field: String,
+    pub local_value: Value,
+    pub local_updated_at: String,
+    pub remote_value: Value,
+    pub remote_updated_at: String,
+    pub remote_event_id: String,
+    pub resolution: Option<SyncConflictResolution>,
+    pub resolved_value: Option<Value>,
+    pub created_at: String,
+    pub resolved_at: Option<String>,
+}
+
+/// Request to settle an open conflict. `value` is required for manual resolutions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveSyncConflictRequest {
+    pub conflict_id: String,
+    pub resolution: SyncConflictResolution,
+    #[serde(default)]
+    pub value: Option<Value>,
+}
+
+/// Health source used when publishing open conflicts.
+pub const SYNC_CONFLICTS_HEALTH_SOURCE: &str = "sync_conflicts";
+
+/// Summarizes open conflicts as a single Health Center issue.
+pub fn sync_conflicts_to_health_issues(conflicts: &[SyncFieldConflict]) -> Vec<HealthIssue> {
+    let open = conflicts
+        .iter()
+        .filter(|conflict| conflict.resolution.is_none())
+        .collect::<Vec<_>>();
+    if open.is_empty() {
+        return Vec::new();
+    }
+
+    let mut ids = open
+        .iter()
+        .map(|conflict| conflict.id.as_str())
+        .collect::<Vec<_>>();
+    ids.sort_unstable();
+    let details = open
+        .iter()
+        .map(|conflict| {
+            format!(
+                "{:?} {}: {}",
+                conflict.entity, conflict.entity_id, conflict.field
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    vec![HealthIssue::builder()
+        .id("sync_conflicts")
+        .severity(Severity::Warning)
+        .category(HealthCategory::SyncConflicts)
+        .title(format!("{} sync conflict(s) to review", open.len()))
+        .message("The same fields were edited on two devices. Choose which value to keep.")
+        .affected_count(open.len() as u32)
+        .navigate_action(NavigateAction {
+            route: "/settings/exports".to_string(),
+            query: None,
+            label: "Review Conflicts".to_string(),
+        })
+        .details(details)
+        .data_hash(ids.join(","))
+        .build()]
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn local(value: Value, updated_at: &str) -> SyncFieldState {
+        SyncFieldState {
+            value,
+            updated_at: updated_at.to_string(),
+            event_id: "local-event".to_string(),
+        }
+    }
+
+    fn remote(updated_at: &str, base: Option<&str>) -> SyncFieldVersion {
+        SyncFieldVersion {
+            updated_at: updated_at.to_string(),
+            base: base.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn takes_remote_change_made_on_top_of_local_version() {
+        let decision = merge_field(
+            "notes",
+            Some(&local(json!("old"), "2026-03-01T10:00:00Z")),
+            &json!("new"),
+            &remote("2026-03-01T11:00:00Z", Some("2026-03-01T10:00:00Z")),
+            "remote-event",
+        );
+        assert_eq!(decision, FieldMergeDecision::TakeRemote);
+    }
+
+    #[test]
+    fn reports_conflict_when_both_sides_changed_the_field() {
+        let decision = merge_field(
+            "notes",
+            Some(&local(json!("desktop"), "2026-03-01T11:00:00Z")),
+            &json!("phone"),
+            &remote("2026-03-01T12:00:00Z", Some("2026-03-01T10:00:00Z")),
+            "remote-event",
+        );
+        assert_eq!(decision, FieldMergeDecision::Conflict);
+    }
+
+    #[test]
+    fn equal_values_and_bookkeeping_fields_never_conflict() {
+        let state = local(json!("same"), "2026-03-01T11:00:00Z");
+        let change = remote("2026-03-01T12:00:00Z", Some("2026-03-01T10:00:00Z"));
+        assert_eq!(
+            merge_field(
+                "notes",
+                Some(&state),
+                &json!("same"),
+                &change,
+                "remote-event"
+            ),
+            FieldMergeDecision::TakeRemote
+        );
+        assert_eq!(
+            merge_field(
+                "updated_at",
+                Some(&state),
+                &json!("2026-03-01T12:00:00Z"),
+                &change,
+                "remote-event"
+            ),
+            FieldMergeDecision::TakeRemote
+        );
+    }
+
+    #[test]
+    fn falls_back_to_timestamps_without_a_base() {
+        let state = local(json!("desktop"), "2026-03-01T11:00:00Z");
+        assert_eq!(
+            merge_field(
+                "notes",
+                Some(&state),
+                &json!("phone"),
+                &remote("2026-03-01T10:30:00Z", None),
+                "remote-event"
+            ),
+            FieldMergeDecision::KeepLocal
+        );
+        assert_eq!(
+            merge_field(
+                "notes",
+                Some(&state),
+                &json!("phone"),
+                &remote("2026-03-01T11:30:00Z", None),
+                "remote-event"
+            ),
+            FieldMergeDecision::TakeRemote
+        );
+    }
+
+    #[test]
+    fn strips_field_versions_from_payload() {
+        let mut payload = json!({
+            "id": "act-1",
+            "notes": "new",
+            "_field_versions": {
+                "notes": { "updatedAt": "2026-03-01T11:00:00Z", "base": null }
+            }
+        });
+        let versions = take_field_versions(payload.as_object_mut().unwrap())
+            .expect("parse")
+            .expect("versions");
+        assert_eq!(versions["notes"], remote("2026-03-01T11:00:00Z", None));
+        assert!(payload.get(SYNC_FIELD_VERSIONS_KEY).is_none());
+    }
+
+    #[test]
+    fn summarizes_open_conflicts_as_one_health_issue() {
+        let conflict = SyncFieldConflict {
+            id: "conflict-1".to_string(),
+            entity: SyncEntity::Activity,
+            entity_id: "act-1".to_string(),
+            field: "notes".to_string(),
+            local_value: json!("desktop"),
+            local_updated_at: "2026-03-01T11:00:00Z".to_string(),
+            remote_value: json!("phone"),
+            remote_updated_at: "2026-03-01T12:00:00Z".to_string(),
+            remote_event_id: "remote-event".to_string(),
+            resolution: None,
+            resolved_value: None,
+            created_at: "2026-03-01T12:00:01Z".to_string(),
+            resolved_at: None,
+        };
+        let resolved = SyncFieldConflict {
+            id: "conflict-2".to_string(),
+            resolution: Some(SyncConflictResolution::Local),
+            ..conflict.clone()
+        };
+
+        let issues = sync_conflicts_to_health_issues(&[conflict, resolved]);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].category, HealthCategory::SyncConflicts);
+        assert_eq!(issues[0].affected_count, 1);
+        assert_eq!(issues[0].data_hash, "conflict-1");
+        assert!(sync_conflicts_to_health_issues(&[]).is_empty());
+    }
+}
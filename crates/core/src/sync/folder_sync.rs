@@ -4,11 +4,16 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
-use super::{SyncEntity, SyncOperation};
+use super::{SyncEntity, SyncOperation, APP_SYNC_SETTING_KEYS};
 
 pub const FOLDER_SYNC_VERSION_V1: i32 = 1;
 pub const FOLDER_SYNC_METADATA_FILE: &str = "folder.json";
-pub const FOLDER_SYNC_SHARED_SETTING_KEYS: [&str; 1] = ["base_currency"];
+/// Settings a peer may apply from a snapshot manifest. Snapshots also carry
+/// these rows in the synced `app_settings` table.
+pub const FOLDER_SYNC_SHARED_SETTING_KEYS: [&str; 5] = APP_SYNC_SETTING_KEYS;
+pub const FOLDER_SYNC_ENCRYPTION_VERSION_V1: i32 = 1;
+pub const FOLDER_SYNC_CIPHER_XCHACHA20POLY1305: &str = "xchacha20poly1305";
+pub const FOLDER_SYNC_KDF_ARGON2ID: &str = "argon2id";
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -16,6 +21,44 @@ pub struct FolderSyncMetadataV1 {
     pub version: i32,
     pub created_at: String,
     pub created_by_device_id: String,
+    /// Present when event files and snapshots are encrypted with a passphrase.
+    /// Folders created before encryption existed omit it and stay plaintext.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<FolderSyncEncryptionV1>,
+}
+
+/// Header describing how the folder key is derived from the passphrase.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSyncEncryptionV1 {
+    pub version: i32,
+    pub cipher: String,
+    pub kdf: FolderSyncKdfV1,
+    /// Random id of the current key; changes on every passphrase rotation.
+    pub key_id: String,
+    /// HMAC of the key id under the derived key, used to verify a passphrase.
+    pub key_check: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotated_at: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSyncKdfV1 {
+    pub algorithm: String,
+    pub salt: String,
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+/// Envelope for encrypted JSON files (events and snapshot manifests).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSyncEncryptedFileV1 {
+    pub version: i32,
+    pub key_id: String,
+    pub ciphertext: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -67,7 +110,8 @@ mod tests {
 
     use super::{
         event_file_name, is_shared_setting_key, snapshot_file_name, FolderSyncEventFileV1,
-        FolderSyncMetadataV1, FOLDER_SYNC_METADATA_FILE, FOLDER_SYNC_VERSION_V1,
+        FolderSyncMetadataV1, FOLDER_SYNC_CIPHER_XCHACHA20POLY1305, FOLDER_SYNC_KDF_ARGON2ID,
+        FOLDER_SYNC_METADATA_FILE, FOLDER_SYNC_VERSION_V1,
     };
     use crate::sync::{SyncEntity, SyncOperation};
 
@@ -85,9 +129,42 @@ mod tests {
         assert_eq!(parsed.version, FOLDER_SYNC_VERSION_V1);
         assert_eq!(parsed.created_at, "2026-03-07T13:00:00.000Z");
         assert_eq!(parsed.created_by_device_id, "device-a");
+        assert_eq!(parsed.encryption, None);
         assert_eq!(FOLDER_SYNC_METADATA_FILE, "folder.json");
     }
 
+    #[test]
+    fn parses_encrypted_folder_metadata_from_json() {
+        let parsed: FolderSyncMetadataV1 = serde_json::from_str(
+            r#"{
+                "version": 1,
+                "createdAt": "2026-03-07T13:00:00.000Z",
+                "createdByDeviceId": "device-a",
+                "encryption": {
+                    "version": 1,
+                    "cipher": "xchacha20poly1305",
+                    "kdf": {
+                        "algorithm": "argon2id",
+                        "salt": "c2FsdA==",
+                        "memoryKib": 19456,
+                        "iterations": 2,
+                        "parallelism": 1
+                    },
+                    "keyId": "key-1",
+                    "keyCheck": "abc"
+                }
+            }"#,
+        )
+        .expect("parse encrypted folder metadata");
+
+        let encryption = parsed.encryption.expect("encryption header");
+        assert_eq!(encryption.cipher, FOLDER_SYNC_CIPHER_XCHACHA20POLY1305);
+        assert_eq!(encryption.kdf.algorithm, FOLDER_SYNC_KDF_ARGON2ID);
+        assert_eq!(encryption.kdf.memory_kib, 19456);
+        assert_eq!(encryption.key_id, "key-1");
+        assert_eq!(encryption.rotated_at, None);
+    }
+
     #[test]
     fn event_file_name_uses_json_extension() {
         assert_eq!(event_file_name("evt-123"), "evt-123.json");
@@ -134,6 +211,7 @@ mod tests {
     #[test]
     fn shared_setting_allowlist_only_exposes_shared_values() {
         assert!(is_shared_setting_key("base_currency"));
+        assert!(is_shared_setting_key("ai_settings"));
         assert!(!is_shared_setting_key("instance_id"));
         assert!(!is_shared_setting_key("theme"));
         assert!(!is_shared_setting_key("font"));
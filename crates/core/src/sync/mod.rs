@@ -1,7 +1,9 @@
 //! Generic app/device sync contracts shared across layers.
 
 mod app_sync_model;
+mod field_merge;
 mod folder_sync;
 
 pub use app_sync_model::*;
+pub use field_merge::*;
 pub use folder_sync::*;
@@ -19,6 +19,8 @@ use crate::types::*;
 /// Default timeout for API requests.
 const DEFAULT_TIMEOUT_SECS: u64 = 30;
 const MAX_LOG_BODY_CHARS: usize = 512;
+const DEVICE_ID_HEADER: &str = "x-wf-device-id";
+const DEVICE_TOKEN_HEADER: &str = "x-wf-device-token";
 const SNAPSHOT_UPLOAD_MAX_ATTEMPTS: usize = 5;
 const SNAPSHOT_UPLOAD_BASE_BACKOFF_MS: u64 = 250;
 const SNAPSHOT_UPLOAD_MAX_BACKOFF_MS: u64 = 8_000;
@@ -64,6 +66,8 @@ fn snapshot_backoff_with_jitter(attempt: usize) -> Duration {
 pub struct DeviceSyncClient {
     client: reqwest::Client,
     base_url: String,
+    /// Relay-issued credential sent alongside the device ID.
+    device_token: Option<String>,
 }
 
 impl DeviceSyncClient {
@@ -207,9 +211,17 @@ impl DeviceSyncClient {
         Self {
             client,
             base_url: base_url.trim_end_matches('/').to_string(),
+            device_token: None,
         }
     }
 
+    /// Authenticate device-scoped requests with the token a self-hosted relay
+    /// issued at enrollment.
+    pub fn with_device_token(mut self, device_token: Option<String>) -> Self {
+        self.device_token = device_token;
+        self
+    }
+
     /// Create headers for an API request.
     fn headers(&self, token: &str) -> Result<HeaderMap> {
         self.headers_with_device(token, None)
@@ -227,7 +239,13 @@ impl DeviceSyncClient {
         if let Some(device_id) = device_id {
             let device_id_value = HeaderValue::from_str(device_id)
                 .map_err(|_| DeviceSyncError::auth("Invalid device ID format"))?;
-            headers.insert("x-wf-device-id", device_id_value);
+            headers.insert(DEVICE_ID_HEADER, device_id_value);
+
+            if let Some(device_token) = &self.device_token {
+                let device_token_value = HeaderValue::from_str(device_token)
+                    .map_err(|_| DeviceSyncError::auth("Invalid device token format"))?;
+                headers.insert(DEVICE_TOKEN_HEADER, device_token_value);
+            }
         }
 
         Ok(headers)
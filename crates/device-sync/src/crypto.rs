@@ -4,8 +4,10 @@
 //! - Root key generation and derivation
 //! - X25519 ECDH key exchange
 //! - XChaCha20-Poly1305 authenticated encryption
+//! - Argon2id passphrase key derivation
 //! - Pairing code generation and verification
 
+use argon2::{Algorithm, Argon2, Params, Version};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use chacha20poly1305::{
     aead::{Aead, KeyInit},
@@ -21,6 +23,7 @@ use x25519_dalek::{PublicKey, StaticSecret};
 /// Key sizes
 const ROOT_KEY_SIZE: usize = 32;
 const NONCE_SIZE: usize = 24; // XChaCha20 uses 24-byte nonce
+const SALT_SIZE: usize = 16;
 
 /// HKDF info strings
 const DEK_INFO: &[u8] = b"wealthfolio-dek";
@@ -122,14 +125,22 @@ pub fn derive_session_key(shared_secret_b64: &str, context: &str) -> Result<Stri
 
 /// Encrypt data using XChaCha20-Poly1305
 pub fn encrypt(key_b64: &str, plaintext: &str) -> Result<String, String> {
-    let key_bytes: [u8; 32] = BASE64
-        .decode(key_b64)
-        .map_err(|e| format!("Invalid key: {}", e))?
-        .try_into()
-        .map_err(|_| "Key must be 32 bytes")?;
+    encrypt_bytes(key_b64, plaintext.as_bytes()).map(|data| BASE64.encode(data))
+}
+
+/// Decrypt data using XChaCha20-Poly1305
+pub fn decrypt(key_b64: &str, ciphertext_b64: &str) -> Result<String, String> {
+    let data = BASE64
+        .decode(ciphertext_b64)
+        .map_err(|e| format!("Invalid ciphertext: {}", e))?;
+    let plaintext = decrypt_bytes(key_b64, &data)?;
+    String::from_utf8(plaintext).map_err(|e| format!("Invalid UTF-8 in plaintext: {}", e))
+}
 
-    let cipher = XChaCha20Poly1305::new_from_slice(&key_bytes)
-        .map_err(|e| format!("Failed to create cipher: {}", e))?;
+/// Encrypt binary data using XChaCha20-Poly1305.
+/// Returns `nonce || ciphertext` without base64 encoding.
+pub fn encrypt_bytes(key_b64: &str, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let cipher = cipher_from_key(key_b64)?;
 
     // Generate random nonce
     let mut nonce_bytes = [0u8; NONCE_SIZE];
@@ -138,7 +149,7 @@ pub fn encrypt(key_b64: &str, plaintext: &str) -> Result<String, String> {
 
     // Encrypt
     let ciphertext = cipher
-        .encrypt(nonce, plaintext.as_bytes())
+        .encrypt(nonce, plaintext)
         .map_err(|e| format!("Encryption failed: {}", e))?;
 
     // Prepend nonce to ciphertext
@@ -146,37 +157,85 @@ pub fn encrypt(key_b64: &str, plaintext: &str) -> Result<String, String> {
     result.extend_from_slice(&nonce_bytes);
     result.extend_from_slice(&ciphertext);
 
-    Ok(BASE64.encode(result))
+    Ok(result)
 }
 
-/// Decrypt data using XChaCha20-Poly1305
-pub fn decrypt(key_b64: &str, ciphertext_b64: &str) -> Result<String, String> {
+/// Decrypt binary data produced by [`encrypt_bytes`].
+pub fn decrypt_bytes(key_b64: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+    if data.len() < NONCE_SIZE {
+        return Err("Ciphertext too short".to_string());
+    }
+
+    // Extract nonce and ciphertext
+    let (nonce_bytes, ciphertext) = data.split_at(NONCE_SIZE);
+    let nonce = XNonce::from_slice(nonce_bytes);
+
+    let cipher = cipher_from_key(key_b64)?;
+    cipher
+        .decrypt(nonce, ciphertext)
+        .map_err(|_| "Decryption failed - invalid key or corrupted data".to_string())
+}
+
+fn cipher_from_key(key_b64: &str) -> Result<XChaCha20Poly1305, String> {
     let key_bytes: [u8; 32] = BASE64
         .decode(key_b64)
         .map_err(|e| format!("Invalid key: {}", e))?
         .try_into()
         .map_err(|_| "Key must be 32 bytes")?;
 
-    let data = BASE64
-        .decode(ciphertext_b64)
-        .map_err(|e| format!("Invalid ciphertext: {}", e))?;
+    XChaCha20Poly1305::new_from_slice(&key_bytes)
+        .map_err(|e| format!("Failed to create cipher: {}", e))
+}
 
-    if data.len() < NONCE_SIZE {
-        return Err("Ciphertext too short".to_string());
+/// Argon2id cost parameters for passphrase-derived keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PassphraseKdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for PassphraseKdfParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
     }
+}
 
-    // Extract nonce and ciphertext
-    let (nonce_bytes, ciphertext) = data.split_at(NONCE_SIZE);
-    let nonce = XNonce::from_slice(nonce_bytes);
+/// Generate a random salt for passphrase key derivation
+pub fn generate_salt() -> String {
+    let mut salt = [0u8; SALT_SIZE];
+    OsRng.fill_bytes(&mut salt);
+    BASE64.encode(salt)
+}
 
-    let cipher = XChaCha20Poly1305::new_from_slice(&key_bytes)
-        .map_err(|e| format!("Failed to create cipher: {}", e))?;
+/// Derive a 32-byte encryption key from a passphrase using Argon2id
+pub fn derive_passphrase_key(
+    passphrase: &str,
+    salt_b64: &str,
+    params: &PassphraseKdfParams,
+) -> Result<String, String> {
+    let salt = BASE64
+        .decode(salt_b64)
+        .map_err(|e| format!("Invalid salt: {}", e))?;
+    let params = Params::new(
+        params.memory_kib,
+        params.iterations,
+        params.parallelism,
+        Some(ROOT_KEY_SIZE),
+    )
+    .map_err(|e| format!("Invalid KDF parameters: {}", e))?;
 
-    let plaintext = cipher
-        .decrypt(nonce, ciphertext)
-        .map_err(|_| "Decryption failed - invalid key or corrupted data")?;
+    let mut key = [0u8; ROOT_KEY_SIZE];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+        .map_err(|e| format!("Key derivation failed: {}", e))?;
 
-    String::from_utf8(plaintext).map_err(|e| format!("Invalid UTF-8 in plaintext: {}", e))
+    Ok(BASE64.encode(key))
 }
 
 /// Generate a 6-character alphanumeric pairing code
@@ -312,6 +371,43 @@ mod tests {
         assert_eq!(plaintext, decrypted);
     }
 
+    #[test]
+    fn test_encrypt_decrypt_bytes() {
+        let key = generate_root_key();
+        let plaintext = b"SQLite format 3\0\x01\xff";
+
+        let ciphertext = encrypt_bytes(&key, plaintext).unwrap();
+        assert_eq!(decrypt_bytes(&key, &ciphertext).unwrap(), plaintext);
+        assert!(decrypt_bytes(&generate_root_key(), &ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_passphrase_key_derivation() {
+        let params = PassphraseKdfParams {
+            memory_kib: 1024,
+            iterations: 1,
+            parallelism: 1,
+        };
+        let salt = generate_salt();
+        let key = derive_passphrase_key("correct horse", &salt, &params).unwrap();
+        assert_eq!(BASE64.decode(&key).unwrap().len(), 32);
+
+        // Same passphrase and salt should produce the same key
+        assert_eq!(
+            key,
+            derive_passphrase_key("correct horse", &salt, &params).unwrap()
+        );
+        // A different passphrase or salt should not
+        assert_ne!(
+            key,
+            derive_passphrase_key("wrong horse", &salt, &params).unwrap()
+        );
+        assert_ne!(
+            key,
+            derive_passphrase_key("correct horse", &generate_salt(), &params).unwrap()
+        );
+    }
+
     #[test]
     fn test_pairing_code() {
         let code = generate_pairing_code();
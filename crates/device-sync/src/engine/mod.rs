@@ -53,6 +53,11 @@ fn sync_entity_name(entity: &SyncEntity) -> &'static str {
         SyncEntity::ContributionLimit => "contribution_limit",
         SyncEntity::Platform => "platform",
         SyncEntity::Snapshot => "snapshot",
+        SyncEntity::Taxonomy => "taxonomy",
+        SyncEntity::TaxonomyCategory => "taxonomy_category",
+        SyncEntity::AppSetting => "app_setting",
+        SyncEntity::HealthIssueDismissal => "health_issue_dismissal",
+        SyncEntity::MarketDataProvider => "market_data_provider",
     }
 }
 
@@ -13,7 +13,7 @@ use wealthfolio_core::secrets::SecretStore;
 
 use crate::{
     crypto, CommitInitializeKeysRequest, DevicePlatform, DeviceSyncClient, EnrollDeviceResponse,
-    InitializeKeysResult, RegisterDeviceRequest, TrustState, TrustedDeviceSummary,
+    InitializeKeysResult, RegisterDeviceRequest, SyncRelayConfig, TrustState, TrustedDeviceSummary,
 };
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -140,6 +140,8 @@ pub struct DeviceEnrollService {
     client: DeviceSyncClient,
     device_display_name: String,
     app_version: Option<String>,
+    /// Relay token used instead of the Connect session token.
+    relay_token: Option<String>,
 }
 
 impl DeviceEnrollService {
@@ -155,9 +157,19 @@ impl DeviceEnrollService {
             client: DeviceSyncClient::new(base_url),
             device_display_name,
             app_version,
+            relay_token: None,
         }
     }
 
+    /// Route enrollment through a self-hosted relay instead of the cloud.
+    pub fn with_relay(mut self, relay: Option<SyncRelayConfig>) -> Self {
+        if let Some(relay) = relay {
+            self.client = DeviceSyncClient::new(&relay.base_url);
+            self.relay_token = Some(relay.token);
+        }
+        self
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // PUBLIC API
     // ═══════════════════════════════════════════════════════════════════════════
@@ -200,7 +212,7 @@ impl DeviceEnrollService {
         // Verify device on server
         let token = self.get_access_token()?;
 
-        let device = match self.client.get_device(&token, &device_id).await {
+        let device = match self.client().get_device(&token, &device_id).await {
             Ok(d) => d,
             Err(e) => {
                 // Device not found = RECOVERY
@@ -336,7 +348,7 @@ impl DeviceEnrollService {
         );
 
         let enroll_result = self
-            .client
+            .client()
             .enroll_device(
                 &token,
                 RegisterDeviceRequest {
@@ -349,11 +361,15 @@ impl DeviceEnrollService {
             )
             .await
             .map_err(|e| format!("Enrollment failed: {}", e))?;
+        if let Some(device_token) = enroll_result.device_token() {
+            SyncRelayConfig::save_device_token(self.secret_store.as_ref(), device_token)?;
+        }
 
         match enroll_result {
             EnrollDeviceResponse::Bootstrap {
                 device_id,
                 e2ee_key_version,
+                ..
             } => {
                 info!(
                     "[DeviceEnrollService] Device enrolled: {} (mode: BOOTSTRAP, server_key_version: {:?})",
@@ -455,7 +471,7 @@ impl DeviceEnrollService {
     ) -> Result<KeyInitializationOutcome, EnrollServiceError> {
         // Phase 1: Get challenge from server
         let init_result = self
-            .client
+            .client()
             .initialize_team_keys(token, device_id)
             .await
             .map_err(|e| format!("Failed to initialize keys: {}", e))?;
@@ -513,7 +529,7 @@ impl DeviceEnrollService {
 
         // Phase 2: Commit the keys
         let commit_result = self
-            .client
+            .client()
             .commit_initialize_team_keys(
                 token,
                 CommitInitializeKeysRequest {
@@ -574,7 +590,23 @@ impl DeviceEnrollService {
             .map_err(|e| format!("Failed to save identity: {}", e).into())
     }
 
+    /// Client for the sync server, carrying the relay device token once the
+    /// device has enrolled through a relay.
+    fn client(&self) -> DeviceSyncClient {
+        if self.relay_token.is_none() {
+            return self.client.clone();
+        }
+        self.client
+            .clone()
+            .with_device_token(SyncRelayConfig::load_device_token(
+                self.secret_store.as_ref(),
+            ))
+    }
+
     fn get_access_token(&self) -> Result<String, EnrollServiceError> {
+        if let Some(token) = &self.relay_token {
+            return Ok(token.clone());
+        }
         self.secret_store
             .get_secret(CLOUD_ACCESS_TOKEN_KEY)
             .map_err(|e| format!("Failed to get access token: {}", e))?
@@ -676,7 +708,7 @@ impl DeviceEnrollService {
         reason: &str,
     ) -> Result<(), EnrollServiceError> {
         let reset_result = self
-            .client
+            .client()
             .reset_team_sync(token, Some(reason))
             .await
             .map_err(|e| format!("Failed to reset team sync: {}", e))?;
@@ -692,7 +724,7 @@ impl DeviceEnrollService {
     }
 
     async fn get_trusted_devices(&self, token: &str) -> Vec<TrustedDeviceSummary> {
-        match self.client.list_devices(token, Some("my")).await {
+        match self.client().list_devices(token, Some("my")).await {
             Ok(devices) => devices
                 .into_iter()
                 .filter(|d| d.trust_state == TrustState::Trusted)
@@ -726,7 +758,7 @@ impl DeviceEnrollService {
 
         // Some server responses omit key version for untrusted devices.
         // In that case, probe key-init state to distinguish REGISTERED vs ORPHANED.
-        match self.client.initialize_team_keys(token, device_id).await {
+        match self.client().initialize_team_keys(token, device_id).await {
             Ok(InitializeKeysResult::PairingRequired {
                 e2ee_key_version,
                 trusted_devices: pairing_trusted_devices,
@@ -39,6 +39,7 @@ pub mod crypto;
 pub mod engine;
 mod enroll_service;
 mod error;
+mod relay;
 mod types;
 
 pub use client::DeviceSyncClient;
@@ -47,6 +48,9 @@ pub use enroll_service::{
     SyncStateResult,
 };
 pub use error::{ApiRetryClass, DeviceSyncError, Result};
+pub use relay::{
+    SyncRelayConfig, SYNC_RELAY_CONFIG_KEY, SYNC_RELAY_TOKEN_ENV, SYNC_RELAY_URL_ENV,
+};
 pub use types::*;
 
 pub fn parse_sync_datetime_to_utc(
@@ -0,0 +1,137 @@
+//! Connection settings for a self-hosted sync relay.
+//!
+//! By default devices sync through the Wealthfolio Connect cloud and
+//! authenticate with the Connect session token. A self-hosted server can act
+//! as the relay instead; devices then point `DeviceSyncClient` at its base URL
+//! and authenticate with a shared relay token. Devices using the same token
+//! form one sync team. On enrollment the relay also issues each device its own
+//! token, which proves the device ID on every request the device makes as
+//! itself.
+
+use serde::{Deserialize, Serialize};
+
+use wealthfolio_core::secrets::SecretStore;
+
+/// Secret store key for a relay configured from the app.
+pub const SYNC_RELAY_CONFIG_KEY: &str = "sync_relay_config";
+/// Environment variable with the relay base URL (e.g. `https://wealthfolio.home.lan`).
+pub const SYNC_RELAY_URL_ENV: &str = "WF_DEVICE_SYNC_RELAY_URL";
+/// Environment variable with the relay token.
+pub const SYNC_RELAY_TOKEN_ENV: &str = "WF_DEVICE_SYNC_RELAY_TOKEN";
+/// Secret store key for the device token the relay issued at enrollment.
+pub const SYNC_RELAY_DEVICE_TOKEN_KEY: &str = "sync_relay_device_token";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncRelayConfig {
+    pub base_url: String,
+    pub token: String,
+}
+
+impl SyncRelayConfig {
+    /// Validates and normalizes a relay configuration.
+    pub fn new(base_url: &str, token: &str) -> Result<Self, String> {
+        let base_url = base_url.trim().trim_end_matches('/');
+        let token = token.trim();
+        if !(base_url.starts_with("http://") || base_url.starts_with("https://")) {
+            return Err("Relay URL must start with http:// or https://".to_string());
+        }
+        if token.is_empty() {
+            return Err("Relay token is required".to_string());
+        }
+        Ok(Self {
+            base_url: base_url.to_string(),
+            token: token.to_string(),
+        })
+    }
+
+    /// Reads the relay from `WF_DEVICE_SYNC_RELAY_URL` and `WF_DEVICE_SYNC_RELAY_TOKEN`.
+    pub fn from_env() -> Option<Self> {
+        let base_url = std::env::var(SYNC_RELAY_URL_ENV).ok()?;
+        let token = std::env::var(SYNC_RELAY_TOKEN_ENV).ok()?;
+        match Self::new(&base_url, &token) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                log::warn!("Ignoring device sync relay from environment: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Loads the relay saved in the secret store, if any.
+    pub fn load(secret_store: &dyn SecretStore) -> Option<Self> {
+        let raw = match secret_store.get_secret(SYNC_RELAY_CONFIG_KEY) {
+            Ok(Some(raw)) => raw,
+            Ok(None) => return None,
+            Err(e) => {
+                log::warn!("Failed to read device sync relay config: {}", e);
+                return None;
+            }
+        };
+        match serde_json::from_str(&raw) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                log::warn!("Ignoring malformed device sync relay config: {}", e);
+                None
+            }
+        }
+    }
+
+    /// The relay to use, preferring the environment over saved settings.
+    pub fn resolve(secret_store: &dyn SecretStore) -> Option<Self> {
+        Self::from_env().or_else(|| Self::load(secret_store))
+    }
+
+    pub fn save(&self, secret_store: &dyn SecretStore) -> Result<(), String> {
+        let raw = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        secret_store
+            .set_secret(SYNC_RELAY_CONFIG_KEY, &raw)
+            .map_err(|e| format!("Failed to save device sync relay config: {}", e))
+    }
+
+    pub fn clear(secret_store: &dyn SecretStore) -> Result<(), String> {
+        secret_store
+            .delete_secret(SYNC_RELAY_DEVICE_TOKEN_KEY)
+            .map_err(|e| format!("Failed to clear device sync relay token: {}", e))?;
+        secret_store
+            .delete_secret(SYNC_RELAY_CONFIG_KEY)
+            .map_err(|e| format!("Failed to clear device sync relay config: {}", e))
+    }
+
+    /// Loads the device token issued by the relay at enrollment, if any.
+    pub fn load_device_token(secret_store: &dyn SecretStore) -> Option<String> {
+        match secret_store.get_secret(SYNC_RELAY_DEVICE_TOKEN_KEY) {
+            Ok(token) => token,
+            Err(e) => {
+                log::warn!("Failed to read device sync relay token: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Saves the device token from an enrollment response. Re-enrolling issues
+    /// a new token, so the previous one is replaced.
+    pub fn save_device_token(
+        secret_store: &dyn SecretStore,
+        device_token: &str,
+    ) -> Result<(), String> {
+        secret_store
+            .set_secret(SYNC_RELAY_DEVICE_TOKEN_KEY, device_token)
+            .map_err(|e| format!("Failed to save device sync relay token: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_normalizes_and_validates() {
+        let config = SyncRelayConfig::new(" https://relay.local/ ", " secret ").unwrap();
+        assert_eq!(config.base_url, "https://relay.local");
+        assert_eq!(config.token, "secret");
+
+        assert!(SyncRelayConfig::new("relay.local", "secret").is_err());
+        assert!(SyncRelayConfig::new("https://relay.local", "  ").is_err());
+    }
+}
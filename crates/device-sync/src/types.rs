@@ -132,6 +132,9 @@ pub enum EnrollDeviceResponse {
     Bootstrap {
         device_id: String,
         e2ee_key_version: i32,
+        /// Per-device credential issued by a self-hosted relay
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        device_token: Option<String>,
     },
     /// Device must pair with an existing trusted device to receive root key
     Pair {
@@ -140,15 +143,42 @@ pub enum EnrollDeviceResponse {
         require_sas: bool,
         pairing_ttl_seconds: i32,
         trusted_devices: Vec<TrustedDeviceSummary>,
+        /// Per-device credential issued by a self-hosted relay
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        device_token: Option<String>,
     },
     /// Device is already trusted and ready to sync
     Ready {
         device_id: String,
         e2ee_key_version: i32,
         trust_state: TrustState,
+        /// Per-device credential issued by a self-hosted relay
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        device_token: Option<String>,
     },
 }
 
+impl EnrollDeviceResponse {
+    pub fn device_id(&self) -> &str {
+        match self {
+            Self::Bootstrap { device_id, .. }
+            | Self::Pair { device_id, .. }
+            | Self::Ready { device_id, .. } => device_id,
+        }
+    }
+
+    /// Credential the device sends with every request it makes as itself.
+    /// Only a self-hosted relay issues one; the cloud binds devices to the
+    /// Connect session instead.
+    pub fn device_token(&self) -> Option<&str> {
+        match self {
+            Self::Bootstrap { device_token, .. }
+            | Self::Pair { device_token, .. }
+            | Self::Ready { device_token, .. } => device_token.as_deref(),
+        }
+    }
+}
+
 /// Full device information.
 /// Uses camelCase for frontend serialization with snake_case aliases for API deserialization.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,4 @@
+-- Drop activity duplicate decisions table
+DROP INDEX IF EXISTS idx_activity_duplicate_decisions_other_activity_id;
+DROP INDEX IF EXISTS idx_activity_duplicate_decisions_activity_id;
+DROP TABLE IF EXISTS activity_duplicate_decisions;
@@ -0,0 +1,14 @@
+-- Activity duplicate decisions table
+-- Remembers merge / keep-both decisions from the duplicate review queue so the
+-- same pair of activities is not flagged again.
+
+CREATE TABLE activity_duplicate_decisions (
+    pair_key TEXT PRIMARY KEY NOT NULL,
+    activity_id TEXT NOT NULL,
+    other_activity_id TEXT NOT NULL,
+    decision TEXT NOT NULL,
+    decided_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_activity_duplicate_decisions_activity_id ON activity_duplicate_decisions(activity_id);
+CREATE INDEX idx_activity_duplicate_decisions_other_activity_id ON activity_duplicate_decisions(other_activity_id);
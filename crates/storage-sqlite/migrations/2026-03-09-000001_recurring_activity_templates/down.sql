@@ -0,0 +1,3 @@
+-- Drop recurring activity templates table
+DROP INDEX IF EXISTS idx_recurring_activity_templates_account_id;
+DROP TABLE IF EXISTS recurring_activity_templates;
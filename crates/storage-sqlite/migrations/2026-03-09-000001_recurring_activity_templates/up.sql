@@ -0,0 +1,32 @@
+-- Recurring activity templates
+-- Schedules (RRULE-style) from which activities are generated when due.
+-- last_generated_date tracks the latest nominal occurrence already generated;
+-- generated activities also carry an idempotency key per occurrence.
+
+CREATE TABLE recurring_activity_templates (
+    id TEXT PRIMARY KEY NOT NULL,
+    account_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    activity_type TEXT NOT NULL,
+    subtype TEXT,
+    asset_id TEXT,
+    quantity TEXT,
+    unit_price TEXT,
+    amount TEXT,
+    amount_formula TEXT,
+    fee TEXT,
+    currency TEXT NOT NULL,
+    notes TEXT,
+    rrule TEXT NOT NULL,
+    start_date TEXT NOT NULL,
+    end_date TEXT,
+    business_day_adjustment TEXT NOT NULL DEFAULT 'NONE',
+    generate_status TEXT NOT NULL DEFAULT 'POSTED',
+    is_active BOOLEAN NOT NULL DEFAULT 1,
+    last_generated_date TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_recurring_activity_templates_account_id ON recurring_activity_templates(account_id);
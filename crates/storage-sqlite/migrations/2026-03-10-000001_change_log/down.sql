@@ -0,0 +1,4 @@
+-- Drop change log table
+DROP INDEX IF EXISTS idx_change_log_source_ref;
+DROP INDEX IF EXISTS idx_change_log_entity;
+DROP TABLE IF EXISTS change_log;
@@ -0,0 +1,20 @@
+-- Append-only change log for activities and assets
+-- Stores the entity state before and after each create/update/delete along
+-- with what caused it. source_ref holds the import run id for imports and
+-- broker syncs, or the undone change id for rollbacks.
+
+CREATE TABLE change_log (
+    id TEXT PRIMARY KEY NOT NULL,
+    entity_type TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    operation TEXT NOT NULL,
+    source TEXT NOT NULL,
+    source_ref TEXT,
+    account_id TEXT,
+    before_json TEXT,
+    after_json TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_change_log_entity ON change_log(entity_type, entity_id);
+CREATE INDEX idx_change_log_source_ref ON change_log(source_ref);
@@ -0,0 +1,4 @@
+-- Drop attachments table
+DROP INDEX IF EXISTS idx_attachments_content_hash;
+DROP INDEX IF EXISTS idx_attachments_entity;
+DROP TABLE IF EXISTS attachments;
@@ -0,0 +1,19 @@
+-- File attachments for activities, assets and accounts
+-- Metadata only; contents live in the content-addressed, encrypted file store
+-- under <app data>/attachments, keyed by content_hash (SHA-256 hex).
+-- entity_id is polymorphic (see entity_type), so there is no foreign key.
+
+CREATE TABLE attachments (
+    id TEXT PRIMARY KEY NOT NULL,
+    entity_type TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    content_type TEXT,
+    size_bytes BIGINT NOT NULL,
+    content_hash TEXT NOT NULL,
+    notes TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_attachments_entity ON attachments(entity_type, entity_id);
+CREATE INDEX idx_attachments_content_hash ON attachments(content_hash);
@@ -0,0 +1,4 @@
+-- Drop field-level merge tables
+DROP INDEX IF EXISTS ix_sync_field_conflicts_entity;
+DROP TABLE IF EXISTS sync_field_conflicts;
+DROP TABLE IF EXISTS sync_field_versions;
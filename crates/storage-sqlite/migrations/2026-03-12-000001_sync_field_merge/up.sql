@@ -0,0 +1,33 @@
+-- Field-level merge state for device sync
+-- sync_field_versions holds the last known value and change timestamp of each
+-- synced field; sync_field_conflicts records fields changed on two devices from
+-- the same base until the user picks a value.
+
+CREATE TABLE sync_field_versions (
+    entity TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    field TEXT NOT NULL,
+    value TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    event_id TEXT NOT NULL,
+    PRIMARY KEY (entity, entity_id, field)
+);
+
+CREATE TABLE sync_field_conflicts (
+    id TEXT PRIMARY KEY NOT NULL,
+    entity TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    field TEXT NOT NULL,
+    local_value TEXT NOT NULL,
+    local_updated_at TEXT NOT NULL,
+    remote_value TEXT NOT NULL,
+    remote_updated_at TEXT NOT NULL,
+    remote_event_id TEXT NOT NULL,
+    resolution TEXT,
+    resolved_value TEXT,
+    created_at TEXT NOT NULL,
+    resolved_at TEXT
+);
+
+CREATE INDEX ix_sync_field_conflicts_entity
+    ON sync_field_conflicts(entity, entity_id, field);
@@ -0,0 +1,4 @@
+-- Drop holdings drift checks
+DROP INDEX IF EXISTS idx_holdings_drift_checks_status;
+DROP INDEX IF EXISTS idx_holdings_drift_checks_account_id;
+DROP TABLE IF EXISTS holdings_drift_checks;
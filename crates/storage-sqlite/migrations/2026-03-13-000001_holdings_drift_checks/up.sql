@@ -0,0 +1,22 @@
+-- Holdings drift checks
+-- Each broker sync records the holdings the broker reported (statement, JSON)
+-- as a PENDING check. Once the account's snapshots are recalculated the check is
+-- reconciled against computed holdings and the report (JSON) is kept as
+-- discrepancy history. Older pending checks for the same account are SUPERSEDED.
+
+CREATE TABLE holdings_drift_checks (
+    id TEXT PRIMARY KEY NOT NULL,
+    account_id TEXT NOT NULL,
+    as_of_date TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'PENDING',
+    statement TEXT NOT NULL,
+    report TEXT,
+    discrepancy_count INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    checked_at TIMESTAMP,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_holdings_drift_checks_account_id ON holdings_drift_checks(account_id, created_at);
+CREATE INDEX idx_holdings_drift_checks_status ON holdings_drift_checks(status);
@@ -0,0 +1,5 @@
+-- Drop staged activities and rejected keys
+DROP TABLE IF EXISTS rejected_activity_keys;
+DROP INDEX IF EXISTS idx_staged_activities_account_status;
+DROP INDEX IF EXISTS idx_staged_activities_import_run_id;
+DROP TABLE IF EXISTS staged_activities;
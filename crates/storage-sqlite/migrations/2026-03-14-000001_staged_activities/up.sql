@@ -0,0 +1,33 @@
+-- Staged activities
+-- Import runs with a review mode other than NEVER write their activities here
+-- (payload as ActivityUpsert JSON) instead of the ledger. Rows are applied,
+-- edited or rejected from the review screen.
+
+CREATE TABLE staged_activities (
+    id TEXT PRIMARY KEY NOT NULL,
+    import_run_id TEXT NOT NULL,
+    account_id TEXT NOT NULL,
+    idempotency_key TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'PENDING',
+    edited INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (import_run_id) REFERENCES import_runs(id) ON DELETE CASCADE,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_staged_activities_import_run_id ON staged_activities(import_run_id);
+CREATE INDEX idx_staged_activities_account_status ON staged_activities(account_id, status);
+
+-- Rejected activity keys
+-- Idempotency keys rejected during review, so later syncs skip the same rows.
+
+CREATE TABLE rejected_activity_keys (
+    account_id TEXT NOT NULL,
+    idempotency_key TEXT NOT NULL,
+    import_run_id TEXT NOT NULL,
+    rejected_at TEXT NOT NULL,
+    PRIMARY KEY (account_id, idempotency_key),
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+-- Drop AI thread summaries
+ALTER TABLE ai_threads DROP COLUMN summary_json;
@@ -0,0 +1,4 @@
+-- Rolling summary of older turns for long AI chat threads (JSON: content,
+-- throughMessageId, updatedAt). Turns up to throughMessageId are replaced by the
+-- summary when the thread history no longer fits the model's context window.
+ALTER TABLE ai_threads ADD COLUMN summary_json TEXT;
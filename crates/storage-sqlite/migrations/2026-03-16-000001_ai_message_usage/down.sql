@@ -0,0 +1,6 @@
+-- Drop AI message token usage
+DROP INDEX IF EXISTS idx_ai_messages_created_at;
+ALTER TABLE ai_messages DROP COLUMN completion_tokens;
+ALTER TABLE ai_messages DROP COLUMN prompt_tokens;
+ALTER TABLE ai_messages DROP COLUMN model_id;
+ALTER TABLE ai_messages DROP COLUMN provider_id;
@@ -0,0 +1,9 @@
+-- Token usage reported by the provider for assistant messages. Provider and
+-- model are recorded per message so cost can be estimated against the pricing
+-- of the model that actually produced the reply.
+ALTER TABLE ai_messages ADD COLUMN provider_id TEXT;
+ALTER TABLE ai_messages ADD COLUMN model_id TEXT;
+ALTER TABLE ai_messages ADD COLUMN prompt_tokens INTEGER;
+ALTER TABLE ai_messages ADD COLUMN completion_tokens INTEGER;
+
+CREATE INDEX idx_ai_messages_created_at ON ai_messages(created_at);
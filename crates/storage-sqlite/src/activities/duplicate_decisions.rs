@@ -0,0 +1,71 @@
+//! Repository for remembered duplicate review decisions.
+
+use async_trait::async_trait;
+use diesel::prelude::*;
+use diesel::r2d2::{self, Pool};
+use diesel::SqliteConnection;
+use std::sync::Arc;
+
+use wealthfolio_core::activities::{DuplicateDecisionRepositoryTrait, DuplicatePairDecision};
+use wealthfolio_core::Result;
+
+use super::model::ActivityDuplicateDecisionDB;
+use crate::db::{get_connection, WriteHandle};
+use crate::errors::StorageError;
+use crate::schema::activity_duplicate_decisions;
+use crate::schema::activity_duplicate_decisions::dsl::*;
+
+pub struct DuplicateDecisionRepository {
+    pool: Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
+    writer: WriteHandle,
+}
+
+impl DuplicateDecisionRepository {
+    pub fn new(
+        pool: Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
+        writer: WriteHandle,
+    ) -> Self {
+        Self { pool, writer }
+    }
+}
+
+#[async_trait]
+impl DuplicateDecisionRepositoryTrait for DuplicateDecisionRepository {
+    fn get_decisions(&self) -> Result<Vec<DuplicatePairDecision>> {
+        let mut conn = get_connection(&self.pool)?;
+        let rows = activity_duplicate_decisions
+            .load::<ActivityDuplicateDecisionDB>(&mut conn)
+            .map_err(StorageError::from)?;
+        Ok(rows
+            .into_iter()
+            .filter_map(ActivityDuplicateDecisionDB::into_domain)
+            .collect())
+    }
+
+    fn get_decision(&self, key: &str) -> Result<Option<DuplicatePairDecision>> {
+        let mut conn = get_connection(&self.pool)?;
+        let row = activity_duplicate_decisions
+            .find(key)
+            .first::<ActivityDuplicateDecisionDB>(&mut conn)
+            .optional()
+            .map_err(StorageError::from)?;
+        Ok(row.and_then(ActivityDuplicateDecisionDB::into_domain))
+    }
+
+    async fn save_decision(&self, decision_record: DuplicatePairDecision) -> Result<()> {
+        let row: ActivityDuplicateDecisionDB = decision_record.into();
+
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<()> {
+                diesel::insert_into(activity_duplicate_decisions::table)
+                    .values(&row)
+                    .on_conflict(pair_key)
+                    .do_update()
+                    .set(&row)
+                    .execute(conn)
+                    .map_err(StorageError::from)?;
+                Ok(())
+            })
+            .await
+    }
+}
@@ -1,7 +1,14 @@
 //! SQLite storage implementation for activities.
 
+mod duplicate_decisions;
 mod model;
 mod repository;
+mod staged_activities;
 
-pub use model::{ActivityDB, ActivityDetailsDB, ImportMappingDB, IncomeDataDB};
+pub use duplicate_decisions::DuplicateDecisionRepository;
+pub use model::{
+    ActivityDB, ActivityDetailsDB, ActivityDuplicateDecisionDB, ImportMappingDB, IncomeDataDB,
+    RejectedActivityKeyDB, StagedActivityDB,
+};
 pub use repository::ActivityRepository;
+pub use staged_activities::StagedActivityRepository;
@@ -7,7 +7,8 @@ use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
 use wealthfolio_core::activities::{
-    Activity, ActivityStatus, ActivityUpdate, ActivityUpsert, NewActivity,
+    Activity, ActivityStatus, ActivityUpdate, ActivityUpsert, DuplicateDecision,
+    DuplicatePairDecision, NewActivity, RejectedActivityKey, StagedActivity, StagedActivityStatus,
 };
 
 /// Helper function to parse a string into a Decimal,
@@ -675,3 +676,156 @@ impl From<ActivityUpsert> for ActivityDB {
         }
     }
 }
+
+/// Database model for remembered duplicate review decisions
+#[derive(
+    Queryable,
+    Identifiable,
+    Insertable,
+    AsChangeset,
+    Selectable,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+)]
+#[diesel(table_name = crate::schema::activity_duplicate_decisions)]
+#[diesel(primary_key(pair_key))]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct ActivityDuplicateDecisionDB {
+    pub pair_key: String,
+    pub activity_id: String,
+    pub other_activity_id: String,
+    pub decision: String,
+    pub decided_at: String,
+}
+
+impl ActivityDuplicateDecisionDB {
+    /// Converts to the domain model, skipping rows with an unknown decision.
+    pub fn into_domain(self) -> Option<DuplicatePairDecision> {
+        Some(DuplicatePairDecision {
+            decision: DuplicateDecision::parse(&self.decision)?,
+            pair_key: self.pair_key,
+            activity_id: self.activity_id,
+            other_activity_id: self.other_activity_id,
+            decided_at: chrono::DateTime::parse_from_rfc3339(&self.decided_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        })
+    }
+}
+
+impl From<DuplicatePairDecision> for ActivityDuplicateDecisionDB {
+    fn from(domain: DuplicatePairDecision) -> Self {
+        Self {
+            pair_key: domain.pair_key,
+            activity_id: domain.activity_id,
+            other_activity_id: domain.other_activity_id,
+            decision: domain.decision.as_str().to_string(),
+            decided_at: domain.decided_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Database model for activities staged on an import run for review
+#[derive(
+    Queryable,
+    Identifiable,
+    Insertable,
+    AsChangeset,
+    Selectable,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+)]
+#[diesel(table_name = crate::schema::staged_activities)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct StagedActivityDB {
+    pub id: String,
+    pub import_run_id: String,
+    pub account_id: String,
+    pub idempotency_key: String,
+    /// `ActivityUpsert` serialized as JSON
+    pub payload: String,
+    pub status: String,
+    pub edited: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+fn parse_timestamp(value: &str) -> chrono::DateTime<Utc> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+impl StagedActivityDB {
+    /// Converts to the domain model, skipping rows with an unknown status or
+    /// an unreadable payload.
+    pub fn into_domain(self) -> Option<StagedActivity> {
+        let activity = match serde_json::from_str(&self.payload) {
+            Ok(activity) => activity,
+            Err(e) => {
+                log::warn!("Skipping staged activity {}: {}", self.id, e);
+                return None;
+            }
+        };
+        Some(StagedActivity {
+            status: StagedActivityStatus::parse(&self.status)?,
+            id: self.id,
+            import_run_id: self.import_run_id,
+            account_id: self.account_id,
+            idempotency_key: self.idempotency_key,
+            activity,
+            edited: self.edited != 0,
+            created_at: parse_timestamp(&self.created_at),
+            updated_at: parse_timestamp(&self.updated_at),
+        })
+    }
+
+    /// Serializes a domain row for storage.
+    pub fn from_domain(domain: StagedActivity) -> wealthfolio_core::Result<Self> {
+        let payload = serde_json::to_string(&domain.activity).map_err(|e| {
+            wealthfolio_core::Error::Unexpected(format!(
+                "Failed to serialize staged activity: {}",
+                e
+            ))
+        })?;
+        Ok(Self {
+            payload,
+            id: domain.id,
+            import_run_id: domain.import_run_id,
+            account_id: domain.account_id,
+            idempotency_key: domain.idempotency_key,
+            status: domain.status.as_str().to_string(),
+            edited: domain.edited as i32,
+            created_at: domain.created_at.to_rfc3339(),
+            updated_at: domain.updated_at.to_rfc3339(),
+        })
+    }
+}
+
+/// Database model for idempotency keys rejected during import review
+#[derive(Queryable, Insertable, Selectable, PartialEq, Debug, Clone)]
+#[diesel(table_name = crate::schema::rejected_activity_keys)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct RejectedActivityKeyDB {
+    pub account_id: String,
+    pub idempotency_key: String,
+    pub import_run_id: String,
+    pub rejected_at: String,
+}
+
+impl From<RejectedActivityKey> for RejectedActivityKeyDB {
+    fn from(domain: RejectedActivityKey) -> Self {
+        Self {
+            account_id: domain.account_id,
+            idempotency_key: domain.idempotency_key,
+            import_run_id: domain.import_run_id,
+            rejected_at: domain.rejected_at.to_rfc3339(),
+        }
+    }
+}
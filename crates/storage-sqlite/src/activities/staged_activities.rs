@@ -0,0 +1,132 @@
+//! Repository for activities staged on import runs awaiting review.
+
+use async_trait::async_trait;
+use diesel::prelude::*;
+use diesel::r2d2::{self, Pool};
+use diesel::SqliteConnection;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use wealthfolio_core::activities::{
+    RejectedActivityKey, StagedActivity, StagedActivityRepositoryTrait, StagedActivityStatus,
+};
+use wealthfolio_core::Result;
+
+use super::model::{RejectedActivityKeyDB, StagedActivityDB};
+use crate::db::{get_connection, WriteHandle};
+use crate::errors::StorageError;
+use crate::schema::{rejected_activity_keys, staged_activities};
+
+pub struct StagedActivityRepository {
+    pool: Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
+    writer: WriteHandle,
+}
+
+impl StagedActivityRepository {
+    pub fn new(
+        pool: Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
+        writer: WriteHandle,
+    ) -> Self {
+        Self { pool, writer }
+    }
+}
+
+#[async_trait]
+impl StagedActivityRepositoryTrait for StagedActivityRepository {
+    async fn insert_staged(&self, rows: Vec<StagedActivity>) -> Result<usize> {
+        let rows = rows
+            .into_iter()
+            .map(StagedActivityDB::from_domain)
+            .collect::<Result<Vec<_>>>()?;
+
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<usize> {
+                let inserted = diesel::insert_into(staged_activities::table)
+                    .values(&rows)
+                    .execute(conn)
+                    .map_err(StorageError::from)?;
+                Ok(inserted)
+            })
+            .await
+    }
+
+    fn get_staged(&self, staged_id: &str) -> Result<Option<StagedActivity>> {
+        let mut conn = get_connection(&self.pool)?;
+        let row = staged_activities::table
+            .find(staged_id)
+            .first::<StagedActivityDB>(&mut conn)
+            .optional()
+            .map_err(StorageError::from)?;
+        Ok(row.and_then(StagedActivityDB::into_domain))
+    }
+
+    fn list_for_run(&self, run_id: &str) -> Result<Vec<StagedActivity>> {
+        let mut conn = get_connection(&self.pool)?;
+        let rows = staged_activities::table
+            .filter(staged_activities::import_run_id.eq(run_id))
+            .order(staged_activities::created_at.asc())
+            .load::<StagedActivityDB>(&mut conn)
+            .map_err(StorageError::from)?;
+        Ok(rows
+            .into_iter()
+            .filter_map(StagedActivityDB::into_domain)
+            .collect())
+    }
+
+    async fn update_staged(&self, rows: Vec<StagedActivity>) -> Result<()> {
+        let rows = rows
+            .into_iter()
+            .map(StagedActivityDB::from_domain)
+            .collect::<Result<Vec<_>>>()?;
+
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<()> {
+                for row in &rows {
+                    diesel::update(staged_activities::table.find(&row.id))
+                        .set(row)
+                        .execute(conn)
+                        .map_err(StorageError::from)?;
+                }
+                Ok(())
+            })
+            .await
+    }
+
+    fn get_pending_keys(&self, account: &str) -> Result<HashSet<String>> {
+        let mut conn = get_connection(&self.pool)?;
+        let keys = staged_activities::table
+            .filter(staged_activities::account_id.eq(account))
+            .filter(staged_activities::status.eq_any([
+                StagedActivityStatus::Pending.as_str(),
+                StagedActivityStatus::Accepted.as_str(),
+            ]))
+            .select(staged_activities::idempotency_key)
+            .load::<String>(&mut conn)
+            .map_err(StorageError::from)?;
+        Ok(keys.into_iter().collect())
+    }
+
+    fn get_rejected_keys(&self, account: &str) -> Result<HashSet<String>> {
+        let mut conn = get_connection(&self.pool)?;
+        let keys = rejected_activity_keys::table
+            .filter(rejected_activity_keys::account_id.eq(account))
+            .select(rejected_activity_keys::idempotency_key)
+            .load::<String>(&mut conn)
+            .map_err(StorageError::from)?;
+        Ok(keys.into_iter().collect())
+    }
+
+    async fn save_rejected_keys(&self, keys: Vec<RejectedActivityKey>) -> Result<()> {
+        let rows: Vec<RejectedActivityKeyDB> = keys.into_iter().map(Into::into).collect();
+
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<()> {
+                diesel::insert_or_ignore_into(rejected_activity_keys::table)
+                    .values(&rows)
+                    .execute(conn)
+                    .map_err(StorageError::from)?;
+                Ok(())
+            })
+            .await
+    }
+}
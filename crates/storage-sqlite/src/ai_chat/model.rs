@@ -46,6 +46,8 @@ pub struct AiThreadDB {
     pub config_snapshot: Option<String>,
     /// Whether the thread is pinned to the top of the list.
     pub is_pinned: i32,
+    /// JSON blob containing the rolling summary of older turns.
+    pub summary_json: Option<String>,
 }
 
 /// Database model for AI chat messages.
@@ -68,6 +70,14 @@ pub struct AiMessageDB {
     pub role: String,
     pub content_json: String,
     pub created_at: String,
+    /// Provider that produced the message (assistant messages with usage only).
+    pub provider_id: Option<String>,
+    /// Model that produced the message (assistant messages with usage only).
+    pub model_id: Option<String>,
+    /// Prompt tokens reported by the provider for this reply.
+    pub prompt_tokens: Option<i32>,
+    /// Completion tokens reported by the provider for this reply.
+    pub completion_tokens: Option<i32>,
 }
 
 /// Database model for thread tags.
@@ -294,6 +304,7 @@ impl AiThreadDB {
             updated_at: now,
             config_snapshot: None,
             is_pinned: 0,
+            summary_json: None,
         }
     }
 
@@ -307,6 +318,7 @@ impl AiThreadDB {
             updated_at: now,
             config_snapshot,
             is_pinned: 0,
+            summary_json: None,
         }
     }
 }
@@ -324,6 +336,10 @@ impl AiMessageDB {
             role,
             content_json,
             created_at: Utc::now().to_rfc3339(),
+            provider_id: None,
+            model_id: None,
+            prompt_tokens: None,
+            completion_tokens: None,
         }
     }
 
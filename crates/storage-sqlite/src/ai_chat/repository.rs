@@ -13,7 +13,7 @@ use std::sync::Arc;
 use wealthfolio_ai::{
     AiError, ChatMessage, ChatMessageContent, ChatMessagePart, ChatMessageRole,
     ChatRepositoryResult, ChatRepositoryTrait, ChatThread, ChatThreadConfig, ListThreadsRequest,
-    ThreadPage, CHAT_MAX_CONTENT_SIZE_BYTES,
+    MessageUsage, ThreadPage, ThreadSummary, UsageRecord, CHAT_MAX_CONTENT_SIZE_BYTES,
 };
 use wealthfolio_core::errors::{DatabaseError, ValidationError};
 use wealthfolio_core::{Error as CoreError, Result as CoreResult};
@@ -226,6 +226,10 @@ impl ChatRepositoryTrait for AiChatRepository {
             .config
             .as_ref()
             .and_then(|c| serde_json::to_string(c).ok());
+        let summary_json = thread
+            .summary
+            .as_ref()
+            .and_then(|s| serde_json::to_string(s).ok());
         let updated_at = Utc::now().to_rfc3339();
 
         self.writer
@@ -235,6 +239,7 @@ impl ChatRepositoryTrait for AiChatRepository {
                         ai_threads::title.eq(&title),
                         ai_threads::is_pinned.eq(&is_pinned),
                         ai_threads::config_snapshot.eq(&config_snapshot),
+                        ai_threads::summary_json.eq(&summary_json),
                         ai_threads::updated_at.eq(&updated_at),
                     ))
                     .execute(tx.conn())
@@ -356,13 +361,20 @@ impl ChatRepositoryTrait for AiChatRepository {
     }
 
     async fn update_message(&self, message: ChatMessage) -> ChatRepositoryResult<ChatMessage> {
-        let message_id = message.id.clone();
-        let content_json = convert_content_to_json(&message.content)?;
+        let message_db = message_to_db(&message)?;
+        let message_id = message_db.id.clone();
 
         self.writer
             .exec_tx(move |tx| -> CoreResult<ChatMessage> {
+                // Content and usage are mutable; role, thread and timestamp are not
                 diesel::update(ai_messages::table.find(&message_id))
-                    .set(ai_messages::content_json.eq(&content_json))
+                    .set((
+                        ai_messages::content_json.eq(&message_db.content_json),
+                        ai_messages::provider_id.eq(&message_db.provider_id),
+                        ai_messages::model_id.eq(&message_db.model_id),
+                        ai_messages::prompt_tokens.eq(message_db.prompt_tokens),
+                        ai_messages::completion_tokens.eq(message_db.completion_tokens),
+                    ))
                     .execute(tx.conn())
                     .map_err(|e| CoreError::Database(DatabaseError::QueryFailed(e.to_string())))?;
 
@@ -455,6 +467,43 @@ impl ChatRepositoryTrait for AiChatRepository {
                 )))
             })
     }
+
+    // ========================================================================
+    // Usage Operations
+    // ========================================================================
+
+    fn list_usage(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> ChatRepositoryResult<Vec<UsageRecord>> {
+        let mut conn = get_connection(&self.pool).map_err(core_to_ai_error)?;
+
+        // created_at is stored as RFC 3339 UTC, so string bounds compare chronologically
+        let messages_db = ai_messages::table
+            .filter(ai_messages::prompt_tokens.is_not_null())
+            .filter(ai_messages::created_at.ge(from.to_rfc3339()))
+            .filter(ai_messages::created_at.lt(to.to_rfc3339()))
+            .order(ai_messages::created_at.asc())
+            .load::<AiMessageDB>(&mut conn)
+            .map_err(|e| {
+                AiError::Core(CoreError::Database(DatabaseError::QueryFailed(
+                    e.to_string(),
+                )))
+            })?;
+
+        Ok(messages_db
+            .iter()
+            .filter_map(|db| {
+                db_to_usage(db).map(|usage| UsageRecord {
+                    message_id: db.id.clone(),
+                    thread_id: db.thread_id.clone(),
+                    created_at: parse_timestamp(&db.created_at),
+                    usage,
+                })
+            })
+            .collect())
+    }
 }
 
 // ============================================================================
@@ -494,6 +543,10 @@ fn thread_to_db(thread: &ChatThread) -> AiThreadDB {
         .config
         .as_ref()
         .and_then(|c| serde_json::to_string(c).ok());
+    let summary_json = thread
+        .summary
+        .as_ref()
+        .and_then(|s| serde_json::to_string(s).ok());
 
     AiThreadDB {
         id: thread.id.clone(),
@@ -502,6 +555,7 @@ fn thread_to_db(thread: &ChatThread) -> AiThreadDB {
         updated_at: thread.updated_at.to_rfc3339(),
         config_snapshot,
         is_pinned: if thread.is_pinned { 1 } else { 0 },
+        summary_json,
     }
 }
 
@@ -511,6 +565,10 @@ fn db_to_thread(db: &AiThreadDB) -> ChatThread {
         .config_snapshot
         .as_ref()
         .and_then(|json| serde_json::from_str::<ChatThreadConfig>(json).ok());
+    let summary = db
+        .summary_json
+        .as_ref()
+        .and_then(|json| serde_json::from_str::<ThreadSummary>(json).ok());
 
     ChatThread {
         id: db.id.clone(),
@@ -518,6 +576,7 @@ fn db_to_thread(db: &AiThreadDB) -> ChatThread {
         is_pinned: db.is_pinned != 0,
         tags: Vec::new(), // Tags are loaded separately
         config,
+        summary,
         created_at: DateTime::parse_from_rfc3339(&db.created_at)
             .map(|dt| dt.with_timezone(&Utc))
             .unwrap_or_else(|_| Utc::now()),
@@ -536,6 +595,13 @@ fn message_to_db(msg: &ChatMessage) -> ChatRepositoryResult<AiMessageDB> {
         role: msg.role.to_string(),
         content_json,
         created_at: msg.created_at.to_rfc3339(),
+        provider_id: msg.usage.as_ref().map(|u| u.provider_id.clone()),
+        model_id: msg.usage.as_ref().map(|u| u.model_id.clone()),
+        prompt_tokens: msg.usage.as_ref().map(|u| clamp_tokens(u.prompt_tokens)),
+        completion_tokens: msg
+            .usage
+            .as_ref()
+            .map(|u| clamp_tokens(u.completion_tokens)),
     })
 }
 
@@ -551,12 +617,31 @@ fn db_to_message(db: &AiMessageDB) -> ChatRepositoryResult<ChatMessage> {
         thread_id: db.thread_id.clone(),
         role,
         content,
-        created_at: DateTime::parse_from_rfc3339(&db.created_at)
-            .map(|dt| dt.with_timezone(&Utc))
-            .unwrap_or_else(|_| Utc::now()),
+        created_at: parse_timestamp(&db.created_at),
+        usage: db_to_usage(db),
     })
 }
 
+/// Usage columns are all set together; a row missing any of them has no usage.
+fn db_to_usage(db: &AiMessageDB) -> Option<MessageUsage> {
+    Some(MessageUsage {
+        provider_id: db.provider_id.clone()?,
+        model_id: db.model_id.clone()?,
+        prompt_tokens: u32::try_from(db.prompt_tokens?).unwrap_or(0),
+        completion_tokens: u32::try_from(db.completion_tokens?).unwrap_or(0),
+    })
+}
+
+fn clamp_tokens(tokens: u32) -> i32 {
+    i32::try_from(tokens).unwrap_or(i32::MAX)
+}
+
+fn parse_timestamp(value: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
 /// Convert ChatMessageContent to JSON string for storage.
 fn convert_content_to_json(content: &ChatMessageContent) -> ChatRepositoryResult<String> {
     // Convert core parts to storage parts
@@ -716,6 +801,22 @@ mod tests {
         assert_eq!(back.title, Some("Pinned Thread".to_string()));
     }
 
+    #[test]
+    fn test_thread_conversion_summary() {
+        let mut thread = ChatThread::new();
+        thread.summary = Some(ThreadSummary {
+            content: "User asked about rebalancing.".to_string(),
+            through_message_id: "msg-42".to_string(),
+            updated_at: Utc::now(),
+        });
+
+        let db = thread_to_db(&thread);
+        assert!(db.summary_json.is_some());
+
+        let back = db_to_thread(&db);
+        assert_eq!(back.summary, thread.summary);
+    }
+
     #[test]
     fn test_thread_conversion_timestamps() {
         let thread = ChatThread::new();
@@ -747,6 +848,26 @@ mod tests {
         assert_eq!(msg.thread_id, back.thread_id);
         assert_eq!(msg.role, back.role);
         assert_eq!(msg.content.parts.len(), back.content.parts.len());
+        assert!(db.prompt_tokens.is_none());
+        assert!(back.usage.is_none());
+    }
+
+    #[test]
+    fn test_message_conversion_usage() {
+        let mut msg = ChatMessage::assistant("thread-1");
+        msg.usage = Some(MessageUsage {
+            provider_id: "openai".to_string(),
+            model_id: "gpt-5-mini".to_string(),
+            prompt_tokens: 2_400,
+            completion_tokens: 180,
+        });
+
+        let db = message_to_db(&msg).unwrap();
+        assert_eq!(db.model_id.as_deref(), Some("gpt-5-mini"));
+        assert_eq!(db.prompt_tokens, Some(2_400));
+
+        let back = db_to_message(&db).unwrap();
+        assert_eq!(back.usage, msg.usage);
     }
 
     #[test]
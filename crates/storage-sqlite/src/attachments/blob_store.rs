@@ -0,0 +1,328 @@
+//! Content-addressed, encrypted file store for attachment contents.
+//!
+//! Each file is stored once under `<root>/<hash[0..2]>/<hash>` as
+//! `MAGIC || nonce || ciphertext` using ChaCha20-Poly1305 with the content
+//! hash as associated data, so a blob cannot be swapped for another one.
+//!
+//! Backups and sync snapshots are plain SQLite files, so the contents they
+//! need are packed into an [`ATTACHMENT_ARCHIVE_TABLE`] inside the file and
+//! unpacked (and re-encrypted with the local key) on restore.
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use log::warn;
+use rand::RngCore;
+use rusqlite::{params, Connection as RusqliteConnection, OptionalExtension};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use wealthfolio_core::attachments::{content_hash, AttachmentBlobStoreTrait};
+use wealthfolio_core::errors::{DatabaseError, Error, Result};
+
+/// Table holding plaintext attachment contents inside backup and snapshot
+/// files. Never present in the live database after a restore completes.
+pub const ATTACHMENT_ARCHIVE_TABLE: &str = "attachment_archive";
+
+const MAGIC: &[u8; 4] = b"WFA1";
+const NONCE_LEN: usize = 12;
+
+fn storage_error(context: &str, err: impl std::fmt::Display) -> Error {
+    Error::Database(DatabaseError::Internal(format!("{}: {}", context, err)))
+}
+
+fn is_valid_hash(hash: &str) -> bool {
+    hash.len() == 64 && hash.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+pub struct FileAttachmentStore {
+    root: PathBuf,
+    cipher: ChaCha20Poly1305,
+}
+
+impl FileAttachmentStore {
+    /// Creates a store rooted at `<app_data_dir>/attachments`.
+    pub fn new(app_data_dir: &str, key: [u8; 32]) -> Self {
+        Self {
+            root: Path::new(app_data_dir).join("attachments"),
+            cipher: ChaCha20Poly1305::new(Key::from_slice(&key)),
+        }
+    }
+
+    fn blob_path(&self, hash: &str) -> Result<PathBuf> {
+        if !is_valid_hash(hash) {
+            return Err(Error::Unexpected(format!(
+                "Invalid attachment content hash '{}'",
+                hash
+            )));
+        }
+        Ok(self.root.join(&hash[..2]).join(hash))
+    }
+
+    fn encrypt(&self, hash: &str, data: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: data,
+                    aad: hash.as_bytes(),
+                },
+            )
+            .map_err(|_| Error::Unexpected("Attachment encryption failed".to_string()))?;
+
+        let mut out = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    fn decrypt(&self, hash: &str, stored: &[u8]) -> Result<Vec<u8>> {
+        let header_len = MAGIC.len() + NONCE_LEN;
+        if stored.len() < header_len || &stored[..MAGIC.len()] != MAGIC {
+            return Err(Error::Unexpected(format!(
+                "Attachment file {} has an unknown format",
+                hash
+            )));
+        }
+        self.cipher
+            .decrypt(
+                Nonce::from_slice(&stored[MAGIC.len()..header_len]),
+                Payload {
+                    msg: &stored[header_len..],
+                    aad: hash.as_bytes(),
+                },
+            )
+            .map_err(|_| {
+                Error::Unexpected(format!("Attachment file {} could not be decrypted", hash))
+            })
+    }
+
+    /// Copies the contents of every attachment referenced by the `attachments`
+    /// table of `db_file` into its archive table. Returns the number of files
+    /// packed; files missing locally are skipped with a warning.
+    pub fn pack_archive(&self, db_file: &str) -> Result<usize> {
+        let mut conn = RusqliteConnection::open(db_file)
+            .map_err(|e| storage_error("Failed to open attachment archive", e))?;
+        if !table_exists(&conn, "attachments")? {
+            return Ok(0);
+        }
+
+        let hashes: Vec<String> = {
+            let mut stmt = conn
+                .prepare("SELECT DISTINCT content_hash FROM attachments")
+                .map_err(|e| storage_error("Failed to read attachments", e))?;
+            let rows = stmt
+                .query_map([], |row| row.get::<_, String>(0))
+                .map_err(|e| storage_error("Failed to read attachments", e))?;
+            rows.collect::<std::result::Result<_, _>>()
+                .map_err(|e| storage_error("Failed to read attachments", e))?
+        };
+
+        let tx = conn
+            .transaction()
+            .map_err(|e| storage_error("Failed to pack attachments", e))?;
+        tx.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS {} (content_hash TEXT PRIMARY KEY NOT NULL, data BLOB NOT NULL)",
+            ATTACHMENT_ARCHIVE_TABLE
+        ))
+        .map_err(|e| storage_error("Failed to pack attachments", e))?;
+
+        let mut packed = 0;
+        for hash in hashes {
+            let data = match self.get(&hash) {
+                Ok(data) => data,
+                Err(e) => {
+                    warn!("Skipping attachment {} while packing: {}", hash, e);
+                    continue;
+                }
+            };
+            tx.execute(
+                &format!(
+                    "INSERT OR REPLACE INTO {} (content_hash, data) VALUES (?1, ?2)",
+                    ATTACHMENT_ARCHIVE_TABLE
+                ),
+                params![hash, data],
+            )
+            .map_err(|e| storage_error("Failed to pack attachments", e))?;
+            packed += 1;
+        }
+        tx.commit()
+            .map_err(|e| storage_error("Failed to pack attachments", e))?;
+        Ok(packed)
+    }
+
+    /// Stores every file found in the archive table of `db_file`. With
+    /// `drop_archive`, the table is removed afterwards (used when `db_file`
+    /// is the live database after a restore). Returns the number of files
+    /// added to the store.
+    pub fn unpack_archive(&self, db_file: &str, drop_archive: bool) -> Result<usize> {
+        let conn = RusqliteConnection::open(db_file)
+            .map_err(|e| storage_error("Failed to open attachment archive", e))?;
+        if !table_exists(&conn, ATTACHMENT_ARCHIVE_TABLE)? {
+            return Ok(0);
+        }
+
+        let mut unpacked = 0;
+        {
+            let mut stmt = conn
+                .prepare(&format!(
+                    "SELECT content_hash, data FROM {}",
+                    ATTACHMENT_ARCHIVE_TABLE
+                ))
+                .map_err(|e| storage_error("Failed to read attachment archive", e))?;
+            let mut rows = stmt
+                .query([])
+                .map_err(|e| storage_error("Failed to read attachment archive", e))?;
+            while let Some(row) = rows
+                .next()
+                .map_err(|e| storage_error("Failed to read attachment archive", e))?
+            {
+                let hash: String = row
+                    .get(0)
+                    .map_err(|e| storage_error("Failed to read attachment archive", e))?;
+                let data: Vec<u8> = row
+                    .get(1)
+                    .map_err(|e| storage_error("Failed to read attachment archive", e))?;
+                if content_hash(&data) != hash {
+                    warn!("Skipping corrupt attachment {} in archive", hash);
+                    continue;
+                }
+                if !self.exists(&hash) {
+                    self.put(&hash, &data)?;
+                    unpacked += 1;
+                }
+            }
+        }
+
+        if drop_archive {
+            conn.execute_batch(&format!("DROP TABLE {}", ATTACHMENT_ARCHIVE_TABLE))
+                .map_err(|e| storage_error("Failed to drop attachment archive", e))?;
+        }
+        Ok(unpacked)
+    }
+}
+
+fn table_exists(conn: &RusqliteConnection, table: &str) -> Result<bool> {
+    conn.query_row(
+        "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        params![table],
+        |_| Ok(()),
+    )
+    .optional()
+    .map(|found| found.is_some())
+    .map_err(|e| storage_error("Failed to inspect database", e))
+}
+
+impl AttachmentBlobStoreTrait for FileAttachmentStore {
+    fn exists(&self, hash: &str) -> bool {
+        self.blob_path(hash).map(|p| p.exists()).unwrap_or(false)
+    }
+
+    fn put(&self, hash: &str, data: &[u8]) -> Result<()> {
+        let path = self.blob_path(hash)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| storage_error("Failed to create attachment directory", e))?;
+        }
+        let encrypted = self.encrypt(hash, data)?;
+
+        // Write to a temporary file first so a crash never leaves a partial blob
+        let tmp_path = path.with_extension(format!("{}.tmp", Uuid::new_v4().simple()));
+        fs::write(&tmp_path, encrypted)
+            .map_err(|e| storage_error("Failed to write attachment", e))?;
+        fs::rename(&tmp_path, &path).map_err(|e| {
+            let _ = fs::remove_file(&tmp_path);
+            storage_error("Failed to write attachment", e)
+        })
+    }
+
+    fn get(&self, hash: &str) -> Result<Vec<u8>> {
+        let path = self.blob_path(hash)?;
+        if !path.exists() {
+            return Err(Error::Database(DatabaseError::NotFound(format!(
+                "Attachment file {}",
+                hash
+            ))));
+        }
+        let stored = fs::read(&path).map_err(|e| storage_error("Failed to read attachment", e))?;
+        self.decrypt(hash, &stored)
+    }
+
+    fn delete(&self, hash: &str) -> Result<()> {
+        let path = self.blob_path(hash)?;
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| storage_error("Failed to delete attachment", e))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn store(dir: &Path, key_byte: u8) -> FileAttachmentStore {
+        FileAttachmentStore::new(dir.to_str().unwrap(), [key_byte; 32])
+    }
+
+    #[test]
+    fn blobs_are_encrypted_at_rest_and_round_trip() {
+        let dir = tempdir().unwrap();
+        let store = store(dir.path(), 7);
+        let data = b"contract note for 10 AAPL".to_vec();
+        let hash = content_hash(&data);
+
+        store.put(&hash, &data).unwrap();
+
+        let on_disk = fs::read(store.blob_path(&hash).unwrap()).unwrap();
+        assert!(!on_disk
+            .windows(data.len())
+            .any(|window| window == data.as_slice()));
+        assert_eq!(store.get(&hash).unwrap(), data);
+
+        store.delete(&hash).unwrap();
+        assert!(!store.exists(&hash));
+    }
+
+    #[test]
+    fn rejects_hashes_that_are_not_sha256_hex() {
+        let dir = tempdir().unwrap();
+        let store = store(dir.path(), 7);
+        assert!(store.put("../../etc/passwd", b"x").is_err());
+        assert!(!store.exists("../app.db"));
+    }
+
+    #[test]
+    fn archive_moves_contents_between_stores_with_different_keys() {
+        let dir = tempdir().unwrap();
+        let source = store(&dir.path().join("source"), 1);
+        let target = store(&dir.path().join("target"), 2);
+        let data = b"property deed".to_vec();
+        let hash = content_hash(&data);
+        source.put(&hash, &data).unwrap();
+
+        let db_file = dir.path().join("backup.db");
+        let db_file = db_file.to_str().unwrap();
+        let conn = RusqliteConnection::open(db_file).unwrap();
+        conn.execute_batch("CREATE TABLE attachments (id TEXT, content_hash TEXT)")
+            .unwrap();
+        conn.execute(
+            "INSERT INTO attachments (id, content_hash) VALUES ('a1', ?1)",
+            params![hash],
+        )
+        .unwrap();
+        drop(conn);
+
+        assert_eq!(source.pack_archive(db_file).unwrap(), 1);
+        assert_eq!(target.unpack_archive(db_file, true).unwrap(), 1);
+        assert_eq!(target.get(&hash).unwrap(), data);
+
+        let conn = RusqliteConnection::open(db_file).unwrap();
+        assert!(!table_exists(&conn, ATTACHMENT_ARCHIVE_TABLE).unwrap());
+    }
+}
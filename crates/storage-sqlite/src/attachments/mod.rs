@@ -0,0 +1,12 @@
+//! SQLite storage implementation for attachments.
+//!
+//! Metadata lives in the `attachments` table; file contents are kept by
+//! [`FileAttachmentStore`] under the app data dir.
+
+mod blob_store;
+mod model;
+mod repository;
+
+pub use blob_store::{FileAttachmentStore, ATTACHMENT_ARCHIVE_TABLE};
+pub use model::AttachmentDB;
+pub use repository::AttachmentRepository;
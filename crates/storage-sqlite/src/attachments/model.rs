@@ -0,0 +1,61 @@
+//! Database models for attachments.
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use wealthfolio_core::attachments::{Attachment, AttachmentEntityType};
+
+/// Database model for attachment metadata
+#[derive(
+    Queryable, Identifiable, Insertable, Selectable, PartialEq, Serialize, Deserialize, Debug, Clone,
+)]
+#[diesel(table_name = crate::schema::attachments)]
+#[diesel(primary_key(id))]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentDB {
+    pub id: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub file_name: String,
+    pub content_type: Option<String>,
+    pub size_bytes: i64,
+    pub content_hash: String,
+    pub notes: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+impl AttachmentDB {
+    /// Converts to the domain model, returning None for rows with an
+    /// unknown entity type.
+    pub fn into_domain(self) -> Option<Attachment> {
+        Some(Attachment {
+            entity_type: AttachmentEntityType::parse(&self.entity_type)?,
+            id: self.id,
+            entity_id: self.entity_id,
+            file_name: self.file_name,
+            content_type: self.content_type,
+            size_bytes: self.size_bytes,
+            content_hash: self.content_hash,
+            notes: self.notes,
+            created_at: self.created_at,
+        })
+    }
+}
+
+impl From<Attachment> for AttachmentDB {
+    fn from(domain: Attachment) -> Self {
+        Self {
+            id: domain.id,
+            entity_type: domain.entity_type.as_str().to_string(),
+            entity_id: domain.entity_id,
+            file_name: domain.file_name,
+            content_type: domain.content_type,
+            size_bytes: domain.size_bytes,
+            content_hash: domain.content_hash,
+            notes: domain.notes,
+            created_at: domain.created_at,
+        }
+    }
+}
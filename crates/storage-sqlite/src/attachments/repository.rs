@@ -0,0 +1,98 @@
+//! Repository for attachment metadata.
+
+use async_trait::async_trait;
+use diesel::prelude::*;
+use diesel::r2d2::{self, Pool};
+use diesel::SqliteConnection;
+use std::sync::Arc;
+
+use wealthfolio_core::attachments::{Attachment, AttachmentEntityType, AttachmentRepositoryTrait};
+use wealthfolio_core::errors::Error;
+use wealthfolio_core::Result;
+
+use super::model::AttachmentDB;
+use crate::db::{get_connection, WriteHandle};
+use crate::errors::StorageError;
+use crate::schema::attachments;
+use crate::schema::attachments::dsl::*;
+
+pub struct AttachmentRepository {
+    pool: Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
+    writer: WriteHandle,
+}
+
+impl AttachmentRepository {
+    pub fn new(
+        pool: Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
+        writer: WriteHandle,
+    ) -> Self {
+        Self { pool, writer }
+    }
+}
+
+#[async_trait]
+impl AttachmentRepositoryTrait for AttachmentRepository {
+    fn get_attachment(&self, attachment_id: &str) -> Result<Attachment> {
+        let mut conn = get_connection(&self.pool)?;
+        let row = attachments
+            .find(attachment_id)
+            .first::<AttachmentDB>(&mut conn)
+            .map_err(StorageError::from)?;
+        row.into_domain()
+            .ok_or_else(|| Error::Unexpected(format!("Attachment {} is malformed", attachment_id)))
+    }
+
+    fn list_attachments(
+        &self,
+        kind: AttachmentEntityType,
+        target_id: &str,
+    ) -> Result<Vec<Attachment>> {
+        let mut conn = get_connection(&self.pool)?;
+        let rows = attachments
+            .filter(entity_type.eq(kind.as_str()))
+            .filter(entity_id.eq(target_id))
+            .order(created_at.desc())
+            .load::<AttachmentDB>(&mut conn)
+            .map_err(StorageError::from)?;
+        Ok(rows
+            .into_iter()
+            .filter_map(AttachmentDB::into_domain)
+            .collect())
+    }
+
+    fn count_by_content_hash(&self, hash: &str) -> Result<i64> {
+        let mut conn = get_connection(&self.pool)?;
+        let count = attachments
+            .filter(content_hash.eq(hash))
+            .count()
+            .get_result::<i64>(&mut conn)
+            .map_err(StorageError::from)?;
+        Ok(count)
+    }
+
+    async fn insert_attachment(&self, attachment: Attachment) -> Result<Attachment> {
+        let row = AttachmentDB::from(attachment.clone());
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<()> {
+                diesel::insert_into(attachments::table)
+                    .values(&row)
+                    .execute(conn)
+                    .map_err(StorageError::from)?;
+                Ok(())
+            })
+            .await?;
+        Ok(attachment)
+    }
+
+    async fn delete_attachment(&self, attachment_id: &str) -> Result<usize> {
+        let target_id = attachment_id.to_string();
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<usize> {
+                let deleted = diesel::delete(attachments.find(target_id))
+                    .execute(conn)
+                    .map_err(StorageError::from)?;
+                Ok(deleted)
+            })
+            .await
+    }
+}
@@ -0,0 +1,7 @@
+//! SQLite storage implementation for the activity and asset change log.
+
+mod model;
+mod repository;
+
+pub use model::ChangeLogEntryDB;
+pub use repository::ChangeLogRepository;
@@ -0,0 +1,69 @@
+//! Database models for the change log.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use wealthfolio_core::audit::{
+    ChangeEntityType, ChangeLogEntry, ChangeOperation, ChangeSource, NewChangeLogEntry,
+};
+
+/// Database model for change log entries
+#[derive(
+    Queryable, Identifiable, Insertable, Selectable, PartialEq, Serialize, Deserialize, Debug, Clone,
+)]
+#[diesel(table_name = crate::schema::change_log)]
+#[diesel(primary_key(id))]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeLogEntryDB {
+    pub id: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub operation: String,
+    pub source: String,
+    pub source_ref: Option<String>,
+    pub account_id: Option<String>,
+    pub before_json: Option<String>,
+    pub after_json: Option<String>,
+    pub created_at: String,
+}
+
+impl ChangeLogEntryDB {
+    /// Builds a row for a new entry. Timestamps use a fixed width so that
+    /// ordering by `created_at` is chronological.
+    pub fn from_new(id: String, entry: NewChangeLogEntry, created_at: DateTime<Utc>) -> Self {
+        Self {
+            id,
+            entity_type: entry.entity_type.as_str().to_string(),
+            entity_id: entry.entity_id,
+            operation: entry.operation.as_str().to_string(),
+            source: entry.source.as_str().to_string(),
+            source_ref: entry.source_ref,
+            account_id: entry.account_id,
+            before_json: entry.before.map(|v| v.to_string()),
+            after_json: entry.after.map(|v| v.to_string()),
+            created_at: created_at.to_rfc3339_opts(SecondsFormat::Micros, true),
+        }
+    }
+
+    /// Converts to the domain model, returning None for rows with an
+    /// unknown entity type or operation.
+    pub fn into_domain(self) -> Option<ChangeLogEntry> {
+        let parse_json = |json: Option<String>| json.and_then(|s| serde_json::from_str(&s).ok());
+        Some(ChangeLogEntry {
+            entity_type: ChangeEntityType::parse(&self.entity_type)?,
+            operation: ChangeOperation::parse(&self.operation)?,
+            source: ChangeSource::parse(&self.source),
+            created_at: DateTime::parse_from_rfc3339(&self.created_at)
+                .ok()?
+                .with_timezone(&Utc),
+            id: self.id,
+            entity_id: self.entity_id,
+            source_ref: self.source_ref,
+            account_id: self.account_id,
+            before: parse_json(self.before_json),
+            after: parse_json(self.after_json),
+        })
+    }
+}
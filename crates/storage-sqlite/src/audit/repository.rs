@@ -0,0 +1,108 @@
+//! Repository for the append-only change log.
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{self, Pool};
+use diesel::SqliteConnection;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use wealthfolio_core::audit::{
+    ChangeEntityType, ChangeLogEntry, ChangeLogRepositoryTrait, NewChangeLogEntry,
+};
+use wealthfolio_core::errors::Error;
+use wealthfolio_core::Result;
+
+use super::model::ChangeLogEntryDB;
+use crate::db::{get_connection, WriteHandle};
+use crate::errors::StorageError;
+use crate::schema::change_log;
+use crate::schema::change_log::dsl::*;
+
+pub struct ChangeLogRepository {
+    pool: Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
+    writer: WriteHandle,
+}
+
+impl ChangeLogRepository {
+    pub fn new(
+        pool: Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
+        writer: WriteHandle,
+    ) -> Self {
+        Self { pool, writer }
+    }
+}
+
+fn to_domain(rows: Vec<ChangeLogEntryDB>) -> Vec<ChangeLogEntry> {
+    rows.into_iter()
+        .filter_map(ChangeLogEntryDB::into_domain)
+        .collect()
+}
+
+#[async_trait]
+impl ChangeLogRepositoryTrait for ChangeLogRepository {
+    async fn append(&self, entries: Vec<NewChangeLogEntry>) -> Result<usize> {
+        if entries.is_empty() {
+            return Ok(0);
+        }
+        // Entries of one batch get increasing timestamps to keep their order
+        let now = Utc::now();
+        let rows: Vec<ChangeLogEntryDB> = entries
+            .into_iter()
+            .enumerate()
+            .map(|(idx, entry)| {
+                ChangeLogEntryDB::from_new(
+                    Uuid::new_v4().to_string(),
+                    entry,
+                    now + Duration::microseconds(idx as i64),
+                )
+            })
+            .collect();
+
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<usize> {
+                let inserted = diesel::insert_into(change_log::table)
+                    .values(&rows)
+                    .execute(conn)
+                    .map_err(StorageError::from)?;
+                Ok(inserted)
+            })
+            .await
+    }
+
+    fn get_entry(&self, entry_id: &str) -> Result<ChangeLogEntry> {
+        let mut conn = get_connection(&self.pool)?;
+        let row = change_log
+            .find(entry_id)
+            .first::<ChangeLogEntryDB>(&mut conn)
+            .map_err(StorageError::from)?;
+        row.into_domain()
+            .ok_or_else(|| Error::Unexpected(format!("Change log entry {} is malformed", entry_id)))
+    }
+
+    fn get_entity_history(
+        &self,
+        kind: ChangeEntityType,
+        target_id: &str,
+    ) -> Result<Vec<ChangeLogEntry>> {
+        let mut conn = get_connection(&self.pool)?;
+        let rows = change_log
+            .filter(entity_type.eq(kind.as_str()))
+            .filter(entity_id.eq(target_id))
+            .order(created_at.desc())
+            .load::<ChangeLogEntryDB>(&mut conn)
+            .map_err(StorageError::from)?;
+        Ok(to_domain(rows))
+    }
+
+    fn get_entries_by_source_ref(&self, reference: &str) -> Result<Vec<ChangeLogEntry>> {
+        let mut conn = get_connection(&self.pool)?;
+        let rows = change_log
+            .filter(source_ref.eq(reference))
+            .order(created_at.desc())
+            .load::<ChangeLogEntryDB>(&mut conn)
+            .map_err(StorageError::from)?;
+        Ok(to_domain(rows))
+    }
+}
@@ -0,0 +1,338 @@
+//! Encrypted backup archive format.
+//!
+//! An archive is `MAGIC || header JSON || '\n' || nonce || ciphertext`. The
+//! ciphertext is the whole SQLite backup file sealed with XChaCha20-Poly1305;
+//! the header carries the Argon2id parameters needed to derive the key from
+//! the passphrase again.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rusqlite::{Connection as RusqliteConnection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use wealthfolio_core::errors::{DatabaseError, Error, Result, ValidationError};
+use wealthfolio_device_sync::crypto::{self, PassphraseKdfParams};
+
+use super::model::{BackupKdf, RestorePreview};
+
+const MAGIC: &[u8; 5] = b"WFBK1";
+const ARCHIVE_VERSION: u32 = 1;
+const CIPHER: &str = "xchacha20poly1305";
+const KEY_CHECK_LABEL: &str = "wealthfolio-local-backup";
+
+/// File extension of encrypted archives.
+pub const ARCHIVE_EXTENSION: &str = "wfbackup";
+const FILE_PREFIX: &str = "wealthfolio_backup_";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d_%H%M%S";
+
+/// Tables counted in a restore preview, when present.
+const PREVIEW_TABLES: [&str; 7] = [
+    "accounts",
+    "activities",
+    "assets",
+    "quotes",
+    "holdings_snapshots",
+    "goals",
+    "attachments",
+];
+
+fn backup_error(context: &str, err: impl fmt::Display) -> Error {
+    Error::Database(DatabaseError::BackupFailed(format!("{}: {}", context, err)))
+}
+
+fn restore_error(context: &str, err: impl fmt::Display) -> Error {
+    Error::Database(DatabaseError::RestoreFailed(format!(
+        "{}: {}",
+        context, err
+    )))
+}
+
+/// Unencrypted archive header.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveHeader {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    pub cipher: String,
+    pub kdf: BackupKdf,
+}
+
+/// Key derived from the backup passphrase.
+#[derive(Clone)]
+pub struct BackupKey {
+    key: String,
+}
+
+impl fmt::Debug for BackupKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BackupKey").finish_non_exhaustive()
+    }
+}
+
+impl BackupKey {
+    /// Derives a new key from `passphrase` with a fresh salt.
+    pub fn create(passphrase: &str) -> Result<(BackupKdf, Self)> {
+        Self::create_with_params(passphrase, PassphraseKdfParams::default())
+    }
+
+    pub(crate) fn create_with_params(
+        passphrase: &str,
+        params: PassphraseKdfParams,
+    ) -> Result<(BackupKdf, Self)> {
+        let salt = crypto::generate_salt();
+        let key = crypto::derive_passphrase_key(passphrase, &salt, &params)
+            .map_err(|e| backup_error("Key derivation failed", e))?;
+        let key_check = crypto::hmac_sha256(&key, KEY_CHECK_LABEL)
+            .map_err(|e| backup_error("Key derivation failed", e))?;
+        let kdf = BackupKdf {
+            salt,
+            memory_kib: params.memory_kib,
+            iterations: params.iterations,
+            parallelism: params.parallelism,
+            key_check,
+        };
+        Ok((kdf, Self { key }))
+    }
+
+    /// Derives the key for `kdf` from `passphrase`, rejecting wrong passphrases.
+    pub fn unlock(passphrase: &str, kdf: &BackupKdf) -> Result<Self> {
+        let params = PassphraseKdfParams {
+            memory_kib: kdf.memory_kib,
+            iterations: kdf.iterations,
+            parallelism: kdf.parallelism,
+        };
+        let key = crypto::derive_passphrase_key(passphrase, &kdf.salt, &params)
+            .map_err(|e| restore_error("Key derivation failed", e))?;
+        Self::from_secret(key, kdf).ok_or_else(|| {
+            Error::Validation(ValidationError::InvalidInput(
+                "Incorrect backup passphrase".to_string(),
+            ))
+        })
+    }
+
+    /// Wraps a stored key if it matches `kdf`.
+    pub fn from_secret(key: String, kdf: &BackupKdf) -> Option<Self> {
+        crypto::hmac_sha256(&key, KEY_CHECK_LABEL)
+            .is_ok_and(|check| check == kdf.key_check)
+            .then_some(Self { key })
+    }
+
+    pub fn secret(&self) -> &str {
+        &self.key
+    }
+}
+
+pub fn archive_file_name(created_at: DateTime<Utc>) -> String {
+    format!(
+        "{}{}.{}",
+        FILE_PREFIX,
+        created_at.format(TIMESTAMP_FORMAT),
+        ARCHIVE_EXTENSION
+    )
+}
+
+/// Creation time encoded in an archive file name, or `None` for other files.
+pub fn parse_archive_file_name(file_name: &str) -> Option<DateTime<Utc>> {
+    let timestamp = file_name
+        .strip_prefix(FILE_PREFIX)?
+        .strip_suffix(ARCHIVE_EXTENSION)?
+        .strip_suffix('.')?;
+    NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT)
+        .ok()
+        .map(|dt| dt.and_utc())
+}
+
+/// Encrypts `db_bytes` into an archive at `target`. Written to a temporary
+/// file first so a crash never leaves a truncated archive behind.
+pub fn write_archive(
+    db_bytes: &[u8],
+    target: &Path,
+    key: &BackupKey,
+    kdf: &BackupKdf,
+    created_at: DateTime<Utc>,
+) -> Result<()> {
+    let header = ArchiveHeader {
+        version: ARCHIVE_VERSION,
+        created_at,
+        cipher: CIPHER.to_string(),
+        kdf: kdf.clone(),
+    };
+    let header_json =
+        serde_json::to_vec(&header).map_err(|e| backup_error("Invalid archive header", e))?;
+    let ciphertext = crypto::encrypt_bytes(&key.key, db_bytes)
+        .map_err(|e| backup_error("Encryption failed", e))?;
+
+    let mut out = Vec::with_capacity(MAGIC.len() + header_json.len() + 1 + ciphertext.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&header_json);
+    out.push(b'\n');
+    out.extend_from_slice(&ciphertext);
+
+    let partial = target.with_extension("partial");
+    fs::write(&partial, &out).map_err(|e| backup_error("Failed to write archive", e))?;
+    fs::rename(&partial, target).map_err(|e| {
+        let _ = fs::remove_file(&partial);
+        backup_error("Failed to write archive", e)
+    })
+}
+
+fn split_archive(data: &[u8]) -> Result<(ArchiveHeader, &[u8])> {
+    let rest = data
+        .strip_prefix(MAGIC.as_slice())
+        .ok_or_else(|| restore_error("Not a Wealthfolio backup archive", "bad magic"))?;
+    let newline = rest
+        .iter()
+        .position(|b| *b == b'\n')
+        .ok_or_else(|| restore_error("Corrupted backup archive", "missing header"))?;
+    let header: ArchiveHeader = serde_json::from_slice(&rest[..newline])
+        .map_err(|e| restore_error("Corrupted backup archive", e))?;
+    if header.version != ARCHIVE_VERSION || header.cipher != CIPHER {
+        return Err(restore_error(
+            "Unsupported backup archive",
+            format!("{} v{}", header.cipher, header.version),
+        ));
+    }
+    Ok((header, &rest[newline + 1..]))
+}
+
+pub fn read_archive_header(path: &Path) -> Result<ArchiveHeader> {
+    let data = fs::read(path).map_err(|e| restore_error("Failed to read archive", e))?;
+    split_archive(&data).map(|(header, _)| header)
+}
+
+/// Decrypts the archive at `path` into `db_file`.
+pub fn decrypt_archive(path: &Path, key: &BackupKey, db_file: &Path) -> Result<ArchiveHeader> {
+    let data = fs::read(path).map_err(|e| restore_error("Failed to read archive", e))?;
+    let (header, ciphertext) = split_archive(&data)?;
+    let plaintext = crypto::decrypt_bytes(&key.key, ciphertext)
+        .map_err(|e| restore_error("Failed to decrypt archive", e))?;
+    fs::write(db_file, plaintext).map_err(|e| restore_error("Failed to write database", e))?;
+    Ok(header)
+}
+
+/// Integrity-checks a decrypted database and summarizes its contents.
+pub fn inspect_database(db_file: &Path, created_at: DateTime<Utc>) -> Result<RestorePreview> {
+    let conn = RusqliteConnection::open_with_flags(db_file, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| restore_error("Failed to open backup database", e))?;
+    let integrity: String = conn
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .map_err(|e| restore_error("Integrity check failed", e))?;
+
+    let mut table_counts = BTreeMap::new();
+    for table in PREVIEW_TABLES {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1)",
+                [table],
+                |row| row.get(0),
+            )
+            .map_err(|e| restore_error("Failed to inspect backup database", e))?;
+        if exists {
+            let count: i64 = conn
+                .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                    row.get(0)
+                })
+                .map_err(|e| restore_error("Failed to inspect backup database", e))?;
+            table_counts.insert(table.to_string(), count);
+        }
+    }
+
+    let (first_activity_date, last_activity_date) = if table_counts.contains_key("activities") {
+        conn.query_row(
+            "SELECT MIN(substr(activity_date, 1, 10)), MAX(substr(activity_date, 1, 10)) FROM activities",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| restore_error("Failed to inspect backup database", e))?
+    } else {
+        (None, None)
+    };
+
+    Ok(RestorePreview {
+        created_at,
+        integrity_ok: integrity == "ok",
+        table_counts,
+        first_activity_date,
+        last_activity_date,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_key(passphrase: &str) -> (BackupKdf, BackupKey) {
+        // Cheap parameters keep the tests fast
+        let params = PassphraseKdfParams {
+            memory_kib: 64,
+            iterations: 1,
+            parallelism: 1,
+        };
+        BackupKey::create_with_params(passphrase, params).unwrap()
+    }
+
+    #[test]
+    fn archive_round_trips_and_previews_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source.db");
+        let conn = RusqliteConnection::open(&source).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE accounts (id TEXT PRIMARY KEY);
+             CREATE TABLE activities (id TEXT PRIMARY KEY, activity_date TEXT NOT NULL);
+             INSERT INTO accounts VALUES ('a1');
+             INSERT INTO activities VALUES ('x1', '2024-03-01T00:00:00Z'), ('x2', '2025-01-15');",
+        )
+        .unwrap();
+        drop(conn);
+
+        let (kdf, key) = test_key("correct horse battery");
+        let created_at = Utc::now();
+        let archive = dir.path().join(archive_file_name(created_at));
+        write_archive(
+            &fs::read(&source).unwrap(),
+            &archive,
+            &key,
+            &kdf,
+            created_at,
+        )
+        .unwrap();
+
+        let raw = fs::read(&archive).unwrap();
+        assert!(!raw.windows(7).any(|w| w == b"2024-03"));
+
+        let unlocked = BackupKey::unlock("correct horse battery", &kdf).unwrap();
+        let restored = dir.path().join("restored.db");
+        let header = decrypt_archive(&archive, &unlocked, &restored).unwrap();
+        let preview = inspect_database(&restored, header.created_at).unwrap();
+
+        assert!(preview.integrity_ok);
+        assert_eq!(preview.table_counts.get("accounts"), Some(&1));
+        assert_eq!(preview.table_counts.get("activities"), Some(&2));
+        assert!(!preview.table_counts.contains_key("quotes"));
+        assert_eq!(preview.first_activity_date.as_deref(), Some("2024-03-01"));
+        assert_eq!(preview.last_activity_date.as_deref(), Some("2025-01-15"));
+    }
+
+    #[test]
+    fn wrong_passphrase_is_rejected() {
+        let (kdf, _) = test_key("correct horse battery");
+        assert!(BackupKey::unlock("wrong horse battery", &kdf).is_err());
+    }
+
+    #[test]
+    fn archive_file_names_round_trip() {
+        let created_at = DateTime::parse_from_rfc3339("2025-06-01T08:30:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let name = archive_file_name(created_at);
+        assert_eq!(name, "wealthfolio_backup_20250601_083005.wfbackup");
+        assert_eq!(parse_archive_file_name(&name), Some(created_at));
+        assert_eq!(
+            parse_archive_file_name("wealthfolio_backup_20250601.db"),
+            None
+        );
+    }
+}
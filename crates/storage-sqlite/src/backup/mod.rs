@@ -0,0 +1,27 @@
+//! Scheduled, encrypted local backups.
+//!
+//! [`LocalBackupService`] takes a consistent online copy of the database
+//! (`VACUUM INTO`, with attachment contents packed in), encrypts it with a key
+//! derived from the user's passphrase and writes it to a target directory.
+//! Each archive is verified by decrypting and integrity-checking it, and old
+//! archives are pruned with a grandfather-father-son [`RetentionPolicy`].
+//! [`BackupScheduler`] drives it from the server and desktop apps.
+//!
+//! Only the derived key is kept on the device, in the secret store; archives
+//! carry the KDF parameters so they can be restored elsewhere with the
+//! passphrase.
+
+mod archive;
+mod model;
+mod retention;
+mod scheduler;
+mod service;
+
+pub use archive::ARCHIVE_EXTENSION;
+pub use model::{
+    BackupArchiveInfo, BackupKdf, BackupRunResult, BackupSchedule, BackupScheduleUpdate,
+    RestorePreview, RetentionPolicy, BACKUP_KEY_SECRET, BACKUP_SCHEDULE_KEY,
+    DEFAULT_BACKUP_INTERVAL_HOURS,
+};
+pub use scheduler::BackupScheduler;
+pub use service::LocalBackupService;
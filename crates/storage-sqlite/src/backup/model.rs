@@ -0,0 +1,174 @@
+//! Scheduled backup models.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use wealthfolio_core::errors::{Error, Result, ValidationError};
+
+/// App setting holding the JSON-encoded [`BackupSchedule`]. Not in the shared
+/// settings allowlist, since target directories are per device.
+pub const BACKUP_SCHEDULE_KEY: &str = "local_backup_schedule";
+
+/// Secret store key holding the key derived from the backup passphrase.
+pub const BACKUP_KEY_SECRET: &str = "local_backup_key";
+
+/// Default time between scheduled backups (1 day).
+pub const DEFAULT_BACKUP_INTERVAL_HOURS: u32 = 24;
+
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+pub(super) fn invalid(message: impl Into<String>) -> Error {
+    Error::Validation(ValidationError::InvalidInput(message.into()))
+}
+
+/// Grandfather-father-son retention: the newest backup of each of the last
+/// `daily` days, `weekly` ISO weeks and `monthly` months is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPolicy {
+    pub daily: u32,
+    pub weekly: u32,
+    pub monthly: u32,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            daily: 7,
+            weekly: 4,
+            monthly: 12,
+        }
+    }
+}
+
+/// Argon2id parameters and salt used to derive the backup key from the
+/// passphrase. Written into every archive so it can be restored on another
+/// device with the passphrase alone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupKdf {
+    pub salt: String,
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    /// HMAC over a fixed label under the derived key, used to check a passphrase.
+    pub key_check: String,
+}
+
+/// Scheduled backup settings for this device.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSchedule {
+    pub enabled: bool,
+    pub interval_hours: u32,
+    /// Directory receiving the encrypted archives
+    #[serde(default)]
+    pub target_dir: Option<String>,
+    #[serde(default)]
+    pub retention: RetentionPolicy,
+    /// Set once a passphrase has been configured
+    #[serde(default)]
+    pub kdf: Option<BackupKdf>,
+}
+
+impl Default for BackupSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: DEFAULT_BACKUP_INTERVAL_HOURS,
+            target_dir: None,
+            retention: RetentionPolicy::default(),
+            kdf: None,
+        }
+    }
+}
+
+impl BackupSchedule {
+    /// Whether a backup is due given the creation time of the newest archive.
+    pub fn is_due(&self, last_backup_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        last_backup_at.is_none_or(|last| {
+            now - last >= chrono::Duration::hours(i64::from(self.interval_hours))
+        })
+    }
+}
+
+/// Changes to the backup schedule. A passphrase is required the first time
+/// backups are enabled and replaces the key when given again.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupScheduleUpdate {
+    pub enabled: bool,
+    pub interval_hours: u32,
+    pub target_dir: Option<String>,
+    #[serde(default)]
+    pub retention: RetentionPolicy,
+    #[serde(default)]
+    pub passphrase: Option<String>,
+}
+
+impl BackupScheduleUpdate {
+    pub fn validate(&self) -> Result<()> {
+        if self.interval_hours == 0 {
+            return Err(invalid("Backup interval must be at least 1 hour"));
+        }
+        let retention = &self.retention;
+        if retention.daily + retention.weekly + retention.monthly == 0 {
+            return Err(invalid("Retention must keep at least one backup"));
+        }
+        if self.enabled
+            && self
+                .target_dir
+                .as_deref()
+                .is_none_or(|dir| dir.trim().is_empty())
+        {
+            return Err(invalid(
+                "A target directory is required for scheduled backups",
+            ));
+        }
+        if let Some(passphrase) = &self.passphrase {
+            if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+                return Err(invalid(format!(
+                    "Passphrase must be at least {} characters",
+                    MIN_PASSPHRASE_LEN
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An encrypted archive in the target directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupArchiveInfo {
+    pub path: String,
+    pub file_name: String,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+/// Outcome of a scheduled or manual backup run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupRunResult {
+    pub archive: BackupArchiveInfo,
+    /// Archives removed by the retention policy
+    pub pruned: Vec<String>,
+}
+
+/// What restoring an archive would bring back, read from a decrypted copy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestorePreview {
+    pub created_at: DateTime<Utc>,
+    /// Result of `PRAGMA integrity_check` on the decrypted database
+    pub integrity_ok: bool,
+    /// Row counts of the main user tables present in the backup
+    pub table_counts: BTreeMap<String, i64>,
+    pub first_activity_date: Option<String>,
+    pub last_activity_date: Option<String>,
+}
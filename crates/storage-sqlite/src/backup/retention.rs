@@ -0,0 +1,124 @@
+//! Grandfather-father-son pruning of backup archives.
+
+use chrono::{DateTime, Datelike, Utc};
+use std::collections::HashSet;
+
+use super::model::{BackupArchiveInfo, RetentionPolicy};
+
+/// Keeps the newest archive of each of the `limit` most recent periods.
+fn keep_per_period<K: Eq + std::hash::Hash>(
+    sorted: &[&BackupArchiveInfo],
+    limit: u32,
+    period: impl Fn(DateTime<Utc>) -> K,
+    keep: &mut HashSet<String>,
+) {
+    let mut seen = HashSet::new();
+    for archive in sorted {
+        if seen.len() >= limit as usize {
+            break;
+        }
+        if seen.insert(period(archive.created_at)) {
+            keep.insert(archive.path.clone());
+        }
+    }
+}
+
+/// Returns the archives `policy` no longer keeps. The newest archive is
+/// always kept.
+pub fn archives_to_prune<'a>(
+    archives: &'a [BackupArchiveInfo],
+    policy: &RetentionPolicy,
+) -> Vec<&'a BackupArchiveInfo> {
+    let mut sorted: Vec<&BackupArchiveInfo> = archives.iter().collect();
+    sorted.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    let mut keep = HashSet::new();
+    if let Some(newest) = sorted.first() {
+        keep.insert(newest.path.clone());
+    }
+    keep_per_period(&sorted, policy.daily, |at| at.date_naive(), &mut keep);
+    keep_per_period(
+        &sorted,
+        policy.weekly,
+        |at| {
+            let week = at.iso_week();
+            (week.year(), week.week())
+        },
+        &mut keep,
+    );
+    keep_per_period(
+        &sorted,
+        policy.monthly,
+        |at| (at.year(), at.month()),
+        &mut keep,
+    );
+
+    sorted
+        .into_iter()
+        .filter(|archive| !keep.contains(&archive.path))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn archive(at: DateTime<Utc>) -> BackupArchiveInfo {
+        let file_name = super::super::archive::archive_file_name(at);
+        BackupArchiveInfo {
+            path: format!("/backups/{}", file_name),
+            file_name,
+            created_at: at,
+            size_bytes: 1,
+        }
+    }
+
+    #[test]
+    fn keeps_newest_per_day_week_and_month() {
+        // Two backups a day for 90 days, ending on Sunday 2025-06-29
+        let end = Utc.with_ymd_and_hms(2025, 6, 29, 18, 0, 0).unwrap();
+        let archives: Vec<_> = (0..180)
+            .map(|i| archive(end - Duration::hours(12 * i)))
+            .collect();
+        let policy = RetentionPolicy {
+            daily: 7,
+            weekly: 4,
+            monthly: 3,
+        };
+
+        let pruned: HashSet<_> = archives_to_prune(&archives, &policy)
+            .into_iter()
+            .map(|a| a.created_at)
+            .collect();
+        let kept: Vec<_> = archives
+            .iter()
+            .map(|a| a.created_at)
+            .filter(|at| !pruned.contains(at))
+            .collect();
+
+        // 7 daily; the weekly picks for the current week and the month pick
+        // for June coincide with the newest daily, leaving 3 older weeks and
+        // 2 older months (May 31 and April 30)
+        assert_eq!(kept.len(), 7 + 3 + 2);
+        assert!(kept.contains(&end));
+        assert!(!kept.contains(&(end - Duration::hours(12))));
+        assert!(kept.contains(&Utc.with_ymd_and_hms(2025, 6, 22, 18, 0, 0).unwrap()));
+        assert!(kept.contains(&Utc.with_ymd_and_hms(2025, 5, 31, 18, 0, 0).unwrap()));
+        assert!(kept.contains(&Utc.with_ymd_and_hms(2025, 4, 30, 18, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn always_keeps_the_newest_archive() {
+        let at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let archives = vec![archive(at), archive(at - Duration::days(1))];
+        let policy = RetentionPolicy {
+            daily: 0,
+            weekly: 0,
+            monthly: 0,
+        };
+        let pruned = archives_to_prune(&archives, &policy);
+        assert_eq!(pruned.len(), 1);
+        assert_eq!(pruned[0].created_at, at - Duration::days(1));
+    }
+}
@@ -0,0 +1,73 @@
+//! Background loop firing scheduled backups, shared by the server and desktop apps.
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use log::{error, info};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::service::LocalBackupService;
+
+/// Delay before the first check, so startup work and migrations settle first.
+const INITIAL_DELAY_SECS: u64 = 120;
+
+/// How often the schedule is checked.
+const TICK_INTERVAL_SECS: u64 = 5 * 60;
+
+/// Minimum gap between attempts, so a failing backup (e.g. an unmounted
+/// target drive) is not retried on every tick.
+const RETRY_AFTER_MINUTES: i64 = 60;
+
+pub struct BackupScheduler {
+    service: Arc<LocalBackupService>,
+    last_attempt: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl BackupScheduler {
+    pub fn new(service: Arc<LocalBackupService>) -> Self {
+        Self {
+            service,
+            last_attempt: Mutex::new(None),
+        }
+    }
+
+    /// Spawns the scheduler loop on the current tokio runtime.
+    pub fn start(self: Arc<Self>) {
+        info!(
+            "Starting backup scheduler (first check in {}s)",
+            INITIAL_DELAY_SECS
+        );
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(INITIAL_DELAY_SECS)).await;
+
+            let mut interval = tokio::time::interval(Duration::from_secs(TICK_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                self.run_due(Utc::now()).await;
+            }
+        });
+    }
+
+    /// Runs a backup if one is due at `now`. Returns whether one was attempted.
+    pub async fn run_due(&self, now: DateTime<Utc>) -> bool {
+        {
+            let mut last_attempt = self.last_attempt.lock().unwrap();
+            let retry_at = last_attempt.map(|at| at + ChronoDuration::minutes(RETRY_AFTER_MINUTES));
+            if retry_at.is_some_and(|at| now < at) || !self.service.is_due(now) {
+                return false;
+            }
+            *last_attempt = Some(now);
+        }
+
+        let service = self.service.clone();
+        match tokio::task::spawn_blocking(move || service.run_backup()).await {
+            Ok(Ok(result)) => info!(
+                "Scheduled backup {} completed ({} pruned)",
+                result.archive.file_name,
+                result.pruned.len()
+            ),
+            Ok(Err(e)) => error!("Scheduled backup failed: {}", e),
+            Err(e) => error!("Scheduled backup task failed: {}", e),
+        }
+        true
+    }
+}
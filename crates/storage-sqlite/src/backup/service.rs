@@ -0,0 +1,306 @@
+//! Scheduled, encrypted local backups.
+
+use chrono::{DateTime, SubsecRound, Utc};
+use log::{info, warn};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use wealthfolio_core::errors::{DatabaseError, Error, Result};
+use wealthfolio_core::secrets::SecretStore;
+use wealthfolio_core::settings::SettingsRepositoryTrait;
+
+use super::archive::{
+    archive_file_name, decrypt_archive, inspect_database, parse_archive_file_name,
+    read_archive_header, write_archive, BackupKey,
+};
+use super::model::{
+    invalid, BackupArchiveInfo, BackupRunResult, BackupSchedule, BackupScheduleUpdate,
+    RestorePreview, BACKUP_KEY_SECRET, BACKUP_SCHEDULE_KEY,
+};
+use super::retention::archives_to_prune;
+use crate::attachments::FileAttachmentStore;
+use crate::db;
+
+/// Plaintext working copy under `<app_data_dir>/backups`, removed on drop.
+struct ScratchFile(PathBuf);
+
+impl ScratchFile {
+    fn new(app_data_dir: &str) -> Result<Self> {
+        let dir = Path::new(app_data_dir).join("backups");
+        fs::create_dir_all(&dir)
+            .map_err(|e| Error::Database(DatabaseError::BackupFailed(e.to_string())))?;
+        Ok(Self(dir.join(format!(".scratch-{}.db", Uuid::new_v4()))))
+    }
+
+    fn path_str(&self) -> String {
+        self.0.to_string_lossy().to_string()
+    }
+}
+
+impl Drop for ScratchFile {
+    fn drop(&mut self) {
+        if self.0.exists() {
+            if let Err(e) = fs::remove_file(&self.0) {
+                warn!("Failed to remove backup scratch file {:?}: {}", self.0, e);
+            }
+        }
+    }
+}
+
+/// Takes encrypted backups into a target directory on a schedule, prunes
+/// them by retention policy and restores them after a preview.
+pub struct LocalBackupService {
+    app_data_dir: String,
+    settings_repo: Arc<dyn SettingsRepositoryTrait>,
+    secret_store: Arc<dyn SecretStore>,
+    attachment_store: Arc<FileAttachmentStore>,
+    /// Serializes backup runs between the scheduler and manual triggers
+    run_lock: Mutex<()>,
+}
+
+impl LocalBackupService {
+    pub fn new(
+        app_data_dir: &str,
+        settings_repo: Arc<dyn SettingsRepositoryTrait>,
+        secret_store: Arc<dyn SecretStore>,
+        attachment_store: Arc<FileAttachmentStore>,
+    ) -> Self {
+        Self {
+            app_data_dir: app_data_dir.to_string(),
+            settings_repo,
+            secret_store,
+            attachment_store,
+            run_lock: Mutex::new(()),
+        }
+    }
+
+    /// Stored schedule, or the (disabled) default if missing/corrupt.
+    pub fn get_schedule(&self) -> BackupSchedule {
+        match self.settings_repo.get_setting(BACKUP_SCHEDULE_KEY) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable backup schedule: {}", e);
+                BackupSchedule::default()
+            }),
+            Err(_) => BackupSchedule::default(),
+        }
+    }
+
+    /// Validates and stores the schedule. A new passphrase replaces the key
+    /// used for future archives; existing archives keep their own.
+    pub async fn update_schedule(&self, update: BackupScheduleUpdate) -> Result<BackupSchedule> {
+        update.validate()?;
+
+        let mut schedule = self.get_schedule();
+        if let Some(passphrase) = update.passphrase.as_deref() {
+            let (kdf, key) = BackupKey::create(passphrase)?;
+            self.secret_store
+                .set_secret(BACKUP_KEY_SECRET, key.secret())?;
+            schedule.kdf = Some(kdf);
+        }
+        if update.enabled && self.stored_key(&schedule).is_none() {
+            return Err(invalid(
+                "A passphrase is required to enable scheduled backups",
+            ));
+        }
+
+        schedule.enabled = update.enabled;
+        schedule.interval_hours = update.interval_hours;
+        schedule.retention = update.retention;
+        schedule.target_dir = update
+            .target_dir
+            .map(|dir| dir.trim().to_string())
+            .filter(|dir| !dir.is_empty());
+        if let (true, Some(dir)) = (schedule.enabled, &schedule.target_dir) {
+            fs::create_dir_all(dir)
+                .map_err(|e| invalid(format!("Cannot use '{}' as backup directory: {}", dir, e)))?;
+        }
+
+        let json = serde_json::to_string(&schedule)?;
+        self.settings_repo
+            .update_setting(BACKUP_SCHEDULE_KEY, &json)
+            .await?;
+        Ok(schedule)
+    }
+
+    fn stored_key(&self, schedule: &BackupSchedule) -> Option<BackupKey> {
+        let kdf = schedule.kdf.as_ref()?;
+        let secret = self
+            .secret_store
+            .get_secret(BACKUP_KEY_SECRET)
+            .unwrap_or_else(|e| {
+                warn!("Failed to read backup key: {}", e);
+                None
+            })?;
+        BackupKey::from_secret(secret, kdf)
+    }
+
+    /// Archives in the target directory, newest first.
+    pub fn list_backups(&self) -> Result<Vec<BackupArchiveInfo>> {
+        let Some(target_dir) = self.get_schedule().target_dir else {
+            return Ok(Vec::new());
+        };
+        let entries = match fs::read_dir(&target_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(Error::Database(DatabaseError::BackupFailed(e.to_string()))),
+        };
+
+        let mut archives: Vec<BackupArchiveInfo> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let created_at = parse_archive_file_name(&file_name)?;
+                let size_bytes = entry.metadata().ok()?.len();
+                Some(BackupArchiveInfo {
+                    path: entry.path().to_string_lossy().to_string(),
+                    file_name,
+                    created_at,
+                    size_bytes,
+                })
+            })
+            .collect();
+        archives.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(archives)
+    }
+
+    /// Whether the schedule calls for a backup at `now`.
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        let schedule = self.get_schedule();
+        if !schedule.enabled {
+            return false;
+        }
+        match self.list_backups() {
+            Ok(archives) => schedule.is_due(archives.first().map(|a| a.created_at), now),
+            Err(e) => {
+                warn!("Failed to list backups: {}", e);
+                true
+            }
+        }
+    }
+
+    /// Takes a backup now: a consistent online copy of the database (with
+    /// attachment contents), encrypted into the target directory, verified
+    /// by decrypting and integrity-checking it, then pruned by retention.
+    pub fn run_backup(&self) -> Result<BackupRunResult> {
+        let _guard = self.run_lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        let schedule = self.get_schedule();
+        let target_dir = schedule
+            .target_dir
+            .clone()
+            .ok_or_else(|| invalid("No backup directory configured"))?;
+        let kdf = schedule
+            .kdf
+            .clone()
+            .ok_or_else(|| invalid("No backup passphrase configured"))?;
+        let key = self
+            .stored_key(&schedule)
+            .ok_or_else(|| invalid("The backup passphrase is not set on this device"))?;
+        fs::create_dir_all(&target_dir)
+            .map_err(|e| Error::Database(DatabaseError::BackupFailed(e.to_string())))?;
+
+        let created_at = Utc::now().trunc_subsecs(0);
+        let file_name = archive_file_name(created_at);
+        let archive_path = Path::new(&target_dir).join(&file_name);
+        {
+            // VACUUM INTO reads a single transaction, so writers are not blocked
+            let scratch = ScratchFile::new(&self.app_data_dir)?;
+            db::backup_database_to_file(&self.app_data_dir, &scratch.path_str())?;
+            self.attachment_store.pack_archive(&scratch.path_str())?;
+            let bytes = fs::read(&scratch.0)
+                .map_err(|e| Error::Database(DatabaseError::BackupFailed(e.to_string())))?;
+            write_archive(&bytes, &archive_path, &key, &kdf, created_at)?;
+        }
+
+        let preview = self.inspect_archive(&archive_path, &key)?;
+        if !preview.integrity_ok {
+            let _ = fs::remove_file(&archive_path);
+            return Err(Error::Database(DatabaseError::BackupFailed(format!(
+                "Backup {} failed verification",
+                file_name
+            ))));
+        }
+
+        let archives = self.list_backups()?;
+        let mut pruned = Vec::new();
+        for archive in archives_to_prune(&archives, &schedule.retention) {
+            match fs::remove_file(&archive.path) {
+                Ok(()) => pruned.push(archive.file_name.clone()),
+                Err(e) => warn!("Failed to prune backup {}: {}", archive.path, e),
+            }
+        }
+
+        let archive = archives
+            .into_iter()
+            .find(|a| a.file_name == file_name)
+            .ok_or_else(|| {
+                Error::Database(DatabaseError::BackupFailed(format!(
+                    "Backup {} disappeared after writing",
+                    file_name
+                )))
+            })?;
+        info!(
+            "Backup {} written and verified ({} bytes, {} pruned)",
+            archive.file_name,
+            archive.size_bytes,
+            pruned.len()
+        );
+        Ok(BackupRunResult { archive, pruned })
+    }
+
+    fn inspect_archive(&self, path: &Path, key: &BackupKey) -> Result<RestorePreview> {
+        let scratch = ScratchFile::new(&self.app_data_dir)?;
+        let header = decrypt_archive(path, key, &scratch.0)?;
+        inspect_database(&scratch.0, header.created_at)
+    }
+
+    /// Key for an archive: derived from `passphrase`, or the stored key if
+    /// the archive was written with it.
+    fn key_for_archive(&self, path: &Path, passphrase: Option<&str>) -> Result<BackupKey> {
+        let header = read_archive_header(path)?;
+        match passphrase {
+            Some(passphrase) => BackupKey::unlock(passphrase, &header.kdf),
+            None => self
+                .secret_store
+                .get_secret(BACKUP_KEY_SECRET)?
+                .and_then(|secret| BackupKey::from_secret(secret, &header.kdf))
+                .ok_or_else(|| invalid("A passphrase is required to open this backup")),
+        }
+    }
+
+    /// Decrypts an archive to a scratch copy and reports what restoring it
+    /// would bring back, without touching the live database.
+    pub fn preview_restore(
+        &self,
+        archive_path: &str,
+        passphrase: Option<&str>,
+    ) -> Result<RestorePreview> {
+        let path = Path::new(archive_path);
+        let key = self.key_for_archive(path, passphrase)?;
+        self.inspect_archive(path, &key)
+    }
+
+    /// Replaces the live database with the archive contents. The current
+    /// database is kept as a pre-restore copy by [`db::restore_database`].
+    pub fn restore_backup(&self, archive_path: &str, passphrase: Option<&str>) -> Result<()> {
+        let path = Path::new(archive_path);
+        let key = self.key_for_archive(path, passphrase)?;
+
+        let scratch = ScratchFile::new(&self.app_data_dir)?;
+        let header = decrypt_archive(path, &key, &scratch.0)?;
+        if !inspect_database(&scratch.0, header.created_at)?.integrity_ok {
+            return Err(Error::Database(DatabaseError::RestoreFailed(
+                "Backup failed the integrity check".to_string(),
+            )));
+        }
+
+        db::restore_database_safe(&self.app_data_dir, &scratch.path_str())?;
+        // Archives carry attachment contents; move them into the file store
+        self.attachment_store
+            .unpack_archive(&db::get_db_path(&self.app_data_dir), true)?;
+        info!("Restored database from backup {}", archive_path);
+        Ok(())
+    }
+}
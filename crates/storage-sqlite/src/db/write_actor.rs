@@ -132,8 +132,8 @@ impl<'a> DbWriteTx<'a> {
         self.projection.capture_delete::<T>(entity_id);
     }
 
-    pub fn delete_model<T: SyncOutboxModel>(&mut self, model: &T) {
-        self.projection.capture_model_delete(model);
+    pub fn delete_model<T: SyncOutboxModel>(&mut self, model: &T) -> Result<()> {
+        self.projection.capture_model_delete(model)
     }
 }
 
@@ -179,10 +179,16 @@ impl WriteProjection {
         }
     }
 
-    pub fn capture_model_delete<T: SyncOutboxModel>(&mut self, model: &T) {
-        if model.should_sync_outbox(SyncOperation::Delete) {
-            self.capture_delete::<T>(model.sync_entity_id().to_string());
+    pub fn capture_model_delete<T: SyncOutboxModel>(&mut self, model: &T) -> Result<()> {
+        if !model.should_sync_outbox(SyncOperation::Delete) {
+            return Ok(());
+        }
+        if T::ENTITY.uses_derived_id() {
+            // Replay locates the row by the key columns in the payload.
+            return self.capture_model(model, SyncOperation::Delete);
         }
+        self.capture_delete::<T>(model.sync_entity_id().to_string());
+        Ok(())
     }
 
     fn flush(self, conn: &mut SqliteConnection) -> Result<()> {
@@ -56,14 +56,15 @@ impl HealthDismissalStore for HealthDismissalRepository {
         let dismissal_db: HealthIssueDismissalDB = dismissal.clone().into();
 
         self.writer
-            .exec(move |conn: &mut SqliteConnection| -> Result<()> {
+            .exec_tx(move |tx| -> Result<()> {
                 diesel::insert_into(health_issue_dismissals::table)
                     .values(&dismissal_db)
                     .on_conflict(issue_id)
                     .do_update()
                     .set(&dismissal_db)
-                    .execute(conn)
+                    .execute(tx.conn())
                     .map_err(StorageError::from)?;
+                tx.update(&dismissal_db)?;
                 Ok(())
             })
             .await
@@ -73,10 +74,18 @@ impl HealthDismissalStore for HealthDismissalRepository {
         let id_owned = id.to_string();
 
         self.writer
-            .exec(move |conn: &mut SqliteConnection| -> Result<()> {
-                diesel::delete(health_issue_dismissals.find(id_owned))
-                    .execute(conn)
+            .exec_tx(move |tx| -> Result<()> {
+                let existing = health_issue_dismissals
+                    .find(&id_owned)
+                    .first::<HealthIssueDismissalDB>(tx.conn())
+                    .optional()
                     .map_err(StorageError::from)?;
+                diesel::delete(health_issue_dismissals.find(&id_owned))
+                    .execute(tx.conn())
+                    .map_err(StorageError::from)?;
+                if let Some(existing) = existing.as_ref() {
+                    tx.delete_model(existing)?;
+                }
                 Ok(())
             })
             .await
@@ -92,10 +101,16 @@ impl HealthDismissalStore for HealthDismissalRepository {
 
     async fn clear_all(&self) -> Result<()> {
         self.writer
-            .exec(move |conn: &mut SqliteConnection| -> Result<()> {
+            .exec_tx(move |tx| -> Result<()> {
+                let existing = health_issue_dismissals
+                    .load::<HealthIssueDismissalDB>(tx.conn())
+                    .map_err(StorageError::from)?;
                 diesel::delete(health_issue_dismissals::table)
-                    .execute(conn)
+                    .execute(tx.conn())
                     .map_err(StorageError::from)?;
+                for dismissal in &existing {
+                    tx.delete_model(dismissal)?;
+                }
                 Ok(())
             })
             .await
@@ -34,12 +34,16 @@ pub mod accounts;
 pub mod activities;
 pub mod ai_chat;
 pub mod assets;
+pub mod attachments;
+pub mod audit;
+pub mod backup;
 pub mod fx;
 pub mod goals;
 pub mod health;
 pub mod limits;
 pub mod market_data;
 pub mod portfolio;
+pub mod recurring;
 pub mod settings;
 pub mod sync;
 pub mod taxonomies;
@@ -5,7 +5,7 @@ mod quote_sync_state_repository;
 mod repository;
 
 pub use model::{
-    MarketDataProviderSettingDB, QuoteDB, QuoteSyncStateDB, QuoteSyncStateUpdateDB,
+    MarketDataProviderPreferenceDB, MarketDataProviderSettingDB, QuoteDB, QuoteSyncStateDB, QuoteSyncStateUpdateDB,
     UpdateMarketDataProviderSettingDB,
 };
 pub use quote_sync_state_repository::QuoteSyncStateRepository;
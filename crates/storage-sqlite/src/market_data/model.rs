@@ -91,6 +91,26 @@ pub struct MarketDataProviderSettingDB {
     pub last_sync_error: Option<String>,
 }
 
+/// Provider preferences shared with other devices through sync. Sync status
+/// columns stay device-local.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketDataProviderPreferenceDB {
+    pub id: String,
+    pub priority: i32,
+    pub enabled: bool,
+}
+
+impl From<&MarketDataProviderSettingDB> for MarketDataProviderPreferenceDB {
+    fn from(db: &MarketDataProviderSettingDB) -> Self {
+        Self {
+            id: db.id.clone(),
+            priority: db.priority,
+            enabled: db.enabled,
+        }
+    }
+}
+
 /// Database model for updating market data provider settings
 #[derive(Debug, Clone, Serialize, Deserialize, Insertable, AsChangeset)]
 #[diesel(table_name = crate::schema::market_data_providers)]
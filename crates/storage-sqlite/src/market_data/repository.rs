@@ -9,17 +9,22 @@ use diesel::sqlite::SqliteConnection;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use super::model::{MarketDataProviderSettingDB, QuoteDB, UpdateMarketDataProviderSettingDB};
+use super::model::{
+    MarketDataProviderPreferenceDB, MarketDataProviderSettingDB, QuoteDB,
+    UpdateMarketDataProviderSettingDB,
+};
 use crate::db::{get_connection, WriteHandle};
 use crate::errors::{IntoCore, StorageError};
 use crate::schema::market_data_providers::dsl as market_data_providers_dsl;
 use crate::schema::quotes::dsl as quotes_dsl;
+use crate::sync::{insert_outbox_event, outbox_request_for_model};
 use crate::utils::chunk_for_sqlite;
 use wealthfolio_core::quotes::store::{ProviderSettingsStore, QuoteStore};
 use wealthfolio_core::quotes::types::{AssetId, Day, QuoteSource};
 use wealthfolio_core::quotes::{
     LatestQuotePair, MarketDataProviderSetting, Quote, UpdateMarketDataProviderSetting,
 };
+use wealthfolio_core::sync::SyncOperation;
 use wealthfolio_core::Result;
 
 pub struct MarketDataRepository {
@@ -99,7 +104,7 @@ impl QuoteStore for MarketDataRepository {
                     .map_err(StorageError::QueryFailed)?;
 
                 if let Some(row) = existing {
-                    tx.delete_model(&row);
+                    tx.delete_model(&row)?;
                 }
                 Ok(())
             })
@@ -217,7 +222,7 @@ impl QuoteStore for MarketDataRepository {
                 .map_err(StorageError::QueryFailed)?;
 
                 for row in &existing_rows {
-                    tx.delete_model(row);
+                    tx.delete_model(row)?;
                 }
 
                 Ok(count)
@@ -766,16 +771,23 @@ impl ProviderSettingsStore for MarketDataRepository {
             enabled: changes.enabled,
         };
 
-        diesel::update(market_data_providers_dsl::market_data_providers.find(id))
-            .set(&changes_db)
-            .execute(&mut conn)
-            .into_core()?;
-
-        let db_result = market_data_providers_dsl::market_data_providers
-            .find(id)
-            .select(MarketDataProviderSettingDB::as_select())
-            .first::<MarketDataProviderSettingDB>(&mut conn)
-            .into_core()?;
+        let db_result = conn.immediate_transaction::<_, StorageError, _>(|tx| {
+            diesel::update(market_data_providers_dsl::market_data_providers.find(id))
+                .set(&changes_db)
+                .execute(tx)?;
+
+            let db_result = market_data_providers_dsl::market_data_providers
+                .find(id)
+                .select(MarketDataProviderSettingDB::as_select())
+                .first::<MarketDataProviderSettingDB>(tx)?;
+
+            let preference = MarketDataProviderPreferenceDB::from(&db_result);
+            insert_outbox_event(
+                tx,
+                outbox_request_for_model(&preference, SyncOperation::Update)?,
+            )?;
+            Ok(db_result)
+        })?;
 
         Ok(MarketDataProviderSetting::from(db_result))
     }
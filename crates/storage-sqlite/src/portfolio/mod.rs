@@ -1,4 +1,5 @@
 //! SQLite storage implementation for portfolio data.
 
+pub mod reconciliation;
 pub mod snapshot;
 pub mod valuation;
@@ -0,0 +1,7 @@
+//! SQLite storage implementation for holdings drift checks.
+
+mod model;
+mod repository;
+
+pub use model::HoldingsDriftCheckDB;
+pub use repository::HoldingsDriftRepository;
@@ -0,0 +1,95 @@
+//! Database models for holdings drift checks.
+
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use wealthfolio_core::errors::{Error, Result};
+use wealthfolio_core::portfolio::reconciliation::{HoldingsDriftCheck, HoldingsDriftStatus};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Database model for holdings drift checks
+#[derive(
+    Queryable,
+    Identifiable,
+    Insertable,
+    AsChangeset,
+    Selectable,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+)]
+#[diesel(table_name = crate::schema::holdings_drift_checks)]
+#[diesel(primary_key(id))]
+#[diesel(treat_none_as_null = true)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[serde(rename_all = "camelCase")]
+pub struct HoldingsDriftCheckDB {
+    pub id: String,
+    pub account_id: String,
+    pub as_of_date: String,
+    pub status: String,
+    /// BrokerStatement as JSON
+    pub statement: String,
+    /// ReconciliationReport as JSON
+    pub report: Option<String>,
+    pub discrepancy_count: i32,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub checked_at: Option<NaiveDateTime>,
+}
+
+impl HoldingsDriftCheckDB {
+    /// Serializes a domain check for storage.
+    pub fn from_domain(check: HoldingsDriftCheck) -> Result<Self> {
+        let statement = serde_json::to_string(&check.statement)
+            .map_err(|e| Error::Unexpected(format!("Failed to serialize statement: {}", e)))?;
+        let report = check
+            .report
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| Error::Unexpected(format!("Failed to serialize report: {}", e)))?;
+
+        Ok(Self {
+            id: check.id,
+            account_id: check.account_id,
+            as_of_date: check.as_of_date.format(DATE_FORMAT).to_string(),
+            status: check.status.as_str().to_string(),
+            statement,
+            report,
+            discrepancy_count: check.discrepancy_count,
+            error: check.error,
+            created_at: check.created_at,
+            checked_at: check.checked_at,
+        })
+    }
+
+    /// Converts to the domain model, skipping rows whose JSON or status can no
+    /// longer be read (e.g. written by a newer version).
+    pub fn into_domain(self) -> Option<HoldingsDriftCheck> {
+        let as_of_date = NaiveDate::parse_from_str(&self.as_of_date, DATE_FORMAT).ok()?;
+        let status = HoldingsDriftStatus::parse(&self.status)?;
+        let statement = serde_json::from_str(&self.statement).ok()?;
+        let report = match self.report {
+            Some(json) => Some(serde_json::from_str(&json).ok()?),
+            None => None,
+        };
+
+        Some(HoldingsDriftCheck {
+            id: self.id,
+            account_id: self.account_id,
+            as_of_date,
+            status,
+            statement,
+            report,
+            discrepancy_count: self.discrepancy_count,
+            error: self.error,
+            created_at: self.created_at,
+            checked_at: self.checked_at,
+        })
+    }
+}
@@ -0,0 +1,111 @@
+//! Repository for holdings drift checks.
+
+use async_trait::async_trait;
+use diesel::prelude::*;
+use diesel::r2d2::{self, Pool};
+use diesel::SqliteConnection;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use wealthfolio_core::errors::Error;
+use wealthfolio_core::portfolio::reconciliation::{
+    HoldingsDriftCheck, HoldingsDriftRepositoryTrait, HoldingsDriftStatus,
+};
+use wealthfolio_core::Result;
+
+use super::model::HoldingsDriftCheckDB;
+use crate::db::{get_connection, WriteHandle};
+use crate::errors::StorageError;
+use crate::schema::holdings_drift_checks;
+use crate::schema::holdings_drift_checks::dsl::*;
+
+pub struct HoldingsDriftRepository {
+    pool: Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
+    writer: WriteHandle,
+}
+
+impl HoldingsDriftRepository {
+    pub fn new(
+        pool: Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
+        writer: WriteHandle,
+    ) -> Self {
+        Self { pool, writer }
+    }
+}
+
+fn to_domain(rows: Vec<HoldingsDriftCheckDB>) -> Vec<HoldingsDriftCheck> {
+    rows.into_iter()
+        .filter_map(HoldingsDriftCheckDB::into_domain)
+        .collect()
+}
+
+#[async_trait]
+impl HoldingsDriftRepositoryTrait for HoldingsDriftRepository {
+    async fn upsert_check(&self, check: HoldingsDriftCheck) -> Result<HoldingsDriftCheck> {
+        let row = HoldingsDriftCheckDB::from_domain(check)?;
+
+        self.writer
+            .exec(
+                move |conn: &mut SqliteConnection| -> Result<HoldingsDriftCheck> {
+                    let saved = diesel::insert_into(holdings_drift_checks::table)
+                        .values(&row)
+                        .on_conflict(holdings_drift_checks::id)
+                        .do_update()
+                        .set(&row)
+                        .get_result::<HoldingsDriftCheckDB>(conn)
+                        .map_err(StorageError::from)?;
+                    let saved_id = saved.id.clone();
+                    saved.into_domain().ok_or_else(|| {
+                        Error::Unexpected(format!(
+                            "Holdings drift check {} could not be read back",
+                            saved_id
+                        ))
+                    })
+                },
+            )
+            .await
+    }
+
+    fn list_pending(&self) -> Result<Vec<HoldingsDriftCheck>> {
+        let mut conn = get_connection(&self.pool)?;
+        let rows = holdings_drift_checks
+            .filter(status.eq(HoldingsDriftStatus::Pending.as_str()))
+            .order(created_at.asc())
+            .load::<HoldingsDriftCheckDB>(&mut conn)
+            .map_err(StorageError::from)?;
+        Ok(to_domain(rows))
+    }
+
+    fn list_checks(
+        &self,
+        filter_account_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<HoldingsDriftCheck>> {
+        let mut conn = get_connection(&self.pool)?;
+        let mut query = holdings_drift_checks.into_boxed();
+        if let Some(filter_account_id) = filter_account_id {
+            query = query.filter(account_id.eq(filter_account_id.to_string()));
+        }
+        let rows = query
+            .order(created_at.desc())
+            .limit(limit)
+            .load::<HoldingsDriftCheckDB>(&mut conn)
+            .map_err(StorageError::from)?;
+        Ok(to_domain(rows))
+    }
+
+    fn latest_checked(&self) -> Result<Vec<HoldingsDriftCheck>> {
+        let mut conn = get_connection(&self.pool)?;
+        let rows = holdings_drift_checks
+            .filter(status.eq(HoldingsDriftStatus::Checked.as_str()))
+            .order(created_at.desc())
+            .load::<HoldingsDriftCheckDB>(&mut conn)
+            .map_err(StorageError::from)?;
+
+        let mut seen = HashSet::new();
+        Ok(to_domain(rows)
+            .into_iter()
+            .filter(|check| seen.insert(check.account_id.clone()))
+            .collect())
+    }
+}
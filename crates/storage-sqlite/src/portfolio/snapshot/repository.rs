@@ -306,7 +306,7 @@ impl SnapshotRepository {
                 .map_err(StorageError::from)?;
 
                 for row in &existing_rows {
-                    tx.delete_model(row);
+                    tx.delete_model(row)?;
                 }
 
                 Ok(())
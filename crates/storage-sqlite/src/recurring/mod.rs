@@ -0,0 +1,7 @@
+//! SQLite storage implementation for recurring activity templates.
+
+mod model;
+mod repository;
+
+pub use model::RecurringActivityTemplateDB;
+pub use repository::RecurringActivityRepository;
@@ -0,0 +1,134 @@
+//! Database models for recurring activity templates.
+
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use wealthfolio_core::activities::ActivityStatus;
+use wealthfolio_core::recurring::{BusinessDayAdjustment, RecurringActivityTemplate};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Database model for recurring activity templates
+#[derive(
+    Queryable,
+    Identifiable,
+    Insertable,
+    AsChangeset,
+    Selectable,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+)]
+#[diesel(table_name = crate::schema::recurring_activity_templates)]
+#[diesel(primary_key(id))]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringActivityTemplateDB {
+    pub id: String,
+    pub account_id: String,
+    pub name: String,
+    pub activity_type: String,
+    pub subtype: Option<String>,
+    pub asset_id: Option<String>,
+    pub quantity: Option<String>,
+    pub unit_price: Option<String>,
+    pub amount: Option<String>,
+    pub amount_formula: Option<String>,
+    pub fee: Option<String>,
+    pub currency: String,
+    pub notes: Option<String>,
+    pub rrule: String,
+    pub start_date: String,
+    pub end_date: Option<String>,
+    pub business_day_adjustment: String,
+    pub generate_status: String,
+    pub is_active: bool,
+    pub last_generated_date: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+fn parse_decimal(value: Option<String>) -> Option<Decimal> {
+    value.and_then(|v| Decimal::from_str(&v).ok())
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, DATE_FORMAT).ok()
+}
+
+fn format_date(value: NaiveDate) -> String {
+    value.format(DATE_FORMAT).to_string()
+}
+
+impl RecurringActivityTemplateDB {
+    /// Converts to the domain model, skipping rows with an unreadable start date.
+    pub fn into_domain(self) -> Option<RecurringActivityTemplate> {
+        let start_date = parse_date(&self.start_date)?;
+        let generate_status = match self.generate_status.as_str() {
+            "PENDING" => ActivityStatus::Pending,
+            _ => ActivityStatus::Posted,
+        };
+        Some(RecurringActivityTemplate {
+            id: self.id,
+            account_id: self.account_id,
+            name: self.name,
+            activity_type: self.activity_type,
+            subtype: self.subtype,
+            asset_id: self.asset_id,
+            quantity: parse_decimal(self.quantity),
+            unit_price: parse_decimal(self.unit_price),
+            amount: parse_decimal(self.amount),
+            amount_formula: self.amount_formula,
+            fee: parse_decimal(self.fee),
+            currency: self.currency,
+            notes: self.notes,
+            rrule: self.rrule,
+            start_date,
+            end_date: self.end_date.as_deref().and_then(parse_date),
+            business_day_adjustment: BusinessDayAdjustment::parse(&self.business_day_adjustment),
+            generate_status,
+            is_active: self.is_active,
+            last_generated_date: self.last_generated_date.as_deref().and_then(parse_date),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+impl From<RecurringActivityTemplate> for RecurringActivityTemplateDB {
+    fn from(domain: RecurringActivityTemplate) -> Self {
+        Self {
+            id: domain.id,
+            account_id: domain.account_id,
+            name: domain.name,
+            activity_type: domain.activity_type,
+            subtype: domain.subtype,
+            asset_id: domain.asset_id,
+            quantity: domain.quantity.map(|d| d.to_string()),
+            unit_price: domain.unit_price.map(|d| d.to_string()),
+            amount: domain.amount.map(|d| d.to_string()),
+            amount_formula: domain.amount_formula,
+            fee: domain.fee.map(|d| d.to_string()),
+            currency: domain.currency,
+            notes: domain.notes,
+            rrule: domain.rrule,
+            start_date: format_date(domain.start_date),
+            end_date: domain.end_date.map(format_date),
+            business_day_adjustment: domain.business_day_adjustment.as_str().to_string(),
+            generate_status: match domain.generate_status {
+                ActivityStatus::Pending => "PENDING",
+                _ => "POSTED",
+            }
+            .to_string(),
+            is_active: domain.is_active,
+            last_generated_date: domain.last_generated_date.map(format_date),
+            created_at: domain.created_at,
+            updated_at: domain.updated_at,
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Repository for recurring activity templates.
+
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{self, Pool};
+use diesel::SqliteConnection;
+use std::sync::Arc;
+
+use wealthfolio_core::errors::Error;
+use wealthfolio_core::recurring::{RecurringActivityRepositoryTrait, RecurringActivityTemplate};
+use wealthfolio_core::Result;
+
+use super::model::RecurringActivityTemplateDB;
+use crate::db::{get_connection, WriteHandle};
+use crate::errors::StorageError;
+use crate::schema::recurring_activity_templates;
+use crate::schema::recurring_activity_templates::dsl::*;
+
+pub struct RecurringActivityRepository {
+    pool: Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
+    writer: WriteHandle,
+}
+
+impl RecurringActivityRepository {
+    pub fn new(
+        pool: Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
+        writer: WriteHandle,
+    ) -> Self {
+        Self { pool, writer }
+    }
+}
+
+fn to_domain(row: RecurringActivityTemplateDB) -> Result<RecurringActivityTemplate> {
+    let template_id = row.id.clone();
+    row.into_domain().ok_or_else(|| {
+        Error::Unexpected(format!(
+            "Recurring activity template {} has an invalid start date",
+            template_id
+        ))
+    })
+}
+
+#[async_trait]
+impl RecurringActivityRepositoryTrait for RecurringActivityRepository {
+    fn list_templates(&self) -> Result<Vec<RecurringActivityTemplate>> {
+        let mut conn = get_connection(&self.pool)?;
+        let rows = recurring_activity_templates
+            .order(name.asc())
+            .load::<RecurringActivityTemplateDB>(&mut conn)
+            .map_err(StorageError::from)?;
+        Ok(rows
+            .into_iter()
+            .filter_map(RecurringActivityTemplateDB::into_domain)
+            .collect())
+    }
+
+    fn get_template(&self, template_id: &str) -> Result<RecurringActivityTemplate> {
+        let mut conn = get_connection(&self.pool)?;
+        let row = recurring_activity_templates
+            .find(template_id)
+            .first::<RecurringActivityTemplateDB>(&mut conn)
+            .map_err(StorageError::from)?;
+        to_domain(row)
+    }
+
+    async fn insert_template(
+        &self,
+        template: RecurringActivityTemplate,
+    ) -> Result<RecurringActivityTemplate> {
+        let row: RecurringActivityTemplateDB = template.into();
+
+        self.writer
+            .exec(
+                move |conn: &mut SqliteConnection| -> Result<RecurringActivityTemplate> {
+                    let inserted = diesel::insert_into(recurring_activity_templates::table)
+                        .values(&row)
+                        .get_result::<RecurringActivityTemplateDB>(conn)
+                        .map_err(StorageError::from)?;
+                    to_domain(inserted)
+                },
+            )
+            .await
+    }
+
+    async fn update_template(
+        &self,
+        template: RecurringActivityTemplate,
+    ) -> Result<RecurringActivityTemplate> {
+        let row: RecurringActivityTemplateDB = template.into();
+
+        self.writer
+            .exec(
+                move |conn: &mut SqliteConnection| -> Result<RecurringActivityTemplate> {
+                    let updated = diesel::update(recurring_activity_templates.find(row.id.clone()))
+                        .set(&row)
+                        .get_result::<RecurringActivityTemplateDB>(conn)
+                        .map_err(StorageError::from)?;
+                    to_domain(updated)
+                },
+            )
+            .await
+    }
+
+    async fn delete_template(&self, template_id: &str) -> Result<usize> {
+        let template_id = template_id.to_string();
+
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<usize> {
+                let deleted = diesel::delete(recurring_activity_templates.find(template_id))
+                    .execute(conn)
+                    .map_err(StorageError::from)?;
+                Ok(deleted)
+            })
+            .await
+    }
+
+    async fn set_last_generated_date(&self, template_id: &str, date: NaiveDate) -> Result<()> {
+        let template_id = template_id.to_string();
+
+        self.writer
+            .exec(move |conn: &mut SqliteConnection| -> Result<()> {
+                diesel::update(recurring_activity_templates.find(template_id))
+                    .set((
+                        last_generated_date.eq(Some(date.format("%Y-%m-%d").to_string())),
+                        updated_at.eq(Utc::now().naive_utc()),
+                    ))
+                    .execute(conn)
+                    .map_err(StorageError::from)?;
+                Ok(())
+            })
+            .await
+    }
+}
@@ -53,6 +53,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    activity_duplicate_decisions (pair_key) {
+        pair_key -> Text,
+        activity_id -> Text,
+        other_activity_id -> Text,
+        decision -> Text,
+        decided_at -> Text,
+    }
+}
+
 diesel::table! {
     activity_import_profiles (account_id) {
         account_id -> Text,
@@ -70,6 +80,10 @@ diesel::table! {
         role -> Text,
         content_json -> Text,
         created_at -> Text,
+        provider_id -> Nullable<Text>,
+        model_id -> Nullable<Text>,
+        prompt_tokens -> Nullable<Integer>,
+        completion_tokens -> Nullable<Integer>,
     }
 }
 
@@ -90,6 +104,7 @@ diesel::table! {
         updated_at -> Text,
         config_snapshot -> Nullable<Text>,
         is_pinned -> Integer,
+        summary_json -> Nullable<Text>,
     }
 }
 
@@ -134,6 +149,20 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    attachments (id) {
+        id -> Text,
+        entity_type -> Text,
+        entity_id -> Text,
+        file_name -> Text,
+        content_type -> Nullable<Text>,
+        size_bytes -> BigInt,
+        content_hash -> Text,
+        notes -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     brokers_sync_state (account_id, provider) {
         account_id -> Text,
@@ -149,6 +178,21 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    change_log (id) {
+        id -> Text,
+        entity_type -> Text,
+        entity_id -> Text,
+        operation -> Text,
+        source -> Text,
+        source_ref -> Nullable<Text>,
+        account_id -> Nullable<Text>,
+        before_json -> Nullable<Text>,
+        after_json -> Nullable<Text>,
+        created_at -> Text,
+    }
+}
+
 diesel::table! {
     contribution_limits (id) {
         id -> Text,
@@ -253,6 +297,21 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    holdings_drift_checks (id) {
+        id -> Text,
+        account_id -> Text,
+        as_of_date -> Text,
+        status -> Text,
+        statement -> Text,
+        report -> Nullable<Text>,
+        discrepancy_count -> Integer,
+        error -> Nullable<Text>,
+        created_at -> Timestamp,
+        checked_at -> Nullable<Timestamp>,
+    }
+}
+
 diesel::table! {
     holdings_snapshots (id) {
         id -> Text,
@@ -386,6 +445,35 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    sync_field_conflicts (id) {
+        id -> Text,
+        entity -> Text,
+        entity_id -> Text,
+        field -> Text,
+        local_value -> Text,
+        local_updated_at -> Text,
+        remote_value -> Text,
+        remote_updated_at -> Text,
+        remote_event_id -> Text,
+        resolution -> Nullable<Text>,
+        resolved_value -> Nullable<Text>,
+        created_at -> Text,
+        resolved_at -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    sync_field_versions (entity, entity_id, field) {
+        entity -> Text,
+        entity_id -> Text,
+        field -> Text,
+        value -> Text,
+        updated_at -> Text,
+        event_id -> Text,
+    }
+}
+
 diesel::table! {
     sync_outbox (event_id) {
         event_id -> Text,
@@ -434,6 +522,56 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    recurring_activity_templates (id) {
+        id -> Text,
+        account_id -> Text,
+        name -> Text,
+        activity_type -> Text,
+        subtype -> Nullable<Text>,
+        asset_id -> Nullable<Text>,
+        quantity -> Nullable<Text>,
+        unit_price -> Nullable<Text>,
+        amount -> Nullable<Text>,
+        amount_formula -> Nullable<Text>,
+        fee -> Nullable<Text>,
+        currency -> Text,
+        notes -> Nullable<Text>,
+        rrule -> Text,
+        start_date -> Text,
+        end_date -> Nullable<Text>,
+        business_day_adjustment -> Text,
+        generate_status -> Text,
+        is_active -> Bool,
+        last_generated_date -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    rejected_activity_keys (account_id, idempotency_key) {
+        account_id -> Text,
+        idempotency_key -> Text,
+        import_run_id -> Text,
+        rejected_at -> Text,
+    }
+}
+
+diesel::table! {
+    staged_activities (id) {
+        id -> Text,
+        import_run_id -> Text,
+        account_id -> Text,
+        idempotency_key -> Text,
+        payload -> Text,
+        status -> Text,
+        edited -> Integer,
+        created_at -> Text,
+        updated_at -> Text,
+    }
+}
+
 diesel::table! {
     taxonomies (id) {
         id -> Text,
@@ -474,13 +612,19 @@ diesel::joinable!(brokers_sync_state -> accounts (account_id));
 diesel::joinable!(brokers_sync_state -> import_runs (last_run_id));
 diesel::joinable!(goals_allocation -> accounts (account_id));
 diesel::joinable!(goals_allocation -> goals (goal_id));
+diesel::joinable!(holdings_drift_checks -> accounts (account_id));
 diesel::joinable!(import_runs -> accounts (account_id));
 diesel::joinable!(quotes -> assets (asset_id));
+diesel::joinable!(recurring_activity_templates -> accounts (account_id));
+diesel::joinable!(rejected_activity_keys -> accounts (account_id));
+diesel::joinable!(staged_activities -> accounts (account_id));
+diesel::joinable!(staged_activities -> import_runs (import_run_id));
 diesel::joinable!(taxonomy_categories -> taxonomies (taxonomy_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     accounts,
     activities,
+    activity_duplicate_decisions,
     activity_import_profiles,
     ai_messages,
     ai_thread_tags,
@@ -488,7 +632,9 @@ diesel::allow_tables_to_appear_in_same_query!(
     app_settings,
     asset_taxonomy_assignments,
     assets,
+    attachments,
     brokers_sync_state,
+    change_log,
     contribution_limits,
     daily_account_valuation,
     folder_sync_config,
@@ -498,17 +644,23 @@ diesel::allow_tables_to_appear_in_same_query!(
     goals,
     goals_allocation,
     health_issue_dismissals,
+    holdings_drift_checks,
     holdings_snapshots,
     import_runs,
     market_data_providers,
     platforms,
     quote_sync_state,
     quotes,
+    recurring_activity_templates,
+    rejected_activity_keys,
+    staged_activities,
     sync_applied_events,
     sync_cursor,
     sync_device_config,
     sync_engine_state,
     sync_entity_metadata,
+    sync_field_conflicts,
+    sync_field_versions,
     sync_outbox,
     sync_table_state,
     taxonomies,
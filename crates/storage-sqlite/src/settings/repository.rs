@@ -64,88 +64,46 @@ impl SettingsRepositoryTrait for SettingsRepository {
 
     async fn update_settings(&self, new_settings: &SettingsUpdate) -> Result<()> {
         let settings = new_settings.clone();
-        self.writer
-            .exec(move |conn| {
-                if let Some(ref theme) = settings.theme {
-                    diesel::replace_into(app_settings)
-                        .values(&AppSettingDB {
-                            setting_key: "theme".to_string(),
-                            setting_value: theme.clone(),
-                        })
-                        .execute(conn)
-                        .map_err(StorageError::from)?;
-                }
-
-                if let Some(ref font) = settings.font {
-                    diesel::replace_into(app_settings)
-                        .values(&AppSettingDB {
-                            setting_key: "font".to_string(),
-                            setting_value: font.clone(),
-                        })
-                        .execute(conn)
-                        .map_err(StorageError::from)?;
-                }
-
-                if let Some(ref base_currency) = settings.base_currency {
-                    diesel::replace_into(app_settings)
-                        .values(&AppSettingDB {
-                            setting_key: "base_currency".to_string(),
-                            setting_value: base_currency.clone(),
-                        })
-                        .execute(conn)
-                        .map_err(StorageError::from)?;
-                }
-
-                if let Some(onboarding_completed) = settings.onboarding_completed {
-                    diesel::replace_into(app_settings)
-                        .values(&AppSettingDB {
-                            setting_key: "onboarding_completed".to_string(),
-                            setting_value: onboarding_completed.to_string(),
-                        })
-                        .execute(conn)
-                        .map_err(StorageError::from)?;
-                }
-
-                if let Some(auto_update_check_enabled) = settings.auto_update_check_enabled {
-                    diesel::replace_into(app_settings)
-                        .values(&AppSettingDB {
-                            setting_key: "auto_update_check_enabled".to_string(),
-                            setting_value: auto_update_check_enabled.to_string(),
-                        })
-                        .execute(conn)
-                        .map_err(StorageError::from)?;
-                }
-
-                if let Some(menu_bar_visible) = settings.menu_bar_visible {
-                    diesel::replace_into(app_settings)
-                        .values(&AppSettingDB {
-                            setting_key: "menu_bar_visible".to_string(),
-                            setting_value: menu_bar_visible.to_string(),
-                        })
-                        .execute(conn)
-                        .map_err(StorageError::from)?;
-                }
-
-                if let Some(sync_enabled) = settings.sync_enabled {
-                    diesel::replace_into(app_settings)
-                        .values(&AppSettingDB {
-                            setting_key: "sync_enabled".to_string(),
-                            setting_value: sync_enabled.to_string(),
-                        })
-                        .execute(conn)
-                        .map_err(StorageError::from)?;
-                }
+        let mut rows = Vec::new();
+        let mut push = |key: &str, value: Option<String>| {
+            if let Some(value) = value {
+                rows.push(AppSettingDB {
+                    setting_key: key.to_string(),
+                    setting_value: value,
+                });
+            }
+        };
+        push("theme", settings.theme);
+        push("font", settings.font);
+        push("base_currency", settings.base_currency);
+        push(
+            "onboarding_completed",
+            settings.onboarding_completed.map(|v| v.to_string()),
+        );
+        push(
+            "auto_update_check_enabled",
+            settings.auto_update_check_enabled.map(|v| v.to_string()),
+        );
+        push(
+            "menu_bar_visible",
+            settings.menu_bar_visible.map(|v| v.to_string()),
+        );
+        push("sync_enabled", settings.sync_enabled.map(|v| v.to_string()));
+        push(
+            "wealthfolio_connect_visible",
+            settings.wealthfolio_connect_visible.map(|v| v.to_string()),
+        );
 
-                if let Some(wealthfolio_connect_visible) = settings.wealthfolio_connect_visible {
+        self.writer
+            .exec_tx(move |tx| -> Result<()> {
+                for row in &rows {
                     diesel::replace_into(app_settings)
-                        .values(&AppSettingDB {
-                            setting_key: "wealthfolio_connect_visible".to_string(),
-                            setting_value: wealthfolio_connect_visible.to_string(),
-                        })
-                        .execute(conn)
+                        .values(row)
+                        .execute(tx.conn())
                         .map_err(StorageError::from)?;
+                    // Only shared keys reach the outbox; see `APP_SYNC_SETTING_KEYS`.
+                    tx.update(row)?;
                 }
-
                 Ok(())
             })
             .await
@@ -187,14 +145,16 @@ impl SettingsRepositoryTrait for SettingsRepository {
         let value = setting_value_param.to_string();
 
         self.writer
-            .exec(move |conn| {
+            .exec_tx(move |tx| -> Result<()> {
+                let row = AppSettingDB {
+                    setting_key: key,
+                    setting_value: value,
+                };
                 diesel::replace_into(app_settings)
-                    .values(AppSettingDB {
-                        setting_key: key.clone(),     // Ensure key is cloned if used after move
-                        setting_value: value.clone(), // Ensure value is cloned if used after move
-                    })
-                    .execute(conn)
+                    .values(&row)
+                    .execute(tx.conn())
                     .map_err(StorageError::from)?;
+                tx.update(&row)?;
                 Ok(())
             })
             .await
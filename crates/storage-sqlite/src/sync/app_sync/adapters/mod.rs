@@ -3,7 +3,7 @@
 //! In v2, most replay is handled by a generic rowset applier in `AppSyncRepository`.
 //! This module is the stable extension point for richer per-entity semantics.
 
-use wealthfolio_core::sync::SyncEntity;
+use wealthfolio_core::sync::{is_synced_setting_key, SyncEntity};
 
 #[derive(Debug, Clone)]
 pub struct EntityAdapterDescriptor {
@@ -69,5 +69,74 @@ pub fn default_adapter_descriptors() -> Vec<EntityAdapterDescriptor> {
             entity: SyncEntity::Snapshot,
             table_name: "holdings_snapshots",
         },
+        EntityAdapterDescriptor {
+            entity: SyncEntity::Taxonomy,
+            table_name: "taxonomies",
+        },
+        EntityAdapterDescriptor {
+            entity: SyncEntity::TaxonomyCategory,
+            table_name: "taxonomy_categories",
+        },
+        EntityAdapterDescriptor {
+            entity: SyncEntity::AppSetting,
+            table_name: "app_settings",
+        },
+        EntityAdapterDescriptor {
+            entity: SyncEntity::HealthIssueDismissal,
+            table_name: "health_issue_dismissals",
+        },
+        EntityAdapterDescriptor {
+            entity: SyncEntity::MarketDataProvider,
+            table_name: "market_data_providers",
+        },
     ]
 }
+
+/// Replay rules for entities whose sync id is derived from natural key columns.
+#[derive(Debug, Clone, Copy)]
+pub struct KeyedEntityAdapter {
+    pub entity: SyncEntity,
+    pub table_name: &'static str,
+    /// Key columns, in the order used to derive the sync id.
+    pub key_columns: &'static [&'static str],
+    /// Columns replayed from remote payloads. `None` replays every column.
+    pub synced_columns: Option<&'static [&'static str]>,
+    /// Only update rows that already exist locally, e.g. rows seeded by migrations.
+    pub update_only: bool,
+}
+
+impl KeyedEntityAdapter {
+    /// Whether a remote row with these key values may be applied locally.
+    pub fn accepts_key(&self, key_values: &[&str]) -> bool {
+        match self.entity {
+            SyncEntity::AppSetting => key_values
+                .first()
+                .is_some_and(|key| is_synced_setting_key(key)),
+            _ => true,
+        }
+    }
+}
+
+pub fn keyed_entity_adapter(entity: &SyncEntity) -> Option<KeyedEntityAdapter> {
+    let adapter = |table_name, key_columns| KeyedEntityAdapter {
+        entity: *entity,
+        table_name,
+        key_columns,
+        synced_columns: None,
+        update_only: false,
+    };
+    match entity {
+        SyncEntity::Taxonomy => Some(adapter("taxonomies", &["id"])),
+        SyncEntity::TaxonomyCategory => {
+            Some(adapter("taxonomy_categories", &["taxonomy_id", "id"]))
+        }
+        SyncEntity::AppSetting => Some(adapter("app_settings", &["setting_key"])),
+        SyncEntity::HealthIssueDismissal => Some(adapter("health_issue_dismissals", &["issue_id"])),
+        SyncEntity::MarketDataProvider => Some(KeyedEntityAdapter {
+            synced_columns: Some(&["priority", "enabled"]),
+            update_only: true,
+            ..adapter("market_data_providers", &["id"])
+        }),
+        _ => None,
+    }
+}
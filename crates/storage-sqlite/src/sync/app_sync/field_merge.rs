@@ -0,0 +1,496 @@
+//! Per-field version tracking and conflict records for device sync.
+
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use wealthfolio_core::errors::{DatabaseError, Error, Result};
+use wealthfolio_core::sync::{
+    merge_field, FieldMergeDecision, ResolveSyncConflictRequest, SyncConflictResolution,
+    SyncEntity, SyncFieldConflict, SyncFieldState, SyncFieldVersion, SyncFieldVersions,
+    SyncOperation, SYNC_FIELD_VERSIONS_KEY,
+};
+
+use crate::errors::StorageError;
+use crate::schema::{sync_field_conflicts, sync_field_versions};
+
+use super::model::{SyncFieldConflictDB, SyncFieldVersionDB};
+use super::repository::{
+    entity_storage_mapping, enum_from_db, enum_to_db, escape_sqlite_str, insert_outbox_event,
+    json_value_to_sql_literal, load_table_columns, quote_identifier, OutboxWriteRequest,
+};
+
+fn internal(message: impl Into<String>) -> Error {
+    Error::Database(DatabaseError::Internal(message.into()))
+}
+
+fn load_field_states(
+    conn: &mut SqliteConnection,
+    entity_db: &str,
+    entity_id: &str,
+) -> Result<HashMap<String, SyncFieldState>> {
+    sync_field_versions::table
+        .filter(sync_field_versions::entity.eq(entity_db))
+        .filter(sync_field_versions::entity_id.eq(entity_id))
+        .load::<SyncFieldVersionDB>(conn)
+        .map_err(StorageError::from)?
+        .into_iter()
+        .map(|row| {
+            Ok((
+                row.field,
+                SyncFieldState {
+                    value: serde_json::from_str(&row.value)?,
+                    updated_at: row.updated_at,
+                    event_id: row.event_id,
+                },
+            ))
+        })
+        .collect()
+}
+
+fn upsert_field_version(
+    conn: &mut SqliteConnection,
+    entity_db: &str,
+    entity_id: &str,
+    field: &str,
+    value: &serde_json::Value,
+    updated_at: &str,
+    event_id: &str,
+) -> Result<()> {
+    let row = SyncFieldVersionDB {
+        entity: entity_db.to_string(),
+        entity_id: entity_id.to_string(),
+        field: field.to_string(),
+        value: serde_json::to_string(value)?,
+        updated_at: updated_at.to_string(),
+        event_id: event_id.to_string(),
+    };
+    diesel::insert_into(sync_field_versions::table)
+        .values(&row)
+        .on_conflict((
+            sync_field_versions::entity,
+            sync_field_versions::entity_id,
+            sync_field_versions::field,
+        ))
+        .do_update()
+        .set(&row)
+        .execute(conn)
+        .map_err(StorageError::from)?;
+    Ok(())
+}
+
+/// Records the fields a local write changed and returns the change map that
+/// travels with its outbox event.
+pub(super) fn record_local_field_versions(
+    conn: &mut SqliteConnection,
+    entity: &SyncEntity,
+    entity_id: &str,
+    payload: &serde_json::Map<String, serde_json::Value>,
+    client_timestamp: &str,
+    event_id: &str,
+) -> Result<SyncFieldVersions> {
+    let pk_name = entity_storage_mapping(entity).map(|(_, pk)| pk);
+    let entity_db = enum_to_db(entity)?;
+    let states = load_field_states(conn, &entity_db, entity_id)?;
+
+    let mut versions = SyncFieldVersions::new();
+    for (field, value) in payload {
+        if Some(field.as_str()) == pk_name {
+            continue;
+        }
+        let previous = states.get(field);
+        if previous.is_some_and(|state| &state.value == value) {
+            continue;
+        }
+        upsert_field_version(
+            conn,
+            &entity_db,
+            entity_id,
+            field,
+            value,
+            client_timestamp,
+            event_id,
+        )?;
+        versions.insert(
+            field.clone(),
+            SyncFieldVersion {
+                updated_at: client_timestamp.to_string(),
+                base: previous.map(|state| state.updated_at.clone()),
+            },
+        );
+    }
+    Ok(versions)
+}
+
+/// Stores the values of a remote event applied as a whole.
+pub(super) fn record_remote_field_values(
+    conn: &mut SqliteConnection,
+    entity_db: &str,
+    entity_id: &str,
+    fields: &[(String, serde_json::Value)],
+    versions: Option<&SyncFieldVersions>,
+    client_timestamp: &str,
+    event_id: &str,
+) -> Result<()> {
+    for (field, value) in fields {
+        let updated_at = versions
+            .and_then(|versions| versions.get(field))
+            .map(|version| version.updated_at.as_str())
+            .unwrap_or(client_timestamp);
+        upsert_field_version(
+            conn, entity_db, entity_id, field, value, updated_at, event_id,
+        )?;
+    }
+    Ok(())
+}
+
+/// Merges the changed fields of a remote event into an existing row.
+///
+/// Returns the fields to write. Fields changed on both sides keep their local
+/// value and get an open conflict.
+pub(super) fn merge_remote_fields(
+    conn: &mut SqliteConnection,
+    entity_db: &str,
+    entity_id: &str,
+    event_id: &str,
+    fields: &[(String, serde_json::Value)],
+    versions: &SyncFieldVersions,
+) -> Result<Vec<(String, serde_json::Value)>> {
+    let states = load_field_states(conn, entity_db, entity_id)?;
+    let mut merged = Vec::new();
+
+    for (field, value) in fields {
+        let Some(version) = versions.get(field) else {
+            continue;
+        };
+        let local = states.get(field);
+        match merge_field(field, local, value, version, event_id) {
+            FieldMergeDecision::TakeRemote => {
+                upsert_field_version(
+                    conn,
+                    entity_db,
+                    entity_id,
+                    field,
+                    value,
+                    &version.updated_at,
+                    event_id,
+                )?;
+                supersede_open_conflict(conn, entity_db, entity_id, field, value)?;
+                merged.push((field.clone(), value.clone()));
+            }
+            FieldMergeDecision::KeepLocal => {}
+            FieldMergeDecision::Conflict => {
+                if let Some(local) = local {
+                    record_conflict(
+                        conn, entity_db, entity_id, field, local, value, version, event_id,
+                    )?;
+                }
+            }
+        }
+    }
+    Ok(merged)
+}
+
+fn find_open_conflict(
+    conn: &mut SqliteConnection,
+    entity_db: &str,
+    entity_id: &str,
+    field: &str,
+) -> Result<Option<SyncFieldConflictDB>> {
+    Ok(sync_field_conflicts::table
+        .filter(sync_field_conflicts::entity.eq(entity_db))
+        .filter(sync_field_conflicts::entity_id.eq(entity_id))
+        .filter(sync_field_conflicts::field.eq(field))
+        .filter(sync_field_conflicts::resolution.is_null())
+        .first::<SyncFieldConflictDB>(conn)
+        .optional()
+        .map_err(StorageError::from)?)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn record_conflict(
+    conn: &mut SqliteConnection,
+    entity_db: &str,
+    entity_id: &str,
+    field: &str,
+    local: &SyncFieldState,
+    remote_value: &serde_json::Value,
+    remote: &SyncFieldVersion,
+    remote_event_id: &str,
+) -> Result<()> {
+    let row = SyncFieldConflictDB {
+        id: Uuid::now_v7().to_string(),
+        entity: entity_db.to_string(),
+        entity_id: entity_id.to_string(),
+        field: field.to_string(),
+        local_value: serde_json::to_string(&local.value)?,
+        local_updated_at: local.updated_at.clone(),
+        remote_value: serde_json::to_string(remote_value)?,
+        remote_updated_at: remote.updated_at.clone(),
+        remote_event_id: remote_event_id.to_string(),
+        resolution: None,
+        resolved_value: None,
+        created_at: Utc::now().to_rfc3339(),
+        resolved_at: None,
+    };
+
+    // One open conflict per field; a newer remote value replaces the older one.
+    if let Some(existing) = find_open_conflict(conn, entity_db, entity_id, field)? {
+        diesel::update(sync_field_conflicts::table.find(existing.id))
+            .set((
+                sync_field_conflicts::local_value.eq(row.local_value),
+                sync_field_conflicts::local_updated_at.eq(row.local_updated_at),
+                sync_field_conflicts::remote_value.eq(row.remote_value),
+                sync_field_conflicts::remote_updated_at.eq(row.remote_updated_at),
+                sync_field_conflicts::remote_event_id.eq(row.remote_event_id),
+            ))
+            .execute(conn)
+            .map_err(StorageError::from)?;
+        return Ok(());
+    }
+
+    diesel::insert_into(sync_field_conflicts::table)
+        .values(&row)
+        .execute(conn)
+        .map_err(StorageError::from)?;
+    Ok(())
+}
+
+fn supersede_open_conflict(
+    conn: &mut SqliteConnection,
+    entity_db: &str,
+    entity_id: &str,
+    field: &str,
+    value: &serde_json::Value,
+) -> Result<()> {
+    let Some(existing) = find_open_conflict(conn, entity_db, entity_id, field)? else {
+        return Ok(());
+    };
+    diesel::update(sync_field_conflicts::table.find(existing.id))
+        .set((
+            sync_field_conflicts::resolution
+                .eq(Some(enum_to_db(&SyncConflictResolution::Superseded)?)),
+            sync_field_conflicts::resolved_value.eq(Some(serde_json::to_string(value)?)),
+            sync_field_conflicts::resolved_at.eq(Some(Utc::now().to_rfc3339())),
+        ))
+        .execute(conn)
+        .map_err(StorageError::from)?;
+    Ok(())
+}
+
+/// Drops field state and open conflicts of a deleted entity.
+pub(super) fn clear_entity_field_state(
+    conn: &mut SqliteConnection,
+    entity_db: &str,
+    entity_id: &str,
+) -> Result<()> {
+    diesel::delete(
+        sync_field_versions::table
+            .filter(sync_field_versions::entity.eq(entity_db))
+            .filter(sync_field_versions::entity_id.eq(entity_id)),
+    )
+    .execute(conn)
+    .map_err(StorageError::from)?;
+    diesel::delete(
+        sync_field_conflicts::table
+            .filter(sync_field_conflicts::entity.eq(entity_db))
+            .filter(sync_field_conflicts::entity_id.eq(entity_id))
+            .filter(sync_field_conflicts::resolution.is_null()),
+    )
+    .execute(conn)
+    .map_err(StorageError::from)?;
+    Ok(())
+}
+
+/// Drops all field state, e.g. before restoring a snapshot baseline.
+pub(super) fn clear_all_field_state(conn: &mut SqliteConnection) -> Result<()> {
+    diesel::delete(sync_field_versions::table)
+        .execute(conn)
+        .map_err(StorageError::from)?;
+    diesel::delete(sync_field_conflicts::table.filter(sync_field_conflicts::resolution.is_null()))
+        .execute(conn)
+        .map_err(StorageError::from)?;
+    Ok(())
+}
+
+#[derive(diesel::QueryableByName)]
+struct RowJson {
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    row_json: String,
+}
+
+/// Loads a synced row as a JSON object keyed by column name.
+pub(super) fn load_entity_row(
+    conn: &mut SqliteConnection,
+    table_name: &str,
+    pk_name: &str,
+    entity_id: &str,
+) -> Result<Option<serde_json::Map<String, serde_json::Value>>> {
+    let pairs = load_table_columns(conn, "main", table_name)?
+        .iter()
+        .map(|column| {
+            format!(
+                "'{}', {}",
+                escape_sqlite_str(column),
+                quote_identifier(column)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!(
+        "SELECT json_object({pairs}) AS row_json FROM {} WHERE {} = '{}'",
+        quote_identifier(table_name),
+        quote_identifier(pk_name),
+        escape_sqlite_str(entity_id)
+    );
+    let row = diesel::sql_query(sql)
+        .get_result::<RowJson>(conn)
+        .optional()
+        .map_err(StorageError::from)?;
+    row.map(|row| match serde_json::from_str(&row.row_json)? {
+        serde_json::Value::Object(fields) => Ok(fields),
+        _ => Err(internal("Synced row is not a JSON object")),
+    })
+    .transpose()
+}
+
+fn to_conflict(row: SyncFieldConflictDB) -> Result<SyncFieldConflict> {
+    Ok(SyncFieldConflict {
+        id: row.id,
+        entity: enum_from_db(&row.entity)?,
+        entity_id: row.entity_id,
+        field: row.field,
+        local_value: serde_json::from_str(&row.local_value)?,
+        local_updated_at: row.local_updated_at,
+        remote_value: serde_json::from_str(&row.remote_value)?,
+        remote_updated_at: row.remote_updated_at,
+        remote_event_id: row.remote_event_id,
+        resolution: row.resolution.as_deref().map(enum_from_db).transpose()?,
+        resolved_value: row
+            .resolved_value
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()?,
+        created_at: row.created_at,
+        resolved_at: row.resolved_at,
+    })
+}
+
+pub(super) fn list_conflicts(
+    conn: &mut SqliteConnection,
+    include_resolved: bool,
+) -> Result<Vec<SyncFieldConflict>> {
+    let mut query = sync_field_conflicts::table
+        .order(sync_field_conflicts::created_at.desc())
+        .into_boxed();
+    if !include_resolved {
+        query = query.filter(sync_field_conflicts::resolution.is_null());
+    }
+    query
+        .load::<SyncFieldConflictDB>(conn)
+        .map_err(StorageError::from)?
+        .into_iter()
+        .map(to_conflict)
+        .collect()
+}
+
+/// Writes the chosen value, records the resolution and queues an outbox event
+/// that carries the value to the other devices.
+pub(super) fn resolve_conflict(
+    conn: &mut SqliteConnection,
+    request: ResolveSyncConflictRequest,
+) -> Result<SyncFieldConflict> {
+    let row = sync_field_conflicts::table
+        .find(&request.conflict_id)
+        .first::<SyncFieldConflictDB>(conn)
+        .optional()
+        .map_err(StorageError::from)?
+        .ok_or_else(|| internal(format!("Sync conflict '{}' not found", request.conflict_id)))?;
+    if row.resolution.is_some() {
+        return Err(internal("Sync conflict is already resolved"));
+    }
+
+    let conflict = to_conflict(row)?;
+    let value = match request.resolution {
+        SyncConflictResolution::Local => conflict.local_value.clone(),
+        SyncConflictResolution::Remote => conflict.remote_value.clone(),
+        SyncConflictResolution::Manual => request
+            .value
+            .ok_or_else(|| internal("A value is required for a manual resolution"))?,
+        SyncConflictResolution::Superseded => {
+            return Err(internal("Choose local, remote or a manual value"));
+        }
+    };
+    let (table_name, pk_name) = entity_storage_mapping(&conflict.entity)
+        .ok_or_else(|| internal("Sync conflict entity is not stored locally"))?;
+
+    let sql = format!(
+        "UPDATE {} SET {} = {} WHERE {} = '{}'",
+        quote_identifier(table_name),
+        quote_identifier(&conflict.field),
+        json_value_to_sql_literal(&value),
+        quote_identifier(pk_name),
+        escape_sqlite_str(&conflict.entity_id)
+    );
+    let updated = diesel::sql_query(sql)
+        .execute(conn)
+        .map_err(StorageError::from)?;
+    if updated == 0 {
+        return Err(internal("The conflicting record no longer exists"));
+    }
+    let mut payload = load_entity_row(conn, table_name, pk_name, &conflict.entity_id)?
+        .ok_or_else(|| internal("The conflicting record no longer exists"))?;
+
+    // The new version builds on the remote one, so devices holding the remote
+    // value apply it without raising the conflict again.
+    let now = Utc::now().to_rfc3339();
+    let versions = SyncFieldVersions::from([(
+        conflict.field.clone(),
+        SyncFieldVersion {
+            updated_at: now.clone(),
+            base: Some(conflict.remote_updated_at.clone()),
+        },
+    )]);
+    payload.insert(
+        SYNC_FIELD_VERSIONS_KEY.to_string(),
+        serde_json::to_value(&versions)?,
+    );
+    let mut request_event = OutboxWriteRequest::new(
+        conflict.entity,
+        conflict.entity_id.clone(),
+        SyncOperation::Update,
+        serde_json::Value::Object(payload),
+    );
+    request_event.client_timestamp = now.clone();
+    let event_id = insert_outbox_event(conn, request_event)?;
+
+    let entity_db = enum_to_db(&conflict.entity)?;
+    upsert_field_version(
+        conn,
+        &entity_db,
+        &conflict.entity_id,
+        &conflict.field,
+        &value,
+        &now,
+        &event_id,
+    )?;
+
+    let resolution = enum_to_db(&request.resolution)?;
+    diesel::update(sync_field_conflicts::table.find(&conflict.id))
+        .set((
+            sync_field_conflicts::resolution.eq(Some(resolution)),
+            sync_field_conflicts::resolved_value.eq(Some(serde_json::to_string(&value)?)),
+            sync_field_conflicts::resolved_at.eq(Some(now.clone())),
+        ))
+        .execute(conn)
+        .map_err(StorageError::from)?;
+
+    Ok(SyncFieldConflict {
+        resolution: Some(request.resolution),
+        resolved_value: Some(value),
+        resolved_at: Some(now),
+        ..conflict
+    })
+}
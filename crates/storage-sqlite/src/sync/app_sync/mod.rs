@@ -2,6 +2,7 @@
 
 pub mod adapters;
 mod engine_ports;
+mod field_merge;
 mod model;
 mod outbox_models;
 mod outbox_projector;
@@ -10,7 +11,7 @@ mod repository;
 pub use engine_ports::SqliteSyncEngineDbPorts;
 pub use model::{
     SyncAppliedEventDB, SyncCursorDB, SyncDeviceConfigDB, SyncEngineStateDB, SyncEntityMetadataDB,
-    SyncOutboxEventDB, SyncTableStateDB,
+    SyncFieldConflictDB, SyncFieldVersionDB, SyncOutboxEventDB, SyncTableStateDB,
 };
 pub(crate) use outbox_projector::{flush_projected_outbox, ProjectedChange};
 pub use repository::{
@@ -164,3 +164,55 @@ pub struct SyncTableStateDB {
     pub last_snapshot_restore_at: Option<String>,
     pub last_incremental_apply_at: Option<String>,
 }
+
+#[derive(
+    Queryable,
+    Identifiable,
+    Insertable,
+    AsChangeset,
+    Selectable,
+    Debug,
+    Clone,
+    Serialize,
+    Deserialize,
+)]
+#[diesel(primary_key(entity, entity_id, field))]
+#[diesel(table_name = crate::schema::sync_field_versions)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct SyncFieldVersionDB {
+    pub entity: String,
+    pub entity_id: String,
+    pub field: String,
+    pub value: String,
+    pub updated_at: String,
+    pub event_id: String,
+}
+
+#[derive(
+    Queryable,
+    Identifiable,
+    Insertable,
+    AsChangeset,
+    Selectable,
+    Debug,
+    Clone,
+    Serialize,
+    Deserialize,
+)]
+#[diesel(table_name = crate::schema::sync_field_conflicts)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct SyncFieldConflictDB {
+    pub id: String,
+    pub entity: String,
+    pub entity_id: String,
+    pub field: String,
+    pub local_value: String,
+    pub local_updated_at: String,
+    pub remote_value: String,
+    pub remote_updated_at: String,
+    pub remote_event_id: String,
+    pub resolution: Option<String>,
+    pub resolved_value: Option<String>,
+    pub created_at: String,
+    pub resolved_at: Option<String>,
+}
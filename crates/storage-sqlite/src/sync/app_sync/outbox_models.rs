@@ -1,30 +1,34 @@
 //! Centralized sync-entity mappings for projected outbox models.
 
+use std::borrow::Cow;
+
 use crate::accounts::AccountDB;
 use crate::activities::{ActivityDB, ImportMappingDB};
 use crate::ai_chat::{AiMessageDB, AiThreadDB, AiThreadTagDB};
 use crate::assets::AssetDB;
 use crate::goals::{GoalDB, GoalsAllocationDB};
+use crate::health::HealthIssueDismissalDB;
 use crate::limits::ContributionLimitDB;
-use crate::market_data::QuoteDB;
+use crate::market_data::{MarketDataProviderPreferenceDB, QuoteDB};
 use crate::portfolio::snapshot::AccountStateSnapshotDB;
+use crate::settings::AppSettingDB;
 use crate::sync::platform::PlatformDB;
 use crate::sync::SyncOutboxModel;
 use crate::sync::{
     should_sync_outbox_for_account_create, should_sync_outbox_for_activity,
     should_sync_outbox_for_platform, should_sync_outbox_for_snapshot_source,
 };
-use crate::taxonomies::AssetTaxonomyAssignmentDB;
+use crate::taxonomies::{AssetTaxonomyAssignmentDB, CategoryDB, TaxonomyDB};
 use uuid::Uuid;
 use wealthfolio_core::portfolio::snapshot::SnapshotSource;
-use wealthfolio_core::sync::SyncEntity;
 use wealthfolio_core::sync::SyncOperation;
+use wealthfolio_core::sync::{derived_sync_entity_id, is_synced_setting_key, SyncEntity};
 
 impl SyncOutboxModel for AccountDB {
     const ENTITY: SyncEntity = SyncEntity::Account;
 
-    fn sync_entity_id(&self) -> &str {
-        &self.id
+    fn sync_entity_id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
     }
 
     fn should_sync_outbox(&self, op: SyncOperation) -> bool {
@@ -40,16 +44,16 @@ impl SyncOutboxModel for AccountDB {
 impl SyncOutboxModel for AssetDB {
     const ENTITY: SyncEntity = SyncEntity::Asset;
 
-    fn sync_entity_id(&self) -> &str {
-        &self.id
+    fn sync_entity_id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
     }
 }
 
 impl SyncOutboxModel for QuoteDB {
     const ENTITY: SyncEntity = SyncEntity::Quote;
 
-    fn sync_entity_id(&self) -> &str {
-        &self.id
+    fn sync_entity_id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
     }
 
     fn should_sync_outbox(&self, _op: SyncOperation) -> bool {
@@ -60,8 +64,8 @@ impl SyncOutboxModel for QuoteDB {
 impl SyncOutboxModel for ActivityDB {
     const ENTITY: SyncEntity = SyncEntity::Activity;
 
-    fn sync_entity_id(&self) -> &str {
-        &self.id
+    fn sync_entity_id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
     }
 
     fn should_sync_outbox(&self, op: SyncOperation) -> bool {
@@ -80,8 +84,8 @@ impl SyncOutboxModel for ActivityDB {
 impl SyncOutboxModel for ImportMappingDB {
     const ENTITY: SyncEntity = SyncEntity::ActivityImportProfile;
 
-    fn sync_entity_id(&self) -> &str {
-        &self.account_id
+    fn sync_entity_id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.account_id)
     }
 
     fn delete_payload(entity_id: &str) -> serde_json::Value {
@@ -92,64 +96,64 @@ impl SyncOutboxModel for ImportMappingDB {
 impl SyncOutboxModel for GoalDB {
     const ENTITY: SyncEntity = SyncEntity::Goal;
 
-    fn sync_entity_id(&self) -> &str {
-        &self.id
+    fn sync_entity_id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
     }
 }
 
 impl SyncOutboxModel for GoalsAllocationDB {
     const ENTITY: SyncEntity = SyncEntity::GoalsAllocation;
 
-    fn sync_entity_id(&self) -> &str {
-        &self.id
+    fn sync_entity_id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
     }
 }
 
 impl SyncOutboxModel for AiThreadDB {
     const ENTITY: SyncEntity = SyncEntity::AiThread;
 
-    fn sync_entity_id(&self) -> &str {
-        &self.id
+    fn sync_entity_id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
     }
 }
 
 impl SyncOutboxModel for AiMessageDB {
     const ENTITY: SyncEntity = SyncEntity::AiMessage;
 
-    fn sync_entity_id(&self) -> &str {
-        &self.id
+    fn sync_entity_id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
     }
 }
 
 impl SyncOutboxModel for AiThreadTagDB {
     const ENTITY: SyncEntity = SyncEntity::AiThreadTag;
 
-    fn sync_entity_id(&self) -> &str {
-        &self.id
+    fn sync_entity_id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
     }
 }
 
 impl SyncOutboxModel for ContributionLimitDB {
     const ENTITY: SyncEntity = SyncEntity::ContributionLimit;
 
-    fn sync_entity_id(&self) -> &str {
-        &self.id
+    fn sync_entity_id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
     }
 }
 
 impl SyncOutboxModel for AssetTaxonomyAssignmentDB {
     const ENTITY: SyncEntity = SyncEntity::AssetTaxonomyAssignment;
 
-    fn sync_entity_id(&self) -> &str {
-        &self.id
+    fn sync_entity_id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
     }
 }
 
 impl SyncOutboxModel for PlatformDB {
     const ENTITY: SyncEntity = SyncEntity::Platform;
 
-    fn sync_entity_id(&self) -> &str {
-        &self.id
+    fn sync_entity_id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
     }
 
     fn should_sync_outbox(&self, _op: SyncOperation) -> bool {
@@ -160,8 +164,8 @@ impl SyncOutboxModel for PlatformDB {
 impl SyncOutboxModel for AccountStateSnapshotDB {
     const ENTITY: SyncEntity = SyncEntity::Snapshot;
 
-    fn sync_entity_id(&self) -> &str {
-        &self.id
+    fn sync_entity_id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
     }
 
     fn should_sync_outbox(&self, _op: SyncOperation) -> bool {
@@ -175,3 +179,50 @@ impl SyncOutboxModel for AccountStateSnapshotDB {
         should_sync_outbox_for_snapshot_source(source) && Uuid::parse_str(&self.id).is_ok()
     }
 }
+
+impl SyncOutboxModel for TaxonomyDB {
+    const ENTITY: SyncEntity = SyncEntity::Taxonomy;
+
+    fn sync_entity_id(&self) -> Cow<'_, str> {
+        Cow::Owned(derived_sync_entity_id(Self::ENTITY, &[&self.id]))
+    }
+}
+
+impl SyncOutboxModel for CategoryDB {
+    const ENTITY: SyncEntity = SyncEntity::TaxonomyCategory;
+
+    fn sync_entity_id(&self) -> Cow<'_, str> {
+        Cow::Owned(derived_sync_entity_id(
+            Self::ENTITY,
+            &[&self.taxonomy_id, &self.id],
+        ))
+    }
+}
+
+impl SyncOutboxModel for AppSettingDB {
+    const ENTITY: SyncEntity = SyncEntity::AppSetting;
+
+    fn sync_entity_id(&self) -> Cow<'_, str> {
+        Cow::Owned(derived_sync_entity_id(Self::ENTITY, &[&self.setting_key]))
+    }
+
+    fn should_sync_outbox(&self, _op: SyncOperation) -> bool {
+        is_synced_setting_key(&self.setting_key)
+    }
+}
+
+impl SyncOutboxModel for HealthIssueDismissalDB {
+    const ENTITY: SyncEntity = SyncEntity::HealthIssueDismissal;
+
+    fn sync_entity_id(&self) -> Cow<'_, str> {
+        Cow::Owned(derived_sync_entity_id(Self::ENTITY, &[&self.issue_id]))
+    }
+}
+
+impl SyncOutboxModel for MarketDataProviderPreferenceDB {
+    const ENTITY: SyncEntity = SyncEntity::MarketDataProvider;
+
+    fn sync_entity_id(&self) -> Cow<'_, str> {
+        Cow::Owned(derived_sync_entity_id(Self::ENTITY, &[&self.id]))
+    }
+}
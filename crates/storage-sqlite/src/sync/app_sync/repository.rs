@@ -10,10 +10,14 @@ use uuid::Uuid;
 
 use wealthfolio_core::errors::{DatabaseError, Error, Result};
 use wealthfolio_core::sync::{
-    should_apply_lww, SyncEngineStatus, SyncEntity, SyncEntityMetadata, SyncOperation,
-    SyncOutboxEvent, SyncOutboxStatus, APP_SYNC_TABLES,
+    derived_sync_entity_id, should_apply_lww, take_field_versions, upgrade_sync_payload,
+    ResolveSyncConflictRequest, SyncEngineStatus, SyncEntity, SyncEntityMetadata,
+    SyncFieldConflict, SyncFieldVersions, SyncOperation, SyncOutboxEvent, SyncOutboxStatus,
+    APP_SYNC_SETTING_KEYS, APP_SYNC_TABLES, SYNC_FIELD_VERSIONS_KEY,
+    SYNC_PAYLOAD_SCHEMA_VERSION_KEY,
 };
 
+use crate::attachments::FileAttachmentStore;
 use crate::db::{get_connection, WriteHandle};
 use crate::errors::StorageError;
 use crate::schema::{
@@ -21,16 +25,21 @@ use crate::schema::{
     sync_outbox, sync_table_state,
 };
 
+use super::adapters::{keyed_entity_adapter, KeyedEntityAdapter};
+use super::field_merge::{
+    clear_all_field_state, clear_entity_field_state, list_conflicts, merge_remote_fields,
+    record_local_field_versions, record_remote_field_values, resolve_conflict,
+};
 use super::model::{
     SyncAppliedEventDB, SyncCursorDB, SyncDeviceConfigDB, SyncEngineStateDB, SyncEntityMetadataDB,
     SyncOutboxEventDB, SyncTableStateDB,
 };
 
-fn enum_to_db<T: serde::Serialize>(value: &T) -> Result<String> {
+pub(super) fn enum_to_db<T: serde::Serialize>(value: &T) -> Result<String> {
     Ok(serde_json::to_string(value)?.trim_matches('"').to_string())
 }
 
-fn enum_from_db<T: serde::de::DeserializeOwned>(value: &str) -> Result<T> {
+pub(super) fn enum_from_db<T: serde::de::DeserializeOwned>(value: &str) -> Result<T> {
     Ok(serde_json::from_str(&format!("\"{}\"", value))?)
 }
 
@@ -55,11 +64,11 @@ fn payload_column_catalog_cache() -> &'static Mutex<HashMap<String, PayloadColum
     CACHE.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
-fn escape_sqlite_str(value: &str) -> String {
+pub(super) fn escape_sqlite_str(value: &str) -> String {
     value.replace('\'', "''")
 }
 
-fn quote_identifier(value: &str) -> String {
+pub(super) fn quote_identifier(value: &str) -> String {
     format!("`{}`", value.replace('`', "``"))
 }
 
@@ -95,7 +104,42 @@ pub struct SyncLocalDataSummary {
     pub non_empty_tables: Vec<SyncTableRowCount>,
 }
 
-fn load_table_columns(
+/// Rows of partly shared tables that take part in snapshots. Other rows stay
+/// device-local and survive a restore.
+fn snapshot_shared_rows_filter(table: &str) -> Option<String> {
+    match table {
+        "app_settings" => Some(format!(
+            "setting_key IN ({})",
+            APP_SYNC_SETTING_KEYS
+                .iter()
+                .map(|key| format!("'{}'", escape_sqlite_str(key)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        _ => None,
+    }
+}
+
+/// Device configuration tables left out of the local data summary.
+const LOCAL_DATA_SUMMARY_EXCLUDED_TABLES: [&str; 3] = [
+    "app_settings",
+    "health_issue_dismissals",
+    "market_data_providers",
+];
+
+/// Row filters for the local data summary, so rows seeded by migrations do
+/// not count as user data.
+fn local_data_summary_filter(table: &str) -> Option<&'static str> {
+    match table {
+        "taxonomies" => Some("is_system = 0"),
+        "taxonomy_categories" => {
+            Some("taxonomy_id IN (SELECT id FROM taxonomies WHERE is_system = 0)")
+        }
+        _ => None,
+    }
+}
+
+pub(super) fn load_table_columns(
     conn: &mut SqliteConnection,
     db_name: &str,
     table_name: &str,
@@ -328,7 +372,7 @@ fn normalize_outbox_payload(payload: serde_json::Value) -> Result<serde_json::Va
     Ok(serde_json::Value::Object(normalized))
 }
 
-fn entity_storage_mapping(entity: &SyncEntity) -> Option<(&'static str, &'static str)> {
+pub(super) fn entity_storage_mapping(entity: &SyncEntity) -> Option<(&'static str, &'static str)> {
     match entity {
         SyncEntity::Account => Some(("accounts", "id")),
         SyncEntity::Asset => Some(("assets", "id")),
@@ -344,10 +388,15 @@ fn entity_storage_mapping(entity: &SyncEntity) -> Option<(&'static str, &'static
         SyncEntity::ContributionLimit => Some(("contribution_limits", "id")),
         SyncEntity::Platform => Some(("platforms", "id")),
         SyncEntity::Snapshot => Some(("holdings_snapshots", "id")),
+        SyncEntity::Taxonomy => Some(("taxonomies", "id")),
+        SyncEntity::TaxonomyCategory => Some(("taxonomy_categories", "id")),
+        SyncEntity::AppSetting => Some(("app_settings", "setting_key")),
+        SyncEntity::HealthIssueDismissal => Some(("health_issue_dismissals", "issue_id")),
+        SyncEntity::MarketDataProvider => Some(("market_data_providers", "id")),
     }
 }
 
-fn json_value_to_sql_literal(value: &serde_json::Value) -> String {
+pub(super) fn json_value_to_sql_literal(value: &serde_json::Value) -> String {
     match value {
         serde_json::Value::Null => "NULL".to_string(),
         serde_json::Value::Bool(v) => {
@@ -442,7 +491,42 @@ pub fn insert_outbox_event(
     } = request;
 
     let event_id = event_id.unwrap_or_else(|| Uuid::now_v7().to_string());
-    let payload = serde_json::to_string(&normalize_outbox_payload(payload)?)?;
+    let mut payload = payload;
+    // Conflict resolutions carry their own field versions.
+    let explicit_versions = payload
+        .as_object_mut()
+        .and_then(|fields| fields.remove(SYNC_FIELD_VERSIONS_KEY));
+    let mut payload = normalize_outbox_payload(payload)?;
+    match (op, payload.as_object_mut()) {
+        (SyncOperation::Delete, _) => {
+            clear_entity_field_state(conn, &enum_to_db(&entity)?, &entity_id)?;
+        }
+        // Keyed entities replay whole rows and keep no field versions.
+        (_, Some(fields)) if !entity.uses_derived_id() => {
+            let versions = match explicit_versions {
+                Some(versions) => versions,
+                None => serde_json::to_value(record_local_field_versions(
+                    conn,
+                    &entity,
+                    &entity_id,
+                    fields,
+                    &client_timestamp,
+                    &event_id,
+                )?)?,
+            };
+            fields.insert(SYNC_FIELD_VERSIONS_KEY.to_string(), versions);
+        }
+        _ => {}
+    }
+    if let (Some(version), Some(fields)) =
+        (entity.payload_schema_version(), payload.as_object_mut())
+    {
+        fields.insert(
+            SYNC_PAYLOAD_SCHEMA_VERSION_KEY.to_string(),
+            serde_json::Value::from(version),
+        );
+    }
+    let payload = serde_json::to_string(&payload)?;
     let now = Utc::now().to_rfc3339();
 
     let payload_key_version = resolve_payload_key_version(conn, payload_key_version)?;
@@ -531,7 +615,7 @@ fn apply_remote_event_lww_tx(
         .optional()
         .map_err(StorageError::from)?;
 
-    let should_apply = match metadata_row.as_ref() {
+    let lww_wins = match metadata_row.as_ref() {
         Some(meta) => should_apply_lww(
             &meta.last_client_timestamp,
             &meta.last_event_id,
@@ -541,95 +625,69 @@ fn apply_remote_event_lww_tx(
         None => true,
     };
 
-    if should_apply {
-        if let Some((table_name, pk_name)) = entity_storage_mapping(&entity) {
-            match op {
-                SyncOperation::Delete => {
-                    let sql = format!(
-                        "DELETE FROM {} WHERE {} = '{}'",
-                        quote_identifier(table_name),
-                        quote_identifier(pk_name),
-                        escape_sqlite_str(&entity_id_value)
-                    );
-                    diesel::sql_query(sql)
-                        .execute(conn)
-                        .map_err(StorageError::from)?;
-                }
-                SyncOperation::Create | SyncOperation::Update => {
-                    let payload_obj = payload_json.as_object().ok_or_else(|| {
-                        Error::Database(DatabaseError::Internal(
-                            "Sync payload must be a JSON object".to_string(),
-                        ))
-                    })?;
+    let mut payload_json = payload_json;
+    if let Some(fields) = payload_json.as_object_mut() {
+        upgrade_sync_payload(entity, fields)
+            .map_err(|err| Error::Database(DatabaseError::Internal(err)))?;
+    }
 
-                    let fields: Vec<(String, serde_json::Value)> = payload_obj
-                        .iter()
-                        .map(|(k, v)| (k.clone(), v.clone()))
-                        .collect();
-                    let mut fields = normalize_payload_fields(conn, table_name, fields)?;
-                    if let Some((_, payload_pk)) = fields.iter().find(|(k, _)| k == pk_name) {
-                        if !payload_value_matches_entity_id(payload_pk, &entity_id_value) {
-                            return Err(Error::Database(DatabaseError::Internal(format!(
-                                "Sync payload PK '{}' does not match entity_id '{}'",
-                                pk_name, entity_id_value
-                            ))));
-                        }
-                    } else {
-                        fields.push((
-                            pk_name.to_string(),
-                            serde_json::Value::String(entity_id_value.clone()),
-                        ));
+    let applied = match (
+        keyed_entity_adapter(&entity),
+        entity_storage_mapping(&entity),
+    ) {
+        (Some(adapter), _) => {
+            let applied = apply_remote_keyed_event(
+                conn,
+                &adapter,
+                &entity_id_value,
+                op,
+                lww_wins,
+                payload_json,
+            )?;
+            if applied {
+                mark_incremental_apply(conn, adapter.table_name)?;
+            }
+            applied
+        }
+        (None, Some((table_name, pk_name))) => {
+            let applied = match op {
+                SyncOperation::Delete => {
+                    if lww_wins {
+                        let sql = format!(
+                            "DELETE FROM {} WHERE {} = '{}'",
+                            quote_identifier(table_name),
+                            quote_identifier(pk_name),
+                            escape_sqlite_str(&entity_id_value)
+                        );
+                        diesel::sql_query(sql)
+                            .execute(conn)
+                            .map_err(StorageError::from)?;
+                        clear_entity_field_state(conn, &entity_db, &entity_id_value)?;
                     }
-
-                    let columns = fields
-                        .iter()
-                        .map(|(k, _)| quote_identifier(k))
-                        .collect::<Vec<_>>()
-                        .join(", ");
-                    let values = fields
-                        .iter()
-                        .map(|(_, v)| json_value_to_sql_literal(v))
-                        .collect::<Vec<_>>()
-                        .join(", ");
-                    let upserts = fields
-                        .iter()
-                        .map(|(k, _)| {
-                            let quoted = quote_identifier(k);
-                            format!("{quoted}=excluded.{quoted}")
-                        })
-                        .collect::<Vec<_>>()
-                        .join(", ");
-
-                    let sql = format!(
-                        "INSERT INTO {} ({columns}) VALUES ({values}) \
-                         ON CONFLICT({}) DO UPDATE SET {upserts}",
-                        quote_identifier(table_name),
-                        quote_identifier(pk_name)
-                    );
-                    diesel::sql_query(sql)
-                        .execute(conn)
-                        .map_err(StorageError::from)?;
+                    lww_wins
                 }
-            }
+                SyncOperation::Create | SyncOperation::Update => apply_remote_upsert(
+                    conn,
+                    table_name,
+                    pk_name,
+                    &entity_db,
+                    &entity_id_value,
+                    &event_id_value,
+                    &client_timestamp_value,
+                    lww_wins,
+                    payload_json,
+                )?,
+            };
 
-            let now = Utc::now().to_rfc3339();
-            diesel::insert_into(sync_table_state::table)
-                .values(SyncTableStateDB {
-                    table_name: table_name.to_string(),
-                    enabled: 1,
-                    last_snapshot_restore_at: None,
-                    last_incremental_apply_at: Some(now.clone()),
-                })
-                .on_conflict(sync_table_state::table_name)
-                .do_update()
-                .set((
-                    sync_table_state::enabled.eq(1),
-                    sync_table_state::last_incremental_apply_at.eq(Some(now)),
-                ))
-                .execute(conn)
-                .map_err(StorageError::from)?;
+            if applied {
+                mark_incremental_apply(conn, table_name)?;
+            }
+            applied
         }
+        (None, None) => lww_wins,
+    };
 
+    if lww_wins {
         diesel::insert_into(sync_entity_metadata::table)
             .values(SyncEntityMetadataDB {
                 entity: entity_db.clone(),
@@ -665,12 +723,307 @@ fn apply_remote_event_lww_tx(
         .execute(conn)
         .map_err(StorageError::from)?;
 
-    Ok(should_apply)
+    Ok(applied)
+}
+
+fn mark_incremental_apply(conn: &mut SqliteConnection, table_name: &str) -> Result<()> {
+    let now = Utc::now().to_rfc3339();
+    diesel::insert_into(sync_table_state::table)
+        .values(SyncTableStateDB {
+            table_name: table_name.to_string(),
+            enabled: 1,
+            last_snapshot_restore_at: None,
+            last_incremental_apply_at: Some(now.clone()),
+        })
+        .on_conflict(sync_table_state::table_name)
+        .do_update()
+        .set((
+            sync_table_state::enabled.eq(1),
+            sync_table_state::last_incremental_apply_at.eq(Some(now)),
+        ))
+        .execute(conn)
+        .map_err(StorageError::from)?;
+    Ok(())
+}
+
+/// Applies a remote event for an entity keyed by natural key columns.
+///
+/// The row is located by the key columns every payload carries, and the
+/// whole row is replaced when the event wins LWW.
+fn apply_remote_keyed_event(
+    conn: &mut SqliteConnection,
+    adapter: &KeyedEntityAdapter,
+    entity_id_value: &str,
+    op: SyncOperation,
+    lww_wins: bool,
+    payload_json: serde_json::Value,
+) -> Result<bool> {
+    let serde_json::Value::Object(mut payload_obj) = payload_json else {
+        return Err(Error::Database(DatabaseError::Internal(
+            "Sync payload must be a JSON object".to_string(),
+        )));
+    };
+    payload_obj.remove(SYNC_FIELD_VERSIONS_KEY);
+    let fields =
+        normalize_payload_fields(conn, adapter.table_name, payload_obj.into_iter().collect())?;
+
+    let mut key_values = Vec::with_capacity(adapter.key_columns.len());
+    for column in adapter.key_columns {
+        let value = fields
+            .iter()
+            .find(|(k, _)| k == column)
+            .and_then(|(_, v)| v.as_str())
+            .ok_or_else(|| {
+                Error::Database(DatabaseError::Internal(format!(
+                    "Sync payload for table '{}' is missing key column '{}'",
+                    adapter.table_name, column
+                )))
+            })?;
+        key_values.push(value);
+    }
+    if derived_sync_entity_id(adapter.entity, &key_values) != entity_id_value {
+        return Err(Error::Database(DatabaseError::Internal(format!(
+            "Sync payload keys for table '{}' do not match entity_id '{}'",
+            adapter.table_name, entity_id_value
+        ))));
+    }
+    if !lww_wins || !adapter.accepts_key(&key_values) {
+        return Ok(false);
+    }
+
+    let table = quote_identifier(adapter.table_name);
+    let predicate = adapter
+        .key_columns
+        .iter()
+        .zip(&key_values)
+        .map(|(column, value)| {
+            format!(
+                "{} = '{}'",
+                quote_identifier(column),
+                escape_sqlite_str(value)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" AND ");
+
+    if matches!(op, SyncOperation::Delete) {
+        if adapter.update_only {
+            return Ok(false);
+        }
+        diesel::sql_query(format!("DELETE FROM {table} WHERE {predicate}"))
+            .execute(conn)
+            .map_err(StorageError::from)?;
+        return Ok(true);
+    }
+
+    let is_key = |column: &str| adapter.key_columns.contains(&column);
+    let fields = fields
+        .iter()
+        .filter(|(k, _)| {
+            is_key(k)
+                || adapter
+                    .synced_columns
+                    .is_none_or(|columns| columns.contains(&k.as_str()))
+        })
+        .collect::<Vec<_>>();
+
+    if adapter.update_only {
+        let assignments = fields
+            .iter()
+            .filter(|(k, _)| !is_key(k))
+            .map(|(k, v)| format!("{} = {}", quote_identifier(k), json_value_to_sql_literal(v)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if assignments.is_empty() {
+            return Ok(false);
+        }
+        let affected = diesel::sql_query(format!(
+            "UPDATE {table} SET {assignments} WHERE {predicate}"
+        ))
+        .execute(conn)
+        .map_err(StorageError::from)?;
+        return Ok(affected > 0);
+    }
+
+    let columns = fields
+        .iter()
+        .map(|(k, _)| quote_identifier(k))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let values = fields
+        .iter()
+        .map(|(_, v)| json_value_to_sql_literal(v))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let conflict_target = adapter
+        .key_columns
+        .iter()
+        .map(|column| quote_identifier(column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let upserts = fields
+        .iter()
+        .map(|(k, _)| {
+            let quoted = quote_identifier(k);
+            format!("{quoted}=excluded.{quoted}")
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    diesel::sql_query(format!(
+        "INSERT INTO {table} ({columns}) VALUES ({values}) \
+         ON CONFLICT({conflict_target}) DO UPDATE SET {upserts}"
+    ))
+    .execute(conn)
+    .map_err(StorageError::from)?;
+    Ok(true)
+}
+
+/// Applies a remote create/update.
+///
+/// Events that list their changed fields merge field by field into an
+/// existing row; anything else replaces the row when it wins LWW.
+#[allow(clippy::too_many_arguments)]
+fn apply_remote_upsert(
+    conn: &mut SqliteConnection,
+    table_name: &str,
+    pk_name: &str,
+    entity_db: &str,
+    entity_id_value: &str,
+    event_id_value: &str,
+    client_timestamp_value: &str,
+    lww_wins: bool,
+    payload_json: serde_json::Value,
+) -> Result<bool> {
+    let serde_json::Value::Object(mut payload_obj) = payload_json else {
+        return Err(Error::Database(DatabaseError::Internal(
+            "Sync payload must be a JSON object".to_string(),
+        )));
+    };
+    let field_versions = take_field_versions(&mut payload_obj)
+        .map_err(|err| Error::Database(DatabaseError::Internal(err)))?;
+
+    let fields: Vec<(String, serde_json::Value)> = payload_obj.into_iter().collect();
+    let mut fields = normalize_payload_fields(conn, table_name, fields)?;
+    if let Some((_, payload_pk)) = fields.iter().find(|(k, _)| k == pk_name) {
+        if !payload_value_matches_entity_id(payload_pk, entity_id_value) {
+            return Err(Error::Database(DatabaseError::Internal(format!(
+                "Sync payload PK '{}' does not match entity_id '{}'",
+                pk_name, entity_id_value
+            ))));
+        }
+    } else {
+        fields.push((
+            pk_name.to_string(),
+            serde_json::Value::String(entity_id_value.to_string()),
+        ));
+    }
+
+    if let Some(versions) = field_versions.as_ref() {
+        let versions: SyncFieldVersions = versions
+            .iter()
+            .map(|(field, version)| (normalize_payload_key_to_snake_case(field), version.clone()))
+            .collect();
+        if row_exists(conn, table_name, pk_name, entity_id_value)? {
+            let merged = merge_remote_fields(
+                conn,
+                entity_db,
+                entity_id_value,
+                event_id_value,
+                &fields,
+                &versions,
+            )?;
+            if merged.is_empty() {
+                return Ok(false);
+            }
+            let assignments = merged
+                .iter()
+                .map(|(k, v)| format!("{} = {}", quote_identifier(k), json_value_to_sql_literal(v)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let sql = format!(
+                "UPDATE {} SET {assignments} WHERE {} = '{}'",
+                quote_identifier(table_name),
+                quote_identifier(pk_name),
+                escape_sqlite_str(entity_id_value)
+            );
+            diesel::sql_query(sql)
+                .execute(conn)
+                .map_err(StorageError::from)?;
+            return Ok(true);
+        }
+    }
+
+    if !lww_wins {
+        return Ok(false);
+    }
+
+    let columns = fields
+        .iter()
+        .map(|(k, _)| quote_identifier(k))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let values = fields
+        .iter()
+        .map(|(_, v)| json_value_to_sql_literal(v))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let upserts = fields
+        .iter()
+        .map(|(k, _)| {
+            let quoted = quote_identifier(k);
+            format!("{quoted}=excluded.{quoted}")
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let sql = format!(
+        "INSERT INTO {} ({columns}) VALUES ({values}) \
+         ON CONFLICT({}) DO UPDATE SET {upserts}",
+        quote_identifier(table_name),
+        quote_identifier(pk_name)
+    );
+    diesel::sql_query(sql)
+        .execute(conn)
+        .map_err(StorageError::from)?;
+
+    let tracked = fields
+        .into_iter()
+        .filter(|(k, _)| k != pk_name)
+        .collect::<Vec<_>>();
+    record_remote_field_values(
+        conn,
+        entity_db,
+        entity_id_value,
+        &tracked,
+        field_versions.as_ref(),
+        client_timestamp_value,
+        event_id_value,
+    )?;
+    Ok(true)
+}
+
+fn row_exists(
+    conn: &mut SqliteConnection,
+    table_name: &str,
+    pk_name: &str,
+    entity_id: &str,
+) -> Result<bool> {
+    let sql = format!(
+        "SELECT COUNT(*) AS count FROM {} WHERE {} = '{}'",
+        quote_identifier(table_name),
+        quote_identifier(pk_name),
+        escape_sqlite_str(entity_id)
+    );
+    let row = diesel::sql_query(sql)
+        .get_result::<TableRowCountResult>(conn)
+        .map_err(StorageError::from)?;
+    Ok(row.count > 0)
 }
 
 pub struct AppSyncRepository {
     pool: Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
     writer: WriteHandle,
+    attachment_store: Option<Arc<FileAttachmentStore>>,
 }
 
 impl AppSyncRepository {
@@ -678,7 +1031,18 @@ impl AppSyncRepository {
         pool: Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
         writer: WriteHandle,
     ) -> Self {
-        Self { pool, writer }
+        Self {
+            pool,
+            writer,
+            attachment_store: None,
+        }
+    }
+
+    /// Includes attachment contents in exported snapshots and stores the ones
+    /// found in restored snapshots.
+    pub fn with_attachment_store(mut self, store: Arc<FileAttachmentStore>) -> Self {
+        self.attachment_store = Some(store);
+        self
     }
 
     pub fn get_cursor(&self) -> Result<i64> {
@@ -800,8 +1164,16 @@ impl AppSyncRepository {
         let mut non_empty_tables = Vec::new();
 
         for table in APP_SYNC_TABLES {
+            if LOCAL_DATA_SUMMARY_EXCLUDED_TABLES.contains(&table) {
+                continue;
+            }
             let table_ident = quote_identifier(table);
-            let count_sql = format!("SELECT COUNT(*) AS count FROM {table_ident}");
+            let count_sql = match local_data_summary_filter(table) {
+                Some(where_clause) => {
+                    format!("SELECT COUNT(*) AS count FROM {table_ident} WHERE {where_clause}")
+                }
+                None => format!("SELECT COUNT(*) AS count FROM {table_ident}"),
+            };
             let row = diesel::sql_query(count_sql)
                 .get_result::<TableRowCountResult>(&mut conn)
                 .map_err(StorageError::from)?;
@@ -1020,6 +1392,22 @@ impl AppSyncRepository {
         row.map(to_entity_metadata).transpose()
     }
 
+    /// Lists field conflicts, newest first. Resolved ones are included on request.
+    pub fn list_sync_conflicts(&self, include_resolved: bool) -> Result<Vec<SyncFieldConflict>> {
+        let mut conn = get_connection(&self.pool)?;
+        list_conflicts(&mut conn, include_resolved)
+    }
+
+    /// Settles a field conflict and queues the chosen value for the other devices.
+    pub async fn resolve_sync_conflict(
+        &self,
+        request: ResolveSyncConflictRequest,
+    ) -> Result<SyncFieldConflict> {
+        self.writer
+            .exec(move |conn| resolve_conflict(conn, request))
+            .await
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn apply_remote_event_lww(
         &self,
@@ -1403,6 +1791,7 @@ impl AppSyncRepository {
         ];
 
         let pool = Arc::clone(&self.pool);
+        let attachment_store = self.attachment_store.clone();
         tokio::task::spawn_blocking(move || -> Result<Vec<u8>> {
             let mut conn = get_connection(&pool)?;
             let table_set = if tables.is_empty() {
@@ -1433,7 +1822,8 @@ impl AppSyncRepository {
                         let filter = SYNC_TABLE_EXPORT_FILTERS
                             .iter()
                             .find(|(t, _)| *t == table.as_str())
-                            .map(|(_, f)| *f);
+                            .map(|(_, f)| f.to_string())
+                            .or_else(|| snapshot_shared_rows_filter(table));
                         let copy_sql = match filter {
                             Some(where_clause) => format!(
                                 "CREATE TABLE {snapshot_alias}.{table_ident} AS SELECT * FROM main.{table_ident} WHERE {where_clause}"
@@ -1457,6 +1847,15 @@ impl AppSyncRepository {
                 let _ = std::fs::remove_file(&snapshot_path);
                 return Err(Error::from(err));
             }
+            if let Some(store) = attachment_store
+                .as_ref()
+                .filter(|_| table_set.iter().any(|t| t == "attachments"))
+            {
+                if let Err(err) = store.pack_archive(&snapshot_path.to_string_lossy()) {
+                    let _ = std::fs::remove_file(&snapshot_path);
+                    return Err(err);
+                }
+            }
 
             let payload = std::fs::read(&snapshot_path).map_err(|e| {
                 Error::Database(DatabaseError::Internal(format!(
@@ -1484,6 +1883,20 @@ impl AppSyncRepository {
         device_id_value: String,
         key_version_value: Option<i32>,
     ) -> Result<()> {
+        // Attachment files are content-addressed, so storing them ahead of the
+        // metadata is harmless if the restore below fails.
+        if let Some(store) = self.attachment_store.clone() {
+            let path = snapshot_db_path.clone();
+            tokio::task::spawn_blocking(move || store.unpack_archive(&path, false))
+                .await
+                .map_err(|e| {
+                    Error::Database(DatabaseError::Internal(format!(
+                        "Attachment restore worker failed: {}",
+                        e
+                    )))
+                })??;
+        }
+
         self.writer
             .exec(move |conn| {
                 let table_set = if tables.is_empty() {
@@ -1526,6 +1939,7 @@ impl AppSyncRepository {
                     diesel::delete(sync_table_state::table)
                         .execute(conn)
                         .map_err(StorageError::from)?;
+                    clear_all_field_state(conn)?;
 
                     for table in &table_set {
                         let target_columns = load_table_columns(conn, "main", table)?;
@@ -1550,16 +1964,40 @@ impl AppSyncRepository {
                             .map(|column| quote_identifier(column))
                             .collect::<Vec<_>>()
                             .join(", ");
-                        let copy_sql = format!(
-                            "INSERT INTO {table_ident} ({columns_sql}) SELECT {columns_sql} FROM {alias_ident}.{table_ident}"
-                        );
-                        let clear_sql = format!("DELETE FROM {table_ident}");
-                        diesel::sql_query(clear_sql)
-                            .execute(conn)
-                            .map_err(StorageError::from)?;
-                        diesel::sql_query(copy_sql)
-                            .execute(conn)
-                            .map_err(StorageError::from)?;
+                        if table == "market_data_providers" {
+                            // Providers are seeded by migrations; only their
+                            // preferences are shared.
+                            let merge_sql = format!(
+                                "UPDATE {table_ident} SET \
+                                 priority = (SELECT s.priority FROM {alias_ident}.{table_ident} s WHERE s.id = {table_ident}.id), \
+                                 enabled = (SELECT s.enabled FROM {alias_ident}.{table_ident} s WHERE s.id = {table_ident}.id) \
+                                 WHERE id IN (SELECT id FROM {alias_ident}.{table_ident})"
+                            );
+                            diesel::sql_query(merge_sql)
+                                .execute(conn)
+                                .map_err(StorageError::from)?;
+                        } else {
+                            let (copy_sql, clear_sql) = match snapshot_shared_rows_filter(table) {
+                                Some(where_clause) => (
+                                    format!(
+                                        "INSERT INTO {table_ident} ({columns_sql}) SELECT {columns_sql} FROM {alias_ident}.{table_ident} WHERE {where_clause}"
+                                    ),
+                                    format!("DELETE FROM {table_ident} WHERE {where_clause}"),
+                                ),
+                                None => (
+                                    format!(
+                                        "INSERT INTO {table_ident} ({columns_sql}) SELECT {columns_sql} FROM {alias_ident}.{table_ident}"
+                                    ),
+                                    format!("DELETE FROM {table_ident}"),
+                                ),
+                            };
+                            diesel::sql_query(clear_sql)
+                                .execute(conn)
+                                .map_err(StorageError::from)?;
+                            diesel::sql_query(copy_sql)
+                                .execute(conn)
+                                .map_err(StorageError::from)?;
+                        }
 
                         let state_row = SyncTableStateDB {
                             table_name: table.clone(),
@@ -1652,6 +2090,7 @@ mod tests {
     use diesel::dsl::count_star;
     use std::collections::BTreeSet;
     use tempfile::tempdir;
+    use wealthfolio_core::sync::SyncConflictResolution;
 
     use crate::db::{create_pool, get_connection, init, run_migrations, write_actor::spawn_writer};
     use crate::schema::{
@@ -2145,6 +2584,227 @@ mod tests {
         assert_eq!(pending[0].payload_key_version, 3);
     }
 
+    fn platform_outbox_request(
+        payload: serde_json::Value,
+        op: SyncOperation,
+        client_timestamp: &str,
+    ) -> OutboxWriteRequest {
+        let mut request =
+            OutboxWriteRequest::new(SyncEntity::Platform, "platform-merge", op, payload);
+        request.client_timestamp = client_timestamp.to_string();
+        request
+    }
+
+    fn platform_name_and_url(
+        pool: &Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
+    ) -> (Option<String>, String) {
+        let mut conn = get_connection(pool).expect("conn");
+        platforms::table
+            .find("platform-merge")
+            .select((platforms::name, platforms::url))
+            .first::<(Option<String>, String)>(&mut conn)
+            .expect("platform row")
+    }
+
+    /// Creates the platform from a remote event, then renames it locally.
+    async fn setup_platform_with_local_rename(repo: &AppSyncRepository, writer: &WriteHandle) {
+        repo.apply_remote_event_lww(
+            SyncEntity::Platform,
+            "platform-merge".to_string(),
+            SyncOperation::Create,
+            "evt-remote-create".to_string(),
+            "2026-03-01T10:00:00Z".to_string(),
+            1,
+            serde_json::json!({
+                "id": "platform-merge",
+                "name": "Initial",
+                "url": "https://broker.example",
+                "kind": "BROKERAGE"
+            }),
+        )
+        .await
+        .expect("apply create");
+
+        writer
+            .exec(|conn| {
+                diesel::update(platforms::table.find("platform-merge"))
+                    .set(platforms::name.eq("Desktop"))
+                    .execute(conn)
+                    .map_err(StorageError::from)?;
+                insert_outbox_event(
+                    conn,
+                    platform_outbox_request(
+                        serde_json::json!({
+                            "id": "platform-merge",
+                            "name": "Desktop",
+                            "url": "https://broker.example",
+                            "kind": "BROKERAGE"
+                        }),
+                        SyncOperation::Update,
+                        "2026-03-01T11:00:00Z",
+                    ),
+                )?;
+                Ok(())
+            })
+            .await
+            .expect("local rename");
+    }
+
+    #[tokio::test]
+    async fn outbox_payload_lists_changed_fields_with_their_base() {
+        let (pool, writer) = setup_db();
+        let repo = AppSyncRepository::new(pool, writer.clone());
+
+        writer
+            .exec(|conn| {
+                insert_outbox_event(
+                    conn,
+                    platform_outbox_request(
+                        serde_json::json!({ "id": "platform-merge", "name": "A", "url": "u" }),
+                        SyncOperation::Create,
+                        "2026-03-01T10:00:00Z",
+                    ),
+                )?;
+                insert_outbox_event(
+                    conn,
+                    platform_outbox_request(
+                        serde_json::json!({ "id": "platform-merge", "name": "B", "url": "u" }),
+                        SyncOperation::Update,
+                        "2026-03-01T11:00:00Z",
+                    ),
+                )?;
+                Ok(())
+            })
+            .await
+            .expect("write outbox");
+
+        let pending = repo.list_pending_outbox(10).expect("list pending");
+        assert_eq!(pending.len(), 2);
+        let first: serde_json::Value = serde_json::from_str(&pending[0].payload).unwrap();
+        let second: serde_json::Value = serde_json::from_str(&pending[1].payload).unwrap();
+        assert_eq!(
+            first[SYNC_FIELD_VERSIONS_KEY],
+            serde_json::json!({
+                "name": { "updatedAt": "2026-03-01T10:00:00Z", "base": null },
+                "url": { "updatedAt": "2026-03-01T10:00:00Z", "base": null }
+            })
+        );
+        assert_eq!(
+            second[SYNC_FIELD_VERSIONS_KEY],
+            serde_json::json!({
+                "name": { "updatedAt": "2026-03-01T11:00:00Z", "base": "2026-03-01T10:00:00Z" }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn replay_merges_edits_to_different_fields() {
+        let (pool, writer) = setup_db();
+        let repo = AppSyncRepository::new(pool.clone(), writer.clone());
+        setup_platform_with_local_rename(&repo, &writer).await;
+
+        let applied = repo
+            .apply_remote_event_lww(
+                SyncEntity::Platform,
+                "platform-merge".to_string(),
+                SyncOperation::Update,
+                "evt-remote-url".to_string(),
+                "2026-03-01T12:00:00Z".to_string(),
+                2,
+                serde_json::json!({
+                    "id": "platform-merge",
+                    "name": "Initial",
+                    "url": "https://broker.example/v2",
+                    "_field_versions": {
+                        "url": { "updatedAt": "2026-03-01T12:00:00Z", "base": "2026-03-01T10:00:00Z" }
+                    }
+                }),
+            )
+            .await
+            .expect("apply remote update");
+
+        assert!(applied);
+        assert_eq!(
+            platform_name_and_url(&pool),
+            (
+                Some("Desktop".to_string()),
+                "https://broker.example/v2".to_string()
+            )
+        );
+        assert!(repo
+            .list_sync_conflicts(false)
+            .expect("conflicts")
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn replay_records_conflict_and_resolution_queues_outbox_event() {
+        let (pool, writer) = setup_db();
+        let repo = AppSyncRepository::new(pool.clone(), writer.clone());
+        setup_platform_with_local_rename(&repo, &writer).await;
+
+        repo.apply_remote_event_lww(
+            SyncEntity::Platform,
+            "platform-merge".to_string(),
+            SyncOperation::Update,
+            "evt-remote-name".to_string(),
+            "2026-03-01T12:00:00Z".to_string(),
+            2,
+            serde_json::json!({
+                "id": "platform-merge",
+                "name": "Phone",
+                "_field_versions": {
+                    "name": { "updatedAt": "2026-03-01T12:00:00Z", "base": "2026-03-01T10:00:00Z" }
+                }
+            }),
+        )
+        .await
+        .expect("apply remote update");
+
+        assert_eq!(platform_name_and_url(&pool).0.as_deref(), Some("Desktop"));
+        let conflicts = repo.list_sync_conflicts(false).expect("conflicts");
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].field, "name");
+        assert_eq!(conflicts[0].local_value, serde_json::json!("Desktop"));
+        assert_eq!(conflicts[0].remote_value, serde_json::json!("Phone"));
+
+        let manual_without_value = repo
+            .resolve_sync_conflict(ResolveSyncConflictRequest {
+                conflict_id: conflicts[0].id.clone(),
+                resolution: SyncConflictResolution::Manual,
+                value: None,
+            })
+            .await;
+        assert!(manual_without_value.is_err());
+
+        let resolved = repo
+            .resolve_sync_conflict(ResolveSyncConflictRequest {
+                conflict_id: conflicts[0].id.clone(),
+                resolution: SyncConflictResolution::Remote,
+                value: None,
+            })
+            .await
+            .expect("resolve conflict");
+
+        assert_eq!(resolved.resolution, Some(SyncConflictResolution::Remote));
+        assert_eq!(platform_name_and_url(&pool).0.as_deref(), Some("Phone"));
+        assert!(repo
+            .list_sync_conflicts(false)
+            .expect("conflicts")
+            .is_empty());
+        assert_eq!(repo.list_sync_conflicts(true).expect("conflicts").len(), 1);
+
+        let pending = repo.list_pending_outbox(10).expect("list pending");
+        let last: serde_json::Value =
+            serde_json::from_str(&pending.last().expect("resolution event").payload).unwrap();
+        assert_eq!(last["name"], serde_json::json!("Phone"));
+        assert_eq!(last["url"], serde_json::json!("https://broker.example"));
+        assert_eq!(
+            last[SYNC_FIELD_VERSIONS_KEY]["name"]["base"],
+            serde_json::json!("2026-03-01T12:00:00Z")
+        );
+    }
+
     #[test]
     fn normalize_outbox_payload_keys_to_snake_case() {
         let payload = normalize_outbox_payload(serde_json::json!({
@@ -2229,6 +2889,11 @@ mod tests {
             SyncEntity::ContributionLimit,
             SyncEntity::Platform,
             SyncEntity::Snapshot,
+            SyncEntity::Taxonomy,
+            SyncEntity::TaxonomyCategory,
+            SyncEntity::AppSetting,
+            SyncEntity::HealthIssueDismissal,
+            SyncEntity::MarketDataProvider,
         ];
 
         for entity in entities {
@@ -2582,6 +3247,352 @@ mod tests {
         );
     }
 
+    fn count_rows_where(
+        pool: &Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
+        table: &str,
+        where_clause: &str,
+    ) -> i64 {
+        let mut conn = get_connection(pool).expect("conn");
+        diesel::sql_query(format!(
+            "SELECT COUNT(*) AS count FROM {} WHERE {}",
+            quote_identifier(table),
+            where_clause
+        ))
+        .get_result::<TableRowCountResult>(&mut conn)
+        .expect("count rows")
+        .count
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn apply_keyed_event(
+        repo: &AppSyncRepository,
+        entity: SyncEntity,
+        keys: &[&str],
+        op: SyncOperation,
+        event_id: &str,
+        client_timestamp: &str,
+        seq: i64,
+        payload: serde_json::Value,
+    ) -> Result<bool> {
+        repo.apply_remote_event_lww(
+            entity,
+            derived_sync_entity_id(entity, keys),
+            op,
+            event_id.to_string(),
+            client_timestamp.to_string(),
+            seq,
+            payload,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn replay_applies_taxonomy_and_category_by_natural_key() {
+        let (pool, writer) = setup_db();
+        let repo = AppSyncRepository::new(pool.clone(), writer);
+
+        let created = apply_keyed_event(
+            &repo,
+            SyncEntity::Taxonomy,
+            &["tax-sync"],
+            SyncOperation::Create,
+            "evt-tax-create",
+            "2026-03-10T00:00:00Z",
+            1,
+            serde_json::json!({
+                "id": "tax-sync",
+                "name": "Brokers",
+                "color": "#123456",
+                "description": null,
+                "isSystem": 0,
+                "isSingleSelect": 1,
+                "sortOrder": 90,
+                "createdAt": "2026-03-10T00:00:00Z",
+                "updatedAt": "2026-03-10T00:00:00Z",
+                "_schema_version": 1
+            }),
+        )
+        .await
+        .expect("apply taxonomy create");
+        assert!(created);
+
+        // Category ids are only unique within their taxonomy.
+        let category = apply_keyed_event(
+            &repo,
+            SyncEntity::TaxonomyCategory,
+            &["tax-sync", "CASH"],
+            SyncOperation::Create,
+            "evt-category-create",
+            "2026-03-10T00:00:01Z",
+            2,
+            serde_json::json!({
+                "id": "CASH",
+                "taxonomyId": "tax-sync",
+                "parentId": null,
+                "name": "Cash at broker",
+                "key": "cash",
+                "color": "#654321",
+                "description": null,
+                "sortOrder": 1,
+                "createdAt": "2026-03-10T00:00:01Z",
+                "updatedAt": "2026-03-10T00:00:01Z",
+                "_schema_version": 1
+            }),
+        )
+        .await
+        .expect("apply category create");
+        assert!(category);
+        assert_eq!(
+            count_rows_where(&pool, "taxonomy_categories", "taxonomy_id = 'tax-sync'"),
+            1
+        );
+        assert!(count_rows_where(&pool, "taxonomy_categories", "id = 'CASH'") > 1);
+
+        let deleted = apply_keyed_event(
+            &repo,
+            SyncEntity::Taxonomy,
+            &["tax-sync"],
+            SyncOperation::Delete,
+            "evt-tax-delete",
+            "2026-03-10T00:00:02Z",
+            3,
+            serde_json::json!({ "id": "tax-sync", "_schema_version": 1 }),
+        )
+        .await
+        .expect("apply taxonomy delete");
+        assert!(deleted);
+        assert_eq!(count_rows_where(&pool, "taxonomies", "id = 'tax-sync'"), 0);
+        assert_eq!(
+            count_rows_where(&pool, "taxonomy_categories", "taxonomy_id = 'tax-sync'"),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn replay_rejects_keyed_payload_that_does_not_match_entity_id() {
+        let (pool, writer) = setup_db();
+        let repo = AppSyncRepository::new(pool, writer);
+
+        let result = apply_keyed_event(
+            &repo,
+            SyncEntity::HealthIssueDismissal,
+            &["issue-a"],
+            SyncOperation::Create,
+            "evt-dismissal-mismatch",
+            "2026-03-10T00:00:00Z",
+            1,
+            serde_json::json!({
+                "issue_id": "issue-b",
+                "dismissed_at": "2026-03-10T00:00:00Z",
+                "data_hash": "hash"
+            }),
+        )
+        .await;
+
+        assert!(result.is_err(), "expected key mismatch to be rejected");
+    }
+
+    #[tokio::test]
+    async fn replay_applies_only_shared_setting_keys() {
+        let (pool, writer) = setup_db();
+        let repo = AppSyncRepository::new(pool.clone(), writer);
+
+        let shared = apply_keyed_event(
+            &repo,
+            SyncEntity::AppSetting,
+            &["base_currency"],
+            SyncOperation::Update,
+            "evt-setting-shared",
+            "2026-03-10T00:00:00Z",
+            1,
+            serde_json::json!({ "settingKey": "base_currency", "settingValue": "CHF" }),
+        )
+        .await
+        .expect("apply shared setting");
+        assert!(shared);
+        assert_eq!(
+            count_rows_where(
+                &pool,
+                "app_settings",
+                "setting_key = 'base_currency' AND setting_value = 'CHF'"
+            ),
+            1
+        );
+
+        let local_only = apply_keyed_event(
+            &repo,
+            SyncEntity::AppSetting,
+            &["theme"],
+            SyncOperation::Update,
+            "evt-setting-local",
+            "2026-03-10T00:00:01Z",
+            2,
+            serde_json::json!({ "settingKey": "theme", "settingValue": "dark" }),
+        )
+        .await
+        .expect("apply local-only setting");
+        assert!(!local_only);
+        assert_eq!(
+            count_rows_where(
+                &pool,
+                "app_settings",
+                "setting_key = 'theme' AND setting_value = 'dark'"
+            ),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn replay_updates_provider_preferences_of_existing_providers_only() {
+        let (pool, writer) = setup_db();
+        let repo = AppSyncRepository::new(pool.clone(), writer);
+
+        let updated = apply_keyed_event(
+            &repo,
+            SyncEntity::MarketDataProvider,
+            &["YAHOO"],
+            SyncOperation::Update,
+            "evt-provider-yahoo",
+            "2026-03-10T00:00:00Z",
+            1,
+            serde_json::json!({ "id": "YAHOO", "priority": 9, "enabled": false }),
+        )
+        .await
+        .expect("apply provider preference");
+        assert!(updated);
+        assert_eq!(
+            count_rows_where(
+                &pool,
+                "market_data_providers",
+                "id = 'YAHOO' AND priority = 9 AND enabled = 0 AND name = 'Yahoo Finance'"
+            ),
+            1
+        );
+
+        let unknown = apply_keyed_event(
+            &repo,
+            SyncEntity::MarketDataProvider,
+            &["NOT_INSTALLED"],
+            SyncOperation::Update,
+            "evt-provider-unknown",
+            "2026-03-10T00:00:01Z",
+            2,
+            serde_json::json!({ "id": "NOT_INSTALLED", "priority": 1, "enabled": true }),
+        )
+        .await
+        .expect("apply unknown provider preference");
+        assert!(!unknown);
+        assert_eq!(
+            count_rows_where(&pool, "market_data_providers", "id = 'NOT_INSTALLED'"),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn replay_rejects_payload_schema_newer_than_supported() {
+        let (pool, writer) = setup_db();
+        let repo = AppSyncRepository::new(pool.clone(), writer);
+
+        let result = apply_keyed_event(
+            &repo,
+            SyncEntity::HealthIssueDismissal,
+            &["issue-future"],
+            SyncOperation::Create,
+            "evt-dismissal-future",
+            "2026-03-10T00:00:00Z",
+            1,
+            serde_json::json!({
+                "issue_id": "issue-future",
+                "dismissed_at": "2026-03-10T00:00:00Z",
+                "data_hash": "hash",
+                "_schema_version": 99
+            }),
+        )
+        .await;
+
+        assert!(result.is_err(), "expected newer schema to be rejected");
+        assert_eq!(
+            count_rows_where(
+                &pool,
+                "health_issue_dismissals",
+                "issue_id = 'issue-future'"
+            ),
+            0
+        );
+        assert!(!repo
+            .has_applied_event("evt-dismissal-future")
+            .expect("applied lookup"));
+    }
+
+    #[tokio::test]
+    async fn keyed_outbox_events_carry_schema_version_and_key_columns() {
+        use wealthfolio_core::health::HealthDismissalStore;
+
+        let (pool, writer) = setup_db();
+        let repo = AppSyncRepository::new(pool.clone(), writer.clone());
+        let dismissals = crate::health::HealthDismissalRepository::new(pool, writer);
+        let dismissal = wealthfolio_core::health::IssueDismissal {
+            issue_id: "issue-outbox".to_string(),
+            dismissed_at: Utc::now(),
+            data_hash: "hash-1".to_string(),
+        };
+
+        dismissals
+            .save_dismissal(&dismissal)
+            .await
+            .expect("save dismissal");
+        dismissals
+            .remove_dismissal("issue-outbox")
+            .await
+            .expect("remove dismissal");
+
+        let pending = repo.list_pending_outbox(10).expect("list pending");
+        assert_eq!(pending.len(), 2);
+        let expected_id =
+            derived_sync_entity_id(SyncEntity::HealthIssueDismissal, &["issue-outbox"]);
+        for event in &pending {
+            assert_eq!(event.entity, SyncEntity::HealthIssueDismissal);
+            assert_eq!(event.entity_id, expected_id);
+            let payload: serde_json::Value =
+                serde_json::from_str(&event.payload).expect("payload json");
+            assert_eq!(payload["issue_id"], "issue-outbox");
+            assert_eq!(payload[SYNC_PAYLOAD_SCHEMA_VERSION_KEY], 1);
+            assert!(payload.get(SYNC_FIELD_VERSIONS_KEY).is_none());
+        }
+        assert_eq!(pending[1].op, SyncOperation::Delete);
+    }
+
+    #[tokio::test]
+    async fn local_sync_data_summary_ignores_seeded_and_config_rows() {
+        let (pool, writer) = setup_db();
+        let repo = AppSyncRepository::new(pool.clone(), writer);
+        {
+            let mut conn = get_connection(&pool).expect("conn");
+            diesel::sql_query(
+                "INSERT INTO app_settings (setting_key, setting_value) VALUES ('base_currency', 'EUR')",
+            )
+            .execute(&mut conn)
+            .expect("insert setting");
+        }
+
+        let summary = repo.get_local_sync_data_summary().expect("sync summary");
+        for table in [
+            "taxonomies",
+            "taxonomy_categories",
+            "app_settings",
+            "market_data_providers",
+        ] {
+            assert!(
+                summary
+                    .non_empty_tables
+                    .iter()
+                    .all(|row| row.table != table),
+                "seeded table '{}' should not count as local data",
+                table
+            );
+        }
+    }
+
     #[test]
     fn quote_identifier_escapes_backticks() {
         assert_eq!(quote_identifier("col`name"), "`col``name`");
@@ -1,11 +1,14 @@
-//! SQLite storage implementation for sync (platforms, app sync state, import runs).
+//! SQLite storage implementation for sync (platforms, app sync state, import runs, relay).
 
 pub mod app_sync;
 pub mod folder_sync;
 pub mod import_run;
 pub mod platform;
+pub mod relay;
 pub mod state;
 
+use std::borrow::Cow;
+
 use serde::Serialize;
 use uuid::Uuid;
 use wealthfolio_core::portfolio::snapshot::SnapshotSource;
@@ -34,6 +37,7 @@ pub use folder_sync::{
 };
 pub use import_run::{ImportRunDB, ImportRunRepository};
 pub use platform::{Platform, PlatformDB, PlatformRepository};
+pub use relay::{RelayError, SyncRelayStore};
 pub use state::{
     BrokerSyncState, BrokerSyncStateDB, BrokerSyncStateRepository, PlaidInvestmentsCheckpoint,
     PlaidSyncCheckpoint, SnapTradeCheckpoint, SyncStatus,
@@ -77,9 +81,12 @@ pub fn should_sync_outbox_for_snapshot_source(source: SnapshotSource) -> bool {
 }
 
 /// Centralized metadata for mapping DB models to sync outbox entities.
+///
+/// Entities with derived ids (`SyncEntity::uses_derived_id`) must be deleted
+/// through `delete_model` so the event payload carries their key columns.
 pub trait SyncOutboxModel: Serialize {
     const ENTITY: SyncEntity;
-    fn sync_entity_id(&self) -> &str;
+    fn sync_entity_id(&self) -> Cow<'_, str>;
     fn should_sync_outbox(&self, _op: SyncOperation) -> bool {
         true
     }
@@ -126,7 +126,7 @@ impl PlatformRepository {
                     .map_err(StorageError::from)?;
                 if affected > 0 {
                     if let Some(existing) = existing_platform.as_ref() {
-                        tx.delete_model(existing);
+                        tx.delete_model(existing)?;
                     }
                 }
                 Ok(affected)
@@ -0,0 +1,12 @@
+//! Storage for the self-hosted device sync relay.
+//!
+//! The relay implements the device registry, team key, pairing, event and
+//! snapshot endpoints that `DeviceSyncClient` talks to, so a server can stand
+//! in for the Connect cloud. It keeps its state in a dedicated SQLite file,
+//! separate from the app database. Event payloads, key envelopes, pairing
+//! messages and snapshots are ciphertext produced by the clients; the relay
+//! never holds a key that can decrypt them.
+
+mod store;
+
+pub use store::{RelayError, SyncRelayStore, PAIRING_TTL_SECONDS};
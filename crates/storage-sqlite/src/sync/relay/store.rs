@@ -0,0 +1,1860 @@
+//! SQLite-backed state for the self-hosted device sync relay.
+//!
+//! Every operation is scoped to a team. The server derives the team from the
+//! relay token a client authenticates with, so one relay can host several
+//! independent households.
+
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::{Duration, SecondsFormat, Utc};
+use rand::RngCore;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use thiserror::Error;
+use uuid::Uuid;
+
+use wealthfolio_device_sync::crypto::{hash_pairing_code, hash_sha256, sha256_checksum};
+use wealthfolio_device_sync::{
+    ClaimPairingRequest, ClaimPairingResponse, CommitInitializeKeysRequest,
+    CommitInitializeKeysResponse, CommitRotateKeysRequest, CommitRotateKeysResponse,
+    CompletePairingRequest, ConfirmPairingRequest, ConfirmPairingResponse, CreatePairingRequest,
+    CreatePairingResponse, Device, EnrollDeviceResponse, GetPairingResponse, InitializeKeysResult,
+    KeyState, PairingMessage, PairingMessagesResponse, PairingStatus, ReconcileReadyStateResponse,
+    RegisterDeviceRequest, ResetTeamSyncResponse, RotateKeysResponse, SnapshotDownloadHeaders,
+    SnapshotLatestResponse, SnapshotUploadHeaders, SnapshotUploadResponse, SyncCursorResponse,
+    SyncEntity, SyncEvent, SyncLatestSnapshotRef, SyncPullResponse, SyncPushRequest,
+    SyncPushResponse, SyncPushResultItem, TrustState, TrustedDeviceSummary, UpdateDeviceRequest,
+};
+
+/// Lifetime of a pairing session before it expires unclaimed.
+pub const PAIRING_TTL_SECONDS: i64 = 300;
+
+const DEFAULT_PULL_LIMIT: i64 = 500;
+const MAX_PULL_LIMIT: i64 = 1000;
+const MAX_PUSH_BATCH: usize = 1000;
+/// Older snapshots are pruned once a team has more than this many.
+const SNAPSHOT_RETENTION: i64 = 3;
+const KEY_BUNDLE_PAYLOAD_TYPE: &str = "rk_transfer_v1";
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS relay_teams (
+    team_id TEXT PRIMARY KEY NOT NULL,
+    key_version INTEGER NOT NULL DEFAULT 0,
+    keys_active INTEGER NOT NULL DEFAULT 0,
+    pending_key_version INTEGER,
+    pending_challenge TEXT,
+    pending_nonce TEXT,
+    pending_device_id TEXT,
+    reset_at TEXT
+);
+CREATE TABLE IF NOT EXISTS relay_devices (
+    id TEXT PRIMARY KEY NOT NULL,
+    team_id TEXT NOT NULL,
+    device_nonce TEXT NOT NULL,
+    display_name TEXT NOT NULL,
+    platform TEXT NOT NULL,
+    os_version TEXT,
+    app_version TEXT,
+    trust_state TEXT NOT NULL,
+    trusted_key_version INTEGER,
+    key_envelope TEXT,
+    pull_cursor INTEGER,
+    token_hash TEXT,
+    last_seen_at TEXT,
+    created_at TEXT NOT NULL,
+    UNIQUE (team_id, device_nonce)
+);
+CREATE TABLE IF NOT EXISTS relay_pairings (
+    id TEXT PRIMARY KEY NOT NULL,
+    team_id TEXT NOT NULL,
+    issuer_device_id TEXT NOT NULL,
+    code_hash TEXT NOT NULL,
+    issuer_ephemeral_pub TEXT NOT NULL,
+    claimer_device_id TEXT,
+    claimer_ephemeral_pub TEXT,
+    status TEXT NOT NULL,
+    key_version INTEGER NOT NULL,
+    expires_at TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_relay_pairings_code ON relay_pairings (team_id, code_hash);
+CREATE TABLE IF NOT EXISTS relay_pairing_messages (
+    id TEXT PRIMARY KEY NOT NULL,
+    pairing_id TEXT NOT NULL,
+    payload_type TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS relay_events (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    team_id TEXT NOT NULL,
+    event_id TEXT NOT NULL,
+    device_id TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    entity TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    client_timestamp TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    payload_key_version INTEGER NOT NULL,
+    server_timestamp TEXT NOT NULL,
+    UNIQUE (team_id, event_id)
+);
+CREATE TABLE IF NOT EXISTS relay_snapshots (
+    id TEXT PRIMARY KEY NOT NULL,
+    team_id TEXT NOT NULL,
+    event_id TEXT NOT NULL,
+    device_id TEXT NOT NULL,
+    schema_version INTEGER NOT NULL,
+    covers_tables TEXT NOT NULL,
+    oplog_seq INTEGER NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    checksum TEXT NOT NULL,
+    metadata_payload TEXT NOT NULL,
+    payload_key_version INTEGER NOT NULL,
+    data BLOB NOT NULL,
+    created_at TEXT NOT NULL,
+    UNIQUE (team_id, event_id)
+);
+"#;
+
+/// Errors surfaced by the relay. `code()` is the machine-readable code sent to
+/// clients, matching the codes the cloud service uses where one exists.
+#[derive(Debug, Error)]
+pub enum RelayError {
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    Forbidden(String),
+    #[error("{0}")]
+    Unauthenticated(String),
+    #[error("{message}")]
+    Conflict { code: &'static str, message: String },
+    #[error("{0}")]
+    Invalid(String),
+    #[error("Relay storage error: {0}")]
+    Storage(String),
+}
+
+impl RelayError {
+    pub fn code(&self) -> &str {
+        match self {
+            RelayError::NotFound(_) => "NOT_FOUND",
+            RelayError::Forbidden(_) => "DEVICE_NOT_TRUSTED",
+            RelayError::Unauthenticated(_) => "INVALID_DEVICE_TOKEN",
+            RelayError::Conflict { code, .. } => code,
+            RelayError::Invalid(_) => "INVALID_REQUEST",
+            RelayError::Storage(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    fn conflict(code: &'static str, message: impl Into<String>) -> Self {
+        RelayError::Conflict {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<rusqlite::Error> for RelayError {
+    fn from(err: rusqlite::Error) -> Self {
+        RelayError::Storage(err.to_string())
+    }
+}
+
+type Result<T> = std::result::Result<T, RelayError>;
+
+fn now() -> String {
+    timestamp(Utc::now())
+}
+
+/// Fixed-width UTC timestamps so they compare correctly as strings in SQL.
+fn timestamp(value: chrono::DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn random_hex(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn trust_state_from_str(value: &str) -> TrustState {
+    match value {
+        "trusted" => TrustState::Trusted,
+        "revoked" => TrustState::Revoked,
+        _ => TrustState::Untrusted,
+    }
+}
+
+fn pairing_status_from_str(value: &str) -> PairingStatus {
+    match value {
+        "claimed" => PairingStatus::Claimed,
+        "approved" => PairingStatus::Approved,
+        "completed" => PairingStatus::Completed,
+        "cancelled" => PairingStatus::Cancelled,
+        "expired" => PairingStatus::Expired,
+        _ => PairingStatus::Open,
+    }
+}
+
+fn entity_to_str(entity: &SyncEntity) -> Result<String> {
+    match serde_json::to_value(entity) {
+        Ok(serde_json::Value::String(value)) => Ok(value),
+        _ => Err(RelayError::Invalid("Unsupported sync entity".to_string())),
+    }
+}
+
+fn entity_from_str(value: String) -> rusqlite::Result<SyncEntity> {
+    serde_json::from_value(serde_json::Value::String(value)).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+struct TeamRow {
+    key_version: i32,
+    keys_active: bool,
+    pending_key_version: Option<i32>,
+    pending_challenge: Option<String>,
+    pending_nonce: Option<String>,
+    pending_device_id: Option<String>,
+}
+
+struct DeviceRow {
+    id: String,
+    display_name: String,
+    platform: String,
+    os_version: Option<String>,
+    app_version: Option<String>,
+    trust_state: String,
+    trusted_key_version: Option<i32>,
+    pull_cursor: Option<i64>,
+    last_seen_at: Option<String>,
+    created_at: String,
+}
+
+const DEVICE_COLUMNS: &str = "id, display_name, platform, os_version, app_version, trust_state, \
+     trusted_key_version, pull_cursor, last_seen_at, created_at";
+
+impl DeviceRow {
+    fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            display_name: row.get(1)?,
+            platform: row.get(2)?,
+            os_version: row.get(3)?,
+            app_version: row.get(4)?,
+            trust_state: row.get(5)?,
+            trusted_key_version: row.get(6)?,
+            pull_cursor: row.get(7)?,
+            last_seen_at: row.get(8)?,
+            created_at: row.get(9)?,
+        })
+    }
+
+    fn is_trusted_at(&self, team: &TeamRow) -> bool {
+        team.keys_active
+            && self.trust_state == "trusted"
+            && self.trusted_key_version == Some(team.key_version)
+    }
+
+    fn into_device(self, team_id: &str) -> Device {
+        Device {
+            id: self.id,
+            user_id: team_id.to_string(),
+            display_name: self.display_name,
+            platform: self.platform,
+            device_public_key: None,
+            trust_state: trust_state_from_str(&self.trust_state),
+            trusted_key_version: self.trusted_key_version.map(f64::from),
+            os_version: self.os_version,
+            app_version: self.app_version,
+            last_seen_at: self.last_seen_at,
+            created_at: self.created_at,
+        }
+    }
+}
+
+struct PairingRow {
+    id: String,
+    issuer_device_id: String,
+    issuer_ephemeral_pub: String,
+    claimer_device_id: Option<String>,
+    claimer_ephemeral_pub: Option<String>,
+    status: String,
+    key_version: i32,
+    expires_at: String,
+}
+
+impl PairingRow {
+    /// Status with expiry applied; unfinished sessions past their deadline are expired.
+    fn effective_status(&self) -> PairingStatus {
+        let status = pairing_status_from_str(&self.status);
+        let unfinished = matches!(
+            status,
+            PairingStatus::Open | PairingStatus::Claimed | PairingStatus::Approved
+        );
+        if unfinished && self.expires_at < now() {
+            PairingStatus::Expired
+        } else {
+            status
+        }
+    }
+
+    fn ensure_participant(&self, device_id: &str) -> Result<()> {
+        if self.issuer_device_id == device_id
+            || self.claimer_device_id.as_deref() == Some(device_id)
+        {
+            Ok(())
+        } else {
+            Err(RelayError::NotFound(format!(
+                "Pairing {} not found",
+                self.id
+            )))
+        }
+    }
+}
+
+fn ensure_team(conn: &Connection, team_id: &str) -> Result<TeamRow> {
+    conn.execute(
+        "INSERT OR IGNORE INTO relay_teams (team_id) VALUES (?1)",
+        params![team_id],
+    )?;
+    Ok(conn.query_row(
+        "SELECT key_version, keys_active, pending_key_version, pending_challenge, pending_nonce, \
+         pending_device_id FROM relay_teams WHERE team_id = ?1",
+        params![team_id],
+        |row| {
+            Ok(TeamRow {
+                key_version: row.get(0)?,
+                keys_active: row.get::<_, i64>(1)? != 0,
+                pending_key_version: row.get(2)?,
+                pending_challenge: row.get(3)?,
+                pending_nonce: row.get(4)?,
+                pending_device_id: row.get(5)?,
+            })
+        },
+    )?)
+}
+
+fn load_device(conn: &Connection, team_id: &str, device_id: &str) -> Result<DeviceRow> {
+    let device = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM relay_devices WHERE team_id = ?1 AND id = ?2",
+                DEVICE_COLUMNS
+            ),
+            params![team_id, device_id],
+            DeviceRow::from_row,
+        )
+        .optional()?
+        .ok_or_else(|| RelayError::NotFound(format!("Device {} not found", device_id)))?;
+    conn.execute(
+        "UPDATE relay_devices SET last_seen_at = ?1 WHERE id = ?2",
+        params![now(), device_id],
+    )?;
+    Ok(device)
+}
+
+/// Loads a device that holds the team's current key.
+fn load_trusted_device(
+    conn: &Connection,
+    team_id: &str,
+    device_id: &str,
+) -> Result<(TeamRow, DeviceRow)> {
+    let team = ensure_team(conn, team_id)?;
+    let device = load_device(conn, team_id, device_id)?;
+    if !device.is_trusted_at(&team) {
+        return Err(RelayError::Forbidden(format!(
+            "Device {} is not trusted at the current key version",
+            device_id
+        )));
+    }
+    Ok((team, device))
+}
+
+fn trusted_devices(conn: &Connection, team_id: &str) -> Result<Vec<TrustedDeviceSummary>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, display_name, platform, last_seen_at FROM relay_devices \
+         WHERE team_id = ?1 AND trust_state = 'trusted' ORDER BY created_at",
+    )?;
+    let rows = stmt.query_map(params![team_id], |row| {
+        Ok(TrustedDeviceSummary {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            platform: row.get(2)?,
+            last_seen_at: row.get(3)?,
+        })
+    })?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+fn head_seq(conn: &Connection, team_id: &str) -> Result<i64> {
+    Ok(conn.query_row(
+        "SELECT COALESCE(MAX(seq), 0) FROM relay_events WHERE team_id = ?1",
+        params![team_id],
+        |row| row.get(0),
+    )?)
+}
+
+fn latest_snapshot_row(conn: &Connection, team_id: &str) -> Result<Option<SnapshotLatestResponse>> {
+    Ok(conn
+        .query_row(
+            "SELECT id, schema_version, covers_tables, oplog_seq, size_bytes, checksum, created_at \
+             FROM relay_snapshots WHERE team_id = ?1 \
+             ORDER BY oplog_seq DESC, created_at DESC LIMIT 1",
+            params![team_id],
+            |row| {
+                let covers_tables: String = row.get(2)?;
+                Ok(SnapshotLatestResponse {
+                    snapshot_id: row.get(0)?,
+                    schema_version: row.get(1)?,
+                    covers_tables: covers_tables
+                        .split(',')
+                        .filter(|value| !value.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                    oplog_seq: row.get(3)?,
+                    size_bytes: row.get(4)?,
+                    checksum: row.get(5)?,
+                    created_at: row.get(6)?,
+                })
+            },
+        )
+        .optional()?)
+}
+
+fn latest_snapshot_ref(conn: &Connection, team_id: &str) -> Result<Option<SyncLatestSnapshotRef>> {
+    Ok(
+        latest_snapshot_row(conn, team_id)?.map(|snapshot| SyncLatestSnapshotRef {
+            snapshot_id: snapshot.snapshot_id,
+            schema_version: snapshot.schema_version,
+            oplog_seq: snapshot.oplog_seq,
+        }),
+    )
+}
+
+fn load_pairing(conn: &Connection, team_id: &str, pairing_id: &str) -> Result<PairingRow> {
+    conn.query_row(
+        "SELECT id, issuer_device_id, issuer_ephemeral_pub, claimer_device_id, \
+         claimer_ephemeral_pub, status, key_version, expires_at \
+         FROM relay_pairings WHERE team_id = ?1 AND id = ?2",
+        params![team_id, pairing_id],
+        |row| {
+            Ok(PairingRow {
+                id: row.get(0)?,
+                issuer_device_id: row.get(1)?,
+                issuer_ephemeral_pub: row.get(2)?,
+                claimer_device_id: row.get(3)?,
+                claimer_ephemeral_pub: row.get(4)?,
+                status: row.get(5)?,
+                key_version: row.get(6)?,
+                expires_at: row.get(7)?,
+            })
+        },
+    )
+    .optional()?
+    .ok_or_else(|| RelayError::NotFound(format!("Pairing {} not found", pairing_id)))
+}
+
+fn set_pairing_status(conn: &Connection, pairing_id: &str, status: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE relay_pairings SET status = ?1 WHERE id = ?2",
+        params![status, pairing_id],
+    )?;
+    Ok(())
+}
+
+fn verify_challenge_response(team: &TeamRow, challenge_response: Option<&str>) -> Result<()> {
+    let (Some(challenge), Some(nonce)) = (&team.pending_challenge, &team.pending_nonce) else {
+        return Err(RelayError::conflict(
+            "KEY_CHALLENGE_MISSING",
+            "No key challenge is pending for this team",
+        ));
+    };
+    if let Some(response) = challenge_response {
+        if response != hash_sha256(&format!("{}:{}", challenge, nonce)) {
+            return Err(RelayError::Invalid(
+                "Challenge response does not match the issued challenge".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub struct SyncRelayStore {
+    conn: Mutex<Connection>,
+}
+
+impl SyncRelayStore {
+    /// Opens (and creates if needed) the relay database at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                RelayError::Storage(format!("Failed to create relay directory: {}", e))
+            })?;
+        }
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn with_conn<T>(&self, f: impl FnOnce(&mut Connection) -> Result<T>) -> Result<T> {
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|_| RelayError::Storage("Relay connection lock poisoned".to_string()))?;
+        f(&mut conn)
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // Devices
+    // ─────────────────────────────────────────────────────────────────────────
+
+    pub fn enroll_device(
+        &self,
+        team_id: &str,
+        request: RegisterDeviceRequest,
+    ) -> Result<EnrollDeviceResponse> {
+        if request.device_nonce.trim().is_empty() || request.display_name.trim().is_empty() {
+            return Err(RelayError::Invalid(
+                "device_nonce and display_name are required".to_string(),
+            ));
+        }
+
+        self.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            let team = ensure_team(&tx, team_id)?;
+            let existing_id: Option<String> = tx
+                .query_row(
+                    "SELECT id FROM relay_devices WHERE team_id = ?1 AND device_nonce = ?2",
+                    params![team_id, request.device_nonce],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let timestamp = now();
+            // Every enrollment issues a fresh device token; only its hash is kept
+            let device_token = random_hex(32);
+            let token_hash = hash_sha256(&device_token);
+            let device_id = match existing_id {
+                Some(id) => {
+                    // Re-enrolling a revoked device lets it pair again
+                    tx.execute(
+                        "UPDATE relay_devices SET display_name = ?1, platform = ?2, os_version = ?3, \
+                         app_version = ?4, last_seen_at = ?5, token_hash = ?6, \
+                         trust_state = CASE WHEN trust_state = 'revoked' THEN 'untrusted' ELSE trust_state END \
+                         WHERE id = ?7",
+                        params![
+                            request.display_name,
+                            request.platform,
+                            request.os_version,
+                            request.app_version,
+                            timestamp,
+                            token_hash,
+                            id
+                        ],
+                    )?;
+                    id
+                }
+                None => {
+                    let id = Uuid::new_v4().to_string();
+                    tx.execute(
+                        "INSERT INTO relay_devices (id, team_id, device_nonce, display_name, platform, \
+                         os_version, app_version, trust_state, token_hash, last_seen_at, created_at) \
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'untrusted', ?8, ?9, ?9)",
+                        params![
+                            id,
+                            team_id,
+                            request.device_nonce,
+                            request.display_name,
+                            request.platform,
+                            request.os_version,
+                            request.app_version,
+                            token_hash,
+                            timestamp
+                        ],
+                    )?;
+                    id
+                }
+            };
+            let device = load_device(&tx, team_id, &device_id)?;
+
+            let response = if !team.keys_active {
+                EnrollDeviceResponse::Bootstrap {
+                    device_id,
+                    e2ee_key_version: team.key_version,
+                    device_token: Some(device_token),
+                }
+            } else if device.is_trusted_at(&team) {
+                EnrollDeviceResponse::Ready {
+                    device_id,
+                    e2ee_key_version: team.key_version,
+                    trust_state: TrustState::Trusted,
+                    device_token: Some(device_token),
+                }
+            } else {
+                EnrollDeviceResponse::Pair {
+                    device_id,
+                    e2ee_key_version: team.key_version,
+                    require_sas: true,
+                    pairing_ttl_seconds: PAIRING_TTL_SECONDS as i32,
+                    trusted_devices: trusted_devices(&tx, team_id)?,
+                    device_token: Some(device_token),
+                }
+            };
+            tx.commit()?;
+            Ok(response)
+        })
+    }
+
+    /// Checks that `device_token` is the token issued to `device_id` when it
+    /// last enrolled, so one device cannot act as another in the same team.
+    pub fn authenticate_device(
+        &self,
+        team_id: &str,
+        device_id: &str,
+        device_token: &str,
+    ) -> Result<()> {
+        self.with_conn(|conn| {
+            let token_hash: Option<String> = conn
+                .query_row(
+                    "SELECT token_hash FROM relay_devices WHERE team_id = ?1 AND id = ?2",
+                    params![team_id, device_id],
+                    |row| row.get(0),
+                )
+                .optional()?
+                .flatten();
+            if token_hash.as_deref() == Some(hash_sha256(device_token).as_str()) {
+                Ok(())
+            } else {
+                Err(RelayError::Unauthenticated(format!(
+                    "Invalid device token for device {}",
+                    device_id
+                )))
+            }
+        })
+    }
+
+    pub fn get_device(&self, team_id: &str, device_id: &str) -> Result<Device> {
+        self.with_conn(|conn| Ok(load_device(conn, team_id, device_id)?.into_device(team_id)))
+    }
+
+    pub fn list_devices(&self, team_id: &str) -> Result<Vec<Device>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM relay_devices WHERE team_id = ?1 ORDER BY created_at",
+                DEVICE_COLUMNS
+            ))?;
+            let rows = stmt.query_map(params![team_id], DeviceRow::from_row)?;
+            let devices = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(devices
+                .into_iter()
+                .map(|device| device.into_device(team_id))
+                .collect())
+        })
+    }
+
+    pub fn update_device(
+        &self,
+        team_id: &str,
+        device_id: &str,
+        request: UpdateDeviceRequest,
+    ) -> Result<()> {
+        self.with_conn(|conn| {
+            load_device(conn, team_id, device_id)?;
+            if let Some(name) = request
+                .display_name
+                .as_deref()
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+            {
+                conn.execute(
+                    "UPDATE relay_devices SET display_name = ?1 WHERE id = ?2",
+                    params![name, device_id],
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    pub fn delete_device(&self, team_id: &str, device_id: &str) -> Result<()> {
+        self.with_conn(|conn| {
+            let deleted = conn.execute(
+                "DELETE FROM relay_devices WHERE team_id = ?1 AND id = ?2",
+                params![team_id, device_id],
+            )?;
+            if deleted == 0 {
+                return Err(RelayError::NotFound(format!(
+                    "Device {} not found",
+                    device_id
+                )));
+            }
+            Ok(())
+        })
+    }
+
+    pub fn revoke_device(&self, team_id: &str, device_id: &str) -> Result<()> {
+        self.with_conn(|conn| {
+            let updated = conn.execute(
+                "UPDATE relay_devices SET trust_state = 'revoked', trusted_key_version = NULL, \
+                 key_envelope = NULL WHERE team_id = ?1 AND id = ?2",
+                params![team_id, device_id],
+            )?;
+            if updated == 0 {
+                return Err(RelayError::NotFound(format!(
+                    "Device {} not found",
+                    device_id
+                )));
+            }
+            Ok(())
+        })
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // Team keys
+    // ─────────────────────────────────────────────────────────────────────────
+
+    pub fn initialize_keys(&self, team_id: &str, device_id: &str) -> Result<InitializeKeysResult> {
+        self.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            let team = ensure_team(&tx, team_id)?;
+            let device = load_device(&tx, team_id, device_id)?;
+
+            let result = if team.keys_active {
+                if device.is_trusted_at(&team) {
+                    InitializeKeysResult::Ready {
+                        e2ee_key_version: team.key_version,
+                    }
+                } else {
+                    InitializeKeysResult::PairingRequired {
+                        e2ee_key_version: team.key_version,
+                        require_sas: true,
+                        pairing_ttl_seconds: PAIRING_TTL_SECONDS as i32,
+                        trusted_devices: trusted_devices(&tx, team_id)?,
+                    }
+                }
+            } else {
+                let challenge = random_hex(32);
+                let nonce = random_hex(16);
+                let key_version = team.key_version + 1;
+                tx.execute(
+                    "UPDATE relay_teams SET pending_key_version = ?1, pending_challenge = ?2, \
+                     pending_nonce = ?3, pending_device_id = ?4 WHERE team_id = ?5",
+                    params![key_version, challenge, nonce, device_id, team_id],
+                )?;
+                InitializeKeysResult::Bootstrap {
+                    challenge,
+                    nonce,
+                    key_version,
+                }
+            };
+            tx.commit()?;
+            Ok(result)
+        })
+    }
+
+    pub fn commit_initialize_keys(
+        &self,
+        team_id: &str,
+        request: CommitInitializeKeysRequest,
+    ) -> Result<CommitInitializeKeysResponse> {
+        self.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            let team = ensure_team(&tx, team_id)?;
+            load_device(&tx, team_id, &request.device_id)?;
+            if team.keys_active {
+                return Err(RelayError::conflict(
+                    "KEYS_ALREADY_INITIALIZED",
+                    "Team keys are already initialized; pair with a trusted device instead",
+                ));
+            }
+            if team.pending_device_id.as_deref() != Some(request.device_id.as_str())
+                || team.pending_key_version != Some(request.key_version)
+            {
+                return Err(RelayError::conflict(
+                    "KEY_CHALLENGE_MISMATCH",
+                    "Key initialization was not started by this device at this version",
+                ));
+            }
+            verify_challenge_response(&team, request.challenge_response.as_deref())?;
+            let challenge = team.pending_challenge.as_deref().unwrap_or_default();
+            let expected_signature = hash_sha256(&format!(
+                "{}:{}:{}",
+                challenge, request.key_version, request.device_key_envelope
+            ));
+            if request.signature != expected_signature {
+                return Err(RelayError::Invalid(
+                    "Key commitment signature is invalid".to_string(),
+                ));
+            }
+
+            tx.execute(
+                "UPDATE relay_teams SET key_version = ?1, keys_active = 1, pending_key_version = NULL, \
+                 pending_challenge = NULL, pending_nonce = NULL, pending_device_id = NULL \
+                 WHERE team_id = ?2",
+                params![request.key_version, team_id],
+            )?;
+            tx.execute(
+                "UPDATE relay_devices SET trust_state = 'trusted', trusted_key_version = ?1, \
+                 key_envelope = ?2 WHERE id = ?3",
+                params![
+                    request.key_version,
+                    request.device_key_envelope,
+                    request.device_id
+                ],
+            )?;
+            tx.commit()?;
+            Ok(CommitInitializeKeysResponse {
+                success: true,
+                key_state: KeyState::Active,
+            })
+        })
+    }
+
+    pub fn rotate_keys(
+        &self,
+        team_id: &str,
+        initiator_device_id: &str,
+    ) -> Result<RotateKeysResponse> {
+        self.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            let (team, _) = load_trusted_device(&tx, team_id, initiator_device_id)?;
+            let challenge = random_hex(32);
+            let nonce = random_hex(16);
+            let new_key_version = team.key_version + 1;
+            tx.execute(
+                "UPDATE relay_teams SET pending_key_version = ?1, pending_challenge = ?2, \
+                 pending_nonce = ?3, pending_device_id = ?4 WHERE team_id = ?5",
+                params![
+                    new_key_version,
+                    challenge,
+                    nonce,
+                    initiator_device_id,
+                    team_id
+                ],
+            )?;
+            tx.commit()?;
+            Ok(RotateKeysResponse {
+                challenge,
+                nonce,
+                new_key_version,
+            })
+        })
+    }
+
+    /// Completes a rotation. Trusted devices without an envelope for the new
+    /// version lose their trust and have to pair again.
+    pub fn commit_rotate_keys(
+        &self,
+        team_id: &str,
+        device_id: &str,
+        request: CommitRotateKeysRequest,
+    ) -> Result<CommitRotateKeysResponse> {
+        self.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            let (team, _) = load_trusted_device(&tx, team_id, device_id)?;
+            if team.pending_device_id.as_deref() != Some(device_id)
+                || team.pending_key_version != Some(request.new_key_version)
+            {
+                return Err(RelayError::conflict(
+                    "KEY_CHALLENGE_MISMATCH",
+                    "Key rotation was not started by this device at this version",
+                ));
+            }
+            verify_challenge_response(&team, request.challenge_response.as_deref())?;
+
+            tx.execute(
+                "UPDATE relay_devices SET trust_state = 'untrusted', trusted_key_version = NULL, \
+                 key_envelope = NULL WHERE team_id = ?1 AND trust_state = 'trusted'",
+                params![team_id],
+            )?;
+            for envelope in &request.envelopes {
+                let updated = tx.execute(
+                    "UPDATE relay_devices SET trust_state = 'trusted', trusted_key_version = ?1, \
+                     key_envelope = ?2 WHERE team_id = ?3 AND id = ?4 AND trust_state != 'revoked'",
+                    params![
+                        request.new_key_version,
+                        envelope.device_key_envelope,
+                        team_id,
+                        envelope.device_id
+                    ],
+                )?;
+                if updated == 0 {
+                    return Err(RelayError::Invalid(format!(
+                        "Envelope targets unknown or revoked device {}",
+                        envelope.device_id
+                    )));
+                }
+            }
+            tx.execute(
+                "UPDATE relay_teams SET key_version = ?1, pending_key_version = NULL, \
+                 pending_challenge = NULL, pending_nonce = NULL, pending_device_id = NULL \
+                 WHERE team_id = ?2",
+                params![request.new_key_version, team_id],
+            )?;
+            tx.commit()?;
+            Ok(CommitRotateKeysResponse {
+                success: true,
+                key_version: request.new_key_version,
+            })
+        })
+    }
+
+    /// Drops the team's keys, oplog, snapshots and pairings. Devices stay
+    /// enrolled but untrusted, and the next key initialization bootstraps again.
+    pub fn reset_team(&self, team_id: &str) -> Result<ResetTeamSyncResponse> {
+        self.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            let team = ensure_team(&tx, team_id)?;
+            let reset_at = now();
+            tx.execute(
+                "DELETE FROM relay_pairing_messages WHERE pairing_id IN \
+                 (SELECT id FROM relay_pairings WHERE team_id = ?1)",
+                params![team_id],
+            )?;
+            for table in ["relay_pairings", "relay_events", "relay_snapshots"] {
+                tx.execute(
+                    &format!("DELETE FROM {} WHERE team_id = ?1", table),
+                    params![team_id],
+                )?;
+            }
+            tx.execute(
+                "UPDATE relay_devices SET trust_state = 'untrusted', trusted_key_version = NULL, \
+                 key_envelope = NULL, pull_cursor = NULL \
+                 WHERE team_id = ?1 AND trust_state != 'revoked'",
+                params![team_id],
+            )?;
+            tx.execute(
+                "UPDATE relay_teams SET keys_active = 0, pending_key_version = NULL, \
+                 pending_challenge = NULL, pending_nonce = NULL, pending_device_id = NULL, \
+                 reset_at = ?1 WHERE team_id = ?2",
+                params![reset_at, team_id],
+            )?;
+            tx.commit()?;
+            Ok(ResetTeamSyncResponse {
+                success: true,
+                key_version: team.key_version,
+                reset_at: Some(reset_at),
+            })
+        })
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // Pairing
+    // ─────────────────────────────────────────────────────────────────────────
+
+    pub fn create_pairing(
+        &self,
+        team_id: &str,
+        issuer_device_id: &str,
+        request: CreatePairingRequest,
+    ) -> Result<CreatePairingResponse> {
+        let code_hash = request.code_hash.to_ascii_lowercase();
+        if code_hash.len() != 64 || !code_hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(RelayError::Invalid(
+                "code_hash must be a hex-encoded SHA-256 digest".to_string(),
+            ));
+        }
+        if request.ephemeral_public_key.trim().is_empty() {
+            return Err(RelayError::Invalid(
+                "ephemeral_public_key is required".to_string(),
+            ));
+        }
+
+        self.with_conn(|conn| {
+            let (team, _) = load_trusted_device(conn, team_id, issuer_device_id)?;
+            let pairing_id = Uuid::new_v4().to_string();
+            let created_at = Utc::now();
+            let expires_at = timestamp(created_at + Duration::seconds(PAIRING_TTL_SECONDS));
+            conn.execute(
+                "INSERT INTO relay_pairings (id, team_id, issuer_device_id, code_hash, \
+                 issuer_ephemeral_pub, status, key_version, expires_at, created_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, 'open', ?6, ?7, ?8)",
+                params![
+                    pairing_id,
+                    team_id,
+                    issuer_device_id,
+                    code_hash,
+                    request.ephemeral_public_key,
+                    team.key_version,
+                    expires_at,
+                    timestamp(created_at)
+                ],
+            )?;
+            Ok(CreatePairingResponse {
+                pairing_id,
+                expires_at,
+                key_version: team.key_version,
+                require_sas: true,
+            })
+        })
+    }
+
+    pub fn get_pairing(
+        &self,
+        team_id: &str,
+        device_id: &str,
+        pairing_id: &str,
+    ) -> Result<GetPairingResponse> {
+        self.with_conn(|conn| {
+            let pairing = load_pairing(conn, team_id, pairing_id)?;
+            pairing.ensure_participant(device_id)?;
+            Ok(GetPairingResponse {
+                status: pairing.effective_status(),
+                pairing_id: pairing.id,
+                claimer_device_id: pairing.claimer_device_id,
+                claimer_ephemeral_pub: pairing.claimer_ephemeral_pub,
+                expires_at: pairing.expires_at,
+            })
+        })
+    }
+
+    pub fn approve_pairing(
+        &self,
+        team_id: &str,
+        issuer_device_id: &str,
+        pairing_id: &str,
+    ) -> Result<()> {
+        self.with_conn(|conn| {
+            let pairing = load_issuer_pairing(conn, team_id, issuer_device_id, pairing_id)?;
+            match pairing.effective_status() {
+                PairingStatus::Claimed | PairingStatus::Approved => {
+                    set_pairing_status(conn, pairing_id, "approved")
+                }
+                status => Err(invalid_pairing_state(&status)),
+            }
+        })
+    }
+
+    /// Stores the issuer's encrypted key bundle for the claimer to collect.
+    pub fn complete_pairing(
+        &self,
+        team_id: &str,
+        issuer_device_id: &str,
+        pairing_id: &str,
+        request: CompletePairingRequest,
+    ) -> Result<()> {
+        self.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            let pairing = load_issuer_pairing(&tx, team_id, issuer_device_id, pairing_id)?;
+            match pairing.effective_status() {
+                PairingStatus::Claimed | PairingStatus::Approved => {}
+                status => return Err(invalid_pairing_state(&status)),
+            }
+            tx.execute(
+                "INSERT INTO relay_pairing_messages (id, pairing_id, payload_type, payload, created_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    Uuid::new_v4().to_string(),
+                    pairing_id,
+                    KEY_BUNDLE_PAYLOAD_TYPE,
+                    request.encrypted_key_bundle,
+                    now()
+                ],
+            )?;
+            set_pairing_status(&tx, pairing_id, "completed")?;
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    pub fn cancel_pairing(&self, team_id: &str, device_id: &str, pairing_id: &str) -> Result<()> {
+        self.with_conn(|conn| {
+            let pairing = load_pairing(conn, team_id, pairing_id)?;
+            pairing.ensure_participant(device_id)?;
+            match pairing.effective_status() {
+                PairingStatus::Completed => Err(invalid_pairing_state(&PairingStatus::Completed)),
+                _ => set_pairing_status(conn, pairing_id, "cancelled"),
+            }
+        })
+    }
+
+    pub fn claim_pairing(
+        &self,
+        team_id: &str,
+        claimer_device_id: &str,
+        request: ClaimPairingRequest,
+    ) -> Result<ClaimPairingResponse> {
+        if request.ephemeral_public_key.trim().is_empty() {
+            return Err(RelayError::Invalid(
+                "ephemeral_public_key is required".to_string(),
+            ));
+        }
+        let code_hash = hash_pairing_code(&request.code);
+
+        self.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            let claimer = load_device(&tx, team_id, claimer_device_id)?;
+            if claimer.trust_state == "revoked" {
+                return Err(RelayError::Forbidden(format!(
+                    "Device {} has been revoked",
+                    claimer_device_id
+                )));
+            }
+            let pairing_id: String = tx
+                .query_row(
+                    "SELECT id FROM relay_pairings WHERE team_id = ?1 AND code_hash = ?2 \
+                     AND status = 'open' AND expires_at > ?3 ORDER BY created_at DESC LIMIT 1",
+                    params![team_id, code_hash, now()],
+                    |row| row.get(0),
+                )
+                .optional()?
+                .ok_or_else(|| {
+                    RelayError::NotFound("Pairing code is invalid or has expired".to_string())
+                })?;
+            let pairing = load_pairing(&tx, team_id, &pairing_id)?;
+            if pairing.issuer_device_id == claimer_device_id {
+                return Err(RelayError::Invalid(
+                    "A device cannot claim its own pairing session".to_string(),
+                ));
+            }
+            tx.execute(
+                "UPDATE relay_pairings SET status = 'claimed', claimer_device_id = ?1, \
+                 claimer_ephemeral_pub = ?2 WHERE id = ?3",
+                params![claimer_device_id, request.ephemeral_public_key, pairing_id],
+            )?;
+            tx.commit()?;
+            Ok(ClaimPairingResponse {
+                session_id: pairing.id,
+                issuer_ephemeral_pub: pairing.issuer_ephemeral_pub,
+                e2ee_key_version: pairing.key_version,
+                require_sas: true,
+                expires_at: pairing.expires_at,
+            })
+        })
+    }
+
+    pub fn pairing_messages(
+        &self,
+        team_id: &str,
+        claimer_device_id: &str,
+        pairing_id: &str,
+    ) -> Result<PairingMessagesResponse> {
+        self.with_conn(|conn| {
+            let pairing = load_claimer_pairing(conn, team_id, claimer_device_id, pairing_id)?;
+            let mut stmt = conn.prepare(
+                "SELECT id, payload_type, payload, created_at FROM relay_pairing_messages \
+                 WHERE pairing_id = ?1 ORDER BY created_at",
+            )?;
+            let rows = stmt.query_map(params![pairing_id], |row| {
+                Ok(PairingMessage {
+                    id: row.get(0)?,
+                    payload_type: row.get(1)?,
+                    payload: row.get(2)?,
+                    created_at: row.get(3)?,
+                })
+            })?;
+            Ok(PairingMessagesResponse {
+                session_status: pairing.effective_status(),
+                messages: rows.collect::<rusqlite::Result<_>>()?,
+            })
+        })
+    }
+
+    /// Marks the claimer as trusted once it has received the key bundle.
+    pub fn confirm_pairing(
+        &self,
+        team_id: &str,
+        claimer_device_id: &str,
+        pairing_id: &str,
+        _request: ConfirmPairingRequest,
+    ) -> Result<ConfirmPairingResponse> {
+        self.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            let team = ensure_team(&tx, team_id)?;
+            let pairing = load_claimer_pairing(&tx, team_id, claimer_device_id, pairing_id)?;
+            let status = pairing.effective_status();
+            if status != PairingStatus::Completed {
+                return Err(invalid_pairing_state(&status));
+            }
+            if !team.keys_active || pairing.key_version != team.key_version {
+                return Err(RelayError::conflict(
+                    "KEY_VERSION_MISMATCH",
+                    format!(
+                        "Pairing was issued for key version {} but the team is at {}",
+                        pairing.key_version, team.key_version
+                    ),
+                ));
+            }
+            tx.execute(
+                "UPDATE relay_devices SET trust_state = 'trusted', trusted_key_version = ?1 \
+                 WHERE id = ?2",
+                params![team.key_version, claimer_device_id],
+            )?;
+            tx.commit()?;
+            Ok(ConfirmPairingResponse {
+                success: true,
+                key_version: team.key_version,
+            })
+        })
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // Events
+    // ─────────────────────────────────────────────────────────────────────────
+
+    pub fn push_events(
+        &self,
+        team_id: &str,
+        device_id: &str,
+        request: SyncPushRequest,
+    ) -> Result<SyncPushResponse> {
+        if request.events.len() > MAX_PUSH_BATCH {
+            return Err(RelayError::Invalid(format!(
+                "At most {} events can be pushed at once",
+                MAX_PUSH_BATCH
+            )));
+        }
+
+        self.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            let (team, _) = load_trusted_device(&tx, team_id, device_id)?;
+            let mut accepted = Vec::new();
+            let mut duplicate = Vec::new();
+            let server_timestamp = now();
+
+            for event in request.events {
+                if event.device_id != device_id {
+                    return Err(RelayError::Invalid(format!(
+                        "Event {} belongs to another device",
+                        event.event_id
+                    )));
+                }
+                if event.payload_key_version != team.key_version {
+                    return Err(RelayError::conflict(
+                        "KEY_VERSION_MISMATCH",
+                        format!(
+                            "Event {} uses key version {} but the team is at {}",
+                            event.event_id, event.payload_key_version, team.key_version
+                        ),
+                    ));
+                }
+                let existing: Option<i64> = tx
+                    .query_row(
+                        "SELECT seq FROM relay_events WHERE team_id = ?1 AND event_id = ?2",
+                        params![team_id, event.event_id],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                if let Some(seq) = existing {
+                    duplicate.push(SyncPushResultItem {
+                        event_id: event.event_id,
+                        seq,
+                    });
+                    continue;
+                }
+                tx.execute(
+                    "INSERT INTO relay_events (team_id, event_id, device_id, event_type, entity, \
+                     entity_id, client_timestamp, payload, payload_key_version, server_timestamp) \
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                    params![
+                        team_id,
+                        event.event_id,
+                        device_id,
+                        event.event_type,
+                        entity_to_str(&event.entity)?,
+                        event.entity_id,
+                        event.client_timestamp,
+                        event.payload,
+                        event.payload_key_version,
+                        server_timestamp
+                    ],
+                )?;
+                accepted.push(SyncPushResultItem {
+                    event_id: event.event_id,
+                    seq: tx.last_insert_rowid(),
+                });
+            }
+
+            let server_cursor = head_seq(&tx, team_id)?;
+            tx.commit()?;
+            Ok(SyncPushResponse {
+                accepted,
+                duplicate,
+                server_cursor,
+            })
+        })
+    }
+
+    pub fn pull_events(
+        &self,
+        team_id: &str,
+        device_id: &str,
+        since: Option<i64>,
+        limit: Option<i64>,
+    ) -> Result<SyncPullResponse> {
+        let since = since.unwrap_or(0).max(0);
+        let limit = limit.unwrap_or(DEFAULT_PULL_LIMIT).clamp(1, MAX_PULL_LIMIT);
+
+        self.with_conn(|conn| {
+            load_trusted_device(conn, team_id, device_id)?;
+            let mut events = {
+                let mut stmt = conn.prepare(
+                    "SELECT event_id, device_id, event_type, entity, entity_id, client_timestamp, \
+                     payload, payload_key_version, seq, server_timestamp \
+                     FROM relay_events WHERE team_id = ?1 AND seq > ?2 ORDER BY seq LIMIT ?3",
+                )?;
+                let rows = stmt.query_map(params![team_id, since, limit + 1], |row| {
+                    Ok(SyncEvent {
+                        event_id: row.get(0)?,
+                        device_id: row.get(1)?,
+                        event_type: row.get(2)?,
+                        entity: entity_from_str(row.get(3)?)?,
+                        entity_id: row.get(4)?,
+                        client_timestamp: row.get(5)?,
+                        payload: row.get(6)?,
+                        payload_key_version: row.get(7)?,
+                        seq: row.get(8)?,
+                        user_id: team_id.to_string(),
+                        team_id: team_id.to_string(),
+                        server_timestamp: row.get(9)?,
+                    })
+                })?;
+                rows.collect::<rusqlite::Result<Vec<_>>>()?
+            };
+            let has_more = events.len() as i64 > limit;
+            events.truncate(limit as usize);
+            let next_cursor = events.last().map(|event| event.seq).unwrap_or(since);
+
+            conn.execute(
+                "UPDATE relay_devices SET pull_cursor = MAX(COALESCE(pull_cursor, 0), ?1) \
+                 WHERE id = ?2",
+                params![next_cursor, device_id],
+            )?;
+            let latest_snapshot_seq =
+                latest_snapshot_ref(conn, team_id)?.map(|snapshot| snapshot.oplog_seq);
+
+            Ok(SyncPullResponse {
+                from: since,
+                to: next_cursor,
+                next_cursor,
+                has_more,
+                events,
+                gc_watermark: None,
+                latest_snapshot_seq,
+            })
+        })
+    }
+
+    pub fn events_cursor(&self, team_id: &str, device_id: &str) -> Result<SyncCursorResponse> {
+        self.with_conn(|conn| {
+            load_device(conn, team_id, device_id)?;
+            Ok(SyncCursorResponse {
+                cursor: head_seq(conn, team_id)?,
+                gc_watermark: None,
+                latest_snapshot: latest_snapshot_ref(conn, team_id)?,
+            })
+        })
+    }
+
+    /// The relay never garbage-collects the oplog, so a device is either
+    /// caught up or can pull the tail from wherever its cursor is.
+    pub fn reconcile_ready_state(
+        &self,
+        team_id: &str,
+        device_id: &str,
+    ) -> Result<ReconcileReadyStateResponse> {
+        self.with_conn(|conn| {
+            let device = load_device(conn, team_id, device_id)?;
+            let head = head_seq(conn, team_id)?;
+            let action = if head > device.pull_cursor.unwrap_or(0) {
+                "PULL_TAIL"
+            } else {
+                "NOOP"
+            };
+            Ok(ReconcileReadyStateResponse {
+                action: action.to_string(),
+                cursor: Some(head),
+                latest_snapshot: latest_snapshot_ref(conn, team_id)?,
+            })
+        })
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // Snapshots
+    // ─────────────────────────────────────────────────────────────────────────
+
+    pub fn latest_snapshot(
+        &self,
+        team_id: &str,
+        device_id: &str,
+    ) -> Result<SnapshotLatestResponse> {
+        self.with_conn(|conn| {
+            load_device(conn, team_id, device_id)?;
+            latest_snapshot_row(conn, team_id)?
+                .ok_or_else(|| RelayError::NotFound("No snapshot available".to_string()))
+        })
+    }
+
+    pub fn get_snapshot(
+        &self,
+        team_id: &str,
+        device_id: &str,
+        snapshot_id: &str,
+    ) -> Result<(SnapshotDownloadHeaders, Vec<u8>)> {
+        self.with_conn(|conn| {
+            load_trusted_device(conn, team_id, device_id)?;
+            conn.query_row(
+                "SELECT schema_version, covers_tables, checksum, data FROM relay_snapshots \
+                 WHERE team_id = ?1 AND id = ?2",
+                params![team_id, snapshot_id],
+                |row| {
+                    let covers_tables: String = row.get(1)?;
+                    Ok((
+                        SnapshotDownloadHeaders {
+                            schema_version: row.get(0)?,
+                            covers_tables: covers_tables
+                                .split(',')
+                                .filter(|value| !value.is_empty())
+                                .map(str::to_string)
+                                .collect(),
+                            checksum: row.get(2)?,
+                        },
+                        row.get(3)?,
+                    ))
+                },
+            )
+            .optional()?
+            .ok_or_else(|| RelayError::NotFound(format!("Snapshot {} not found", snapshot_id)))
+        })
+    }
+
+    /// Stores an encrypted snapshot. Uploads are idempotent per snapshot event id.
+    pub fn put_snapshot(
+        &self,
+        team_id: &str,
+        device_id: &str,
+        headers: SnapshotUploadHeaders,
+        data: Vec<u8>,
+    ) -> Result<SnapshotUploadResponse> {
+        if headers.size_bytes != data.len() as i64 {
+            return Err(RelayError::Invalid(format!(
+                "Snapshot size mismatch: header={} payload={}",
+                headers.size_bytes,
+                data.len()
+            )));
+        }
+        let checksum = sha256_checksum(&data);
+        if !headers.checksum.eq_ignore_ascii_case(&checksum) {
+            return Err(RelayError::Invalid(
+                "Snapshot checksum does not match payload".to_string(),
+            ));
+        }
+        let event_id = headers
+            .event_id
+            .clone()
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+        self.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            let (team, _) = load_trusted_device(&tx, team_id, device_id)?;
+            if headers.payload_key_version != team.key_version {
+                return Err(RelayError::conflict(
+                    "KEY_VERSION_MISMATCH",
+                    format!(
+                        "Snapshot uses key version {} but the team is at {}",
+                        headers.payload_key_version, team.key_version
+                    ),
+                ));
+            }
+
+            let existing = tx
+                .query_row(
+                    "SELECT id, oplog_seq, created_at FROM relay_snapshots \
+                     WHERE team_id = ?1 AND event_id = ?2",
+                    params![team_id, event_id],
+                    |row| {
+                        Ok((
+                            row.get::<_, String>(0)?,
+                            row.get::<_, i64>(1)?,
+                            row.get::<_, String>(2)?,
+                        ))
+                    },
+                )
+                .optional()?;
+            if let Some((snapshot_id, oplog_seq, created_at)) = existing {
+                return Ok(SnapshotUploadResponse {
+                    r2_key: format!("relay/{}", snapshot_id),
+                    snapshot_id,
+                    oplog_seq,
+                    created_at,
+                });
+            }
+
+            let head = head_seq(&tx, team_id)?;
+            let oplog_seq = headers.base_seq.map(|seq| seq.min(head)).unwrap_or(head);
+            let snapshot_id = Uuid::new_v4().to_string();
+            let created_at = now();
+            tx.execute(
+                "INSERT INTO relay_snapshots (id, team_id, event_id, device_id, schema_version, \
+                 covers_tables, oplog_seq, size_bytes, checksum, metadata_payload, \
+                 payload_key_version, data, created_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                params![
+                    snapshot_id,
+                    team_id,
+                    event_id,
+                    device_id,
+                    headers.schema_version,
+                    headers.covers_tables.join(","),
+                    oplog_seq,
+                    headers.size_bytes,
+                    checksum,
+                    headers.metadata_payload,
+                    headers.payload_key_version,
+                    data,
+                    created_at
+                ],
+            )?;
+            tx.execute(
+                "DELETE FROM relay_snapshots WHERE team_id = ?1 AND id NOT IN \
+                 (SELECT id FROM relay_snapshots WHERE team_id = ?1 \
+                  ORDER BY oplog_seq DESC, created_at DESC LIMIT ?2)",
+                params![team_id, SNAPSHOT_RETENTION],
+            )?;
+            tx.commit()?;
+            Ok(SnapshotUploadResponse {
+                r2_key: format!("relay/{}", snapshot_id),
+                snapshot_id,
+                oplog_seq,
+                created_at,
+            })
+        })
+    }
+}
+
+fn load_issuer_pairing(
+    conn: &Connection,
+    team_id: &str,
+    issuer_device_id: &str,
+    pairing_id: &str,
+) -> Result<PairingRow> {
+    let pairing = load_pairing(conn, team_id, pairing_id)?;
+    if pairing.issuer_device_id != issuer_device_id {
+        return Err(RelayError::NotFound(format!(
+            "Pairing {} not found",
+            pairing_id
+        )));
+    }
+    Ok(pairing)
+}
+
+fn load_claimer_pairing(
+    conn: &Connection,
+    team_id: &str,
+    claimer_device_id: &str,
+    pairing_id: &str,
+) -> Result<PairingRow> {
+    let pairing = load_pairing(conn, team_id, pairing_id)?;
+    if pairing.claimer_device_id.as_deref() != Some(claimer_device_id) {
+        return Err(RelayError::NotFound(format!(
+            "Pairing {} not found",
+            pairing_id
+        )));
+    }
+    Ok(pairing)
+}
+
+fn invalid_pairing_state(status: &PairingStatus) -> RelayError {
+    RelayError::conflict(
+        "PAIRING_INVALID_STATE",
+        format!("Pairing session is {:?}", status).to_lowercase(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    use wealthfolio_device_sync::SyncPushEventRequest;
+
+    const TEAM: &str = "team-a";
+
+    fn store() -> (tempfile::TempDir, SyncRelayStore) {
+        let dir = tempdir().unwrap();
+        let store = SyncRelayStore::open(dir.path().join("relay.db")).unwrap();
+        (dir, store)
+    }
+
+    fn enroll(store: &SyncRelayStore, nonce: &str) -> EnrollDeviceResponse {
+        store
+            .enroll_device(
+                TEAM,
+                RegisterDeviceRequest {
+                    device_nonce: nonce.to_string(),
+                    display_name: format!("Device {}", nonce),
+                    platform: "linux".to_string(),
+                    os_version: None,
+                    app_version: None,
+                },
+            )
+            .unwrap()
+    }
+
+    /// Enrolls a first device and commits team keys the way the client does.
+    fn bootstrap(store: &SyncRelayStore) -> String {
+        let EnrollDeviceResponse::Bootstrap { device_id, .. } = enroll(store, "nonce-1") else {
+            panic!("first device should bootstrap");
+        };
+        let InitializeKeysResult::Bootstrap {
+            challenge,
+            nonce,
+            key_version,
+        } = store.initialize_keys(TEAM, &device_id).unwrap()
+        else {
+            panic!("expected bootstrap challenge");
+        };
+        let envelope = "envelope".to_string();
+        store
+            .commit_initialize_keys(
+                TEAM,
+                CommitInitializeKeysRequest {
+                    device_id: device_id.clone(),
+                    key_version,
+                    signature: hash_sha256(&format!("{}:{}:{}", challenge, key_version, envelope)),
+                    device_key_envelope: envelope,
+                    challenge_response: Some(hash_sha256(&format!("{}:{}", challenge, nonce))),
+                    recovery_envelope: None,
+                },
+            )
+            .unwrap();
+        device_id
+    }
+
+    fn event(device_id: &str, event_id: &str, key_version: i32) -> SyncPushEventRequest {
+        SyncPushEventRequest {
+            event_id: event_id.to_string(),
+            device_id: device_id.to_string(),
+            event_type: "account.create.v1".to_string(),
+            entity: SyncEntity::Account,
+            entity_id: Uuid::new_v4().to_string(),
+            client_timestamp: now(),
+            payload: "ciphertext".to_string(),
+            payload_key_version: key_version,
+        }
+    }
+
+    #[test]
+    fn first_device_bootstraps_and_later_devices_must_pair() {
+        let (_dir, store) = store();
+        let first = bootstrap(&store);
+
+        assert!(matches!(
+            enroll(&store, "nonce-1"),
+            EnrollDeviceResponse::Ready { .. }
+        ));
+        match enroll(&store, "nonce-2") {
+            EnrollDeviceResponse::Pair {
+                trusted_devices,
+                e2ee_key_version,
+                ..
+            } => {
+                assert_eq!(e2ee_key_version, 1);
+                assert_eq!(trusted_devices.len(), 1);
+                assert_eq!(trusted_devices[0].id, first);
+            }
+            other => panic!("expected PAIR, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn device_tokens_prove_the_device_and_rotate_on_enrollment() {
+        let (_dir, store) = store();
+        let first = enroll(&store, "nonce-1");
+        let second = enroll(&store, "nonce-2");
+        let first_token = first.device_token().unwrap().to_string();
+        let second_token = second.device_token().unwrap().to_string();
+
+        assert!(store
+            .authenticate_device(TEAM, first.device_id(), &first_token)
+            .is_ok());
+        assert!(matches!(
+            store.authenticate_device(TEAM, first.device_id(), &second_token),
+            Err(RelayError::Unauthenticated(_))
+        ));
+        assert!(store
+            .authenticate_device("team-b", first.device_id(), &first_token)
+            .is_err());
+
+        let again = enroll(&store, "nonce-1");
+        assert_eq!(again.device_id(), first.device_id());
+        assert!(store
+            .authenticate_device(TEAM, first.device_id(), &first_token)
+            .is_err());
+        assert!(store
+            .authenticate_device(TEAM, first.device_id(), again.device_token().unwrap())
+            .is_ok());
+    }
+
+    #[test]
+    fn push_is_idempotent_and_rejects_stale_key_versions() {
+        let (_dir, store) = store();
+        let device_id = bootstrap(&store);
+
+        let first = store
+            .push_events(
+                TEAM,
+                &device_id,
+                SyncPushRequest {
+                    events: vec![event(&device_id, "e1", 1), event(&device_id, "e2", 1)],
+                },
+            )
+            .unwrap();
+        assert_eq!(first.accepted.len(), 2);
+
+        let again = store
+            .push_events(
+                TEAM,
+                &device_id,
+                SyncPushRequest {
+                    events: vec![event(&device_id, "e2", 1)],
+                },
+            )
+            .unwrap();
+        assert_eq!(again.duplicate.len(), 1);
+        assert_eq!(again.server_cursor, first.server_cursor);
+
+        let err = store
+            .push_events(
+                TEAM,
+                &device_id,
+                SyncPushRequest {
+                    events: vec![event(&device_id, "e3", 0)],
+                },
+            )
+            .unwrap_err();
+        assert_eq!(err.code(), "KEY_VERSION_MISMATCH");
+
+        let pulled = store
+            .pull_events(TEAM, &device_id, Some(0), Some(1))
+            .unwrap();
+        assert_eq!(pulled.events.len(), 1);
+        assert!(pulled.has_more);
+        assert_eq!(pulled.events[0].payload, "ciphertext");
+    }
+
+    #[test]
+    fn pairing_hands_over_the_bundle_and_trusts_the_claimer() {
+        let (_dir, store) = store();
+        let issuer = bootstrap(&store);
+        let EnrollDeviceResponse::Pair {
+            device_id: claimer, ..
+        } = enroll(&store, "nonce-2")
+        else {
+            panic!("second device should pair");
+        };
+        assert!(store.pull_events(TEAM, &claimer, None, None).is_err());
+
+        let created = store
+            .create_pairing(
+                TEAM,
+                &issuer,
+                CreatePairingRequest {
+                    code_hash: hash_pairing_code("ABC234"),
+                    ephemeral_public_key: "issuer-pub".to_string(),
+                },
+            )
+            .unwrap();
+        let claimed = store
+            .claim_pairing(
+                TEAM,
+                &claimer,
+                ClaimPairingRequest {
+                    code: "abc-234".to_string(),
+                    ephemeral_public_key: "claimer-pub".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(claimed.session_id, created.pairing_id);
+        assert_eq!(claimed.issuer_ephemeral_pub, "issuer-pub");
+
+        let pairing = store
+            .get_pairing(TEAM, &issuer, &created.pairing_id)
+            .unwrap();
+        assert_eq!(pairing.status, PairingStatus::Claimed);
+        assert_eq!(
+            pairing.claimer_ephemeral_pub.as_deref(),
+            Some("claimer-pub")
+        );
+
+        store
+            .approve_pairing(TEAM, &issuer, &created.pairing_id)
+            .unwrap();
+        store
+            .complete_pairing(
+                TEAM,
+                &issuer,
+                &created.pairing_id,
+                CompletePairingRequest {
+                    encrypted_key_bundle: "sealed-bundle".to_string(),
+                    sas_proof: serde_json::json!("123456"),
+                    signature: "sig".to_string(),
+                },
+            )
+            .unwrap();
+        let messages = store
+            .pairing_messages(TEAM, &claimer, &created.pairing_id)
+            .unwrap();
+        assert_eq!(messages.messages[0].payload_type, KEY_BUNDLE_PAYLOAD_TYPE);
+        assert_eq!(messages.messages[0].payload, "sealed-bundle");
+
+        let confirmed = store
+            .confirm_pairing(
+                TEAM,
+                &claimer,
+                &created.pairing_id,
+                ConfirmPairingRequest { proof: None },
+            )
+            .unwrap();
+        assert_eq!(confirmed.key_version, 1);
+        assert!(store.pull_events(TEAM, &claimer, None, None).is_ok());
+    }
+
+    #[test]
+    fn snapshots_are_verified_and_teams_are_isolated() {
+        let (_dir, store) = store();
+        let device_id = bootstrap(&store);
+        let data = b"encrypted snapshot".to_vec();
+        let headers = SnapshotUploadHeaders {
+            event_id: Some(Uuid::new_v4().to_string()),
+            schema_version: 1,
+            covers_tables: vec!["accounts".to_string(), "activities".to_string()],
+            size_bytes: data.len() as i64,
+            checksum: "sha256:00".to_string(),
+            metadata_payload: "meta".to_string(),
+            payload_key_version: 1,
+            base_seq: None,
+        };
+        assert!(store
+            .put_snapshot(TEAM, &device_id, headers.clone(), data.clone())
+            .is_err());
+
+        let uploaded = store
+            .put_snapshot(
+                TEAM,
+                &device_id,
+                SnapshotUploadHeaders {
+                    checksum: sha256_checksum(&data),
+                    ..headers
+                },
+                data.clone(),
+            )
+            .unwrap();
+        let latest = store.latest_snapshot(TEAM, &device_id).unwrap();
+        assert_eq!(latest.snapshot_id, uploaded.snapshot_id);
+        assert_eq!(latest.covers_tables, vec!["accounts", "activities"]);
+
+        let (download_headers, body) = store
+            .get_snapshot(TEAM, &device_id, &uploaded.snapshot_id)
+            .unwrap();
+        assert_eq!(body, data);
+        assert_eq!(download_headers.checksum, sha256_checksum(&data));
+
+        let err = store.get_device("team-b", &device_id).unwrap_err();
+        assert!(matches!(err, RelayError::NotFound(_)));
+    }
+}
@@ -58,15 +58,16 @@ impl TaxonomyRepositoryTrait for TaxonomyRepository {
 
     async fn create_taxonomy(&self, taxonomy: NewTaxonomy) -> Result<Taxonomy> {
         self.writer
-            .exec(move |conn: &mut SqliteConnection| -> Result<Taxonomy> {
+            .exec_tx(move |tx| -> Result<Taxonomy> {
                 let mut db: NewTaxonomyDB = taxonomy.into();
                 db.id = Some(db.id.unwrap_or_else(|| Uuid::new_v4().to_string()));
 
                 let result = diesel::insert_into(taxonomies::table)
                     .values(&db)
                     .returning(TaxonomyDB::as_returning())
-                    .get_result(conn)
+                    .get_result(tx.conn())
                     .map_err(StorageError::from)?;
+                tx.insert(&result)?;
 
                 Ok(Taxonomy::from(result))
             })
@@ -76,7 +77,7 @@ impl TaxonomyRepositoryTrait for TaxonomyRepository {
     async fn update_taxonomy(&self, taxonomy: Taxonomy) -> Result<Taxonomy> {
         let id = taxonomy.id.clone();
         self.writer
-            .exec(move |conn: &mut SqliteConnection| -> Result<Taxonomy> {
+            .exec_tx(move |tx| -> Result<Taxonomy> {
                 let db = TaxonomyDB {
                     id: taxonomy.id,
                     name: taxonomy.name,
@@ -94,13 +95,14 @@ impl TaxonomyRepositoryTrait for TaxonomyRepository {
 
                 diesel::update(taxonomies::table.find(&id))
                     .set(&db)
-                    .execute(conn)
+                    .execute(tx.conn())
                     .map_err(StorageError::from)?;
 
                 let result = taxonomies::table
                     .find(&id)
-                    .first::<TaxonomyDB>(conn)
+                    .first::<TaxonomyDB>(tx.conn())
                     .map_err(StorageError::from)?;
+                tx.update(&result)?;
 
                 Ok(Taxonomy::from(result))
             })
@@ -110,10 +112,20 @@ impl TaxonomyRepositoryTrait for TaxonomyRepository {
     async fn delete_taxonomy(&self, id: &str) -> Result<usize> {
         let id = id.to_string();
         self.writer
-            .exec(move |conn: &mut SqliteConnection| -> Result<usize> {
-                Ok(diesel::delete(taxonomies::table.find(&id))
-                    .execute(conn)
-                    .map_err(StorageError::from)?)
+            .exec_tx(move |tx| -> Result<usize> {
+                let existing = taxonomies::table
+                    .find(&id)
+                    .first::<TaxonomyDB>(tx.conn())
+                    .optional()
+                    .map_err(StorageError::from)?;
+                let affected = diesel::delete(taxonomies::table.find(&id))
+                    .execute(tx.conn())
+                    .map_err(StorageError::from)?;
+                // Categories and assignments cascade on every device.
+                if let Some(existing) = existing.as_ref() {
+                    tx.delete_model(existing)?;
+                }
+                Ok(affected)
             })
             .await
     }
@@ -141,15 +153,16 @@ impl TaxonomyRepositoryTrait for TaxonomyRepository {
 
     async fn create_category(&self, category: NewCategory) -> Result<Category> {
         self.writer
-            .exec(move |conn: &mut SqliteConnection| -> Result<Category> {
+            .exec_tx(move |tx| -> Result<Category> {
                 let mut db: NewCategoryDB = category.into();
                 db.id = Some(db.id.unwrap_or_else(|| Uuid::new_v4().to_string()));
 
                 let result = diesel::insert_into(taxonomy_categories::table)
                     .values(&db)
                     .returning(CategoryDB::as_returning())
-                    .get_result(conn)
+                    .get_result(tx.conn())
                     .map_err(StorageError::from)?;
+                tx.insert(&result)?;
 
                 Ok(Category::from(result))
             })
@@ -160,7 +173,7 @@ impl TaxonomyRepositoryTrait for TaxonomyRepository {
         let taxonomy_id = category.taxonomy_id.clone();
         let id = category.id.clone();
         self.writer
-            .exec(move |conn: &mut SqliteConnection| -> Result<Category> {
+            .exec_tx(move |tx| -> Result<Category> {
                 let db = CategoryDB {
                     id: category.id,
                     taxonomy_id: category.taxonomy_id,
@@ -183,14 +196,15 @@ impl TaxonomyRepositoryTrait for TaxonomyRepository {
                         .filter(taxonomy_categories::id.eq(&id)),
                 )
                 .set(&db)
-                .execute(conn)
+                .execute(tx.conn())
                 .map_err(StorageError::from)?;
 
                 let result = taxonomy_categories::table
                     .filter(taxonomy_categories::taxonomy_id.eq(&taxonomy_id))
                     .filter(taxonomy_categories::id.eq(&id))
-                    .first::<CategoryDB>(conn)
+                    .first::<CategoryDB>(tx.conn())
                     .map_err(StorageError::from)?;
+                tx.update(&result)?;
 
                 Ok(Category::from(result))
             })
@@ -201,30 +215,42 @@ impl TaxonomyRepositoryTrait for TaxonomyRepository {
         let taxonomy_id = taxonomy_id.to_string();
         let category_id = category_id.to_string();
         self.writer
-            .exec(move |conn: &mut SqliteConnection| -> Result<usize> {
-                Ok(diesel::delete(
+            .exec_tx(move |tx| -> Result<usize> {
+                let existing = taxonomy_categories::table
+                    .filter(taxonomy_categories::taxonomy_id.eq(&taxonomy_id))
+                    .filter(taxonomy_categories::id.eq(&category_id))
+                    .first::<CategoryDB>(tx.conn())
+                    .optional()
+                    .map_err(StorageError::from)?;
+                let affected = diesel::delete(
                     taxonomy_categories::table
                         .filter(taxonomy_categories::taxonomy_id.eq(&taxonomy_id))
                         .filter(taxonomy_categories::id.eq(&category_id)),
                 )
-                .execute(conn)
-                .map_err(StorageError::from)?)
+                .execute(tx.conn())
+                .map_err(StorageError::from)?;
+                if let Some(existing) = existing.as_ref() {
+                    tx.delete_model(existing)?;
+                }
+                Ok(affected)
             })
             .await
     }
 
     async fn bulk_create_categories(&self, categories: Vec<NewCategory>) -> Result<usize> {
         self.writer
-            .exec(move |conn: &mut SqliteConnection| -> Result<usize> {
+            .exec_tx(move |tx| -> Result<usize> {
                 let mut count = 0;
                 for cat in categories {
                     let mut db: NewCategoryDB = cat.into();
                     db.id = Some(db.id.unwrap_or_else(|| Uuid::new_v4().to_string()));
 
-                    diesel::insert_into(taxonomy_categories::table)
+                    let result = diesel::insert_into(taxonomy_categories::table)
                         .values(&db)
-                        .execute(conn)
+                        .returning(CategoryDB::as_returning())
+                        .get_result(tx.conn())
                         .map_err(StorageError::from)?;
+                    tx.insert(&result)?;
                     count += 1;
                 }
                 Ok(count)